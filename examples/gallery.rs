@@ -0,0 +1,598 @@
+//! Every widget on its own page, with the color modes, zoom and animations in the View menu. Run
+//! it with `cargo run --example gallery` after a change to see whether anything else broke
+
+extern crate sdl2;
+
+use std::default::Default;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rui_lopez::accessibility::{self, ColorMode};
+use rui_lopez::accessibility_tree::{Accessible, Live, Role};
+use rui_lopez::context::ContextValues;
+use rui_lopez::elements::*;
+use rui_lopez::engines::sdl::EngineConfig;
+use rui_lopez::list_model::ListModel;
+use rui_lopez::paging::{self, DataProvider, PagedRows, PageRequest};
+use rui_lopez::sections::Section;
+use rui_lopez::table_model::VecTableModel;
+use rui_lopez::tree_table::{TreeNode, TreeTable};
+
+/// What the If and the ForEach of the Layout page show
+static SHOW_DETAILS: AtomicBool = AtomicBool::new(false);
+static ROWS: AtomicUsize = AtomicUsize::new(2);
+
+fn main() -> Result<(), String> {
+    accessibility::set_preferences(accessibility::detect());
+    #[allow(unused_mut)]
+    let mut tabs = vec![page("Inputs", inputs()), page("Text", text()), page("Lists", lists()),
+                        page("Media", media()), page("Layout", layout())];
+    #[cfg(feature = "charts")]
+    tabs.push(page("Charts", charts()));
+    #[cfg(feature = "lyon")]
+    tabs.push(page("Vector", vector()));
+    let window = Window {
+        title: "Widget Gallery".to_string(),
+        menu: Some(view_menu()),
+        container: Some(Container {
+            children: vec![Box::new(TabPane {
+                tabs,
+                on_change: |event, index| {
+                    println!("Page {} ({})", event.source, index);
+                    true
+                },
+                ..Default::default()
+            })],
+            ..Default::default()
+        }),
+        status_bar: Some(StatusBar {
+            text: "The View menu switches the color mode and the zoom".to_string(),
+            sections: vec![StatusSection {
+                text: "Gallery".to_string(),
+                ..Default::default()
+            }],
+            progress: Some(0.4),
+            ..Default::default()
+        }),
+        background: WindowBackground::Gradient {
+            from: Color::rgb(24, 26, 34),
+            to: Color::rgb(8, 8, 10),
+            vertical: true,
+        },
+        ..Default::default()
+    };
+    rui_lopez::engines::sdl::main_loop(vec![window], EngineConfig::default())
+}
+
+fn page(title: &str, children: Vec<Box<dyn Component>>) -> Tab {
+    Tab {
+        title: title.to_string(),
+        content: Container {
+            children: vec![Box::new(ScrollPane {
+                height: Dimension::Pixels(440),
+                content: Container { children, ..Default::default() },
+                ..Default::default()
+            })],
+            ..Default::default()
+        },
+    }
+}
+
+fn heading(text: &str) -> Box<dyn Component> {
+    Box::new(Label {
+        text: text.to_string(),
+        color: Some(Color::rgb(255, 200, 80)),
+        size: Some(24.),
+        ..Default::default()
+    })
+}
+
+fn view_menu() -> MainMenu {
+    let item = |title: &str, accelerator: Option<&str>, on_action: fn(Event) -> bool| {
+        Submenu::MenuItem(MenuItem {
+            title: title.to_string(),
+            accelerator: accelerator.map(|a| a.to_string()),
+            on_action,
+            ..Default::default()
+        })
+    };
+    let color_mode = |title: &str, on_action: fn(Event) -> bool| Submenu::MenuItem(MenuItem {
+        title: title.to_string(),
+        checked: Some(title == "&Standard"),
+        radio_group: Some("color_mode".to_string()),
+        on_action,
+        ..Default::default()
+    });
+    MainMenu {
+        menu: Menu {
+            title: "&View".to_string(),
+            children: vec![color_mode("&Standard", |_event| set_color_mode(ColorMode::Standard)),
+                           color_mode("&High Contrast", |_event| set_color_mode(ColorMode::HighContrast)),
+                           color_mode("&Forced Colors", |_event| {
+                               set_color_mode(ColorMode::ForcedColors(Default::default()))
+                           }),
+                           item("Zoom &In", Some("Ctrl+="), |_event| accessibility::zoom_in()),
+                           item("Zoom &Out", Some("Ctrl+-"), |_event| accessibility::zoom_out()),
+                           item("&Reset Zoom", Some("Ctrl+0"), |_event| accessibility::reset_zoom()),
+                           item("Reduce &Motion", None, |_event| {
+                               let mut preferences = accessibility::preferences();
+                               preferences.reduce_motion = !preferences.reduce_motion;
+                               accessibility::set_preferences(preferences);
+                               true
+                           }),
+                           item("&Caret Browsing", Some("F7"), |_event| {
+                               accessibility::toggle_caret_browsing();
+                               true
+                           })],
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn set_color_mode(color_mode: ColorMode) -> bool {
+    let mut preferences = accessibility::preferences();
+    preferences.color_mode = color_mode;
+    accessibility::set_preferences(preferences);
+    true
+}
+
+fn inputs() -> Vec<Box<dyn Component>> {
+    let mut disabled = Button {
+        title: "Disabled".to_string(),
+        ..Default::default()
+    };
+    disabled.set_enabled(false);
+    vec![heading("Buttons"), Box::new(Button {
+        title: "Press me".to_string(),
+        on_action: |event| {
+            println!("Pressed {:?}", event);
+            true
+        },
+        ..Default::default()
+    }), Box::new(disabled), heading("Text input"), Box::new(TextField {
+        text: "Click to type".to_string(),
+        editable: true,
+        ..Default::default()
+    }), Box::new(TextArea {
+        text: "A TextArea wraps its text and scrolls it with the wheel once it doesn't fit.\n\
+               Return breaks the line while it has the focus.".to_string(),
+        editable: true,
+        height: Dimension::Pixels(80),
+        ..Default::default()
+    }), Box::new(ChipInput {
+        chips: vec!["rust".to_string(), "sdl".to_string()],
+        on_change: |_event, chips| {
+            println!("Tagged {}", chips.join(", "));
+            true
+        },
+        accessible: Accessible { name: Some("Tags".to_string()), ..Default::default() },
+        ..Default::default()
+    }), heading("Choices"), Box::new(Checkbox {
+        label: "Checked".to_string(),
+        checked: true,
+        ..Default::default()
+    }), Box::new(RadioGroup {
+        children: ["Small", "Medium", "Large"].iter().enumerate().map(|(i, label)| RadioButton {
+            label: label.to_string(),
+            selected: i == 1,
+        }).collect(),
+        accessible: Accessible { name: Some("Size".to_string()), ..Default::default() },
+        ..Default::default()
+    }), Box::new(SegmentedControl {
+        segments: vec!["Day".to_string(), "Week".to_string(), "Month".to_string()],
+        selected: Some(0),
+        ..Default::default()
+    }), Box::new(Rating {
+        value: 3.5,
+        half_stars: true,
+        on_change: |_event, stars| {
+            println!("Rated {} stars", stars);
+            true
+        },
+        accessible: Accessible { name: Some("Rating".to_string()), ..Default::default() },
+        ..Default::default()
+    }), Box::new(ComboBox {
+        items: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+        selected: Some(2),
+        ..Default::default()
+    }), Box::new(ColorPicker {
+        hue: 210.,
+        saturation: 0.6,
+        value: 0.8,
+        on_change: |_event, color| {
+            println!("Picked {:?}", color);
+            true
+        },
+        ..Default::default()
+    }), Box::new(DatePicker {
+        on_change: |_event, date| {
+            println!("Picked {}", date);
+            true
+        },
+        ..Default::default()
+    }), Box::new(Slider {
+        value: 30.,
+        step: 10.,
+        ..Default::default()
+    }), Box::new(Spinner {
+        min: -1.,
+        max: 1.,
+        step: 0.25,
+        text: "0.00".to_string(),
+        ..Default::default()
+    }), Box::new(SpinBox {
+        max: 400.,
+        step: 10.,
+        value: 120.,
+        text: "120".to_string(),
+        unit: "px".to_string(),
+        ..Default::default()
+    })]
+}
+
+fn text() -> Vec<Box<dyn Component>> {
+    let mut console = Console { source: "build".to_string(), visible_rows: 6, ..Default::default() };
+    console.write("\x1b[1;32m   Compiling\x1b[0m rui_lopez v0.1.0\n\
+                   \x1b[1;33mwarning\x1b[0m: unused variable: `x`\n\
+                   \x1b[1;31merror\x1b[0m[E0308]: mismatched types\n\
+                   \x1b[38;5;244m  --> src/main.rs:4:5\x1b[0m\n\
+                   Building [=====>    ] 5/9\rBuilding [=========>] 9/9\n");
+    vec![heading("Labels"), Box::new(Label {
+        text: "Left aligned text wraps to the width of the page when it's too long to fit on a \
+               single line, which this one is".to_string(),
+        ..Default::default()
+    }), Box::new(Label {
+        text: "Centered".to_string(),
+        align: TextAlign::Center,
+        ..Default::default()
+    }), Box::new(Label {
+        text: "Right aligned and small".to_string(),
+        align: TextAlign::Right,
+        size: Some(14.),
+        ..Default::default()
+    }), Box::new(Label {
+        text: "Translucent".to_string(),
+        color: Some(Color { r: 230, g: 230, b: 230, a: 120 }),
+        ..Default::default()
+    }), Box::new(Label {
+        text: "A live status Label, screen readers announce it when it changes".to_string(),
+        accessible: Accessible { role: Some(Role::Status), live: Live::Polite, ..Default::default() },
+        ..Default::default()
+    }), heading("Links"), Box::new(Hyperlink {
+        text: "Opens in the browser".to_string(),
+        url: "https://www.libsdl.org".to_string(),
+        ..Default::default()
+    }), Box::new(Hyperlink {
+        text: "Handled by the app".to_string(),
+        url: "app://settings".to_string(),
+        on_click: |_event, url| {
+            println!("Navigate to {}", url);
+            true
+        },
+        ..Default::default()
+    }), Box::new(Breadcrumb {
+        segments: ["Home", "Documents", "Projects", "Gallery", "Notes.txt"].iter().map(|s| s.to_string()).collect(),
+        on_click: |event, index| {
+            println!("Go back to {} ({})", event.source, index);
+            true
+        },
+        accessible: Accessible { name: Some("Location".to_string()), ..Default::default() },
+    }), heading("MarkdownView"), Box::new(MarkdownView {
+        text: "## Release notes\n\
+               Text in **bold**, *italic* and `code`, see [SDL](https://www.libsdl.org).\n\n\
+               - Nested\n  - lists\n1. Numbered\n\n\
+               > A quote\n\n\
+               ```\nfn main() {}\n```".to_string(),
+        on_link: |_event, url| {
+            println!("Open {}", url);
+            false
+        },
+        ..Default::default()
+    }), heading("Console"), Box::new(console)]
+}
+
+fn lists() -> Vec<Box<dyn Component>> {
+    let planets = ["Mercury", "Venus", "Earth", "Mars", "Jupiter", "Saturn", "Uranus", "Neptune"];
+    vec![heading("ListView"), Box::new(ListView {
+        model: ListModel::new(planets.iter().map(|p| p.to_string()).collect()),
+        visible_rows: 5,
+        sections: vec![Section { title: "Inner planets".to_string(), rows: 4 },
+                       Section { title: "Outer planets".to_string(), rows: 4 }],
+        ..Default::default()
+    }), heading("TableView"), Box::new(TableView {
+        visible_rows: 5,
+        ..TableView::new(VecTableModel {
+            columns: vec!["Planet".to_string(), "Moons".to_string()],
+            rows: planets.iter().zip([0, 0, 1, 2, 95, 146, 28, 16])
+                .map(|(planet, moons)| vec![planet.to_string(), moons.to_string()])
+                .collect(),
+        }, vec![160., 80.])
+    }), heading("TreeTable"), Box::new(call_tree()), heading("Paged ListView"), Box::new(ListView {
+        visible_rows: 5,
+        ..ListView::paged(PagedRows::new("gallery.stars", Box::new(Stars)))
+    }), heading("Deferred"), Box::new(Deferred {
+        content: Container {
+            height: Dimension::Pixels(120),
+            children: (1..=1000).map(|row| -> Box<dyn Component> {
+                Box::new(Label { text: format!("Row {} built after the first frame", row), ..Default::default() })
+            }).collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    })]
+}
+
+/// A profiler's call tree with the time spent in each function
+fn call_tree() -> TreeTable {
+    let call = |name: &str, time: &str, children| TreeNode::new(vec![name.to_string(), time.to_string()], children);
+    let mut tree = TreeTable::new(vec!["Function".to_string(), "Time".to_string()], vec![
+        call("main", "16.2ms", vec![
+            call("update", "4.1ms", vec![call("physics", "3.3ms", vec![]), call("input", "0.8ms", vec![])]),
+            call("render", "12.1ms", vec![call("layout", "2.9ms", vec![]), call("draw", "9.2ms", vec![])]),
+        ]),
+    ]);
+    tree.visible_rows = 5;
+    tree.toggle(0);
+    tree
+}
+
+/// Pretends to fetch the rows of the paged ListView from a server, 200 of them
+struct Stars;
+
+impl DataProvider for Stars {
+    fn request_page(&mut self, source: &str, request: PageRequest) {
+        let source = source.to_string();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(500));
+            let end = (request.offset + request.count).min(200);
+            let rows = (request.offset..end).map(|i| vec![format!("Star #{}", i + 1)]).collect();
+            paging::deliver_page(&source, request.offset, rows, end < 200);
+        });
+    }
+}
+
+fn media() -> Vec<Box<dyn Component>> {
+    let image = |scale_mode| -> Box<dyn Component> {
+        Box::new(Image {
+            source: ImageSource::Path("logo.png".to_string()),
+            height: Dimension::Pixels(64),
+            scale_mode,
+            ..Default::default()
+        })
+    };
+    let avatar = |name: &str, source: Option<&str>, shape| -> Box<dyn Component> {
+        Box::new(Avatar {
+            source: source.map(|s| ImageSource::Path(s.to_string())),
+            name: name.to_string(),
+            shape,
+            ..Default::default()
+        })
+    };
+    vec![heading("Images"), image(ScaleMode::Fit), image(ScaleMode::Fill), image(ScaleMode::Stretch),
+         image(ScaleMode::Tile), heading("Patterns"), Box::new(Image {
+             source: ImageSource::Path("logo.png".to_string()),
+             height: Dimension::Pixels(64),
+             background: Some(Pattern::transparency()),
+             ..Default::default()
+         }), Box::new(Canvas {
+             height: Dimension::Pixels(32),
+             background: Some(Pattern::stripes(6, &[Color::rgb(60, 60, 70), Color::rgb(80, 80, 90)], true)),
+             ..Default::default()
+         }), heading("Avatars"), avatar("Ada Lovelace", Some("logo.png"), AvatarShape::Circle),
+         avatar("Grace Hopper", None, AvatarShape::Circle),
+         avatar("Alan Turing", Some("missing.png"), AvatarShape::Rounded(8.)), heading("Progress"), Box::new(ProgressBar {
+             value: Some(0.65),
+             ..Default::default()
+         }), Box::new(Label {
+             text: "Busy, it stands still when reducing motion".to_string(),
+             ..Default::default()
+         }), Box::new(ProgressBar::default()), heading("Gauge"), Box::new(Gauge {
+             max: 120.,
+             value: 72.,
+             zones: vec![GaugeZone { from: 80., to: 100., color: Color::rgb(230, 160, 40) },
+                         GaugeZone { from: 100., to: 120., color: Color::rgb(210, 60, 60) }],
+             unit: "°C".to_string(),
+             ..Default::default()
+         }), heading("Skeleton"), Box::new(Loadable {
+             source: "gallery".to_string(),
+             content: Container {
+                 children: vec![Box::new(Label {
+                     text: "Shown once paging::set_loading(\"gallery\", false) is posted".to_string(),
+                     ..Default::default()
+                 }), Box::new(Button::default())],
+                 ..Default::default()
+             },
+             loading: true,
+             ..Default::default()
+         }), heading("Drop zone"), Box::new(DropZone {
+             text: "Drop images from the file manager".to_string(),
+             extensions: vec!["png".to_string(), "jpg".to_string()],
+             on_files: |_event, files| {
+                 println!("Dropped {:?}", files);
+                 true
+             },
+             ..Default::default()
+         }), heading("Canvas"), Box::new(Canvas {
+             draw: Rc::new(|width, height| {
+                 let corner = |x, y, color| CanvasVertex { x, y, color };
+                 let hexagon: Vec<(f32, f32)> = (0..6).map(|i| {
+                     let angle = i as f32 * std::f32::consts::TAU / 6.;
+                     (height / 2. + 40. * angle.cos(), height / 2. + 40. * angle.sin())
+                 }).collect();
+                 vec![CanvasPolygon::convex(&hexagon, Color::rgb(70, 130, 200)), CanvasPolygon {
+                     vertices: vec![corner(120., height - 10., Color::rgb(220, 60, 60)),
+                                    corner(170., 10., Color::rgb(60, 200, 90)),
+                                    corner(220., height - 10., Color::rgb(60, 90, 220))],
+                     indexes: vec![0, 1, 2],
+                 }, CanvasPolygon::line((240., height / 2.), (width - 10., height / 2.), 2., Color::rgb(200, 200, 200))]
+             }),
+             ..Default::default()
+         })]
+}
+
+#[cfg(feature = "lyon")]
+fn vector() -> Vec<Box<dyn Component>> {
+    use rui_lopez::svg::SvgImage;
+    use rui_lopez::tessellation::{self, Cap, Join, Stroke};
+
+    let mut children: Vec<Box<dyn Component>> = vec![heading("Icons and strokes"), Box::new(Canvas {
+        height: Dimension::Pixels(120),
+        draw: Rc::new(|_width, height| {
+            let heart = "M 12 21 C 5 15 2 12 2 8 C 2 5 4.5 3 7 3 C 9 3 11 4.5 12 6 \
+                         C 13 4.5 15 3 17 3 C 19.5 3 22 5 22 8 C 22 12 19 15 12 21 Z";
+            let check = "M 4 12 L 10 18 L 20 6";
+            let scale = height / 24.;
+            let solid = Stroke { width: 2.5, join: Join::Round, cap: Cap::Round, ..Default::default() };
+            let dashed = Stroke { width: 2., dashes: vec![8., 4.], ..Default::default() };
+            let shapes = [
+                tessellation::parse_svg(heart).and_then(|p| {
+                    tessellation::fill(&tessellation::scaled(&p, scale, 0., 0.), Color::rgb(200, 80, 90))
+                }),
+                tessellation::parse_svg(check).and_then(|p| {
+                    tessellation::stroke(&tessellation::scaled(&p, scale, height + 20., 0.), &solid,
+                                         Color::rgb(90, 180, 100))
+                }),
+                tessellation::stroke(&tessellation::rounded_rect(2. * height + 40., 10., 160., height - 20., 16.),
+                                     &dashed, Color::rgb(200, 200, 200)),
+            ];
+            shapes.into_iter().filter_map(|shape| shape.map_err(|e| println!("{}", e)).ok()).collect()
+        }),
+        ..Default::default()
+    })];
+    // The same icon in its own size and scaled up, in the color of the image
+    let save = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" width="24" height="24" fill="none"
+                      stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                   <path d="M19 21H5a2 2 0 0 1-2-2V5a2 2 0 0 1 2-2h11l5 5v11a2 2 0 0 1-2 2z"/>
+                   <polyline points="17 21 17 13 7 13 7 21"/>
+                   <polyline points="7 3 7 8 15 8"/>
+                 </svg>"#;
+    match SvgImage::from_svg(save) {
+        Ok(icon) => {
+            let large = SvgImage {
+                width: Dimension::Pixels(96),
+                height: Dimension::Pixels(96),
+                color: Color::rgb(100, 160, 255),
+                ..icon.clone()
+            };
+            children.extend([heading("SVG"), Box::new(icon) as Box<dyn Component>, Box::new(large)]);
+        }
+        Err(e) => println!("{}", e),
+    }
+    children
+}
+
+#[cfg(feature = "charts")]
+fn charts() -> Vec<Box<dyn Component>> {
+    use rui_lopez::charts::{BarChart, LineChart, PieChart, Series};
+
+    let months: Vec<String> = ["Jan", "Feb", "Mar", "Apr", "May", "Jun"].iter().map(|m| m.to_string()).collect();
+    vec![heading("Line chart"), Box::new(LineChart {
+        categories: months.clone(),
+        series: vec![Series::new("Visits", vec![120., 180., 150., 240., 210., 300.]),
+                     Series::new("Sign-ups", vec![20., 35., 30., 60., 45., 80.])],
+        ..Default::default()
+    }), heading("Bar chart"), Box::new(BarChart {
+        categories: months,
+        series: vec![Series::new("Income", vec![12., 15., 9., 18., 14., 20.]),
+                     Series::new("Balance", vec![4., 6., -3., 7., 2., 9.])],
+        ..Default::default()
+    }), heading("Pie chart"), Box::new(PieChart {
+        labels: vec!["Rent".to_string(), "Food".to_string(), "Travel".to_string(), "Other".to_string()],
+        values: vec![1200., 600., 300., 400.],
+        ..Default::default()
+    })]
+}
+
+fn layout() -> Vec<Box<dyn Component>> {
+    vec![heading("Nested containers"), Box::new(Container {
+        width: Dimension::Percentage(50),
+        children: vec![Box::new(Label {
+            text: "Half the width of the page".to_string(),
+            ..Default::default()
+        }), Box::new(Button::default())],
+        ..Default::default()
+    }), heading("Context"), Box::new(Provider {
+        values: ContextValues::new().with(TextColor(Color::rgb(120, 200, 140))),
+        content: Container {
+            children: vec![Box::new(Label {
+                text: "Colored by the Provider around it".to_string(),
+                ..Default::default()
+            }), Box::new(Label {
+                text: "Unless it has a color of its own".to_string(),
+                color: Some(Color::rgb(230, 230, 230)),
+                ..Default::default()
+            })],
+            ..Default::default()
+        },
+    }), heading("If and ForEach"), Box::new(Checkbox {
+        label: "Show details".to_string(),
+        on_toggle: |_event, checked| {
+            SHOW_DETAILS.store(checked, Ordering::Relaxed);
+            true
+        },
+        ..Default::default()
+    }), Box::new(If {
+        condition: || SHOW_DETAILS.load(Ordering::Relaxed),
+        content: Container {
+            children: vec![Box::new(Label {
+                text: "Details, shown while the box is checked".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        },
+        ..Default::default()
+    }), Box::new(Button {
+        title: "Add a row".to_string(),
+        on_action: |_event| {
+            ROWS.fetch_add(1, Ordering::Relaxed);
+            true
+        },
+        ..Default::default()
+    }), Box::new(ForEach {
+        keys: || (1..=ROWS.load(Ordering::Relaxed)).map(|i| format!("Row {}", i)).collect(),
+        template: |key| Box::new(TextField { text: key.to_string(), editable: true, ..Default::default() }),
+        ..Default::default()
+    }), heading("Separators"), Box::new(Label {
+        text: "Above the rule".to_string(),
+        ..Default::default()
+    }), Box::new(Separator::default()), Box::new(Separator {
+        thickness: 3.,
+        length: Some(Dimension::Percentage(50)),
+        color: Some(Color::rgb(255, 200, 80)),
+        ..Default::default()
+    }), heading("Scrolling"), Box::new(ScrollPane {
+        height: Dimension::Pixels(100),
+        content: Container {
+            children: (1..=10).map(|i| Box::new(Checkbox {
+                label: format!("Option {}", i),
+                ..Default::default()
+            }) as Box<dyn Component>).collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }), heading("Carousel"), Box::new(Carousel {
+        pages: ["Drag the page", "or use the arrows", "and the dots"].iter().map(|text| Container {
+            children: vec![Box::new(Label {
+                text: text.to_string(),
+                align: TextAlign::Center,
+                size: Some(24.),
+                ..Default::default()
+            }) as Box<dyn Component>],
+            ..Default::default()
+        }).collect(),
+        height: Dimension::Pixels(100),
+        autoplay: Some(Duration::from_secs(4)),
+        on_change: |_event, page| {
+            println!("Page {}", page);
+            true
+        },
+        ..Default::default()
+    }), heading("Not there yet"), Box::new(Label {
+        // Stubs until the layouts exist, so the page shows what is missing
+        text: "Rows, grids and split panes aren't supported yet, containers only stack their \
+               children".to_string(),
+        ..Default::default()
+    })]
+}
@@ -0,0 +1,45 @@
+/* C ABI of rui_lopez, built as a shared library by the rui_lopez_ffi crate.
+ * Strings are UTF-8 and NUL terminated, the ones given are copied. Functions returning int give
+ * 0 on success and -1 on failure, rui_last_error() tells why. */
+#ifndef RUI_LOPEZ_H
+#define RUI_LOPEZ_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct RuiWindow RuiWindow;
+
+/* Called with the title (or text) of the component that fired and the registered user data,
+ * returns whether the event was consumed */
+typedef bool (*RuiCallback)(const char *source, void *user_data);
+
+const char *rui_last_error(void);
+
+RuiWindow *rui_window_new(const char *title);
+/* Only for windows that were not given to rui_run() */
+void rui_window_free(RuiWindow *window);
+int rui_window_set_title(RuiWindow *window, const char *title);
+int rui_window_add_button(RuiWindow *window, const char *title);
+int rui_window_add_text_field(RuiWindow *window, const char *text, bool editable);
+/* accelerator can be NULL, the callback source is the title without the & of its mnemonic */
+int rui_window_add_menu_item(RuiWindow *window, const char *title, const char *accelerator);
+int rui_window_set_status_text(RuiWindow *window, const char *text);
+
+/* Replaces the callback of the components (and commands) titled source, a NULL callback fails */
+int rui_set_callback(const char *source, RuiCallback callback, void *user_data);
+/* shortcut can be NULL, running the command calls the callback of its title */
+int rui_register_command(const char *id, const char *title, const char *shortcut);
+/* Can be called from any thread */
+int rui_post_status_text(const char *text);
+
+/* Blocks until the window is closed, the window is freed by it */
+int rui_run(RuiWindow *window);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,273 @@
+//! C ABI of rui_lopez so C, C++ or Python (ctypes/cffi) hosts can drive its UIs, the declarations
+//! are in include/rui_lopez.h. Strings are UTF-8 and NUL terminated, the ones given are copied.
+//! Functions returning int give 0 on success and -1 on failure, rui_last_error() tells why
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use rui_lopez::commands::{self, Command};
+use rui_lopez::elements::*;
+use rui_lopez::engines::sdl::{self, EngineConfig};
+use rui_lopez::ui_channel::{self, UiMessage};
+
+#[cfg(feature = "python")]
+mod python;
+
+/// Called with the source of the event (the title or text of the component) and the user data
+/// given when it was registered, returns whether the event was consumed. Functions take it as an
+/// Option since C may pass NULL
+pub type RuiCallback = extern "C" fn(source: *const c_char, user_data: *mut c_void) -> bool;
+
+struct Registered {
+    callback: RuiCallback,
+    user_data: *mut c_void,
+}
+
+// The user data is only given back to the host, which is responsible for its thread safety
+unsafe impl Send for Registered {}
+
+/// C callbacks by the source they are for, the components get a Rust fn that looks them up
+static CALLBACKS: Lazy<Mutex<HashMap<String, Registered>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(error: &str) {
+    let error = CString::new(error.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = error);
+}
+
+fn dispatch(event: Event) -> bool {
+    let registered = CALLBACKS.lock().expect("FFI callbacks are poisoned")
+        .get(&event.source)
+        .map(|r| (r.callback, r.user_data));
+    match (registered, CString::new(event.source)) {
+        (Some((callback, user_data)), Ok(source)) => callback(source.as_ptr(), user_data),
+        _ => false,
+    }
+}
+
+/// # Safety
+/// text has to be NULL or a valid NUL terminated string
+unsafe fn to_string(text: *const c_char) -> Result<String, String> {
+    if text.is_null() {
+        return Err("Unexpected NULL string".to_string());
+    }
+    CStr::from_ptr(text).to_str().map(|t| t.to_string()).map_err(|e| e.to_string())
+}
+
+/// Runs f reporting its error through rui_last_error()
+fn status<F: FnOnce() -> Result<(), String>>(f: F) -> c_int {
+    match f() {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// # Safety
+/// window has to be NULL or returned by rui_window_new() and not freed
+unsafe fn window_mut<'a>(window: *mut Window) -> Result<&'a mut Window, String> {
+    window.as_mut().ok_or("Unexpected NULL window".to_string())
+}
+
+/// Why the last call of this thread failed, valid until the next failing call
+#[no_mangle]
+pub extern "C" fn rui_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
+
+/// A new window, NULL when title is not valid UTF-8
+///
+/// # Safety
+/// title has to be a valid NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_new(title: *const c_char) -> *mut Window {
+    match to_string(title) {
+        Ok(title) => Box::into_raw(Box::new(Window { title, ..Default::default() })),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Only for windows that were not given to rui_run()
+///
+/// # Safety
+/// window has to be NULL or returned by rui_window_new() and not freed
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_free(window: *mut Window) {
+    if !window.is_null() {
+        drop(Box::from_raw(window));
+    }
+}
+
+/// # Safety
+/// window has to be returned by rui_window_new() and title a valid NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_set_title(window: *mut Window, title: *const c_char) -> c_int {
+    status(|| {
+        window_mut(window)?.title = to_string(title)?;
+        Ok(())
+    })
+}
+
+fn push_child(window: &mut Window, child: Box<dyn Component>) {
+    window.container.get_or_insert_with(Container::default).children.push(child);
+}
+
+/// Adds a button to the window, rui_set_callback() with its title reacts to it
+///
+/// # Safety
+/// window has to be returned by rui_window_new() and title a valid NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_add_button(window: *mut Window, title: *const c_char) -> c_int {
+    status(|| {
+        let button = Button { title: to_string(title)?, on_action: dispatch, ..Default::default() };
+        push_child(window_mut(window)?, Box::new(button));
+        Ok(())
+    })
+}
+
+/// # Safety
+/// window has to be returned by rui_window_new() and text a valid NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_add_text_field(window: *mut Window, text: *const c_char,
+                                                   editable: bool) -> c_int {
+    status(|| {
+        let text_field = TextField { text: to_string(text)?, editable, ..Default::default() };
+        push_child(window_mut(window)?, Box::new(text_field));
+        Ok(())
+    })
+}
+
+/// Adds an item to the menu of the window, creating the menu when needed. rui_set_callback()
+/// with the item title without the & of its mnemonic ("Open" for "&Open") reacts to it,
+/// accelerator can be NULL
+///
+/// # Safety
+/// window has to be returned by rui_window_new(), the strings valid NUL terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_add_menu_item(window: *mut Window, title: *const c_char,
+                                                  accelerator: *const c_char) -> c_int {
+    status(|| {
+        let accelerator = match accelerator.is_null() {
+            true => None,
+            false => Some(to_string(accelerator)?),
+        };
+        let item = MenuItem { title: to_string(title)?, accelerator, on_action: dispatch, ..Default::default() };
+        let menu = window_mut(window)?.menu.get_or_insert_with(|| MainMenu {
+            menu: Menu { title: "&File".to_string(), ..Default::default() },
+            ..Default::default()
+        });
+        menu.menu.children.push(Submenu::MenuItem(item));
+        Ok(())
+    })
+}
+
+/// # Safety
+/// window has to be returned by rui_window_new() and text a valid NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rui_window_set_status_text(window: *mut Window, text: *const c_char) -> c_int {
+    status(|| {
+        window_mut(window)?.status_bar.get_or_insert_with(StatusBar::default).text = to_string(text)?;
+        Ok(())
+    })
+}
+
+/// Calls callback with user_data when the component (or command) titled source fires, replacing
+/// the previous one of that source. callback can be called from any thread, NULL fails
+///
+/// # Safety
+/// source has to be a valid NUL terminated string, user_data is only given back to callback
+#[no_mangle]
+pub unsafe extern "C" fn rui_set_callback(source: *const c_char, callback: Option<RuiCallback>,
+                                          user_data: *mut c_void) -> c_int {
+    status(|| {
+        let callback = callback.ok_or("Unexpected NULL callback".to_string())?;
+        CALLBACKS.lock().expect("FFI callbacks are poisoned")
+            .insert(to_string(source)?, Registered { callback, user_data });
+        Ok(())
+    })
+}
+
+/// Registers a command for the command palette and its shortcut (can be NULL), running it calls
+/// the callback set with rui_set_callback() for title
+///
+/// # Safety
+/// The strings have to be valid NUL terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn rui_register_command(id: *const c_char, title: *const c_char,
+                                              shortcut: *const c_char) -> c_int {
+    status(|| {
+        let mut command = Command::new(&to_string(id)?, &to_string(title)?, dispatch);
+        if !shortcut.is_null() {
+            command = command.with_shortcut(&to_string(shortcut)?);
+        }
+        commands::register(command);
+        Ok(())
+    })
+}
+
+/// Replaces the status bar text from any thread, it's applied on the next frame
+///
+/// # Safety
+/// text has to be a valid NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rui_post_status_text(text: *const c_char) -> c_int {
+    status(|| {
+        ui_channel::post(UiMessage::StatusText(to_string(text)?));
+        Ok(())
+    })
+}
+
+/// Shows the window and blocks until it's closed, the window is freed by it
+///
+/// # Safety
+/// window has to be returned by rui_window_new() and not freed
+#[no_mangle]
+pub unsafe extern "C" fn rui_run(window: *mut Window) -> c_int {
+    if window.is_null() {
+        set_last_error("Unexpected NULL window");
+        return -1;
+    }
+    let window = *Box::from_raw(window);
+    status(|| sdl::main_loop(vec![window], EngineConfig::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn count(_source: *const c_char, user_data: *mut c_void) -> bool {
+        unsafe { *(user_data as *mut i32) += 1 };
+        true
+    }
+
+    #[test]
+    fn callbacks_reach_the_host() {
+        let mut calls = 0i32;
+        unsafe {
+            let window = rui_window_new(c"Host".as_ptr());
+            assert_eq!(rui_window_add_button(window, c"Press".as_ptr()), 0);
+            assert_eq!(rui_set_callback(c"Press".as_ptr(), Some(count), &mut calls as *mut i32 as *mut c_void), 0);
+            assert_eq!(rui_set_callback(c"Press".as_ptr(), None, std::ptr::null_mut()), -1);
+            assert_eq!(CStr::from_ptr(rui_last_error()).to_str(), Ok("Unexpected NULL callback"));
+            let button = (*window).container.as_ref().unwrap().children[0].clone();
+            let button = button.downcast_ref::<Button>().unwrap();
+            assert!((button.on_action)(Event { source: "Press".to_string(), ..Default::default() }));
+            assert_eq!(rui_window_set_title(std::ptr::null_mut(), c"x".as_ptr()), -1);
+            assert_eq!(CStr::from_ptr(rui_last_error()).to_str(), Ok("Unexpected NULL window"));
+            rui_window_free(window);
+        }
+        assert_eq!(calls, 1);
+    }
+}
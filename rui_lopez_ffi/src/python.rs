@@ -0,0 +1,160 @@
+//! Python bindings of the builder API, enabled by the python feature. The library has to be
+//! renamed to rui_lopez.so (rui_lopez.pyd on Windows) to be imported:
+//!
+//! ```python
+//! import rui_lopez
+//! window = rui_lopez.Window("Tool")
+//! window.add_button("Run", lambda source: print("Clicked", source))
+//! window.run()
+//! ```
+
+// pyo3 0.20 expands #[pymethods] into impls inside a const, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+use rui_lopez::commands::{self, Command};
+use rui_lopez::elements::*;
+use rui_lopez::engines::sdl::{self, EngineConfig};
+use rui_lopez::ui_channel::{self, UiMessage};
+
+/// Python callables by the source they are for, like the C callbacks of the crate root
+static CALLBACKS: Lazy<Mutex<HashMap<String, PyObject>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn set_callback(source: &str, callback: Option<PyObject>) {
+    if let Some(callback) = callback {
+        CALLBACKS.lock().expect("Python callbacks are poisoned").insert(source.to_string(), callback);
+    }
+}
+
+/// Calls the callable with the source, returning None counts as consumed
+fn dispatch(event: Event) -> bool {
+    Python::with_gil(|py| {
+        let callback = CALLBACKS.lock().expect("Python callbacks are poisoned")
+            .get(&event.source)
+            .map(|c| c.clone_ref(py));
+        let callback = match callback {
+            Some(callback) => callback,
+            None => return false,
+        };
+        match callback.call1(py, (event.source.as_str(),)) {
+            Ok(result) if result.is_none(py) => true,
+            Ok(result) => result.extract::<bool>(py).unwrap_or(true),
+            Err(e) => {
+                e.print(py);
+                false
+            }
+        }
+    })
+}
+
+/// Carries a value that isn't Send into Python::allow_threads(), which runs the closure on the
+/// calling thread
+struct SameThread<T>(T);
+
+// allow_threads() doesn't move the closure to another thread, the value never leaves this one
+unsafe impl<T> Send for SameThread<T> {}
+
+impl<T> SameThread<T> {
+    fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[pyclass(name = "Window", unsendable)]
+struct PyWindow {
+    window: Window,
+}
+
+#[pymethods]
+impl PyWindow {
+    #[new]
+    fn new(title: &str) -> Self {
+        PyWindow { window: Window { title: title.to_string(), ..Default::default() } }
+    }
+
+    #[getter]
+    fn title(&self) -> String {
+        self.window.title.clone()
+    }
+
+    #[setter]
+    fn set_title(&mut self, title: &str) {
+        self.window.title = title.to_string();
+    }
+
+    /// on_action gets the title of the button
+    #[pyo3(signature = (title, on_action = None))]
+    fn add_button(&mut self, title: &str, on_action: Option<PyObject>) {
+        set_callback(title, on_action);
+        let button = Button { title: title.to_string(), on_action: dispatch, ..Default::default() };
+        self.window.container.get_or_insert_with(Container::default).children.push(Box::new(button));
+    }
+
+    #[pyo3(signature = (text, editable = false))]
+    fn add_text_field(&mut self, text: &str, editable: bool) {
+        let text_field = TextField { text: text.to_string(), editable, ..Default::default() };
+        self.window.container.get_or_insert_with(Container::default).children.push(Box::new(text_field));
+    }
+
+    /// Adds an item to the File menu, creating the menu when needed. on_action gets the title
+    /// without the & of its mnemonic
+    #[pyo3(signature = (title, accelerator = None, on_action = None))]
+    fn add_menu_item(&mut self, title: &str, accelerator: Option<String>, on_action: Option<PyObject>) {
+        set_callback(&parse_mnemonic(title).0, on_action);
+        let item = MenuItem { title: title.to_string(), accelerator, on_action: dispatch, ..Default::default() };
+        let menu = self.window.menu.get_or_insert_with(|| MainMenu {
+            menu: Menu { title: "&File".to_string(), ..Default::default() },
+            ..Default::default()
+        });
+        menu.menu.children.push(Submenu::MenuItem(item));
+    }
+
+    fn set_status_text(&mut self, text: &str) {
+        self.window.status_bar.get_or_insert_with(StatusBar::default).text = text.to_string();
+    }
+
+    /// Shows the window and blocks until it's closed, callbacks run meanwhile. The GIL is released
+    /// while it runs so other Python threads keep running, callbacks take it back
+    fn run(&self, py: Python) -> PyResult<()> {
+        let window = SameThread(self.window.clone());
+        py.allow_threads(move || sdl::main_loop(vec![window.into_inner()], EngineConfig::default()))
+            .map_err(PyRuntimeError::new_err)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.window)
+    }
+}
+
+/// Registers a command for the command palette, callback gets its title
+#[pyfunction]
+#[pyo3(signature = (id, title, callback, shortcut = None))]
+fn register_command(id: &str, title: &str, callback: PyObject, shortcut: Option<&str>) {
+    set_callback(title, Some(callback));
+    let mut command = Command::new(id, title, dispatch);
+    if let Some(shortcut) = shortcut {
+        command = command.with_shortcut(shortcut);
+    }
+    commands::register(command);
+}
+
+/// Replaces the status bar text, it can be called from any thread
+#[pyfunction]
+fn post_status_text(text: &str) {
+    ui_channel::post(UiMessage::StatusText(text.to_string()));
+}
+
+#[pymodule]
+#[pyo3(name = "rui_lopez")]
+fn module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyWindow>()?;
+    m.add_function(wrap_pyfunction!(register_command, m)?)?;
+    m.add_function(wrap_pyfunction!(post_status_text, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,310 @@
+//! Accessibility preferences shared by the whole UI: the animations stop when reducing motion,
+//! the engines draw with the colors of the color mode, text is never smaller than the minimum font
+//! scale, the pointer is drawn bigger, the whole UI is zoomed and read-only text can be walked with
+//! a caret. The app sets them with set_preferences(), usually from detect(), and can change them at
+//! any time
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+/// The few colors everything is drawn with in forced colors mode, named after the CSS system
+/// colors. The default is the black high contrast theme of Windows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemPalette {
+    /// Backgrounds
+    pub canvas: (u8, u8, u8),
+    /// Text and borders
+    pub canvas_text: (u8, u8, u8),
+    /// Disabled text and other secondary parts
+    pub gray_text: (u8, u8, u8),
+    /// Selections, links and everything else drawn with an accent
+    pub highlight: (u8, u8, u8),
+}
+
+impl Default for SystemPalette {
+    fn default() -> Self {
+        SystemPalette {
+            canvas: (0, 0, 0),
+            canvas_text: (255, 255, 255),
+            gray_text: (63, 242, 63),
+            highlight: (26, 235, 255),
+        }
+    }
+}
+
+/// How the engines choose the colors they draw with
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorMode {
+    /// The colors of each widget
+    #[default]
+    Standard,
+    /// The colors pushed to black, white and bright accents, see high_contrast_color()
+    HighContrast,
+    /// Every color becomes one of the palette and every widget draws its border, since its
+    /// background is the same color as the window
+    ForcedColors(SystemPalette),
+}
+
+impl ColorMode {
+    /// The color to draw instead of (r, g, b)
+    pub fn map(&self, (r, g, b): (u8, u8, u8)) -> (u8, u8, u8) {
+        match self {
+            ColorMode::Standard => (r, g, b),
+            ColorMode::HighContrast => high_contrast_color(r, g, b),
+            ColorMode::ForcedColors(palette) => {
+                let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+                let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+                match luminance {
+                    _ if max - min > 60 => palette.highlight,
+                    l if l > 140. => palette.canvas_text,
+                    l if l >= 100. => palette.gray_text,
+                    _ => palette.canvas,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityPreferences {
+    /// Animations jump to their end instead of moving
+    pub reduce_motion: bool,
+    pub color_mode: ColorMode,
+    /// The text is scaled by at least this
+    pub min_font_scale: f32,
+    /// Size of the pointer compared to the default one
+    pub cursor_scale: f32,
+    /// Scale of the whole UI on top of the display density, like the zoom of a browser. Windows
+    /// are laid out again in the space that leaves
+    pub zoom: f32,
+    /// Labels take the focus and show a caret moved with the arrows, Shift selects
+    pub caret_browsing: bool,
+}
+
+impl Default for AccessibilityPreferences {
+    fn default() -> Self {
+        AccessibilityPreferences {
+            reduce_motion: false,
+            color_mode: ColorMode::Standard,
+            min_font_scale: 1.,
+            cursor_scale: 1.,
+            zoom: 1.,
+            caret_browsing: false,
+        }
+    }
+}
+
+struct State {
+    preferences: AccessibilityPreferences,
+    /// Bumped every time the preferences change, so the engines know when to rebuild
+    generation: u64,
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(|| Mutex::new(State {
+    preferences: AccessibilityPreferences::default(),
+    generation: 0,
+}));
+
+fn state() -> std::sync::MutexGuard<'static, State> {
+    STATE.lock().expect("Accessibility preferences are poisoned")
+}
+
+pub fn preferences() -> AccessibilityPreferences {
+    state().preferences.clone()
+}
+
+pub fn set_preferences(preferences: AccessibilityPreferences) {
+    let mut state = state();
+    if state.preferences != preferences {
+        state.preferences = preferences;
+        state.generation += 1;
+    }
+}
+
+pub fn generation() -> u64 {
+    state().generation
+}
+
+pub fn reduce_motion() -> bool {
+    state().preferences.reduce_motion
+}
+
+pub fn color_mode() -> ColorMode {
+    state().preferences.color_mode
+}
+
+/// Whether widgets have to draw their borders, see ColorMode::ForcedColors
+pub fn forced_colors() -> bool {
+    matches!(color_mode(), ColorMode::ForcedColors(_))
+}
+
+/// The scale of the text, never less than 1
+pub fn font_scale() -> f32 {
+    state().preferences.min_font_scale.max(1.)
+}
+
+pub fn caret_browsing() -> bool {
+    state().preferences.caret_browsing
+}
+
+/// Turns caret browsing on or off, what F7 does
+pub fn toggle_caret_browsing() {
+    let mut preferences = preferences();
+    preferences.caret_browsing = !preferences.caret_browsing;
+    set_preferences(preferences);
+}
+
+/// The zoom levels zoom_in() and zoom_out() step through, the ones of browsers
+pub const ZOOM_LEVELS: [f32; 13] = [0.5, 0.67, 0.75, 0.8, 0.9, 1., 1.1, 1.25, 1.5, 1.75, 2., 2.5, 3.];
+
+pub fn zoom() -> f32 {
+    state().preferences.zoom
+}
+
+/// The level after zoom in ZOOM_LEVELS (before it with a negative steps), zooms between levels
+/// go to the next one in that direction
+pub fn step_zoom(zoom: f32, steps: i32) -> f32 {
+    let position = ZOOM_LEVELS.partition_point(|level| *level < zoom - 0.001);
+    let on_level = ZOOM_LEVELS.get(position).is_some_and(|level| (level - zoom).abs() < 0.001);
+    let index = match (steps > 0, on_level) {
+        (true, true) => position as i32 + steps,
+        (true, false) => position as i32 + steps - 1,
+        (false, _) => position as i32 + steps,
+    };
+    ZOOM_LEVELS[index.clamp(0, ZOOM_LEVELS.len() as i32 - 1) as usize]
+}
+
+/// Sets the zoom of the UI, returns whether it changed
+pub fn set_zoom(zoom: f32) -> bool {
+    let mut preferences = preferences();
+    if preferences.zoom == zoom {
+        return false;
+    }
+    preferences.zoom = zoom;
+    set_preferences(preferences);
+    true
+}
+
+/// The next zoom level, what Ctrl+= does
+pub fn zoom_in() -> bool {
+    set_zoom(step_zoom(zoom(), 1))
+}
+
+/// The previous zoom level, what Ctrl+- does
+pub fn zoom_out() -> bool {
+    set_zoom(step_zoom(zoom(), -1))
+}
+
+/// Back to no zoom, what Ctrl+0 does
+pub fn reset_zoom() -> bool {
+    set_zoom(1.)
+}
+
+/// The color to draw instead of (r, g, b) with high contrast: dark colors become black, light ones
+/// white and the saturated ones (e.g. the selection) keep their hue at full brightness
+pub fn high_contrast_color(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    if max - min > 60 {
+        let scale = 255. / max as f32;
+        return ((r as f32 * scale) as u8, (g as f32 * scale) as u8, (b as f32 * scale) as u8);
+    }
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    match luminance > 100. {
+        true => (255, 255, 255),
+        false => (0, 0, 0),
+    }
+}
+
+/// The preferences the OS tells about, the defaults where it doesn't. Only GNOME and macOS are
+/// asked
+#[cfg(not(target_arch = "wasm32"))]
+pub fn detect() -> AccessibilityPreferences {
+    let mut preferences = AccessibilityPreferences::default();
+    if cfg!(target_os = "macos") {
+        let universal_access = |key: &str| read_command("defaults", &["read", "com.apple.universalaccess", key]);
+        preferences.reduce_motion = universal_access("reduceMotion").is_some_and(|v| v == "1");
+        if universal_access("increaseContrast").is_some_and(|v| v == "1") {
+            preferences.color_mode = ColorMode::HighContrast;
+        }
+        if let Some(scale) = universal_access("mouseDriverCursorSize").and_then(|v| v.parse().ok()) {
+            preferences.cursor_scale = scale;
+        }
+    } else if cfg!(target_os = "linux") {
+        let gnome = |schema: &str, key: &str| read_command("gsettings", &["get", schema, key]);
+        preferences.reduce_motion = gnome("org.gnome.desktop.interface", "enable-animations")
+            .is_some_and(|v| v == "false");
+        if gnome("org.gnome.desktop.a11y.interface", "high-contrast").is_some_and(|v| v == "true") {
+            preferences.color_mode = ColorMode::HighContrast;
+        }
+        if let Some(scale) = gnome("org.gnome.desktop.interface", "text-scaling-factor").and_then(|v| v.parse().ok()) {
+            preferences.min_font_scale = scale;
+        }
+        // 24 pixels is the default cursor size of GNOME
+        if let Some(size) = gnome("org.gnome.desktop.interface", "cursor-size").and_then(|v| v.parse::<f32>().ok()) {
+            preferences.cursor_scale = size / 24.;
+        }
+    }
+    preferences
+}
+
+/// The preferences the browser tells about through its media queries, it knows nothing about
+/// the font and pointer size. Forced colors use the default palette since the page can't read the
+/// one of the browser
+#[cfg(target_arch = "wasm32")]
+pub fn detect() -> AccessibilityPreferences {
+    let matches = |query: &str| web_sys::window()
+        .and_then(|w| w.match_media(query).ok().flatten())
+        .is_some_and(|m| m.matches());
+    AccessibilityPreferences {
+        reduce_motion: matches("(prefers-reduced-motion: reduce)"),
+        color_mode: match (matches("(forced-colors: active)"), matches("(prefers-contrast: more)")) {
+            (true, _) => ColorMode::ForcedColors(SystemPalette::default()),
+            (false, true) => ColorMode::HighContrast,
+            (false, false) => ColorMode::Standard,
+        },
+        ..Default::default()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_command(program: &str, arguments: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(arguments).output().ok()?;
+    match output.status.success() {
+        true => Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_contrast_keeps_accents() {
+        assert_eq!(high_contrast_color(30, 30, 35), (0, 0, 0));
+        assert_eq!(high_contrast_color(220, 220, 220), (255, 255, 255));
+        assert_eq!(high_contrast_color(70, 130, 200), (89, 165, 255));
+    }
+
+    #[test]
+    fn zoom_steps_through_the_levels() {
+        assert_eq!(step_zoom(1., 1), 1.1);
+        assert_eq!(step_zoom(1., -2), 0.8);
+        assert_eq!(step_zoom(1.2, 1), 1.25, "Between levels");
+        assert_eq!(step_zoom(1.2, -1), 1.1);
+        assert_eq!(step_zoom(3., 1), 3.);
+        assert_eq!(step_zoom(0.1, -1), 0.5);
+    }
+
+    #[test]
+    fn forced_colors_use_the_palette() {
+        let palette = SystemPalette::default();
+        let forced = ColorMode::ForcedColors(palette);
+        assert_eq!(forced.map((80, 80, 90)), palette.canvas, "Button background");
+        assert_eq!(forced.map((230, 230, 230)), palette.canvas_text);
+        assert_eq!(forced.map((120, 120, 120)), palette.gray_text, "Disabled text");
+        assert_eq!(forced.map((70, 130, 200)), palette.highlight);
+        assert_eq!(ColorMode::Standard.map((70, 130, 200)), (70, 130, 200));
+    }
+}
@@ -0,0 +1,353 @@
+//! What assistive technologies are told about the components of a window: a role, a name and a
+//! description for each one, derived from what it shows unless the app annotates it with an
+//! Accessible. Live components (e.g. a Label showing status messages) are announced when they change
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::elements::*;
+use crate::tree_table::TreeTable;
+
+/// How many announcements are kept until they're taken
+const MAX_ANNOUNCEMENTS: usize = 32;
+
+static ANNOUNCEMENTS: Lazy<Mutex<VecDeque<Announcement>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    /// An important message that needs attention right away
+    Alert,
+    Button,
+    /// Pages shown one at a time, e.g. a Carousel
+    Carousel,
+    CheckBox,
+    ColorWell,
+    ComboBox,
+    Image,
+    Link,
+    List,
+    /// Output added over time where the newest is last, e.g. a Console
+    Log,
+    /// A reading within a known range, e.g. a Gauge
+    Meter,
+    /// Links to the places above the current one, e.g. a Breadcrumb
+    Navigation,
+    ProgressBar,
+    RadioGroup,
+    Slider,
+    SpinButton,
+    /// Advisory information like the result of an action
+    Status,
+    Table,
+    TabList,
+    Text,
+    TextInput,
+}
+
+/// How the changes of a component are announced
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Live {
+    #[default]
+    Off,
+    /// Once the screen reader is done with what it's saying
+    Polite,
+    /// Right away, interrupting what the screen reader is saying
+    Assertive,
+}
+
+/// Annotations of a component for assistive technologies, what is None is derived from the
+/// component
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Accessible {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Replaces the role of the component, e.g. Status for a Label showing status messages
+    pub role: Option<Role>,
+    pub live: Live,
+}
+
+/// A component as assistive technologies see it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibleNode {
+    /// Where the component is in the container
+    pub path: Vec<usize>,
+    pub role: Role,
+    pub name: String,
+    pub description: Option<String>,
+    /// The state of the component, e.g. whether a Checkbox is checked or the text of a TextField
+    pub value: Option<String>,
+    pub live: Live,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Announcement {
+    pub text: String,
+    pub live: Live,
+}
+
+/// The nodes of the components of container in the order they're shown, their paths tell how
+/// they nest. Only the active page of a TabPane or a Carousel is in it
+pub fn tree(container: &Container) -> Vec<AccessibleNode> {
+    let mut nodes = vec![];
+    add_nodes(container, &[], &mut nodes);
+    nodes
+}
+
+fn add_nodes(container: &Container, parent: &[usize], nodes: &mut Vec<AccessibleNode>) {
+    for (i, child) in container.children.iter().enumerate() {
+        let path = [parent, &[i]].concat();
+        if let Some(container) = child.downcast_ref::<Container>() {
+            add_nodes(container, &path, nodes);
+        } else if let Some(scroll_pane) = child.downcast_ref::<ScrollPane>() {
+            add_nodes(&scroll_pane.content, &[path, vec![ScrollPane::CONTENT]].concat(), nodes);
+        } else if let Some(form) = child.downcast_ref::<Form>() {
+            add_nodes(&form.content, &[path.clone(), vec![Form::CONTENT]].concat(), nodes);
+            nodes.push(AccessibleNode {
+                path: [path, vec![Form::SUBMIT]].concat(),
+                role: Role::Button,
+                name: form.submit_title.clone(),
+                description: None,
+                value: None,
+                live: Live::Off,
+            });
+        } else if let Some(tab_pane) = child.downcast_ref::<TabPane>() {
+            let tab = tab_pane.active_tab();
+            nodes.push(AccessibleNode {
+                path: path.clone(),
+                role: Role::TabList,
+                name: String::new(),
+                description: None,
+                value: tab.map(|t| t.title.clone()),
+                live: Live::Off,
+            });
+            if let Some(tab) = tab {
+                add_nodes(&tab.content, &[path, vec![tab_pane.active]].concat(), nodes);
+            }
+        } else if let Some(carousel) = child.downcast_ref::<Carousel>() {
+            nodes.push(AccessibleNode {
+                path: path.clone(),
+                role: carousel.accessible.role.unwrap_or(Role::Carousel),
+                name: carousel.accessible.name.clone().unwrap_or_default(),
+                description: carousel.accessible.description.clone(),
+                value: Some(format!("Page {} of {}", carousel.active + 1, carousel.pages.len())),
+                live: carousel.accessible.live,
+            });
+            if let Some(page) = carousel.active_page() {
+                add_nodes(page, &[path, vec![Carousel::PAGES, carousel.active]].concat(), nodes);
+            }
+        } else if let Some(loadable) = child.downcast_ref::<Loadable>() {
+            // The skeleton means nothing to a screen reader, it's told the content is on its way
+            match loadable.loading {
+                true => nodes.push(AccessibleNode {
+                    path,
+                    role: Role::ProgressBar,
+                    name: String::new(),
+                    description: None,
+                    value: Some("busy".to_string()),
+                    live: Live::Off,
+                }),
+                false => add_nodes(&loadable.content, &path, nodes),
+            }
+        } else if let Some(deferred) = child.downcast_ref::<Deferred>() {
+            match deferred.ready {
+                true => add_nodes(&deferred.content, &path, nodes),
+                false => nodes.push(AccessibleNode {
+                    path,
+                    role: Role::ProgressBar,
+                    name: String::new(),
+                    description: None,
+                    value: Some("busy".to_string()),
+                    live: Live::Off,
+                }),
+            }
+        } else if let Some(provider) = child.downcast_ref::<Provider>() {
+            add_nodes(&provider.content, &path, nodes);
+        } else if let Some(shown_if) = child.downcast_ref::<If>() {
+            add_nodes(shown_if.active(), &path, nodes);
+        } else if let Some(for_each) = child.downcast_ref::<ForEach>() {
+            add_nodes(&for_each.content, &path, nodes);
+        } else if let Some(node) = widget_node(child.as_ref(), path) {
+            nodes.push(node);
+        }
+    }
+}
+
+/// The node of a widget, None for the components that aren't one
+fn widget_node(component: &dyn Component, path: Vec<usize>) -> Option<AccessibleNode> {
+    let (accessible, role, name, value) = if let Some(button) = component.downcast_ref::<Button>() {
+        (&button.accessible, Role::Button, button.title.clone(), None)
+    } else if let Some(text_field) = component.downcast_ref::<TextField>() {
+        (&text_field.accessible, Role::TextInput, String::new(), Some(text_field.text.clone()))
+    } else if let Some(text_area) = component.downcast_ref::<TextArea>() {
+        (&text_area.accessible, Role::TextInput, String::new(), Some(text_area.text.clone()))
+    } else if let Some(chip_input) = component.downcast_ref::<ChipInput>() {
+        (&chip_input.accessible, Role::TextInput, String::new(), Some(chip_input.chips.join(", ")))
+    } else if let Some(label) = component.downcast_ref::<Label>() {
+        (&label.accessible, Role::Text, label.text.clone(), None)
+    } else if let Some(hyperlink) = component.downcast_ref::<Hyperlink>() {
+        (&hyperlink.accessible, Role::Link, hyperlink.text.clone(), Some(hyperlink.url.clone()))
+    } else if let Some(markdown_view) = component.downcast_ref::<MarkdownView>() {
+        (&markdown_view.accessible, Role::Text, markdown_view.document().plain_text(), None)
+    } else if let Some(breadcrumb) = component.downcast_ref::<Breadcrumb>() {
+        let path = breadcrumb.segments.join(&format!(" {} ", Breadcrumb::SEPARATOR));
+        (&breadcrumb.accessible, Role::Navigation, String::new(), Some(path))
+    } else if let Some(checkbox) = component.downcast_ref::<Checkbox>() {
+        let checked = if checkbox.checked { "checked" } else { "not checked" };
+        (&checkbox.accessible, Role::CheckBox, checkbox.label.clone(), Some(checked.to_string()))
+    } else if let Some(radio_group) = component.downcast_ref::<RadioGroup>() {
+        let selected = radio_group.selected_index().map(|i| radio_group.children[i].label.clone());
+        (&radio_group.accessible, Role::RadioGroup, String::new(), selected)
+    } else if let Some(segmented_control) = component.downcast_ref::<SegmentedControl>() {
+        let selected = segmented_control.selected_segment().map(|s| s.to_string());
+        (&segmented_control.accessible, Role::RadioGroup, String::new(), selected)
+    } else if let Some(rating) = component.downcast_ref::<Rating>() {
+        let value = format!("{} of {} stars", rating.value, rating.max);
+        (&rating.accessible, Role::Slider, String::new(), Some(value))
+    } else if let Some(slider) = component.downcast_ref::<Slider>() {
+        (&slider.accessible, Role::Slider, String::new(), Some(slider.value.to_string()))
+    } else if let Some(spinner) = component.downcast_ref::<Spinner>() {
+        (&spinner.accessible, Role::SpinButton, String::new(), Some(spinner.text.clone()))
+    } else if let Some(color_picker) = component.downcast_ref::<ColorPicker>() {
+        let Color { r, g, b, .. } = color_picker.color();
+        (&color_picker.accessible, Role::ColorWell, String::new(), Some(format!("#{:02x}{:02x}{:02x}", r, g, b)))
+    } else if let Some(date_picker) = component.downcast_ref::<DatePicker>() {
+        (&date_picker.accessible, Role::ComboBox, String::new(), date_picker.date.map(|d| d.to_string()))
+    } else if let Some(combo_box) = component.downcast_ref::<ComboBox>() {
+        let selected = combo_box.selected_item().map(|i| i.to_string());
+        (&combo_box.accessible, Role::ComboBox, String::new(), selected)
+    } else if let Some(list_view) = component.downcast_ref::<ListView>() {
+        (&list_view.accessible, Role::List, String::new(), None)
+    } else if let Some(table_view) = component.downcast_ref::<TableView>() {
+        (&table_view.accessible, Role::Table, String::new(), None)
+    } else if let Some(tree_table) = component.downcast_ref::<TreeTable>() {
+        (&tree_table.accessible, Role::Table, String::new(), None)
+    } else if let Some(console) = component.downcast_ref::<Console>() {
+        let last = console.lines.back().map(|line| line.iter().map(|s| s.text.as_str()).collect());
+        (&console.accessible, Role::Log, String::new(), last)
+    } else if let Some(progress_bar) = component.downcast_ref::<ProgressBar>() {
+        let value = match progress_bar.value {
+            Some(value) => format!("{}%", (value.clamp(0., 1.) * 100.).round()),
+            None => "busy".to_string(),
+        };
+        (&progress_bar.accessible, Role::ProgressBar, String::new(), Some(value))
+    } else if let Some(gauge) = component.downcast_ref::<Gauge>() {
+        let value = format!("{} {}", gauge.text(), gauge.unit);
+        (&gauge.accessible, Role::Meter, String::new(), Some(value.trim_end().to_string()))
+    } else if let Some(drop_zone) = component.downcast_ref::<DropZone>() {
+        (&drop_zone.accessible, Role::Text, drop_zone.text.clone(), drop_zone.hint())
+    } else if let Some(avatar) = component.downcast_ref::<Avatar>() {
+        (&avatar.accessible, Role::Image, avatar.name.clone(), None)
+    } else if let Some(image) = component.downcast_ref::<Image>() {
+        // Images are decorative unless they're given a name
+        (&image.accessible, Role::Image, String::new(), None)
+    } else if let Some(canvas) = component.downcast_ref::<Canvas>() {
+        (&canvas.accessible, Role::Image, String::new(), None)
+    } else {
+        chart_node_parts(component).or_else(|| svg_node_parts(component))?
+    };
+    Some(AccessibleNode {
+        path,
+        role: accessible.role.unwrap_or(role),
+        name: accessible.name.clone().unwrap_or(name),
+        description: accessible.description.clone(),
+        value,
+        live: accessible.live,
+    })
+}
+
+/// The accessible, role, name and value of a chart, which reads its values as the value
+#[cfg(feature = "charts")]
+fn chart_node_parts(component: &dyn Component) -> Option<(&Accessible, Role, String, Option<String>)> {
+    use crate::charts::{BarChart, LineChart, PieChart};
+
+    if let Some(chart) = component.downcast_ref::<LineChart>() {
+        Some((&chart.accessible, Role::Image, String::new(), Some(chart.summary())))
+    } else if let Some(chart) = component.downcast_ref::<BarChart>() {
+        Some((&chart.accessible, Role::Image, String::new(), Some(chart.summary())))
+    } else {
+        component.downcast_ref::<PieChart>()
+            .map(|chart| (&chart.accessible, Role::Image, String::new(), Some(chart.summary())))
+    }
+}
+
+#[cfg(not(feature = "charts"))]
+fn chart_node_parts(_component: &dyn Component) -> Option<(&Accessible, Role, String, Option<String>)> {
+    None
+}
+
+/// The accessible, role, name and value of an SvgImage, decorative like an Image unless it's named
+#[cfg(feature = "lyon")]
+fn svg_node_parts(component: &dyn Component) -> Option<(&Accessible, Role, String, Option<String>)> {
+    component.downcast_ref::<crate::svg::SvgImage>()
+        .map(|image| (&image.accessible, Role::Image, String::new(), None))
+}
+
+#[cfg(not(feature = "lyon"))]
+fn svg_node_parts(_component: &dyn Component) -> Option<(&Accessible, Role, String, Option<String>)> {
+    None
+}
+
+/// What the live nodes of current say that they didn't in previous: their value when it changed,
+/// otherwise their name. The ones that weren't in previous are announced whole
+pub fn changes(previous: &[AccessibleNode], current: &[AccessibleNode]) -> Vec<Announcement> {
+    current.iter()
+        .filter(|node| node.live != Live::Off)
+        .filter_map(|node| {
+            let text = match previous.iter().find(|p| p.path == node.path) {
+                Some(old) if old.value != node.value => node.value.clone().unwrap_or_default(),
+                Some(old) if old.name != node.name => node.name.clone(),
+                Some(_) => return None,
+                None => node.name.clone(),
+            };
+            match text.is_empty() {
+                true => None,
+                false => Some(Announcement { text, live: node.live }),
+            }
+        })
+        .collect()
+}
+
+/// Queues an announcement for take_announcements(), only the last ones are kept
+pub fn announce(announcement: Announcement) {
+    let mut announcements = ANNOUNCEMENTS.lock().expect("Announcements are poisoned");
+    if announcements.len() == MAX_ANNOUNCEMENTS {
+        announcements.pop_front();
+    }
+    announcements.push_back(announcement);
+}
+
+/// The announcements made since the last call, oldest first. It's how apps pass them on to a
+/// screen reader, the web engine also puts them in live regions of the page
+pub fn take_announcements() -> Vec<Announcement> {
+    ANNOUNCEMENTS.lock().expect("Announcements are poisoned").drain(..).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn live_labels_are_announced_when_they_change() {
+        let status = |text: &str| Label {
+            text: text.to_string(),
+            accessible: Accessible { role: Some(Role::Status), live: Live::Polite, ..Default::default() },
+            ..Default::default()
+        };
+        let mut container = Container {
+            children: vec![Box::new(Button {
+                accessible: Accessible { name: Some("Save the file".to_string()), ..Default::default() },
+                ..Default::default()
+            }), Box::new(status("Ready"))],
+            ..Default::default()
+        };
+        let before = tree(&container);
+        assert_eq!(before[0].name, "Save the file");
+        assert_eq!(before[1].role, Role::Status);
+        container.children[1] = Box::new(status("Saved"));
+        let after = tree(&container);
+        assert_eq!(changes(&before, &after), vec![Announcement { text: "Saved".to_string(), live: Live::Polite }]);
+        assert!(changes(&after, &after).is_empty());
+    }
+}
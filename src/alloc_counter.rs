@@ -0,0 +1,140 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Debug facility to validate that steady-state frames don't allocate. The binary installs it as
+/// its global allocator, a library can't since the binary may have its own:
+///
+/// ```ignore
+/// #[cfg(feature = "alloc-counter")]
+/// #[global_allocator]
+/// static GLOBAL: rui_lopez::alloc_counter::CountingAllocator = rui_lopez::alloc_counter::CountingAllocator;
+/// ```
+///
+/// main_loop() then reports every frame that allocated, listing how much each subsystem allocated.
+/// Without it installed nothing is counted
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Allocations done by one subsystem (or widget) during a frame
+#[derive(Debug, Clone)]
+pub struct ScopeAllocations {
+    pub name: &'static str,
+    pub allocations: usize,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FrameAllocations {
+    pub allocations: usize,
+    pub bytes: usize,
+    pub scopes: Vec<ScopeAllocations>,
+}
+
+struct FrameStart {
+    allocations: usize,
+    bytes: usize,
+}
+
+thread_local! {
+    static FRAME_START: RefCell<FrameStart> = const { RefCell::new(FrameStart { allocations: 0, bytes: 0 }) };
+    // Reserved up front so recording scopes doesn't show up as allocations of the frame
+    static SCOPES: RefCell<Vec<ScopeAllocations>> = RefCell::new(Vec::with_capacity(64));
+    /// What the scopes nested in each open AllocScope allocated, innermost last
+    static NESTED: RefCell<Vec<(usize, usize)>> = RefCell::new(Vec::with_capacity(16));
+}
+
+/// Counts the allocations done between its creation and its drop and records them under name for
+/// the current frame. Allocations of nested scopes are only recorded under the innermost one
+pub struct AllocScope {
+    name: &'static str,
+    allocations: usize,
+    bytes: usize,
+}
+
+impl AllocScope {
+    pub fn new(name: &'static str) -> Self {
+        NESTED.with(|nested| nested.borrow_mut().push((0, 0)));
+        AllocScope {
+            name,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for AllocScope {
+    fn drop(&mut self) {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - self.allocations;
+        let bytes = BYTES.load(Ordering::Relaxed) - self.bytes;
+        let (nested_allocations, nested_bytes) = NESTED.with(|nested| {
+            let mut nested = nested.borrow_mut();
+            let own = nested.pop().unwrap_or_default();
+            if let Some(parent) = nested.last_mut() {
+                parent.0 += allocations;
+                parent.1 += bytes;
+            }
+            own
+        });
+        let allocations = allocations.saturating_sub(nested_allocations);
+        let bytes = bytes.saturating_sub(nested_bytes);
+        if allocations > 0 {
+            SCOPES.with(|scopes| scopes.borrow_mut().push(ScopeAllocations {
+                name: self.name,
+                allocations,
+                bytes,
+            }));
+        }
+    }
+}
+
+pub fn begin_frame() {
+    SCOPES.with(|scopes| scopes.borrow_mut().clear());
+    FRAME_START.with(|start| *start.borrow_mut() = FrameStart {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+    });
+}
+
+/// Returns the allocations done since begin_frame(), None when the frame didn't allocate at all
+pub fn end_frame() -> Option<FrameAllocations> {
+    let (allocations, bytes) = FRAME_START.with(|start| {
+        let start = start.borrow();
+        (ALLOCATIONS.load(Ordering::Relaxed) - start.allocations,
+         BYTES.load(Ordering::Relaxed) - start.bytes)
+    });
+    if allocations == 0 {
+        return None;
+    }
+    Some(FrameAllocations {
+        allocations,
+        bytes,
+        scopes: SCOPES.with(|scopes| scopes.borrow().clone()),
+    })
+}
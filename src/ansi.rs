@@ -0,0 +1,192 @@
+//! The ANSI escape sequences programs color their output with, turned into spans of text in a style
+//! for the Console. The SGR sequences (`ESC [ ... m`) set the colors and the weight: the 16 colors
+//! of the palette, the 256 of xterm and 24-bit ones. Other sequences (moving the cursor, clearing,
+//! titles...) are left out of the text:
+//!
+//! ```ignore
+//! let mut style = AnsiStyle::default();
+//! let spans = ansi::parse("\x1b[1;31merror\x1b[0m: not found", &mut style);
+//! ```
+//!
+//! The style is carried from one call to the next since programs often color several lines at once
+
+use crate::elements::Color;
+use crate::ui_channel::{self, UiMessage};
+
+/// Black, red, green, yellow, blue, magenta, cyan and white, then their bright variants
+pub const PALETTE: [Color; 16] = [
+    Color::rgb(0, 0, 0), Color::rgb(205, 49, 49), Color::rgb(13, 188, 121), Color::rgb(229, 229, 16),
+    Color::rgb(36, 114, 200), Color::rgb(188, 63, 188), Color::rgb(17, 168, 205), Color::rgb(229, 229, 229),
+    Color::rgb(102, 102, 102), Color::rgb(241, 76, 76), Color::rgb(35, 209, 139), Color::rgb(245, 245, 67),
+    Color::rgb(59, 142, 234), Color::rgb(214, 112, 214), Color::rgb(41, 184, 219), Color::rgb(255, 255, 255),
+];
+
+/// Columns between tab stops
+const TAB_WIDTH: usize = 8;
+
+const ESCAPE: char = '\x1b';
+const BELL: char = '\x07';
+
+/// The colors and the weight text is written in, None for the colors of the console
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AnsiStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl AnsiStyle {
+    /// The color of the text, bold brightens the first 8 colors of the palette like terminals do
+    pub fn text_color(&self, default: Color) -> Color {
+        match self.foreground {
+            Some(color) if self.bold => PALETTE[..8].iter().position(|c| *c == color).map_or(color, |i| PALETTE[i + 8]),
+            Some(color) => color,
+            None => default,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnsiSpan {
+    pub text: String,
+    pub style: AnsiStyle,
+}
+
+/// The spans of text without its escape sequences, starting in style. style is left as the text
+/// leaves it. Tabs are expanded to the next tab stop and other control characters dropped
+pub fn parse(text: &str, style: &mut AnsiStyle) -> Vec<AnsiSpan> {
+    let mut spans: Vec<AnsiSpan> = vec![];
+    let mut current = String::new();
+    let mut column = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ESCAPE => {
+                let before = *style;
+                match chars.next() {
+                    Some('[') => {
+                        let mut parameters = String::new();
+                        // Up to the final byte, '@' to '~'
+                        for c in chars.by_ref() {
+                            if ('@'..='~').contains(&c) {
+                                if c == 'm' {
+                                    select_graphic_rendition(&parameters, style);
+                                }
+                                break;
+                            }
+                            parameters.push(c);
+                        }
+                    }
+                    // An OSC, e.g. the title, ends with a bell or ESC \
+                    Some(']') => {
+                        while let Some(c) = chars.next() {
+                            if c == BELL || (c == ESCAPE && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                if *style != before && !current.is_empty() {
+                    spans.push(AnsiSpan { text: std::mem::take(&mut current), style: before });
+                }
+            }
+            '\t' => {
+                let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                current.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            c if c.is_control() => {}
+            c => {
+                current.push(c);
+                column += 1;
+            }
+        }
+    }
+    if !current.is_empty() {
+        spans.push(AnsiSpan { text: current, style: *style });
+    }
+    spans
+}
+
+/// Writes the text to the Consoles with the source on the UI thread, it can be called from any
+/// thread, e.g. one reading the output of a child process
+pub fn write_console(source: &str, text: &str) {
+    ui_channel::post(UiMessage::ConsoleOutput { source: source.to_string(), text: text.to_string() });
+}
+
+/// The text without its escape sequences
+pub fn strip(text: &str) -> String {
+    parse(text, &mut AnsiStyle::default()).into_iter().map(|span| span.text).collect()
+}
+
+/// Applies the parameters of an SGR sequence, separated by semicolons. An empty one resets
+fn select_graphic_rendition(parameters: &str, style: &mut AnsiStyle) {
+    let mut codes = parameters.split(';').map(|p| p.parse::<u32>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = AnsiStyle::default(),
+            1 => style.bold = true,
+            22 => style.bold = false,
+            30..=37 => style.foreground = Some(PALETTE[code as usize - 30]),
+            90..=97 => style.foreground = Some(PALETTE[code as usize - 90 + 8]),
+            39 => style.foreground = None,
+            40..=47 => style.background = Some(PALETTE[code as usize - 40]),
+            100..=107 => style.background = Some(PALETTE[code as usize - 100 + 8]),
+            49 => style.background = None,
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(color_256),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(Color::rgb(r as u8, g as u8, b as u8)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match code {
+                    38 => style.foreground = color.or(style.foreground),
+                    _ => style.background = color.or(style.background),
+                }
+            }
+            // Italic, underline, blinking... aren't shown
+            _ => {}
+        }
+    }
+}
+
+/// The color at index in the palette of xterm: the 16 colors, a 6x6x6 cube and 24 grays
+fn color_256(index: u32) -> Color {
+    match index {
+        0..=15 => PALETTE[index as usize],
+        16..=231 => {
+            let level = |n: u32| if n == 0 { 0 } else { (55 + n * 40) as u8 };
+            let cube = index - 16;
+            Color::rgb(level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+        }
+        _ => {
+            let gray = (8 + (index.min(255) - 232) * 10) as u8;
+            Color::rgb(gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_sequences_become_styled_spans() {
+        let mut style = AnsiStyle::default();
+        let spans = parse("\x1b[1;31merror\x1b[0m:\tnot \x1b[2Kfound \x1b]0;title\x07\x1b[38;5;196mhere", &mut style);
+        let texts: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["error", ":  not found ", "here"], "The tab goes to column 8");
+        assert_eq!(spans[0].style, AnsiStyle { foreground: Some(PALETTE[1]), background: None, bold: true });
+        assert_eq!(spans[0].style.text_color(Color::rgb(1, 1, 1)), PALETTE[9], "Bold is bright");
+        assert_eq!(spans[1].style, AnsiStyle::default());
+        assert_eq!(style.foreground, Some(Color::rgb(255, 0, 0)), "Carried to the next text");
+        parse("\x1b[48;2;1;2;3m\x1b[39m", &mut style);
+        assert_eq!(style, AnsiStyle { foreground: None, background: Some(Color::rgb(1, 2, 3)), bold: false });
+        assert_eq!(color_256(244), Color::rgb(128, 128, 128));
+        assert_eq!(strip("\x1b[32mok\x1b[m"), "ok");
+    }
+}
@@ -0,0 +1,49 @@
+/// Metadata of the application shown by the About dialog, app_info!() fills it from the
+/// application's Cargo.toml
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppInfo {
+    pub name: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    pub license: Option<String>,
+    pub website: Option<String>,
+}
+
+impl AppInfo {
+    pub fn new(name: &str, version: &str) -> Self {
+        AppInfo {
+            name: name.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The lines of text of the About dialog, without the website which is drawn as a link
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{} {}", self.name, self.version)];
+        if !self.authors.is_empty() {
+            lines.push(format!("By {}", self.authors.join(", ")));
+        }
+        if let Some(license) = &self.license {
+            lines.push(format!("License: {}", license));
+        }
+        lines
+    }
+}
+
+/// Builds an AppInfo from the package metadata of the crate where it's used
+#[macro_export]
+macro_rules! app_info {
+    () => {
+        $crate::app_info::AppInfo {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            authors: env!("CARGO_PKG_AUTHORS").split(':')
+                .filter(|a| !a.is_empty())
+                .map(|a| a.to_string())
+                .collect(),
+            license: option_env!("CARGO_PKG_LICENSE").filter(|l| !l.is_empty()).map(|l| l.to_string()),
+            website: option_env!("CARGO_PKG_HOMEPAGE").filter(|w| !w.is_empty()).map(|w| w.to_string()),
+        }
+    };
+}
@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+/// Logical name of the font that is always embedded in the crate
+pub const DEFAULT_FONT: &str = "default-font";
+/// Logical names of the fonts of the styles of text (see elements::FontStyle), none is embedded.
+/// Text in a style without one is drawn in the regular font, bold text thickened
+pub const BOLD_FONT: &str = "bold-font";
+pub const ITALIC_FONT: &str = "italic-font";
+pub const MONOSPACE_FONT: &str = "monospace-font";
+
+/// Where the data of an asset comes from, either bytes embedded in the binary (usually with
+/// include_bytes!) or a file that is read when the asset is resolved
+#[derive(Debug, Clone)]
+pub enum Asset {
+    Embedded(&'static [u8]),
+    Path(PathBuf),
+}
+
+impl Asset {
+    pub fn bytes(&self) -> Result<Cow<'static, [u8]>, String> {
+        match self {
+            Asset::Embedded(bytes) => Ok(Cow::Borrowed(bytes)),
+            Asset::Path(path) => std::fs::read(path)
+                .map(Cow::Owned)
+                .map_err(|e| format!("Failed to read asset {}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// Fonts, icons and themes registered by logical name, so widgets and the engine don't depend on
+/// files being present in the working directory
+static REGISTRY: Lazy<Mutex<HashMap<String, Asset>>> = Lazy::new(|| {
+    let mut registry = HashMap::new();
+    registry.insert(DEFAULT_FONT.to_string(),
+                    Asset::Embedded(include_bytes!("../Nouveau_IBM.ttf")));
+    Mutex::new(registry)
+});
+
+/// Registers (or replaces) an asset embedded in the binary, e.g.
+/// register_bytes("logo", include_bytes!("../assets/logo.png"))
+pub fn register_bytes(name: &str, bytes: &'static [u8]) {
+    register(name, Asset::Embedded(bytes));
+}
+
+/// Registers (or replaces) an asset that lives in the file system, it's read every time it's
+/// resolved
+pub fn register_path<P: Into<PathBuf>>(name: &str, path: P) {
+    register(name, Asset::Path(path.into()));
+}
+
+pub fn register(name: &str, asset: Asset) {
+    REGISTRY.lock().expect("Assets registry is poisoned")
+        .insert(name.to_string(), asset);
+}
+
+pub fn resolve(name: &str) -> Option<Asset> {
+    REGISTRY.lock().expect("Assets registry is poisoned")
+        .get(name).cloned()
+}
+
+/// Returns the data of the asset registered with the given name
+pub fn load(name: &str) -> Result<Cow<'static, [u8]>, String> {
+    resolve(name)
+        .ok_or(format!("No asset registered as {}", name))?
+        .bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_font_is_embedded() {
+        assert!(matches!(resolve(DEFAULT_FONT), Some(Asset::Embedded(_))));
+        assert!(!load(DEFAULT_FONT).unwrap().is_empty());
+    }
+
+    #[test]
+    fn registered_bytes_are_resolved_by_name() {
+        register_bytes("test-icon", &[1, 2, 3]);
+        assert_eq!(load("test-icon").unwrap().as_ref(), &[1, 2, 3]);
+        assert!(load("missing").is_err());
+    }
+}
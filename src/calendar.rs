@@ -0,0 +1,167 @@
+//! Dates of the Gregorian calendar for the DatePicker, without times or time zones. Weeks start on
+//! Monday and today is the date in UTC
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub const MONTH_NAMES: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July",
+                                     "August", "September", "October", "November", "December"];
+pub const WEEKDAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// How many days a month grid shows, six weeks fit every month
+pub const GRID_DAYS: usize = 42;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    /// From 1 to 12
+    pub month: u32,
+    /// From 1 to the days in the month
+    pub day: u32,
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Date {
+    /// None when there is no such day
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        match (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            true => Some(Date { year, month, day }),
+            false => None,
+        }
+    }
+
+    pub fn today() -> Date {
+        Date::from_days(seconds_since_epoch() as i64 / 86_400)
+    }
+
+    /// Days since 1970-01-01, negative before it
+    pub fn days(&self) -> i64 {
+        // Years starting in March put the leap day at their end
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((self.month as i64 + 9) % 12) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The date days after 1970-01-01, see days()
+    pub fn from_days(days: i64) -> Date {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let march_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * march_month + 2) / 5 + 1) as u32;
+        let month = if march_month < 10 { march_month + 3 } else { march_month - 9 } as u32;
+        let year = (year_of_era + era * 400) as i32 + i32::from(month <= 2);
+        Date { year, month, day }
+    }
+
+    /// From 0 for Monday to 6 for Sunday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.days() + 3).rem_euclid(7) as u32
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    /// The same day months later, or the last one of that month when it's shorter
+    pub fn add_months(&self, months: i32) -> Date {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let (year, month) = (index.div_euclid(12), index.rem_euclid(12) as u32 + 1);
+        Date { year, month, day: self.day.min(days_in_month(year, month)) }
+    }
+
+    pub fn first_of_month(&self) -> Date {
+        Date { day: 1, ..*self }
+    }
+
+    /// The days a calendar shows for the month of the date: the weeks it's in, from the Monday on
+    /// or before its first day, always GRID_DAYS of them
+    pub fn month_grid(&self) -> Vec<Date> {
+        let first = self.first_of_month();
+        let start = first.add_days(-(first.weekday() as i64));
+        (0..GRID_DAYS as i64).map(|i| start.add_days(i)).collect()
+    }
+
+    /// E.g. "March 2024"
+    pub fn month_title(&self) -> String {
+        format!("{} {}", MONTH_NAMES[self.month as usize - 1], self.year)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn seconds_since_epoch() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// The clock of the system isn't reachable from wasm32, the one of the page is
+#[cfg(target_arch = "wasm32")]
+fn seconds_since_epoch() -> u64 {
+    let milliseconds = web_sys::window().and_then(|w| w.performance()).map_or(0., |p| p.time_origin() + p.now());
+    (milliseconds / 1000.) as u64
+}
+
+/// ISO 8601, e.g. 2024-03-09
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parses the ISO 8601 dates Display gives
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().splitn(3, '-').collect();
+        let number = |part: &str| part.parse::<u32>().map_err(|_| format!("{} is not a date", s));
+        match parts.as_slice() {
+            [year, month, day] => {
+                let year = year.parse::<i32>().map_err(|_| format!("{} is not a date", s))?;
+                Date::new(year, number(month)?, number(day)?).ok_or(format!("There is no day {}", s))
+            }
+            _ => Err(format!("{} is not a date", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_count_days_across_months_and_leap_years() {
+        let leap_day = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(Date::from_days(leap_day.days()), leap_day);
+        assert_eq!(Date::from_days(0), Date { year: 1970, month: 1, day: 1 });
+        assert_eq!(leap_day.add_days(1), Date { year: 2024, month: 3, day: 1 });
+        assert_eq!(leap_day.add_months(12), Date { year: 2025, month: 2, day: 28 });
+        assert_eq!(Date::new(2024, 1, 31).unwrap().add_months(-2), Date { year: 2023, month: 11, day: 30 });
+        assert_eq!(leap_day.weekday(), 3, "A Thursday");
+        assert!(Date::new(2023, 2, 29).is_none());
+        let grid = Date::new(2024, 9, 15).unwrap().month_grid();
+        assert_eq!((grid[0], grid[6]), (Date { year: 2024, month: 8, day: 26 }, Date { year: 2024, month: 9, day: 1 }));
+        assert_eq!("2024-02-29".parse::<Date>(), Ok(leap_day));
+        assert_eq!(leap_day.to_string(), "2024-02-29");
+        assert!("2024-13-01".parse::<Date>().is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Caret browsing: a caret walked through read-only text with the keyboard, as browsers do with F7.
+//! Positions are in the lines the text was wrapped into, so the caret moves through what is shown
+
+/// A place between two characters, column counts the characters before it in the line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaretMove {
+    Left,
+    Right,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    TextStart,
+    TextEnd,
+}
+
+/// The caret and the selection it extends, the selection goes from anchor to position
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Caret {
+    pub position: TextPosition,
+    /// Where the selection started, None when nothing is selected
+    pub anchor: Option<TextPosition>,
+}
+
+impl Caret {
+    pub fn at(line: usize, column: usize) -> Self {
+        Caret { position: TextPosition { line, column }, anchor: None }
+    }
+
+    /// Moves the caret through lines, with extend (e.g. Shift held) the selection grows from where
+    /// the caret was, otherwise it's dropped. Left and Right go on to the previous and next lines,
+    /// Up and Down keep the column when the line is long enough. Returns whether anything changed
+    pub fn move_by(&mut self, movement: CaretMove, extend: bool, lines: &[String]) -> bool {
+        let old = *self;
+        let length = |line: usize| lines.get(line).map_or(0, |l| l.chars().count());
+        let last_line = lines.len().saturating_sub(1);
+        let TextPosition { line, column } = self.clamped(lines);
+        let position = match movement {
+            CaretMove::Left if column > 0 => TextPosition { line, column: column - 1 },
+            CaretMove::Left if line > 0 => TextPosition { line: line - 1, column: length(line - 1) },
+            CaretMove::Right if column < length(line) => TextPosition { line, column: column + 1 },
+            CaretMove::Right if line < last_line => TextPosition { line: line + 1, column: 0 },
+            CaretMove::Up if line > 0 => TextPosition { line: line - 1, column: column.min(length(line - 1)) },
+            CaretMove::Down if line < last_line => TextPosition { line: line + 1, column: column.min(length(line + 1)) },
+            CaretMove::LineStart => TextPosition { line, column: 0 },
+            CaretMove::LineEnd => TextPosition { line, column: length(line) },
+            CaretMove::TextStart => TextPosition::default(),
+            CaretMove::TextEnd => TextPosition { line: last_line, column: length(last_line) },
+            _ => TextPosition { line, column },
+        };
+        self.anchor = match extend {
+            true => self.anchor.or(Some(TextPosition { line, column })),
+            false => None,
+        };
+        self.position = position;
+        if self.anchor == Some(position) {
+            self.anchor = None;
+        }
+        *self != old
+    }
+
+    /// The position kept inside lines, they may have been wrapped again since it was set
+    pub fn clamped(&self, lines: &[String]) -> TextPosition {
+        let line = self.position.line.min(lines.len().saturating_sub(1));
+        let column = self.position.column.min(lines.get(line).map_or(0, |l| l.chars().count()));
+        TextPosition { line, column }
+    }
+
+    /// The start and end of the selection, None when nothing is selected
+    pub fn selection(&self) -> Option<(TextPosition, TextPosition)> {
+        let anchor = self.anchor?;
+        Some((anchor.min(self.position), anchor.max(self.position)))
+    }
+
+    /// The columns selected in line, if any. The end is past the last character when the
+    /// selection goes on to the next line
+    pub fn selected_columns(&self, line: usize, lines: &[String]) -> Option<(usize, usize)> {
+        let (start, end) = self.selection()?;
+        if line < start.line || line > end.line {
+            return None;
+        }
+        let length = lines.get(line).map_or(0, |l| l.chars().count());
+        let from = if line == start.line { start.column.min(length) } else { 0 };
+        let to = if line == end.line { end.column.min(length) } else { length };
+        Some((from, to))
+    }
+
+    /// The selected text with a line break between lines, None when nothing is selected
+    pub fn selected_text(&self, lines: &[String]) -> Option<String> {
+        let (start, end) = self.selection()?;
+        let selected: Vec<String> = (start.line..=end.line.min(lines.len().saturating_sub(1)))
+            .filter_map(|line| {
+                let (from, to) = self.selected_columns(line, lines)?;
+                Some(lines[line].chars().skip(from).take(to - from).collect())
+            })
+            .collect();
+        Some(selected.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_moves_select_across_lines() {
+        let lines = vec!["the quick".to_string(), "brown fox".to_string()];
+        let mut caret = Caret::at(0, 4);
+        assert!(caret.move_by(CaretMove::LineEnd, true, &lines));
+        assert!(caret.move_by(CaretMove::Right, true, &lines));
+        assert!(caret.move_by(CaretMove::Right, true, &lines));
+        assert_eq!(caret.position, TextPosition { line: 1, column: 1 });
+        assert_eq!(caret.selected_text(&lines).as_deref(), Some("quick\nb"));
+        assert!(caret.move_by(CaretMove::Up, false, &lines));
+        assert_eq!(caret, Caret::at(0, 1));
+        assert!(!caret.move_by(CaretMove::Up, false, &lines), "Already on the first line");
+        assert!(caret.move_by(CaretMove::TextEnd, false, &lines));
+        assert_eq!(caret.position, TextPosition { line: 1, column: 9 });
+    }
+}
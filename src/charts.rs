@@ -0,0 +1,382 @@
+//! Charts for dashboards: LineChart and BarChart draw series of values over categories with a value
+//! axis, PieChart the share of each value of a total. The charts lay themselves out here as
+//! ChartShapes, the engines only draw them, so both engines show the same chart:
+//!
+//! ```ignore
+//! BarChart {
+//!     categories: vec!["Q1".to_string(), "Q2".to_string()],
+//!     series: vec![Series::new("Sales", vec![120., 180.]), Series::new("Costs", vec![90., 100.])],
+//!     ..Default::default()
+//! }
+//! ```
+
+use crate::accessibility_tree::Accessible;
+use crate::elements::Dimension::Relative;
+use crate::elements::{Color, Dimension};
+
+/// The colors of the series (or of the slices of a pie) in order, starting over after the last
+pub const PALETTE: [Color; 6] = [Color::rgb(70, 130, 200), Color::rgb(230, 140, 50), Color::rgb(90, 180, 100),
+                                 Color::rgb(200, 80, 90), Color::rgb(150, 110, 200), Color::rgb(200, 190, 80)];
+
+/// The size of the labels relative to the text of the theme
+pub const LABEL_SCALE: f32 = 0.75;
+
+/// The dashes of the grid lines, see StrokeStyle::dashes
+pub const GRID_DASHES: [f32; 2] = [3., 3.];
+
+/// Space between the labels and what they label, and between the entries of a legend
+const GAP: f32 = 6.;
+/// About how many steps the value axis is split in
+const TICKS: f32 = 5.;
+/// The fraction of its slot a group of bars takes
+const BAR_GROUP: f32 = 0.8;
+
+pub fn series_color(index: usize) -> Color {
+    PALETTE[index % PALETTE.len()]
+}
+
+/// Named values, the value of each category in order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub values: Vec<f32>,
+}
+
+impl Series {
+    pub fn new(name: &str, values: Vec<f32>) -> Self {
+        Series { name: name.to_string(), values }
+    }
+}
+
+/// What a chart is made of, in pixels from its top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChartShape {
+    /// A line of the axes, drawn in a color of the theme
+    Rule { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// A line across the plot at a value of the axis, dashed by GRID_DASHES in a faint color of
+    /// the theme, behind the series
+    Grid { x1: f32, y1: f32, x2: f32, y2: f32 },
+    Rect { x: f32, y: f32, w: f32, h: f32, color: Color },
+    /// Connected segments, e.g. the values of a series
+    Polyline { points: Vec<(f32, f32)>, color: Color },
+    /// Filled as triangles fanned from its first point, e.g. a slice of a pie from its center
+    Polygon { points: Vec<(f32, f32)>, color: Color },
+    /// Text drawn from its top left corner in the size given to the chart, in a color of the theme
+    Label { text: String, x: f32, y: f32 },
+}
+
+/// The value axis of values from min to max by step, always including 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueAxis {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl ValueAxis {
+    /// Split in steps of 1, 2 or 5 times a power of ten
+    pub fn new(values: impl Iterator<Item = f32>) -> Self {
+        let (low, high) = values.filter(|v| v.is_finite())
+            .fold((0f32, 0f32), |(low, high), v| (low.min(v), high.max(v)));
+        let range = if high > low { high - low } else { 1. };
+        let raw = range / TICKS;
+        let magnitude = 10f32.powf(raw.log10().floor());
+        let step = magnitude * match raw / magnitude {
+            fraction if fraction <= 1. => 1.,
+            fraction if fraction <= 2. => 2.,
+            fraction if fraction <= 5. => 5.,
+            _ => 10.,
+        };
+        let (min, max) = ((low / step).floor() * step, (high / step).ceil() * step);
+        ValueAxis { min, max: if max > min { max } else { min + step }, step }
+    }
+
+    pub fn ticks(&self) -> Vec<f32> {
+        let count = ((self.max - self.min) / self.step).round() as usize;
+        (0..=count).map(|i| self.min + i as f32 * self.step).collect()
+    }
+
+    /// The label of a tick, with as many decimals as the step needs
+    pub fn label(&self, value: f32) -> String {
+        // A little over so a step like 0.1 isn't taken for 0.0999
+        let decimals = (-(self.step.log10() + 0.001).floor()).max(0.) as usize;
+        format!("{:.*}", decimals, value)
+    }
+
+    /// How far up value is from the bottom of an axis of length
+    pub fn position(&self, value: f32, length: f32) -> f32 {
+        (value - self.min) / (self.max - self.min) * length
+    }
+}
+
+/// The rectangle inside the axes of a LineChart or a BarChart
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Plot {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    /// The width of the slot of each category
+    slot: f32,
+}
+
+impl Plot {
+    fn y_of(&self, axis: &ValueAxis, value: f32) -> f32 {
+        self.y + self.h - axis.position(value, self.h)
+    }
+}
+
+/// Lays out a legend of the names in a row from y, returns its height
+fn push_legend(shapes: &mut Vec<ChartShape>, names: &[&str], y: f32, size: f32,
+               measure: &dyn Fn(&str, f32) -> (f32, f32)) -> f32 {
+    let mut x = 0.;
+    for (i, name) in names.iter().enumerate() {
+        shapes.push(ChartShape::Rect { x, y: y + size / 4., w: size / 2., h: size / 2., color: series_color(i) });
+        shapes.push(ChartShape::Label { text: name.to_string(), x: x + size / 2. + GAP, y });
+        x += size / 2. + GAP + measure(name, size).0 + 2. * GAP;
+    }
+    size + GAP
+}
+
+/// Lays out the legend of the series, the value axis with its labels and the labels of the
+/// categories, one every few when they don't fit
+fn push_axes(shapes: &mut Vec<ChartShape>, categories: &[String], series: &[Series], axis: &ValueAxis,
+             (width, height): (f32, f32), size: f32, measure: &dyn Fn(&str, f32) -> (f32, f32)) -> Plot {
+    let names: Vec<&str> = series.iter().map(|s| s.name.as_str()).collect();
+    let top = match names.iter().any(|n| !n.is_empty()) {
+        true => push_legend(shapes, &names, 0., size, measure),
+        false => 0.,
+    };
+    let labels: Vec<(f32, String)> = axis.ticks().into_iter().map(|t| (t, axis.label(t))).collect();
+    let label_width = labels.iter().map(|(_, l)| measure(l, size).0).fold(0., f32::max);
+    let count = categories.len().max(series.iter().map(|s| s.values.len()).max().unwrap_or(0)).max(1);
+    let (x, y) = (label_width + GAP, top + size / 2.);
+    let (w, h) = ((width - x).max(0.), (height - y - size - GAP).max(0.));
+    let plot = Plot { x, y, w, h, slot: w / count as f32 };
+    for (tick, label) in labels {
+        let tick_y = plot.y_of(axis, tick);
+        shapes.push(ChartShape::Label { x: label_width - measure(&label, size).0, y: tick_y - size / 2., text: label });
+        shapes.push(ChartShape::Rule { x1: x - GAP / 2., y1: tick_y, x2: x, y2: tick_y });
+        if tick != 0. {
+            shapes.push(ChartShape::Grid { x1: x, y1: tick_y, x2: x + w, y2: tick_y });
+        }
+    }
+    shapes.push(ChartShape::Rule { x1: x, y1: y, x2: x, y2: y + h });
+    let zero = plot.y_of(axis, 0.);
+    shapes.push(ChartShape::Rule { x1: x, y1: zero, x2: x + w, y2: zero });
+    let widest = categories.iter().map(|c| measure(c, size).0).fold(0., f32::max);
+    let every = ((widest + GAP) / plot.slot.max(1.)).ceil().max(1.) as usize;
+    for (i, category) in categories.iter().enumerate().step_by(every) {
+        let center = x + (i as f32 + 0.5) * plot.slot;
+        shapes.push(ChartShape::Label { text: category.clone(), x: center - measure(category, size).0 / 2.,
+                                        y: y + h + GAP });
+    }
+    plot
+}
+
+/// Each series as a line through its values, over the categories
+#[derive(Debug, Clone)]
+pub struct LineChart {
+    pub width: Dimension,
+    pub height: Dimension,
+    pub categories: Vec<String>,
+    pub series: Vec<Series>,
+    pub accessible: Accessible,
+}
+
+impl Default for LineChart {
+    fn default() -> Self {
+        LineChart {
+            width: Relative(-1),
+            height: Dimension::Pixels(200),
+            categories: vec![],
+            series: vec![],
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl LineChart {
+    /// The chart laid out in width by height with labels of size
+    pub fn shapes(&self, width: f32, height: f32, size: f32, measure: &dyn Fn(&str, f32) -> (f32, f32))
+                  -> Vec<ChartShape> {
+        let axis = ValueAxis::new(self.series.iter().flat_map(|s| s.values.iter().copied()));
+        let mut shapes = vec![];
+        let plot = push_axes(&mut shapes, &self.categories, &self.series, &axis, (width, height), size, measure);
+        for (i, series) in self.series.iter().enumerate() {
+            let points = series.values.iter().enumerate()
+                .map(|(j, value)| (plot.x + (j as f32 + 0.5) * plot.slot, plot.y_of(&axis, *value)))
+                .collect();
+            shapes.push(ChartShape::Polyline { points, color: series_color(i) });
+        }
+        shapes
+    }
+
+    /// What a screen reader says about it, see summary()
+    pub fn summary(&self) -> String {
+        summary(&self.categories, &self.series)
+    }
+}
+
+/// The values of each category as bars side by side, a bar per series
+#[derive(Debug, Clone)]
+pub struct BarChart {
+    pub width: Dimension,
+    pub height: Dimension,
+    pub categories: Vec<String>,
+    pub series: Vec<Series>,
+    pub accessible: Accessible,
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        BarChart {
+            width: Relative(-1),
+            height: Dimension::Pixels(200),
+            categories: vec![],
+            series: vec![],
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl BarChart {
+    /// The chart laid out in width by height with labels of size, bars go down from 0 for
+    /// negative values
+    pub fn shapes(&self, width: f32, height: f32, size: f32, measure: &dyn Fn(&str, f32) -> (f32, f32))
+                  -> Vec<ChartShape> {
+        let axis = ValueAxis::new(self.series.iter().flat_map(|s| s.values.iter().copied()));
+        let mut shapes = vec![];
+        let plot = push_axes(&mut shapes, &self.categories, &self.series, &axis, (width, height), size, measure);
+        let bar_width = plot.slot * BAR_GROUP / self.series.len().max(1) as f32;
+        let zero = plot.y_of(&axis, 0.);
+        for (i, series) in self.series.iter().enumerate() {
+            for (j, value) in series.values.iter().enumerate() {
+                let x = plot.x + (j as f32 + (1. - BAR_GROUP) / 2.) * plot.slot + i as f32 * bar_width;
+                let top = plot.y_of(&axis, *value);
+                shapes.push(ChartShape::Rect { x, y: top.min(zero), w: bar_width, h: (top - zero).abs(),
+                                               color: series_color(i) });
+            }
+        }
+        shapes
+    }
+
+    /// What a screen reader says about it, see summary()
+    pub fn summary(&self) -> String {
+        summary(&self.categories, &self.series)
+    }
+}
+
+/// The values of each series by category, e.g. "Sales: Q1 120, Q2 180; Costs: Q1 90, Q2 100"
+fn summary(categories: &[String], series: &[Series]) -> String {
+    series.iter().map(|s| {
+        let values = s.values.iter().enumerate()
+            .map(|(i, v)| format!("{} {}", categories.get(i).map(String::as_str).unwrap_or("?"), v))
+            .collect::<Vec<String>>().join(", ");
+        format!("{}: {}", s.name, values)
+    }).collect::<Vec<String>>().join("; ")
+}
+
+/// Each value as a slice of a pie by its share of the total, with a legend of the labels
+#[derive(Debug, Clone)]
+pub struct PieChart {
+    pub width: Dimension,
+    pub height: Dimension,
+    pub labels: Vec<String>,
+    /// The negative ones are left out
+    pub values: Vec<f32>,
+    pub accessible: Accessible,
+}
+
+impl Default for PieChart {
+    fn default() -> Self {
+        PieChart {
+            width: Relative(-1),
+            height: Dimension::Pixels(200),
+            labels: vec![],
+            values: vec![],
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl PieChart {
+    /// The share of the total of each value, from 0 to 1
+    pub fn shares(&self) -> Vec<f32> {
+        let total: f32 = self.values.iter().filter(|v| **v > 0.).sum();
+        self.values.iter().map(|v| if total > 0. { v.max(0.) / total } else { 0. }).collect()
+    }
+
+    /// The pie laid out in width by height with labels of size, the slices go clockwise from the
+    /// top and the legend is on the right
+    pub fn shapes(&self, width: f32, height: f32, size: f32, measure: &dyn Fn(&str, f32) -> (f32, f32))
+                  -> Vec<ChartShape> {
+        let shares = self.shares();
+        let entries: Vec<String> = shares.iter().enumerate()
+            .map(|(i, share)| format!("{} {:.0}%", self.labels.get(i).map(String::as_str).unwrap_or(""), share * 100.))
+            .collect();
+        let legend_width = entries.iter().map(|e| measure(e, size).0).fold(0., f32::max) + size / 2. + 2. * GAP;
+        let radius = ((width - legend_width).min(height) / 2.).max(0.);
+        let (cx, cy) = (radius, height / 2.);
+        let mut shapes = vec![];
+        let mut start = 0.;
+        for (i, share) in shares.iter().enumerate().filter(|(_, share)| **share > 0.) {
+            let end = start + share * std::f32::consts::TAU;
+            // A point every few degrees so the arc looks round
+            let steps = ((end - start) / 0.1).ceil().max(1.) as usize;
+            let mut points = vec![(cx, cy)];
+            points.extend((0..=steps).map(|s| {
+                let angle = start + (end - start) * s as f32 / steps as f32;
+                (cx + radius * angle.sin(), cy - radius * angle.cos())
+            }));
+            shapes.push(ChartShape::Polygon { points, color: series_color(i) });
+            start = end;
+        }
+        let x = 2. * radius + 2. * GAP;
+        let mut y = (height - entries.len() as f32 * (size + GAP)) / 2.;
+        for (i, entry) in entries.into_iter().enumerate() {
+            shapes.push(ChartShape::Rect { x, y: y + size / 4., w: size / 2., h: size / 2., color: series_color(i) });
+            shapes.push(ChartShape::Label { text: entry, x: x + size / 2. + GAP, y });
+            y += size + GAP;
+        }
+        shapes
+    }
+
+    /// What a screen reader says about it, e.g. "Rent 50%, Food 30%, Other 20%"
+    pub fn summary(&self) -> String {
+        self.shares().iter().enumerate()
+            .map(|(i, share)| format!("{} {:.0}%", self.labels.get(i).map(String::as_str).unwrap_or(""), share * 100.))
+            .collect::<Vec<String>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charts_get_nice_axes_and_share_their_slots() {
+        let axis = ValueAxis::new([3., 47., -2.].into_iter());
+        assert_eq!((axis.min, axis.max, axis.step), (-10., 50., 10.));
+        assert_eq!(axis.ticks().len(), 7);
+        assert_eq!(ValueAxis::new([0.3].into_iter()).label(0.1), "0.1");
+        assert_eq!(ValueAxis::new(std::iter::empty()).max, ValueAxis::new(std::iter::empty()).step);
+        let measure = |text: &str, size: f32| (text.len() as f32 * size / 2., size);
+        let chart = BarChart {
+            categories: vec!["Q1".to_string(), "Q2".to_string()],
+            series: vec![Series::new("", vec![10., -5.]), Series::new("", vec![20., 0.])],
+            ..Default::default()
+        };
+        let bars: Vec<(f32, f32, f32)> = chart.shapes(200., 100., 10., &measure).into_iter().filter_map(|s| match s {
+            ChartShape::Rect { x, w, h, .. } => Some((x, w, h)),
+            _ => None,
+        }).collect();
+        assert_eq!(bars.len(), 4);
+        assert_eq!(bars[0].1, bars[2].1);
+        assert_eq!(bars[0].0 + bars[0].1, bars[2].0, "Side by side");
+        assert!(bars[1].2 > 0. && bars[3].2 == 0.);
+        let pie = PieChart { labels: vec!["Rent".to_string(), "Food".to_string()], values: vec![3., 1.],
+                             ..Default::default() };
+        assert_eq!(pie.summary(), "Rent 75%, Food 25%");
+    }
+}
@@ -0,0 +1,115 @@
+/// Horizontal layout of the columns of a Table. The first frozen columns stay pinned to the left
+/// while the others scroll, and only the columns intersecting the viewport are returned so wide
+/// tables only build the cells that can be seen
+#[derive(Debug, Clone, Default)]
+pub struct ColumnLayout {
+    widths: Vec<f32>,
+    /// Where each column starts, widths are accumulated so lookups are binary searches
+    starts: Vec<f32>,
+    pub frozen: usize,
+}
+
+impl ColumnLayout {
+    pub fn new(widths: Vec<f32>, frozen: usize) -> Self {
+        let mut layout = ColumnLayout {
+            widths: vec![],
+            starts: vec![],
+            frozen,
+        };
+        layout.set_widths(widths);
+        layout
+    }
+
+    pub fn widths(&self) -> &[f32] {
+        &self.widths
+    }
+
+    pub fn set_widths(&mut self, widths: Vec<f32>) {
+        let mut start = 0.;
+        self.starts = widths.iter().map(|w| {
+            let column_start = start;
+            start += w;
+            column_start
+        }).collect();
+        self.widths = widths;
+    }
+
+    /// Changes the width of one column, e.g. while dragging its header border
+    pub fn resize(&mut self, column: usize, width: f32) {
+        let mut widths = std::mem::take(&mut self.widths);
+        if let Some(w) = widths.get_mut(column) {
+            *w = width.max(0.);
+        }
+        self.set_widths(widths);
+    }
+
+    /// Width of the columns that don't scroll
+    pub fn frozen_width(&self) -> f32 {
+        self.widths.iter().take(self.frozen).sum()
+    }
+
+    pub fn total_width(&self) -> f32 {
+        self.widths.iter().sum()
+    }
+
+    /// How far the scrollable columns can be scrolled in a viewport of the given width
+    pub fn max_scroll(&self, viewport_width: f32) -> f32 {
+        (self.total_width() - viewport_width).max(0.)
+    }
+
+    /// Where the column is drawn in the viewport for the horizontal scroll offset
+    pub fn column_x(&self, column: usize, scroll_x: f32) -> Option<f32> {
+        let start = *self.starts.get(column)?;
+        Some(if column < self.frozen { start } else { start - scroll_x })
+    }
+
+    /// The columns that can be seen as (column, viewport x), frozen ones first. Scrollable
+    /// columns hidden behind the frozen ones are left out
+    pub fn visible_columns(&self, scroll_x: f32, viewport_width: f32) -> Vec<(usize, f32)> {
+        let frozen = self.frozen.min(self.widths.len());
+        let frozen_width = self.frozen_width();
+        let mut visible: Vec<(usize, f32)> = (0..frozen)
+            .filter(|c| self.starts[*c] < viewport_width)
+            .map(|c| (c, self.starts[c]))
+            .collect();
+        // The first scrollable column that ends after the frozen ones
+        let left = frozen_width + scroll_x;
+        let first = frozen + self.starts[frozen..].partition_point(|s| *s <= left).saturating_sub(1);
+        for column in first..self.widths.len() {
+            let x = self.starts[column] - scroll_x;
+            if x >= viewport_width {
+                break;
+            }
+            if x + self.widths[column] > frozen_width {
+                visible.push((column, x));
+            }
+        }
+        visible
+    }
+
+    /// The column under viewport x, the frozen ones cover the ones scrolled under them
+    pub fn column_at(&self, x: f32, scroll_x: f32) -> Option<usize> {
+        if x < 0. {
+            return None;
+        }
+        let content_x = if x < self.frozen_width() { x } else { x + scroll_x };
+        let column = self.starts.partition_point(|s| *s <= content_x).checked_sub(1)?;
+        (content_x < self.starts[column] + self.widths[column]).then_some(column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frozen_columns_stay_while_the_rest_scroll() {
+        let layout = ColumnLayout::new(vec![50.; 200], 1);
+        assert_eq!(layout.visible_columns(0., 120.), vec![(0, 0.), (1, 50.), (2, 100.)]);
+        // Column 1 is under the frozen one, column 2 is partially visible
+        assert_eq!(layout.visible_columns(60., 120.), vec![(0, 0.), (2, 40.), (3, 90.)]);
+        assert_eq!(layout.column_at(10., 60.), Some(0));
+        assert_eq!(layout.column_at(60., 60.), Some(2));
+        assert_eq!(layout.column_at(10_001., 0.), None);
+    }
+}
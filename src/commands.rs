@@ -0,0 +1,154 @@
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::elements::Event;
+use crate::fuzzy::{self, FuzzyMatch};
+
+/// An action of the application that can be found and run from the CommandPalette (and later
+/// from menus and shortcuts), id is what identifies it when executing it
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub id: String,
+    pub title: String,
+    /// Shortcut hint shown next to the title, e.g. "Ctrl+S"
+    pub shortcut: Option<String>,
+    pub action: fn(Event) -> bool,
+}
+
+impl Command {
+    pub fn new(id: &str, title: &str, action: fn(Event) -> bool) -> Self {
+        Command {
+            id: id.to_string(),
+            title: title.to_string(),
+            shortcut: None,
+            action,
+        }
+    }
+
+    pub fn with_shortcut(mut self, shortcut: &str) -> Self {
+        self.shortcut = Some(shortcut.to_string());
+        self
+    }
+}
+
+static COMMANDS: Lazy<Mutex<Vec<Command>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// Registers (or replaces the one with the same id) a command
+pub fn register(command: Command) {
+    let mut commands = COMMANDS.lock().expect("Commands registry is poisoned");
+    match commands.iter_mut().find(|c| c.id == command.id) {
+        Some(old) => *old = command,
+        None => commands.push(command),
+    }
+}
+
+pub fn unregister(id: &str) {
+    COMMANDS.lock().expect("Commands registry is poisoned").retain(|c| c.id != id);
+}
+
+/// Every registered command in registration order
+pub fn commands() -> Vec<Command> {
+    COMMANDS.lock().expect("Commands registry is poisoned").clone()
+}
+
+/// The command whose shortcut is the given one, e.g. "Ctrl+Shift+S"
+pub fn find_by_shortcut(shortcut: &str) -> Option<Command> {
+    COMMANDS.lock().expect("Commands registry is poisoned")
+        .iter()
+        .find(|c| c.shortcut.as_deref() == Some(shortcut))
+        .cloned()
+}
+
+/// Runs the action of the command, returns what the action returned
+pub fn execute(id: &str) -> Result<bool, String> {
+    execute_with(id, |command| Event {
+        source: command.title.clone(),
+        ..Default::default()
+    })
+}
+
+/// Like execute() but the action gets the event made by make_event, e.g. with a deep link as source
+pub fn execute_with<F: FnOnce(&Command) -> Event>(id: &str, make_event: F) -> Result<bool, String> {
+    let command = commands().into_iter()
+        .find(|c| c.id == id)
+        .ok_or(format!("No command registered as {}", id))?;
+    Ok((command.action)(make_event(&command)))
+}
+
+/// State of the command palette overlay (Ctrl+Shift+P): the typed query, the registered commands
+/// fuzzy matching it (best first) and which one is selected
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+    matches: Vec<Command>,
+    match_ranges: Vec<FuzzyMatch>,
+}
+
+impl CommandPalette {
+    /// Opens the palette listing every registered command
+    pub fn open() -> Self {
+        let mut palette = CommandPalette::default();
+        palette.set_query("");
+        palette
+    }
+
+    pub fn matches(&self) -> &[Command] {
+        &self.matches
+    }
+
+    /// How the title of matches()[index] matched, to highlight the matched characters
+    pub fn match_of(&self, index: usize) -> Option<&FuzzyMatch> {
+        self.match_ranges.get(index)
+    }
+
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        let commands = commands();
+        let ranked = fuzzy::rank(query, commands.iter().map(|c| c.title.as_str()));
+        self.matches = ranked.iter().map(|(i, _)| commands[*i].clone()).collect();
+        self.match_ranges = ranked.into_iter().map(|(_, m)| m).collect();
+        self.selected = 0;
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        let query = format!("{}{}", self.query, text);
+        self.set_query(&query);
+    }
+
+    pub fn pop_char(&mut self) {
+        let mut query = self.query.clone();
+        query.pop();
+        self.set_query(&query);
+    }
+
+    /// Moves the selection by delta rows, wrapping around
+    pub fn move_selection(&mut self, delta: isize) {
+        if !self.matches.is_empty() {
+            let len = self.matches.len() as isize;
+            self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+        }
+    }
+
+    pub fn selected_command(&self) -> Option<&Command> {
+        self.matches.get(self.selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_lists_matching_commands() {
+        register(Command::new("test.save", "Save File", |_event| true).with_shortcut("Ctrl+S"));
+        register(Command::new("test.open", "Open Folder", |_event| false));
+        let mut palette = CommandPalette::open();
+        palette.push_str("sf");
+        assert!(palette.matches().iter().any(|c| c.id == "test.save"));
+        assert!(!palette.matches().iter().any(|c| c.id == "test.open"));
+        assert_eq!(execute("test.open"), Ok(false));
+        assert!(execute("test.missing").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Values an ancestor publishes to the components built inside of it, so they don't have to be
+//! passed down through every constructor: theme overrides for a part of the window, the locale, a
+//! data source... A Provider publishes its values while its content is built and the components
+//! read the innermost value of a type from their build:
+//!
+//! ```ignore
+//! Provider { values: ContextValues::new().with(TextColor(Color::rgb(255, 200, 80))), content };
+//! let color = context::get::<TextColor>();
+//! ```
+//!
+//! The values are only there during the build, not when handling events
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+thread_local! {
+    /// The values of the Providers being built, innermost last
+    static PROVIDED: RefCell<Vec<ContextValues>> = const { RefCell::new(vec![]) };
+}
+
+/// Values published together, one of each type
+#[derive(Clone, Default)]
+pub struct ContextValues {
+    values: Vec<(TypeId, &'static str, Arc<dyn Any + Send + Sync>)>,
+}
+
+impl ContextValues {
+    pub fn new() -> Self {
+        ContextValues::default()
+    }
+
+    /// Adds the value, replacing the one of the same type
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.values.retain(|(type_id, _, _)| *type_id != TypeId::of::<T>());
+        self.values.push((TypeId::of::<T>(), std::any::type_name::<T>(), Arc::new(value)));
+        self
+    }
+
+    pub fn get<T: Any + Send + Sync + Clone>(&self) -> Option<T> {
+        self.values.iter().find_map(|(_, _, value)| value.downcast_ref::<T>()).cloned()
+    }
+}
+
+/// The names of the types of the values
+impl Debug for ContextValues {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.values.iter().map(|(_, name, _)| name)).finish()
+    }
+}
+
+/// Runs build with the values published, the engines call it to build the content of a Provider
+pub fn provide<R>(values: &ContextValues, build: impl FnOnce() -> R) -> R {
+    PROVIDED.with(|provided| provided.borrow_mut().push(values.clone()));
+    let built = build();
+    PROVIDED.with(|provided| provided.borrow_mut().pop());
+    built
+}
+
+/// The value of type T of the innermost Provider being built that has one
+pub fn get<T: Any + Send + Sync + Clone>() -> Option<T> {
+    PROVIDED.with(|provided| provided.borrow().iter().rev().find_map(|values| values.get::<T>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_innermost_value_of_a_type_is_read() {
+        let outer = ContextValues::new().with("en".to_string()).with(1u32);
+        let inner = ContextValues::new().with(2u32);
+        let read = provide(&outer, || provide(&inner, || (get::<u32>(), get::<String>())));
+        assert_eq!(read, (Some(2), Some("en".to_string())));
+        assert_eq!(get::<u32>(), None, "Only while building");
+        assert_eq!(format!("{:?}", inner.with(3u32)), "[\"u32\"]");
+    }
+}
@@ -0,0 +1,116 @@
+//! WCAG contrast checks of text and background colors, the engines check the pairs they draw when
+//! a window is created and warn about the ones that are hard to read
+
+/// Least contrast for normal text to pass WCAG AA
+pub const AA_NORMAL_TEXT: f32 = 4.5;
+/// Least contrast for large text (18pt, or 14pt bold) to pass WCAG AA
+pub const AA_LARGE_TEXT: f32 = 3.;
+/// Least contrast for normal text to pass WCAG AAA
+pub const AAA_NORMAL_TEXT: f32 = 7.;
+
+pub type Rgb = (u8, u8, u8);
+
+/// Text drawn with foreground on background, named after where it's drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorPair {
+    pub name: String,
+    pub foreground: Rgb,
+    pub background: Rgb,
+}
+
+impl ColorPair {
+    pub fn new(name: &str, foreground: Rgb, background: Rgb) -> Self {
+        ColorPair { name: name.to_string(), foreground, background }
+    }
+}
+
+/// A pair below the wanted contrast and the closest foreground that reaches it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    pub pair: ColorPair,
+    pub ratio: f32,
+    pub suggested: Rgb,
+}
+
+impl std::fmt::Display for ContrastIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (r, g, b) = self.suggested;
+        write!(f, "{} has a contrast of {:.2}:1, rgb({}, {}, {}) would be readable",
+               self.pair.name, self.ratio, r, g, b)
+    }
+}
+
+/// The relative luminance of WCAG, from 0 for black to 1 for white
+pub fn relative_luminance((r, g, b): Rgb) -> f32 {
+    let linear = |channel: u8| {
+        let c = channel as f32 / 255.;
+        match c <= 0.03928 {
+            true => c / 12.92,
+            false => ((c + 0.055) / 1.055).powf(2.4),
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// From 1 (the same luminance) to 21 (black and white), the order of the colors doesn't matter
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// foreground moved the least toward black or white (whichever contrasts more with background)
+/// to reach min_ratio, or as far as it goes when it can't
+pub fn adjust(foreground: Rgb, background: Rgb, min_ratio: f32) -> Rgb {
+    if contrast_ratio(foreground, background) >= min_ratio {
+        return foreground;
+    }
+    let target = match contrast_ratio((0, 0, 0), background) > contrast_ratio((255, 255, 255), background) {
+        true => 0.,
+        false => 255.,
+    };
+    let mix = |amount: f32| {
+        let channel = |c: u8| (c as f32 + (target - c as f32) * amount).round() as u8;
+        (channel(foreground.0), channel(foreground.1), channel(foreground.2))
+    };
+    // The contrast grows with the amount, so the least one reaching it is found by bisection
+    let (mut low, mut high) = (0., 1.);
+    for _ in 0..16 {
+        let middle = (low + high) / 2.;
+        match contrast_ratio(mix(middle), background) >= min_ratio {
+            true => high = middle,
+            false => low = middle,
+        }
+    }
+    mix(high)
+}
+
+/// The pairs with less contrast than min_ratio
+pub fn check(pairs: &[ColorPair], min_ratio: f32) -> Vec<ContrastIssue> {
+    pairs.iter()
+        .map(|pair| (pair, contrast_ratio(pair.foreground, pair.background)))
+        .filter(|(_, ratio)| *ratio < min_ratio)
+        .map(|(pair, ratio)| ContrastIssue {
+            pair: pair.clone(),
+            ratio,
+            suggested: adjust(pair.foreground, pair.background, min_ratio),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_contrast_pairs_get_a_readable_variant() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.).abs() < 0.001);
+        assert_eq!(contrast_ratio((90, 90, 90), (90, 90, 90)), 1.);
+        let pairs = [ColorPair::new("Readable", (230, 230, 230), (30, 30, 35)),
+                     ColorPair::new("Faint", (120, 120, 120), (80, 80, 90))];
+        let issues = check(&pairs, AA_NORMAL_TEXT);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].pair.name, "Faint");
+        assert!(contrast_ratio(issues[0].suggested, (80, 80, 90)) >= AA_NORMAL_TEXT);
+        assert!(issues[0].suggested.0 > 120, "Lightened on a dark background");
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::commands;
+use crate::elements::Event;
+
+/// A parsed custom URL scheme activation, e.g. myapp://open/recent?id=5 has action "open",
+/// path "/recent" and the query id=5
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeepLink {
+    pub scheme: String,
+    pub action: String,
+    pub path: String,
+    /// Percent-decoded name/value pairs in the order of the URL
+    pub query: Vec<(String, String)>,
+}
+
+impl DeepLink {
+    pub fn parse(url: &str) -> Option<DeepLink> {
+        let (scheme, rest) = url.split_once("://")?;
+        if scheme.is_empty() || !scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) {
+            return None;
+        }
+        let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let query = query.split_once('#').map(|(q, _)| q).unwrap_or(query);
+        let (action, path) = match location.find('/') {
+            Some(slash) => location.split_at(slash),
+            None => (location, ""),
+        };
+        let query = query.split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let (name, value) = p.split_once('=').unwrap_or((p, ""));
+                (percent_decode(name), percent_decode(value))
+            })
+            .collect();
+        Some(DeepLink {
+            scheme: scheme.to_ascii_lowercase(),
+            action: percent_decode(action),
+            path: percent_decode(path),
+            query,
+        })
+    }
+
+    /// The first value of the query parameter called name
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes[i] {
+            // from_str_radix() would also take a sign, e.g. %+5
+            b'%' => text.get(i + 1..i + 3)
+                .filter(|h| h.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|h| u8::from_str_radix(h, 16).ok()),
+            _ => None,
+        };
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (None, b'+') => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[derive(Default)]
+struct Routes {
+    scheme: Option<String>,
+    /// Action of the link -> id of the command that handles it
+    commands: HashMap<String, String>,
+}
+
+static ROUTES: Lazy<Mutex<Routes>> = Lazy::new(|| Mutex::new(Routes::default()));
+
+fn routes() -> std::sync::MutexGuard<'static, Routes> {
+    ROUTES.lock().expect("Deep link routes are poisoned")
+}
+
+/// The scheme the app handles, links of other schemes are ignored
+pub fn set_scheme(scheme: &str) {
+    routes().scheme = Some(scheme.to_ascii_lowercase());
+}
+
+/// Links with this action run the command, its action gets the whole URL as the event source so
+/// it can DeepLink::parse() it
+pub fn route(action: &str, command_id: &str) {
+    routes().commands.insert(action.to_string(), command_id.to_string());
+}
+
+/// Runs the command routed to the action of the link, returns what the command returned
+pub fn dispatch(url: &str) -> Result<bool, String> {
+    let link = DeepLink::parse(url).ok_or(format!("{} is not a deep link", url))?;
+    let command_id = {
+        let routes = routes();
+        if routes.scheme.as_ref() != Some(&link.scheme) {
+            return Err(format!("The scheme of {} is not handled", url));
+        }
+        routes.commands.get(&link.action).cloned()
+            .ok_or(format!("No command routed for {}", url))?
+    };
+    commands::execute_with(&command_id, |_command| Event {
+        source: url.to_string(),
+        ..Default::default()
+    })
+}
+
+/// Dispatches the arguments that are links of the app's scheme. The OS launches the app with the
+/// link as an argument, so call it with the startup arguments and give it to
+/// single_instance::acquire() as on_arguments for links opened while the app runs
+pub fn dispatch_arguments(arguments: &[String]) {
+    let prefix = match routes().scheme.as_ref() {
+        Some(scheme) => format!("{}://", scheme),
+        None => return,
+    };
+    for argument in arguments.iter().filter(|a| a.to_ascii_lowercase().starts_with(&prefix)) {
+        if let Err(e) = dispatch(argument) {
+            println!("Failed to open the link {}", e);
+        }
+    }
+}
+
+/// Tells the OS to launch executable for links of scheme. On macOS the scheme is declared with
+/// CFBundleURLTypes in the Info.plist of the bundle instead
+pub fn register_with_os(scheme: &str, app_name: &str, executable: &str) -> Result<(), String> {
+    let run = |program: &str, arguments: &[&str]| -> Result<(), String> {
+        let status = std::process::Command::new(program).args(arguments).status()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("{} failed with {}", program, status)),
+        }
+    };
+    if cfg!(target_os = "windows") {
+        let key = format!("HKCU\\Software\\Classes\\{}", scheme);
+        let command = format!("\"{}\" \"%1\"", executable);
+        run("reg", &["add", &key, "/ve", "/d", &format!("URL:{}", app_name), "/f"])?;
+        run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+        run("reg", &["add", &format!("{}\\shell\\open\\command", key), "/ve", "/d", &command, "/f"])
+    } else if cfg!(target_os = "macos") {
+        Err(format!("Declare {} in CFBundleURLTypes of the Info.plist", scheme))
+    } else {
+        let home = std::env::var("HOME").map_err(|e| format!("No home directory: {}", e))?;
+        let dir = std::path::Path::new(&home).join(".local/share/applications");
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let desktop = format!("{}-{}-handler.desktop", app_name.to_lowercase().replace(' ', "-"), scheme);
+        let entry = format!("[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" %u\n\
+                             NoDisplay=true\nMimeType=x-scheme-handler/{};\n", app_name, executable, scheme);
+        let file = dir.join(&desktop);
+        std::fs::write(&file, entry).map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+        run("xdg-mime", &["default", &desktop, &format!("x-scheme-handler/{}", scheme)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::Command;
+
+    #[test]
+    fn links_are_parsed_and_routed_to_commands() {
+        let link = DeepLink::parse("MyApp://open/recent?id=5&name=a%20b+c#top").unwrap();
+        assert_eq!(link.scheme, "myapp");
+        assert_eq!(link.action, "open");
+        assert_eq!(link.path, "/recent");
+        assert_eq!(link.param("id"), Some("5"));
+        assert_eq!(link.param("name"), Some("a b c"));
+        assert_eq!(DeepLink::parse("not a link"), None);
+        set_scheme("myapp");
+        commands::register(Command::new("test.open_link", "Open Link", |event| {
+            DeepLink::parse(&event.source).and_then(|l| l.param("id").map(|id| id == "5")) == Some(true)
+        }));
+        route("open", "test.open_link");
+        assert_eq!(dispatch("myapp://open?id=5"), Ok(true));
+        assert!(dispatch("otherapp://open?id=5").is_err());
+        assert!(dispatch("myapp://close").is_err());
+    }
+
+    #[test]
+    fn only_hex_digits_are_percent_decoded() {
+        assert_eq!(percent_decode("a%2Fb%41"), "a/bA");
+        assert_eq!(percent_decode("%+5"), "% 5");
+        assert_eq!(percent_decode("%-1%4"), "%-1%4");
+    }
+}
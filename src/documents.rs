@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::commands::{self, Command};
+use crate::elements::{MenuItem, Submenu};
+
+/// The document edited in a window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    /// None until it's saved for the first time
+    pub path: Option<PathBuf>,
+    pub dirty: bool,
+}
+
+impl Document {
+    /// The file name, or "Untitled" when it was never saved
+    pub fn name(&self) -> String {
+        self.path.as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or("Untitled".to_string())
+    }
+}
+
+/// The unsaved content of a document, autosaved by the recovery module
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub window: String,
+    pub path: Option<PathBuf>,
+    pub contents: Vec<u8>,
+}
+
+/// What to do with unsaved changes when closing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CloseAnswer {
+    Save,
+    Discard,
+    Cancel,
+}
+
+/// The application's side of the document flows: writing the content and asking the user.
+/// engines::sdl::confirm_close_dialog() is a ready to use confirm_close()
+pub trait DocumentHandler: Send {
+    /// Writes the document of the window to path
+    fn write(&mut self, window: &str, path: &Path) -> Result<(), String>;
+    /// Asks where to save the document, None cancels the save
+    fn choose_path(&mut self, window: &str, suggested: &str) -> Option<PathBuf>;
+    fn confirm_close(&mut self, window: &str, name: &str) -> CloseAnswer;
+    /// Called when a document was closed, the app usually closes the window
+    fn closed(&mut self, _window: &str) {}
+    /// The current content of the document for autosaving, None when there's nothing to save
+    fn snapshot(&mut self, _window: &str) -> Option<Vec<u8>> {
+        None
+    }
+    /// Loads recovered content into the document of the window
+    fn restore(&mut self, window: &str, _contents: &[u8]) -> Result<(), String> {
+        Err(format!("Window {} can't restore documents", window))
+    }
+}
+
+struct DocumentManager {
+    documents: HashMap<String, Document>,
+    active: Option<String>,
+    handler: Option<Box<dyn DocumentHandler>>,
+    generation: u64,
+}
+
+/// Per-window document state, windows are identified by the title of their Window model
+static MANAGER: Lazy<Mutex<DocumentManager>> = Lazy::new(|| Mutex::new(DocumentManager {
+    documents: HashMap::new(),
+    active: None,
+    handler: None,
+    generation: 0,
+}));
+
+fn manager() -> std::sync::MutexGuard<'static, DocumentManager> {
+    MANAGER.lock().expect("Document manager is poisoned")
+}
+
+/// Changes every time a document is opened, closed, saved or flagged, so windows only update
+/// their titles when needed
+pub fn generation() -> u64 {
+    manager().generation
+}
+
+pub fn set_handler(handler: Box<dyn DocumentHandler>) {
+    manager().handler = Some(handler);
+}
+
+/// The window the Save/Save As/Close commands act on, the engine sets it on focus
+pub fn set_active_window(window: &str) {
+    manager().active = Some(window.to_string());
+}
+
+pub fn active_window() -> Option<String> {
+    manager().active.clone()
+}
+
+/// Starts tracking the document of the window, path is None for new documents
+pub fn open(window: &str, path: Option<PathBuf>) {
+    let mut manager = manager();
+    manager.documents.insert(window.to_string(), Document { path, dirty: false });
+    manager.generation += 1;
+}
+
+pub fn document(window: &str) -> Option<Document> {
+    manager().documents.get(window).cloned()
+}
+
+/// Flags the document of the window as having unsaved changes
+pub fn set_dirty(window: &str, dirty: bool) {
+    let mut manager = manager();
+    if let Some(document) = manager.documents.get_mut(window) {
+        document.dirty = dirty;
+        manager.generation += 1;
+    }
+}
+
+/// The title the OS window should show, e.g. "• notes.txt — Editor", the • marks unsaved changes
+pub fn display_title(window: &str) -> String {
+    match manager().documents.get(window) {
+        Some(document) => format!("{}{} — {}", if document.dirty { "• " } else { "" },
+                                  document.name(), window),
+        None => window.to_string(),
+    }
+}
+
+/// Puts the handler back into the manager when dropped, also when the handler panicked. The panic
+/// hook runs before that and can't take snapshots, so the last autosave is done here
+struct HandlerGuard(Option<Box<dyn DocumentHandler>>);
+
+impl Drop for HandlerGuard {
+    fn drop(&mut self) {
+        {
+            // Unwinding must not panic again because the manager is poisoned
+            let mut manager = MANAGER.lock().unwrap_or_else(|e| e.into_inner());
+            if manager.handler.is_none() {
+                manager.handler = self.0.take();
+            }
+        }
+        if std::thread::panicking() {
+            if let Err(e) = crate::recovery::autosave_now() {
+                println!("Failed to autosave before crashing {}", e);
+            }
+        }
+    }
+}
+
+/// Runs f with the handler taken out of the manager, so it can call back into this module
+fn with_handler<T, F: FnOnce(&mut dyn DocumentHandler) -> T>(f: F) -> Result<T, String> {
+    let handler = manager().handler.take().ok_or("No document handler set".to_string())?;
+    let mut guard = HandlerGuard(Some(handler));
+    Ok(f(guard.0.as_mut().expect("The guard holds the handler").as_mut()))
+}
+
+/// Saves to the document's path, asking for one when it was never saved. Returns false when the
+/// user cancelled
+pub fn save(window: &str) -> Result<bool, String> {
+    let document = document(window).ok_or(format!("Window {} has no document", window))?;
+    match document.path {
+        Some(path) => save_to(window, &path).map(|_| true),
+        None => save_as(window),
+    }
+}
+
+/// Asks for a path and saves there. Returns false when the user cancelled
+pub fn save_as(window: &str) -> Result<bool, String> {
+    let document = document(window).ok_or(format!("Window {} has no document", window))?;
+    match with_handler(|h| h.choose_path(window, &document.name()))? {
+        Some(path) => save_to(window, &path).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+fn save_to(window: &str, path: &Path) -> Result<(), String> {
+    with_handler(|h| h.write(window, path))??;
+    let mut manager = manager();
+    if let Some(document) = manager.documents.get_mut(window) {
+        document.path = Some(path.to_path_buf());
+        document.dirty = false;
+        manager.generation += 1;
+    }
+    Ok(())
+}
+
+/// Closes the document, asking to save the unsaved changes first. Returns false when the user
+/// cancelled, then the document stays open
+pub fn close(window: &str) -> Result<bool, String> {
+    let document = document(window).ok_or(format!("Window {} has no document", window))?;
+    if document.dirty {
+        let proceed = match with_handler(|h| h.confirm_close(window, &document.name()))? {
+            CloseAnswer::Save => save(window)?,
+            CloseAnswer::Discard => true,
+            CloseAnswer::Cancel => false,
+        };
+        if !proceed {
+            return Ok(false);
+        }
+    }
+    {
+        let mut manager = manager();
+        manager.documents.remove(window);
+        manager.generation += 1;
+    }
+    with_handler(|h| h.closed(window))?;
+    Ok(true)
+}
+
+/// Snapshots of the documents with unsaved changes. It never waits for the manager, so it's safe to
+/// call from a panic hook, Err means it was busy
+pub fn dirty_snapshots() -> Result<Vec<Snapshot>, String> {
+    let busy = || "Document manager is busy".to_string();
+    let (dirty, mut handler) = {
+        let mut manager = MANAGER.try_lock().map_err(|_| busy())?;
+        let dirty: Vec<(String, Option<PathBuf>)> = manager.documents.iter()
+            .filter(|(_, d)| d.dirty)
+            .map(|(w, d)| (w.clone(), d.path.clone()))
+            .collect();
+        if dirty.is_empty() {
+            return Ok(vec![]);
+        }
+        (dirty, manager.handler.take().ok_or("No document handler set".to_string())?)
+    };
+    let snapshots = dirty.into_iter()
+        .filter_map(|(window, path)| handler.snapshot(&window)
+            .map(|contents| Snapshot { window, path, contents }))
+        .collect();
+    MANAGER.try_lock().map_err(|_| busy())?.handler = Some(handler);
+    Ok(snapshots)
+}
+
+/// Opens the document of a snapshot again with its content, flagged as unsaved
+pub fn restore(snapshot: &Snapshot) -> Result<(), String> {
+    open(&snapshot.window, snapshot.path.clone());
+    with_handler(|h| h.restore(&snapshot.window, &snapshot.contents))??;
+    set_dirty(&snapshot.window, true);
+    Ok(())
+}
+
+fn on_active(action: fn(&str) -> Result<bool, String>) -> bool {
+    match active_window() {
+        Some(window) => action(&window).unwrap_or_else(|e| {
+            println!("Document action failed {}", e);
+            false
+        }),
+        None => false,
+    }
+}
+
+/// Registers the document.save, document.save_as and document.close commands with their
+/// shortcuts, they act on the active window
+pub fn register_commands() {
+    commands::register(Command::new("document.save", "Save", |_event| on_active(save))
+        .with_shortcut("Ctrl+S"));
+    commands::register(Command::new("document.save_as", "Save As...", |_event| on_active(save_as))
+        .with_shortcut("Ctrl+Shift+S"));
+    commands::register(Command::new("document.close", "Close", |_event| on_active(close))
+        .with_shortcut("Ctrl+W"));
+}
+
+/// &Save, Save &As... and &Close items for the File menu, they run the commands registered by
+/// register_commands()
+pub fn file_menu_items() -> Vec<Submenu> {
+    let item = |title: &str, accelerator: &str, on_action: fn(crate::elements::Event) -> bool| {
+        Submenu::MenuItem(MenuItem {
+            title: title.to_string(),
+            accelerator: Some(accelerator.to_string()),
+            on_action,
+            ..Default::default()
+        })
+    };
+    vec![item("&Save", "Ctrl+S", |_event| commands::execute("document.save").unwrap_or(false)),
+         item("Save &As...", "Ctrl+Shift+S",
+              |_event| commands::execute("document.save_as").unwrap_or(false)),
+         item("&Close", "Ctrl+W", |_event| commands::execute("document.close").unwrap_or(false))]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Answers {
+        answer: CloseAnswer,
+        written: std::sync::Arc<Mutex<Vec<PathBuf>>>,
+    }
+
+    impl DocumentHandler for Answers {
+        fn write(&mut self, _window: &str, path: &Path) -> Result<(), String> {
+            self.written.lock().unwrap().push(path.to_path_buf());
+            Ok(())
+        }
+
+        fn choose_path(&mut self, _window: &str, suggested: &str) -> Option<PathBuf> {
+            Some(PathBuf::from(format!("{}.txt", suggested)))
+        }
+
+        fn confirm_close(&mut self, _window: &str, _name: &str) -> CloseAnswer {
+            self.answer
+        }
+    }
+
+    #[test]
+    fn dirty_documents_are_saved_before_closing() {
+        let written = std::sync::Arc::new(Mutex::new(vec![]));
+        set_handler(Box::new(Answers { answer: CloseAnswer::Cancel, written: written.clone() }));
+        open("Editor", None);
+        set_dirty("Editor", true);
+        assert_eq!(display_title("Editor"), "• Untitled — Editor");
+        assert_eq!(close("Editor"), Ok(false));
+        set_handler(Box::new(Answers { answer: CloseAnswer::Save, written: written.clone() }));
+        assert_eq!(close("Editor"), Ok(true));
+        assert_eq!(*written.lock().unwrap(), vec![PathBuf::from("Untitled.txt")]);
+        assert_eq!(document("Editor"), None);
+    }
+}
@@ -1,16 +1,46 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crate::accessibility;
+use crate::accessibility_tree::Accessible;
+use crate::ansi::{self, AnsiSpan, AnsiStyle};
+use crate::calendar::Date;
+use crate::caret::{Caret, CaretMove};
+use crate::columns::ColumnLayout;
+use crate::context::ContextValues;
 use crate::elements::Dimension::Relative;
+use crate::list_model::ListModel;
+use crate::markdown;
+use crate::overscroll::Overscroll;
+use crate::paging::{PageDelivery, PagedRows};
+use crate::row_heights::RowHeights;
+use crate::sections::{line_of_row, row_at_line, Section, SectionLayout};
+use crate::selection::{SelectModifiers, Selection, SelectionItem};
+use crate::statechart::StateMachine;
+use crate::table_export::{to_delimited, DelimitedOptions, ExportRows};
+use crate::table_model::{SortFilterProxy, SortKey, SortOrder, TableModel, VecTableModel};
+use crate::taskbar::TaskbarProgress;
+use crate::timers::{self, Limited, TimerId};
+use crate::validation::{Trigger, Validator};
+use crate::view_state::{self, ViewState};
 
 pub trait NativeDrawable: mopa::Any + Debug + private::Sealed {}
 mopafy!(NativeDrawable);
 
 mod private {
-    use crate::engines::sdl::SDLBody;
-
     pub trait Sealed {}
 
-    impl Sealed for SDLBody {}
+    #[cfg(not(target_arch = "wasm32"))]
+    impl Sealed for crate::engines::sdl::SDLBody {}
+
+    #[cfg(target_arch = "wasm32")]
+    impl Sealed for crate::engines::web::WebBody {}
 }
 
 // pub trait NativeFonts: mopa::Any {}
@@ -21,6 +51,11 @@ pub trait Component: Debug + mopa::Any {
     fn get_width(&self) -> &Dimension;
     fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable>;
     fn clone_dyn(&self) -> Box<dyn Component>;
+    /// Reacts to a click on the hit area at path (relative to this component), returns whether
+    /// it was consumed, the component is rebuilt when it was
+    fn handle_click(&mut self, _path: &[usize], _event: Event) -> bool {
+        false
+    }
 }
 mopafy!(Component);
 
@@ -31,26 +66,445 @@ impl Clone for Box<dyn Component> {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Submenu {
     Menu(Menu),
     MenuItem(MenuItem),
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MenuItem {
+    /// A & marks the next letter as the mnemonic, e.g. "&Open", use && for a literal &
     pub title: String,
+    pub enabled: bool,
+    /// None when the item can't be checked
+    pub checked: Option<bool>,
+    /// Checkable items sharing a radio group within the same Menu are mutually exclusive
+    pub radio_group: Option<String>,
+    /// Drawn in the gutter when the item is not checked, usually a single glyph of an icon font
+    pub icon: Option<String>,
+    /// Shortcut hint drawn right-aligned, e.g. "Ctrl+O"
+    pub accelerator: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_action"))]
+    pub on_action: fn(Event) -> bool,
+}
+
+#[cfg(feature = "serde")]
+fn default_action() -> fn(Event) -> bool {
+    |_event| true
+}
+
+impl Default for MenuItem {
+    fn default() -> Self {
+        MenuItem {
+            title: "MenuItem".to_string(),
+            enabled: true,
+            checked: None,
+            radio_group: None,
+            icon: None,
+            accelerator: None,
+            on_action: |_event| true,
+        }
+    }
+}
+
+impl Submenu {
+    pub fn title(&self) -> &str {
+        match self {
+            Submenu::Menu(menu) => &menu.title,
+            Submenu::MenuItem(item) => &item.title,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Menu {
     pub title: String,
     pub children: Vec<Submenu>,
+    /// Whether its children are shown
+    pub open: bool,
 }
 
+impl Default for Menu {
+    fn default() -> Self {
+        Menu {
+            title: "Menu".to_string(),
+            children: vec![],
+            open: false,
+        }
+    }
+}
 
-#[derive(Debug, Clone)]
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MainMenu {
     pub menu: Menu,
+    /// Mnemonics are only underlined while Alt is held
+    pub show_mnemonics: bool,
+}
+
+/// Splits a title like "&File" into the text to show ("File") and its mnemonic, as the char index
+/// in the text to show and the lowercase letter
+pub fn parse_mnemonic(title: &str) -> (String, Option<(usize, char)>) {
+    let mut text = String::with_capacity(title.len());
+    let mut mnemonic = None;
+    let mut chars = title.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('&') => text.push('&'),
+            Some(next) => {
+                if mnemonic.is_none() {
+                    mnemonic = Some((text.chars().count(), next.to_ascii_lowercase()));
+                }
+                text.push(next);
+            }
+            None => {}
+        }
+    }
+    (text, mnemonic)
+}
+
+fn mnemonic_of(title: &str) -> Option<char> {
+    parse_mnemonic(title).1.map(|(_, letter)| letter)
+}
+
+/// A single modification of the menu model. Paths are the titles of the entries starting from the
+/// children of MainMenu.menu, e.g. ["Recent files", "notes.txt"]
+#[derive(Debug, Clone)]
+pub enum MenuChange {
+    SetEnabled { path: Vec<String>, enabled: bool },
+    SetChecked { path: Vec<String>, checked: bool },
+    /// Inserts entry into the Menu at parent (an empty path is the root), at the end when index
+    /// is None
+    Insert { parent: Vec<String>, index: Option<usize>, entry: Submenu },
+    Remove { path: Vec<String> },
+    /// Replaces all the entries of the Menu at parent, handy for lists like "Recent files"
+    SetChildren { parent: Vec<String>, children: Vec<Submenu> },
+}
+
+/// A group of MenuChange that are applied together, so the menu is rebuilt only once
+#[derive(Debug, Clone, Default)]
+pub struct MenuBatch {
+    pub changes: Vec<MenuChange>,
+}
+
+fn to_path(path: &[&str]) -> Vec<String> {
+    path.iter().map(|p| p.to_string()).collect()
+}
+
+impl MenuBatch {
+    pub fn new() -> Self {
+        MenuBatch { changes: vec![] }
+    }
+
+    pub fn set_enabled(mut self, path: &[&str], enabled: bool) -> Self {
+        self.changes.push(MenuChange::SetEnabled { path: to_path(path), enabled });
+        self
+    }
+
+    pub fn set_checked(mut self, path: &[&str], checked: bool) -> Self {
+        self.changes.push(MenuChange::SetChecked { path: to_path(path), checked });
+        self
+    }
+
+    pub fn insert(mut self, parent: &[&str], index: Option<usize>, entry: Submenu) -> Self {
+        self.changes.push(MenuChange::Insert { parent: to_path(parent), index, entry });
+        self
+    }
+
+    pub fn remove(mut self, path: &[&str]) -> Self {
+        self.changes.push(MenuChange::Remove { path: to_path(path) });
+        self
+    }
+
+    pub fn set_children(mut self, parent: &[&str], children: Vec<Submenu>) -> Self {
+        self.changes.push(MenuChange::SetChildren { parent: to_path(parent), children });
+        self
+    }
+}
+
+impl MainMenu {
+    /// Alt+letter, opens the menu whose mnemonic is letter. Returns whether it did something
+    pub fn open_by_mnemonic(&mut self, letter: char) -> bool {
+        if mnemonic_of(&self.menu.title) == Some(letter) {
+            self.menu.open = true;
+            return true;
+        }
+        false
+    }
+
+    /// A letter pressed while a menu is open, it opens the submenu or activates the item with
+    /// that mnemonic in the innermost open menu. Returns whether it did something
+    pub fn activate_by_mnemonic(&mut self, letter: char) -> bool {
+        let mut path = match self.menu.innermost_open_path() {
+            Some(path) => path,
+            None => return false,
+        };
+        let menu = match self.menu.menu_at_mut(&path) {
+            Some(menu) => menu,
+            None => return false,
+        };
+        let index = match menu.children.iter().position(|c| mnemonic_of(c.title()) == Some(letter)) {
+            Some(index) => index,
+            None => return false,
+        };
+        if matches!(menu.children[index], Submenu::Menu(_)) {
+            menu.open_child(index);
+            return true;
+        }
+        path.push(index);
+        self.activate(&path)
+    }
+
+    /// Activates the enabled item whose accelerator is shortcut (e.g. "Ctrl+O"), also when its
+    /// menu is closed. Returns whether there was one
+    pub fn activate_by_accelerator(&mut self, shortcut: &str) -> bool {
+        match self.menu.accelerator_path(shortcut) {
+            Some(path) => self.activate(&path),
+            None => false,
+        }
+    }
+
+    /// A click on the entry at path, the indexes from the children of the menu. An empty path is
+    /// the title in the menu bar, which opens or closes the menu. Submenus are opened and items
+    /// activated. Returns whether it did something
+    pub fn click(&mut self, path: &[usize]) -> bool {
+        let (index, parent) = match path.split_last() {
+            Some(split) => split,
+            None => {
+                match self.menu.open {
+                    true => self.menu.close_all(),
+                    false => self.menu.open = true,
+                }
+                return true;
+            }
+        };
+        let menu = match self.menu.menu_at_mut(parent) {
+            Some(menu) => menu,
+            None => return false,
+        };
+        let is_menu = match menu.children.get(*index) {
+            Some(entry) => matches!(entry, Submenu::Menu(_)),
+            None => return false,
+        };
+        if is_menu {
+            menu.open_child(*index);
+            return true;
+        }
+        self.activate(path)
+    }
+
+    /// Activates the item at path if it's enabled: checkable items are toggled (a radio item is
+    /// checked and the rest of its group unchecked), the menu closes and on_action gets the title
+    /// without its mnemonic
+    fn activate(&mut self, path: &[usize]) -> bool {
+        let (index, parent) = match path.split_last() {
+            Some((index, parent)) => (*index, parent),
+            None => return false,
+        };
+        let menu = match self.menu.menu_at_mut(parent) {
+            Some(menu) => menu,
+            None => return false,
+        };
+        let (action, title, checked, radio_group) = match menu.children.get(index) {
+            Some(Submenu::MenuItem(item)) if item.enabled => {
+                (item.on_action, item.title.clone(), item.checked, item.radio_group.clone())
+            }
+            _ => return false,
+        };
+        for (i, child) in menu.children.iter_mut().enumerate() {
+            if let Submenu::MenuItem(item) = child {
+                match (&radio_group, checked) {
+                    (Some(group), Some(_)) if item.radio_group.as_ref() == Some(group) => {
+                        item.checked = Some(i == index);
+                    }
+                    (None, Some(checked)) if i == index => item.checked = Some(!checked),
+                    _ => {}
+                }
+            }
+        }
+        self.menu.close_all();
+        (action)(Event {
+            source: parse_mnemonic(&title).0,
+            ..Default::default()
+        });
+        true
+    }
+
+    /// Applies all the changes of the batch, if any of them fails the menu is left untouched
+    pub fn apply(&mut self, batch: &MenuBatch) -> Result<(), String> {
+        let mut menu = self.menu.clone();
+        for change in batch.changes.iter() {
+            menu.apply(change)?;
+        }
+        self.menu = menu;
+        Ok(())
+    }
+}
+
+impl Menu {
+    /// The deepest open menu, None when this one is closed
+    pub fn innermost_open_mut(&mut self) -> Option<&mut Menu> {
+        if !self.open {
+            return None;
+        }
+        let open_child = self.children.iter().position(|c| matches!(c, Submenu::Menu(m) if m.open));
+        match open_child {
+            Some(index) => match &mut self.children[index] {
+                Submenu::Menu(child) => child.innermost_open_mut(),
+                Submenu::MenuItem(_) => None,
+            },
+            None => Some(self),
+        }
+    }
+
+    /// The indexes of the deepest open menu, None when this one is closed
+    fn innermost_open_path(&self) -> Option<Vec<usize>> {
+        if !self.open {
+            return None;
+        }
+        let open_child = self.children.iter().position(|c| matches!(c, Submenu::Menu(m) if m.open));
+        match open_child {
+            Some(index) => match &self.children[index] {
+                Submenu::Menu(child) => {
+                    let mut path = child.innermost_open_path()?;
+                    path.insert(0, index);
+                    Some(path)
+                }
+                Submenu::MenuItem(_) => None,
+            },
+            None => Some(vec![]),
+        }
+    }
+
+    /// The indexes of the enabled item whose accelerator is shortcut
+    fn accelerator_path(&self, shortcut: &str) -> Option<Vec<usize>> {
+        self.children.iter().enumerate().find_map(|(i, child)| match child {
+            Submenu::MenuItem(item) if item.enabled && item.accelerator.as_ref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(shortcut)) => Some(vec![i]),
+            Submenu::MenuItem(_) => None,
+            Submenu::Menu(menu) => {
+                let mut path = menu.accelerator_path(shortcut)?;
+                path.insert(0, i);
+                Some(path)
+            }
+        })
+    }
+
+    fn menu_at_mut(&mut self, path: &[usize]) -> Option<&mut Menu> {
+        match path.split_first() {
+            None => Some(self),
+            Some((first, rest)) => match self.children.get_mut(*first)? {
+                Submenu::Menu(menu) => menu.menu_at_mut(rest),
+                Submenu::MenuItem(_) => None,
+            },
+        }
+    }
+
+    /// Opens the submenu at index closing the other ones
+    fn open_child(&mut self, index: usize) {
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Submenu::Menu(menu) = child {
+                match i == index {
+                    true => menu.open = true,
+                    false => menu.close_all(),
+                }
+            }
+        }
+    }
+
+    pub fn close_all(&mut self) {
+        self.open = false;
+        for child in self.children.iter_mut() {
+            if let Submenu::Menu(menu) = child {
+                menu.close_all();
+            }
+        }
+    }
+
+    fn entry_mut(&mut self, path: &[String]) -> Option<&mut Submenu> {
+        let (first, rest) = path.split_first()?;
+        let first = parse_mnemonic(first).0;
+        let entry = self.children.iter_mut().find(|c| parse_mnemonic(c.title()).0 == first)?;
+        match (rest.is_empty(), entry) {
+            (true, entry) => Some(entry),
+            (false, Submenu::Menu(menu)) => menu.entry_mut(rest),
+            (false, Submenu::MenuItem(_)) => None,
+        }
+    }
+
+    fn menu_mut(&mut self, path: &[String]) -> Option<&mut Menu> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        match self.entry_mut(path)? {
+            Submenu::Menu(menu) => Some(menu),
+            Submenu::MenuItem(_) => None,
+        }
+    }
+
+    fn item_mut(&mut self, path: &[String]) -> Result<&mut MenuItem, String> {
+        match self.entry_mut(path) {
+            Some(Submenu::MenuItem(item)) => Ok(item),
+            _ => Err(format!("No MenuItem at {:?}", path)),
+        }
+    }
+
+    fn apply(&mut self, change: &MenuChange) -> Result<(), String> {
+        match change {
+            MenuChange::SetEnabled { path, enabled } => {
+                self.item_mut(path)?.enabled = *enabled;
+            }
+            MenuChange::SetChecked { path, checked } => {
+                let item = self.item_mut(path)?;
+                item.checked = Some(*checked);
+                let radio_group = item.radio_group.clone();
+                if let (Some(group), true) = (radio_group, *checked) {
+                    let (title, parent) = path.split_last()
+                        .ok_or("Can't check the root menu".to_string())?;
+                    let menu = self.menu_mut(parent)
+                        .ok_or(format!("No Menu at {:?}", parent))?;
+                    for child in menu.children.iter_mut() {
+                        if let Submenu::MenuItem(other) = child {
+                            if other.radio_group.as_ref() == Some(&group)
+                                && parse_mnemonic(&other.title).0 != parse_mnemonic(title).0 {
+                                other.checked = Some(false);
+                            }
+                        }
+                    }
+                }
+            }
+            MenuChange::Insert { parent, index, entry } => {
+                let menu = self.menu_mut(parent)
+                    .ok_or(format!("No Menu at {:?}", parent))?;
+                let index = index.unwrap_or(menu.children.len()).min(menu.children.len());
+                menu.children.insert(index, entry.clone());
+            }
+            MenuChange::Remove { path } => {
+                let (title, parent) = path.split_last()
+                    .ok_or("Can't remove the root menu".to_string())?;
+                let menu = self.menu_mut(parent)
+                    .ok_or(format!("No Menu at {:?}", parent))?;
+                let title = parse_mnemonic(title).0;
+                let index = menu.children.iter().position(|c| parse_mnemonic(c.title()).0 == title)
+                    .ok_or(format!("No entry at {:?}", path))?;
+                menu.children.remove(index);
+            }
+            MenuChange::SetChildren { parent, children } => {
+                self.menu_mut(parent)
+                    .ok_or(format!("No Menu at {:?}", parent))?
+                    .children = children.clone();
+            }
+        }
+        Ok(())
+    }
 }
 
 // pub struct PopupMenu {
@@ -58,6 +512,7 @@ pub struct MainMenu {
 // }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dimension {
     Relative(i32),
     Percentage(i32),
@@ -81,15 +536,792 @@ impl Default for Container {
     }
 }
 
+impl Container {
+    /// Shows or hides the Skeleton of the Loadable with that source, going down through nested
+    /// containers. Returns whether there was one
+    pub fn set_loading(&mut self, source: &str, loading: bool) -> bool {
+        let mut found = false;
+        for child in self.children.iter_mut() {
+            if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                if loadable.source == source {
+                    loadable.loading = loading;
+                    found = true;
+                } else {
+                    found |= loadable.content.set_loading(source, loading);
+                }
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                found |= container.set_loading(source, loading);
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                found |= scroll_pane.content.set_loading(source, loading);
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                for tab in tab_pane.tabs.iter_mut() {
+                    found |= tab.content.set_loading(source, loading);
+                }
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                for page in carousel.pages.iter_mut() {
+                    found |= page.set_loading(source, loading);
+                }
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                found |= form.content.set_loading(source, loading);
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                found |= deferred.content.set_loading(source, loading);
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                found |= provider.content.set_loading(source, loading);
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                found |= shown_if.content.set_loading(source, loading);
+                found |= shown_if.otherwise.set_loading(source, loading);
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                found |= for_each.content.set_loading(source, loading);
+            }
+        }
+        found
+    }
+
+    /// Writes the text to the Consoles with that source, going down through nested containers like
+    /// set_loading(). Returns whether there was one
+    pub fn write_console(&mut self, source: &str, text: &str) -> bool {
+        let mut found = false;
+        for child in self.children.iter_mut() {
+            if let Some(console) = child.downcast_mut::<Console>() {
+                if console.source == source {
+                    console.write(text);
+                    found = true;
+                }
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                found |= container.write_console(source, text);
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                found |= scroll_pane.content.write_console(source, text);
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                for tab in tab_pane.tabs.iter_mut() {
+                    found |= tab.content.write_console(source, text);
+                }
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                for page in carousel.pages.iter_mut() {
+                    found |= page.write_console(source, text);
+                }
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                found |= loadable.content.write_console(source, text);
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                found |= form.content.write_console(source, text);
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                found |= deferred.content.write_console(source, text);
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                found |= provider.content.write_console(source, text);
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                found |= shown_if.content.write_console(source, text);
+                found |= shown_if.otherwise.write_console(source, text);
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                found |= for_each.content.write_console(source, text);
+            }
+        }
+        found
+    }
+
+    /// Appends the rows of delivery to the ListView or TableView that requested them, going down
+    /// through nested containers like set_loading(). Returns whether there was one
+    pub fn deliver_page(&mut self, delivery: &PageDelivery) -> bool {
+        let mut found = false;
+        for child in self.children.iter_mut() {
+            if let Some(list_view) = child.downcast_mut::<ListView>() {
+                found |= list_view.deliver_page(delivery);
+            } else if let Some(table_view) = child.downcast_mut::<TableView>() {
+                found |= table_view.deliver_page(delivery);
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                found |= container.deliver_page(delivery);
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                found |= scroll_pane.content.deliver_page(delivery);
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                for tab in tab_pane.tabs.iter_mut() {
+                    found |= tab.content.deliver_page(delivery);
+                }
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                for page in carousel.pages.iter_mut() {
+                    found |= page.deliver_page(delivery);
+                }
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                found |= loadable.content.deliver_page(delivery);
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                found |= form.content.deliver_page(delivery);
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                found |= deferred.content.deliver_page(delivery);
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                found |= provider.content.deliver_page(delivery);
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                found |= shown_if.content.deliver_page(delivery);
+                found |= shown_if.otherwise.deliver_page(delivery);
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                found |= for_each.content.deliver_page(delivery);
+            }
+        }
+        found
+    }
+
+    /// The DropZones that are shown, i.e. in the active tab of a TabPane or the active page of a
+    /// Carousel and not in a loading Loadable or a Deferred that isn't built
+    pub fn drop_zones_mut(&mut self) -> Vec<&mut DropZone> {
+        let mut zones = vec![];
+        for child in self.children.iter_mut() {
+            // Checked before borrowing so the borrow lives only as long as the zones it gave
+            if child.is::<DropZone>() {
+                zones.extend(child.downcast_mut::<DropZone>());
+                continue;
+            }
+            let content = if child.is::<Container>() {
+                child.downcast_mut::<Container>()
+            } else if child.is::<ScrollPane>() {
+                child.downcast_mut::<ScrollPane>().map(|s| &mut s.content)
+            } else if child.is::<TabPane>() {
+                child.downcast_mut::<TabPane>().and_then(|t| t.active_content_mut())
+            } else if child.is::<Carousel>() {
+                child.downcast_mut::<Carousel>().and_then(|c| c.active_page_mut())
+            } else if child.is::<Loadable>() {
+                child.downcast_mut::<Loadable>().filter(|l| !l.loading).map(|l| &mut l.content)
+            } else if child.is::<Deferred>() {
+                child.downcast_mut::<Deferred>().filter(|d| d.ready).map(|d| &mut d.content)
+            } else if child.is::<Form>() {
+                child.downcast_mut::<Form>().map(|f| &mut f.content)
+            } else if child.is::<Provider>() {
+                child.downcast_mut::<Provider>().map(|p| &mut p.content)
+            } else if child.is::<If>() {
+                child.downcast_mut::<If>().map(|i| i.active_mut())
+            } else if child.is::<ForEach>() {
+                child.downcast_mut::<ForEach>().map(|f| &mut f.content)
+            } else {
+                None
+            };
+            zones.extend(content.map(|c| c.drop_zones_mut()).unwrap_or_default());
+        }
+        zones
+    }
+
+    /// Lets the Deferred with the highest priority (the first one of them) among those shown build
+    /// its content. Returns whether there was one, the engines call it once per frame
+    pub fn build_next_deferred(&mut self) -> bool {
+        let next = self.pending_deferred().into_iter().min_by_key(|d| std::cmp::Reverse(d.priority));
+        next.map(|deferred| deferred.ready = true).is_some()
+    }
+
+    /// The Deferred that are shown and not built yet, like drop_zones_mut()
+    fn pending_deferred(&mut self) -> Vec<&mut Deferred> {
+        let mut pending = vec![];
+        for child in self.children.iter_mut() {
+            if child.downcast_ref::<Deferred>().is_some_and(|d| !d.ready) {
+                pending.extend(child.downcast_mut::<Deferred>());
+                continue;
+            }
+            let content = if child.is::<Container>() {
+                child.downcast_mut::<Container>()
+            } else if child.is::<ScrollPane>() {
+                child.downcast_mut::<ScrollPane>().map(|s| &mut s.content)
+            } else if child.is::<TabPane>() {
+                child.downcast_mut::<TabPane>().and_then(|t| t.active_content_mut())
+            } else if child.is::<Carousel>() {
+                child.downcast_mut::<Carousel>().and_then(|c| c.active_page_mut())
+            } else if child.is::<Loadable>() {
+                child.downcast_mut::<Loadable>().filter(|l| !l.loading).map(|l| &mut l.content)
+            } else if child.is::<Deferred>() {
+                child.downcast_mut::<Deferred>().map(|d| &mut d.content)
+            } else if child.is::<Form>() {
+                child.downcast_mut::<Form>().map(|f| &mut f.content)
+            } else if child.is::<Provider>() {
+                child.downcast_mut::<Provider>().map(|p| &mut p.content)
+            } else if child.is::<If>() {
+                child.downcast_mut::<If>().map(|i| i.active_mut())
+            } else if child.is::<ForEach>() {
+                child.downcast_mut::<ForEach>().map(|f| &mut f.content)
+            } else {
+                None
+            };
+            pending.extend(content.map(|c| c.pending_deferred()).unwrap_or_default());
+        }
+        pending
+    }
+
+    /// The component at path, indexes of children going down through nested containers
+    pub fn child_at_mut(&mut self, path: &[usize]) -> Option<&mut Box<dyn Component>> {
+        let (index, rest) = path.split_first()?;
+        let child = self.children.get_mut(*index)?;
+        if rest.is_empty() {
+            return Some(child);
+        }
+        if child.is::<TabPane>() {
+            return child.downcast_mut::<TabPane>()?.child_at_mut(rest);
+        }
+        if child.is::<Carousel>() {
+            return child.downcast_mut::<Carousel>()?.child_at_mut(rest);
+        }
+        if child.is::<Loadable>() {
+            return child.downcast_mut::<Loadable>()?.content.child_at_mut(rest);
+        }
+        if child.is::<Deferred>() {
+            return child.downcast_mut::<Deferred>()?.content.child_at_mut(rest);
+        }
+        if child.is::<Provider>() {
+            return child.downcast_mut::<Provider>()?.content.child_at_mut(rest);
+        }
+        if child.is::<If>() {
+            return child.downcast_mut::<If>()?.active_mut().child_at_mut(rest);
+        }
+        if child.is::<ForEach>() {
+            return child.downcast_mut::<ForEach>()?.content.child_at_mut(rest);
+        }
+        if child.is::<ScrollPane>() {
+            return child.downcast_mut::<ScrollPane>()?.child_at_mut(rest);
+        }
+        if child.is::<Form>() {
+            return child.downcast_mut::<Form>()?.child_at_mut(rest);
+        }
+        child.downcast_mut::<Container>()?.child_at_mut(rest)
+    }
+
+    /// The path with the indexes of the children of ForEach replaced by their keys, the engines
+    /// keep the paths of what has the focus or is dragged like this while the keys change
+    pub fn keyed_path(&mut self, path: &[usize]) -> Vec<PathStep> {
+        (0..path.len()).map(|i| {
+            let key = self.child_at_mut(&path[..i])
+                .and_then(|c| c.downcast_ref::<ForEach>())
+                .and_then(|f| f.expanded.get(path[i]).cloned());
+            key.map_or(PathStep::Index(path[i]), PathStep::Key)
+        }).collect()
+    }
+
+    /// Where the keyed path leads now, None when one of its keys is gone
+    pub fn resolve_keyed_path(&mut self, keyed: &[PathStep]) -> Option<Vec<usize>> {
+        let mut path = vec![];
+        for step in keyed {
+            let index = match step {
+                PathStep::Index(index) => *index,
+                PathStep::Key(key) => self.child_at_mut(&path)?.downcast_ref::<ForEach>()?
+                    .expanded.iter().position(|k| k == key)?,
+            };
+            path.push(index);
+        }
+        Some(path)
+    }
+
+    /// Keeps the ViewState of the components (their scroll, tab or page) in the view_state store
+    /// under their keyed paths, going down through nested containers and every tab and page
+    pub fn save_view_state(&mut self) {
+        self.sync_view_state(&[], &[], false);
+    }
+
+    /// Puts the ViewState kept under their keyed paths back into the components, the engines call
+    /// it once they replaced the container. Returns whether it changed any of them
+    pub fn restore_view_state(&mut self) -> bool {
+        self.sync_view_state(&[], &[], true)
+    }
+
+    /// keys are the ones of the children when the container is the content of a ForEach
+    fn sync_view_state(&mut self, prefix: &[PathStep], keys: &[String], restore: bool) -> bool {
+        let mut changed = false;
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let step = keys.get(i).map_or(PathStep::Index(i), |key| PathStep::Key(key.clone()));
+            let path = [prefix, &[step]].concat();
+            let key = view_state::key_of(&path);
+            if restore {
+                changed |= view_state::get(&key).is_some_and(|state| state.apply(child.as_mut()));
+            } else if let Some(state) = ViewState::of(child.as_ref()) {
+                view_state::set(&key, state);
+            }
+            let inside = |index: usize| [path.as_slice(), &[PathStep::Index(index)]].concat();
+            if let Some(container) = child.downcast_mut::<Container>() {
+                changed |= container.sync_view_state(&path, &[], restore);
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                changed |= scroll_pane.content.sync_view_state(&inside(ScrollPane::CONTENT), &[], restore);
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                changed |= form.content.sync_view_state(&inside(Form::CONTENT), &[], restore);
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                for (tab, page) in tab_pane.tabs.iter_mut().enumerate() {
+                    changed |= page.content.sync_view_state(&inside(tab), &[], restore);
+                }
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                for (index, page) in carousel.pages.iter_mut().enumerate() {
+                    changed |= page.sync_view_state(&inside(index), &[], restore);
+                }
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                changed |= loadable.content.sync_view_state(&path, &[], restore);
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                changed |= deferred.content.sync_view_state(&path, &[], restore);
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                changed |= provider.content.sync_view_state(&path, &[], restore);
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                changed |= shown_if.active_mut().sync_view_state(&path, &[], restore);
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                let keys = for_each.expanded.clone();
+                changed |= for_each.content.sync_view_state(&path, &keys, restore);
+            }
+        }
+        changed
+    }
+
+    /// Validates the TextFields for trigger (see TextField::validate()), going down through
+    /// nested containers and every page of a TabPane or a Carousel. Returns whether they and the
+    /// Spinners are all valid
+    pub fn validate(&mut self, trigger: Trigger) -> bool {
+        let mut valid = true;
+        for child in self.children.iter_mut() {
+            if let Some(text_field) = child.downcast_mut::<TextField>() {
+                text_field.validate(trigger);
+                valid &= text_field.is_valid();
+            } else if let Some(spinner) = child.downcast_ref::<Spinner>() {
+                valid &= spinner.is_valid();
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                valid &= container.validate(trigger);
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                valid &= scroll_pane.content.validate(trigger);
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                for tab in tab_pane.tabs.iter_mut() {
+                    valid &= tab.content.validate(trigger);
+                }
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                for page in carousel.pages.iter_mut() {
+                    valid &= page.validate(trigger);
+                }
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                valid &= form.content.validate(trigger);
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                valid &= loadable.content.validate(trigger);
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                valid &= deferred.content.validate(trigger);
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                valid &= provider.content.validate(trigger);
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                // What is hidden can't be fixed, so it doesn't count
+                valid &= shown_if.active_mut().validate(trigger);
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                valid &= for_each.content.validate(trigger);
+            }
+        }
+        valid
+    }
+
+    /// The paths of the children that can take the keyboard focus in the order Tab visits them,
+    /// going down through nested containers
+    pub fn focusable_paths(&self) -> Vec<Vec<usize>> {
+        let mut paths = vec![];
+        for (i, child) in self.children.iter().enumerate() {
+            if let Some(container) = child.downcast_ref::<Container>() {
+                paths.extend(container.focusable_paths().into_iter().map(|mut path| {
+                    path.insert(0, i);
+                    path
+                }));
+                continue;
+            }
+            if let Some(tab_pane) = child.downcast_ref::<TabPane>() {
+                paths.extend(tab_pane.focusable_paths().into_iter().map(|mut path| {
+                    path.insert(0, i);
+                    path
+                }));
+                continue;
+            }
+            if let Some(carousel) = child.downcast_ref::<Carousel>() {
+                paths.extend(carousel.focusable_paths().into_iter().map(|mut path| {
+                    path.insert(0, i);
+                    path
+                }));
+                continue;
+            }
+            // Nothing of the content takes the focus while it's loading
+            if let Some(loadable) = child.downcast_ref::<Loadable>().filter(|l| !l.loading) {
+                paths.extend(loadable.content.focusable_paths().into_iter().map(|mut path| {
+                    path.insert(0, i);
+                    path
+                }));
+                continue;
+            }
+            let content = child.downcast_ref::<Provider>().map(|p| &p.content)
+                .or_else(|| child.downcast_ref::<Deferred>().filter(|d| d.ready).map(|d| &d.content))
+                .or_else(|| child.downcast_ref::<If>().map(If::active))
+                .or_else(|| child.downcast_ref::<ForEach>().map(|f| &f.content));
+            if let Some(content) = content {
+                paths.extend(content.focusable_paths().into_iter().map(|mut path| {
+                    path.insert(0, i);
+                    path
+                }));
+                continue;
+            }
+            if let Some(scroll_pane) = child.downcast_ref::<ScrollPane>() {
+                paths.extend(scroll_pane.content.focusable_paths().into_iter().map(|mut path| {
+                    path.splice(0..0, [i, ScrollPane::CONTENT]);
+                    path
+                }));
+                continue;
+            }
+            if let Some(form) = child.downcast_ref::<Form>() {
+                paths.extend(form.content.focusable_paths().into_iter().map(|mut path| {
+                    path.splice(0..0, [i, Form::CONTENT]);
+                    path
+                }));
+                continue;
+            }
+            let focusable = child.downcast_ref::<TextField>().is_some_and(|t| t.editable)
+                || child.downcast_ref::<TextArea>().is_some_and(|t| t.editable) || child.is::<ChipInput>()
+                || child.downcast_ref::<Button>().is_some_and(|b| b.is_enabled())
+                || child.is::<Slider>() || child.is::<Spinner>() || child.is::<Checkbox>() || child.is::<ComboBox>()
+                || child.is::<DatePicker>() || child.is::<Rating>() || child.is::<SegmentedControl>()
+                || child.is::<ListView>() || child.is::<Hyperlink>()
+                || (child.is::<Label>() && accessibility::caret_browsing());
+            if focusable {
+                paths.push(vec![i]);
+            }
+        }
+        paths
+    }
+
+    /// Closes the popups of the children but the one of the component at keep (or inside it),
+    /// going down through nested containers. Returns whether any closed
+    pub fn close_popups(&mut self, keep: &[usize]) -> bool {
+        let mut closed = false;
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let kept = match keep.split_first() {
+                Some((index, rest)) if *index == i => Some(rest),
+                _ => None,
+            };
+            if let Some(combo_box) = child.downcast_mut::<ComboBox>() {
+                if kept.is_none() {
+                    closed |= std::mem::replace(&mut combo_box.open, false);
+                }
+            } else if let Some(date_picker) = child.downcast_mut::<DatePicker>() {
+                if kept.is_none() {
+                    closed |= std::mem::replace(&mut date_picker.open, false);
+                }
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                let kept = match kept.and_then(|k| k.split_first()) {
+                    Some((&Form::CONTENT, rest)) => rest,
+                    _ => &[],
+                };
+                closed |= form.content.close_popups(kept);
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                closed |= container.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                closed |= tab_pane.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                closed |= carousel.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                closed |= loadable.content.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                closed |= deferred.content.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                closed |= provider.content.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                closed |= shown_if.active_mut().close_popups(kept.unwrap_or(&[]));
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                closed |= for_each.content.close_popups(kept.unwrap_or(&[]));
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                let kept = match kept.and_then(|k| k.split_first()) {
+                    Some((&ScrollPane::CONTENT, rest)) => rest,
+                    _ => &[],
+                };
+                closed |= scroll_pane.content.close_popups(kept);
+            }
+        }
+        closed
+    }
+
+    /// Checks the conditions of the If children and the keys of the ForEach ones, going down
+    /// through nested containers. Returns whether what they show changed, the engines call it
+    /// every frame and before building the container
+    pub fn expand(&mut self) -> bool {
+        let mut changed = false;
+        for child in self.children.iter_mut() {
+            if let Some(shown_if) = child.downcast_mut::<If>() {
+                let shown = (shown_if.condition)();
+                changed |= std::mem::replace(&mut shown_if.shown, shown) != shown;
+                changed |= shown_if.active_mut().expand();
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                changed |= for_each.reconcile((for_each.keys)());
+                changed |= for_each.content.expand();
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                changed |= container.expand();
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                changed |= scroll_pane.content.expand();
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                for tab in tab_pane.tabs.iter_mut() {
+                    changed |= tab.content.expand();
+                }
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                for page in carousel.pages.iter_mut() {
+                    changed |= page.expand();
+                }
+            } else if let Some(form) = child.downcast_mut::<Form>() {
+                changed |= form.content.expand();
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                changed |= loadable.content.expand();
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                changed |= deferred.content.expand();
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                changed |= provider.content.expand();
+            }
+        }
+        changed
+    }
+
+    /// Advances the animations of the children, going down through nested containers. Returns
+    /// whether any moved, the engines call it every frame
+    pub fn animate(&mut self, elapsed: Duration) -> bool {
+        let mut moved = false;
+        for child in self.children.iter_mut() {
+            if let Some(progress_bar) = child.downcast_mut::<ProgressBar>() {
+                moved |= progress_bar.advance(elapsed);
+            } else if let Some(list_view) = child.downcast_mut::<ListView>() {
+                moved |= list_view.overscroll.advance(elapsed);
+            } else if let Some(spinner) = child.downcast_mut::<Spinner>() {
+                moved |= spinner.advance(elapsed);
+            } else if let Some(gauge) = child.downcast_mut::<Gauge>() {
+                moved |= gauge.advance(elapsed);
+            } else if let Some(container) = child.downcast_mut::<Container>() {
+                moved |= container.animate(elapsed);
+            } else if let Some(tab_pane) = child.downcast_mut::<TabPane>() {
+                moved |= tab_pane.active_content_mut().is_some_and(|c| c.animate(elapsed));
+            } else if let Some(carousel) = child.downcast_mut::<Carousel>() {
+                moved |= carousel.advance(elapsed);
+                moved |= carousel.active_page_mut().is_some_and(|p| p.animate(elapsed));
+            } else if let Some(skeleton) = child.downcast_mut::<Skeleton>() {
+                moved |= skeleton.advance(elapsed);
+            } else if let Some(loadable) = child.downcast_mut::<Loadable>() {
+                moved |= match loadable.loading {
+                    true => loadable.skeleton.advance(elapsed),
+                    false => loadable.content.animate(elapsed),
+                };
+            } else if let Some(deferred) = child.downcast_mut::<Deferred>() {
+                moved |= match deferred.ready {
+                    true => deferred.content.animate(elapsed),
+                    false => deferred.placeholder.advance(elapsed),
+                };
+            } else if let Some(provider) = child.downcast_mut::<Provider>() {
+                moved |= provider.content.animate(elapsed);
+            } else if let Some(shown_if) = child.downcast_mut::<If>() {
+                moved |= shown_if.active_mut().animate(elapsed);
+            } else if let Some(for_each) = child.downcast_mut::<ForEach>() {
+                moved |= for_each.content.animate(elapsed);
+            } else if let Some(scroll_pane) = child.downcast_mut::<ScrollPane>() {
+                moved |= scroll_pane.content.animate(elapsed);
+            }
+        }
+        moved
+    }
+}
+
+/// A right-aligned part of the StatusBar, e.g. "Ln 1, Col 1"
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusSection {
+    pub text: String,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_action"))]
+    pub on_click: fn(Event) -> bool,
+}
+
+impl Default for StatusSection {
+    fn default() -> Self {
+        StatusSection {
+            text: "".to_string(),
+            on_click: |_event| true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusBar {
+    /// Permanent text on the left, temporarily replaced by show_message()
+    pub text: String,
+    pub sections: Vec<StatusSection>,
+    /// Fraction (0.0 - 1.0) shown in a mini progress bar, None hides it
+    pub progress: Option<f32>,
+    /// Transient text and when it expires, see show_message()
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub message: Option<(String, Instant)>,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        StatusBar {
+            text: "".to_string(),
+            sections: vec![],
+            progress: None,
+            message: None,
+        }
+    }
+}
+
+impl StatusBar {
+    /// Shows text instead of the permanent text until duration passes
+    pub fn show_message(&mut self, text: &str, duration: Duration) {
+        self.message = Some((text.to_string(), Instant::now() + duration));
+    }
+
+    /// The transient message if there is one, otherwise the permanent text
+    pub fn current_text(&self) -> &str {
+        match &self.message {
+            Some((message, _)) => message,
+            None => &self.text,
+        }
+    }
+
+    /// Replaces the text of the section at index, e.g. the position of the caret
+    pub fn set_section_text(&mut self, index: usize, text: &str) -> Result<(), String> {
+        let section = self.sections.get_mut(index).ok_or(format!("Status bar has no section {}", index))?;
+        section.text = text.to_string();
+        Ok(())
+    }
+
+    /// Calls on_click of the section at index, returns whether the event was consumed
+    pub fn click_section(&self, index: usize, position: (i32, i32)) -> bool {
+        match self.sections.get(index) {
+            Some(section) => (section.on_click)(Event {
+                source: section.text.clone(),
+                position: Some(position),
+            }),
+            None => false,
+        }
+    }
+
+    /// Drops the transient message once it expired, returns whether it did
+    pub fn expire_message(&mut self, now: Instant) -> bool {
+        match &self.message {
+            Some((_, until)) if *until <= now => {
+                self.message = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A button of a ToolBar, shown as its icon when it has one and otherwise as its title
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToolButton {
+    /// Shown next to the icon when show_title, and in the overflow menu
+    pub title: String,
+    /// Usually a single glyph of an icon font, like the icon of a MenuItem
+    pub icon: Option<String>,
+    pub show_title: bool,
+    pub enabled: bool,
+    /// None when the button can't be toggled, otherwise it's drawn pressed while it's checked
+    pub checked: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_action"))]
+    pub on_action: fn(Event) -> bool,
+}
+
+impl Default for ToolButton {
+    fn default() -> Self {
+        ToolButton {
+            title: "ToolButton".to_string(),
+            icon: None,
+            show_title: false,
+            enabled: true,
+            checked: None,
+            on_action: |_event| true,
+        }
+    }
+}
+
+impl ToolButton {
+    /// The icon, or the title when there is none
+    pub fn label(&self) -> String {
+        match (&self.icon, self.show_title) {
+            (Some(icon), true) => format!("{} {}", icon, self.title),
+            (Some(icon), false) => icon.clone(),
+            (None, _) => self.title.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct StatusBar {}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ToolItem {
+    Button(ToolButton),
+    /// A line between groups of buttons
+    Separator,
+}
+
+/// A row of buttons under the menu bar. The ones that don't fit in the window are in a menu opened
+/// with the button at its right end. The buttons are hit at [ITEMS, index], that one at [OVERFLOW]
+/// and the buttons of its menu at [OVERFLOW, index]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToolBar {
+    pub items: Vec<ToolItem>,
+    /// Whether the menu with the buttons that don't fit is open
+    pub overflow_open: bool,
+    /// The button held down, it's drawn pressed until the pointer is released
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub pressed: Option<usize>,
+}
+
+impl ToolBar {
+    pub const ITEMS: usize = 0;
+    pub const OVERFLOW: usize = 1;
+
+    /// How many items fit in available when they're as wide as widths, the overflow button of
+    /// overflow_width takes room at the end unless they all fit. A separator isn't left last
+    pub fn fitting_items(widths: &[f32], available: f32, overflow_width: f32) -> usize {
+        if widths.iter().sum::<f32>() <= available {
+            return widths.len();
+        }
+        let mut x = overflow_width;
+        widths.iter().take_while(|w| {
+            x += *w;
+            x <= available
+        }).count()
+    }
+
+    /// The items that fit, see fitting_items(), without a separator at their end
+    pub fn shown_items(&self, fitting: usize) -> usize {
+        let mut shown = fitting.min(self.items.len());
+        while shown > 0 && shown < self.items.len() && matches!(self.items[shown - 1], ToolItem::Separator) {
+            shown -= 1;
+        }
+        shown
+    }
+
+    /// Presses the button at index: a toggle button flips, then on_action is called. Disabled
+    /// buttons and separators ignore it. Returns whether it was pressed
+    pub fn press(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        let button = match self.items.get_mut(index) {
+            Some(ToolItem::Button(button)) if button.enabled => button,
+            _ => return false,
+        };
+        if let Some(checked) = button.checked.as_mut() {
+            *checked = !*checked;
+        }
+        (button.on_action)(Event { source: button.title.clone(), position });
+        self.pressed = Some(index);
+        true
+    }
+
+    /// Lets go of the button held down, returns whether there was one
+    pub fn release(&mut self) -> bool {
+        self.pressed.take().is_some()
+    }
+
+    /// Presses the button at index in the overflow menu, which closes
+    pub fn choose_overflow(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        self.overflow_open = false;
+        let pressed = self.press(index, position);
+        self.pressed = None;
+        pressed
+    }
+
+    /// Opens or closes the menu with the buttons that don't fit
+    pub fn toggle_overflow(&mut self) {
+        self.overflow_open = !self.overflow_open;
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Window {
     pub title: String,
     pub menu: Option<MainMenu>,
+    /// Under the menu bar
+    pub tool_bar: Option<ToolBar>,
     pub container: Option<Container>,
     pub status_bar: Option<StatusBar>,
+    /// Shown on the taskbar or the dock, see taskbar::set_progress()
+    pub taskbar_progress: Option<TaskbarProgress>,
+    /// Behind the components, the engines' set_background() changes it while the window is shown
+    pub background: WindowBackground,
     pub height: Dimension,
     pub width: Dimension,
 }
@@ -99,21 +1331,102 @@ impl Default for Window {
         Window {
             title: "RUI Lopez".to_string(),
             menu: None,
+            tool_bar: None,
             container: None,
             status_bar: None,
+            taskbar_progress: None,
+            background: WindowBackground::default(),
             height: Relative(-1),
             width: Relative(-1),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Event {}
+/// What a Window shows behind its components. Its colors are mapped by the color mode of the
+/// accessibility preferences like the rest of the window, and the path of an image is looked up in
+/// the assets so a theme can register its own
+#[derive(Clone)]
+pub enum WindowBackground {
+    /// What the window is cleared to, an embedded SDL window leaves it to the app
+    Color(Color),
+    /// From the first color at the top (or on the left when it's not vertical) to the second
+    Gradient { from: Color, to: Color, vertical: bool },
+    /// Over black, which shows until it's loaded and where the scale mode leaves room
+    Image { source: ImageSource, scale_mode: ScaleMode },
+    /// Called with the size of the window every time it's built, like the draw of a Canvas. Over
+    /// black too
+    Draw(CanvasDraw),
+}
+
+impl Default for WindowBackground {
+    fn default() -> Self {
+        WindowBackground::Color(Color::rgb(0, 0, 0))
+    }
+}
+
+impl WindowBackground {
+    /// The color the window is cleared to before the rest of the background is drawn
+    pub fn clear_color(&self) -> Color {
+        match self {
+            WindowBackground::Color(color) => *color,
+            _ => Color::rgb(0, 0, 0),
+        }
+    }
+}
+
+impl Debug for WindowBackground {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowBackground::Color(color) => f.debug_tuple("Color").field(color).finish(),
+            WindowBackground::Gradient { from, to, vertical } => f.debug_struct("Gradient")
+                .field("from", from)
+                .field("to", to)
+                .field("vertical", vertical)
+                .finish(),
+            WindowBackground::Image { source, scale_mode } => f.debug_struct("Image")
+                .field("source", source)
+                .field("scale_mode", scale_mode)
+                .finish(),
+            WindowBackground::Draw(_) => f.write_str("Draw"),
+        }
+    }
+}
+
+/// What is given to the callbacks of the components
+#[derive(Debug, Clone, Default)]
+pub struct Event {
+    /// The title (or text) of the component that fired the event
+    pub source: String,
+    /// Position of the pointer in window coordinates, when the event comes from it
+    pub position: Option<(i32, i32)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ButtonState {
+    Idle,
+    Hover,
+    Pressed,
+    Disabled,
+}
+
+/// What the engines tell a Button about the pointer, plus enabling it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ButtonEvent {
+    PointerEnter,
+    PointerLeave,
+    Press,
+    Release,
+    Disable,
+    Enable,
+}
 
 #[derive(Debug, Clone)]
 pub struct Button {
     pub title: String,
     pub on_action: fn(Event) -> bool,
+    /// See button_states()
+    pub states: StateMachine<ButtonState, ButtonEvent>,
+    pub accessible: Accessible,
 }
 
 impl Default for Button {
@@ -121,28 +1434,3648 @@ impl Default for Button {
         Button {
             title: "Button".to_string(),
             on_action: |_event| true,
+            states: button_states(),
+            accessible: Accessible::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct TextField {
-    pub text: String,
-    pub editable: bool,
+/// The interaction states of a Button: hovering and pressing it, a disabled Button ignores the
+/// pointer until it's enabled again
+pub fn button_states() -> StateMachine<ButtonState, ButtonEvent> {
+    StateMachine::new(ButtonState::Idle)
+        .with_transition(ButtonState::Idle, ButtonEvent::PointerEnter, ButtonState::Hover)
+        .with_transition(ButtonState::Idle, ButtonEvent::Press, ButtonState::Pressed)
+        .with_transition(ButtonState::Hover, ButtonEvent::PointerLeave, ButtonState::Idle)
+        .with_transition(ButtonState::Hover, ButtonEvent::Press, ButtonState::Pressed)
+        .with_transition(ButtonState::Pressed, ButtonEvent::Release, ButtonState::Hover)
+        .with_transition(ButtonState::Pressed, ButtonEvent::PointerLeave, ButtonState::Idle)
+        .with_transition(ButtonState::Disabled, ButtonEvent::Enable, ButtonState::Idle)
+        .with_transition_from_any(ButtonEvent::Disable, ButtonState::Disabled)
 }
 
-impl Default for TextField {
-    fn default() -> Self {
-        TextField {
-            text: "TextField".to_string(),
-            editable: false,
-        }
+impl Button {
+    pub fn state(&self) -> ButtonState {
+        self.states.state()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.states.is_in(ButtonState::Disabled)
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) -> bool {
+        self.states.fire(if enabled { ButtonEvent::Enable } else { ButtonEvent::Disable })
+    }
+
+    /// Presses the Button and calls on_action, nothing happens while it's disabled. Returns
+    /// whether on_action consumed the event or the Button looks different
+    pub fn press(&mut self, event: Event) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        let pressed = self.states.fire(ButtonEvent::Press);
+        (self.on_action)(Event { source: self.title.clone(), ..event }) || pressed
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextField {
+    pub text: String,
+    pub editable: bool,
+    pub accessible: Accessible,
+    pub validator: Validator,
+    /// What the validator found the last time it checked the text, None before that. They're
+    /// shown under the field
+    pub errors: Option<Vec<String>>,
+    /// Gets the text every time it's typed, it can be debounced or throttled (see timers)
+    pub on_change: Limited<String>,
+}
+
+impl Default for TextField {
+    fn default() -> Self {
+        TextField {
+            text: "TextField".to_string(),
+            editable: false,
+            accessible: Accessible::default(),
+            validator: Validator::default(),
+            errors: None,
+            on_change: Limited::immediate(|_event, _text| true),
+        }
+    }
+}
+
+impl TextField {
+    /// Checks the text when trigger is at or after the one of the validator, or at any trigger once
+    /// it was checked. Returns whether the errors changed
+    pub fn validate(&mut self, trigger: Trigger) -> bool {
+        if self.errors.is_none() && trigger < self.validator.trigger {
+            return false;
+        }
+        let errors = Some(self.validator.errors(&self.text));
+        let changed = errors != self.errors;
+        self.errors = errors;
+        changed
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.as_ref().is_none_or(|errors| errors.is_empty())
+    }
+}
+
+/// Entries typed in a field that become chips inside of it on Return or a comma, each with a cross
+/// that removes it. Backspace in the empty field removes the last chip and Return leaves the field
+/// when nothing is typed. The crosses are hit tested as [index] inside it
+#[derive(Debug, Clone)]
+pub struct ChipInput {
+    pub chips: Vec<String>,
+    /// What is being typed after the chips
+    pub text: String,
+    /// Gets the chips every time one is added or removed
+    pub on_change: fn(Event, Vec<String>) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for ChipInput {
+    fn default() -> Self {
+        ChipInput {
+            chips: vec![],
+            text: String::new(),
+            on_change: |_event, _chips| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl ChipInput {
+    /// The narrowest the field is after the chips, it goes to the next row when there is less left
+    pub const TEXT_MIN_WIDTH: f32 = 60.;
+
+    /// Types text after the chips, a comma makes what was typed before it a chip. Returns whether
+    /// it changed anything
+    pub fn insert(&mut self, text: &str, position: Option<(i32, i32)>) -> bool {
+        let mut pieces = text.split(',');
+        let mut changed = pieces.next().is_some_and(|piece| {
+            self.text.push_str(piece);
+            !piece.is_empty()
+        });
+        for piece in pieces {
+            changed |= self.commit(position);
+            self.text.push_str(piece);
+            changed |= !piece.is_empty();
+        }
+        changed
+    }
+
+    /// Makes what was typed a chip, it's only cleared when there is such a chip already. Returns
+    /// whether the chips or the text changed
+    pub fn commit(&mut self, position: Option<(i32, i32)>) -> bool {
+        let typed = std::mem::take(&mut self.text);
+        let chip = typed.trim();
+        if chip.is_empty() || self.chips.iter().any(|c| c == chip) {
+            return !typed.is_empty();
+        }
+        self.chips.push(chip.to_string());
+        (self.on_change)(Event { source: "ChipInput".to_string(), position }, self.chips.clone());
+        true
+    }
+
+    /// Removes the chip at index, returns whether there was one
+    pub fn remove(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index >= self.chips.len() {
+            return false;
+        }
+        self.chips.remove(index);
+        (self.on_change)(Event { source: "ChipInput".to_string(), position }, self.chips.clone());
+        true
+    }
+
+    /// Erases the last character typed, or the last chip when nothing is typed
+    pub fn backspace(&mut self) -> bool {
+        match self.text.pop() {
+            Some(_) => true,
+            None => self.remove(self.chips.len().wrapping_sub(1), None),
+        }
+    }
+
+    /// Where the chips and then the text go in a field of width, as (x, row). chip_width measures
+    /// a chip and gap is left around them, a chip wider than the field gets a row of its own
+    pub fn layout(&self, chip_width: impl Fn(&str) -> f32, gap: f32, width: f32) -> Vec<(f32, usize)> {
+        let widths = self.chips.iter().map(|c| chip_width(c)).chain([ChipInput::TEXT_MIN_WIDTH]);
+        let (mut x, mut row) = (gap, 0);
+        widths.map(|w| {
+            if x > gap && x + w + gap > width {
+                (x, row) = (gap, row + 1);
+            }
+            let place = (x, row);
+            x += w + gap;
+            place
+        }).collect()
+    }
+}
+
+/// Fields stacked like in a Container with a submit button under them. Submitting validates the
+/// TextFields of content and calls on_submit only when they're all valid, otherwise their errors
+/// show. The components of content are at [CONTENT, index...] and the button at [SUBMIT]
+#[derive(Debug, Clone)]
+pub struct Form {
+    pub content: Container,
+    pub submit_title: String,
+    pub on_submit: fn(Event) -> bool,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Form {
+            content: Container::default(),
+            submit_title: "Submit".to_string(),
+            on_submit: |_event| true,
+        }
+    }
+}
+
+impl Form {
+    pub const CONTENT: usize = 0;
+    pub const SUBMIT: usize = 1;
+
+    /// Validates the fields and calls on_submit when they're valid, returns whether it was called
+    pub fn submit(&mut self, position: Option<(i32, i32)>) -> bool {
+        if !self.content.validate(Trigger::Submit) {
+            return false;
+        }
+        (self.on_submit)(Event { source: self.submit_title.clone(), position })
+    }
+
+    /// The component at path, CONTENT followed by the path in the content
+    pub fn child_at_mut(&mut self, path: &[usize]) -> Option<&mut Box<dyn Component>> {
+        match path.split_first()? {
+            (&Self::CONTENT, rest) => self.content.child_at_mut(rest),
+            _ => None,
+        }
+    }
+}
+
+/// A boolean option of a form, clicking the box or the label toggles it
+#[derive(Debug, Clone)]
+pub struct Checkbox {
+    pub label: String,
+    pub checked: bool,
+    /// Gets the new state after it was toggled
+    pub on_toggle: fn(Event, bool) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for Checkbox {
+    fn default() -> Self {
+        Checkbox {
+            label: "Checkbox".to_string(),
+            checked: false,
+            on_toggle: |_event, _checked| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Checkbox {
+    /// Flips checked and calls on_toggle, returns what it returned
+    pub fn toggle(&mut self, position: Option<(i32, i32)>) -> bool {
+        self.checked = !self.checked;
+        (self.on_toggle)(Event { source: self.label.clone(), position }, self.checked)
+    }
+}
+
+/// One option of a RadioGroup
+#[derive(Debug, Clone)]
+pub struct RadioButton {
+    pub label: String,
+    pub selected: bool,
+}
+
+impl Default for RadioButton {
+    fn default() -> Self {
+        RadioButton {
+            label: "RadioButton".to_string(),
+            selected: false,
+        }
+    }
+}
+
+/// A column of RadioButtons of which only one is selected, clicking one selects it
+#[derive(Debug, Clone)]
+pub struct RadioGroup {
+    pub children: Vec<RadioButton>,
+    /// Gets the index of the newly selected child
+    pub on_change: fn(Event, usize) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for RadioGroup {
+    fn default() -> Self {
+        RadioGroup {
+            children: vec![],
+            on_change: |_event, _index| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl RadioGroup {
+    pub fn selected_index(&self) -> Option<usize> {
+        self.children.iter().position(|c| c.selected)
+    }
+
+    /// Selects the child at index and deselects the others, then calls on_change. Returns whether
+    /// the selection changed
+    pub fn select(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index >= self.children.len() || self.selected_index() == Some(index) {
+            return false;
+        }
+        for (i, child) in self.children.iter_mut().enumerate() {
+            child.selected = i == index;
+        }
+        (self.on_change)(Event { source: self.children[index].label.clone(), position }, index);
+        true
+    }
+}
+
+/// A score of up to max stars, clicking a star gives that many and clicking the last given one
+/// again clears them. The stars up to the one under the pointer preview what clicking it would
+/// give, Left and Right (or Up and Down) change the score once it has the focus. With half_stars
+/// the left half of a star gives half of it
+#[derive(Debug, Clone)]
+pub struct Rating {
+    pub max: usize,
+    /// How many stars are given, a multiple of half a star with half_stars or else of a star
+    pub value: f32,
+    pub half_stars: bool,
+    /// The part under the pointer from 0, a star or with half_stars a half of one
+    pub hovered: Option<usize>,
+    /// Gets the new score
+    pub on_change: fn(Event, f32) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Rating {
+            max: 5,
+            value: 0.,
+            half_stars: false,
+            hovered: None,
+            on_change: |_event, _value| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Rating {
+    /// The smallest change of the score
+    pub fn step(&self) -> f32 {
+        if self.half_stars { 0.5 } else { 1. }
+    }
+
+    /// How many parts a star is hit tested as, 2 with half_stars
+    pub fn parts_per_star(&self) -> usize {
+        if self.half_stars { 2 } else { 1 }
+    }
+
+    /// How many stars are filled: the preview while hovering, otherwise the score
+    pub fn shown(&self) -> f32 {
+        self.hovered.map_or(self.value, |part| (part + 1) as f32 * self.step())
+    }
+
+    /// How much of the star at index is filled, from 0 to 1
+    pub fn star_fill(&self, index: usize) -> f32 {
+        (self.shown() - index as f32).clamp(0., 1.)
+    }
+
+    /// Sets the score rounded to a step and clamped to max and calls on_change, returns whether it
+    /// changed
+    pub fn set_value(&mut self, value: f32, position: Option<(i32, i32)>) -> bool {
+        let value = ((value / self.step()).round() * self.step()).clamp(0., self.max as f32);
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        (self.on_change)(Event { source: "Rating".to_string(), position }, value);
+        true
+    }
+
+    /// What clicking the part at index does, a star or with half_stars a half of one
+    pub fn click_part(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        let given = (index + 1) as f32 * self.step();
+        let value = if self.value == given { 0. } else { given };
+        self.set_value(value, position)
+    }
+
+    pub fn step_by(&mut self, steps: i32) -> bool {
+        self.set_value(self.value + steps as f32 * self.step(), None)
+    }
+
+    /// Returns whether the preview changed
+    pub fn hover(&mut self, part: Option<usize>) -> bool {
+        let part = part.filter(|p| *p < self.max * self.parts_per_star());
+        std::mem::replace(&mut self.hovered, part) != part
+    }
+}
+
+/// A row of joined buttons of which only one is selected, like the tabs of a TabPane without
+/// their pages or a horizontal RadioGroup. Left and Right move the selection once it has the
+/// focus
+#[derive(Debug, Clone)]
+pub struct SegmentedControl {
+    pub segments: Vec<String>,
+    pub selected: Option<usize>,
+    /// Gets the index of the newly selected segment
+    pub on_change: fn(Event, usize) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for SegmentedControl {
+    fn default() -> Self {
+        SegmentedControl {
+            segments: vec![],
+            selected: None,
+            on_change: |_event, _index| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl SegmentedControl {
+    pub fn selected_segment(&self) -> Option<&str> {
+        self.selected.and_then(|i| self.segments.get(i)).map(String::as_str)
+    }
+
+    /// Selects the segment at index and calls on_change, returns whether the selection changed
+    pub fn select(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index >= self.segments.len() || self.selected == Some(index) {
+            return false;
+        }
+        self.selected = Some(index);
+        (self.on_change)(Event { source: self.segments[index].clone(), position }, index);
+        true
+    }
+
+    /// Selects the segment delta segments away, the first one when there is none, clamped to the
+    /// segments
+    pub fn move_selection(&mut self, delta: i32) -> bool {
+        let last = match self.segments.len().checked_sub(1) {
+            Some(last) => last as i64,
+            None => return false,
+        };
+        let index = self.selected.map_or(0, |i| (i as i64 + delta as i64).clamp(0, last));
+        self.select(index as usize, None)
+    }
+}
+
+/// The path from the first place to the current one, e.g. Home › Documents › Report. Clicking
+/// a place gives its index to on_click, the last one is the current place and can't be clicked.
+/// When the path doesn't fit, the places after the first one are hidden behind an ellipsis that
+/// goes to the last hidden place. The places are hit tested as [index] inside it
+#[derive(Debug, Clone)]
+pub struct Breadcrumb {
+    pub segments: Vec<String>,
+    /// Gets the index of the clicked place
+    pub on_click: fn(Event, usize) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for Breadcrumb {
+    fn default() -> Self {
+        Breadcrumb {
+            segments: vec![],
+            on_click: |_event, _index| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Breadcrumb {
+    pub const SEPARATOR: &'static str = "\u{203A}";
+    pub const ELLIPSIS: &'static str = "\u{2026}";
+
+    /// The indexes of the segments shown in width, those missing after the first one are behind
+    /// the ellipsis (e.g. [0, 3, 4]). widths are the ones of the segments, gap the one of a
+    /// separator and ellipsis the one of the ellipsis. The first and the last are always shown
+    pub fn visible(&self, widths: &[f32], gap: f32, ellipsis: f32, width: f32) -> Vec<usize> {
+        let count = widths.len();
+        let fits = |shown: &[usize], hidden: bool| {
+            let items = shown.len() + usize::from(hidden);
+            let hidden_width = if hidden { ellipsis } else { 0. };
+            shown.iter().map(|i| widths[*i]).sum::<f32>() + hidden_width + gap * (items - 1) as f32 <= width
+        };
+        let all: Vec<usize> = (0..count).collect();
+        if count <= 2 || fits(&all, false) {
+            return all;
+        }
+        (2..count).map(|first_shown| [0].into_iter().chain(first_shown..count).collect::<Vec<usize>>())
+            .find(|shown| fits(shown, true))
+            .unwrap_or_else(|| vec![0, count - 1])
+    }
+
+    /// Calls on_click for the place at index unless it's the current one, returns whether it did
+    pub fn click(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index + 1 >= self.segments.len() {
+            return false;
+        }
+        (self.on_click)(Event { source: self.segments[index].clone(), position }, index);
+        true
+    }
+}
+
+/// A value between min and max picked by dragging the thumb along the track, the arrow keys move
+/// it by step once it has the focus
+#[derive(Debug, Clone)]
+pub struct Slider {
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    /// Values are multiples of step from min, 0 allows any value
+    pub step: f32,
+    /// Gets the new value
+    pub on_change: fn(Event, f32) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for Slider {
+    fn default() -> Self {
+        Slider {
+            min: 0.,
+            max: 100.,
+            value: 0.,
+            step: 1.,
+            on_change: |_event, _value| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Slider {
+    /// Where the value is between min and max, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        match self.max > self.min {
+            true => ((self.value - self.min) / (self.max - self.min)).clamp(0., 1.),
+            false => 0.,
+        }
+    }
+
+    /// Sets the value rounded to step and clamped to min..max, then calls on_change when it
+    /// changed. Returns whether it did
+    pub fn set_value(&mut self, value: f32, position: Option<(i32, i32)>) -> bool {
+        let mut value = value;
+        if self.step > 0. {
+            value = self.min + ((value - self.min) / self.step).round() * self.step;
+        }
+        let value = value.clamp(self.min, self.max.max(self.min));
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        (self.on_change)(Event { source: "Slider".to_string(), position }, value);
+        true
+    }
+
+    pub fn set_fraction(&mut self, fraction: f32, position: Option<(i32, i32)>) -> bool {
+        self.set_value(self.min + fraction * (self.max - self.min), position)
+    }
+
+    /// Moves the value by a number of steps, a step is a hundredth of the range when step is 0
+    pub fn step_by(&mut self, steps: i32) -> bool {
+        let step = match self.step > 0. {
+            true => self.step,
+            false => (self.max - self.min) / 100.,
+        };
+        self.set_value(self.value + steps as f32 * step, None)
+    }
+}
+
+/// How long an arrow of a Spinner is held before it starts repeating
+const SPINNER_REPEAT_DELAY: Duration = Duration::from_millis(400);
+/// Time between the first repeats of a held arrow, each one is faster down to the minimum
+const SPINNER_REPEAT_INTERVAL: Duration = Duration::from_millis(120);
+const SPINNER_MIN_REPEAT_INTERVAL: Duration = Duration::from_millis(20);
+
+/// An arrow of a Spinner held down, it steps again and again until it's released
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldArrow {
+    /// -1 for DECREMENT, 1 for INCREMENT
+    steps: i32,
+    /// Time left until the next repeat
+    next: Duration,
+    repeats: u32,
+}
+
+/// A number typed in a field or stepped with the arrows next to it (or Up and Down while it has
+/// the focus, or the wheel while it's hovered). Holding an arrow repeats it faster and faster.
+/// Only what could become a number can be typed, it becomes the value when the field loses the
+/// focus or reverts to it when it isn't one between min and max
+#[derive(Debug, Clone)]
+pub struct Spinner {
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    /// Values are multiples of step from min, it's also what the arrows add, 0 allows any value
+    pub step: f32,
+    /// What the field shows, the value unless it's being typed
+    pub text: String,
+    /// Shown dimmed after the number, e.g. "px" or "%"
+    pub unit: String,
+    /// Decimals shown, None shows as many as step has
+    pub decimals: Option<usize>,
+    /// The arrow being held, see press()
+    pub held: Option<HeldArrow>,
+    /// Gets the new value
+    pub on_change: fn(Event, f32) -> bool,
+    pub accessible: Accessible,
+}
+
+/// The Spinner is also known as a SpinBox or a numeric stepper
+pub type SpinBox = Spinner;
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Spinner {
+            min: 0.,
+            max: 100.,
+            value: 0.,
+            step: 1.,
+            text: "0".to_string(),
+            unit: String::new(),
+            decimals: None,
+            held: None,
+            on_change: |_event, _value| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Spinner {
+    /// Paths of the arrows in the Spinner, the field is the Spinner itself
+    pub const DECREMENT: usize = 0;
+    pub const INCREMENT: usize = 1;
+
+    /// The value with the decimals, or as many as step has
+    pub fn format(&self, value: f32) -> String {
+        let step = self.step.to_string();
+        let decimals = self.decimals.unwrap_or_else(|| step.split_once('.').map_or(0, |(_, decimals)| decimals.len()));
+        format!("{:.*}", decimals, value)
+    }
+
+    /// Sets the value rounded to step and clamped to min..max and shows it in the field, then calls
+    /// on_change when it changed. Returns whether it did
+    pub fn set_value(&mut self, value: f32, position: Option<(i32, i32)>) -> bool {
+        let mut value = value;
+        if self.step > 0. {
+            value = self.min + ((value - self.min) / self.step).round() * self.step;
+        }
+        let value = value.clamp(self.min, self.max.max(self.min));
+        self.text = self.format(value);
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        (self.on_change)(Event { source: "Spinner".to_string(), position }, value);
+        true
+    }
+
+    /// Moves the value by a number of steps, from what is typed when it's a number
+    pub fn step_by(&mut self, steps: i32, position: Option<(i32, i32)>) -> bool {
+        let step = if self.step > 0. { self.step } else { 1. };
+        let value = self.text.trim().parse().unwrap_or(self.value);
+        let changed = self.set_value(value + steps as f32 * step, position);
+        // Stepping from a typed number shows it even if the value stays
+        changed || self.text != self.format(value)
+    }
+
+    /// Types text at the end of the field, it's rejected when the field couldn't become a number
+    /// with it. Returns whether it was typed
+    pub fn insert(&mut self, text: &str) -> bool {
+        let typed = format!("{}{}", self.text, text);
+        let digits = typed.strip_prefix('-').filter(|_| self.min < 0.).unwrap_or(&typed);
+        let decimals = self.step == 0. || self.step.fract() != 0.;
+        let points = digits.matches('.').count();
+        if !digits.chars().all(|c| c.is_ascii_digit() || c == '.') || points > usize::from(decimals) {
+            return false;
+        }
+        self.text = typed;
+        true
+    }
+
+    pub fn backspace(&mut self) -> bool {
+        self.text.pop().is_some()
+    }
+
+    /// Steps once for the arrow at path and holds it, so advance() repeats it until release().
+    /// Returns whether it stepped
+    pub fn press(&mut self, path: usize, position: Option<(i32, i32)>) -> bool {
+        let steps = if path == Self::INCREMENT { 1 } else { -1 };
+        self.held = Some(HeldArrow { steps, next: SPINNER_REPEAT_DELAY, repeats: 0 });
+        self.step_by(steps, position)
+    }
+
+    pub fn release(&mut self) {
+        self.held = None;
+    }
+
+    /// Repeats the held arrow as often as it became due, returns whether the value changed
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        let mut elapsed = elapsed;
+        let mut changed = false;
+        while let Some(held) = self.held.as_mut() {
+            if elapsed < held.next {
+                held.next -= elapsed;
+                break;
+            }
+            elapsed -= held.next;
+            held.repeats += 1;
+            held.next = (SPINNER_REPEAT_INTERVAL / held.repeats).max(SPINNER_MIN_REPEAT_INTERVAL);
+            let steps = held.steps;
+            match self.step_by(steps, None) {
+                true => changed = true,
+                // Nothing left to step at min or max
+                false => self.release(),
+            }
+        }
+        changed
+    }
+
+    /// Whether the field shows a number between min and max
+    pub fn is_valid(&self) -> bool {
+        self.text.trim().parse::<f32>().is_ok_and(|n| n >= self.min && n <= self.max)
+    }
+
+    /// Makes what is typed the value, or shows the value again when it isn't valid. Returns whether
+    /// the field or the value changed
+    pub fn commit(&mut self, position: Option<(i32, i32)>) -> bool {
+        let old = self.text.clone();
+        match self.is_valid() {
+            true => self.set_value(self.text.trim().parse().unwrap_or(self.value), position) || old != self.text,
+            false => {
+                self.text = self.format(self.value);
+                old != self.text
+            }
+        }
+    }
+}
+
+/// Picks one of items, clicking it opens a popup list drawn over the rest of the window
+#[derive(Debug, Clone)]
+pub struct ComboBox {
+    pub items: Vec<String>,
+    pub selected: Option<usize>,
+    /// Whether the popup is shown
+    pub open: bool,
+    /// Gets the index of the new selected item
+    pub on_change: fn(Event, usize) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for ComboBox {
+    fn default() -> Self {
+        ComboBox {
+            items: vec![],
+            selected: None,
+            open: false,
+            on_change: |_event, _index| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl ComboBox {
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected?).map(|i| i.as_str())
+    }
+
+    /// Selects the item at index and closes the popup, on_change is only called when the
+    /// selection changed. Returns whether anything changed
+    pub fn select(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index >= self.items.len() {
+            return false;
+        }
+        let closed = std::mem::replace(&mut self.open, false);
+        if self.selected == Some(index) {
+            return closed;
+        }
+        self.selected = Some(index);
+        (self.on_change)(Event { source: self.items[index].clone(), position }, index);
+        true
+    }
+}
+
+/// A date shown in a field, clicking it opens a calendar of a month drawn over the rest of the
+/// window. In the popup the arrows are at [PREVIOUS_MONTH] and [NEXT_MONTH] and the days of
+/// Date::month_grid() at [FIRST_DAY + index]
+#[derive(Debug, Clone)]
+pub struct DatePicker {
+    pub date: Option<Date>,
+    /// Shown when there is no date
+    pub placeholder: String,
+    /// Whether the popup is shown
+    pub open: bool,
+    /// A day of the month the popup shows
+    pub month: Date,
+    /// Gets the new date
+    pub on_change: fn(Event, Date) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        DatePicker {
+            date: None,
+            placeholder: "YYYY-MM-DD".to_string(),
+            open: false,
+            month: Date { year: 1970, month: 1, day: 1 },
+            on_change: |_event, _date| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl DatePicker {
+    pub const PREVIOUS_MONTH: usize = 0;
+    pub const NEXT_MONTH: usize = 1;
+    pub const FIRST_DAY: usize = 2;
+
+    /// What the field shows
+    pub fn text(&self) -> String {
+        self.date.map_or_else(|| self.placeholder.clone(), |d| d.to_string())
+    }
+
+    /// Opens the popup on the month of the date (today's without one) or closes it
+    pub fn toggle(&mut self) -> bool {
+        self.open = !self.open;
+        if self.open {
+            self.month = self.date.unwrap_or_else(Date::today).first_of_month();
+        }
+        true
+    }
+
+    /// Shows the month months after the one shown
+    pub fn show_month(&mut self, months: i32) -> bool {
+        self.month = self.month.add_months(months);
+        true
+    }
+
+    /// Sets the date and closes the popup, on_change is only called when the date changed.
+    /// Returns whether anything changed
+    pub fn select(&mut self, date: Date, position: Option<(i32, i32)>) -> bool {
+        let closed = std::mem::replace(&mut self.open, false);
+        self.month = date.first_of_month();
+        if self.date == Some(date) {
+            return closed;
+        }
+        self.date = Some(date);
+        (self.on_change)(Event { source: date.to_string(), position }, date);
+        true
+    }
+
+    /// Moves the date by days and then by months, keeping the popup open or closed, e.g. with
+    /// the arrows. It starts from today without a date
+    pub fn step_by(&mut self, days: i64, months: i32) -> bool {
+        let open = self.open;
+        let date = self.date.unwrap_or_else(Date::today).add_days(days).add_months(months);
+        let changed = self.select(date, None);
+        self.open = open;
+        changed
+    }
+}
+
+/// Picks a color by its hue on a strip and its saturation and value on a square showing them for
+/// that hue, clicking or dragging on them picks there. The square is at [SQUARE] and the strip at
+/// [HUE]
+#[derive(Debug, Clone)]
+pub struct ColorPicker {
+    /// In degrees, from 0 to 360
+    pub hue: f32,
+    /// From 0 (gray) to 1, left to right on the square
+    pub saturation: f32,
+    /// From 0 (black) to 1, bottom to top on the square
+    pub value: f32,
+    /// Gets the new color
+    pub on_change: fn(Event, Color) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        ColorPicker {
+            hue: 0.,
+            saturation: 1.,
+            value: 1.,
+            on_change: |_event, _color| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl ColorPicker {
+    pub const SQUARE: usize = 0;
+    pub const HUE: usize = 1;
+
+    pub fn color(&self) -> Color {
+        Color::from_hsv(self.hue, self.saturation, self.value)
+    }
+
+    /// Sets the components clamped to their ranges and calls on_change when they changed.
+    /// Returns whether they did
+    pub fn set_hsv(&mut self, hue: f32, saturation: f32, value: f32, position: Option<(i32, i32)>) -> bool {
+        let hsv = (hue.clamp(0., 360.), saturation.clamp(0., 1.), value.clamp(0., 1.));
+        if hsv == (self.hue, self.saturation, self.value) {
+            return false;
+        }
+        (self.hue, self.saturation, self.value) = hsv;
+        (self.on_change)(Event { source: "ColorPicker".to_string(), position }, self.color());
+        true
+    }
+
+    /// Picks color, grays keep the hue shown so the strip doesn't jump
+    pub fn set_color(&mut self, color: Color, position: Option<(i32, i32)>) -> bool {
+        let (hue, saturation, value) = color.to_hsv();
+        let hue = if saturation > 0. { hue } else { self.hue };
+        self.set_hsv(hue, saturation, value, position)
+    }
+}
+
+/// Where the lines of a Label go in its width
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    /// How far from the left a line line_width wide starts in width
+    pub fn offset(&self, line_width: f32, width: f32) -> f32 {
+        let left_over = (width - line_width).max(0.);
+        match self {
+            TextAlign::Left => 0.,
+            TextAlign::Center => left_over / 2.,
+            TextAlign::Right => left_over,
+        }
+    }
+}
+
+/// The font text is drawn in. The engines use the fonts registered in the assets under
+/// assets::BOLD_FONT, ITALIC_FONT and MONOSPACE_FONT, or the regular font when there is none
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FontStyle {
+    #[default]
+    Regular,
+    Bold,
+    Italic,
+    /// For code
+    Monospace,
+}
+
+/// Read-only text wrapped to the width of its parent. With caret browsing (see
+/// accessibility::caret_browsing()) it takes the focus and its text can be walked and selected with
+/// the keyboard
+#[derive(Debug, Clone, Default)]
+pub struct Label {
+    pub text: String,
+    /// The text color of the engine when None
+    pub color: Option<Color>,
+    /// The text size of the engine when None, either is scaled by accessibility::font_scale()
+    pub size: Option<f32>,
+    pub align: TextAlign,
+    /// The keyboard caret, the engines set it while the label has the focus with caret browsing
+    pub caret: Option<Caret>,
+    /// Set by the engines every time it's built, the lines the text was wrapped into
+    pub lines: RefCell<Vec<String>>,
+    pub accessible: Accessible,
+}
+
+impl Label {
+    /// Moves the caret, see Caret::move_by(). Returns whether it moved or the selection changed
+    pub fn move_caret(&mut self, movement: CaretMove, extend: bool) -> bool {
+        let lines = self.lines.borrow();
+        self.caret.as_mut().is_some_and(|c| c.move_by(movement, extend, &lines))
+    }
+
+    /// The text selected with the caret, lines are joined with line breaks
+    pub fn selected_text(&self) -> Option<String> {
+        self.caret.as_ref()?.selected_text(&self.lines.borrow())
+    }
+}
+
+/// Underlined text that opens its url when clicked, or on Return once it has the focus. The
+/// pointer turns into a hand over it
+#[derive(Debug, Clone)]
+pub struct Hyperlink {
+    pub text: String,
+    pub url: String,
+    /// Gets the url, the engine opens it with the platform (e.g. the browser) unless it returns
+    /// true
+    pub on_click: fn(Event, &str) -> bool,
+    /// It was opened, it's drawn in another color
+    pub visited: bool,
+    pub accessible: Accessible,
+}
+
+impl Default for Hyperlink {
+    fn default() -> Self {
+        Hyperlink {
+            text: String::new(),
+            url: String::new(),
+            on_click: |_event, _url| false,
+            visited: false,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Hyperlink {
+    /// Calls on_click, returns the url when the engine should open it
+    pub fn activate(&mut self, position: Option<(i32, i32)>) -> Option<&str> {
+        self.visited = true;
+        let handled = (self.on_click)(Event { source: self.text.clone(), position }, &self.url);
+        match handled {
+            true => None,
+            false => Some(&self.url),
+        }
+    }
+}
+
+/// Text written in Markdown (see the markdown module) shown with its headings, emphasis, lists,
+/// code and links, wrapped to its width. It's as tall as its text, put it in a ScrollPane to
+/// scroll a long one
+#[derive(Debug, Clone)]
+pub struct MarkdownView {
+    pub text: String,
+    pub width: Dimension,
+    /// Gets the url of the link clicked, the engine opens it with the platform (e.g. the browser)
+    /// unless it returns true
+    pub on_link: fn(Event, &str) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for MarkdownView {
+    fn default() -> Self {
+        MarkdownView {
+            text: String::new(),
+            width: Relative(-1),
+            on_link: |_event, _url| false,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl MarkdownView {
+    pub fn document(&self) -> markdown::Document {
+        markdown::parse(&self.text)
+    }
+
+    /// Calls on_link with the url of the link at index (the order the links are written in),
+    /// returns it when the engine should open it
+    pub fn activate(&self, index: usize, position: Option<(i32, i32)>) -> Option<String> {
+        let url = self.document().links.into_iter().nth(index)?;
+        match (self.on_link)(Event { source: "MarkdownView".to_string(), position }, &url) {
+            true => None,
+            false => Some(url),
+        }
+    }
+}
+
+/// Multiline text wrapped to its width, the mouse wheel scrolls it once it's taller than its
+/// height. An editable one takes the focus and what is typed goes at the end of its text, line
+/// breaks included
+#[derive(Debug, Clone)]
+pub struct TextArea {
+    pub text: String,
+    pub editable: bool,
+    pub width: Dimension,
+    pub height: Dimension,
+    /// How far the text is scrolled down, in pixels
+    pub scroll: f32,
+    /// Set by the engines every time it's built, the scrolling stops at the end of the text
+    pub extent: Cell<ScrollExtent>,
+    pub accessible: Accessible,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        TextArea {
+            text: String::new(),
+            editable: false,
+            width: Relative(-1),
+            height: Dimension::Pixels(120),
+            scroll: 0.,
+            extent: Cell::new(ScrollExtent::default()),
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl TextArea {
+    /// How far it can be scrolled down
+    pub fn max_scroll(&self) -> f32 {
+        let ScrollExtent { content, viewport } = self.extent.get();
+        (content.1 - viewport.1).max(0.)
+    }
+
+    /// The scroll clamped to the text, what is shown when the text shrank since scrolling
+    pub fn clamped_scroll(&self) -> f32 {
+        self.scroll.clamp(0., self.max_scroll())
+    }
+
+    /// Scrolls by dy pixels as far as the text goes, returns whether it moved
+    pub fn scroll_by(&mut self, dy: f32) -> bool {
+        let scroll = (self.clamped_scroll() + dy).clamp(0., self.max_scroll());
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        changed
+    }
+
+    /// Start and length of the scrollbar thumb as fractions of its track, None when the whole
+    /// text is shown
+    pub fn thumb(&self) -> Option<(f32, f32)> {
+        let ScrollExtent { content, viewport } = self.extent.get();
+        let max = self.max_scroll();
+        if max <= 0. {
+            return None;
+        }
+        let length = (viewport.1 / content.1).clamp(ScrollPane::MIN_THUMB, 1.);
+        Some((self.clamped_scroll() / max * (1. - length), length))
+    }
+
+    /// Adds text at the end and scrolls down to it, returns whether anything was added
+    pub fn insert(&mut self, text: &str) -> bool {
+        self.text.push_str(text);
+        // Clamped to the new end of the text once it's built again
+        self.scroll = f32::MAX;
+        !text.is_empty()
+    }
+
+    /// Removes the last character, returns whether there was one
+    pub fn backspace(&mut self) -> bool {
+        self.scroll = f32::MAX;
+        self.text.pop().is_some()
+    }
+}
+
+/// The output of a program (a build, a server, a script...) as it's written, colored by its ANSI
+/// escape sequences (see the ansi module). It keeps the last capacity lines and shows visible_rows
+/// of them starting at scroll, following the output down while the last line is shown. Other
+/// threads write to it with ansi::write_console() through the source it's given
+#[derive(Debug, Clone)]
+pub struct Console {
+    /// Names it for ansi::write_console()
+    pub source: String,
+    pub lines: VecDeque<Vec<AnsiSpan>>,
+    /// The most lines kept, the oldest are dropped to make room
+    pub capacity: usize,
+    pub visible_rows: usize,
+    /// The first visible line
+    pub scroll: usize,
+    /// Whether it scrolls down to what is written, scrolling to the last line turns it back on
+    pub follow: bool,
+    /// The style the next text is written in, escape sequences carry over from one write to the next
+    pub style: AnsiStyle,
+    /// Whether the last line was written without its line break, what follows goes on it
+    pub partial: bool,
+    pub accessible: Accessible,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Console {
+            source: String::new(),
+            lines: VecDeque::new(),
+            capacity: 1000,
+            visible_rows: 10,
+            scroll: 0,
+            follow: true,
+            style: AnsiStyle::default(),
+            partial: false,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Console {
+    /// Adds the output at the end. A carriage return (but the one of \r\n) starts its line over
+    /// like a progress bar does
+    pub fn write(&mut self, text: &str) {
+        let mut pieces = text.split('\n').peekable();
+        while let Some(piece) = pieces.next() {
+            let last = pieces.peek().is_none();
+            // Nothing after the last line break
+            if last && piece.is_empty() {
+                self.partial = false;
+                break;
+            }
+            let piece = piece.strip_suffix('\r').unwrap_or(piece);
+            let (restart, piece) = match piece.rsplit_once('\r') {
+                Some((_, after)) => (true, after),
+                None => (false, piece),
+            };
+            let spans = ansi::parse(piece, &mut self.style);
+            match self.lines.back_mut() {
+                Some(line) if self.partial => {
+                    if restart {
+                        line.clear();
+                    }
+                    line.extend(spans);
+                }
+                _ => self.push_line(spans),
+            }
+            self.partial = last;
+        }
+        if self.follow {
+            self.scroll = self.last_scroll();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.scroll = 0;
+        self.partial = false;
+    }
+
+    fn push_line(&mut self, spans: Vec<AnsiSpan>) {
+        self.lines.push_back(spans);
+        while self.lines.len() > self.capacity.max(1) {
+            self.lines.pop_front();
+            // What is shown stays put as the lines above it go
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+    }
+
+    /// The lines that are shown
+    pub fn visible_range(&self) -> std::ops::Range<usize> {
+        let end = (self.scroll + self.visible_rows).min(self.lines.len());
+        self.scroll.min(end)..end
+    }
+
+    /// The scroll that shows the last line at the bottom
+    fn last_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.visible_rows)
+    }
+
+    /// Scrolls by rows (negative toward the first line), following the output again once at the
+    /// last line. Returns whether it moved
+    pub fn scroll_by(&mut self, rows: i32) -> bool {
+        let scroll = (self.scroll as i64 + rows as i64).clamp(0, self.last_scroll() as i64) as usize;
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        self.follow = scroll == self.last_scroll();
+        changed
+    }
+
+    /// Start and length of the scrollbar thumb as fractions of its track, None when every line
+    /// is shown
+    pub fn thumb(&self) -> Option<(f32, f32)> {
+        let last = self.last_scroll();
+        if last == 0 {
+            return None;
+        }
+        let length = (self.visible_rows as f32 / self.lines.len() as f32).clamp(ScrollPane::MIN_THUMB, 1.);
+        Some((self.scroll.min(last) as f32 / last as f32 * (1. - length), length))
+    }
+
+    /// The text of the lines without the escape sequences
+    pub fn text(&self) -> String {
+        let line = |spans: &Vec<AnsiSpan>| spans.iter().map(|s| s.text.as_str()).collect::<String>();
+        self.lines.iter().map(line).collect::<Vec<String>>().join("\n")
+    }
+}
+
+/// Rows of text from a ListModel, clicking selects them. It shows visible_rows rows at a time
+/// starting at scroll, the arrow keys move the selection once it has the focus
+#[derive(Debug, Clone)]
+pub struct ListView {
+    pub model: ListModel,
+    pub visible_rows: usize,
+    /// The first visible row
+    pub scroll: usize,
+    /// Scrolling past the first or last row, in rows
+    pub overscroll: Overscroll,
+    /// Rows loaded page by page while it scrolls near the end, see ListView::paged()
+    pub paged: Option<PagedRows>,
+    /// Groups of consecutive rows under a header that sticks to the top while its section is
+    /// scrolled. Headers take a line like rows, see sections::line_of_row()
+    pub sections: Vec<Section>,
+    pub accessible: Accessible,
+}
+
+impl Default for ListView {
+    fn default() -> Self {
+        ListView {
+            model: ListModel::default(),
+            visible_rows: 6,
+            scroll: 0,
+            overscroll: Overscroll::default(),
+            paged: None,
+            sections: vec![],
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl ListView {
+    /// The headers of the sections are hit tested as [SECTION_HEADERS, section], rows as [row]
+    pub const SECTION_HEADERS: usize = 0;
+
+    /// Shows the first cell of the rows of paged, its first page is requested right away
+    pub fn paged(mut rows: PagedRows) -> Self {
+        rows.request_next_page();
+        ListView {
+            model: ListModel::new(rows.rows.iter().map(|row| row.first().cloned().unwrap_or_default()).collect()),
+            paged: Some(rows),
+            ..Default::default()
+        }
+    }
+
+    /// The rows of the model plus the loading row while a page is loading, which comes last
+    pub fn row_count(&self) -> usize {
+        self.model.len() + self.paged.as_ref().is_some_and(|p| p.is_loading()) as usize
+    }
+
+    /// The rows that are shown
+    pub fn visible_range(&self) -> std::ops::Range<usize> {
+        let end = row_at_line(&self.sections, self.scroll + self.visible_rows).min(self.row_count());
+        row_at_line(&self.sections, self.scroll).min(end)..end
+    }
+
+    /// The rows plus the headers of the sections, what scroll counts
+    pub fn line_count(&self) -> usize {
+        line_of_row(&self.sections, self.row_count())
+    }
+
+    /// The sections laid out with headers as high as the rows, None when there are none
+    pub fn section_layout(&self, row_height: f32) -> Option<SectionLayout> {
+        match self.sections.is_empty() {
+            true => None,
+            false => Some(SectionLayout::new(self.sections.clone(), row_height, row_height)),
+        }
+    }
+
+    /// The visible rows with their y in the list, below the headers of their sections
+    pub fn row_positions(&self, row_height: f32) -> Vec<(usize, f32)> {
+        match self.section_layout(row_height) {
+            Some(layout) => {
+                let height = row_height * self.visible_rows as f32;
+                layout.visible_rows(self.scroll as f32 * row_height, height).into_iter()
+                    .filter(|(row, _)| *row < self.row_count())
+                    .collect()
+            }
+            None => self.visible_range().enumerate().map(|(i, row)| (row, row_height * i as f32)).collect(),
+        }
+    }
+
+    /// Scrolls the least so row is visible, below the sticky header of its section if any
+    pub fn scroll_to(&mut self, row: usize) {
+        let line = line_of_row(&self.sections, row);
+        let top = line - !self.sections.is_empty() as usize;
+        if top < self.scroll {
+            self.scroll = top;
+        } else if line >= self.scroll + self.visible_rows {
+            self.scroll = line + 1 - self.visible_rows.max(1);
+        }
+        self.load_more();
+    }
+
+    /// Scrolls by lines (negative toward the first row), what goes past the rows becomes
+    /// overscroll. Returns whether anything changed, a page starting to load included
+    pub fn scroll_by(&mut self, rows: i32) -> bool {
+        let last = self.line_count().saturating_sub(self.visible_rows) as i64;
+        let wanted = self.scroll as i64 + rows as i64;
+        let scroll = wanted.clamp(0, last) as usize;
+        let overscrolled = self.overscroll.push((wanted - scroll as i64) as f32);
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        let requested = self.load_more();
+        changed || overscrolled || requested
+    }
+
+    /// Requests the next page of the paged rows when the last visible row is close to the end.
+    /// Returns whether it was requested
+    fn load_more(&mut self) -> bool {
+        let last_visible = self.visible_range().end.saturating_sub(1);
+        self.paged.as_mut().is_some_and(|p| p.scrolled_to(last_visible))
+    }
+
+    /// Appends the rows of delivery when the paged rows requested them. Returns whether they did
+    pub fn deliver_page(&mut self, delivery: &PageDelivery) -> bool {
+        let paged = match self.paged.as_mut() {
+            Some(paged) => paged,
+            None => return false,
+        };
+        if !paged.deliver(delivery) {
+            return false;
+        }
+        let items = paged.rows.iter().map(|row| row.first().cloned().unwrap_or_default()).collect();
+        self.model.set_items(items);
+        // A short page may still leave the end in sight
+        self.load_more();
+        true
+    }
+
+    /// Handles a click on row, returns whether the selection changed
+    pub fn click(&mut self, row: usize, modifiers: SelectModifiers) -> bool {
+        self.model.click(row, modifiers)
+    }
+
+    /// Starts a rubber band at (x, y) in the list. Pressed on a row, which the press selected, the
+    /// band adds to it, on empty space it replaces the selection. Returns whether it changed
+    pub fn begin_rubber_band(&mut self, x: f32, y: f32, on_row: bool) -> bool {
+        self.model.selection.begin_rubber_band(x, y, SelectModifiers { ctrl: on_row, shift: false })
+    }
+
+    /// Moves the end of the rubber band, the visible rows it touches are selected. Rows are laid
+    /// out by row_positions(), each row_height high. Returns whether the selection changed
+    pub fn drag_rubber_band(&mut self, x: f32, y: f32, width: f32, row_height: f32) -> bool {
+        let items: Vec<SelectionItem> = self.row_positions(row_height).into_iter()
+            .filter(|(row, _)| *row < self.model.len())
+            .map(|(row, y)| SelectionItem { id: row, bounds: (0., y, width, row_height) })
+            .collect();
+        self.model.selection.drag_rubber_band(x, y, &items)
+    }
+
+    /// Moves the selection by delta rows keeping it visible, returns whether anything changed
+    pub fn move_selection(&mut self, delta: i32) -> bool {
+        let scroll = self.scroll;
+        let selected = self.model.selection.selected().to_vec();
+        match self.model.move_selection(delta) {
+            Some(row) => {
+                self.scroll_to(row);
+                self.scroll != scroll || self.model.selection.selected() != selected.as_slice()
+            }
+            None => false,
+        }
+    }
+}
+
+/// Rows of a TableModel under a header with the column titles, shown through a SortFilterProxy.
+/// Clicking a header sorts by its column and clicking it again flips the order, dragging the
+/// border at its right resizes it. The columns after the frozen ones of its ColumnLayout scroll
+/// sideways with the wheel, shift and the wheel or the scrollbar below the rows. The header cells
+/// are hit tested as [HEADER, column], their borders as [COLUMN_BORDER, column], the rows as
+/// [ROWS, row] and the scrollbar as [HORIZONTAL_SCROLLBAR]
+#[derive(Debug, Clone)]
+pub struct TableView {
+    pub model: VecTableModel,
+    pub proxy: SortFilterProxy,
+    pub columns: ColumnLayout,
+    pub visible_rows: usize,
+    /// The first visible row of the proxy
+    pub scroll: usize,
+    /// How far the columns after the frozen ones are scrolled left, in pixels
+    pub scroll_x: f32,
+    /// Set by the engines every time it's built, the horizontal scrolling stops at the last column
+    pub viewport_width: Cell<f32>,
+    /// Gets the sort key of the clicked column
+    pub on_sort: fn(Event, SortKey) -> bool,
+    /// Rows of the model loaded page by page while it scrolls near the end, see TableView::paged()
+    pub paged: Option<PagedRows>,
+    /// Cells wrap their text and each row is as high as its tallest cell, otherwise rows are a
+    /// line high and long cells are cut. Rows are a line high anyway when there are sections
+    pub wrap_cells: bool,
+    /// Groups of consecutive rows of the proxy under a header that sticks below the column titles
+    /// while its section is scrolled, like the sections of a ListView
+    pub sections: Vec<Section>,
+    /// Set by the engines every time it's built, the heights of the wrapped rows of the model
+    pub row_heights: RefCell<RowHeights>,
+    /// The ids are rows of the model, so sorting and filtering keep the selected ones
+    pub selection: Selection,
+    pub accessible: Accessible,
+}
+
+impl Default for TableView {
+    fn default() -> Self {
+        TableView {
+            model: VecTableModel::default(),
+            proxy: SortFilterProxy::default(),
+            columns: ColumnLayout::default(),
+            visible_rows: 6,
+            scroll: 0,
+            scroll_x: 0.,
+            viewport_width: Cell::new(0.),
+            on_sort: |_event, _key| true,
+            paged: None,
+            wrap_cells: false,
+            sections: vec![],
+            row_heights: RefCell::new(RowHeights::new(0., 0.)),
+            selection: Selection::default(),
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl TableView {
+    pub const HEADER: usize = 0;
+    pub const COLUMN_BORDER: usize = 1;
+    pub const ROWS: usize = 2;
+    pub const SECTION_HEADERS: usize = 3;
+    pub const HORIZONTAL_SCROLLBAR: usize = 4;
+    /// The narrowest a column can be resized to
+    pub const MIN_COLUMN_WIDTH: f32 = 24.;
+
+    /// Shows every row of model in its order, with a column of each width
+    pub fn new(model: VecTableModel, widths: Vec<f32>) -> Self {
+        TableView {
+            proxy: SortFilterProxy::new(&model),
+            columns: ColumnLayout::new(widths, 0),
+            model,
+            ..Default::default()
+        }
+    }
+
+    /// Shows the rows of paged under the columns with their widths, its first page is requested
+    /// right away
+    pub fn paged(columns: Vec<String>, widths: Vec<f32>, mut rows: PagedRows) -> Self {
+        rows.request_next_page();
+        let model = VecTableModel { columns, rows: rows.rows.clone() };
+        TableView {
+            paged: Some(rows),
+            ..TableView::new(model, widths)
+        }
+    }
+
+    /// The rows of the proxy plus the loading row while a page is loading, which comes last
+    pub fn row_count(&self) -> usize {
+        self.proxy.row_count() + self.paged.as_ref().is_some_and(|p| p.is_loading()) as usize
+    }
+
+    /// The rows of the proxy that are shown
+    pub fn visible_range(&self) -> std::ops::Range<usize> {
+        let end = row_at_line(&self.sections, self.scroll + self.visible_rows).min(self.row_count());
+        row_at_line(&self.sections, self.scroll).min(end)..end
+    }
+
+    /// The sections laid out with headers as high as the rows, None when there are none
+    pub fn section_layout(&self, row_height: f32) -> Option<SectionLayout> {
+        match self.sections.is_empty() {
+            true => None,
+            false => Some(SectionLayout::new(self.sections.clone(), row_height, row_height)),
+        }
+    }
+
+    /// Where the visible rows go below top: their row of the proxy, y and height, those starting
+    /// at bottom or past it are left out. height gives the height of a row of the proxy
+    pub fn row_layout(&self, top: f32, bottom: f32, height: &mut dyn FnMut(usize) -> f32) -> Vec<(usize, f32, f32)> {
+        let mut y = top;
+        let mut rows = vec![];
+        for row in self.visible_range() {
+            if y >= bottom {
+                break;
+            }
+            let row_height = height(row);
+            rows.push((row, y, row_height));
+            y += row_height;
+        }
+        rows
+    }
+
+    /// Scrolls by lines (negative toward the first row), returns whether it scrolled or a page
+    /// started to load. Wrapped rows scroll until the last one is at the top, since fewer than
+    /// visible_rows may fit
+    pub fn scroll_by(&mut self, rows: i32) -> bool {
+        let shown = if self.wrap_cells && self.sections.is_empty() { 1 } else { self.visible_rows };
+        let last = line_of_row(&self.sections, self.row_count()).saturating_sub(shown) as i64;
+        let scroll = (self.scroll as i64 + rows as i64).clamp(0, last) as usize;
+        let scrolled = std::mem::replace(&mut self.scroll, scroll) != scroll;
+        let requested = self.load_more();
+        scrolled || requested
+    }
+
+    /// How far the columns after the frozen ones can be scrolled left
+    pub fn max_scroll_x(&self) -> f32 {
+        self.columns.max_scroll(self.viewport_width.get())
+    }
+
+    /// scroll_x clamped to the columns, what is shown when they got narrower since scrolling
+    pub fn clamped_scroll_x(&self) -> f32 {
+        self.scroll_x.clamp(0., self.max_scroll_x())
+    }
+
+    /// Scrolls the columns after the frozen ones by dx pixels as far as they go, returns whether
+    /// they moved
+    pub fn scroll_x_by(&mut self, dx: f32) -> bool {
+        let scroll_x = (self.clamped_scroll_x() + dx).clamp(0., self.max_scroll_x());
+        std::mem::replace(&mut self.scroll_x, scroll_x) != scroll_x
+    }
+
+    /// Scrolls the columns to fraction (0.0 - 1.0) of the way to the last one, returns whether they
+    /// moved
+    pub fn set_scroll_x_fraction(&mut self, fraction: f32) -> bool {
+        let scroll_x = fraction.clamp(0., 1.) * self.max_scroll_x();
+        std::mem::replace(&mut self.scroll_x, scroll_x) != scroll_x
+    }
+
+    /// Start and length of the thumb of the horizontal scrollbar as fractions of its track, None
+    /// when every column fits and there is no scrollbar
+    pub fn horizontal_thumb(&self) -> Option<(f32, f32)> {
+        let max = self.max_scroll_x();
+        if max <= 0. {
+            return None;
+        }
+        let length = (self.viewport_width.get() / self.columns.total_width()).clamp(ScrollPane::MIN_THUMB, 1.);
+        Some((self.clamped_scroll_x() / max * (1. - length), length))
+    }
+
+    /// Requests the next page of the paged rows when the last visible row is close to the end.
+    /// Rows the proxy filters out are skipped, so it's measured from the end of the proxy
+    fn load_more(&mut self) -> bool {
+        let left = self.proxy.row_count().saturating_sub(self.visible_range().end);
+        self.paged.as_mut().is_some_and(|p| p.scrolled_to(p.rows.len().saturating_sub(left + 1)))
+    }
+
+    /// Appends the rows of delivery to the model when the paged rows requested them. Returns
+    /// whether they did
+    pub fn deliver_page(&mut self, delivery: &PageDelivery) -> bool {
+        let paged = match self.paged.as_mut() {
+            Some(paged) => paged,
+            None => return false,
+        };
+        if !paged.deliver(delivery) {
+            return false;
+        }
+        self.model.rows = paged.rows.clone();
+        // Also requests the next page when a short one leaves the end in sight
+        self.model_changed();
+        true
+    }
+
+    /// The order of column in the sorting, if it's sorted by it
+    pub fn sort_order(&self, column: usize) -> Option<SortOrder> {
+        self.proxy.sort_keys().iter().find(|k| k.column == column).map(|k| k.order)
+    }
+
+    /// What a click on the header of column does, see SortFilterProxy::header_clicked(). Returns
+    /// whether it was a column
+    pub fn sort_by(&mut self, column: usize, position: Option<(i32, i32)>) -> bool {
+        if column >= self.model.column_count() {
+            return false;
+        }
+        self.proxy.header_clicked(column, false, &self.model);
+        let order = self.sort_order(column).unwrap_or(SortOrder::Ascending);
+        (self.on_sort)(Event { source: self.model.column_title(column), position }, SortKey { column, order });
+        true
+    }
+
+    /// Changes the width of column, no narrower than MIN_COLUMN_WIDTH. Returns whether it changed
+    pub fn resize_column(&mut self, column: usize, width: f32) -> bool {
+        let width = width.max(Self::MIN_COLUMN_WIDTH);
+        match self.columns.widths().get(column) {
+            Some(current) if *current != width => {
+                self.columns.resize(column, width);
+                self.row_heights.get_mut().invalidate_column(column);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles a click on the row of the proxy, returns whether the selection changed
+    pub fn click(&mut self, row: usize, modifiers: SelectModifiers) -> bool {
+        let id = match self.proxy.map_to_model(row) {
+            Some(id) => id,
+            None => return false,
+        };
+        // One unit high each so ranges follow the order of the proxy
+        let items: Vec<SelectionItem> = (0..self.proxy.row_count())
+            .filter_map(|row| self.proxy.map_to_model(row).map(|id| (row, id)))
+            .map(|(row, id)| SelectionItem { id, bounds: (0., row as f32, 1., 1.) })
+            .collect();
+        self.selection.click(id, modifiers, &items)
+    }
+
+    /// Whether the row of the proxy is selected
+    pub fn is_row_selected(&self, row: usize) -> bool {
+        self.proxy.map_to_model(row).is_some_and(|id| self.selection.is_selected(id))
+    }
+
+    /// The selected rows as tab separated values in the order they are shown, what Ctrl+C copies.
+    /// None when nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        let rows: Vec<usize> = (0..self.proxy.row_count()).filter(|row| self.is_row_selected(*row)).collect();
+        match rows.is_empty() {
+            true => None,
+            false => {
+                let rows = ExportRows::Selected(&self.proxy, &rows);
+                Some(to_delimited(&self.model, rows, &DelimitedOptions::tsv()))
+            }
+        }
+    }
+
+    /// Has to be called after changing the model, so the proxy shows its rows. The selected rows
+    /// that no longer exist are unselected
+    pub fn model_changed(&mut self) {
+        self.proxy.invalidate(&self.model);
+        self.row_heights.get_mut().clear();
+        let rows = self.model.rows.len();
+        let selected = self.selection.selected().iter().copied().filter(|id| *id < rows).collect();
+        self.selection.set_selected(selected);
+        self.scroll_by(0);
+    }
+}
+
+/// A page of a TabPane, its title is shown in the tab strip
+#[derive(Debug, Clone, Default)]
+pub struct Tab {
+    pub title: String,
+    pub content: Container,
+}
+
+/// Named pages of components where only the active one is shown, under a strip with their
+/// titles. The hit areas of the strip are [tab] and the components of a page are at
+/// [tab, index...]
+#[derive(Debug, Clone)]
+pub struct TabPane {
+    pub tabs: Vec<Tab>,
+    pub active: usize,
+    /// Gets the index of the new active tab
+    pub on_change: fn(Event, usize) -> bool,
+}
+
+impl Default for TabPane {
+    fn default() -> Self {
+        TabPane {
+            tabs: vec![],
+            active: 0,
+            on_change: |_event, _index| true,
+        }
+    }
+}
+
+impl TabPane {
+    pub fn active_tab(&self) -> Option<&Tab> {
+        self.tabs.get(self.active)
+    }
+
+    pub fn active_content_mut(&mut self) -> Option<&mut Container> {
+        self.tabs.get_mut(self.active).map(|t| &mut t.content)
+    }
+
+    /// Shows the tab at index, on_change is only called when it wasn't already shown. Returns
+    /// whether it changed
+    pub fn select(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index >= self.tabs.len() || index == self.active {
+            return false;
+        }
+        self.active = index;
+        (self.on_change)(Event { source: self.tabs[index].title.clone(), position }, index);
+        true
+    }
+
+    /// The component at path, the index of the tab followed by the path in its content
+    pub fn child_at_mut(&mut self, path: &[usize]) -> Option<&mut Box<dyn Component>> {
+        let (tab, rest) = path.split_first()?;
+        self.tabs.get_mut(*tab)?.content.child_at_mut(rest)
+    }
+
+    /// Only the components of the active tab can take the focus
+    pub fn focusable_paths(&self) -> Vec<Vec<usize>> {
+        match self.active_tab() {
+            Some(tab) => tab.content.focusable_paths().into_iter().map(|mut path| {
+                path.insert(0, self.active);
+                path
+            }).collect(),
+            None => vec![],
+        }
+    }
+
+    /// Closes the popups of the active tab but the one at keep, see Container::close_popups()
+    pub fn close_popups(&mut self, keep: &[usize]) -> bool {
+        let active = self.active;
+        let kept = match keep.split_first() {
+            Some((tab, rest)) if *tab == active => rest,
+            _ => &[],
+        };
+        self.active_content_mut().is_some_and(|c| c.close_popups(kept))
+    }
+}
+
+/// How long a Carousel takes to slide from a page to the next
+const CAROUSEL_TRANSITION: Duration = Duration::from_millis(300);
+/// How far a page has to be dragged, as a fraction of its width, to go to the one next to it
+const CAROUSEL_SWIPE_THRESHOLD: f32 = 0.25;
+
+/// The timer of an autoplaying Carousel, it only raises the flag since the timers can't reach the
+/// component
+#[derive(Debug, Clone)]
+struct AutoplayTimer {
+    id: TimerId,
+    due: Arc<AtomicBool>,
+}
+
+/// Where the pages of a Carousel are while they slide or are dragged, and its autoplay timer.
+/// Clones share the timer
+#[derive(Debug, Clone, Default)]
+pub struct CarouselMotion {
+    /// How many pages the shown position is before the active page while it slides there
+    offset: f32,
+    /// The offset when the page started being dragged
+    drag_start: Option<f32>,
+    timer: Option<AutoplayTimer>,
+}
+
+/// Pages of components side by side of which one is shown, they slide to another one with the
+/// arrows on its sides, the dots under it, the arrow keys or by dragging the page. The arrows are
+/// hit at [PREVIOUS] and [NEXT], the dots at [DOTS, page] and the components of a page at [PAGES,
+/// page, index...]
+#[derive(Debug, Clone)]
+pub struct Carousel {
+    pub pages: Vec<Container>,
+    pub active: usize,
+    /// Height of the pages, the dots are under them
+    pub height: Dimension,
+    /// Goes to the next page that often, back to the first one after the last. Dragging or
+    /// changing the page starts the interval again
+    pub autoplay: Option<Duration>,
+    /// Gets the index of the new active page
+    pub on_change: fn(Event, usize) -> bool,
+    pub accessible: Accessible,
+    /// See position() and start_drag()
+    pub motion: CarouselMotion,
+}
+
+impl Default for Carousel {
+    fn default() -> Self {
+        Carousel {
+            pages: vec![],
+            active: 0,
+            height: Dimension::Pixels(160),
+            autoplay: None,
+            on_change: |_event, _index| true,
+            accessible: Accessible::default(),
+            motion: CarouselMotion::default(),
+        }
+    }
+}
+
+impl Carousel {
+    pub const PREVIOUS: usize = 0;
+    pub const NEXT: usize = 1;
+    pub const DOTS: usize = 2;
+    pub const PAGES: usize = 3;
+
+    pub fn active_page(&self) -> Option<&Container> {
+        self.pages.get(self.active)
+    }
+
+    pub fn active_page_mut(&mut self) -> Option<&mut Container> {
+        self.pages.get_mut(self.active)
+    }
+
+    /// Where the pages are shown, in pages from the first one. It's between the active page and
+    /// the one it's sliding from or dragged towards
+    pub fn position(&self) -> f32 {
+        self.active as f32 - self.motion.offset
+    }
+
+    /// Slides to the page at index, on_change is only called when it wasn't already the active
+    /// one. Returns whether it changed
+    pub fn go_to(&mut self, index: usize, position: Option<(i32, i32)>) -> bool {
+        if index >= self.pages.len() || index == self.active {
+            return false;
+        }
+        // The shown position stays where it is and slides from there
+        self.motion.offset += index as f32 - self.active as f32;
+        if accessibility::reduce_motion() {
+            self.motion.offset = 0.;
+        }
+        self.active = index;
+        self.restart_autoplay();
+        (self.on_change)(Event { source: "Carousel".to_string(), position }, index);
+        true
+    }
+
+    pub fn previous(&mut self, position: Option<(i32, i32)>) -> bool {
+        self.active > 0 && self.go_to(self.active - 1, position)
+    }
+
+    pub fn next(&mut self, position: Option<(i32, i32)>) -> bool {
+        self.go_to(self.active + 1, position)
+    }
+
+    pub fn start_drag(&mut self) {
+        self.motion.drag_start = Some(self.motion.offset);
+        self.restart_autoplay();
+    }
+
+    /// Moves the pages by the fraction of their width dragged since start_drag(), towards the
+    /// previous page when it's positive. Returns whether they moved
+    pub fn drag_by(&mut self, fraction: f32) -> bool {
+        let start = match self.motion.drag_start {
+            Some(start) => start,
+            None => return false,
+        };
+        let last = self.pages.len().saturating_sub(1) as f32;
+        let offset = (start + fraction).clamp(self.active as f32 - last, self.active as f32);
+        let moved = offset != self.motion.offset;
+        self.motion.offset = offset;
+        moved
+    }
+
+    /// Goes to the page the pages were dragged towards when they went far enough, otherwise they
+    /// slide back. Returns whether the active page changed
+    pub fn end_drag(&mut self, position: Option<(i32, i32)>) -> bool {
+        if self.motion.drag_start.take().is_none() {
+            return false;
+        }
+        if self.motion.offset > CAROUSEL_SWIPE_THRESHOLD {
+            self.previous(position)
+        } else if self.motion.offset < -CAROUSEL_SWIPE_THRESHOLD {
+            self.next(position)
+        } else {
+            false
+        }
+    }
+
+    fn restart_autoplay(&mut self) {
+        if let Some(timer) = self.motion.timer.take() {
+            timers::cancel(timer.id);
+        }
+    }
+
+    /// Slides the pages towards the active one and goes to the next page when the autoplay timer
+    /// went off, returns whether they moved. Nothing slides when reducing motion
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        let mut moved = false;
+        if self.motion.drag_start.is_none() && self.motion.offset != 0. {
+            let step = match accessibility::reduce_motion() {
+                true => f32::MAX,
+                false => elapsed.as_secs_f32() / CAROUSEL_TRANSITION.as_secs_f32(),
+            };
+            self.motion.offset = match self.motion.offset > 0. {
+                true => (self.motion.offset - step).max(0.),
+                false => (self.motion.offset + step).min(0.),
+            };
+            moved = true;
+        }
+        match (self.autoplay, &self.motion.timer) {
+            (Some(interval), None) if self.pages.len() > 1 && self.motion.drag_start.is_none() => {
+                let due = Arc::new(AtomicBool::new(false));
+                let flag = due.clone();
+                let id = timers::schedule(interval, move || flag.store(true, Ordering::SeqCst));
+                self.motion.timer = Some(AutoplayTimer { id, due });
+            }
+            (Some(_), Some(timer)) if timer.due.load(Ordering::SeqCst) => {
+                self.motion.timer = None;
+                let next = (self.active + 1) % self.pages.len();
+                moved |= self.go_to(next, None);
+            }
+            _ => {}
+        }
+        moved
+    }
+
+    /// The component at path, PAGES and the index of the page followed by the path in it
+    pub fn child_at_mut(&mut self, path: &[usize]) -> Option<&mut Box<dyn Component>> {
+        match path {
+            [Carousel::PAGES, page, rest @ ..] => self.pages.get_mut(*page)?.child_at_mut(rest),
+            _ => None,
+        }
+    }
+
+    /// The Carousel itself and the components of the active page
+    pub fn focusable_paths(&self) -> Vec<Vec<usize>> {
+        let mut paths = vec![vec![]];
+        if let Some(page) = self.active_page() {
+            paths.extend(page.focusable_paths().into_iter().map(|mut path| {
+                path.splice(0..0, [Carousel::PAGES, self.active]);
+                path
+            }));
+        }
+        paths
+    }
+
+    /// Closes the popups of the active page but the one at keep, see Container::close_popups()
+    pub fn close_popups(&mut self, keep: &[usize]) -> bool {
+        let active = self.active;
+        let kept = match keep {
+            [Carousel::PAGES, page, rest @ ..] if *page == active => rest,
+            _ => &[],
+        };
+        self.active_page_mut().is_some_and(|p| p.close_popups(kept))
+    }
+}
+
+/// Sizes a ScrollPane had when it was last built
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollExtent {
+    /// Size of everything in the content
+    pub content: (f32, f32),
+    /// Size of the part that is shown, the pane without its scrollbars
+    pub viewport: (f32, f32),
+}
+
+/// Shows the part of its content that fits in its size, the rest is reached with the mouse wheel
+/// or the scrollbars. The components of the content are at [CONTENT, index...] and the scrollbars
+/// at [VERTICAL_SCROLLBAR] and [HORIZONTAL_SCROLLBAR]
+#[derive(Debug, Clone)]
+pub struct ScrollPane {
+    pub width: Dimension,
+    pub height: Dimension,
+    pub content: Container,
+    /// How far the content is scrolled right and down, in pixels
+    pub scroll: (f32, f32),
+    /// Set by the engines every time it's built, the scrolling stops at the end of the content
+    pub extent: Cell<ScrollExtent>,
+}
+
+impl Default for ScrollPane {
+    fn default() -> Self {
+        ScrollPane {
+            width: Relative(-1),
+            height: Dimension::Pixels(200),
+            content: Container::default(),
+            scroll: (0., 0.),
+            extent: Cell::new(ScrollExtent::default()),
+        }
+    }
+}
+
+impl ScrollPane {
+    pub const CONTENT: usize = 0;
+    pub const VERTICAL_SCROLLBAR: usize = 1;
+    pub const HORIZONTAL_SCROLLBAR: usize = 2;
+    /// The shortest a scrollbar thumb gets, as a fraction of its track
+    const MIN_THUMB: f32 = 0.1;
+
+    /// What is left of size for the content once there are scrollbars for what doesn't fit, a
+    /// scrollbar for one axis can leave too little for the other one
+    pub fn viewport_for(content: (f32, f32), size: (f32, f32), scrollbar: f32) -> (f32, f32) {
+        let vertical = content.1 > size.1;
+        let horizontal = content.0 > size.0 - if vertical { scrollbar } else { 0. };
+        let vertical = vertical || content.1 > size.1 - if horizontal { scrollbar } else { 0. };
+        (size.0 - if vertical { scrollbar } else { 0. }, size.1 - if horizontal { scrollbar } else { 0. })
+    }
+
+    /// How far it can be scrolled right and down
+    pub fn max_scroll(&self) -> (f32, f32) {
+        let ScrollExtent { content, viewport } = self.extent.get();
+        ((content.0 - viewport.0).max(0.), (content.1 - viewport.1).max(0.))
+    }
+
+    /// The scroll clamped to the content, what is shown when the content shrank since scrolling
+    pub fn clamped_scroll(&self) -> (f32, f32) {
+        let (max_x, max_y) = self.max_scroll();
+        (self.scroll.0.clamp(0., max_x), self.scroll.1.clamp(0., max_y))
+    }
+
+    /// Scrolls by dx and dy pixels as far as the content goes, returns whether it moved
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) -> bool {
+        let (x, y) = self.clamped_scroll();
+        let (max_x, max_y) = self.max_scroll();
+        let scroll = ((x + dx).clamp(0., max_x), (y + dy).clamp(0., max_y));
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        changed
+    }
+
+    /// Scrolls to fraction (0.0 - 1.0) of the way along the vertical or horizontal axis, returns
+    /// whether it moved
+    pub fn set_scroll_fraction(&mut self, vertical: bool, fraction: f32) -> bool {
+        let (max_x, max_y) = self.max_scroll();
+        let (x, y) = self.clamped_scroll();
+        let fraction = fraction.clamp(0., 1.);
+        let scroll = match vertical {
+            true => (x, fraction * max_y),
+            false => (fraction * max_x, y),
+        };
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        changed
+    }
+
+    /// Start and length of the thumb of the vertical or horizontal scrollbar as fractions of its
+    /// track, None when everything fits and there is no scrollbar
+    pub fn thumb(&self, vertical: bool) -> Option<(f32, f32)> {
+        let ScrollExtent { content, viewport } = self.extent.get();
+        let (max_x, max_y) = self.max_scroll();
+        let (x, y) = self.clamped_scroll();
+        let (content, viewport, scroll, max) = match vertical {
+            true => (content.1, viewport.1, y, max_y),
+            false => (content.0, viewport.0, x, max_x),
+        };
+        if max <= 0. {
+            return None;
+        }
+        let length = (viewport / content).clamp(Self::MIN_THUMB, 1.);
+        Some((scroll / max * (1. - length), length))
+    }
+
+    /// The component at path, CONTENT followed by the path in the content
+    pub fn child_at_mut(&mut self, path: &[usize]) -> Option<&mut Box<dyn Component>> {
+        match path.split_first()? {
+            (&Self::CONTENT, rest) => self.content.child_at_mut(rest),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// A thin line dividing the components around it, e.g. the groups of fields of a form. Unless
+/// given a length a horizontal one spans the width of its parent and a vertical one the height of
+/// a line of text
+#[derive(Debug, Clone)]
+pub struct Separator {
+    pub orientation: Orientation,
+    /// Of the line, in pixels
+    pub thickness: f32,
+    pub length: Option<Dimension>,
+    pub color: Option<Color>,
+}
+
+impl Default for Separator {
+    fn default() -> Self {
+        Separator {
+            orientation: Orientation::Horizontal,
+            thickness: 1.,
+            length: None,
+            color: None,
+        }
+    }
+}
+
+impl Separator {
+    /// Space left on both sides of the line, across it
+    pub const MARGIN: f32 = 4.;
+
+    /// The width and height it takes with the margins when it's length long
+    pub fn size(&self, length: f32) -> (f32, f32) {
+        let across = self.thickness.max(1.) + 2. * Separator::MARGIN;
+        match self.orientation {
+            Orientation::Horizontal => (length, across),
+            Orientation::Vertical => (across, length),
+        }
+    }
+}
+
+/// How long the block of an indeterminate ProgressBar takes to go back and forth
+const INDETERMINATE_CYCLE: Duration = Duration::from_millis(1500);
+/// Width of the block of an indeterminate ProgressBar as a fraction of the track
+const INDETERMINATE_WIDTH: f32 = 0.3;
+
+/// Shows how much of a task is done, or that it's busy when it can't tell
+#[derive(Debug, Clone, Default)]
+pub struct ProgressBar {
+    /// Fraction (0.0 - 1.0) that is done, None is indeterminate and moves a block back and forth
+    pub value: Option<f32>,
+    /// Where the indeterminate block is in its cycle, from 0 to 1
+    pub phase: f32,
+    pub accessible: Accessible,
+}
+
+impl ProgressBar {
+    /// Moves the indeterminate block, returns whether it moved. It stays still when reducing
+    /// motion
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        if self.value.is_some() || accessibility::reduce_motion() {
+            return false;
+        }
+        self.phase = (self.phase + elapsed.as_secs_f32() / INDETERMINATE_CYCLE.as_secs_f32()).fract();
+        true
+    }
+
+    /// Start and end of the filled part as fractions of the track
+    pub fn filled_range(&self) -> (f32, f32) {
+        match self.value {
+            Some(value) => (0., value.clamp(0., 1.)),
+            None => {
+                let there_and_back = 1. - (2. * self.phase - 1.).abs();
+                let start = there_and_back * (1. - INDETERMINATE_WIDTH);
+                (start, start + INDETERMINATE_WIDTH)
+            }
+        }
+    }
+}
+
+/// How long the needle of a Gauge takes to swing from its min to its max
+const GAUGE_SWING: Duration = Duration::from_millis(800);
+/// The largest angle between two points of the arc of a Gauge, in radians
+const GAUGE_ARC_STEP: f32 = std::f32::consts::PI / 36.;
+
+/// A part of the dial of a Gauge in a color, e.g. red past a limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaugeZone {
+    pub from: f32,
+    pub to: f32,
+    pub color: Color,
+}
+
+/// A round dial with a needle pointing at a value between min and max, e.g. the load of a server.
+/// The needle swings to a new value rather than jumping there, unless reducing motion
+#[derive(Debug, Clone)]
+pub struct Gauge {
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    /// Parts of the arc in their color, drawn in order over the track
+    pub zones: Vec<GaugeZone>,
+    /// Shown under the value, e.g. "rpm" or "°C"
+    pub unit: String,
+    pub decimals: usize,
+    /// Diameter of the dial
+    pub size: Dimension,
+    /// The value the needle points at while it swings to value, None until it's first animated
+    pub needle: Option<f32>,
+    pub accessible: Accessible,
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Gauge {
+            min: 0.,
+            max: 100.,
+            value: 0.,
+            zones: vec![],
+            unit: String::new(),
+            decimals: 0,
+            size: Dimension::Pixels(120),
+            needle: None,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Gauge {
+    /// Angle of min on the dial in radians, clockwise from the right. The dial is open at the bottom
+    pub const START_ANGLE: f32 = std::f32::consts::PI * 0.75;
+    /// Angle from min to max
+    pub const SWEEP: f32 = std::f32::consts::PI * 1.5;
+    /// Width of the arc as a fraction of the radius of the dial
+    pub const ARC_WIDTH: f32 = 0.15;
+
+    /// The angle of value on the dial, values out of the range stop at its ends
+    pub fn angle_of(&self, value: f32) -> f32 {
+        let fraction = match self.max > self.min {
+            true => ((value - self.min) / (self.max - self.min)).clamp(0., 1.),
+            false => 0.,
+        };
+        Gauge::START_ANGLE + Gauge::SWEEP * fraction
+    }
+
+    /// The point at radius from center in the direction of value
+    pub fn point_at(&self, value: f32, center: (f32, f32), radius: f32) -> (f32, f32) {
+        let angle = self.angle_of(value);
+        (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+    }
+
+    /// Points along the arc at radius from center between the values from and to, close enough
+    /// together for a stroke through them to look round
+    pub fn arc(&self, from: f32, to: f32, center: (f32, f32), radius: f32) -> Vec<(f32, f32)> {
+        let (start, end) = (self.angle_of(from), self.angle_of(to));
+        let steps = ((end - start).abs() / GAUGE_ARC_STEP).ceil().max(1.) as usize;
+        (0..=steps).map(|i| {
+            let angle = start + (end - start) * i as f32 / steps as f32;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        }).collect()
+    }
+
+    /// The triangle of the needle, its tip length away from center and its base width wide
+    pub fn needle_triangle(&self, center: (f32, f32), length: f32, width: f32) -> [(f32, f32); 3] {
+        let angle = self.angle_of(self.needle.unwrap_or(self.value));
+        let (dx, dy) = (-angle.sin() * width / 2., angle.cos() * width / 2.);
+        [(center.0 + length * angle.cos(), center.1 + length * angle.sin()),
+         (center.0 + dx, center.1 + dy), (center.0 - dx, center.1 - dy)]
+    }
+
+    /// The value as shown under the needle
+    pub fn text(&self) -> String {
+        format!("{:.*}", self.decimals, self.value)
+    }
+
+    /// Swings the needle towards the value, returns whether it moved. It starts at the value the
+    /// first time and jumps to it when reducing motion
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        let needle = match self.needle {
+            Some(needle) if needle != self.value => needle,
+            Some(_) => return false,
+            None => {
+                self.needle = Some(self.value);
+                return false;
+            }
+        };
+        let step = match accessibility::reduce_motion() || self.max <= self.min {
+            true => f32::MAX,
+            false => (self.max - self.min) * elapsed.as_secs_f32() / GAUGE_SWING.as_secs_f32(),
+        };
+        self.needle = Some(match needle < self.value {
+            true => (needle + step).min(self.value),
+            false => (needle - step).max(self.value),
+        });
+        true
+    }
+}
+
+/// How long the shimmer of a Skeleton takes to sweep across it
+const SKELETON_SHIMMER_CYCLE: Duration = Duration::from_millis(1200);
+/// Width of the shimmer as a fraction of the Skeleton
+const SKELETON_SHIMMER_WIDTH: f32 = 0.3;
+
+/// A block of a Skeleton, in pixels from its top left corner
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SkeletonBlock {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+/// Gray blocks where content will be once it's loaded, with a shimmer sweeping across them. It
+/// stands still when reducing motion
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Skeleton {
+    pub blocks: Vec<SkeletonBlock>,
+    /// Where the shimmer is in its sweep, from 0 to 1
+    pub phase: f32,
+}
+
+impl Skeleton {
+    /// Lines of text of line_height as wide as the fractions of width, usually the last one is
+    /// shorter
+    pub fn lines(fractions: &[f32], width: f32, line_height: f32) -> Skeleton {
+        let spacing = line_height / 2.;
+        let blocks = fractions.iter().enumerate().map(|(i, fraction)| SkeletonBlock {
+            x: 0.,
+            y: i as f32 * (line_height + spacing),
+            w: width * fraction.clamp(0., 1.),
+            h: line_height,
+        }).collect();
+        Skeleton { blocks, phase: 0. }
+    }
+
+    /// Moves the shimmer, returns whether it moved
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        if accessibility::reduce_motion() {
+            return false;
+        }
+        self.phase = (self.phase + elapsed.as_secs_f32() / SKELETON_SHIMMER_CYCLE.as_secs_f32()).fract();
+        true
+    }
+
+    /// Where the shimmer is on something width wide, as the x of its start, middle and end. It
+    /// sweeps from before the left edge to past the right one
+    pub fn shimmer(&self, width: f32) -> (f32, f32, f32) {
+        let band = width * SKELETON_SHIMMER_WIDTH;
+        let start = self.phase * (width + band) - band;
+        (start, start + band / 2., start + band)
+    }
+
+    /// How much the shimmer lights x, from 1 in its middle to 0 outside it
+    pub fn shine(&self, x: f32, width: f32) -> f32 {
+        let (start, middle, _) = self.shimmer(width);
+        let half = middle - start;
+        match half > 0. {
+            true => (1. - (x - middle).abs() / half).max(0.),
+            false => 0.,
+        }
+    }
+}
+
+/// Content that shows a Skeleton while it's loading, see paging::set_loading(). The components
+/// of the content are at [index...] like in a Container
+#[derive(Debug, Clone, Default)]
+pub struct Loadable {
+    /// Routes set_loading() to it, so it should be unique in the window
+    pub source: String,
+    pub content: Container,
+    pub loading: bool,
+    /// Drawn instead of the content while it's loading. Without blocks they're the rectangles of
+    /// the components of the content
+    pub skeleton: Skeleton,
+}
+
+/// Content too heavy to build with the rest of the window, e.g. a huge TableView or the view of a
+/// long document. Its placeholder is drawn at first so the window shows up right away, then the
+/// engines build the content of the Deferred that are shown one per frame, the highest priority
+/// first. The components of the content are at [index...] like in a Container
+#[derive(Debug, Clone, Default)]
+pub struct Deferred {
+    pub content: Container,
+    pub priority: i32,
+    /// Drawn until the content is built, without blocks it's a block as big as the content
+    pub placeholder: Skeleton,
+    /// Whether the content is built instead of the placeholder, the engines set it
+    pub ready: bool,
+}
+
+/// Publishes values to the components of its content while they're built, they read them with
+/// context::get(). The components of the content are at [index...] like in a Container
+#[derive(Debug, Clone, Default)]
+pub struct Provider {
+    pub values: ContextValues,
+    pub content: Container,
+}
+
+/// Shows content while condition returns true and otherwise while it returns false, e.g. a panel
+/// shown for a setting. The condition is checked every frame (see Container::expand()) and the
+/// components of the shown one are at [index...] like in a Container
+#[derive(Debug, Clone)]
+pub struct If {
+    pub condition: fn() -> bool,
+    pub content: Container,
+    pub otherwise: Container,
+    /// What condition returned when it was last checked
+    pub shown: bool,
+}
+
+impl Default for If {
+    fn default() -> Self {
+        If {
+            condition: || false,
+            content: Container::default(),
+            otherwise: Container::default(),
+            shown: false,
+        }
+    }
+}
+
+impl If {
+    pub fn active(&self) -> &Container {
+        match self.shown {
+            true => &self.content,
+            false => &self.otherwise,
+        }
+    }
+
+    pub fn active_mut(&mut self) -> &mut Container {
+        match self.shown {
+            true => &mut self.content,
+            false => &mut self.otherwise,
+        }
+    }
+}
+
+/// A child made by template for each of the keys, e.g. a row for each open document. The keys are
+/// checked every frame (see Container::expand()) and the children of the keys that stay are kept,
+/// so a TextField in them keeps what was typed. They're at [index...] like in a Container
+#[derive(Debug, Clone)]
+pub struct ForEach {
+    pub keys: fn() -> Vec<String>,
+    pub template: fn(&str) -> Box<dyn Component>,
+    pub content: Container,
+    /// The keys of the children of content
+    pub expanded: Vec<String>,
+}
+
+impl Default for ForEach {
+    fn default() -> Self {
+        ForEach {
+            keys: Vec::new,
+            template: |key| Box::new(Label { text: key.to_string(), ..Default::default() }),
+            content: Container::default(),
+            expanded: vec![],
+        }
+    }
+}
+
+impl ForEach {
+    /// Makes the children match the keys, the ones of the keys it already had are moved where
+    /// their key is now and the others made by template. Returns whether they changed
+    pub fn reconcile(&mut self, keys: Vec<String>) -> bool {
+        if keys == self.expanded {
+            return false;
+        }
+        let mut previous: Vec<Option<Box<dyn Component>>> = self.content.children.drain(..).map(Some).collect();
+        self.content.children = keys.iter().map(|key| {
+            let kept = self.expanded.iter().position(|k| k == key).and_then(|i| previous[i].take());
+            kept.unwrap_or_else(|| (self.template)(key))
+        }).collect();
+        self.expanded = keys;
+        true
+    }
+}
+
+/// A step of a path in a container, the children of a ForEach are found by their key so the path
+/// still leads to them once they moved
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathStep {
+    Index(usize),
+    Key(String),
+}
+
+/// Replaces the color of the Labels built inside of a Provider publishing it, the color of a
+/// Label wins over it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextColor(pub Color);
+
+/// An area files are dragged onto from the file manager, it lights up while they're dragged over
+/// the window. Only the files whose extension it accepts are given to on_files
+#[derive(Debug, Clone)]
+pub struct DropZone {
+    pub text: String,
+    /// Extensions of the accepted files without the dot, e.g. "png", in any case. Empty accepts
+    /// every file
+    pub extensions: Vec<String>,
+    pub height: Dimension,
+    /// Files are being dragged over the window
+    pub drag_over: bool,
+    /// How many files of the last drop it didn't accept
+    pub rejected: usize,
+    /// Gets the accepted files of a drop
+    pub on_files: fn(Event, Vec<PathBuf>) -> bool,
+    pub accessible: Accessible,
+}
+
+impl Default for DropZone {
+    fn default() -> Self {
+        DropZone {
+            text: "Drop files here".to_string(),
+            extensions: vec![],
+            height: Dimension::Pixels(100),
+            drag_over: false,
+            rejected: 0,
+            on_files: |_event, _files| true,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl DropZone {
+    pub fn accepts(&self, path: &Path) -> bool {
+        self.extensions.is_empty() || path.extension().and_then(|e| e.to_str())
+            .is_some_and(|extension| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(extension)))
+    }
+
+    /// Gives the accepted files to on_files and counts the others, returns whether it changed
+    pub fn drop_files(&mut self, files: Vec<PathBuf>) -> bool {
+        let (accepted, rejected): (Vec<PathBuf>, Vec<PathBuf>) = files.into_iter().partition(|f| self.accepts(f));
+        let changed = self.drag_over || self.rejected != rejected.len();
+        self.drag_over = false;
+        self.rejected = rejected.len();
+        if !accepted.is_empty() {
+            (self.on_files)(Event { source: "DropZone".to_string(), position: None }, accepted);
+        }
+        changed
+    }
+
+    /// What it says under its text: the accepted extensions, or the files it skipped once some were
+    pub fn hint(&self) -> Option<String> {
+        match (self.rejected, self.extensions.is_empty()) {
+            (0, true) => None,
+            (0, false) => Some(format!("Accepts {}", self.extensions.join(", "))),
+            (1, _) => Some("Skipped 1 file of another type".to_string()),
+            (rejected, _) => Some(format!("Skipped {} files of another type", rejected)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// An opaque color from its hue in degrees and its saturation and value from 0 to 1
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let channel = |n: f32| {
+            let k = (n + hue.rem_euclid(360.) / 60.) % 6.;
+            let c = value - value * saturation * (k.min(4. - k).clamp(0., 1.));
+            (c.clamp(0., 1.) * 255.).round() as u8
+        };
+        Color::rgb(channel(5.), channel(3.), channel(1.))
+    }
+
+    /// The hue in degrees (0 for grays) and the saturation and value from 0 to 1
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.r as f32 / 255., self.g as f32 / 255., self.b as f32 / 255.);
+        let max = r.max(g).max(b);
+        let range = max - r.min(g).min(b);
+        let hue = if range == 0. {
+            0.
+        } else if max == r {
+            60. * ((g - b) / range).rem_euclid(6.)
+        } else if max == g {
+            60. * ((b - r) / range + 2.)
+        } else {
+            60. * ((r - g) / range + 4.)
+        };
+        let saturation = if max > 0. { range / max } else { 0. };
+        (hue, saturation, max)
+    }
+}
+
+/// Where the pixels of an Image come from, PNG or JPEG encoded. Paths are looked up in the assets
+/// registry first, see assets::resolve()
+#[derive(Clone)]
+pub enum ImageSource {
+    Path(String),
+    Bytes(Rc<[u8]>),
+}
+
+impl ImageSource {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        ImageSource::Bytes(bytes.into())
+    }
+}
+
+impl Debug for ImageSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageSource::Path(path) => write!(f, "Path({:?})", path),
+            ImageSource::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+        }
+    }
+}
+
+/// Bytes are the same source only when they are the same allocation, so the engines don't have to
+/// compare whole images to find the decoded one
+impl PartialEq for ImageSource {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ImageSource::Path(a), ImageSource::Path(b)) => a == b,
+            (ImageSource::Bytes(a), ImageSource::Bytes(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// How an Image is drawn in a size that is not its own
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ScaleMode {
+    /// As big as it fits keeping its aspect ratio, centered
+    #[default]
+    Fit,
+    /// Covers the whole size keeping its aspect ratio, what is left over is cut evenly
+    Fill,
+    /// Takes the whole size, not keeping its aspect ratio
+    Stretch,
+    /// Repeated in its own size from the top left corner
+    Tile,
+}
+
+/// A part of an Image drawn in a rectangle, u and v are the range of the image taken horizontally
+/// and vertically as fractions of its size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageQuad {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    pub u: (f32, f32),
+    pub v: (f32, f32),
+}
+
+impl ScaleMode {
+    /// The parts of an image of the given size drawn in area, nothing when either is empty. Parts
+    /// are cut instead of drawn past area, so nothing has to be clipped
+    pub fn layout(self, image: (f32, f32), area: (f32, f32)) -> Vec<ImageQuad> {
+        let ((image_w, image_h), (area_w, area_h)) = (image, area);
+        if image_w <= 0. || image_h <= 0. || area_w <= 0. || area_h <= 0. {
+            return vec![];
+        }
+        let whole = |x, y, w, h| ImageQuad { x, y, w, h, u: (0., 1.), v: (0., 1.) };
+        match self {
+            ScaleMode::Fit => {
+                let scale = (area_w / image_w).min(area_h / image_h);
+                let (w, h) = (image_w * scale, image_h * scale);
+                vec![whole((area_w - w) / 2., (area_h - h) / 2., w, h)]
+            }
+            ScaleMode::Fill => {
+                let scale = (area_w / image_w).max(area_h / image_h);
+                let (cut_u, cut_v) = ((1. - area_w / (image_w * scale)) / 2., (1. - area_h / (image_h * scale)) / 2.);
+                vec![ImageQuad { u: (cut_u, 1. - cut_u), v: (cut_v, 1. - cut_v), ..whole(0., 0., area_w, area_h) }]
+            }
+            ScaleMode::Stretch => vec![whole(0., 0., area_w, area_h)],
+            ScaleMode::Tile => {
+                let mut quads = vec![];
+                let mut y = 0.;
+                while y < area_h {
+                    let h = image_h.min(area_h - y);
+                    let mut x = 0.;
+                    while x < area_w {
+                        let w = image_w.min(area_w - x);
+                        quads.push(ImageQuad { x, y, w, h, u: (0., w / image_w), v: (0., h / image_h) });
+                        x += image_w;
+                    }
+                    y += image_h;
+                }
+                quads
+            }
+        }
+    }
+}
+
+/// The side of a cell of Pattern::transparency()
+const CHECKERBOARD_CELL: u32 = 8;
+
+/// A small tile of pixels repeated from the top left corner of what it fills, e.g. the
+/// checkerboard shown behind transparent pixels. The engines turn it into a texture the first time
+/// it's drawn, tiles are small so they're told apart by their pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA32, row by row
+    pub pixels: Rc<[u8]>,
+}
+
+impl Pattern {
+    /// The tile of width by height pixels with the color of each given by color_at(x, y)
+    pub fn from_fn(width: u32, height: u32, color_at: impl Fn(u32, u32) -> Color) -> Pattern {
+        let pixels = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let Color { r, g, b, a } = color_at(x, y);
+                [r, g, b, a]
+            })
+            .collect();
+        Pattern { width, height, pixels }
+    }
+
+    /// Squares of side cell in light and dark, light in the top left corner
+    pub fn checkerboard(cell: u32, light: Color, dark: Color) -> Pattern {
+        let cell = cell.max(1);
+        Pattern::from_fn(2 * cell, 2 * cell, |x, y| match (x / cell + y / cell) % 2 {
+            0 => light,
+            _ => dark,
+        })
+    }
+
+    /// The gray checkerboard image editors show where pixels are transparent
+    pub fn transparency() -> Pattern {
+        Pattern::checkerboard(CHECKERBOARD_CELL, Color::rgb(204, 204, 204), Color::rgb(153, 153, 153))
+    }
+
+    /// Stripes of width pixels in the colors in turn, slanted at 45 degrees when diagonal
+    pub fn stripes(width: u32, colors: &[Color], diagonal: bool) -> Pattern {
+        let (width, count) = (width.max(1), colors.len().max(1) as u32);
+        let side = width * count;
+        match colors.is_empty() {
+            true => Pattern::from_fn(1, 1, |_, _| Color { r: 0, g: 0, b: 0, a: 0 }),
+            false if diagonal => Pattern::from_fn(side, side, |x, y| colors[(((x + y) / width) % count) as usize]),
+            false => Pattern::from_fn(side, 1, |x, _| colors[(x / width) as usize]),
+        }
+    }
+
+    pub fn color_at(&self, x: u32, y: u32) -> Color {
+        let index = ((y % self.height.max(1)) * self.width + x % self.width.max(1)) as usize * 4;
+        match self.pixels.get(index..index + 4) {
+            Some(&[r, g, b, a]) => Color { r, g, b, a },
+            _ => Color { r: 0, g: 0, b: 0, a: 0 },
+        }
+    }
+
+    /// The tiles of the pattern filling area, the last ones are cut at its edges
+    pub fn tiles(&self, area: (f32, f32)) -> Vec<ImageQuad> {
+        ScaleMode::Tile.layout((self.width as f32, self.height as f32), area)
+    }
+}
+
+/// A PNG or JPEG picture, decoded by the engine the first time it's built and kept while it's
+/// shown. An outline is drawn instead when it can't be loaded
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub source: ImageSource,
+    pub width: Dimension,
+    pub height: Dimension,
+    pub scale_mode: ScaleMode,
+    /// Drawn under the picture where it is, e.g. Pattern::transparency() so its transparent pixels
+    /// show
+    pub background: Option<Pattern>,
+    pub accessible: Accessible,
+}
+
+impl Default for Image {
+    fn default() -> Self {
+        Image {
+            source: ImageSource::Path(String::new()),
+            width: Relative(-1),
+            height: Dimension::Pixels(100),
+            scale_mode: ScaleMode::Fit,
+            background: None,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AvatarShape {
+    Circle,
+    /// A square with its corners rounded by that many pixels
+    Rounded(f32),
+}
+
+/// A corner of a triangle drawn by a Canvas, in pixels from the top left corner of the Canvas.
+/// The colors of the corners are blended across the triangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CanvasVertex {
+    pub x: f32,
+    pub y: f32,
+    pub color: Color,
+}
+
+/// Triangles drawn by a Canvas, every three indexes are the vertices of one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CanvasPolygon {
+    pub vertices: Vec<CanvasVertex>,
+    pub indexes: Vec<usize>,
+}
+
+impl CanvasPolygon {
+    /// The points in order as triangles fanned from the first one, a convex polygon (e.g. a
+    /// triangle or a hexagon) of a single color
+    pub fn convex(points: &[(f32, f32)], color: Color) -> Self {
+        CanvasPolygon {
+            vertices: points.iter().map(|(x, y)| CanvasVertex { x: *x, y: *y, color }).collect(),
+            indexes: (2..points.len()).flat_map(|i| [0, i - 1, i]).collect(),
+        }
+    }
+
+    pub fn rect(x: f32, y: f32, w: f32, h: f32, color: Color) -> Self {
+        CanvasPolygon::convex(&[(x, y), (x + w, y), (x + w, y + h), (x, y + h)], color)
+    }
+
+    /// A segment with the given thickness
+    pub fn line(from: (f32, f32), to: (f32, f32), thickness: f32, color: Color) -> Self {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let (nx, ny) = (-dy / length * thickness / 2., dx / length * thickness / 2.);
+        CanvasPolygon::convex(&[(from.0 + nx, from.1 + ny), (from.0 - nx, from.1 - ny),
+                                (to.0 - nx, to.1 - ny), (to.0 + nx, to.1 + ny)], color)
+    }
+
+    /// The vertices of the triangles, the ones with an index past the vertices and an incomplete
+    /// last one are left out so the engines never get invalid geometry
+    pub fn triangles(&self) -> Vec<[CanvasVertex; 3]> {
+        self.indexes.chunks_exact(3)
+            .filter_map(|triangle| Some([*self.vertices.get(triangle[0])?, *self.vertices.get(triangle[1])?,
+                                         *self.vertices.get(triangle[2])?]))
+            .collect()
+    }
+}
+
+/// Draws what the Canvas shows given its width and height
+pub type CanvasDraw = Rc<dyn Fn(f32, f32) -> Vec<CanvasPolygon>>;
+
+/// Custom graphics laid out like any other component. draw is called on every build with the size
+/// of the canvas and what it returns is drawn clipped to the canvas, it gets what it draws from its
+/// captures or from context::get()
+#[derive(Clone)]
+pub struct Canvas {
+    pub width: Dimension,
+    pub height: Dimension,
+    pub draw: CanvasDraw,
+    /// Fills the canvas under what is drawn
+    pub background: Option<Pattern>,
+    pub accessible: Accessible,
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Canvas {
+            width: Relative(-1),
+            height: Dimension::Pixels(100),
+            draw: Rc::new(|_width, _height| vec![]),
+            background: None,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Debug for Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Canvas")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("background", &self.background)
+            .field("accessible", &self.accessible)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A picture of someone cropped to a circle or a rounded square, e.g. in a list of contacts. Their
+/// initials on a color picked from their name are shown until the picture is loaded, or when there
+/// is none or it can't be loaded. Pictures are decoded once and shared by the avatars showing them
+#[derive(Debug, Clone)]
+pub struct Avatar {
+    pub source: Option<ImageSource>,
+    /// Who it is, the initials and the color come from it
+    pub name: String,
+    /// Width and height in pixels
+    pub size: f32,
+    pub shape: AvatarShape,
+    pub accessible: Accessible,
+}
+
+impl Default for Avatar {
+    fn default() -> Self {
+        Avatar {
+            source: None,
+            name: String::new(),
+            size: 40.,
+            shape: AvatarShape::Circle,
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl Avatar {
+    /// The first letters of the first and last words of the name, e.g. "AL" for "Ada King
+    /// Lovelace"
+    pub fn initials(&self) -> String {
+        let words: Vec<&str> = self.name.split_whitespace().collect();
+        let first_letter = |word: &str| word.chars().next().into_iter().flat_map(char::to_uppercase);
+        match words.as_slice() {
+            [] => String::new(),
+            [only] => first_letter(only).collect(),
+            [first, .., last] => first_letter(first).chain(first_letter(last)).collect(),
+        }
+    }
+
+    /// The background of the initials, the same name always gets the same one
+    pub fn color(&self) -> Color {
+        let hash = self.name.bytes().fold(0u32, |hash, b| hash.wrapping_mul(31).wrapping_add(b as u32));
+        Color::from_hsv((hash % 360) as f32, 0.45, 0.6)
+    }
+
+    /// How much the corners are rounded, half the size for a circle
+    pub fn corner_radius(&self) -> f32 {
+        match self.shape {
+            AvatarShape::Circle => self.size / 2.,
+            AvatarShape::Rounded(radius) => radius.clamp(0., self.size / 2.),
+        }
     }
 }
 
-struct Color {
-    r: u8,
-    g: u8,
-    b: u8,
-    a: u8
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mnemonics_are_parsed() {
+        assert_eq!(parse_mnemonic("&File"), ("File".to_string(), Some((0, 'f'))));
+        assert_eq!(parse_mnemonic("E&xit"), ("Exit".to_string(), Some((1, 'x'))));
+        assert_eq!(parse_mnemonic("Save && &Quit"), ("Save & Quit".to_string(), Some((7, 'q'))));
+        assert_eq!(parse_mnemonic("Plain"), ("Plain".to_string(), None));
+    }
+
+    #[test]
+    fn menu_items_toggle_when_activated() {
+        let item = |title: &str, checked: Option<bool>, radio_group: Option<&str>, accelerator: Option<&str>| {
+            Submenu::MenuItem(MenuItem {
+                title: title.to_string(),
+                checked,
+                radio_group: radio_group.map(|g| g.to_string()),
+                accelerator: accelerator.map(|a| a.to_string()),
+                ..Default::default()
+            })
+        };
+        let view = Menu {
+            title: "&Zoom".to_string(),
+            children: vec![item("&Small", Some(true), Some("zoom"), None),
+                           item("&Large", Some(false), Some("zoom"), Some("Ctrl+L"))],
+            ..Default::default()
+        };
+        let mut menu = MainMenu {
+            menu: Menu {
+                title: "&View".to_string(),
+                children: vec![item("&Wrap", Some(false), None, None), Submenu::Menu(view)],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let checked = |menu: &MainMenu, path: &[usize]| match path {
+            [index] => match &menu.menu.children[*index] {
+                Submenu::MenuItem(item) => item.checked,
+                Submenu::Menu(_) => None,
+            },
+            [1, index] => match &menu.menu.children[1] {
+                Submenu::Menu(view) => match &view.children[*index] {
+                    Submenu::MenuItem(item) => item.checked,
+                    Submenu::Menu(_) => None,
+                },
+                Submenu::MenuItem(_) => None,
+            },
+            _ => None,
+        };
+        assert!(menu.click(&[]));
+        assert!(menu.menu.open);
+        assert!(menu.click(&[0]));
+        assert_eq!(checked(&menu, &[0]), Some(true));
+        assert!(!menu.menu.open, "Activating closes the menu");
+
+        assert!(menu.click(&[]));
+        assert!(menu.click(&[1]));
+        assert!(menu.activate_by_mnemonic('l'));
+        assert_eq!((checked(&menu, &[1, 0]), checked(&menu, &[1, 1])), (Some(false), Some(true)));
+
+        assert!(menu.activate_by_accelerator("Ctrl+L"));
+        assert_eq!(checked(&menu, &[1, 1]), Some(true), "Radio items stay checked");
+        assert!(!menu.activate_by_accelerator("Ctrl+K"));
+    }
+
+    #[test]
+    fn forms_only_submit_valid_fields() {
+        let field = TextField {
+            text: String::new(),
+            validator: Validator::new().with_rule(crate::validation::Rule::Required, "Required"),
+            ..Default::default()
+        };
+        let mut form = Form {
+            content: Container { children: vec![Box::new(field)], ..Default::default() },
+            ..Default::default()
+        };
+        assert!(!form.submit(None));
+        let field = form.child_at_mut(&[Form::CONTENT, 0]).and_then(|c| c.downcast_mut::<TextField>()).unwrap();
+        assert_eq!(field.errors, Some(vec!["Required".to_string()]));
+        field.text = "filled".to_string();
+        assert!(field.validate(Trigger::Change), "Checked on every trigger once shown");
+        assert!(field.is_valid());
+        assert!(form.submit(None));
+    }
+
+    #[test]
+    fn checkbox_toggles() {
+        let mut checkbox = Checkbox { on_toggle: |_event, checked| checked, ..Default::default() };
+        assert!(checkbox.toggle(None));
+        assert!(checkbox.checked);
+        assert!(!checkbox.toggle(Some((1, 2))));
+    }
+
+    #[test]
+    fn radio_group_keeps_a_single_selection() {
+        let mut group = RadioGroup {
+            children: vec![RadioButton { selected: true, ..Default::default() }, RadioButton::default()],
+            ..Default::default()
+        };
+        assert_eq!(group.selected_index(), Some(0));
+        assert!(group.select(1, None));
+        assert_eq!(group.selected_index(), Some(1));
+        assert!(!group.children[0].selected);
+        assert!(!group.select(1, None), "Already selected");
+        assert!(!group.select(2, None), "Out of range");
+    }
+
+    #[test]
+    fn tab_pane_only_exposes_the_active_tab() {
+        let page = |label: &str| Tab {
+            title: label.to_string(),
+            content: Container {
+                children: vec![Box::new(Checkbox { label: label.to_string(), ..Default::default() })],
+                ..Default::default()
+            },
+        };
+        let mut container = Container {
+            children: vec![Box::new(TabPane { tabs: vec![page("General"), page("Advanced")], ..Default::default() })],
+            ..Default::default()
+        };
+        assert_eq!(container.focusable_paths(), vec![vec![0, 0, 0]]);
+        let advanced = container.child_at_mut(&[0, 1, 0]).and_then(|c| c.downcast_mut::<Checkbox>());
+        assert_eq!(advanced.map(|c| c.label.as_str()), Some("Advanced"));
+        let tab_pane = container.child_at_mut(&[0]).and_then(|c| c.downcast_mut::<TabPane>()).unwrap();
+        assert!(tab_pane.select(1, None));
+        assert!(!tab_pane.select(1, None), "Already active");
+        assert!(!tab_pane.select(2, None), "Out of range");
+        assert_eq!(container.focusable_paths(), vec![vec![0, 1, 0]]);
+    }
+
+    #[test]
+    fn scroll_pane_stops_at_the_end_of_the_content() {
+        let mut pane = ScrollPane::default();
+        assert!(!pane.scroll_by(0., 50.), "Nothing measured yet");
+        pane.extent.set(ScrollExtent { content: (100., 400.), viewport: (100., 100.) });
+        assert!(pane.scroll_by(0., 250.));
+        assert!(pane.scroll_by(0., 250.));
+        assert_eq!(pane.scroll, (0., 300.));
+        assert_eq!(pane.thumb(true), Some((0.75, 0.25)));
+        assert_eq!(pane.thumb(false), None);
+        assert!(pane.set_scroll_fraction(true, 0.5));
+        assert_eq!(pane.scroll, (0., 150.));
+    }
+
+    #[test]
+    fn images_are_laid_out_by_their_scale_mode() {
+        let fit = ScaleMode::Fit.layout((200., 100.), (100., 100.));
+        assert_eq!(fit, vec![ImageQuad { x: 0., y: 25., w: 100., h: 50., u: (0., 1.), v: (0., 1.) }]);
+        let fill = ScaleMode::Fill.layout((200., 100.), (100., 100.));
+        assert_eq!(fill, vec![ImageQuad { x: 0., y: 0., w: 100., h: 100., u: (0.25, 0.75), v: (0., 1.) }]);
+        let tiles = ScaleMode::Tile.layout((40., 40.), (100., 50.));
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[2], ImageQuad { x: 80., y: 0., w: 20., h: 40., u: (0., 0.5), v: (0., 1.) });
+        assert!(ScaleMode::Stretch.layout((0., 10.), (100., 50.)).is_empty());
+    }
+
+    #[test]
+    fn label_carets_select_the_wrapped_lines() {
+        let mut label = Label { text: "caret browsing".to_string(), ..Default::default() };
+        assert!(!label.move_caret(CaretMove::Right, true), "No caret without the focus");
+        *label.lines.borrow_mut() = vec!["caret ".to_string(), "browsing".to_string()];
+        label.caret = Some(Caret::at(0, 2));
+        assert!(label.move_caret(CaretMove::Down, true));
+        assert_eq!(label.selected_text().as_deref(), Some("ret \nbr"));
+        assert!(label.move_caret(CaretMove::Left, false));
+        assert_eq!(label.selected_text(), None);
+        assert_eq!(TextAlign::Center.offset(60., 100.), 20.);
+        assert_eq!(TextAlign::Right.offset(120., 100.), 0., "Lines wider than the label start at the left");
+    }
+
+    #[test]
+    fn text_areas_scroll_to_what_is_typed() {
+        let mut text_area = TextArea { text: "one".to_string(), ..Default::default() };
+        text_area.extent.set(ScrollExtent { content: (100., 400.), viewport: (100., 100.) });
+        assert!(!text_area.scroll_by(-10.), "Already at the top");
+        assert!(text_area.scroll_by(350.));
+        assert_eq!(text_area.scroll, 300.);
+        assert_eq!(text_area.thumb(), Some((0.75, 0.25)));
+        assert!(text_area.scroll_by(-50.));
+        assert!(text_area.insert("\ntwo"));
+        assert_eq!(text_area.text, "one\ntwo");
+        assert_eq!(text_area.clamped_scroll(), 300.);
+    }
+
+    #[test]
+    fn slider_values_snap_to_steps() {
+        let mut slider = Slider { min: 10., max: 20., step: 2., value: 10., ..Default::default() };
+        assert!(slider.set_fraction(0.45, None));
+        assert_eq!(slider.value, 14.);
+        assert!(!slider.set_value(14.4, None), "Rounds to the same step");
+        assert!(slider.step_by(-10));
+        assert_eq!(slider.value, 10.);
+        assert_eq!(slider.fraction(), 0.);
+    }
+
+    #[test]
+    fn loadables_show_their_skeleton_until_loaded() {
+        let mut container = Container {
+            children: vec![Box::new(Container {
+                children: vec![Box::new(Loadable {
+                    source: "profile".to_string(),
+                    content: Container { children: vec![Box::new(Button::default())], ..Default::default() },
+                    loading: true,
+                    ..Default::default()
+                })],
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        assert!(container.focusable_paths().is_empty(), "Still loading");
+        assert!(!container.set_loading("feed", false));
+        assert!(container.set_loading("profile", false));
+        assert_eq!(container.focusable_paths(), vec![vec![0, 0, 0]]);
+        let mut skeleton = Skeleton::lines(&[1., 0.5], 100., 10.);
+        assert_eq!(skeleton.blocks[1], SkeletonBlock { x: 0., y: 15., w: 50., h: 10. });
+        assert_eq!(skeleton.shine(50., 100.), 0., "Before the left edge");
+        skeleton.phase = 0.5;
+        let (_, middle, _) = skeleton.shimmer(100.);
+        assert_eq!(skeleton.shine(middle, 100.), 1.);
+    }
+
+    #[test]
+    fn deferred_contents_are_built_one_at_a_time_by_priority() {
+        let deferred = |priority: i32, children: Vec<Box<dyn Component>>| -> Box<dyn Component> {
+            Box::new(Deferred { content: Container { children, ..Default::default() }, priority, ..Default::default() })
+        };
+        let mut container = Container {
+            children: vec![deferred(0, vec![Box::new(Button::default())]),
+                           deferred(5, vec![deferred(9, vec![Box::new(Button::default())])])],
+            ..Default::default()
+        };
+        let ready = |container: &mut Container, path: &[usize]| container.child_at_mut(path)
+            .and_then(|c| c.downcast_mut::<Deferred>())
+            .map(|d| d.ready);
+        assert!(container.focusable_paths().is_empty(), "Only placeholders");
+        assert!(container.build_next_deferred());
+        assert_eq!((ready(&mut container, &[0]), ready(&mut container, &[1])), (Some(false), Some(true)));
+        assert!(container.build_next_deferred(), "The nested one once its parent is built");
+        assert_eq!(container.focusable_paths(), vec![vec![1, 0, 0]]);
+        assert!(container.build_next_deferred());
+        assert!(!container.build_next_deferred());
+        assert_eq!(container.focusable_paths(), vec![vec![0, 0], vec![1, 0, 0]]);
+    }
+
+    #[test]
+    fn drop_zones_take_only_the_files_they_accept() {
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+        let mut container = Container {
+            children: vec![Box::new(DropZone {
+                extensions: vec!["png".to_string()],
+                on_files: |_event, files| {
+                    assert_eq!(files, vec![PathBuf::from("/photos/cat.PNG")]);
+                    DROPPED.store(true, Ordering::SeqCst);
+                    true
+                },
+                ..Default::default()
+            }), Box::new(Loadable { loading: true, content: Container {
+                children: vec![Box::new(DropZone::default())],
+                ..Default::default()
+            }, ..Default::default() })],
+            ..Default::default()
+        };
+        assert_eq!(container.drop_zones_mut().len(), 1, "The loading one isn't shown");
+        let zone = container.drop_zones_mut().remove(0);
+        assert_eq!(zone.hint().as_deref(), Some("Accepts png"));
+        zone.drag_over = true;
+        assert!(zone.drop_files(vec![PathBuf::from("/photos/cat.PNG"), PathBuf::from("notes.txt")]));
+        assert!(DROPPED.load(Ordering::SeqCst));
+        assert!(!zone.drag_over);
+        assert_eq!(zone.hint().as_deref(), Some("Skipped 1 file of another type"));
+    }
+
+    #[test]
+    fn tool_bar_moves_what_does_not_fit_to_the_overflow_menu() {
+        let button = |title: &str| ToolItem::Button(ToolButton {
+            title: title.to_string(),
+            checked: (title == "Bold").then_some(false),
+            ..Default::default()
+        });
+        let mut tool_bar = ToolBar {
+            items: vec![button("New"), button("Open"), ToolItem::Separator, button("Bold")],
+            ..Default::default()
+        };
+        let widths = [40., 40., 10., 40.];
+        assert_eq!(ToolBar::fitting_items(&widths, 130., 20.), 4, "Everything fits, no overflow button");
+        assert_eq!(ToolBar::fitting_items(&widths, 120., 20.), 3);
+        assert_eq!(tool_bar.shown_items(3), 2, "Not ending with a separator");
+        assert!(tool_bar.choose_overflow(3, None));
+        assert!(matches!(&tool_bar.items[3], ToolItem::Button(b) if b.checked == Some(true)));
+        assert_eq!(tool_bar.pressed, None, "The overflow menu doesn't hold it down");
+        assert!(!tool_bar.press(2, None), "A separator");
+        assert!(tool_bar.press(0, None));
+        assert!(tool_bar.release());
+    }
+
+    #[test]
+    fn carousel_slides_to_the_page_it_was_dragged_towards() {
+        let page = |title: &str| Container {
+            children: vec![Box::new(Button { title: title.to_string(), ..Default::default() })],
+            ..Default::default()
+        };
+        let mut carousel = Carousel { pages: vec![page("One"), page("Two"), page("Three")], ..Default::default() };
+        assert!(!carousel.previous(None), "Already the first page");
+        assert!(carousel.next(None));
+        assert_eq!((carousel.active, carousel.position()), (1, 0.), "Still showing the first page");
+        carousel.advance(CAROUSEL_TRANSITION / 2);
+        assert_eq!(carousel.position(), 0.5);
+        carousel.advance(CAROUSEL_TRANSITION);
+        assert_eq!(carousel.position(), 1.);
+        carousel.start_drag();
+        assert!(carousel.drag_by(-0.1));
+        assert!(!carousel.end_drag(None), "Not far enough");
+        carousel.advance(CAROUSEL_TRANSITION);
+        carousel.start_drag();
+        carousel.drag_by(-5.);
+        assert_eq!(carousel.position(), 2., "No further than the last page");
+        assert!(carousel.end_drag(None));
+        assert_eq!((carousel.active, carousel.position()), (2, 2.));
+        assert!(carousel.child_at_mut(&[Carousel::PAGES, 2, 0]).is_some_and(|c| c.is::<Button>()));
+        assert_eq!(carousel.focusable_paths(), vec![vec![], vec![Carousel::PAGES, 2, 0]]);
+    }
+
+    #[test]
+    fn canvas_polygons_only_give_whole_triangles() {
+        let red = Color::rgb(255, 0, 0);
+        let hexagon = CanvasPolygon::convex(&[(0., 0.), (2., 0.), (3., 1.), (2., 2.), (0., 2.), (-1., 1.)], red);
+        assert_eq!(hexagon.indexes, [0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 5]);
+        assert_eq!(CanvasPolygon::rect(0., 0., 4., 2., red).triangles().len(), 2);
+        let broken = CanvasPolygon { indexes: vec![0, 1, 2, 0, 2, 9, 0, 1], ..hexagon };
+        let corners = broken.triangles().iter().map(|t| t.map(|v| (v.x, v.y))).collect::<Vec<_>>();
+        assert_eq!(corners, [[(0., 0.), (2., 0.), (3., 1.)]]);
+        let canvas = Canvas { draw: Rc::new(move |width, height| vec![CanvasPolygon::rect(0., 0., width, height, red)]),
+                              ..Default::default() };
+        assert_eq!((canvas.draw)(10., 5.)[0].vertices[2], CanvasVertex { x: 10., y: 5., color: red });
+    }
+
+    #[test]
+    fn patterns_repeat_their_tile() {
+        let (light, dark) = (Color::rgb(255, 255, 255), Color::rgb(0, 0, 0));
+        let checkerboard = Pattern::checkerboard(2, light, dark);
+        assert_eq!((checkerboard.width, checkerboard.pixels.len()), (4, 4 * 4 * 4));
+        let colors = [(0, 0), (2, 0), (3, 3), (5, 2)].map(|(x, y)| checkerboard.color_at(x, y));
+        assert_eq!(colors, [light, dark, light, dark], "Repeated past the tile");
+        assert_eq!(checkerboard, Pattern::checkerboard(2, light, dark), "The same pixels");
+        let tiles = checkerboard.tiles((10., 4.));
+        assert_eq!(tiles.len(), 3);
+        assert_eq!((tiles[2].x, tiles[2].w, tiles[2].u), (8., 2., (0., 0.5)));
+        let stripes = Pattern::stripes(1, &[light, dark, dark], true);
+        assert_eq!([(0, 0), (1, 0), (2, 1), (0, 2)].map(|(x, y)| stripes.color_at(x, y)), [light, dark, light, dark]);
+    }
+
+    #[test]
+    fn window_backgrounds_clear_to_black_under_what_they_draw() {
+        let blue = Color::rgb(20, 40, 90);
+        assert_eq!(WindowBackground::Color(blue).clear_color(), blue);
+        let gradient = WindowBackground::Gradient { from: blue, to: blue, vertical: true };
+        assert_eq!(gradient.clear_color(), Color::rgb(0, 0, 0));
+        assert_eq!(format!("{:?}", WindowBackground::Draw(Rc::new(|_, _| vec![]))), "Draw");
+    }
+
+    #[test]
+    fn consoles_keep_the_last_lines_and_follow_the_output() {
+        let mut console = Console { capacity: 4, visible_rows: 2, ..Default::default() };
+        console.write("one\ntw");
+        console.write("o\n\x1b[31mthree\n50%\r100%\r\n");
+        assert_eq!(console.text(), "one\ntwo\nthree\n100%");
+        assert_eq!(console.lines[2][0].style.foreground, Some(ansi::PALETTE[1]));
+        assert_eq!(console.lines[3][0].style.foreground, Some(ansi::PALETTE[1]), "Colors carry over");
+        assert_eq!(console.visible_range(), 2..4, "Following the output");
+        assert!(console.scroll_by(-1) && !console.follow);
+        console.write("five\n");
+        assert_eq!((console.lines.len(), console.visible_range()), (4, 0..2), "The oldest line made room");
+        assert!(console.scroll_by(5) && console.follow);
+        assert_eq!(console.thumb(), Some((0.5, 0.5)));
+    }
+
+    #[test]
+    fn avatars_fall_back_to_the_initials_of_the_name() {
+        let avatar = |name: &str| Avatar { name: name.to_string(), ..Default::default() };
+        assert_eq!(avatar("Ada King Lovelace").initials(), "AL");
+        assert_eq!(avatar("  grace ").initials(), "G");
+        assert_eq!(avatar("").initials(), "");
+        assert_eq!(avatar("Ada").color(), avatar("Ada").color());
+        assert_ne!(avatar("Ada").color(), avatar("Grace").color());
+        let rounded = Avatar { shape: AvatarShape::Rounded(50.), ..avatar("Ada") };
+        assert_eq!(rounded.corner_radius(), 20., "No more than a circle");
+    }
+
+    #[test]
+    fn ratings_preview_hovered_stars_and_segments_select_one() {
+        let mut rating = Rating { value: 2., ..Default::default() };
+        assert!(rating.hover(Some(3)));
+        assert_eq!((rating.shown(), rating.value), (4., 2.), "Only a preview");
+        assert!(rating.hover(None));
+        assert!(rating.click_part(3, None) && rating.value == 4.);
+        assert!(rating.click_part(3, None) && rating.value == 0., "Clicking the score again clears it");
+        assert!(!rating.step_by(-1));
+        assert!(!rating.hover(Some(5)), "There is no sixth star");
+        let mut halves = Rating { half_stars: true, ..Default::default() };
+        assert!(halves.click_part(4, None) && halves.value == 2.5, "The left half of the third star");
+        assert_eq!((halves.star_fill(1), halves.star_fill(2), halves.star_fill(3)), (1., 0.5, 0.));
+        assert!(halves.step_by(1) && halves.value == 3.);
+        assert!(halves.set_value(1.3, None) && halves.value == 1.5, "Rounded to half a star");
+        assert!(halves.hover(Some(9)) && halves.shown() == 5.);
+        let mut segments = SegmentedControl {
+            segments: vec!["List".to_string(), "Grid".to_string()],
+            ..Default::default()
+        };
+        assert!(segments.move_selection(1));
+        assert_eq!(segments.selected_segment(), Some("List"), "Nothing was selected");
+        assert!(segments.move_selection(5));
+        assert_eq!(segments.selected, Some(1));
+        assert!(!segments.select(1, None) && !segments.select(2, None));
+    }
+
+    #[test]
+    fn typed_entries_become_chips() {
+        let mut chip_input = ChipInput { chips: vec!["rust".to_string()], ..Default::default() };
+        assert!(chip_input.insert("gui, sdl,ru", None));
+        assert_eq!((chip_input.chips.join("|").as_str(), chip_input.text.as_str()), ("rust|gui|sdl", "ru"));
+        chip_input.text.push_str("st ");
+        assert!(chip_input.commit(None) && chip_input.chips.len() == 3, "No chip twice");
+        assert!(!chip_input.commit(None) && !chip_input.insert(",", None));
+        assert!(chip_input.backspace() && chip_input.chips.len() == 2, "The last chip with nothing typed");
+        assert!(chip_input.remove(0, None) && !chip_input.remove(1, None));
+        assert_eq!(chip_input.chips, vec!["gui".to_string()]);
+        chip_input.chips.push("framework".to_string());
+        let places = chip_input.layout(|chip| chip.len() as f32 * 10., 2., 150.);
+        assert_eq!(places, vec![(2., 0), (34., 0), (2., 1)], "The field goes to the next row");
+    }
+
+    #[test]
+    fn breadcrumbs_hide_the_places_after_the_first_one_first() {
+        let mut breadcrumb = Breadcrumb {
+            segments: ["Home", "Documents", "Work", "2024", "Report"].iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        let widths = [40., 80., 40., 40., 60.];
+        assert_eq!(breadcrumb.visible(&widths, 10., 20., 300.), vec![0, 1, 2, 3, 4]);
+        assert_eq!(breadcrumb.visible(&widths, 10., 20., 250.), vec![0, 2, 3, 4], "Documents is behind the ellipsis");
+        assert_eq!(breadcrumb.visible(&widths, 10., 20., 50.), vec![0, 4], "Still the first and the last");
+        assert!(breadcrumb.click(3, None) && !breadcrumb.click(4, None), "The current place can't be clicked");
+    }
+
+    #[test]
+    fn spinners_only_take_numbers_in_range() {
+        let mut spinner = Spinner { min: -5., max: 5., step: 0.5, value: 1., text: "1.0".to_string(),
+                                    ..Default::default() };
+        assert!(spinner.step_by(1, None));
+        assert_eq!((spinner.value, spinner.text.as_str()), (1.5, "1.5"));
+        assert!(!spinner.insert("x"));
+        assert!(!spinner.insert("."), "A single decimal point");
+        spinner.text.clear();
+        assert!(spinner.insert("-") && spinner.insert("4") && spinner.insert(".5"));
+        assert!(spinner.commit(None));
+        assert_eq!(spinner.value, -4.5);
+        spinner.text = "9".to_string();
+        assert!(!spinner.is_valid());
+        assert!(spinner.commit(None));
+        assert_eq!((spinner.value, spinner.text.as_str()), (-4.5, "-4.5"), "Out of range reverts");
+    }
+
+    #[test]
+    fn held_spinner_arrows_repeat_faster_until_the_end() {
+        let mut spinner = SpinBox { max: 10., decimals: Some(1), ..Default::default() };
+        assert!(spinner.press(Spinner::INCREMENT, None));
+        assert_eq!(spinner.text, "1.0");
+        assert!(!spinner.advance(Duration::from_millis(399)), "Not repeating yet");
+        assert!(spinner.advance(Duration::from_millis(1)));
+        assert_eq!(spinner.value, 2.);
+        assert!(spinner.advance(Duration::from_millis(120)) && spinner.advance(Duration::from_millis(60)));
+        assert_eq!(spinner.value, 4., "The second repeat came twice as fast");
+        assert!(spinner.advance(Duration::from_secs(1)));
+        assert_eq!((spinner.value, spinner.held.as_ref()), (10., None), "Released at max");
+        spinner.press(Spinner::DECREMENT, None);
+        spinner.release();
+        assert!(!spinner.advance(Duration::from_secs(1)));
+        assert_eq!(spinner.value, 9.);
+    }
+
+    #[test]
+    fn date_pickers_select_days_of_the_shown_month() {
+        let mut picker = DatePicker { date: Date::new(2024, 1, 31), ..Default::default() };
+        assert!(picker.toggle());
+        assert_eq!(picker.month, Date { year: 2024, month: 1, day: 1 });
+        assert!(picker.show_month(1));
+        let day = picker.month.month_grid()[3];
+        assert_eq!(day, Date { year: 2024, month: 2, day: 1 });
+        assert!(picker.select(day, None));
+        assert!(!picker.open);
+        assert!(picker.step_by(7, 0));
+        assert_eq!(picker.text(), "2024-02-08");
+        assert!(!picker.select(Date { year: 2024, month: 2, day: 8 }, None), "Already selected and closed");
+    }
+
+    #[test]
+    fn color_pickers_convert_between_rgb_and_hsv() {
+        assert_eq!(Color::from_hsv(0., 1., 1.), Color::rgb(255, 0, 0));
+        assert_eq!(Color::from_hsv(120., 0.5, 0.5), Color::rgb(64, 128, 64));
+        assert_eq!(Color::from_hsv(360., 1., 1.), Color::rgb(255, 0, 0));
+        assert_eq!(Color::rgb(0, 0, 255).to_hsv(), (240., 1., 1.));
+        let mut picker = ColorPicker { hue: 200., ..Default::default() };
+        assert!(picker.set_color(Color::rgb(128, 128, 128), None));
+        assert_eq!(picker.hue, 200., "Grays keep the hue");
+        assert_eq!(picker.saturation, 0.);
+        assert!(picker.set_color(Color::rgb(255, 255, 0), None));
+        assert_eq!((picker.hue, picker.color()), (60., Color::rgb(255, 255, 0)));
+        assert!(!picker.set_hsv(60., 1., 2., None), "Already the brightest");
+    }
+
+    #[test]
+    fn indeterminate_progress_bars_bounce() {
+        let mut container = Container {
+            children: vec![Box::new(ProgressBar { value: Some(2.), ..Default::default() }),
+                           Box::new(Container { children: vec![Box::new(ProgressBar::default())], ..Default::default() })],
+            ..Default::default()
+        };
+        assert!(container.animate(INDETERMINATE_CYCLE / 2));
+        let bars: Vec<(f32, f32)> = [vec![0], vec![1, 0]].iter()
+            .map(|path| container.child_at_mut(path).unwrap().downcast_ref::<ProgressBar>().unwrap().filled_range())
+            .collect();
+        assert_eq!(bars[0], (0., 1.), "Determinate bars are clamped and don't move");
+        assert_eq!(bars[1], (1. - INDETERMINATE_WIDTH, 1.));
+    }
+
+    #[test]
+    fn gauge_needles_swing_to_the_value() {
+        let mut gauge = Gauge { value: 20., ..Default::default() };
+        assert!(!gauge.advance(GAUGE_SWING), "It starts at the value");
+        assert_eq!(gauge.needle, Some(20.));
+        gauge.value = 80.;
+        assert!(gauge.advance(GAUGE_SWING / 4));
+        assert_eq!(gauge.needle, Some(45.));
+        assert!(gauge.advance(GAUGE_SWING) && !gauge.advance(GAUGE_SWING));
+        assert_eq!(gauge.needle, Some(80.));
+        assert_eq!(gauge.angle_of(150.), Gauge::START_ANGLE + Gauge::SWEEP, "Stops at max");
+        let arc = gauge.arc(0., 100., (0., 0.), 10.);
+        assert_eq!(arc.len(), 55, "Every 5 degrees");
+        assert!((arc[0].0 + arc[54].0).abs() < 1e-4 && (arc[0].1 - arc[54].1).abs() < 1e-4, "Open at the bottom");
+    }
+
+    #[test]
+    fn disabled_buttons_ignore_the_pointer() {
+        let mut button = Button::default();
+        assert!(button.states.fire(ButtonEvent::PointerEnter));
+        assert!(button.press(Event::default()));
+        assert_eq!(button.state(), ButtonState::Pressed);
+        assert!(button.set_enabled(false));
+        assert!(!button.press(Event::default()));
+        assert!(!button.states.fire(ButtonEvent::PointerEnter));
+        assert!(button.set_enabled(true));
+        assert_eq!(button.state(), ButtonState::Idle);
+    }
+
+    #[test]
+    fn popups_close_unless_kept() {
+        let combo_box = || Box::new(ComboBox { items: vec!["A".to_string()], open: true, ..Default::default() });
+        let mut container = Container {
+            children: vec![combo_box(), Box::new(Container { children: vec![combo_box()], ..Default::default() })],
+            ..Default::default()
+        };
+        assert!(container.close_popups(&[1, 0, 0]), "Clicking a row of the nested popup");
+        assert!(container.child_at_mut(&[1, 0]).unwrap().downcast_ref::<ComboBox>().unwrap().open);
+        assert!(container.close_popups(&[]));
+        let combo_box = container.child_at_mut(&[1, 0]).unwrap().downcast_mut::<ComboBox>().unwrap();
+        assert!(!combo_box.open);
+        assert!(combo_box.select(0, None));
+        assert_eq!(combo_box.selected_item(), Some("A"));
+        assert!(!combo_box.select(0, None));
+    }
+
+    #[test]
+    fn list_views_scroll_to_the_selection() {
+        let items = (0..10).map(|i| i.to_string()).collect();
+        let mut list_view = ListView { model: ListModel::new(items), visible_rows: 3, ..Default::default() };
+        assert!(list_view.click(1, SelectModifiers::default()));
+        assert!(list_view.move_selection(4));
+        assert_eq!(list_view.visible_range(), 3..6);
+        assert!(list_view.move_selection(-5));
+        assert_eq!(list_view.visible_range(), 0..3);
+        assert!(!list_view.move_selection(-1), "Already at the first row");
+        assert!(list_view.scroll_by(-2));
+        assert_eq!(list_view.scroll, 0);
+        assert!(list_view.overscroll.offset() < 0.);
+    }
+
+    #[test]
+    fn paged_list_views_load_pages_near_the_end() {
+        struct Requests(Arc<std::sync::Mutex<Vec<usize>>>);
+        impl crate::paging::DataProvider for Requests {
+            fn request_page(&mut self, _source: &str, request: crate::paging::PageRequest) {
+                self.0.lock().unwrap().push(request.offset);
+            }
+        }
+        let requests = Arc::new(std::sync::Mutex::new(vec![]));
+        let mut rows = PagedRows::new("logs", Box::new(Requests(requests.clone())));
+        (rows.page_size, rows.threshold) = (5, 1);
+        let mut container = Container {
+            children: vec![Box::new(ListView { visible_rows: 3, ..ListView::paged(rows) })],
+            ..Default::default()
+        };
+        let page = |offset: usize| PageDelivery {
+            source: "logs".to_string(),
+            offset,
+            rows: (offset..offset + 5).map(|i| vec![i.to_string()]).collect(),
+            more: true,
+        };
+        assert!(!container.deliver_page(&page(5)), "Not the page requested");
+        assert!(container.deliver_page(&page(0)));
+        let list_view = container.child_at_mut(&[0]).unwrap().downcast_mut::<ListView>().unwrap();
+        assert_eq!((list_view.model.len(), list_view.row_count()), (5, 5));
+        assert!(list_view.scroll_by(2));
+        assert_eq!(list_view.row_count(), 6, "Shows the loading row");
+        assert_eq!(*requests.lock().unwrap(), vec![0, 5]);
+    }
+
+    #[test]
+    fn wrapped_table_rows_fill_the_height() {
+        let model = VecTableModel { columns: vec![], rows: (0..4).map(|i| vec![i.to_string()]).collect() };
+        let mut table = TableView { wrap_cells: true, ..TableView::new(model, vec![50.]) };
+        let heights = [30., 50., 40., 20.];
+        assert_eq!(table.row_layout(20., 100., &mut |row| heights[row]), [(0, 20., 30.), (1, 50., 50.)]);
+        assert!(table.scroll_by(5));
+        assert_eq!(table.row_layout(20., 100., &mut |row| heights[row]), [(3, 20., 20.)], "Scrolled to the last row");
+    }
+
+    #[test]
+    fn table_view_headers_sort_and_resize_columns() {
+        let model = VecTableModel {
+            columns: vec!["Name".to_string(), "Age".to_string()],
+            rows: vec![vec!["Rui".to_string(), "30".to_string()], vec!["Ana".to_string(), "9".to_string()]],
+        };
+        let mut table = TableView::new(model, vec![100., 60.]);
+        assert!(table.sort_by(1, None));
+        assert_eq!(table.proxy.cell(&table.model, 0, 0).as_deref(), Some("Ana"));
+        assert!(table.sort_by(1, None));
+        assert_eq!(table.sort_order(1), Some(SortOrder::Descending));
+        assert!(!table.sort_by(2, None));
+        assert!(table.resize_column(0, 5.));
+        assert_eq!(table.columns.widths(), &[TableView::MIN_COLUMN_WIDTH, 60.]);
+        assert!(!table.resize_column(0, 1.), "Already the narrowest");
+    }
+
+    #[test]
+    fn table_view_selection_follows_sorting_and_copies_as_tsv() {
+        let model = VecTableModel {
+            columns: vec!["Name".to_string(), "Notes".to_string()],
+            rows: vec![vec!["Rui".to_string(), "a\tb".to_string()], vec!["Ana".to_string(), "two\nlines".to_string()],
+                       vec!["Eva".to_string(), "".to_string()]],
+        };
+        let mut table = TableView::new(model, vec![100., 60.]);
+        assert_eq!(table.selected_text(), None);
+        assert!(table.click(0, SelectModifiers::default()));
+        assert!(table.click(1, SelectModifiers { ctrl: true, shift: false }));
+        assert!(table.sort_by(0, None));
+        assert!(table.is_row_selected(0) && !table.is_row_selected(1) && table.is_row_selected(2));
+        assert_eq!(table.selected_text().as_deref(), Some("Ana\ttwo lines\nRui\ta b\n"));
+    }
+
+    #[test]
+    fn hyperlinks_are_opened_unless_handled() {
+        let mut link = Hyperlink { url: "https://example.com".to_string(), ..Default::default() };
+        assert_eq!(link.activate(None), Some("https://example.com"));
+        assert!(link.visited);
+        link.on_click = |_event, url| url.starts_with("https://");
+        assert_eq!(link.activate(None), None);
+    }
+
+    #[test]
+    fn separators_keep_a_margin_around_their_line() {
+        let separator = Separator { thickness: 0., ..Default::default() };
+        assert_eq!(separator.size(200.), (200., 9.));
+        let vertical = Separator { orientation: Orientation::Vertical, thickness: 2., ..Default::default() };
+        assert_eq!(vertical.size(20.), (10., 20.));
+    }
+
+    #[test]
+    fn for_each_keeps_the_children_of_the_keys_that_stay() {
+        let mut for_each = ForEach {
+            template: |key| Box::new(TextField { text: key.to_string(), ..Default::default() }),
+            ..Default::default()
+        };
+        assert!(for_each.reconcile(vec!["a".to_string(), "b".to_string()]));
+        if let Some(text_field) = for_each.content.child_at_mut(&[1]).and_then(|c| c.downcast_mut::<TextField>()) {
+            text_field.text.push_str(" edited");
+        }
+        assert!(for_each.reconcile(vec!["b".to_string(), "c".to_string()]));
+        assert!(!for_each.reconcile(vec!["b".to_string(), "c".to_string()]));
+        let texts: Vec<String> = for_each.content.children.iter()
+            .filter_map(|c| c.downcast_ref::<TextField>().map(|t| t.text.clone()))
+            .collect();
+        assert_eq!(texts, ["b edited", "c"]);
+        let mut container = Container {
+            children: vec![Box::new(If { condition: || true, content: for_each.content, ..Default::default() })],
+            ..Default::default()
+        };
+        assert!(container.expand());
+        assert!(container.child_at_mut(&[0, 1]).is_some_and(|c| c.is::<TextField>()));
+        assert!(!container.expand());
+    }
+
+    #[test]
+    fn keyed_paths_follow_the_children_of_for_each() {
+        let mut container = Container { children: vec![Box::new(ForEach::default())], ..Default::default() };
+        let reconcile = |container: &mut Container, keys: &[&str]| container.children[0]
+            .downcast_mut::<ForEach>().unwrap()
+            .reconcile(keys.iter().map(|k| k.to_string()).collect());
+        reconcile(&mut container, &["a", "b"]);
+        let keyed = container.keyed_path(&[0, 1]);
+        assert_eq!(keyed, [PathStep::Index(0), PathStep::Key("b".to_string())]);
+        assert!(reconcile(&mut container, &["b", "c"]));
+        assert_eq!(container.resolve_keyed_path(&keyed), Some(vec![0, 0]));
+        assert_eq!(container.resolve_keyed_path(&[PathStep::Index(0), PathStep::Key("a".to_string())]), None);
+    }
+
+    #[test]
+    fn children_are_found_through_nested_containers() {
+        let nested = Container { children: vec![Box::new(TextField::default())], ..Default::default() };
+        let mut container = Container {
+            children: vec![Box::new(Button::default()), Box::new(nested)],
+            ..Default::default()
+        };
+        let text_field = container.child_at_mut(&[1, 0]).and_then(|c| c.downcast_mut::<TextField>());
+        assert_eq!(text_field.map(|t| t.text.as_str()), Some("TextField"));
+        assert!(container.child_at_mut(&[0, 0]).is_none());
+        assert!(container.child_at_mut(&[]).is_none());
+    }
+}
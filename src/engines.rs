@@ -1,17 +1,67 @@
+#[cfg(not(target_arch = "wasm32"))]
 pub mod sdl {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::fmt::{Debug, Formatter};
+    use std::ops::Range;
+    use std::path::PathBuf;
     use std::ptr;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
 
     use sdl2::{Sdl, sys, VideoSubsystem};
-    use sdl2::event::Event;
-    use sdl2::keyboard::Keycode;
+    use sdl2::event::{Event, WindowEvent};
+    use sdl2::keyboard::{Keycode, Mod};
+    use sdl2::messagebox::{ButtonData, ClickedButton, MessageBoxButtonFlag, MessageBoxFlag,
+                           show_message_box, show_simple_message_box};
+    use sdl2::mouse::{Cursor, MouseButton, MouseState, SystemCursor};
     use sdl2::pixels::Color;
     use sdl2::render::{Texture, WindowCanvas};
+    use sdl2::video::FlashOperation;
+    #[cfg(feature = "ttf-text")]
+    use sdl2::render::TextureCreator;
+    #[cfg(feature = "ttf-text")]
     use sdl2::ttf::Sdl2TtfContext;
+    #[cfg(feature = "ttf-text")]
+    use sdl2::video::WindowContext;
 
+    use crate::accessibility::{self, ColorMode};
+    use crate::accessibility_tree::{self, AccessibleNode};
+    use crate::app_info::AppInfo;
+    use crate::assets;
+    use crate::calendar::{Date, GRID_DAYS, WEEKDAY_NAMES};
+    use crate::caret::{Caret, CaretMove, TextPosition};
+    #[cfg(feature = "charts")]
+    use crate::charts::{self, BarChart, ChartShape, LineChart, PieChart};
+    use crate::commands::{self, CommandPalette};
+    use crate::context;
+    use crate::contrast::{self, ColorPair};
+    use crate::documents::{self, CloseAnswer, Snapshot};
     use crate::elements::*;
+    use crate::file_chooser::{FileChooser, FileChooserMode};
+    use crate::focus::FocusScopes;
+    use crate::frame_diff::{Frame, FrameDiff};
+    use crate::incremental;
+    use crate::layout_check::{self, LayoutBox, LayoutWarning};
+    use crate::markdown::{self, MarkdownShape};
+    use crate::recovery;
+    #[cfg(feature = "remote-debug")]
+    use crate::remote_debug::{self, RemoteCommand};
+    use crate::row_heights::RowHeights;
+    use crate::sections::{SectionEntry, SectionLayout};
+    use crate::selection::{SelectModifiers, Selection};
+    use crate::settings::{Settings, SettingsDialog};
+    use crate::stroke::{self, LineJoin, Point, StrokeStyle};
+    #[cfg(feature = "lyon")]
+    use crate::svg::SvgImage;
+    use crate::table_model::{SortOrder, TableModel};
+    use crate::taskbar::{self, Attention};
+    use crate::telemetry;
+    use crate::text_wrap::wrap_lines;
+    use crate::timers;
+    use crate::tour::{self, Tour, TourStep};
+    use crate::tree_table::TreeTable;
+    use crate::ui_channel::{self, UiMessage};
+    use crate::ui_macros::{self, Macro, MacroStep, WidgetId};
+    use crate::validation::Trigger;
 
 //Structs and Traits *******************************************************************************
 
@@ -42,7 +92,17 @@ pub mod sdl {
     #[derive(Clone)]
     pub struct SDLTexturedPolygon {
         pub poly: SDLPolygon,
-        pub tex: Option<sys::SDL_Texture>,
+        pub tex: Option<TextureId>,
+    }
+
+    /// Textures are owned by the SDLWindow, polygons only refer to them so they can be built
+    /// without access to the renderer
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum TextureId {
+        /// The texture where the text pipeline caches the rasterized glyphs
+        GlyphAtlas,
+        /// A decoded Image, see image_texture_id()
+        Image(u32),
     }
 
     impl Debug for SDLTexturedPolygon {
@@ -57,6 +117,213 @@ pub mod sdl {
     pub struct SDLBody {
         _name: String,
         polygons: Vec<SDLTexturedPolygon>,
+        hit_areas: Vec<SDLHitArea>,
+        /// Popups drawn after every body of the window and hit tested before them, e.g. the list
+        /// of an open ComboBox
+        overlays: Vec<SDLBody>,
+        /// Drawn after the polygons and only inside their rectangle, e.g. the content of a
+        /// ScrollPane
+        clipped: Vec<SDLClippedBody>,
+        /// Where the containers in it laid out their children, see layout_boxes()
+        layout: Vec<LayoutBox>,
+    }
+
+    /// A body drawn and hit tested only inside a rectangle, its overlays are taken by the body
+    /// holding it so popups aren't cut
+    #[derive(Debug, Clone)]
+    pub struct SDLClippedBody {
+        pub x: f32,
+        pub y: f32,
+        pub w: f32,
+        pub h: f32,
+        pub body: SDLBody,
+    }
+
+    /// A rectangle of a SDLBody that reacts to the pointer, path tells the component that built the
+    /// body what was hit (e.g. the index of a section)
+    #[derive(Debug, Clone)]
+    pub struct SDLHitArea {
+        pub x: f32,
+        pub y: f32,
+        pub w: f32,
+        pub h: f32,
+        pub path: Vec<usize>,
+    }
+
+    impl SDLPolygon {
+        /// Appends the vertices and indices of other, so both are drawn with a single call
+        pub fn merge(&mut self, other: &SDLPolygon) {
+            let offset = self.vers.len() as i32;
+            if self.inds.is_empty() {
+                self.inds = (0..offset).collect();
+            }
+            if other.inds.is_empty() {
+                self.inds.extend((0..other.vers.len() as i32).map(|i| i + offset));
+            } else {
+                self.inds.extend(other.inds.iter().map(|i| i + offset));
+            }
+            self.vers.extend_from_slice(&other.vers);
+        }
+    }
+
+    impl SDLBody {
+        pub fn new(name: &str) -> Self {
+            SDLBody {
+                _name: name.to_string(),
+                polygons: vec![],
+                hit_areas: vec![],
+                overlays: vec![],
+                clipped: vec![],
+                layout: vec![],
+            }
+        }
+
+        pub fn push(&mut self, polygon: SDLTexturedPolygon) {
+            self.polygons.push(polygon);
+        }
+
+        pub fn extend(&mut self, polygons: Vec<SDLTexturedPolygon>) {
+            self.polygons.extend(polygons);
+        }
+
+        /// Takes the polygons of other, which is expected to be already translated
+        pub fn append(&mut self, other: SDLBody) {
+            self.polygons.extend(other.polygons);
+            self.hit_areas.extend(other.hit_areas);
+            self.overlays.extend(other.overlays);
+            self.clipped.extend(other.clipped);
+            self.layout.extend(other.layout);
+        }
+
+        /// Like append() but the paths of the hit areas of the child get its index in front, so
+        /// a click can be routed down to it
+        pub fn append_child(&mut self, index: usize, mut child: SDLBody) {
+            child.prefix_paths(index);
+            self.append(child);
+        }
+
+        /// Like append_child() but the child is only drawn and hit inside the rectangle
+        pub fn append_clipped_child(&mut self, index: usize, x: f32, y: f32, w: f32, h: f32,
+                                    mut child: SDLBody) {
+            child.prefix_paths(index);
+            self.overlays.append(&mut child.overlays);
+            self.clipped.push(SDLClippedBody { x, y, w, h, body: child });
+        }
+
+        fn prefix_paths(&mut self, index: usize) {
+            for area in self.hit_areas.iter_mut() {
+                area.path.insert(0, index);
+            }
+            for layout_box in self.layout.iter_mut() {
+                layout_box.path.insert(0, index);
+            }
+            for body in self.overlays.iter_mut().chain(self.clipped.iter_mut().map(|c| &mut c.body)) {
+                body.prefix_paths(index);
+            }
+        }
+
+        pub fn add_hit_area(&mut self, x: f32, y: f32, w: f32, h: f32, path: Vec<usize>) {
+            self.hit_areas.push(SDLHitArea { x, y, w, h, path });
+        }
+
+        /// Adds a popup in the same coordinates as this body, it's moved along with it
+        pub fn push_overlay(&mut self, overlay: SDLBody) {
+            self.overlays.push(overlay);
+        }
+
+        /// The topmost hit area of the overlays containing the point
+        pub fn overlay_hit_area(&self, x: f32, y: f32) -> Option<&SDLHitArea> {
+            self.overlays.iter().rev().find_map(|o| o.hit_area(x, y))
+        }
+
+        /// Replaces the color of every vertex, overlays included
+        pub fn map_colors(&mut self, f: &dyn Fn(sys::SDL_Color) -> sys::SDL_Color) {
+            for tex_poly in self.polygons.iter_mut() {
+                for v in tex_poly.poly.vers.iter_mut() {
+                    v.color = f(v.color);
+                }
+            }
+            for body in self.overlays.iter_mut().chain(self.clipped.iter_mut().map(|c| &mut c.body)) {
+                body.map_colors(f);
+            }
+        }
+
+        /// The hit area with exactly path, e.g. to find where a component of a container is
+        pub fn area_of(&self, path: &[usize]) -> Option<&SDLHitArea> {
+            self.hit_areas.iter().find(|a| a.path == path)
+                .or_else(|| self.clipped.iter().find_map(|c| c.body.area_of(path)))
+        }
+
+        /// The boxes of the components laid out in it, overlays and clipped bodies included
+        pub fn layout_boxes(&self) -> Vec<LayoutBox> {
+            let clipped = self.clipped.iter().flat_map(|c| c.body.layout_boxes())
+                .map(|layout_box| LayoutBox { clipped: true, ..layout_box });
+            self.layout.iter().cloned()
+                .chain(self.overlays.iter().flat_map(|o| o.layout_boxes()))
+                .chain(clipped)
+                .collect()
+        }
+
+        /// The topmost (last added) hit area containing the point, overlays first and then the
+        /// clipped bodies where the point is in their rectangle
+        pub fn hit_area(&self, x: f32, y: f32) -> Option<&SDLHitArea> {
+            self.overlay_hit_area(x, y)
+                .or_else(|| self.clipped.iter().rev()
+                    .filter(|c| x >= c.x && x < c.x + c.w && y >= c.y && y < c.y + c.h)
+                    .find_map(|c| c.body.hit_area(x, y)))
+                .or_else(|| self.hit_areas.iter().rev()
+                    .find(|a| x >= a.x && x < a.x + a.w && y >= a.y && y < a.y + a.h))
+        }
+
+        /// The path of the topmost (last added) hit area containing the point
+        pub fn hit_test(&self, x: f32, y: f32) -> Option<&[usize]> {
+            self.hit_area(x, y).map(|a| a.path.as_slice())
+        }
+
+        /// Components build themselves at the origin, their parents move them into place
+        pub fn translate(&mut self, dx: f32, dy: f32) {
+            for tex_poly in self.polygons.iter_mut() {
+                for v in tex_poly.poly.vers.iter_mut() {
+                    v.position.x += dx;
+                    v.position.y += dy;
+                }
+            }
+            for area in self.hit_areas.iter_mut() {
+                area.x += dx;
+                area.y += dy;
+            }
+            for layout_box in self.layout.iter_mut() {
+                layout_box.x += dx;
+                layout_box.y += dy;
+            }
+            for overlay in self.overlays.iter_mut() {
+                overlay.translate(dx, dy);
+            }
+            for clipped in self.clipped.iter_mut() {
+                clipped.x += dx;
+                clipped.y += dy;
+                clipped.body.translate(dx, dy);
+            }
+        }
+
+        /// The smallest rectangle containing every vertex as (x, y, width, height), the overlays
+        /// are left out and the clipped bodies count as their rectangle
+        pub fn bounds(&self) -> Option<(f32, f32, f32, f32)> {
+            let clip_corners = self.clipped.iter()
+                .flat_map(|c| [(c.x, c.y), (c.x + c.w, c.y + c.h)]);
+            let mut points = self.polygons.iter().flat_map(|p| p.poly.vers.iter())
+                .map(|v| (v.position.x, v.position.y))
+                .chain(clip_corners);
+            let (mut min_x, mut min_y) = points.next()?;
+            let (mut max_x, mut max_y) = (min_x, min_y);
+            for (x, y) in points {
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+            }
+            Some((min_x, min_y, max_x - min_x, max_y - min_y))
+        }
     }
 
     /// This SDL engine will only use SDLBody as NativeDrawable, this is enforced by NativeDrawable
@@ -70,14 +337,17 @@ pub mod sdl {
         fn build(&self, parent: &dyn Component) -> SDLBody;
     }
 
+    #[cfg(feature = "ttf-text")]
     pub struct SDLFontsCache<'ttf_module> {
         pub ttf_context: &'ttf_module Sdl2TtfContext,
         cache: HashMap<String, sdl2::ttf::Font<'ttf_module, 'static>>,
     }
 
+    #[cfg(feature = "ttf-text")]
     unsafe impl<'ttf_module> Send for SDLFontsCache<'ttf_module> {}
 
     //unsafe impl Sync for SDLFont<'_> {}
+    #[cfg(feature = "ttf-text")]
     impl<'ttf_module> SDLFontsCache<'ttf_module> {
         fn new(ttf_context: &'ttf_module Sdl2TtfContext) -> Self {
             SDLFontsCache {
@@ -103,7 +373,99 @@ pub mod sdl {
     pub struct SDLContextAndSubsystems {
         pub context: Sdl,
         pub video: VideoSubsystem,
+        #[cfg(feature = "ttf-text")]
         pub ttf: Box<Sdl2TtfContext>,
+        pub config: EngineConfig,
+    }
+
+    /// How glyphs are cached by the text pipeline, higher quality re-rasterizes glyphs more often
+    /// (e.g. on sub-pixel position changes) and uses more of the glyph atlas
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum TextQuality {
+        Low,
+        Normal,
+        High,
+    }
+
+    impl TextQuality {
+        /// Scale and position tolerances as understood by glyph_brush's draw cache
+        pub fn cache_tolerances(&self) -> (f32, f32) {
+            match self {
+                TextQuality::Low => (0.5, 1.0),
+                TextQuality::Normal => (0.1, 0.1),
+                TextQuality::High => (0.01, 0.01),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum DpiMode {
+        /// Sizes are physical pixels, on high DPI screens everything looks smaller
+        Physical,
+        /// Asks SDL for a high DPI drawable so sizes are scaled to the screen density
+        HighDpi,
+        /// Scales the UI by the density of the display (its dpi / 160, Android's baseline, never
+        /// below 1), what phones and tablets need since their screens are dense and touched
+        Density,
+    }
+
+    /// All the tunables of the SDL engine, pass it to init() or main_loop(). Default gives the
+    /// values that used to be hard-coded
+    #[derive(Debug, Clone)]
+    pub struct EngineConfig {
+        /// Frames per second main_loop() tries to keep, 0 means no pacing at all
+        pub frame_rate: u32,
+        pub vsync: bool,
+        pub window_size: (u32, u32),
+        pub dpi_mode: DpiMode,
+        /// Logical name of the font in the assets registry, unregistered names are loaded as paths
+        pub font_name: String,
+        pub font_size: u16,
+        pub text_quality: TextQuality,
+        /// Initial size of the glyph atlas texture, it grows when glyphs don't fit
+        pub atlas_initial_size: (u32, u32),
+        /// Maximum amount of bytes the engine keeps in textures. Images not drawn lately are
+        /// destroyed to make room for new ones, those that still don't fit aren't drawn
+        pub texture_budget: usize,
+        /// Enables the debugging tools drawn over the window, e.g. the frame diff on Ctrl+Shift+D
+        pub debug_overlays: bool,
+        /// Logs the layout_check warnings whenever they change, on by default in debug builds
+        pub layout_warnings: bool,
+        /// Longest time main_loop() gives the incremental jobs after presenting a frame, they get
+        /// less when the next frame is due sooner
+        pub work_budget: Duration,
+    }
+
+    impl Default for EngineConfig {
+        fn default() -> Self {
+            EngineConfig {
+                frame_rate: 30,
+                vsync: false,
+                window_size: (800, 600),
+                dpi_mode: match cfg!(any(target_os = "android", target_os = "ios")) {
+                    true => DpiMode::Density,
+                    false => DpiMode::Physical,
+                },
+                font_name: assets::DEFAULT_FONT.to_string(),
+                font_size: 20,
+                text_quality: TextQuality::Normal,
+                atlas_initial_size: (256, 256),
+                texture_budget: 64 * 1024 * 1024,
+                debug_overlays: false,
+                layout_warnings: cfg!(debug_assertions),
+                work_budget: Duration::from_millis(8),
+            }
+        }
+    }
+
+    impl EngineConfig {
+        /// The time a frame should last according to frame_rate
+        pub fn frame_duration(&self) -> Duration {
+            match self.frame_rate {
+                0 => Duration::ZERO,
+                rate => Duration::new(0, 1_000_000_000u32 / rate),
+            }
+        }
     }
 
     /// This is a little fun here, since Component.build_dyn() returns the dynamic NativeDrawable
@@ -136,63 +498,375 @@ pub mod sdl {
 
     // Globals *************************************************************************************
 
-    // none yeah!
+    thread_local! {
+        /// The video subsystem init() opened on this thread, what copy_to_clipboard() uses. It's
+        /// None on other threads so a panic hook running there doesn't initialize SDL again
+        static VIDEO: std::cell::RefCell<Option<VideoSubsystem>> = const { std::cell::RefCell::new(None) };
+    }
 
     // Functions ***********************************************************************************
 
     /// Initializes the context and subsystems
-    pub fn init() -> Result<SDLContextAndSubsystems, String> {
+    pub fn init(config: EngineConfig) -> Result<SDLContextAndSubsystems, String> {
         let context = sdl2::init()?;
         let video = context.video()?;
+        VIDEO.with(|v| *v.borrow_mut() = Some(video.clone()));
+        #[cfg(feature = "ttf-text")]
         let ttf = Box::new(sdl2::ttf::init().map_err(|e| e.to_string())?);
         Ok(SDLContextAndSubsystems {
             context,
             video,
+            #[cfg(feature = "ttf-text")]
             ttf,
+            config,
         })
     }
 
     /// A blocking main_loop() for the cases when non-blocking is not necessary. You could also use
     /// BuiltWindow.render() to tell the GUI when you want to render so you are in control of the
     /// loop, specially useful in multimedia applications
-    pub fn main_loop(windows: Vec<Window>) -> Result<(), String> {
+    pub fn main_loop(windows: Vec<Window>, config: EngineConfig) -> Result<(), String> {
         let window = &windows[0];
-        let sdl_ctx = init()?;
+        let sdl_ctx = init(config)?;
+        warn_low_contrast();
         let mut sdl_window = SDLWindow::new(window, &sdl_ctx)?;
-        let drawables = sdl_window.build(window);
-        println!("{:?}", drawables);
+        sdl_window.rebuild();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(components = ?sdl_window.components, "built");
+
+        #[cfg(feature = "ttf-text")]
+        let creator = sdl_window.canvas.texture_creator();
+        #[cfg(feature = "ttf-text")]
+        let texture = Some(hello_texture(&sdl_ctx, &creator)?);
+        #[cfg(not(feature = "ttf-text"))]
+        let texture: Option<Texture> = None;
+
+        let mut event_pump = sdl_ctx.context.event_pump()?;
+        let mut frame = 0u64;
+        'running: loop {
+            let frame_start = Instant::now();
+            let _span = trace_span!("frame", frame);
+            telemetry::report(|t| t.frame_start(frame));
+            #[cfg(feature = "alloc-counter")]
+            crate::alloc_counter::begin_frame();
+            sdl_window.update(Instant::now());
+            recovery::tick(Instant::now());
+            {
+                let _alloc = alloc_scope!("events");
+                for event in event_pump.poll_iter() {
+                    let dispatch_start = Instant::now();
+                    let consumed = sdl_window.handle_event(&event);
+                    #[cfg(feature = "remote-debug")]
+                    remote_debug::log_event(&format!("{:?}", event), consumed, dispatch_start.elapsed());
+                    if !consumed {
+                        match event {
+                            Event::Quit { .. }
+                            | Event::KeyDown {
+                                keycode: Some(Keycode::Escape),
+                                ..
+                            } if close_active_document() => break 'running,
+                            _ => {}
+                        }
+                    }
+                    telemetry::report(|t| t.event_dispatched(event_name(&event),
+                                                             dispatch_start.elapsed()));
+                }
+            }
+
+            sdl_window.render_built(texture.as_ref())?;
+            let frame_duration = sdl_ctx.config.frame_duration();
+            let budget = match frame_duration.is_zero() {
+                true => sdl_ctx.config.work_budget,
+                false => sdl_ctx.config.work_budget.min(frame_duration.saturating_sub(frame_start.elapsed())),
+            };
+            let work_start = Instant::now();
+            incremental::run(|| work_start.elapsed() < budget);
+            telemetry::report(|t| t.frame_end(frame, frame_start.elapsed()));
+            #[cfg(feature = "alloc-counter")]
+            if let Some(allocations) = crate::alloc_counter::end_frame() {
+                println!("Frame {} allocated {} times ({} bytes): {:?}", frame,
+                         allocations.allocations, allocations.bytes, allocations.scopes);
+            }
+            frame += 1;
+            ::std::thread::sleep(frame_duration.saturating_sub(frame_start.elapsed()));
+        }
+        recovery::finish()
+    }
 
-        let font_name = "Nouveau_IBM.ttf";
-        let font = sdl_ctx.ttf.load_font(font_name, 20)?;
+    /// Closes the document of the active window before quitting, asking to save its unsaved
+    /// changes. Returns false when the user cancelled, then the app keeps running
+    fn close_active_document() -> bool {
+        let window = match documents::active_window() {
+            Some(window) if documents::document(&window).is_some() => window,
+            _ => return true,
+        };
+        documents::close(&window).unwrap_or_else(|e| {
+            println!("Failed to close the document {}", e);
+            true
+        })
+    }
 
+    #[cfg(feature = "ttf-text")]
+    fn hello_texture<'a>(sdl_ctx: &SDLContextAndSubsystems, creator: &'a TextureCreator<WindowContext>)
+                         -> Result<Texture<'a>, String> {
+        let font = load_font(&sdl_ctx.ttf, &sdl_ctx.config.font_name, sdl_ctx.config.font_size)?;
         let surface = font.render("Hello").solid(Color {
             r: 255,
             g: 255,
             b: 255,
             a: 255,
         }).map_err(|e| e.to_string())?;
-        let creator = sdl_window.canvas.texture_creator();
-        let mut texture = surface.as_texture(&creator)
-            .map_err(|e| e.to_string())?;
+        surface.as_texture(creator)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Loads a font through the assets registry
+    #[cfg(feature = "ttf-text")]
+    fn load_font<'ttf_module>(ttf: &'ttf_module Sdl2TtfContext, name: &str, size: u16)
+                              -> Result<sdl2::ttf::Font<'ttf_module, 'static>, String> {
+        match assets::resolve(name) {
+            Some(assets::Asset::Embedded(bytes)) => {
+                ttf.load_font_from_rwops(sdl2::rwops::RWops::from_bytes(bytes)?, size)
+            }
+            Some(assets::Asset::Path(path)) => ttf.load_font(path, size),
+            None => ttf.load_font(name, size),
+        }
+    }
 
+    /// The per-user directory where the application should write its files (settings, recent
+    /// files...), it's created by SDL when it doesn't exist
+    pub fn preferences_dir(organization: &str, app_name: &str) -> Result<std::path::PathBuf, String> {
+        sdl2::filesystem::pref_path(organization, app_name)
+            .map(std::path::PathBuf::from)
+            .map_err(|e| e.to_string())
+    }
 
-        let mut event_pump = sdl_ctx.context.event_pump()?;
-        'running: loop {
-            for event in event_pump.poll_iter() {
-                match event {
-                    Event::Quit { .. }
-                    | Event::KeyDown {
-                        keycode: Some(Keycode::Escape),
-                        ..
-                    } => break 'running,
-                    _ => {}
+    /// The lowercase letter or digit of the key, if it's one
+    fn keycode_letter(keycode: Keycode) -> Option<char> {
+        let name = keycode.name();
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// How much the UI is scaled with DpiMode::Density, from the density of the display showing
+    /// the canvas
+    fn density_scale(video: &VideoSubsystem, canvas: &WindowCanvas) -> f32 {
+        let dpi = canvas.window().display_index()
+            .and_then(|index| video.display_dpi(index))
+            .map(|(diagonal, _, _)| diagonal);
+        match dpi {
+            Ok(dpi) => (dpi / 160.).max(1.),
+            Err(e) => {
+                println!("Failed to get the display density {}", e);
+                1.
+            }
+        }
+    }
+
+    /// The name of a key combination the way shortcuts are written, e.g. "Ctrl+Shift+S"
+    fn shortcut_name(keycode: Keycode, keymod: Mod) -> String {
+        let mut name = String::new();
+        if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+            name.push_str("Ctrl+");
+        }
+        if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) {
+            name.push_str("Alt+");
+        }
+        if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+            name.push_str("Shift+");
+        }
+        name.push_str(&keycode.name());
+        name
+    }
+
+    /// The mouse event with its position taken to UI coordinates by map, e.g. map_from_rect() or
+    /// map_from_uv() for a UI drawn with SDLWindow::render_to_texture(). None when the position
+    /// maps outside of the UI, then the event is for the host. Other events are kept as they are
+    pub fn remap_mouse_event<F: Fn(i32, i32) -> Option<(i32, i32)>>(event: &Event, map: F) -> Option<Event> {
+        let mut event = event.clone();
+        match &mut event {
+            Event::MouseMotion { x, y, .. }
+            | Event::MouseButtonDown { x, y, .. }
+            | Event::MouseButtonUp { x, y, .. } => {
+                (*x, *y) = map(*x, *y)?;
+            }
+            _ => {}
+        }
+        Some(event)
+    }
+
+    /// Takes a point of the screen to the texture of size that was copied to dest
+    pub fn map_from_rect(x: i32, y: i32, dest: sdl2::rect::Rect, size: (u32, u32)) -> Option<(i32, i32)> {
+        if !dest.contains_point((x, y)) {
+            return None;
+        }
+        let u = (x - dest.x()) as f32 / dest.width() as f32;
+        let v = (y - dest.y()) as f32 / dest.height() as f32;
+        map_from_uv(u, v, size)
+    }
+
+    /// Takes texture coordinates (0 to 1, e.g. where a ray hit the quad the UI is drawn on) to the
+    /// texture of size
+    pub fn map_from_uv(u: f32, v: f32, size: (u32, u32)) -> Option<(i32, i32)> {
+        match (0. ..1.).contains(&u) && (0. ..1.).contains(&v) {
+            true => Some(((u * size.0 as f32) as i32, (v * size.1 as f32) as i32)),
+            false => None,
+        }
+    }
+
+    /// A short name for the event, only used for reporting
+    fn event_name(event: &Event) -> &'static str {
+        match event {
+            Event::KeyDown { .. } => "KeyDown",
+            Event::KeyUp { .. } => "KeyUp",
+            Event::TextInput { .. } => "TextInput",
+            Event::MouseMotion { .. } => "MouseMotion",
+            Event::MouseButtonDown { .. } => "MouseButtonDown",
+            Event::MouseButtonUp { .. } => "MouseButtonUp",
+            Event::MouseWheel { .. } => "MouseWheel",
+            Event::Window { .. } => "Window",
+            _ => "Other",
+        }
+    }
+
+    // Crash reporter ******************************************************************************
+
+    /// Installs a panic hook that, after the default hook printed the panic, shows a dialog with
+    /// the panic message and backtrace so end users can copy it or save it to a file and report
+    /// it. The dialog is a SDL message box, so it doesn't depend on the (possibly broken) state of
+    /// the windows of the application
+    pub fn install_crash_reporter(app_name: &str) {
+        let app_name = app_name.to_string();
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            default_hook(info);
+            let message = match info.payload().downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => match info.payload().downcast_ref::<String>() {
+                    Some(message) => message.clone(),
+                    None => "Unknown panic".to_string(),
+                }
+            };
+            let location = info.location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+                .unwrap_or_default();
+            if let Err(e) = recovery::autosave_now() {
+                println!("Failed to autosave before crashing {}", e);
+            }
+            let backtrace = std::backtrace::Backtrace::force_capture();
+            let report = format!("{} crashed\n\nPanic: {}\nAt: {}\n\nBacktrace:\n{}",
+                                 app_name, message, location, backtrace);
+            show_crash_dialog(&app_name, &message, &report);
+        }));
+    }
+
+    fn show_crash_dialog(app_name: &str, message: &str, report: &str) {
+        const COPY: i32 = 0;
+        const SAVE: i32 = 1;
+        const CLOSE: i32 = 2;
+        let buttons = [
+            ButtonData { flags: MessageBoxButtonFlag::NOTHING, button_id: COPY, text: "Copy to clipboard" },
+            ButtonData { flags: MessageBoxButtonFlag::NOTHING, button_id: SAVE, text: "Save report" },
+            ButtonData { flags: MessageBoxButtonFlag::RETURNKEY_DEFAULT
+                | MessageBoxButtonFlag::ESCAPEKEY_DEFAULT, button_id: CLOSE, text: "Close" },
+        ];
+        // Panics off the UI thread, or before init(), have no clipboard to copy to
+        let buttons = match has_clipboard() {
+            true => &buttons[..],
+            false => &buttons[1..],
+        };
+        // The whole backtrace doesn't fit in a message box, the saved report contains all of it
+        let summary = report.lines().take(25).collect::<Vec<&str>>().join("\n");
+        let title = format!("{} crashed: {}", app_name, message);
+        loop {
+            let clicked = match show_message_box(MessageBoxFlag::ERROR, buttons, &title, &summary,
+                                                 None, None) {
+                Ok(clicked) => clicked,
+                Err(e) => {
+                    println!("Failed to show the crash reporter dialog {:?}", e);
+                    return;
+                }
+            };
+            match clicked {
+                ClickedButton::CustomButton(ButtonData { button_id: COPY, .. }) => {
+                    if let Err(e) = copy_to_clipboard(report) {
+                        println!("Failed to copy the crash report to the clipboard {}", e);
+                    }
                 }
+                ClickedButton::CustomButton(ButtonData { button_id: SAVE, .. }) => {
+                    let feedback = match save_crash_report(app_name, report) {
+                        Ok(path) => format!("Crash report saved to {}", path.display()),
+                        Err(e) => format!("Failed to save the crash report {}", e),
+                    };
+                    let _ = show_simple_message_box(MessageBoxFlag::INFORMATION, app_name,
+                                                    &feedback, None);
+                }
+                _ => return,
             }
+        }
+    }
 
-            sdl_window.render(&drawables, &mut texture)?;
-            ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 30));
+    /// A Save/Don't save/Cancel message box for closing a document with unsaved changes, ready to
+    /// be used by documents::DocumentHandler::confirm_close()
+    pub fn confirm_close_dialog(app_name: &str, document_name: &str) -> CloseAnswer {
+        const SAVE: i32 = 0;
+        const DISCARD: i32 = 1;
+        const CANCEL: i32 = 2;
+        let buttons = [
+            ButtonData { flags: MessageBoxButtonFlag::RETURNKEY_DEFAULT, button_id: SAVE, text: "Save" },
+            ButtonData { flags: MessageBoxButtonFlag::NOTHING, button_id: DISCARD, text: "Don't save" },
+            ButtonData { flags: MessageBoxButtonFlag::ESCAPEKEY_DEFAULT, button_id: CANCEL, text: "Cancel" },
+        ];
+        let message = format!("Do you want to save the changes to {}?", document_name);
+        match show_message_box(MessageBoxFlag::WARNING, &buttons, app_name, &message, None, None) {
+            Ok(ClickedButton::CustomButton(ButtonData { button_id: SAVE, .. })) => CloseAnswer::Save,
+            Ok(ClickedButton::CustomButton(ButtonData { button_id: DISCARD, .. })) => CloseAnswer::Discard,
+            _ => CloseAnswer::Cancel,
         }
-        Ok(())
+    }
+
+    /// Asks whether to recover the documents autosaved before the last session crashed, returns
+    /// true to recover them with recovery::restore(), false to recovery::discard() them
+    pub fn recovery_prompt(app_name: &str, snapshots: &[Snapshot]) -> bool {
+        const RECOVER: i32 = 0;
+        const DISCARD: i32 = 1;
+        let buttons = [
+            ButtonData { flags: MessageBoxButtonFlag::RETURNKEY_DEFAULT, button_id: RECOVER, text: "Recover" },
+            ButtonData { flags: MessageBoxButtonFlag::ESCAPEKEY_DEFAULT, button_id: DISCARD, text: "Discard" },
+        ];
+        let names: Vec<String> = snapshots.iter()
+            .map(|s| s.path.as_ref().map(|p| p.display().to_string()).unwrap_or(s.window.clone()))
+            .collect();
+        let message = format!("{} didn't close properly last time. Recover the unsaved changes of:\n\n{}",
+                              app_name, names.join("\n"));
+        matches!(show_message_box(MessageBoxFlag::WARNING, &buttons, app_name, &message, None, None),
+                 Ok(ClickedButton::CustomButton(ButtonData { button_id: RECOVER, .. })))
+    }
+
+    /// Whether copy_to_clipboard() works on this thread, i.e. init() ran on it
+    pub fn has_clipboard() -> bool {
+        VIDEO.try_with(|v| v.try_borrow().is_ok_and(|v| v.is_some())).unwrap_or(false)
+    }
+
+    /// Puts the text in the system clipboard, e.g. rows serialized by table_export::to_delimited()
+    pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+        let video = VIDEO.try_with(|v| v.try_borrow().ok().and_then(|v| v.clone()))
+            .map_err(|e| e.to_string())?
+            .ok_or("There is no video subsystem on this thread")?;
+        video.clipboard().set_clipboard_text(text)
+    }
+
+    fn save_crash_report(app_name: &str, report: &str) -> std::io::Result<std::path::PathBuf> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!("{}-crash-{}.txt", app_name, timestamp));
+        std::fs::write(&path, report)?;
+        Ok(path)
     }
 
     // Window **************************************************************************************
@@ -206,8 +880,74 @@ pub mod sdl {
     pub struct SDLWindow<'ttf_module> {
         old_window: Window,
         canvas: sdl2::render::WindowCanvas,
+        #[cfg(feature = "ttf-text")]
         fonts: SDLFontsCache<'ttf_module>,
+        #[cfg(not(feature = "ttf-text"))]
+        _fonts: std::marker::PhantomData<&'ttf_module ()>,
         components: Vec<SDLBody>,
+        textures: SDLTextures,
+        text_generation: u32,
+        command_palette: Option<CommandPalette>,
+        about: Option<AppInfo>,
+        /// See UiMessage::ShowSettings
+        settings: Option<SettingsDialog>,
+        /// The guided tour shown over the window, see tour::start()
+        tour: Option<Tour>,
+        file_chooser: Option<FileChooser>,
+        documents_generation: u64,
+        /// Drawn over a canvas owned by the app, see SDLWindow::embed()
+        embedded: bool,
+        /// Render scale of the canvas, components are laid out in the space it leaves. It's the
+        /// density times the zoom of the accessibility preferences
+        scale: f32,
+        /// Render scale the canvas was created with, see DpiMode
+        density: f32,
+        /// The app is in the background, mobile platforms don't allow drawing meanwhile
+        paused: bool,
+        /// An event given by inject_event() is being handled, macros don't record it
+        injecting: bool,
+        /// Path in the container of the component getting the keys, an editable TextField or a
+        /// Slider
+        focused: Option<Vec<usize>>,
+        /// Regions Tab is confined to, modal dialogs open one
+        focus_scopes: FocusScopes,
+        /// Path in the container of the Slider being dragged and the area of its track
+        dragging: Option<(Vec<usize>, SDLHitArea)>,
+        /// Path in the container of the Spinner whose arrow is held down
+        held_spinner: Option<Vec<usize>>,
+        /// accessibility::generation() when the preferences were last applied
+        accessibility_generation: u64,
+        /// The enlarged pointer, SDL frees it when dropped
+        cursor: Option<Cursor>,
+        /// The hand shown while the pointer is over a Hyperlink
+        hand_cursor: Option<Cursor>,
+        /// When tick() last ran, animations advance by the time since
+        last_tick: Option<Instant>,
+        /// Path in the container of the component under the pointer
+        hovered: Option<Vec<usize>>,
+        /// The accessibility tree of the container when it was last built, None before that
+        accessible_nodes: Option<Vec<AccessibleNode>>,
+        /// Files of the drop in progress, None unless a DropZone is shown to take them
+        dropped_files: Option<Vec<PathBuf>>,
+        /// See EngineConfig.layout_warnings
+        log_layout_warnings: bool,
+        /// What layout_check found when the container was last built
+        layout_warnings: Vec<LayoutWarning>,
+        /// See EngineConfig.debug_overlays
+        debug_overlays: bool,
+        frame_diff: FrameDiffStep,
+    }
+
+    /// Where the frame diff of SDLWindow is, see frame_diff
+    enum FrameDiffStep {
+        Idle,
+        /// The next frame is captured as the one before
+        CaptureBefore,
+        /// Waiting for the state change
+        Before(Frame),
+        /// The next frame is captured as the one after
+        CaptureAfter(Frame),
+        Showing(FrameDiff),
     }
 
     impl<'ttf_module> SDLWindow<'ttf_module> {
@@ -215,418 +955,7032 @@ pub mod sdl {
         /// and returning an already SDLWindow
         pub fn new<'a>(window: &Window, sdl_ctx: &'a SDLContextAndSubsystems)
                        -> Result<SDLWindow<'a>, String> {
-            let sdl_window = sdl_ctx.video
-                .window(window.title.as_str(), 800, 600)
-                .build()
+            let config = &sdl_ctx.config;
+            let mut window_builder = sdl_ctx.video
+                .window(window.title.as_str(), config.window_size.0, config.window_size.1);
+            if config.dpi_mode != DpiMode::Physical {
+                window_builder.allow_highdpi();
+            }
+            let sdl_window = window_builder.build()
                 .map_err(|e| e.to_string())?;
-            let canvas = sdl_window.into_canvas().build().map_err(|e| e.to_string())?;
+            let mut canvas_builder = sdl_window.into_canvas();
+            if config.vsync {
+                canvas_builder = canvas_builder.present_vsync();
+            }
+            let mut canvas = canvas_builder.build().map_err(|e| e.to_string())?;
+            if config.dpi_mode == DpiMode::Density {
+                let scale = density_scale(&sdl_ctx.video, &canvas);
+                canvas.set_scale(scale, scale)?;
+            }
+            SDLWindow::with_canvas(window, canvas, sdl_ctx, false)
+        }
+
+        /// Embeds the widget tree into an existing SDL application (e.g. the in-game UI of a game)
+        /// instead of creating a window. The app keeps drawing with canvas_mut() and calls
+        /// render_built() after its own drawing, the UI is drawn on top without clearing nor
+        /// presenting. Events go through handle_event() first, the app gets the ones that weren't
+        /// consumed, and update() is called once per frame
+        pub fn embed<'a>(window: &Window, canvas: WindowCanvas, sdl_ctx: &'a SDLContextAndSubsystems)
+                         -> Result<SDLWindow<'a>, String> {
+            SDLWindow::with_canvas(window, canvas, sdl_ctx, true)
+        }
 
-            let mut fonts = SDLFontsCache::new(&sdl_ctx.ttf);
-            let font_name = "Nouveau_IBM.ttf";
-            let font = sdl_ctx.ttf.load_font(font_name, 20)?;
-            fonts.cache.insert(font_name.to_string(),
-                               font);
-            let font = fonts.cache.get(font_name).unwrap();
+        fn with_canvas<'a>(window: &Window, canvas: WindowCanvas, sdl_ctx: &'a SDLContextAndSubsystems,
+                           embedded: bool) -> Result<SDLWindow<'a>, String> {
+            let config = &sdl_ctx.config;
+            init_text(config)?;
+            documents::set_active_window(&window.title);
 
+            #[cfg(feature = "ttf-text")]
+            let fonts = {
+                let mut fonts = SDLFontsCache::new(&sdl_ctx.ttf);
+                let font_name = config.font_name.as_str();
+                let font = load_font(&sdl_ctx.ttf, font_name, config.font_size)?;
+                fonts.cache.insert(font_name.to_string(),
+                                   font);
+                fonts
+            };
+
+            let scale = canvas.scale().0;
+            set_text_pixel_scale(scale);
             Ok(SDLWindow {
                 old_window: window.clone(),
                 canvas,
+                #[cfg(feature = "ttf-text")]
                 fonts,
+                #[cfg(not(feature = "ttf-text"))]
+                _fonts: std::marker::PhantomData,
                 components: vec![],
+                textures: SDLTextures::new(config.texture_budget),
+                text_generation: text_generation(),
+                command_palette: None,
+                about: None,
+                settings: None,
+                tour: None,
+                file_chooser: None,
+                documents_generation: 0,
+                embedded,
+                scale,
+                density: scale,
+                paused: false,
+                injecting: false,
+                focused: None,
+                focus_scopes: FocusScopes::default(),
+                dragging: None,
+                held_spinner: None,
+                accessibility_generation: 0,
+                cursor: None,
+                hand_cursor: None,
+                last_tick: None,
+                hovered: None,
+                accessible_nodes: None,
+                dropped_files: None,
+                log_layout_warnings: config.layout_warnings,
+                layout_warnings: vec![],
+                debug_overlays: config.debug_overlays,
+                frame_diff: FrameDiffStep::Idle,
             })
         }
 
         /// This is where the magic happens, the Window model and its children are taken and
         /// converted into SDLBody (trait NativeDrawable)
         pub fn build(&self, window: &Window) -> Vec<SDLBody> {
-            let pseudo = RUIIcon {};
-            let icon = RUIIcon {}.build(&pseudo);
-            let mut res = vec![icon];
+            let _span = trace_span!("layout", window = window.title.as_str());
+            let _alloc = alloc_scope!("layout");
+            let pseudo = self.window_pseudo_parent();
+            let mut res = vec![];
+            if let Some(background) = build_window_background(&window.background, &pseudo) {
+                res.push(background);
+            }
+            res.push(RUIIcon {}.build(&pseudo));
+            // Before the menu so its dropdowns are drawn over the content
+            if let Some(content) = build_docked_container(window, &pseudo) {
+                res.push(content);
+            }
+            // Before the menu too, the menu's dropdowns are drawn over it
+            if let Some(tool_bar) = &window.tool_bar {
+                res.push(build_docked_tool_bar(window, tool_bar, &pseudo));
+            }
             if let Some(menu) = &window.menu {
                 res.push(menu.build(&pseudo));
             }
-            // if let Some(container) = &window.container {
-            //     res.push(container.build(&pseudo));
-            // }
+            if let Some(status_bar) = &window.status_bar {
+                res.push(build_docked_status_bar(status_bar, &pseudo));
+            }
+            // Overlays go last so they are drawn on top
+            if let Some(palette) = self.build_command_palette(&pseudo) {
+                res.push(palette);
+            }
+            if let Some(about) = self.build_about(&pseudo) {
+                res.push(about);
+            }
+            if let Some(settings) = self.build_settings(&pseudo) {
+                res.push(settings);
+            }
+            if let Some(file_chooser) = self.build_file_chooser(&pseudo) {
+                res.push(file_chooser);
+            }
+            telemetry::report(|t| t.components_built(&window.title, res.len()));
             res
         }
 
-        /// It takes many SDLBody (trait NativeDrawable) and renders them by using SDL
-        pub fn render(&mut self, drawables: &Vec<SDLBody>, texture: &Texture) -> Result<(), String> {
-            let canvas = &mut self.canvas;
-            canvas.set_draw_color(Color::RGB(0, 0, 0));
-            canvas.clear();
-            for body in drawables.iter() {
-                for tex_poly in body.polygons.iter() {
-                    SDLWindow::render_geometry(canvas, tex_poly.tex,
-                                               &tex_poly.poly.vers, &tex_poly.poly.inds)?;
-                }
-            }
-            canvas.copy(&texture, None, None).unwrap();
-            canvas.present();
-            Ok(())
+        /// The whole drawable area of the window as a parent for the top level components
+        fn window_pseudo_parent(&self) -> Container {
+            let (width, height) = self.canvas.output_size()
+                .unwrap_or((FALLBACK_PARENT_SIZE.0 as u32, FALLBACK_PARENT_SIZE.1 as u32));
+            pseudo_parent(width as f32 / self.scale, height as f32 / self.scale)
         }
 
-        /// This function shouldn't be here, SDL_RenderGeometry was introduced in SDL 2.0.18 but
-        /// rust-sdl only support earlier versions so the binding for that function was to be done
-        /// here since SDL_RenderGeometry is the basis of this engine
-        fn render_geometry(canvas: &mut WindowCanvas, texture: Option<sys::SDL_Texture>,
-                           vertices: &Vec<sys::SDL_Vertex>, indices: &Vec<i32>) -> Result<(), String> {
-            if !vertices.is_empty() {
-                let sdl_renderer = canvas.raw();
-                let vers_num = vertices.len() as i32;
-                let vers_ptr = (&vertices[0]) as *const sys::SDL_Vertex;
-                let tex_ptr: *mut sys::SDL_Texture = match texture {
-                    None => ptr::null_mut(),
-                    Some(mut t) => &mut t as *mut sys::SDL_Texture
-                };
-                let ind_num = indices.len() as i32;
-                let inds_ptr = match ind_num {
-                    0 => ptr::null(),
-                    _ => (&indices[0])
-                };
-                let ret = unsafe {
-                    sys::SDL_RenderGeometry(sdl_renderer, tex_ptr, vers_ptr, vers_num, inds_ptr, ind_num)
-                };
-                if ret == -1 {
-                    return Err(format!("Failed at SDL_RenderGeometry {}", sdl2::get_error()));
-                }
+        /// Builds the Window this SDLWindow was created with and keeps the result, so it can be
+        /// rendered with render_built() and partially rebuilt later
+        pub fn rebuild(&mut self) {
+            self.expand_container();
+            // Building may recreate the atlas, what was built before that has to be built again
+            let generation = text_generation();
+            let mut built = self.build(&self.old_window);
+            let mode = accessibility::color_mode();
+            if mode != ColorMode::Standard {
+                built.iter_mut().for_each(|b| b.map_colors(&|color| mode_color(mode, color)));
             }
-
-            Ok(())
+            self.components = built;
+            if let FrameDiffStep::Showing(diff) = &self.frame_diff {
+                self.components.push(build_frame_diff(diff, self.scale));
+            }
+            self.text_generation = generation;
+            self.update_focus_ring();
+            self.announce_live_changes();
+            self.check_layout();
+            self.rebuild_tour();
         }
-    }
 
-    // MainMenu ************************************************************************************
+        /// Shows the background behind the components from now on, e.g. when the theme changes
+        pub fn set_background(&mut self, background: WindowBackground) {
+            self.old_window.background = background;
+            self.rebuild();
+        }
 
-    impl SDLComponent for MainMenu {
-        fn build(&self, parent: &dyn Component) -> SDLBody {
-            SDLBody {
-                _name: "MainMenu".to_string(),
-                polygons: vec![],
+        /// Replaces the Window model, everything is rebuilt. The components of the new container
+        /// get the scroll, tab and page of the old ones with the same keyed path (see view_state)
+        pub fn set_window(&mut self, window: Window) {
+            if let Some(container) = self.old_window.container.as_mut() {
+                container.save_view_state();
             }
+            self.old_window = window;
+            // The children of ForEach only have their keys once expanded
+            self.expand_container();
+            if let Some(container) = self.old_window.container.as_mut() {
+                container.restore_view_state();
+            }
+            self.rebuild();
         }
-    }
 
-    impl Component for MainMenu {
-        fn get_height(&self) -> &Dimension {
-            todo!()
+        /// Applies the batch to the menu model and rebuilds only the menu's SDLBody, the rest of
+        /// the window is left as it was
+        pub fn update_menu(&mut self, batch: &MenuBatch) -> Result<(), String> {
+            self.old_window.menu.as_mut()
+                .ok_or("Window has no menu".to_string())?
+                .apply(batch)?;
+            self.rebuild_menu();
+            Ok(())
         }
 
-        fn get_width(&self) -> &Dimension {
-            todo!()
+        fn rebuild_menu(&mut self) {
+            let pseudo = self.window_pseudo_parent();
+            if let Some(menu) = &self.old_window.menu {
+                let body = menu.build(&pseudo);
+                self.replace_body(body);
+            }
         }
 
-        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
-            Box::new(self.build(parent))
+        fn rebuild_container(&mut self) {
+            self.expand_container();
+            let pseudo = self.window_pseudo_parent();
+            if let Some(body) = build_docked_container(&self.old_window, &pseudo) {
+                self.replace_body(body);
+            }
+            self.update_focus_ring();
+            self.announce_live_changes();
+            self.check_layout();
+            self.rebuild_tour();
         }
 
-        fn clone_dyn(&self) -> Box<dyn Component> {
-            Box::new(self.clone())
+        /// Expands the If and ForEach of the container (see Container::expand()), what has the focus,
+        /// is hovered, dragged or held keeps it when the children of a ForEach moved. Returns
+        /// whether anything changed
+        fn expand_container(&mut self) -> bool {
+            let container = match self.old_window.container.as_mut() {
+                Some(container) => container,
+                None => return false,
+            };
+            let focused_before = self.focused.is_some();
+            let focused = self.focused.as_ref().map(|p| container.keyed_path(p));
+            let hovered = self.hovered.as_ref().map(|p| container.keyed_path(p));
+            let held_spinner = self.held_spinner.as_ref().map(|p| container.keyed_path(p));
+            let dragging = self.dragging.take().map(|(p, area)| (container.keyed_path(&p), area));
+            let changed = container.expand();
+            self.focused = focused.and_then(|k| container.resolve_keyed_path(&k));
+            self.hovered = hovered.and_then(|k| container.resolve_keyed_path(&k));
+            self.held_spinner = held_spinner.and_then(|k| container.resolve_keyed_path(&k));
+            self.dragging = dragging.and_then(|(k, area)| Some((container.resolve_keyed_path(&k)?, area)));
+            if focused_before && self.focused.is_none() {
+                self.stop_text_input();
+            }
+            changed
         }
-    }
-
-    // Container ***********************************************************************************
 
-    impl SDLComponent for Container {
-        fn build(&self, parent: &dyn Component) -> SDLBody {
-            // for child in self.children.iter() {
-            //     child.dyn_to_sdl_body().
-            // }
-            todo!()
+        /// Runs layout_check on the container, the warnings that are new are logged
+        fn check_layout(&mut self) {
+            let (width, height) = parent_size(&self.window_pseudo_parent());
+            let boxes = self.components.iter().find(|b| b._name == "Container")
+                .map(|b| b.layout_boxes())
+                .unwrap_or_default();
+            let warnings = layout_check::check(&boxes, width, height);
+            if self.log_layout_warnings {
+                for warning in warnings.iter().filter(|w| !self.layout_warnings.contains(w)) {
+                    println!("Layout warning in {}: {}", self.old_window.title, warning);
+                }
+            }
+            self.layout_warnings = warnings;
         }
-    }
 
-    impl Component for Container {
-        fn get_height(&self) -> &Dimension {
-            &self.height
+        /// Announces what the live components say since the container was last built, see
+        /// accessibility_tree::take_announcements()
+        fn announce_live_changes(&mut self) {
+            let nodes = self.old_window.container.as_ref().map(accessibility_tree::tree).unwrap_or_default();
+            if let Some(previous) = &self.accessible_nodes {
+                accessibility_tree::changes(previous, &nodes).into_iter().for_each(accessibility_tree::announce);
+            }
+            self.accessible_nodes = Some(nodes);
         }
 
-        fn get_width(&self) -> &Dimension {
-            &self.width
+        fn rebuild_tool_bar(&mut self) {
+            let pseudo = self.window_pseudo_parent();
+            if let Some(tool_bar) = &self.old_window.tool_bar {
+                let body = build_docked_tool_bar(&self.old_window, tool_bar, &pseudo);
+                self.replace_body(body);
+            }
         }
 
-        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
-            Box::new(self.build(parent))
+        fn rebuild_status_bar(&mut self) {
+            let pseudo = self.window_pseudo_parent();
+            if let Some(status_bar) = &self.old_window.status_bar {
+                let body = build_docked_status_bar(status_bar, &pseudo);
+                self.replace_body(body);
+            }
         }
 
-        fn clone_dyn(&self) -> Box<dyn Component> {
-            Box::new(self.clone())
+        /// Rebuilds everything with the new text size, colors and zoom and sets the pointer size.
+        /// Embedded windows are not zoomed, the scale of the canvas belongs to the app
+        fn apply_accessibility(&mut self) {
+            let scale = self.density * accessibility::zoom();
+            if !self.embedded && scale != self.scale {
+                match self.canvas.set_scale(scale, scale) {
+                    Ok(()) => {
+                        self.scale = scale;
+                        set_text_pixel_scale(scale);
+                    }
+                    Err(e) => println!("Failed to zoom the UI {}", e),
+                }
+            }
+            let scale = accessibility::preferences().cursor_scale;
+            let cursor = match scale > 1. {
+                true => arrow_cursor(scale).map(Some),
+                false => Ok(None),
+            };
+            match cursor {
+                Ok(cursor) => {
+                    match &cursor {
+                        Some(cursor) => cursor.set(),
+                        None => match Cursor::from_system(SystemCursor::Arrow) {
+                            Ok(arrow) => arrow.set(),
+                            Err(e) => println!("Failed to restore the pointer {}", e),
+                        },
+                    }
+                    self.cursor = cursor;
+                    self.hand_cursor = None;
+                }
+                Err(e) => println!("Failed to enlarge the pointer {}", e),
+            }
+            self.rebuild();
         }
-    }
 
-    // RUIIcon *************************************************************************************
+        /// Replaces the built body with the same name, the rest of the window is left as it was
+        fn replace_body(&mut self, mut body: SDLBody) {
+            let mode = accessibility::color_mode();
+            if mode != ColorMode::Standard {
+                body.map_colors(&|color| mode_color(mode, color));
+            }
+            match self.components.iter_mut().find(|b| b._name == body._name) {
+                Some(old) => *old = body,
+                None => self.components.push(body),
+            }
+            telemetry::report(|t| t.components_built(&self.old_window.title, 1));
+        }
 
-    #[derive(Debug, Clone)]
-    struct RUIIcon;
+        /// Applies a message posted through ui_channel::post()
+        pub fn handle_message(&mut self, message: UiMessage) -> Result<(), String> {
+            match message {
+                UiMessage::MenuUpdate(batch) => return self.update_menu(&batch),
+                UiMessage::StatusText(text) => self.status_bar_mut()?.text = text,
+                UiMessage::StatusMessage(text, duration) => {
+                    self.status_bar_mut()?.show_message(&text, duration)
+                }
+                UiMessage::StatusProgress(progress) => self.status_bar_mut()?.progress = progress,
+                UiMessage::StatusSectionText(index, text) => self.status_bar_mut()?.set_section_text(index, &text)?,
+                UiMessage::PageLoaded(delivery) => {
+                    if !self.old_window.container.as_mut().is_some_and(|c| c.deliver_page(&delivery)) {
+                        return Err(format!("No view requested the page of {} at {}", delivery.source,
+                                           delivery.offset));
+                    }
+                    self.rebuild_container();
+                    return Ok(());
+                }
+                UiMessage::Loading { source, loading } => {
+                    if !self.old_window.container.as_mut().is_some_and(|c| c.set_loading(&source, loading)) {
+                        return Err(format!("No Loadable shows {}", source));
+                    }
+                    self.rebuild_container();
+                    return Ok(());
+                }
+                UiMessage::ConsoleOutput { source, text } => {
+                    if !self.old_window.container.as_mut().is_some_and(|c| c.write_console(&source, &text)) {
+                        return Err(format!("No Console shows {}", source));
+                    }
+                    self.rebuild_container();
+                    return Ok(());
+                }
+                UiMessage::TaskbarProgress(progress) => {
+                    self.old_window.taskbar_progress = progress;
+                    taskbar::show_progress(&self.old_window.title, progress.as_ref());
+                    return Ok(());
+                }
+                UiMessage::RequestAttention(attention) => {
+                    let operation = match attention {
+                        Attention::Briefly => FlashOperation::Briefly,
+                        Attention::UntilFocused => FlashOperation::UntilFocused,
+                        Attention::Cancel => FlashOperation::Cancel,
+                    };
+                    return self.canvas.window_mut().flash(operation);
+                }
+                UiMessage::ShowAbout(info) => {
+                    self.show_about(info);
+                    return Ok(());
+                }
+                UiMessage::ShowSettings(settings) => {
+                    self.show_settings(settings);
+                    return Ok(());
+                }
+                UiMessage::ShowFileChooser(file_chooser) => {
+                    self.show_file_chooser(*file_chooser);
+                    return Ok(());
+                }
+                UiMessage::ArgumentsForwarded(forwarded) => {
+                    self.canvas.window_mut().raise();
+                    (forwarded.on_arguments)(&forwarded.arguments);
+                    return Ok(());
+                }
+                UiMessage::PlayMacro(recorded) => return self.play_macro(&recorded),
+                UiMessage::StartTour(tour) => {
+                    self.start_tour(tour);
+                    return Ok(());
+                }
+                #[cfg(feature = "remote-debug")]
+                UiMessage::RemoteDebug(request) => {
+                    let answer = self.answer_remote(&request.command);
+                    request.reply(answer);
+                    return Ok(());
+                }
+            }
+            self.rebuild_status_bar();
+            Ok(())
+        }
 
-    impl SDLComponent for RUIIcon {
-        fn build(&self, parent: &dyn Component) -> SDLBody {
-            let v0 = sys::SDL_Vertex {
-                position: sys::SDL_FPoint {
-                    x: 400.,
-                    y: 150.,
-                },
-                color: sys::SDL_Color {
-                    r: 255,
-                    g: 0,
-                    b: 0,
-                    a: 255,
-                },
-                tex_coord: sys::SDL_FPoint {
-                    x: 0.,
-                    y: 0.,
-                },
+        #[cfg(feature = "remote-debug")]
+        fn answer_remote(&mut self, command: &RemoteCommand) -> String {
+            match command {
+                RemoteCommand::Tree => format!("{:#?}", self.old_window),
+                RemoteCommand::Layout => self.components.iter()
+                    .map(|b| format!("{} bounds: {:?} hit areas: {:?}", b._name, b.bounds(), b.hit_areas))
+                    .collect::<Vec<String>>().join("\n"),
+                RemoteCommand::Events => remote_debug::events().join("\n"),
+                RemoteCommand::Diff => self.step_frame_diff(),
+                RemoteCommand::Warnings => self.layout_warnings.iter()
+                    .map(|w| w.to_string())
+                    .collect::<Vec<String>>().join("\n"),
+                RemoteCommand::Set { path, value } => {
+                    match remote_debug::set_property(&mut self.old_window, path, value) {
+                        Ok(()) => {
+                            self.rebuild();
+                            "OK".to_string()
+                        }
+                        Err(e) => e,
+                    }
+                }
+            }
+        }
+
+        /// Applies the messages posted to the ui_channel and advances time, main_loop() calls it at
+        /// the start of every frame, embedding apps have to call it themselves
+        pub fn update(&mut self, now: Instant) {
+            for message in ui_channel::drain() {
+                if let Err(e) = self.handle_message(message) {
+                    println!("Failed to apply a UI message {}", e);
+                }
+            }
+            self.tick(now);
+        }
+
+        /// The canvas the UI is drawn on, embedding apps draw their own content with it
+        pub fn canvas_mut(&mut self) -> &mut WindowCanvas {
+            &mut self.canvas
+        }
+
+        fn status_bar_mut(&mut self) -> Result<&mut StatusBar, String> {
+            self.old_window.status_bar.as_mut().ok_or("Window has no status bar".to_string())
+        }
+
+        /// Advances everything that depends on time, it's called by main_loop() once per frame
+        pub fn tick(&mut self, now: Instant) {
+            let elapsed = self.last_tick.map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+            self.last_tick = Some(now);
+            timers::advance(elapsed);
+            // The conditions of If and the keys of ForEach may depend on anything the app changed
+            let expanded = self.expand_container();
+            let animated = self.old_window.container.as_mut().is_some_and(|c| c.animate(elapsed));
+            // A Deferred per frame, after the first one so the window shows up with its placeholders
+            let built = !elapsed.is_zero()
+                && self.old_window.container.as_mut().is_some_and(|c| c.build_next_deferred());
+            if expanded | animated | built {
+                self.rebuild_container();
+            }
+            let expired = match self.old_window.status_bar.as_mut() {
+                Some(status_bar) => status_bar.expire_message(now),
+                None => false,
             };
-            let v1 = sys::SDL_Vertex {
-                position: sys::SDL_FPoint {
-                    x: 200.,
-                    y: 450.,
-                },
-                color: sys::SDL_Color {
-                    r: 0,
-                    g: 0,
-                    b: 255,
-                    a: 255,
-                },
-                tex_coord: sys::SDL_FPoint {
-                    x: 0.,
-                    y: 0.,
+            if expired {
+                self.rebuild_status_bar();
+            }
+            #[cfg(feature = "lua")]
+            if let Some(window) = crate::lua::tick(now) {
+                self.set_window(window);
+            }
+            let generation = accessibility::generation();
+            if generation != self.accessibility_generation {
+                self.accessibility_generation = generation;
+                self.apply_accessibility();
+            }
+            let generation = documents::generation();
+            if generation != self.documents_generation {
+                self.documents_generation = generation;
+                let title = documents::display_title(&self.old_window.title);
+                if let Err(e) = self.canvas.window_mut().set_title(&title) {
+                    println!("Failed to set the window title {}", e);
+                }
+            }
+        }
+
+        /// Gives the event to the components of the window, the parts that changed are rebuilt.
+        /// Returns whether the event was consumed. The lifecycle events of mobile platforms are
+        /// handled too, but never consumed so the app can react to them as well
+        pub fn handle_event(&mut self, event: &Event) -> bool {
+            if self.command_palette.is_some() {
+                return self.handle_palette_event(event);
+            }
+            if self.file_chooser.is_some() && self.handle_file_chooser_event(event) {
+                return true;
+            }
+            if self.about.is_some() {
+                if let Event::KeyDown { keycode: Some(Keycode::Escape | Keycode::Return), .. } = event {
+                    self.close_about();
+                    return true;
+                }
+            }
+            if self.settings.is_some() && self.handle_settings_event(event) {
+                return true;
+            }
+            // The rest of the keys go to the highlighted widget
+            if self.tour.is_some() {
+                if let Event::KeyDown { keycode: Some(Keycode::Escape), .. } = event {
+                    self.skip_tour();
+                    return true;
+                }
+            }
+            if let Some(focused) = self.focused.clone() {
+                if self.handle_focused_event(event) {
+                    self.record_key(&focused, event);
+                    return true;
+                }
+            }
+            match event {
+                Event::MouseMotion { x, y, .. } => self.update_hover(*x, *y),
+                Event::MouseButtonUp { mouse_btn: MouseButton::Left, x, y, .. } => {
+                    self.release_buttons(*x, *y);
+                    self.release_spinner();
+                    if self.old_window.tool_bar.as_mut().is_some_and(|t| t.release()) {
+                        self.rebuild_tool_bar();
+                    }
+                }
+                _ => {}
+            }
+            match event {
+                Event::MouseMotion { x, y, .. } if self.dragging.is_some() => {
+                    self.drag(*x, *y);
+                    true
+                }
+                Event::MouseButtonUp { mouse_btn: MouseButton::Left, x, y, .. } if self.dragging.is_some() => {
+                    if let Some((path, _)) = self.dragging.take() {
+                        self.end_carousel_drag(&path, (*x, *y));
+                        self.end_list_rubber_band(&path);
+                    }
+                    true
+                }
+                Event::KeyDown { keycode: Some(Keycode::P), keymod, .. }
+                if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+                    && keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    self.command_palette = Some(CommandPalette::open());
+                    self.rebuild_command_palette();
+                    self.push_focus_scope("CommandPalette", None);
+                    if let Some((x, y, w, h)) = self.components.iter()
+                        .find(|b| b._name == "CommandPalette").and_then(|b| b.bounds()) {
+                        self.start_text_input(x, y, w, h);
+                    }
+                    true
+                }
+                Event::KeyDown { keycode: Some(Keycode::D), keymod, .. }
+                if self.debug_overlays && keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+                    && keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    println!("{}", self.step_frame_diff());
+                    true
+                }
+                Event::MouseWheel { x, y, .. } => {
+                    // Shift turns the wheel sideways
+                    let keymod = self.canvas.window().subsystem().sdl().keyboard().mod_state();
+                    match keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                        true => self.scroll_hovered(*x - *y, 0),
+                        false => self.scroll_hovered(*x, -*y),
+                    }
+                }
+                Event::KeyDown { keycode: Some(Keycode::F7), .. } => {
+                    accessibility::toggle_caret_browsing();
+                    true
+                }
+                Event::KeyDown { keycode: Some(Keycode::Tab), keymod, .. } => {
+                    self.cycle_focus(keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD))
+                }
+                Event::KeyDown { keycode: Some(keycode), keymod, .. } => {
+                    self.handle_shortcut(*keycode, *keymod) || self.handle_menu_key(*keycode, *keymod)
+                }
+                Event::Window { win_event: WindowEvent::FocusGained, .. } => {
+                    documents::set_active_window(&self.old_window.title);
+                    false
+                }
+                Event::KeyUp { keycode: Some(Keycode::LAlt | Keycode::RAlt), .. } => {
+                    let changed = match self.old_window.menu.as_mut() {
+                        Some(menu) => std::mem::replace(&mut menu.show_mnemonics, false),
+                        None => false,
+                    };
+                    if changed {
+                        self.rebuild_menu();
+                    }
+                    changed
+                }
+                Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+                    self.handle_click(*x, *y) || (self.embedded && self.is_over_ui(*x, *y))
+                }
+                // So clicks on the UI don't reach the app that embeds it
+                Event::MouseButtonDown { x, y, .. }
+                | Event::MouseButtonUp { x, y, .. } if self.embedded => self.is_over_ui(*x, *y),
+                Event::Window { win_event: WindowEvent::SizeChanged(..), .. } => {
+                    self.rebuild();
+                    false
+                }
+                // Mobile platforms may kill the app without notice once it's in the background
+                Event::AppWillEnterBackground { .. } | Event::AppTerminating { .. } => {
+                    self.paused = true;
+                    if let Err(e) = recovery::autosave_now() {
+                        println!("Failed to autosave {}", e);
+                    }
+                    false
+                }
+                Event::AppDidEnterForeground { .. } => {
+                    self.paused = false;
+                    // The surface may have been recreated with another size meanwhile
+                    self.rebuild();
+                    false
+                }
+                Event::RenderDeviceReset { .. } => {
+                    self.reset_textures();
+                    false
+                }
+                Event::DropBegin { .. } => self.begin_drop(),
+                Event::DropFile { filename, .. } => match self.dropped_files.as_mut() {
+                    Some(files) => {
+                        files.push(PathBuf::from(filename));
+                        true
+                    }
+                    None => false,
                 },
+                Event::DropComplete { .. } => self.complete_drop(),
+                _ => false,
+            }
+        }
+
+        /// Lights up the DropZones shown while files are dragged over the window, returns whether
+        /// there is any
+        fn begin_drop(&mut self) -> bool {
+            let zones = self.old_window.container.as_mut().map(|c| c.drop_zones_mut()).unwrap_or_default();
+            if zones.is_empty() {
+                return false;
+            }
+            for zone in zones {
+                zone.drag_over = true;
+            }
+            self.dropped_files = Some(vec![]);
+            self.rebuild_container();
+            true
+        }
+
+        /// Gives the dropped files to the DropZone under the pointer, or to the first one shown.
+        /// SDL doesn't move the pointer during a drop on every platform, so it's where it last was
+        fn complete_drop(&mut self) -> bool {
+            let (files, container) = match (self.dropped_files.take(), self.old_window.container.as_mut()) {
+                (Some(files), Some(container)) => (files, container),
+                _ => return false,
             };
-            let v2 = sys::SDL_Vertex {
-                position: sys::SDL_FPoint {
-                    x: 600.,
-                    y: 450.,
-                },
-                color: sys::SDL_Color {
-                    r: 0,
-                    g: 255,
-                    b: 0,
-                    a: 255,
-                },
-                tex_coord: sys::SDL_FPoint {
-                    x: 0.,
-                    y: 0.,
+            let hovered = self.hovered.clone().unwrap_or_default();
+            let hovered_zone = (1..=hovered.len()).rev()
+                .map(|len| hovered[..len].to_vec())
+                .find(|path| container.child_at_mut(path).is_some_and(|c| c.is::<DropZone>()));
+            for zone in container.drop_zones_mut() {
+                zone.drag_over = false;
+            }
+            let target = match hovered_zone {
+                Some(path) => container.child_at_mut(&path).and_then(|c| c.downcast_mut::<DropZone>()),
+                None => container.drop_zones_mut().into_iter().next(),
+            };
+            if let Some(zone) = target {
+                zone.drop_files(files);
+            }
+            self.rebuild_container();
+            true
+        }
+
+        /// Handles an event made up by the app as if the user did it, e.g. tutorials clicking
+        /// buttons for the user or tests driving a live window. It goes through handle_event() like
+        /// the events of SDL, except that macros being recorded leave it out. Returns whether it
+        /// was consumed
+        pub fn inject_event(&mut self, event: Event) -> bool {
+            let injecting = std::mem::replace(&mut self.injecting, true);
+            let consumed = self.handle_event(&event);
+            self.injecting = injecting;
+            consumed
+        }
+
+        /// Moves the pointer to x, y and clicks there with the left button through inject_event().
+        /// Returns whether the click was consumed
+        pub fn inject_click(&mut self, x: i32, y: i32) -> bool {
+            let window_id = self.canvas.window().id();
+            self.inject_event(Event::MouseMotion {
+                timestamp: 0,
+                window_id,
+                which: 0,
+                mousestate: MouseState::from_sdl_state(0),
+                x,
+                y,
+                xrel: 0,
+                yrel: 0,
+            });
+            let button = |down: bool| {
+                let (mouse_btn, clicks, which) = (MouseButton::Left, 1, 0);
+                match down {
+                    true => Event::MouseButtonDown { timestamp: 0, window_id, which, mouse_btn, clicks, x, y },
+                    false => Event::MouseButtonUp { timestamp: 0, window_id, which, mouse_btn, clicks, x, y },
+                }
+            };
+            let consumed = self.inject_event(button(true));
+            self.inject_event(button(false)) || consumed
+        }
+
+        /// Whether the app is in the background, see handle_event()
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// The renderer lost its textures (e.g. Android recreated the surface), the glyph atlas and
+        /// the images are created and uploaded again on the next render
+        fn reset_textures(&mut self) {
+            self.textures = SDLTextures::new(self.textures.budget);
+            mark_atlas_dirty();
+        }
+
+        /// Keys go to the focused component, typing to a TextField, a TextArea or a ChipInput, the
+        /// arrows to a Slider, a Rating, a SegmentedControl, a ComboBox, the days of a DatePicker
+        /// (PageUp and PageDown the months) or the caret of a Label (Ctrl+C copies what it selected)
+        /// and Return or Space activate the rest. Escape leaves it, Return too for a TextField or a
+        /// Slider while it breaks the line of a TextArea and makes a chip of what a ChipInput has typed
+        fn handle_focused_event(&mut self, event: &Event) -> bool {
+            let path = match self.focused.clone() {
+                Some(path) => path,
+                None => return false,
+            };
+            let component = match self.old_window.container.as_mut().and_then(|c| c.child_at_mut(&path)) {
+                Some(component) => component,
+                None => {
+                    self.set_focus(None);
+                    return false;
+                }
+            };
+            let activated = match event {
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                    self.set_focus(None);
+                    return true;
+                }
+                Event::KeyDown { keycode: Some(Keycode::Return | Keycode::KpEnter | Keycode::Space), .. } => {
+                    component.is::<Button>() || component.is::<Checkbox>() || component.is::<ComboBox>()
+                        || component.is::<DatePicker>() || component.is::<Hyperlink>()
+                }
+                _ => false,
+            };
+            if activated {
+                let event = crate::elements::Event { source: "Keyboard".to_string(), position: None };
+                component.handle_click(&[], event);
+                // There is no pointer to release it
+                if self.fire_button(&path, ButtonEvent::Release) && self.hovered.as_ref() != Some(&path) {
+                    self.fire_button(&path, ButtonEvent::PointerLeave);
+                }
+                self.rebuild_container();
+                return true;
+            }
+            if let Event::KeyDown { keycode: Some(Keycode::Return | Keycode::KpEnter), .. } = event {
+                if let Some(text_area) = component.downcast_mut::<TextArea>() {
+                    text_area.insert("\n");
+                    self.rebuild_container();
+                    return true;
+                }
+                if let Some(chip_input) = component.downcast_mut::<ChipInput>().filter(|c| !c.text.is_empty()) {
+                    chip_input.commit(None);
+                    self.rebuild_container();
+                    return true;
+                }
+                self.set_focus(None);
+                return true;
+            }
+            let consumed = if let Some(text_field) = component.downcast_mut::<TextField>() {
+                let changed = match event {
+                    Event::TextInput { text, .. } => {
+                        text_field.text.push_str(text);
+                        true
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Backspace), .. } => text_field.text.pop().is_some(),
+                    Event::TextEditing { .. } => return true,
+                    _ => false,
+                };
+                if changed {
+                    text_field.validate(Trigger::Change);
+                    let event = crate::elements::Event { source: "Keyboard".to_string(), position: None };
+                    text_field.on_change.call(event, text_field.text.clone());
+                }
+                changed
+            } else if let Some(text_area) = component.downcast_mut::<TextArea>() {
+                let page = text_area.extent.get().viewport.1;
+                match event {
+                    Event::TextInput { text, .. } => text_area.insert(text),
+                    Event::KeyDown { keycode: Some(Keycode::Backspace), .. } => text_area.backspace(),
+                    Event::KeyDown { keycode: Some(Keycode::PageUp), .. } => text_area.scroll_by(-page),
+                    Event::KeyDown { keycode: Some(Keycode::PageDown), .. } => text_area.scroll_by(page),
+                    Event::TextEditing { .. } => true,
+                    _ => false,
+                }
+            } else if let Some(chip_input) = component.downcast_mut::<ChipInput>() {
+                match event {
+                    Event::TextInput { text, .. } => chip_input.insert(text, None),
+                    Event::KeyDown { keycode: Some(Keycode::Backspace), .. } => chip_input.backspace(),
+                    Event::TextEditing { .. } => true,
+                    _ => false,
+                }
+            } else if let Some(slider) = component.downcast_mut::<Slider>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::Left | Keycode::Down), .. } => {
+                        slider.step_by(-1);
+                        true
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Right | Keycode::Up), .. } => {
+                        slider.step_by(1);
+                        true
+                    }
+                    _ => false,
+                }
+            } else if let Some(rating) = component.downcast_mut::<Rating>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::Left | Keycode::Down), .. } => {
+                        rating.step_by(-1);
+                        true
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Right | Keycode::Up), .. } => {
+                        rating.step_by(1);
+                        true
+                    }
+                    _ => false,
+                }
+            } else if let Some(carousel) = component.downcast_mut::<Carousel>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::Left), .. } => carousel.previous(None),
+                    Event::KeyDown { keycode: Some(Keycode::Right), .. } => carousel.next(None),
+                    Event::KeyDown { keycode: Some(Keycode::Home), .. } => carousel.go_to(0, None),
+                    Event::KeyDown { keycode: Some(Keycode::End), .. } => {
+                        carousel.go_to(carousel.pages.len().saturating_sub(1), None)
+                    }
+                    _ => false,
+                }
+            } else if let Some(segmented_control) = component.downcast_mut::<SegmentedControl>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::Left), .. } => {
+                        segmented_control.move_selection(-1);
+                        true
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Right), .. } => {
+                        segmented_control.move_selection(1);
+                        true
+                    }
+                    _ => false,
+                }
+            } else if let Some(spinner) = component.downcast_mut::<Spinner>() {
+                match event {
+                    Event::TextInput { text, .. } => spinner.insert(text),
+                    Event::KeyDown { keycode: Some(Keycode::Backspace), .. } => spinner.backspace(),
+                    Event::KeyDown { keycode: Some(Keycode::Up), .. } => spinner.step_by(1, None),
+                    Event::KeyDown { keycode: Some(Keycode::Down), .. } => spinner.step_by(-1, None),
+                    Event::TextEditing { .. } => true,
+                    _ => false,
+                }
+            } else if let Some(date_picker) = component.downcast_mut::<DatePicker>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::Left), .. } => date_picker.step_by(-1, 0),
+                    Event::KeyDown { keycode: Some(Keycode::Right), .. } => date_picker.step_by(1, 0),
+                    Event::KeyDown { keycode: Some(Keycode::Up), .. } => date_picker.step_by(-7, 0),
+                    Event::KeyDown { keycode: Some(Keycode::Down), .. } => date_picker.step_by(7, 0),
+                    Event::KeyDown { keycode: Some(Keycode::PageUp), .. } => date_picker.step_by(0, -1),
+                    Event::KeyDown { keycode: Some(Keycode::PageDown), .. } => date_picker.step_by(0, 1),
+                    _ => false,
+                }
+            } else if let Some(list_view) = component.downcast_mut::<ListView>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::Up), .. } => list_view.move_selection(-1),
+                    Event::KeyDown { keycode: Some(Keycode::Down), .. } => list_view.move_selection(1),
+                    Event::KeyDown { keycode: Some(Keycode::PageUp), .. } => {
+                        list_view.move_selection(-(list_view.visible_rows as i32))
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::PageDown), .. } => {
+                        list_view.move_selection(list_view.visible_rows as i32)
+                    }
+                    _ => false,
+                }
+            } else if let Some(table) = component.downcast_mut::<TableView>() {
+                match event {
+                    Event::KeyDown { keycode: Some(Keycode::C), keymod, .. }
+                    if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                        if let Err(e) = copy_selection(table) {
+                            println!("Failed to copy the selection {}", e);
+                        }
+                        return true;
+                    }
+                    _ => false,
+                }
+            } else if let Some(label) = component.downcast_mut::<Label>() {
+                let (keycode, keymod) = match event {
+                    Event::KeyDown { keycode: Some(keycode), keymod, .. } => (*keycode, *keymod),
+                    _ => return false,
+                };
+                let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+                let movement = match keycode {
+                    Keycode::Left => Some(CaretMove::Left),
+                    Keycode::Right => Some(CaretMove::Right),
+                    Keycode::Up => Some(CaretMove::Up),
+                    Keycode::Down => Some(CaretMove::Down),
+                    Keycode::Home if ctrl => Some(CaretMove::TextStart),
+                    Keycode::End if ctrl => Some(CaretMove::TextEnd),
+                    Keycode::Home => Some(CaretMove::LineStart),
+                    Keycode::End => Some(CaretMove::LineEnd),
+                    _ => None,
+                };
+                match movement {
+                    Some(movement) => {
+                        label.move_caret(movement, keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD))
+                    }
+                    None if ctrl && keycode == Keycode::C => {
+                        if let Err(e) = label.selected_text().map_or(Ok(()), |text| copy_to_clipboard(&text)) {
+                            println!("Failed to copy the selection {}", e);
+                        }
+                        return true;
+                    }
+                    None => false,
+                }
+            } else if let Some(combo_box) = component.downcast_mut::<ComboBox>() {
+                let index = match (event, combo_box.selected) {
+                    (Event::KeyDown { keycode: Some(Keycode::Up), .. }, Some(selected)) => selected.checked_sub(1),
+                    (Event::KeyDown { keycode: Some(Keycode::Down), .. }, Some(selected)) => Some(selected + 1),
+                    (Event::KeyDown { keycode: Some(Keycode::Down), .. }, None) => Some(0),
+                    _ => None,
+                };
+                match index {
+                    Some(index) => {
+                        let open = combo_box.open;
+                        combo_box.select(index, None);
+                        combo_box.open = open;
+                        true
+                    }
+                    None => false,
+                }
+            } else {
+                false
+            };
+            if consumed {
+                self.rebuild_container();
+            }
+            consumed
+        }
+
+        /// Moves the focus to the component at path in the container, None leaves the focused one
+        fn set_focus(&mut self, path: Option<Vec<usize>>) {
+            if self.focused.is_some() {
+                self.stop_text_input();
+            }
+            let previous = std::mem::replace(&mut self.focused, path.clone());
+            let mut changed = false;
+            if let Some(previous) = previous.filter(|p| path.as_ref() != Some(p)) {
+                changed |= self.set_label_caret(&previous, None);
+                // Leaving a TextField validates it and leaving a Spinner makes what was typed its value
+                if let Some(component) = self.old_window.container.as_mut().and_then(|c| c.child_at_mut(&previous)) {
+                    if let Some(text_field) = component.downcast_mut::<TextField>() {
+                        changed |= text_field.validate(Trigger::Blur);
+                    } else if let Some(spinner) = component.downcast_mut::<Spinner>() {
+                        changed |= spinner.commit(None);
+                    }
+                }
+            }
+            if let Some(path) = path.filter(|_| accessibility::caret_browsing()) {
+                changed |= self.set_label_caret(&path, Some(Caret::default()));
+            }
+            if changed {
+                self.rebuild_container();
+            }
+            self.update_focus_ring();
+        }
+
+        /// Sets the caret of the Label at path, a focused one keeps the caret it has. Returns
+        /// whether it changed
+        fn set_label_caret(&mut self, path: &[usize], caret: Option<Caret>) -> bool {
+            let label = match self.old_window.container.as_mut()
+                .and_then(|c| c.child_at_mut(path))
+                .and_then(|c| c.downcast_mut::<Label>()) {
+                Some(label) => label,
+                None => return false,
+            };
+            let changed = label.caret.is_some() != caret.is_some();
+            if changed {
+                label.caret = caret;
+            }
+            changed
+        }
+
+        /// Like set_focus() but an editable TextField or TextArea starts the text input too, as
+        /// when clicked
+        fn focus(&mut self, path: Vec<usize>) {
+            let editable = self.old_window.container.as_mut()
+                .and_then(|c| c.child_at_mut(&path))
+                .is_some_and(|c| is_editable_text(c.as_ref()));
+            let area = self.components.iter().find(|b| b._name == "Container")
+                .and_then(|b| b.area_of(&path))
+                .map(|a| (a.x, a.y, a.w, a.h));
+            self.set_focus(Some(path));
+            if let (true, Some((x, y, w, h))) = (editable, area) {
+                self.start_text_input(x, y, w, h);
+            }
+        }
+
+        /// Tab moves the focus to the next component of the innermost focus scope and Shift+Tab
+        /// to the previous one. It's consumed inside a scope even when nothing there can take it
+        fn cycle_focus(&mut self, backwards: bool) -> bool {
+            let targets = match &self.old_window.container {
+                Some(container) => container.focusable_paths(),
+                None => vec![],
+            };
+            match self.focus_scopes.cycle(&targets, self.focused.as_deref(), backwards) {
+                Some(path) => {
+                    self.focus(path);
+                    true
+                }
+                None => self.focus_scopes.innermost().is_some(),
+            }
+        }
+
+        /// Confines Tab to the components of the container under region (nothing when None)
+        /// until pop_focus_scope() is called with the same name, the focus is left until then
+        pub fn push_focus_scope(&mut self, name: &str, region: Option<Vec<usize>>) {
+            self.focus_scopes.push(name, region, self.focused.clone());
+            self.set_focus(None);
+        }
+
+        /// Closes the scope named name, what had the focus when it was opened gets it back
+        pub fn pop_focus_scope(&mut self, name: &str) {
+            if let Some(path) = self.focus_scopes.pop(name) {
+                self.focus(path);
+            }
+        }
+
+        /// An outline around the focused component, it's drawn over the rest of the window
+        fn update_focus_ring(&mut self) {
+            self.components.retain(|b| b._name != "FocusRing");
+            let area = self.focused.as_ref().and_then(|path| {
+                self.components.iter().find(|b| b._name == "Container")?.area_of(path)
+            });
+            if let Some(area) = area {
+                let mut ring = SDLBody::new("FocusRing");
+                let style = StrokeStyle::solid(FOCUS_RING_WIDTH).with_join(LineJoin::Round);
+                ring.push(rect_stroke_polygon(area.x - FOCUS_RING_WIDTH, area.y - FOCUS_RING_WIDTH,
+                                              area.w + 2. * FOCUS_RING_WIDTH, area.h + 2. * FOCUS_RING_WIDTH,
+                                              &style, SELECTED_BACKGROUND));
+                self.components.push(ring);
+            }
+        }
+
+        /// The path of the hit area of the container under the pointer
+        fn container_path_at(&self, x: i32, y: i32) -> Option<Vec<usize>> {
+            self.components.iter().find(|b| b._name == "Container")
+                .and_then(|b| b.hit_test(x as f32, y as f32))
+                .map(|path| path.to_vec())
+        }
+
+        /// Gives event to the Button at path in the container, returns whether its state changed
+        fn fire_button(&mut self, path: &[usize], event: ButtonEvent) -> bool {
+            self.old_window.container.as_mut()
+                .and_then(|c| c.child_at_mut(path))
+                .and_then(|c| c.downcast_mut::<Button>())
+                .is_some_and(|b| b.states.fire(event))
+        }
+
+        /// Scrolls the ListView, TableView or Console under the pointer by rows, over one of its rows
+        /// or the empty part, a TableView also by columns. A Spinner under it steps instead, up the
+        /// wheel increments
+        fn scroll_hovered(&mut self, columns: i32, rows: i32) -> bool {
+            let (hovered, container) = match (self.hovered.clone(), self.old_window.container.as_mut()) {
+                (Some(hovered), Some(container)) => (hovered, container),
+                _ => return false,
+            };
+            let spinner_path = [hovered.len(), hovered.len().saturating_sub(1)].into_iter()
+                .map(|len| hovered[..len].to_vec())
+                .find(|path| !path.is_empty() && container.child_at_mut(path).is_some_and(|c| c.is::<Spinner>()));
+            if let Some(path) = spinner_path {
+                let stepped = rows != 0 && container.child_at_mut(&path)
+                    .and_then(|c| c.downcast_mut::<Spinner>())
+                    .is_some_and(|s| s.step_by(-rows.signum(), None));
+                if stepped {
+                    self.rebuild_container();
+                }
+                return stepped;
+            }
+            let list_path = [hovered.len(), hovered.len().saturating_sub(1)].into_iter()
+                .map(|len| hovered[..len].to_vec())
+                .find(|path| !path.is_empty() && container.child_at_mut(path).is_some_and(|c| c.is::<ListView>()));
+            let table_path = (1..=hovered.len()).rev()
+                .map(|len| hovered[..len].to_vec())
+                .find(|path| container.child_at_mut(path)
+                    .is_some_and(|c| c.is::<TableView>() || c.is::<TreeTable>() || c.is::<Console>()));
+            let scrolled = match (list_path, table_path) {
+                (Some(path), _) => container.child_at_mut(&path)
+                    .and_then(|c| c.downcast_mut::<ListView>())
+                    .is_some_and(|l| l.scroll_by(rows)),
+                (None, Some(path)) => match container.child_at_mut(&path) {
+                    Some(console) if console.is::<Console>() => {
+                        console.downcast_mut::<Console>().is_some_and(|c| c.scroll_by(rows))
+                    }
+                    Some(tree) if tree.is::<TreeTable>() => {
+                        tree.downcast_mut::<TreeTable>().is_some_and(|t| t.scroll_by(rows))
+                    }
+                    table => table.and_then(|c| c.downcast_mut::<TableView>()).is_some_and(|t| {
+                        t.scroll_by(rows) | t.scroll_x_by(columns as f32 * text_size() * SCROLL_WHEEL_LINES)
+                    }),
                 },
+                // Otherwise the innermost ScrollPane or TextArea under the pointer
+                (None, None) => {
+                    let step = text_size() * SCROLL_WHEEL_LINES;
+                    let pane_path = (1..=hovered.len()).rev()
+                        .map(|len| hovered[..len].to_vec())
+                        .find(|path| container.child_at_mut(path)
+                            .is_some_and(|c| c.is::<ScrollPane>() || c.is::<TextArea>()));
+                    match pane_path.and_then(|path| container.child_at_mut(&path)) {
+                        Some(pane) if pane.is::<TextArea>() => pane.downcast_mut::<TextArea>()
+                            .is_some_and(|t| t.scroll_by(rows as f32 * step)),
+                        Some(pane) => pane.downcast_mut::<ScrollPane>()
+                            .is_some_and(|p| p.scroll_by(columns as f32 * step, rows as f32 * step)),
+                        None => false,
+                    }
+                }
+            };
+            if scrolled {
+                self.rebuild_container();
+            }
+            scrolled
+        }
+
+        /// Tells the Buttons when the pointer enters or leaves them
+        fn update_hover(&mut self, x: i32, y: i32) {
+            let path = self.container_path_at(x, y);
+            if path == self.hovered {
+                return;
+            }
+            let previous = std::mem::replace(&mut self.hovered, path.clone());
+            let over_link = path.as_ref().is_some_and(|p| self.is_link(p));
+            self.point_at_link(over_link);
+            let left = previous.is_some_and(|p| self.fire_button(&p, ButtonEvent::PointerLeave) | self.hover_rating(&p, false));
+            let entered = path.is_some_and(|p| self.fire_button(&p, ButtonEvent::PointerEnter) | self.hover_rating(&p, true));
+            if left || entered {
+                self.rebuild_container();
+            }
+        }
+
+        /// Whether path is a Hyperlink, a place of a Breadcrumb or a link of a MarkdownView
+        fn is_link(&mut self, path: &[usize]) -> bool {
+            let container = match self.old_window.container.as_mut() {
+                Some(container) => container,
+                None => return false,
+            };
+            if container.child_at_mut(path).is_some_and(|c| c.is::<Hyperlink>()) {
+                return true;
+            }
+            match path.split_last() {
+                Some((_, owner_path)) if !owner_path.is_empty() => {
+                    container.child_at_mut(owner_path).is_some_and(|c| c.is::<Breadcrumb>() || c.is::<MarkdownView>())
+                }
+                _ => false,
+            }
+        }
+
+        /// Shows the hand while the pointer is over a link, and the pointer it replaced once it
+        /// leaves
+        fn point_at_link(&mut self, over_link: bool) {
+            if over_link == self.hand_cursor.is_some() {
+                return;
+            }
+            if !over_link {
+                self.hand_cursor = None;
+                match &self.cursor {
+                    Some(cursor) => cursor.set(),
+                    None => match Cursor::from_system(SystemCursor::Arrow) {
+                        Ok(arrow) => arrow.set(),
+                        Err(e) => println!("Failed to restore the pointer {}", e),
+                    },
+                }
+                return;
+            }
+            match Cursor::from_system(SystemCursor::Hand) {
+                Ok(hand) => {
+                    hand.set();
+                    self.hand_cursor = Some(hand);
+                }
+                Err(e) => println!("Failed to show the hand pointer {}", e),
+            }
+        }
+
+        /// Previews the score of the Rating whose star (or half of one) at path was entered, or
+        /// stops when it was left. Returns whether the preview changed
+        fn hover_rating(&mut self, path: &[usize], entered: bool) -> bool {
+            let (part, owner_path) = match path.split_last() {
+                Some((part, owner_path)) if !owner_path.is_empty() => (*part, owner_path),
+                _ => return false,
+            };
+            self.old_window.container.as_mut()
+                .and_then(|c| c.child_at_mut(owner_path))
+                .and_then(|c| c.downcast_mut::<Rating>())
+                .is_some_and(|r| r.hover(entered.then_some(part)))
+        }
+
+        fn release_buttons(&mut self, x: i32, y: i32) {
+            let released = self.container_path_at(x, y).is_some_and(|p| self.fire_button(&p, ButtonEvent::Release));
+            if released {
+                self.rebuild_container();
+            }
+        }
+
+        /// Stops repeating the arrow of the Spinner held down, wherever the pointer is
+        fn release_spinner(&mut self) {
+            if let Some(path) = self.held_spinner.take() {
+                let spinner = self.old_window.container.as_mut()
+                    .and_then(|c| c.child_at_mut(&path))
+                    .and_then(|c| c.downcast_mut::<Spinner>());
+                if let Some(spinner) = spinner {
+                    spinner.release();
+                }
+            }
+        }
+
+        /// Moves the thumb of the dragged Slider or scrollbar, the dragged column border or the rubber
+        /// band of a ListView to the pointer
+        fn drag(&mut self, x: i32, y: i32) {
+            let (path, area) = match &self.dragging {
+                Some(dragging) => dragging.clone(),
+                None => return,
+            };
+            let dragged = self.drag_scrollbar(&path, &area, (x, y))
+                .or_else(|| self.drag_color_picker(&path, &area, (x, y)))
+                .or_else(|| self.drag_column_border(&path, &area, x))
+                .or_else(|| self.drag_carousel(&path, &area, x))
+                .or_else(|| self.drag_list_rubber_band(&path, &area, (x, y)));
+            let changed = match dragged {
+                Some(changed) => changed,
+                None => self.old_window.container.as_mut()
+                    .and_then(|c| c.child_at_mut(&path))
+                    .and_then(|c| c.downcast_mut::<Slider>())
+                    .is_some_and(|s| s.set_fraction(slider_fraction(&area, x), Some((x, y)))),
+            };
+            if changed {
+                self.rebuild_container();
+            }
+        }
+
+        /// Scrolls the ScrollPane or TableView owning the scrollbar at path so its thumb is under the
+        /// pointer, None when path is not a scrollbar. Otherwise returns whether it scrolled
+        fn drag_scrollbar(&mut self, path: &[usize], track: &SDLHitArea, position: (i32, i32)) -> Option<bool> {
+            let (bar, pane_path) = path.split_last()?;
+            let owner = self.old_window.container.as_mut()?.child_at_mut(pane_path)?;
+            if let Some(table) = owner.downcast_mut::<TableView>() {
+                if *bar != TableView::HORIZONTAL_SCROLLBAR {
+                    return None;
+                }
+                let (_, length) = table.horizontal_thumb()?;
+                return Some(table.set_scroll_x_fraction(scrollbar_fraction(track, length, false, position)));
+            }
+            let vertical = match *bar {
+                ScrollPane::VERTICAL_SCROLLBAR => true,
+                ScrollPane::HORIZONTAL_SCROLLBAR => false,
+                _ => return None,
+            };
+            let pane = owner.downcast_mut::<ScrollPane>()?;
+            let (_, length) = pane.thumb(vertical)?;
+            Some(pane.set_scroll_fraction(vertical, scrollbar_fraction(track, length, vertical, position)))
+        }
+
+        /// Picks the color under the pointer in the square or the hue strip at path of a
+        /// ColorPicker, None when path is neither. Otherwise returns whether the color changed
+        fn drag_color_picker(&mut self, path: &[usize], area: &SDLHitArea, position: (i32, i32)) -> Option<bool> {
+            let (part, picker_path) = path.split_last()?;
+            let picker = self.old_window.container.as_mut()?
+                .child_at_mut(picker_path)?
+                .downcast_mut::<ColorPicker>()?;
+            let x = ((position.0 as f32 - area.x) / area.w.max(1.)).clamp(0., 1.);
+            let y = ((position.1 as f32 - area.y) / area.h.max(1.)).clamp(0., 1.);
+            let (hue, saturation, value) = match *part {
+                ColorPicker::SQUARE => (picker.hue, x, 1. - y),
+                ColorPicker::HUE => (y * 360., picker.saturation, picker.value),
+                _ => return None,
+            };
+            Some(picker.set_hsv(hue, saturation, value, Some(position)))
+        }
+
+        /// The Carousel whose page at path is dragged, None when path is not the background of a
+        /// page
+        fn dragged_carousel(&mut self, path: &[usize]) -> Option<&mut Carousel> {
+            let (_, rest) = path.split_last()?;
+            let (part, carousel_path) = rest.split_last()?;
+            if *part != Carousel::PAGES {
+                return None;
+            }
+            self.old_window.container.as_mut()?
+                .child_at_mut(carousel_path)?
+                .downcast_mut::<Carousel>()
+        }
+
+        /// Moves the pages of the Carousel dragged at path by how far the pointer went since the
+        /// press at the left of area, None when path is not the background of a page. Otherwise
+        /// returns whether they moved
+        fn drag_carousel(&mut self, path: &[usize], area: &SDLHitArea, x: i32) -> Option<bool> {
+            let carousel = self.dragged_carousel(path)?;
+            Some(carousel.drag_by((x as f32 - area.x) / area.w.max(1.)))
+        }
+
+        /// Lets go of the page of a Carousel dragged at path, it goes to the page it was dragged
+        /// towards or slides back
+        fn end_carousel_drag(&mut self, path: &[usize], position: (i32, i32)) {
+            if let Some(carousel) = self.dragged_carousel(path) {
+                carousel.end_drag(Some(position));
+                self.rebuild_container();
+            }
+        }
+
+        /// Starts a rubber band where the ListView at path was pressed, on a row when on_row. It's
+        /// dragged from the area of the whole list
+        fn begin_list_rubber_band(&mut self, path: &[usize], position: (i32, i32), on_row: bool) {
+            let area = match self.components.iter().find(|b| b._name == "Container").and_then(|b| b.area_of(path)) {
+                Some(area) => area.clone(),
+                None => return,
+            };
+            let list = self.old_window.container.as_mut()
+                .and_then(|c| c.child_at_mut(path))
+                .and_then(|c| c.downcast_mut::<ListView>());
+            if let Some(list) = list {
+                list.begin_rubber_band(position.0 as f32 - area.x, position.1 as f32 - area.y, on_row);
+                self.dragging = Some((path.to_vec(), area));
+                self.rebuild_container();
+            }
+        }
+
+        /// Moves the end of the rubber band of the ListView at path to the pointer, None when path
+        /// is not a ListView. Otherwise true, the band moved even when the selection didn't change
+        fn drag_list_rubber_band(&mut self, path: &[usize], area: &SDLHitArea, position: (i32, i32)) -> Option<bool> {
+            let list = self.old_window.container.as_mut()?
+                .child_at_mut(path)?
+                .downcast_mut::<ListView>()?;
+            let (x, y) = (position.0 as f32 - area.x, position.1 as f32 - area.y);
+            list.drag_rubber_band(x, y, area.w, list_row_height());
+            Some(true)
+        }
+
+        /// Lets go of the rubber band of the ListView at path, if that's what was dragged
+        fn end_list_rubber_band(&mut self, path: &[usize]) {
+            let list = self.old_window.container.as_mut()
+                .and_then(|c| c.child_at_mut(path))
+                .and_then(|c| c.downcast_mut::<ListView>());
+            if let Some(list) = list {
+                list.model.selection.end_rubber_band();
+                self.rebuild_container();
+            }
+        }
+
+        /// The border grip at path of a TableView or TreeTable column, as the area dragging it
+        /// resizes the column from: starting where the column starts. None when path is not a
+        /// border grip
+        fn column_resize_area(&mut self, path: &[usize], grip: &SDLHitArea) -> Option<SDLHitArea> {
+            let (column, rest) = path.split_last()?;
+            let (part, table_path) = rest.split_last()?;
+            if *part != TableView::COLUMN_BORDER {
+                return None;
+            }
+            let table = self.old_window.container.as_mut()?.child_at_mut(table_path)?;
+            let widths = match table.downcast_ref::<TableView>() {
+                Some(table) => table.columns.widths(),
+                None => table.downcast_ref::<TreeTable>()?.column_layout.widths(),
+            };
+            let width = *widths.get(*column)?;
+            Some(SDLHitArea { x: grip.x + grip.w / 2. - width, ..grip.clone() })
+        }
+
+        /// Resizes the TableView or TreeTable column whose border is at path so it ends at the
+        /// pointer, None when path is not a border grip. Otherwise returns whether the width changed
+        fn drag_column_border(&mut self, path: &[usize], area: &SDLHitArea, x: i32) -> Option<bool> {
+            let (column, rest) = path.split_last()?;
+            let (part, table_path) = rest.split_last()?;
+            if *part != TableView::COLUMN_BORDER {
+                return None;
+            }
+            let table = self.old_window.container.as_mut()?.child_at_mut(table_path)?;
+            let width = x as f32 - area.x;
+            match table.is::<TableView>() {
+                true => table.downcast_mut::<TableView>().map(|t| t.resize_column(*column, width)),
+                false => table.downcast_mut::<TreeTable>().map(|t| t.resize_column(*column, width)),
+            }
+        }
+
+        /// Shows the on-screen keyboard (where there is one) for text typed in the given area,
+        /// which is kept visible above it
+        fn start_text_input(&self, x: f32, y: f32, w: f32, h: f32) {
+            let text_input = self.canvas.window().subsystem().text_input();
+            let scale = self.scale;
+            text_input.set_rect(sdl2::rect::Rect::new((x * scale) as i32, (y * scale) as i32,
+                                                      (w * scale) as u32, (h * scale) as u32));
+            text_input.start();
+        }
+
+        /// Hides the on-screen keyboard, without one text input stays on since SDL starts it on
+        /// desktops and the app may rely on it
+        fn stop_text_input(&self) {
+            let text_input = self.canvas.window().subsystem().text_input();
+            if text_input.has_screen_keyboard_support() {
+                text_input.stop();
+            }
+        }
+
+        fn is_over_ui(&self, x: i32, y: i32) -> bool {
+            let (x, y) = (x as f32, y as f32);
+            self.components.iter().filter_map(|b| b.bounds())
+                .any(|(bx, by, bw, bh)| x >= bx && x < bx + bw && y >= by && y < by + bh)
+        }
+
+        /// While the command palette is open it takes every key and text event: typing filters the
+        /// commands, Up and Down select, Return executes and Escape closes it
+        fn handle_palette_event(&mut self, event: &Event) -> bool {
+            let palette = match self.command_palette.as_mut() {
+                Some(palette) => palette,
+                None => return false,
+            };
+            match event {
+                Event::TextInput { text, .. } => palette.push_str(text),
+                Event::KeyDown { keycode: Some(keycode), .. } => match *keycode {
+                    Keycode::Up => palette.move_selection(-1),
+                    Keycode::Down => palette.move_selection(1),
+                    Keycode::Backspace => palette.pop_char(),
+                    Keycode::Escape => self.command_palette = None,
+                    Keycode::Return | Keycode::KpEnter => {
+                        let id = palette.selected_command().map(|c| c.id.clone());
+                        self.command_palette = None;
+                        if let Some(Err(e)) = id.map(|id| commands::execute(&id)) {
+                            println!("Failed to execute the command {}", e);
+                        }
+                    }
+                    _ => return true,
+                },
+                Event::TextEditing { .. } | Event::KeyUp { .. } => return true,
+                _ => return false,
+            }
+            self.rebuild_command_palette();
+            if self.command_palette.is_none() {
+                self.stop_text_input();
+                self.pop_focus_scope("CommandPalette");
+            }
+            true
+        }
+
+        fn rebuild_command_palette(&mut self) {
+            let pseudo = self.window_pseudo_parent();
+            match self.build_command_palette(&pseudo) {
+                Some(body) => self.replace_body(body),
+                None => self.components.retain(|b| b._name != "CommandPalette"),
+            }
+        }
+
+        /// The open command palette centered at the top of the window
+        fn build_command_palette(&self, pseudo: &Container) -> Option<SDLBody> {
+            let palette = self.command_palette.as_ref()?;
+            let (width, _) = parent_size(pseudo);
+            let mut body = palette.build(pseudo);
+            let palette_width = body.bounds().map(|(_, _, w, _)| w).unwrap_or_default();
+            body.translate((width - palette_width) / 2., COMMAND_PALETTE_TOP);
+            Some(body)
+        }
+
+        /// Shows the About dialog of the application on top of the window, it closes with
+        /// Escape, Return or a click outside of it
+        pub fn show_about(&mut self, info: AppInfo) {
+            if self.about.is_none() {
+                self.push_focus_scope("AboutDialog", None);
+            }
+            self.about = Some(info);
+            let pseudo = self.window_pseudo_parent();
+            if let Some(body) = self.build_about(&pseudo) {
+                self.replace_body(body);
+            }
+        }
+
+        fn close_about(&mut self) {
+            self.about = None;
+            self.components.retain(|b| b._name != "AboutDialog");
+            self.pop_focus_scope("AboutDialog");
+        }
+
+        /// The About dialog centered in the window
+        fn build_about(&self, pseudo: &Container) -> Option<SDLBody> {
+            let info = self.about.as_ref()?;
+            let (width, height) = parent_size(pseudo);
+            let mut body = info.build(pseudo);
+            let (_, _, about_width, about_height) = body.bounds().unwrap_or_default();
+            body.translate((width - about_width) / 2., (height - about_height) / 2.);
+            Some(body)
+        }
+
+        /// Shows the settings dialog editing settings on top of the window, see SettingsDialog. It
+        /// closes with Escape or a click outside of it
+        pub fn show_settings(&mut self, settings: Settings) {
+            if self.settings.is_none() {
+                self.push_focus_scope("SettingsDialog", None);
+            }
+            self.settings = Some(SettingsDialog::open(settings));
+            self.rebuild_settings();
+        }
+
+        /// Sets the value being typed, if any, and closes the settings dialog
+        fn close_settings(&mut self) {
+            if let Some(Err(e)) = self.settings.take().map(|mut dialog| dialog.commit()) {
+                println!("Failed to change the setting {}", e);
+            }
+            self.stop_text_input();
+            self.components.retain(|b| b._name != "SettingsDialog");
+            self.pop_focus_scope("SettingsDialog");
+        }
+
+        fn rebuild_settings(&mut self) {
+            let pseudo = self.window_pseudo_parent();
+            match self.build_settings(&pseudo) {
+                Some(body) => self.replace_body(body),
+                None => self.components.retain(|b| b._name != "SettingsDialog"),
+            }
+        }
+
+        /// The settings dialog centered in the window
+        fn build_settings(&self, pseudo: &Container) -> Option<SDLBody> {
+            let dialog = self.settings.as_ref()?;
+            let (width, height) = parent_size(pseudo);
+            let (dialog_width, dialog_height) = settings_dialog_size(dialog, width, height);
+            let mut body = build_settings_dialog(dialog, dialog_width, dialog_height);
+            body.translate((width - dialog_width) / 2., (height - dialog_height) / 2.);
+            Some(body)
+        }
+
+        /// Toggles or starts typing the setting of the row, see SettingsDialog::click()
+        fn click_setting(&mut self, section: usize, row: usize) {
+            let clicked = match self.settings.as_mut() {
+                Some(dialog) => dialog.click(section, row),
+                None => return,
+            };
+            match clicked {
+                Ok(false) => return,
+                Ok(true) => {}
+                Err(e) => println!("Failed to change the setting {}", e),
+            }
+            self.rebuild_settings();
+            let typing = self.settings.as_ref().is_some_and(|d| d.editing.is_some());
+            let bounds = self.components.iter().find(|b| b._name == "SettingsDialog").and_then(|b| b.bounds());
+            match (typing, bounds) {
+                (true, Some((x, y, w, h))) => self.start_text_input(x, y, w, h),
+                _ => self.stop_text_input(),
+            }
+        }
+
+        /// The keys and the wheel while the settings dialog is open: typing edits the value being
+        /// typed, Return sets it and Escape closes the dialog. Returns whether the event was used
+        fn handle_settings_event(&mut self, event: &Event) -> bool {
+            let (width, height) = parent_size(&self.window_pseudo_parent());
+            let dialog = match self.settings.as_mut() {
+                Some(dialog) => dialog,
+                None => return false,
+            };
+            match event {
+                Event::TextInput { text, .. } => dialog.push_str(text),
+                Event::KeyDown { keycode: Some(Keycode::Backspace), .. } => dialog.pop_char(),
+                Event::KeyDown { keycode: Some(Keycode::Return | Keycode::KpEnter), .. } => {
+                    if let Err(e) = dialog.commit() {
+                        println!("Failed to change the setting {}", e);
+                    }
+                    self.stop_text_input();
+                }
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                    self.close_settings();
+                    return true;
+                }
+                Event::MouseWheel { y, .. } => {
+                    let (_, dialog_height) = settings_dialog_size(dialog, width, height);
+                    let (sections, _) = dialog.settings.by_category();
+                    let row_height = text_size() + 2. * MENU_PADDING;
+                    let content_height = SectionLayout::new(sections, row_height, row_height).content_height();
+                    let last = (content_height - dialog_height).max(0.);
+                    dialog.scroll = (dialog.scroll - *y as f32 * row_height).clamp(0., last);
+                }
+                Event::TextEditing { .. } | Event::KeyDown { .. } | Event::KeyUp { .. } => return true,
+                _ => return false,
+            }
+            self.rebuild_settings();
+            true
+        }
+
+        /// Shows the guided tour over the window from its current step, replacing the one shown if
+        /// any. Escape or the Skip button end it
+        pub fn start_tour(&mut self, tour: Tour) {
+            if tour.step().is_none() {
+                (tour.on_end)(true);
+                return;
+            }
+            if let Some(shown) = self.tour.take() {
+                shown.skip();
+            }
+            self.tour = Some(tour);
+            self.rebuild_tour();
+            self.announce_tour_step();
+        }
+
+        fn next_tour_step(&mut self) {
+            if self.tour.as_mut().is_some_and(|t| !t.advance()) {
+                self.tour = None;
+            }
+            self.rebuild_tour();
+            self.announce_tour_step();
+        }
+
+        fn announce_tour_step(&self) {
+            if let Some(announcement) = self.tour.as_ref().and_then(Tour::announcement) {
+                accessibility_tree::announce(announcement);
+            }
+        }
+
+        fn skip_tour(&mut self) {
+            if let Some(tour) = self.tour.take() {
+                tour.skip();
+            }
+            self.rebuild_tour();
+        }
+
+        /// Whether path is in the widget the tour highlights
+        fn in_tour_target(&self, path: &[usize]) -> bool {
+            let nodes = self.accessible_nodes.as_deref().unwrap_or_default();
+            self.tour.as_ref().and_then(|t| t.target_path(nodes)).is_some_and(|target| path.starts_with(&target))
+        }
+
+        /// Builds the tour over everything else, it follows the highlighted widget when the
+        /// container is rebuilt
+        fn rebuild_tour(&mut self) {
+            self.components.retain(|b| b._name != "Tour");
+            let pseudo = self.window_pseudo_parent();
+            if let Some(body) = self.build_tour(&pseudo) {
+                self.replace_body(body);
+            }
+        }
+
+        fn build_tour(&self, pseudo: &Container) -> Option<SDLBody> {
+            let tour = self.tour.as_ref()?;
+            let highlight = tour.target_path(self.accessible_nodes.as_deref().unwrap_or_default())
+                .and_then(|path| self.layout_box(&path))
+                .map(|b| (b.x - TOUR_HIGHLIGHT_PADDING, b.y - TOUR_HIGHLIGHT_PADDING,
+                          b.w + 2. * TOUR_HIGHLIGHT_PADDING, b.h + 2. * TOUR_HIGHLIGHT_PADDING));
+            Some(build_tour_step(tour, tour.step()?, highlight, parent_size(pseudo)))
+        }
+
+        /// Shows the dialog asking for a file on top of the window, the rest of the window doesn't
+        /// get the pointer nor the keys until it's closed by choosing a file or cancelling it
+        /// (Escape or the Cancel button). Typing goes to its name field
+        pub fn show_file_chooser(&mut self, file_chooser: FileChooser) {
+            if self.file_chooser.is_none() {
+                self.push_focus_scope("FileChooser", None);
+            }
+            self.file_chooser = Some(file_chooser);
+            self.rebuild_file_chooser();
+            let field = self.components.iter().find(|b| b._name == "FileChooser")
+                .and_then(|b| b.area_of(&[FileChooser::FILE_NAME]))
+                .cloned();
+            if let Some(field) = field {
+                self.start_text_input(field.x, field.y, field.w, field.h);
+            }
+        }
+
+        fn close_file_chooser(&mut self) {
+            self.file_chooser = None;
+            self.components.retain(|b| b._name != "FileChooser");
+            self.stop_text_input();
+            self.pop_focus_scope("FileChooser");
+        }
+
+        fn rebuild_file_chooser(&mut self) {
+            let pseudo = self.window_pseudo_parent();
+            if let Some(body) = self.build_file_chooser(&pseudo) {
+                self.replace_body(body);
+            }
+        }
+
+        /// The FileChooser centered in the window
+        fn build_file_chooser(&self, pseudo: &Container) -> Option<SDLBody> {
+            let file_chooser = self.file_chooser.as_ref()?;
+            let (width, height) = parent_size(pseudo);
+            let mut body = file_chooser.build(pseudo);
+            let (_, _, chooser_width, chooser_height) = body.bounds().unwrap_or_default();
+            body.translate((width - chooser_width) / 2., ((height - chooser_height) / 2.).max(0.));
+            Some(body)
+        }
+
+        /// The keys, the clicks and the wheel while the FileChooser is open, it's modal so they
+        /// don't reach the window. Returns whether the event was one of them
+        fn handle_file_chooser_event(&mut self, event: &Event) -> bool {
+            let file_chooser = match self.file_chooser.as_mut() {
+                Some(file_chooser) => file_chooser,
+                None => return false,
+            };
+            let (changed, closed) = match event {
+                Event::TextInput { text, .. } => {
+                    file_chooser.file_name.text.push_str(text);
+                    (true, false)
+                }
+                Event::KeyDown { keycode: Some(keycode), .. } => match *keycode {
+                    Keycode::Backspace => (file_chooser.file_name.text.pop().is_some(), false),
+                    Keycode::Up => (file_chooser.move_selection(-1), false),
+                    Keycode::Down => (file_chooser.move_selection(1), false),
+                    Keycode::Escape => (file_chooser.cancel(None), true),
+                    Keycode::Return | Keycode::KpEnter => (true, file_chooser.choose(None)),
+                    _ => (false, false),
+                },
+                Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+                    let (x_f, y_f) = (*x as f32, *y as f32);
+                    let path = self.components.iter().find(|b| b._name == "FileChooser")
+                        .and_then(|b| b.overlay_hit_area(x_f, y_f).map(|a| a.path.as_slice()).or_else(|| b.hit_test(x_f, y_f)))
+                        .map(|path| path.to_vec());
+                    match path.as_deref() {
+                        Some([FileChooser::CANCEL]) => (file_chooser.cancel(Some((*x, *y))), true),
+                        Some([FileChooser::CHOOSE]) => (true, file_chooser.choose(Some((*x, *y)))),
+                        Some(path) => (file_chooser.click(path, Some((*x, *y))), false),
+                        // Clicks outside of it are ignored
+                        None => (false, false),
+                    }
+                }
+                Event::MouseWheel { y, .. } => (file_chooser.entries.scroll_by(-*y), false),
+                Event::KeyUp { .. } | Event::TextEditing { .. } | Event::MouseMotion { .. }
+                | Event::MouseButtonDown { .. } | Event::MouseButtonUp { .. } => (false, false),
+                _ => return false,
+            };
+            if closed {
+                self.close_file_chooser();
+            } else if changed {
+                self.rebuild_file_chooser();
+            }
+            true
+        }
+
+        /// Identifies the OS window, e.g. the window of the ComponentHandles made for it
+        pub fn id(&self) -> u32 {
+            self.canvas.window().id()
+        }
+
+        /// Backend, SDL version, video driver and renderer, for bug reports
+        pub fn diagnostic_info(&self) -> String {
+            let version = sdl2::version::version();
+            let renderer = self.canvas.info();
+            let driver = self.canvas.window().subsystem().current_video_driver();
+            let (width, height) = self.canvas.output_size().unwrap_or_default();
+            format!("Backend: SDL\nSDL version: {}.{}.{}\nVideo driver: {}\nRenderer: {} \
+                     (accelerated: {})\nOutput size: {}x{}\nPlatform: {}",
+                    version.major, version.minor, version.patch, driver, renderer.name,
+                    renderer.flags & sys::SDL_RendererFlags::SDL_RENDERER_ACCELERATED as u32 != 0,
+                    width, height, sdl2::get_platform())
+        }
+
+        /// Gives the click to the topmost body with a hit area under the pointer
+        fn handle_click(&mut self, x: i32, y: i32) -> bool {
+            let (x_f, y_f) = (x as f32, y as f32);
+            let hit = self.components.iter().rev()
+                .find_map(|b| b.overlay_hit_area(x_f, y_f).map(|a| (b._name.as_str(), a.path.as_slice())))
+                .or_else(|| self.components.iter().rev()
+                    .find_map(|b| b.hit_test(x_f, y_f).map(|path| (b._name.as_str(), path))));
+            let in_container = matches!(hit, Some(("Container", _)));
+            let in_tool_bar = matches!(hit, Some(("ToolBar", _)));
+            let in_menu = matches!(hit, Some(("MainMenu", _)));
+            // Popups close when clicking anywhere but in them or the component they belong to
+            let keep_popup = match hit {
+                Some(("Container", path)) => path.to_vec(),
+                _ => vec![],
+            };
+            let consumed = match hit {
+                Some(("StatusBar", [section])) => match &self.old_window.status_bar {
+                    Some(status_bar) => status_bar.click_section(*section, (x, y)),
+                    None => false,
+                },
+                Some(("ToolBar", path)) => {
+                    let path = path.to_vec();
+                    self.click_tool_bar(&path, (x, y))
+                }
+                Some(("MainMenu", path)) => {
+                    let path = path.to_vec();
+                    if self.old_window.menu.as_mut().is_some_and(|m| m.click(&path)) {
+                        self.rebuild_menu();
+                    }
+                    // Clicks on disabled items are eaten so the menu stays open
+                    true
+                }
+                Some(("AboutDialog", [ABOUT_WEBSITE])) => {
+                    if let Some(website) = self.about.as_ref().and_then(|a| a.website.as_ref()) {
+                        if let Err(e) = sdl2::url::open_url(website) {
+                            println!("Failed to open {} {}", website, e);
+                        }
+                    }
+                    true
+                }
+                Some(("AboutDialog", [ABOUT_COPY_DIAGNOSTICS])) => {
+                    if let Err(e) = copy_to_clipboard(&self.diagnostic_info()) {
+                        println!("Failed to copy the diagnostic info {}", e);
+                    }
+                    true
+                }
+                // Anywhere else in the dialog does nothing, outside of it closes it
+                Some(("AboutDialog", _)) => true,
+                Some(("SettingsDialog", [section, row])) => {
+                    let (section, row) = (*section, *row);
+                    self.click_setting(section, row);
+                    true
+                }
+                Some(("SettingsDialog", _)) => true,
+                Some(("Tour", [TOUR_NEXT])) => {
+                    self.next_tour_step();
+                    true
+                }
+                Some(("Tour", [TOUR_SKIP])) => {
+                    self.skip_tour();
+                    true
+                }
+                // Only the highlighted widget can be used meanwhile
+                Some(("Tour", _)) => true,
+                _ if self.about.is_some() => {
+                    self.close_about();
+                    true
+                }
+                _ if self.settings.is_some() => {
+                    self.close_settings();
+                    true
+                }
+                // Clicking anywhere else closes the menu
+                _ if !in_menu && self.old_window.menu.as_ref().is_some_and(|m| m.menu.open) => {
+                    if let Some(menu) = self.old_window.menu.as_mut() {
+                        menu.menu.close_all();
+                    }
+                    self.rebuild_menu();
+                    true
+                }
+                Some(("Container", path)) => {
+                    let path = path.to_vec();
+                    self.record_click(&path, (x, y));
+                    let in_tour_target = self.in_tour_target(&path);
+                    let consumed = self.click_container(&path, (x, y));
+                    if in_tour_target {
+                        self.next_tour_step();
+                    }
+                    consumed
+                }
+                _ => false,
+            };
+            // Clicks in the container move the focus themselves
+            if !in_container {
+                self.set_focus(None);
+            }
+            let overflow_closed = !in_tool_bar && self.old_window.tool_bar.as_mut()
+                .is_some_and(|t| std::mem::replace(&mut t.overflow_open, false));
+            if overflow_closed {
+                self.rebuild_tool_bar();
+            }
+            if self.old_window.container.as_mut().is_some_and(|c| c.close_popups(&keep_popup)) {
+                self.rebuild_container();
+            }
+            consumed
+        }
+
+        /// Presses the button of the ToolBar at path or opens its overflow menu
+        fn click_tool_bar(&mut self, path: &[usize], position: (i32, i32)) -> bool {
+            let tool_bar = match self.old_window.tool_bar.as_mut() {
+                Some(tool_bar) => tool_bar,
+                None => return false,
+            };
+            let changed = match path {
+                [ToolBar::ITEMS, index] => tool_bar.press(*index, Some(position)),
+                [ToolBar::OVERFLOW, index] => tool_bar.choose_overflow(*index, Some(position)),
+                [ToolBar::OVERFLOW] => {
+                    tool_bar.toggle_overflow();
+                    true
+                }
+                _ => false,
+            };
+            if changed {
+                self.rebuild_tool_bar();
+            }
+            true
+        }
+
+        /// Gives the click to the component of the container at path, rebuilding the container
+        /// when it was consumed
+        /// Where the container laid out the component at path, in window coordinates
+        fn layout_box(&self, path: &[usize]) -> Option<LayoutBox> {
+            self.components.iter().find(|b| b._name == "Container")?
+                .layout_boxes().into_iter().find(|b| b.path == path)
+        }
+
+        /// Adds the click on the component at path to the macro being recorded, if any
+        fn record_click(&self, path: &[usize], position: (i32, i32)) {
+            if !ui_macros::is_recording() || self.injecting {
+                return;
+            }
+            let nodes = self.accessible_nodes.as_deref().unwrap_or_default();
+            if let Some((widget, part)) = WidgetId::of(nodes, path) {
+                let origin = self.layout_box(&widget.path).map_or((0, 0), |b| (b.x as i32, b.y as i32));
+                let offset = (position.0 - origin.0, position.1 - origin.1);
+                ui_macros::record(MacroStep::Click { widget, part, offset });
+            }
+        }
+
+        /// Adds the text or the key the focused component at path took to the macro being
+        /// recorded, if any
+        fn record_key(&self, path: &[usize], event: &Event) {
+            if !ui_macros::is_recording() || self.injecting {
+                return;
+            }
+            let widget = match WidgetId::of(self.accessible_nodes.as_deref().unwrap_or_default(), path) {
+                Some((widget, _)) => widget,
+                None => return,
+            };
+            match event {
+                Event::TextInput { text, .. } => ui_macros::record(MacroStep::Type { widget, text: text.clone() }),
+                Event::KeyDown { keycode: Some(keycode), .. } => {
+                    ui_macros::record(MacroStep::Key { widget, key: keycode.name() })
+                }
+                _ => {}
+            }
+        }
+
+        /// Replays the steps of the macro as the user did them through inject_event(), see
+        /// ui_macros::play()
+        fn play_macro(&mut self, recorded: &Macro) -> Result<(), String> {
+            let window_id = self.canvas.window().id();
+            for step in &recorded.steps {
+                let path = step.widget().resolve(self.accessible_nodes.as_deref().unwrap_or_default())
+                    .ok_or(format!("The macro {} found no {:?}", recorded.name, step.widget()))?;
+                let event = match step {
+                    MacroStep::Click { offset, .. } => {
+                        let origin = self.layout_box(&path).map_or((0, 0), |b| (b.x as i32, b.y as i32));
+                        self.inject_click(origin.0 + offset.0, origin.1 + offset.1);
+                        continue;
+                    }
+                    MacroStep::Type { text, .. } => Event::TextInput { timestamp: 0, window_id, text: text.clone() },
+                    MacroStep::Key { key, .. } => Event::KeyDown {
+                        timestamp: 0,
+                        window_id,
+                        keycode: Some(Keycode::from_name(key).ok_or(format!("{} is not a key", key))?),
+                        scancode: None,
+                        keymod: Mod::NOMOD,
+                        repeat: false,
+                    },
+                };
+                if self.focused.as_ref() != Some(&path) {
+                    self.focus(path);
+                }
+                self.inject_event(event);
+            }
+            Ok(())
+        }
+
+        fn click_container(&mut self, path: &[usize], position: (i32, i32)) -> bool {
+            let event = crate::elements::Event {
+                source: "Container".to_string(),
+                position: Some(position),
+            };
+            let consumed = match self.old_window.container.as_mut() {
+                Some(container) => container.handle_click(path, event),
+                None => false,
+            };
+            if consumed {
+                self.rebuild_container();
+            }
+            // The rows of a ListView, the arrows of a Spinner, the popup of a DatePicker, the stars
+            // of a Rating, the segments of a SegmentedControl, the arrows of a Carousel and the
+            // crosses of a ChipInput are hit tested as [index] inside them, they focus them
+            let owner_path = match path.split_last() {
+                Some((_, owner_path)) if !owner_path.is_empty() => self.old_window.container.as_mut()
+                    .and_then(|c| c.child_at_mut(owner_path))
+                    .filter(|c| c.is::<ListView>() || c.is::<Spinner>() || c.is::<DatePicker>() || c.is::<Rating>()
+                        || c.is::<SegmentedControl>() || c.is::<Carousel>() || c.is::<ChipInput>())
+                    .map(|_| owner_path.to_vec()),
+                _ => None,
+            };
+            if let Some(owner_path) = owner_path {
+                let (spinner, chip_input, list) = self.old_window.container.as_mut()
+                    .and_then(|c| c.child_at_mut(&owner_path))
+                    .map_or((false, false, false), |c| (c.is::<Spinner>(), c.is::<ChipInput>(), c.is::<ListView>()));
+                if spinner {
+                    self.held_spinner = Some(owner_path.clone());
+                }
+                // Dragging from a row selects the rows down or up to the pointer
+                if list {
+                    self.begin_list_rubber_band(&owner_path, position, true);
+                }
+                // Typing goes on after removing a chip
+                match chip_input {
+                    true => self.focus(owner_path),
+                    false => self.set_focus(Some(owner_path)),
+                }
+                return consumed;
+            }
+            // The rows of a TableView are hit tested as [TableView::ROWS, row], they focus it so Ctrl+C
+            // copies the selection
+            if let [table_path @ .., TableView::ROWS, _] = path {
+                let is_table = self.old_window.container.as_mut()
+                    .and_then(|c| c.child_at_mut(table_path))
+                    .is_some_and(|c| c.is::<TableView>());
+                if !table_path.is_empty() && is_table {
+                    self.set_focus(Some(table_path.to_vec()));
+                    return consumed;
+                }
+            }
+            let area = self.components.iter().find(|b| b._name == "Container")
+                .and_then(|b| b.hit_area(position.0 as f32, position.1 as f32))
+                .cloned();
+            if let Some(area) = &area {
+                let dragged = self.drag_scrollbar(path, area, position)
+                    .or_else(|| self.drag_color_picker(path, area, position));
+                if let Some(changed) = dragged {
+                    self.dragging = Some((path.to_vec(), area.clone()));
+                    if changed {
+                        self.rebuild_container();
+                    }
+                    return true;
+                }
+                if let Some(column_area) = self.column_resize_area(path, area) {
+                    self.dragging = Some((path.to_vec(), column_area));
+                    return true;
+                }
+                if let Some(carousel) = self.dragged_carousel(path) {
+                    carousel.start_drag();
+                    // Dragging measures how far the pointer went from the press
+                    self.dragging = Some((path.to_vec(), SDLHitArea { x: position.0 as f32, ..area.clone() }));
+                    self.set_focus(Some(path[..path.len() - 2].to_vec()));
+                    return true;
+                }
+            }
+            let target = self.old_window.container.as_mut().and_then(|c| c.child_at_mut(path));
+            let (target, area) = match (target, area) {
+                (Some(target), Some(area)) => (target, area),
+                _ => {
+                    self.set_focus(None);
+                    return consumed;
+                }
+            };
+            if is_editable_text(target.as_ref()) {
+                self.focused = Some(path.to_vec());
+                self.start_text_input(area.x, area.y, area.w, area.h);
+                self.update_focus_ring();
+            } else if let Some(slider) = target.downcast_mut::<Slider>() {
+                let changed = slider.set_fraction(slider_fraction(&area, position.0), Some(position));
+                self.set_focus(Some(path.to_vec()));
+                self.dragging = Some((path.to_vec(), area));
+                if changed {
+                    self.rebuild_container();
+                }
+                return true;
+            } else if target.is::<ListView>() {
+                self.set_focus(Some(path.to_vec()));
+                self.begin_list_rubber_band(path, position, false);
+                return true;
+            } else if target.is::<Label>() && accessibility::caret_browsing() {
+                let caret = target.downcast_ref::<Label>()
+                    .map(|l| label_caret_at(l, area.w, position.0 as f32 - area.x, position.1 as f32 - area.y));
+                self.set_focus(Some(path.to_vec()));
+                if let Some(label) = self.old_window.container.as_mut()
+                    .and_then(|c| c.child_at_mut(path))
+                    .and_then(|c| c.downcast_mut::<Label>()) {
+                    label.caret = caret;
+                }
+                self.rebuild_container();
+                return true;
+            } else {
+                self.set_focus(None);
+            }
+            consumed
+        }
+
+        /// Ctrl combinations run the registered command with that shortcut, e.g. Ctrl+S, then Ctrl
+        /// and Alt combinations and function keys activate the menu item with that accelerator.
+        /// Otherwise Ctrl+= and Ctrl+- zoom the UI in and out and Ctrl+0 resets the zoom
+        fn handle_shortcut(&mut self, keycode: Keycode, keymod: Mod) -> bool {
+            let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+            let alt = keymod.intersects(Mod::LALTMOD | Mod::RALTMOD);
+            let function_key = matches!(keycode, Keycode::F1 | Keycode::F2 | Keycode::F3 | Keycode::F4
+                | Keycode::F5 | Keycode::F6 | Keycode::F7 | Keycode::F8 | Keycode::F9 | Keycode::F10
+                | Keycode::F11 | Keycode::F12);
+            if !ctrl && !alt && !function_key {
+                return false;
+            }
+            let name = shortcut_name(keycode, keymod);
+            if let Some(command) = commands::find_by_shortcut(&name).filter(|_| ctrl) {
+                if let Err(e) = commands::execute(&command.id) {
+                    println!("Failed to execute the command {}", e);
+                }
+                return true;
+            }
+            if self.old_window.menu.as_mut().is_some_and(|m| m.activate_by_accelerator(&name)) {
+                self.rebuild_menu();
+                return true;
+            }
+            if !ctrl {
+                return false;
+            }
+            match keycode {
+                Keycode::Equals | Keycode::Plus | Keycode::KpPlus => accessibility::zoom_in(),
+                Keycode::Minus | Keycode::KpMinus => accessibility::zoom_out(),
+                Keycode::Num0 | Keycode::Kp0 => accessibility::reset_zoom(),
+                _ => false,
+            }
+        }
+
+        /// Alt shows the mnemonics, Alt+letter opens a menu and a letter activates an entry of the
+        /// open menu
+        fn handle_menu_key(&mut self, keycode: Keycode, keymod: Mod) -> bool {
+            let menu = match self.old_window.menu.as_mut() {
+                Some(menu) => menu,
+                None => return false,
+            };
+            let alt = keymod.intersects(Mod::LALTMOD | Mod::RALTMOD);
+            let changed = match keycode {
+                Keycode::LAlt | Keycode::RAlt => !std::mem::replace(&mut menu.show_mnemonics, true),
+                Keycode::Escape if menu.menu.open => {
+                    menu.menu.close_all();
+                    true
+                }
+                _ => match keycode_letter(keycode) {
+                    Some(letter) if alt => menu.open_by_mnemonic(letter),
+                    Some(letter) => menu.activate_by_mnemonic(letter),
+                    None => false,
+                },
+            };
+            if changed {
+                self.rebuild_menu();
+            }
+            changed
+        }
+
+        /// It takes many SDLBody (trait NativeDrawable) and renders them by using SDL
+        pub fn render(&mut self, drawables: &[SDLBody], texture: Option<&Texture>) -> Result<(), String> {
+            self.sync_glyph_atlas()?;
+            let clear = self.clear_color();
+            SDLWindow::render_bodies(&mut self.canvas, &mut self.textures, drawables, texture, clear)
+        }
+
+        /// Renders what was built by the last rebuild(), everything is rebuilt first when the glyph
+        /// atlas was recreated since then. Nothing is drawn while the app is in the background
+        pub fn render_built(&mut self, texture: Option<&Texture>) -> Result<(), String> {
+            if self.paused {
+                return Ok(());
+            }
+            if self.text_generation != text_generation() {
+                self.rebuild();
+            }
+            self.sync_glyph_atlas()?;
+            let clear = self.clear_color();
+            SDLWindow::render_bodies(&mut self.canvas, &mut self.textures, &self.components, texture, clear)?;
+            // What was drawn is only readable until it's presented
+            if matches!(self.frame_diff, FrameDiffStep::CaptureBefore | FrameDiffStep::CaptureAfter(_)) {
+                self.capture_frame_diff()?;
+            }
+            if !self.embedded {
+                let _span = trace_span!("present");
+                self.canvas.present();
+            }
+            Ok(())
+        }
+
+        /// Moves the frame diff along: captures the frame before, then the one after and shows
+        /// their heatmap, then hides it. Returns what happened, see frame_diff
+        fn step_frame_diff(&mut self) -> String {
+            let (step, answer) = match std::mem::replace(&mut self.frame_diff, FrameDiffStep::Idle) {
+                FrameDiffStep::Idle => (FrameDiffStep::CaptureBefore, "Capturing the frame before".to_string()),
+                FrameDiffStep::Before(before) => {
+                    (FrameDiffStep::CaptureAfter(before), "Capturing the frame after".to_string())
+                }
+                FrameDiffStep::Showing(_) => {
+                    self.components.retain(|b| b._name != "FrameDiff");
+                    (FrameDiffStep::Idle, "Hid the frame diff".to_string())
+                }
+                pending => (pending, "A capture is pending, it's taken with the next frame".to_string()),
+            };
+            self.frame_diff = step;
+            answer
+        }
+
+        /// Reads what was rendered for the pending capture of the frame diff, the second one shows
+        /// the heatmap
+        fn capture_frame_diff(&mut self) -> Result<(), String> {
+            let (width, height) = self.canvas.output_size()?;
+            let pixels = self.canvas.read_pixels(None, sdl2::pixels::PixelFormatEnum::RGBA32)?;
+            let frame = Frame { width, height, pixels };
+            self.frame_diff = match std::mem::replace(&mut self.frame_diff, FrameDiffStep::Idle) {
+                FrameDiffStep::CaptureAfter(before) => match FrameDiff::new(&before, &frame) {
+                    Ok(diff) => {
+                        println!("Frame diff: {}", diff);
+                        self.components.push(build_frame_diff(&diff, self.scale));
+                        FrameDiffStep::Showing(diff)
+                    }
+                    Err(e) => {
+                        println!("Failed to diff the frames {}", e);
+                        FrameDiffStep::Idle
+                    }
+                },
+                _ => FrameDiffStep::Before(frame),
+            };
+            Ok(())
+        }
+
+        /// Renders the UI into target instead of the window, so a host application can composite it
+        /// into its own scene (e.g. a 3D editor drawing it onto a quad). target has to be created
+        /// with TextureAccess::Target by the canvas_mut() of this window, it's cleared to
+        /// transparent so give it BlendMode::Blend to draw it over the scene. Mouse events have to
+        /// be taken to the texture coordinates with remap_mouse_event() before handle_event()
+        pub fn render_to_texture(&mut self, target: &mut Texture) -> Result<(), String> {
+            if self.text_generation != text_generation() {
+                self.rebuild();
+            }
+            self.sync_glyph_atlas()?;
+            let textures = &mut self.textures;
+            let components = &self.components;
+            let mut result = Ok(());
+            self.canvas.with_texture_canvas(target, |canvas| {
+                canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                canvas.clear();
+                result = SDLWindow::render_bodies(canvas, textures, components, None, None);
+            }).map_err(|e| e.to_string())?;
+            result
+        }
+
+        /// Uploads the glyph atlas to its texture when the text pipeline rasterized new glyphs
+        fn sync_glyph_atlas(&mut self) -> Result<(), String> {
+            if let Some((dims, rgba)) = take_dirty_atlas() {
+                let atlas = match self.textures.glyph_atlas.take() {
+                    Some(atlas) if atlas.dims == dims => atlas,
+                    _ => SDLRawTexture::new(&self.canvas, dims)?,
+                };
+                self.textures.glyph_atlas.insert(atlas).update(&rgba)?;
+            }
+            Ok(())
+        }
+
+        /// The color of the background the window is cleared to. None when it's embedded, the app
+        /// clears and presents around its own drawing
+        fn clear_color(&self) -> Option<Color> {
+            if self.embedded {
+                return None;
+            }
+            let crate::elements::Color { r, g, b, .. } = self.old_window.background.clear_color();
+            let (r, g, b) = accessibility::color_mode().map((r, g, b));
+            Some(Color::RGB(r, g, b))
+        }
+
+        fn render_bodies(canvas: &mut WindowCanvas, textures: &mut SDLTextures,
+                         drawables: &[SDLBody], texture: Option<&Texture>, clear: Option<Color>)
+                         -> Result<(), String> {
+            let _alloc = alloc_scope!("render");
+            if let Some(color) = clear {
+                canvas.set_draw_color(color);
+                canvas.clear();
+            }
+            {
+                let _span = trace_span!("batching", bodies = drawables.len());
+                let overlays = drawables.iter().flat_map(|b| b.overlays.iter());
+                textures.drawn_images.clear();
+                for body in drawables.iter().chain(overlays) {
+                    SDLWindow::render_body(canvas, textures, body, None)?;
+                }
+                textures.release_undrawn_images();
+            }
+            if let Some(texture) = texture {
+                canvas.copy(texture, None, None).unwrap();
+            }
+            Ok(())
+        }
+
+        /// Draws the polygons of body and then its clipped bodies, each one inside its rectangle
+        /// and the one of the bodies holding it
+        fn render_body(canvas: &mut WindowCanvas, textures: &mut SDLTextures, body: &SDLBody,
+                       clip: Option<sdl2::rect::Rect>) -> Result<(), String> {
+            for tex_poly in body.polygons.iter() {
+                let tex = match tex_poly.tex {
+                    None => None,
+                    Some(TextureId::GlyphAtlas) => match &textures.glyph_atlas {
+                        Some(atlas) => Some(atlas.raw),
+                        // Nothing to sample from yet
+                        None => continue,
+                    },
+                    Some(TextureId::Image(id)) => match textures.image(canvas, id)? {
+                        Some(image) => Some(image),
+                        None => continue,
+                    },
+                };
+                SDLWindow::render_geometry(canvas, tex, &tex_poly.poly.vers, &tex_poly.poly.inds)?;
+            }
+            for clipped in body.clipped.iter() {
+                if clipped.w < 1. || clipped.h < 1. {
+                    continue;
+                }
+                let rect = sdl2::rect::Rect::new(clipped.x.floor() as i32, clipped.y.floor() as i32,
+                                                 clipped.w.ceil() as u32, clipped.h.ceil() as u32);
+                let rect = match clip {
+                    Some(outer) => match rect.intersection(outer) {
+                        Some(rect) => rect,
+                        None => continue,
+                    },
+                    None => rect,
+                };
+                canvas.set_clip_rect(rect);
+                let result = SDLWindow::render_body(canvas, textures, &clipped.body, Some(rect));
+                canvas.set_clip_rect(clip);
+                result?;
+            }
+            Ok(())
+        }
+
+        /// This function shouldn't be here, SDL_RenderGeometry was introduced in SDL 2.0.18 but
+        /// rust-sdl only support earlier versions so the binding for that function was to be done
+        /// here since SDL_RenderGeometry is the basis of this engine
+        fn render_geometry(canvas: &mut WindowCanvas, texture: Option<*mut sys::SDL_Texture>,
+                           vertices: &Vec<sys::SDL_Vertex>, indices: &Vec<i32>) -> Result<(), String> {
+            if !vertices.is_empty() {
+                let sdl_renderer = canvas.raw();
+                let vers_num = vertices.len() as i32;
+                let vers_ptr = (&vertices[0]) as *const sys::SDL_Vertex;
+                let tex_ptr: *mut sys::SDL_Texture = match texture {
+                    None => ptr::null_mut(),
+                    Some(t) => t
+                };
+                let ind_num = indices.len() as i32;
+                let inds_ptr = match ind_num {
+                    0 => ptr::null(),
+                    _ => (&indices[0])
+                };
+                let ret = unsafe {
+                    sys::SDL_RenderGeometry(sdl_renderer, tex_ptr, vers_ptr, vers_num, inds_ptr, ind_num)
+                };
+                if ret == -1 {
+                    return Err(format!("Failed at SDL_RenderGeometry {}", sdl2::get_error()));
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Drop for SDLWindow<'_> {
+        fn drop(&mut self) {
+            crate::handles::leak_detector::report_leaks(self.id(), &self.old_window.title);
+        }
+    }
+
+    // MainMenu ************************************************************************************
+
+    const TEXT_COLOR: sys::SDL_Color = sdl_color(230, 230, 230, 255);
+    const DISABLED_TEXT_COLOR: sys::SDL_Color = sdl_color(120, 120, 120, 255);
+    const HIGHLIGHT_TEXT_COLOR: sys::SDL_Color = sdl_color(255, 200, 80, 255);
+    const MENU_BACKGROUND: sys::SDL_Color = sdl_color(50, 50, 55, 255);
+    const MENU_PADDING: f32 = 4.;
+    /// Space on the left of the menu items for the checkmark or icon
+    const MENU_GUTTER: f32 = 24.;
+    const MENU_ACCELERATOR_GAP: f32 = 32.;
+
+    impl SDLComponent for MainMenu {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "MainMenu");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let bar_height = size + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("MainMenu");
+            body.push(rect_polygon(0., 0., width, bar_height, MENU_BACKGROUND));
+            body.extend(build_mnemonic_text(&self.menu.title, size, TEXT_COLOR, MENU_PADDING,
+                                            MENU_PADDING, self.show_mnemonics));
+            let title_width = measure_text(&parse_mnemonic(&self.menu.title).0, size).0;
+            body.add_hit_area(0., 0., title_width + 2. * MENU_PADDING, bar_height, vec![]);
+            if self.menu.open {
+                let mut dropdown = build_dropdown(&self.menu, size, self.show_mnemonics);
+                dropdown.translate(0., bar_height);
+                body.append(dropdown);
+            }
+            body
+        }
+    }
+
+    /// Builds a title that may contain a mnemonic (see parse_mnemonic), which is underlined when
+    /// show_mnemonic
+    fn build_mnemonic_text(title: &str, size: f32, color: sys::SDL_Color, x: f32, y: f32,
+                           show_mnemonic: bool) -> Vec<SDLTexturedPolygon> {
+        let (text, mnemonic) = parse_mnemonic(title);
+        let mut polygons = build_text(&text, size, color, x, y);
+        if let (true, Some((index, _))) = (show_mnemonic, mnemonic) {
+            let prefix: String = text.chars().take(index).collect();
+            let letter: String = text.chars().skip(index).take(1).collect();
+            let underline_x = x + measure_text(&prefix, size).0;
+            polygons.push(rect_polygon(underline_x, y + size, measure_text(&letter, size).0,
+                                       (size / 12.).max(1.), color));
+        }
+        polygons
+    }
+
+    /// Builds text whose characters in ranges (char indexes, e.g. from fuzzy::fuzzy_match()) use the
+    /// highlight color
+    pub fn build_highlighted_text(text: &str, ranges: &[Range<usize>], size: f32,
+                                  color: sys::SDL_Color, highlight: sys::SDL_Color, x: f32, y: f32)
+                                  -> Vec<SDLTexturedPolygon> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut polygons = vec![];
+        let mut start = 0;
+        let mut segment_x = x;
+        let mut push_segment = |from: usize, to: usize, color: sys::SDL_Color| {
+            let segment: String = chars[from..to].iter().collect();
+            polygons.extend(build_text(&segment, size, color, segment_x, y));
+            segment_x += measure_text(&segment, size).0;
+        };
+        for range in ranges {
+            if range.start < start || range.end > chars.len() {
+                continue;
+            }
+            push_segment(start, range.start, color);
+            push_segment(range.start, range.end, highlight);
+            start = range.end;
+        }
+        push_segment(start, chars.len(), color);
+        polygons
+    }
+
+    /// Builds the list of children of an open menu, nested open menus are placed to its right.
+    /// The hit areas have the indexes of the entries, see MainMenu::click()
+    fn build_dropdown(menu: &Menu, size: f32, show_mnemonics: bool) -> SDLBody {
+        let row_height = size + 2. * MENU_PADDING;
+        let title_width = menu.children.iter()
+            .map(|c| measure_text(&parse_mnemonic(c.title()).0, size).0)
+            .fold(0., f32::max);
+        let accelerator_width = menu.children.iter()
+            .filter_map(|c| match c {
+                Submenu::MenuItem(item) => item.accelerator.as_ref(),
+                Submenu::Menu(_) => None,
+            })
+            .map(|a| measure_text(a, size).0 + MENU_ACCELERATOR_GAP)
+            .fold(0., f32::max);
+        // There is always room for the arrow of the submenus
+        let width = MENU_GUTTER + title_width + accelerator_width.max(row_height) + MENU_PADDING;
+
+        let mut body = SDLBody::new("Dropdown");
+        body.push(rect_polygon(0., 0., width, row_height * menu.children.len() as f32,
+                               MENU_BACKGROUND));
+        for (i, entry) in menu.children.iter().enumerate() {
+            let y = i as f32 * row_height;
+            let text_y = y + MENU_PADDING;
+            body.add_hit_area(0., y, width, row_height, vec![i]);
+            match entry {
+                Submenu::MenuItem(item) => {
+                    let color = if item.enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+                    match (item.checked, &item.radio_group, &item.icon) {
+                        (Some(true), Some(_), _) => {
+                            body.push(circle_polygon(MENU_GUTTER / 2., y + row_height / 2.,
+                                                     size / 5., color));
+                        }
+                        (Some(true), None, _) => {
+                            body.push(checkmark_polygon(MENU_GUTTER / 4., y + row_height / 2., size,
+                                                        color));
+                        }
+                        (_, _, Some(icon)) => {
+                            body.extend(build_text(icon, size, color, MENU_PADDING, text_y));
+                        }
+                        _ => {}
+                    }
+                    body.extend(build_mnemonic_text(&item.title, size, color, MENU_GUTTER, text_y,
+                                                    show_mnemonics));
+                    if let Some(accelerator) = &item.accelerator {
+                        let accelerator_x = width - MENU_PADDING - measure_text(accelerator, size).0;
+                        body.extend(build_text(accelerator, size, color, accelerator_x, text_y));
+                    }
+                }
+                Submenu::Menu(submenu) => {
+                    body.extend(build_mnemonic_text(&submenu.title, size, TEXT_COLOR, MENU_GUTTER,
+                                                    text_y, show_mnemonics));
+                    let arrow_x = width - MENU_PADDING - size / 3.;
+                    let mid = y + row_height / 2.;
+                    body.push(SDLTexturedPolygon {
+                        poly: SDLPolygon {
+                            vers: vec![vertex(arrow_x, mid - size / 4., TEXT_COLOR),
+                                       vertex(arrow_x, mid + size / 4., TEXT_COLOR),
+                                       vertex(arrow_x + size / 4., mid, TEXT_COLOR)],
+                            inds: vec![],
+                        },
+                        tex: None,
+                    });
+                    if submenu.open {
+                        let mut nested = build_dropdown(submenu, size, show_mnemonics);
+                        nested.translate(width, y);
+                        body.append_child(i, nested);
+                    }
+                }
+            }
+        }
+        body
+    }
+
+    impl Component for MainMenu {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // StatusBar ***********************************************************************************
+
+    const STATUS_SEPARATOR_COLOR: sys::SDL_Color = sdl_color(90, 90, 95, 255);
+    const PROGRESS_TRACK_COLOR: sys::SDL_Color = sdl_color(30, 30, 30, 255);
+    const PROGRESS_FILL_COLOR: sys::SDL_Color = sdl_color(70, 130, 200, 255);
+    const STATUS_PROGRESS_WIDTH: f32 = 100.;
+
+    impl SDLComponent for StatusBar {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "StatusBar");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let height = size + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("StatusBar");
+            body.push(rect_polygon(0., 0., width, height, MENU_BACKGROUND));
+            body.extend(build_text(self.current_text(), size, TEXT_COLOR, MENU_PADDING,
+                                   MENU_PADDING));
+            // Sections are laid out from the right edge, the first one is the leftmost
+            let mut x = width;
+            for (i, section) in self.sections.iter().enumerate().rev() {
+                let section_width = measure_text(&section.text, size).0 + 2. * MENU_PADDING;
+                x -= section_width;
+                body.push(rect_polygon(x, MENU_PADDING, 1., size, STATUS_SEPARATOR_COLOR));
+                body.extend(build_text(&section.text, size, TEXT_COLOR, x + MENU_PADDING,
+                                       MENU_PADDING));
+                body.add_hit_area(x, 0., section_width, height, vec![i]);
+            }
+            if let Some(progress) = self.progress {
+                x -= STATUS_PROGRESS_WIDTH + 2. * MENU_PADDING;
+                let (track_y, track_height) = (height / 4., height / 2.);
+                body.push(rect_polygon(x + MENU_PADDING, track_y, STATUS_PROGRESS_WIDTH,
+                                       track_height, PROGRESS_TRACK_COLOR));
+                body.push(rect_polygon(x + MENU_PADDING, track_y,
+                                       STATUS_PROGRESS_WIDTH * progress.clamp(0., 1.),
+                                       track_height, PROGRESS_FILL_COLOR));
+            }
+            body
+        }
+    }
+
+    /// Builds the status bar docked at the bottom of the parent
+    fn build_docked_status_bar(status_bar: &StatusBar, parent: &dyn Component) -> SDLBody {
+        let (_, parent_height) = parent_size(parent);
+        let mut body = status_bar.build(parent);
+        body.translate(0., parent_height - (text_size() + 2. * MENU_PADDING));
+        body
+    }
+
+    impl Component for StatusBar {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // ToolBar *************************************************************************************
+
+    /// Space around the buttons of the ToolBar
+    const TOOL_BAR_PADDING: f32 = 2.;
+    const TOOL_SEPARATOR_WIDTH: f32 = 2. * MENU_PADDING + 1.;
+
+    fn tool_bar_height() -> f32 {
+        text_size() + 2. * MENU_PADDING + 2. * TOOL_BAR_PADDING
+    }
+
+    fn tool_item_width(item: &ToolItem, size: f32) -> f32 {
+        match item {
+            ToolItem::Button(button) => measure_text(&button.label(), size).0 + 2. * MENU_PADDING,
+            ToolItem::Separator => TOOL_SEPARATOR_WIDTH,
+        }
+    }
+
+    impl SDLComponent for ToolBar {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ToolBar");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let height = tool_bar_height();
+            let button_height = height - 2. * TOOL_BAR_PADDING;
+            let mut body = SDLBody::new("ToolBar");
+            body.push(rect_polygon(0., 0., width, height, MENU_BACKGROUND));
+            body.push(rect_polygon(0., height - 1., width, 1., STATUS_SEPARATOR_COLOR));
+            let widths: Vec<f32> = self.items.iter().map(|i| tool_item_width(i, size)).collect();
+            let overflow_width = button_height;
+            let shown = self.shown_items(ToolBar::fitting_items(&widths, width - TOOL_BAR_PADDING, overflow_width));
+            let mut x = TOOL_BAR_PADDING;
+            for (i, item) in self.items.iter().enumerate().take(shown) {
+                match item {
+                    ToolItem::Button(button) => {
+                        let pressed = self.pressed == Some(i) || button.checked == Some(true);
+                        if pressed {
+                            body.push(rect_polygon(x, TOOL_BAR_PADDING, widths[i], button_height,
+                                                   BUTTON_PRESSED_BACKGROUND));
+                            push_forced_border(&mut body, x, TOOL_BAR_PADDING, widths[i], button_height);
+                        }
+                        let color = if button.enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+                        body.extend(build_text(&button.label(), size, color, x + MENU_PADDING,
+                                               TOOL_BAR_PADDING + MENU_PADDING));
+                        body.add_hit_area(x, TOOL_BAR_PADDING, widths[i], button_height, vec![ToolBar::ITEMS, i]);
+                    }
+                    ToolItem::Separator => {
+                        body.push(rect_polygon(x + MENU_PADDING, TOOL_BAR_PADDING + MENU_PADDING, 1.,
+                                               button_height - 2. * MENU_PADDING, STATUS_SEPARATOR_COLOR));
+                    }
+                }
+                x += widths[i];
+            }
+            if shown < self.items.len() {
+                let overflow_x = width - TOOL_BAR_PADDING - overflow_width;
+                if self.overflow_open {
+                    body.push(rect_polygon(overflow_x, TOOL_BAR_PADDING, overflow_width, button_height,
+                                           BUTTON_PRESSED_BACKGROUND));
+                }
+                let (mid, half) = (overflow_x + overflow_width / 2., overflow_width / 5.);
+                let arrow_y = TOOL_BAR_PADDING + button_height / 2.;
+                body.push(triangle_polygon([(mid - half, arrow_y - half / 2.), (mid + half, arrow_y - half / 2.),
+                                            (mid, arrow_y + half / 2.)], TEXT_COLOR));
+                body.add_hit_area(overflow_x, TOOL_BAR_PADDING, overflow_width, button_height, vec![ToolBar::OVERFLOW]);
+                if self.overflow_open {
+                    body.push_overlay(self.build_overflow_menu(shown, size, width, height));
+                }
+            }
+            body
+        }
+    }
+
+    impl ToolBar {
+        /// The items from shown on in a list under the right end of the ToolBar
+        fn build_overflow_menu(&self, shown: usize, size: f32, width: f32, top: f32) -> SDLBody {
+            let row_height = size + 2. * MENU_PADDING;
+            let hidden = &self.items[shown..];
+            let menu_width = hidden.iter()
+                .map(|item| match item {
+                    ToolItem::Button(button) => measure_text(&button.title, size).0,
+                    ToolItem::Separator => 0.,
+                })
+                .fold(0., f32::max) + MENU_GUTTER + MENU_PADDING;
+            let x = width - menu_width;
+            let mut body = SDLBody::new("ToolBarOverflow");
+            body.push(rect_polygon(x, top, menu_width, row_height * hidden.len() as f32, MENU_BACKGROUND));
+            for (i, item) in hidden.iter().enumerate() {
+                let y = top + i as f32 * row_height;
+                match item {
+                    ToolItem::Button(button) => {
+                        let color = if button.enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+                        match (button.checked, &button.icon) {
+                            (Some(true), _) => {
+                                body.push(checkmark_polygon(x + MENU_GUTTER / 4., y + row_height / 2., size, color));
+                            }
+                            (_, Some(icon)) => {
+                                body.extend(build_text(icon, size, color, x + MENU_PADDING, y + MENU_PADDING));
+                            }
+                            _ => {}
+                        }
+                        body.extend(build_text(&button.title, size, color, x + MENU_GUTTER, y + MENU_PADDING));
+                        body.add_hit_area(x, y, menu_width, row_height, vec![ToolBar::OVERFLOW, shown + i]);
+                    }
+                    ToolItem::Separator => {
+                        body.push(rect_polygon(x + MENU_PADDING, y + row_height / 2., menu_width - 2. * MENU_PADDING,
+                                               1., STATUS_SEPARATOR_COLOR));
+                    }
+                }
+            }
+            push_forced_border(&mut body, x, top, menu_width, row_height * hidden.len() as f32);
+            body
+        }
+    }
+
+    /// Builds the tool bar docked under the menu bar of the window
+    fn build_docked_tool_bar(window: &Window, tool_bar: &ToolBar, parent: &dyn Component) -> SDLBody {
+        let mut body = tool_bar.build(parent);
+        if window.menu.is_some() {
+            body.translate(0., text_size() + 2. * MENU_PADDING);
+        }
+        body
+    }
+
+    impl Component for ToolBar {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // CommandPalette ******************************************************************************
+
+    const COMMAND_PALETTE_TOP: f32 = 40.;
+    const COMMAND_PALETTE_WIDTH: f32 = 500.;
+    const COMMAND_PALETTE_ROWS: usize = 10;
+    const SELECTED_BACKGROUND: sys::SDL_Color = sdl_color(70, 130, 200, 255);
+    const FOCUS_RING_WIDTH: f32 = 2.;
+
+    impl SDLComponent for CommandPalette {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "CommandPalette");
+            let (parent_width, _) = parent_size(parent);
+            let width = COMMAND_PALETTE_WIDTH.min(parent_width - 2. * MENU_PADDING).max(0.);
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let rows = self.matches().len().min(COMMAND_PALETTE_ROWS);
+            // Keeps the selected command visible when there are more than fit
+            let first = (self.selected + 1).saturating_sub(COMMAND_PALETTE_ROWS);
+            let mut body = SDLBody::new("CommandPalette");
+            body.push(rect_polygon(0., 0., width, row_height * (rows + 1) as f32, MENU_BACKGROUND));
+            body.push(rect_outline_polygon(0., 0., width, row_height, 1., TEXT_COLOR));
+            body.extend(build_text(&format!("> {}", self.query), size, TEXT_COLOR, MENU_PADDING,
+                                   MENU_PADDING));
+            for (i, command) in self.matches().iter().enumerate().skip(first).take(rows) {
+                let y = (i - first + 1) as f32 * row_height;
+                if i == self.selected {
+                    body.push(rect_polygon(0., y, width, row_height, SELECTED_BACKGROUND));
+                }
+                let ranges = self.match_of(i).map(|m| m.ranges.as_slice()).unwrap_or_default();
+                body.extend(build_highlighted_text(&command.title, ranges, size, TEXT_COLOR,
+                                                   HIGHLIGHT_TEXT_COLOR, MENU_PADDING,
+                                                   y + MENU_PADDING));
+                if let Some(shortcut) = &command.shortcut {
+                    let shortcut_x = width - MENU_PADDING - measure_text(shortcut, size).0;
+                    body.extend(build_text(shortcut, size, DISABLED_TEXT_COLOR, shortcut_x,
+                                           y + MENU_PADDING));
+                }
+            }
+            body
+        }
+    }
+
+    impl Component for CommandPalette {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // About ***************************************************************************************
+
+    const LINK_COLOR: sys::SDL_Color = sdl_color(100, 160, 255, 255);
+    const BUTTON_BACKGROUND: sys::SDL_Color = sdl_color(80, 80, 90, 255);
+    const BUTTON_HOVER_BACKGROUND: sys::SDL_Color = sdl_color(95, 95, 110, 255);
+    const BUTTON_PRESSED_BACKGROUND: sys::SDL_Color = sdl_color(60, 60, 70, 255);
+    const ABOUT_WEBSITE: usize = 0;
+    const ABOUT_COPY_DIAGNOSTICS: usize = 1;
+    const ABOUT_BACKGROUND: usize = 2;
+
+    /// The About dialog, the website is hit tested as [ABOUT_WEBSITE] and the copy button as
+    /// [ABOUT_COPY_DIAGNOSTICS]
+    impl SDLComponent for AppInfo {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "AboutDialog");
+            let size = text_size();
+            let line_height = size + 2. * MENU_PADDING;
+            let padding = 4. * MENU_PADDING;
+            let button_text = "Copy diagnostic info";
+            let lines = self.lines();
+            let width = lines.iter().map(String::as_str)
+                .chain(self.website.as_deref())
+                .chain(Some(button_text))
+                .map(|l| measure_text(l, size).0)
+                .fold(0., f32::max) + 2. * padding;
+            let rows = lines.len() + self.website.is_some() as usize;
+            let height = rows as f32 * line_height + line_height + 3. * padding;
+            let mut body = SDLBody::new("AboutDialog");
+            body.push(rect_polygon(0., 0., width, height, MENU_BACKGROUND));
+            body.push(rect_outline_polygon(0., 0., width, height, 1., TEXT_COLOR));
+            body.add_hit_area(0., 0., width, height, vec![ABOUT_BACKGROUND]);
+            let mut y = padding;
+            for line in lines.iter() {
+                body.extend(build_text(line, size, TEXT_COLOR, padding, y + MENU_PADDING));
+                y += line_height;
+            }
+            if let Some(website) = &self.website {
+                let (link_width, _) = measure_text(website, size);
+                body.extend(build_text(website, size, LINK_COLOR, padding, y + MENU_PADDING));
+                body.push(rect_polygon(padding, y + MENU_PADDING + size, link_width,
+                                       (size / 12.).max(1.), LINK_COLOR));
+                body.add_hit_area(padding, y, link_width, line_height, vec![ABOUT_WEBSITE]);
+                y += line_height;
+            }
+            y += padding;
+            let button_width = measure_text(button_text, size).0 + 2. * MENU_PADDING;
+            body.push(rect_polygon(padding, y, button_width, line_height, BUTTON_BACKGROUND));
+            body.extend(build_text(button_text, size, TEXT_COLOR, padding + MENU_PADDING,
+                                   y + MENU_PADDING));
+            body.add_hit_area(padding, y, button_width, line_height, vec![ABOUT_COPY_DIAGNOSTICS]);
+            body
+        }
+    }
+
+    impl Component for AppInfo {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Tour ****************************************************************************************
+
+    const TOUR_DIM_COLOR: sys::SDL_Color = sdl_color(0, 0, 0, 150);
+    const TOUR_BUBBLE_WIDTH: f32 = 320.;
+    /// Space between the highlighted widget and its outline
+    const TOUR_HIGHLIGHT_PADDING: f32 = 4.;
+    const TOUR_NEXT: usize = 0;
+    const TOUR_SKIP: usize = 1;
+    const TOUR_DIMMED: usize = 2;
+    const TOUR_BUBBLE: usize = 3;
+
+    /// The step of the tour over a window of size: the window dimmed but for the highlighted area
+    /// and the bubble explaining it. The Next button is hit tested as [TOUR_NEXT] and the Skip
+    /// one, which the last step doesn't have, as [TOUR_SKIP]
+    fn build_tour_step(tour: &Tour, step: &TourStep, highlight: Option<(f32, f32, f32, f32)>,
+                       (width, height): (f32, f32)) -> SDLBody {
+        let _span = trace_span!("build", component = "Tour");
+        let mut body = SDLBody::new("Tour");
+        for (x, y, w, h) in tour::dimmed_areas(highlight, (width, height)) {
+            body.push(rect_polygon(x, y, w, h, TOUR_DIM_COLOR));
+            body.add_hit_area(x, y, w, h, vec![TOUR_DIMMED]);
+        }
+        if let Some((x, y, w, h)) = highlight {
+            body.push(rect_outline_polygon(x, y, w, h, FOCUS_RING_WIDTH, HIGHLIGHT_TEXT_COLOR));
+        }
+        let size = text_size();
+        let line_height = size + 2. * MENU_PADDING;
+        let padding = 4. * MENU_PADDING;
+        let bubble_width = TOUR_BUBBLE_WIDTH.min(width - 2. * MENU_PADDING).max(0.);
+        let lines = wrap_text(&step.text, size, bubble_width - 2. * padding);
+        let bubble_height = (lines.len() + 2) as f32 * line_height + 3. * padding;
+        let (bubble_x, bubble_y) = tour::bubble_position(highlight, (bubble_width, bubble_height), (width, height));
+        body.push(rect_polygon(bubble_x, bubble_y, bubble_width, bubble_height, MENU_BACKGROUND));
+        body.push(rect_outline_polygon(bubble_x, bubble_y, bubble_width, bubble_height, 1., TEXT_COLOR));
+        body.add_hit_area(bubble_x, bubble_y, bubble_width, bubble_height, vec![TOUR_BUBBLE]);
+        let x = bubble_x + padding;
+        let mut y = bubble_y + padding;
+        body.extend(build_styled_text(&step.title, size, TEXT_COLOR, x, y + MENU_PADDING, FontStyle::Bold));
+        for line in lines.iter() {
+            y += line_height;
+            body.extend(build_text(line, size, TEXT_COLOR, x, y + MENU_PADDING));
+        }
+        y += line_height + padding;
+        body.extend(build_text(&tour.progress(), size, DISABLED_TEXT_COLOR, x, y + MENU_PADDING));
+        let mut button_x = bubble_x + bubble_width - padding;
+        let next = if tour.is_last() { "Done" } else { "Next" };
+        let buttons = [Some((next, TOUR_NEXT)), Some(("Skip", TOUR_SKIP)).filter(|_| !tour.is_last())];
+        for (title, index) in buttons.into_iter().flatten() {
+            let button_width = measure_text(title, size).0 + 2. * MENU_PADDING;
+            button_x -= button_width;
+            body.push(rect_polygon(button_x, y, button_width, line_height, BUTTON_BACKGROUND));
+            body.extend(build_text(title, size, TEXT_COLOR, button_x + MENU_PADDING, y + MENU_PADDING));
+            body.add_hit_area(button_x, y, button_width, line_height, vec![index]);
+            button_x -= MENU_PADDING;
+        }
+        body
+    }
+
+    // FileChooser *********************************************************************************
+
+    const FILE_CHOOSER_WIDTH: f32 = 560.;
+
+    /// The dialog stacks its parts: the title, the buttons of the path bar, the folder, the name
+    /// field with the filters next to it, the error and the buttons. Each part is the widget it
+    /// holds built as a child, see FileChooser for their paths
+    impl SDLComponent for FileChooser {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "FileChooser");
+            let (parent_width, _) = parent_size(parent);
+            let width = FILE_CHOOSER_WIDTH.min(parent_width - 2. * MENU_PADDING).max(0.);
+            let size = text_size();
+            let padding = 4. * MENU_PADDING;
+            let inner_width = width - 2. * padding;
+            let mut parts = SDLBody::new("FileChooser");
+            parts.extend(build_text(&self.title, size, TEXT_COLOR, padding, padding));
+            let mut y = padding + size + 2. * MENU_PADDING;
+            // The folders closest to the listed one are kept when they don't all fit
+            let buttons: Vec<SDLBody> = self.path_bar().iter()
+                .map(|folder| Button { title: FileChooser::folder_title(folder), ..Default::default() }.build(parent))
+                .collect();
+            let widths: Vec<f32> = buttons.iter().map(|b| b.bounds().map_or(0., |(_, _, w, _)| w)).collect();
+            let mut fitting = 0;
+            let mut used = 0.;
+            for width in widths.iter().rev() {
+                if used + width > inner_width && fitting > 0 {
+                    break;
+                }
+                used += width + MENU_PADDING;
+                fitting += 1;
+            }
+            let mut x = padding;
+            for (i, mut button) in buttons.into_iter().enumerate().skip(widths.len() - fitting) {
+                button.translate(x, y);
+                x += widths[i] + MENU_PADDING;
+                parts.append_child(FileChooser::PATH_BAR, prefixed(i, button));
+            }
+            let row_height = size + 2. * MENU_PADDING;
+            y += row_height + MENU_PADDING;
+            let inner = pseudo_parent(inner_width, 0.);
+            let mut entries = self.entries.build(&inner);
+            entries.translate(padding, y);
+            parts.append_child(FileChooser::ENTRIES, entries);
+            y += row_height * self.entries.visible_rows as f32 + MENU_PADDING;
+            let mut filters = self.filter_box.build(&inner);
+            let filters_width = filters.bounds().map_or(0., |(_, _, w, _)| w);
+            filters.translate(padding + inner_width - filters_width, y);
+            let mut file_name = self.file_name.build(&pseudo_parent(inner_width - filters_width - MENU_PADDING, 0.));
+            file_name.translate(padding, y);
+            parts.append_child(FileChooser::FILE_NAME, file_name);
+            y += row_height + MENU_PADDING;
+            if let Some(error) = &self.error {
+                let error_size = size * ERROR_TEXT_SCALE;
+                parts.extend(build_text(error, error_size, ERROR_COLOR, padding, y));
+                y += error_size + MENU_PADDING;
+            }
+            let choose_title = match self.mode {
+                FileChooserMode::Open => "Open",
+                FileChooserMode::Save => "Save",
+            };
+            let mut right = padding + inner_width;
+            for (index, title) in [(FileChooser::CHOOSE, choose_title), (FileChooser::CANCEL, "Cancel")] {
+                let mut button = Button { title: title.to_string(), ..Default::default() }.build(parent);
+                right -= button.bounds().map_or(0., |(_, _, w, _)| w);
+                button.translate(right, y + MENU_PADDING);
+                right -= MENU_PADDING;
+                parts.append_child(index, button);
+            }
+            let height = y + row_height + MENU_PADDING + padding;
+            // The filters go last so their popup is over the buttons
+            parts.append_child(FileChooser::FILTERS, filters);
+            let mut body = SDLBody::new("FileChooser");
+            body.push(rect_polygon(0., 0., width, height, MENU_BACKGROUND));
+            body.push(rect_outline_polygon(0., 0., width, height, 1., TEXT_COLOR));
+            body.add_hit_area(0., 0., width, height, vec![]);
+            body.append(parts);
+            body
+        }
+    }
+
+    /// The body with index in front of the paths of its hit areas, like append_child() does
+    fn prefixed(index: usize, mut body: SDLBody) -> SDLBody {
+        body.prefix_paths(index);
+        body
+    }
+
+    impl Component for FileChooser {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Paging **************************************************************************************
+
+    /// The row shown at the end of a paged view while the next page is loading
+    pub fn build_loading_row(width: f32, row_height: f32) -> SDLBody {
+        let size = text_size();
+        let mut body = SDLBody::new("LoadingRow");
+        body.push(rect_polygon(0., 0., width, row_height, MENU_BACKGROUND));
+        let text = "Loading...";
+        let text_x = (width - measure_text(text, size).0) / 2.;
+        body.extend(build_text(text, size, DISABLED_TEXT_COLOR, text_x, (row_height - size) / 2.));
+        body
+    }
+
+    // Sections ************************************************************************************
+
+    const SECTION_HEADER_BACKGROUND: sys::SDL_Color = sdl_color(70, 70, 78, 255);
+
+    /// The header of a section of a ListView or Table, it's hit tested as [section]
+    pub fn build_section_header(layout: &SectionLayout, section: usize, width: f32) -> SDLBody {
+        let size = text_size();
+        let height = layout.header_height;
+        let mut body = SDLBody::new("SectionHeader");
+        body.push(rect_polygon(0., 0., width, height, SECTION_HEADER_BACKGROUND));
+        if let Some(section) = layout.sections.get(section) {
+            body.extend(build_text(&section.title, size, TEXT_COLOR, MENU_PADDING,
+                                   (height - size) / 2.));
+        }
+        body.add_hit_area(0., 0., width, height, vec![section]);
+        body
+    }
+
+    /// The header pinned to the top of the viewport, views append it after the visible entries so
+    /// it's drawn and hit tested on top of them
+    pub fn build_sticky_header(layout: &SectionLayout, scroll: f32, width: f32) -> Option<SDLBody> {
+        let (section, y) = layout.sticky_header(scroll)?;
+        let mut body = build_section_header(layout, section, width);
+        body.translate(0., y);
+        Some(body)
+    }
+
+    /// The headers of the sections in the viewport of a ListView or TableView, the sticky one last
+    fn build_section_headers(layout: &SectionLayout, scroll: f32, width: f32, height: f32) -> Vec<SDLBody> {
+        let mut headers: Vec<SDLBody> = layout.visible_entries(scroll, height).into_iter()
+            .filter_map(|(entry, y)| match entry {
+                SectionEntry::Header(section) => {
+                    let mut header = build_section_header(layout, section, width);
+                    header.translate(0., y);
+                    Some(header)
+                }
+                SectionEntry::Row { .. } => None,
+            })
+            .collect();
+        headers.extend(build_sticky_header(layout, scroll, width));
+        headers
+    }
+
+    // Sorting *************************************************************************************
+
+    /// The arrow drawn in a sorted column header, pointing up when ascending, centered in a square
+    /// of the given size at (x, y)
+    pub fn build_sort_indicator(order: SortOrder, x: f32, y: f32, size: f32,
+                                color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let (left, right, mid) = (x + size / 4., x + size * 3. / 4., x + size / 2.);
+        let (top, bottom) = (y + size / 3., y + size * 2. / 3.);
+        let vers = match order {
+            SortOrder::Ascending => vec![vertex(left, bottom, color), vertex(right, bottom, color),
+                                         vertex(mid, top, color)],
+            SortOrder::Descending => vec![vertex(left, top, color), vertex(right, top, color),
+                                          vertex(mid, bottom, color)],
+        };
+        SDLTexturedPolygon {
+            poly: SDLPolygon { vers, inds: vec![] },
+            tex: None,
+        }
+    }
+
+    // Tree ****************************************************************************************
+
+    /// The arrow in front of a tree node with children, pointing right when collapsed and down
+    /// when expanded, centered in a square of the given size at (x, y)
+    pub fn build_expander(expanded: bool, x: f32, y: f32, size: f32,
+                          color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let (near, far, mid) = (size / 3., size * 2. / 3., size / 2.);
+        let vers = if expanded {
+            vec![vertex(x + size / 4., y + near, color), vertex(x + size * 3. / 4., y + near, color),
+                 vertex(x + mid, y + far, color)]
+        } else {
+            vec![vertex(x + near, y + size / 4., color), vertex(x + near, y + size * 3. / 4., color),
+                 vertex(x + far, y + mid, color)]
+        };
+        SDLTexturedPolygon {
+            poly: SDLPolygon { vers, inds: vec![] },
+            tex: None,
+        }
+    }
+
+    // Settings ************************************************************************************
+
+    const SETTINGS_DIALOG_WIDTH: f32 = 480.;
+    /// Space left around the settings dialog when the window is smaller than it
+    const SETTINGS_DIALOG_MARGIN: f32 = 40.;
+
+    /// As wide as SETTINGS_DIALOG_WIDTH and as high as its rows, as long as it fits in the window
+    fn settings_dialog_size(dialog: &SettingsDialog, width: f32, height: f32) -> (f32, f32) {
+        let row_height = text_size() + 2. * MENU_PADDING;
+        let (sections, _) = dialog.settings.by_category();
+        let content_height = SectionLayout::new(sections, row_height, row_height).content_height();
+        (SETTINGS_DIALOG_WIDTH.min(width - 2. * SETTINGS_DIALOG_MARGIN).max(0.),
+         content_height.min(height - 2. * SETTINGS_DIALOG_MARGIN).max(0.))
+    }
+
+    /// The settings dialog generated from the declared settings: one row per setting with its
+    /// title and current value, grouped by category under sticky headers. Rows are hit tested as
+    /// [section, row] following Settings.by_category(), headers as [section]. The value being
+    /// typed is shown instead of the current one, followed by the caret
+    pub fn build_settings_dialog(dialog: &SettingsDialog, width: f32, height: f32) -> SDLBody {
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let settings = &dialog.settings;
+        let (sections, specs) = settings.by_category();
+        let layout = SectionLayout::new(sections, row_height, row_height);
+        let mut body = SDLBody::new("SettingsDialog");
+        body.push(rect_polygon(0., 0., width, height, MENU_BACKGROUND));
+        body.add_hit_area(0., 0., width, height, vec![]);
+        for (entry, y) in layout.visible_entries(dialog.scroll, height) {
+            match entry {
+                SectionEntry::Header(section) => {
+                    let mut header = build_section_header(&layout, section, width);
+                    header.translate(0., y);
+                    body.append(header);
+                }
+                SectionEntry::Row { section, row } => {
+                    let index = layout.sections.iter().take(section).map(|s| s.rows).sum::<usize>();
+                    let spec = specs[index + row];
+                    body.extend(build_text(&spec.title, size, TEXT_COLOR, MENU_PADDING,
+                                           y + MENU_PADDING));
+                    match &dialog.editing {
+                        Some((key, typed)) if *key == spec.key => {
+                            let caret_x = width - MENU_PADDING - 1.;
+                            let typed_x = caret_x - measure_text(typed, size).0;
+                            body.push(rect_polygon(0., y, width, row_height, SELECTED_BACKGROUND));
+                            body.extend(build_text(typed, size, TEXT_COLOR, typed_x, y + MENU_PADDING));
+                            body.push(rect_polygon(caret_x, y + MENU_PADDING, 1., size, TEXT_COLOR));
+                        }
+                        _ => {
+                            let value = settings.value(&spec.key).map(|v| v.to_string()).unwrap_or_default();
+                            let value_x = width - MENU_PADDING - measure_text(&value, size).0;
+                            body.extend(build_text(&value, size, DISABLED_TEXT_COLOR, value_x,
+                                                   y + MENU_PADDING));
+                        }
+                    }
+                    body.add_hit_area(0., y, width, row_height, vec![section, row]);
+                }
+            }
+        }
+        if let Some(header) = build_sticky_header(&layout, dialog.scroll, width) {
+            body.append(header);
+        }
+        body.push(rect_outline_polygon(0., 0., width, height, 1., TEXT_COLOR));
+        body
+    }
+
+    // Selection ***********************************************************************************
+
+    const RUBBER_BAND_FILL: sys::SDL_Color = sdl_color(70, 130, 200, 60);
+    const RUBBER_BAND_OUTLINE: sys::SDL_Color = sdl_color(70, 130, 200, 255);
+    const RUBBER_BAND_DASHES: [f32; 2] = [4., 3.];
+
+    /// The rubber band of the selection while it's being dragged, views push it on top of their
+    /// items
+    pub fn build_rubber_band(selection: &Selection) -> Vec<SDLTexturedPolygon> {
+        match selection.rubber_band() {
+            Some((x, y, w, h)) => {
+                let outline = StrokeStyle::dashed(1., &RUBBER_BAND_DASHES);
+                vec![rect_polygon(x, y, w, h, RUBBER_BAND_FILL),
+                     rect_stroke_polygon(x, y, w, h, &outline, RUBBER_BAND_OUTLINE)]
+            }
+            None => vec![],
+        }
+    }
+
+    // Container ***********************************************************************************
+
+    const CONTAINER_PADDING: f32 = 8.;
+    const CONTAINER_SPACING: f32 = 6.;
+
+    impl SDLComponent for Container {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Container");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let mut body = SDLBody::new("Container");
+            for (i, child_body) in stacked_children(self, width, height).into_iter().enumerate() {
+                body.append_child(i, child_body);
+            }
+            body
+        }
+    }
+
+    /// The bodies of the children of container where it shows them, stacked vertically with each
+    /// one taking the height of what it built
+    fn stacked_children(container: &Container, width: f32, height: f32) -> Vec<SDLBody> {
+        let inner = pseudo_parent(width - 2. * CONTAINER_PADDING, height - 2. * CONTAINER_PADDING);
+        let mut y = CONTAINER_PADDING;
+        container.children.iter().map(|child| {
+            let mut child_body = child.build_dyn(&inner).dyn_to_sdl_body();
+            let bounds = child_body.bounds();
+            let child_height = bounds.map(|(_, by, _, bh)| by + bh).unwrap_or(0.);
+            let (x, by, w, h) = bounds.unwrap_or_default();
+            // The path is empty, the container puts the index of the child in front
+            child_body.layout.insert(0, LayoutBox { path: vec![], name: child_body._name.clone(), x, y: by, w, h,
+                                                    clipped: false });
+            child_body.translate(CONTAINER_PADDING, y);
+            y += child_height + CONTAINER_SPACING;
+            child_body
+        }).collect()
+    }
+
+    // Loadable ************************************************************************************
+
+    const SKELETON_COLOR: sys::SDL_Color = sdl_color(60, 60, 65, 255);
+    const SKELETON_SHINE_COLOR: sys::SDL_Color = sdl_color(95, 95, 100, 255);
+
+    /// The color between from and to, amount from 0 to 1
+    fn mix_color(from: sys::SDL_Color, to: sys::SDL_Color, amount: f32) -> sys::SDL_Color {
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount.clamp(0., 1.)).round() as u8;
+        sdl_color(mix(from.r, to.r), mix(from.g, to.g), mix(from.b, to.b), mix(from.a, to.a))
+    }
+
+    /// Draws the blocks of skeleton with its shimmer, which sweeps across width
+    fn push_skeleton_blocks(body: &mut SDLBody, skeleton: &Skeleton, blocks: &[SkeletonBlock], width: f32) {
+        let (start, middle, end) = skeleton.shimmer(width);
+        let color = |x: f32| mix_color(SKELETON_COLOR, SKELETON_SHINE_COLOR, skeleton.shine(x, width));
+        for block in blocks {
+            // The block is split where the shimmer changes direction so each piece is a plain gradient
+            let mut stops = vec![block.x, block.x + block.w];
+            stops.extend([start, middle, end].into_iter().filter(|x| *x > block.x && *x < block.x + block.w));
+            stops.sort_by(f32::total_cmp);
+            for pair in stops.windows(2) {
+                let (left, right) = (color(pair[0]), color(pair[1]));
+                body.push(gradient_polygon(pair[0], block.y, pair[1] - pair[0], block.h, [left, right, right, left]));
+            }
+        }
+    }
+
+    impl SDLComponent for Skeleton {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Skeleton");
+            let (width, _) = parent_size(parent);
+            let mut body = SDLBody::new("Skeleton");
+            push_skeleton_blocks(&mut body, self, &self.blocks, width);
+            body
+        }
+    }
+
+    impl Component for Skeleton {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl SDLComponent for Loadable {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Loadable");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.content.width, parent_width);
+            let height = resolve_dimension(&self.content.height, parent_height);
+            let mut body = SDLBody::new("Loadable");
+            if !self.loading {
+                body.append(self.content.build(parent));
+                return body;
+            }
+            // Without blocks the skeleton takes the shape of the content, which is built but not shown
+            let blocks: Vec<SkeletonBlock> = match self.skeleton.blocks.is_empty() {
+                true => stacked_children(&self.content, width, height).iter()
+                    .filter_map(|child| child.bounds())
+                    .map(|(x, y, w, h)| SkeletonBlock { x, y, w, h })
+                    .collect(),
+                false => self.skeleton.blocks.clone(),
+            };
+            push_skeleton_blocks(&mut body, &self.skeleton, &blocks, width);
+            body
+        }
+    }
+
+    impl Component for Loadable {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Deferred ************************************************************************************
+
+    impl SDLComponent for Deferred {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Deferred", ready = self.ready);
+            let mut body = SDLBody::new("Deferred");
+            if self.ready {
+                body.append(self.content.build(parent));
+                return body;
+            }
+            // Building the content to take its shape would be the expensive build it defers
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.content.width, parent_width);
+            let blocks = match self.placeholder.blocks.is_empty() {
+                true => {
+                    let height = resolve_dimension(&self.content.height, parent_height);
+                    vec![SkeletonBlock { x: 0., y: 0., w: width, h: height }]
+                }
+                false => self.placeholder.blocks.clone(),
+            };
+            push_skeleton_blocks(&mut body, &self.placeholder, &blocks, width);
+            body
+        }
+    }
+
+    impl Component for Deferred {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Provider ************************************************************************************
+
+    impl SDLComponent for Provider {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Provider");
+            let mut body = SDLBody::new("Provider");
+            body.append(context::provide(&self.values, || self.content.build(parent)));
+            body
+        }
+    }
+
+    impl Component for Provider {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // If ******************************************************************************************
+
+    impl SDLComponent for If {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "If");
+            let mut body = SDLBody::new("If");
+            body.append(self.active().build(parent));
+            body
+        }
+    }
+
+    impl Component for If {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // ForEach *************************************************************************************
+
+    impl SDLComponent for ForEach {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ForEach");
+            let mut body = SDLBody::new("ForEach");
+            body.append(self.content.build(parent));
+            body
+        }
+    }
+
+    impl Component for ForEach {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// What is drawn of the background over the color the window is cleared to, None for a color
+    fn build_window_background(background: &WindowBackground, parent: &dyn Component) -> Option<SDLBody> {
+        let (width, height) = parent_size(parent);
+        let color = |c: &crate::elements::Color| sdl_color(c.r, c.g, c.b, c.a);
+        let mut body = SDLBody::new("WindowBackground");
+        match background {
+            WindowBackground::Color(_) => return None,
+            WindowBackground::Gradient { from, to, vertical } => {
+                let (from, to) = (color(from), color(to));
+                let colors = if *vertical { [from, from, to, to] } else { [from, to, to, from] };
+                body.push(gradient_polygon(0., 0., width, height, colors));
+            }
+            WindowBackground::Image { source, scale_mode } => {
+                if let Some((id, (image_w, image_h))) = image_texture_id(source) {
+                    for quad in scale_mode.layout((image_w as f32, image_h as f32), (width, height)) {
+                        body.push(image_polygon(id, &quad));
+                    }
+                }
+            }
+            WindowBackground::Draw(draw) => body.extend(draw(width, height).iter().map(canvas_polygon).collect()),
+        }
+        Some(body)
+    }
+
+    /// Builds the container of the window in the space between the menu and the status bar
+    fn build_docked_container(window: &Window, parent: &dyn Component) -> Option<SDLBody> {
+        let container = window.container.as_ref()?;
+        let (width, height) = parent_size(parent);
+        let bar_height = text_size() + 2. * MENU_PADDING;
+        let mut top = if window.menu.is_some() { bar_height } else { 0. };
+        if window.tool_bar.is_some() {
+            top += tool_bar_height();
+        }
+        let bottom = if window.status_bar.is_some() { bar_height } else { 0. };
+        let mut body = container.build(&pseudo_parent(width, height - top - bottom));
+        body.translate(0., top);
+        Some(body)
+    }
+
+    impl Component for Container {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.split_first() {
+                Some((index, rest)) => match self.children.get_mut(*index) {
+                    Some(child) => child.handle_click(rest, event),
+                    None => false,
+                },
+                None => false,
+            }
+        }
+    }
+
+    // Form ****************************************************************************************
+
+    impl SDLComponent for Form {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Form");
+            let content = self.content.build(parent);
+            let bottom = content.bounds().map(|(_, y, _, h)| y + h).unwrap_or(0.);
+            let mut submit = Button { title: self.submit_title.clone(), ..Default::default() }.build(parent);
+            submit.translate(CONTAINER_PADDING, bottom + CONTAINER_SPACING);
+            let mut body = SDLBody::new("Form");
+            body.append_child(Form::CONTENT, content);
+            body.append_child(Form::SUBMIT, submit);
+            body
+        }
+    }
+
+    impl Component for Form {
+        fn get_height(&self) -> &Dimension {
+            &self.content.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.content.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// Clicks in the content go to it, the submit button submits
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.split_first() {
+                Some((&Form::CONTENT, rest)) => self.content.handle_click(rest, event),
+                Some((&Form::SUBMIT, _)) => {
+                    self.submit(event.position);
+                    // The errors may have changed either way
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    // RUIIcon *************************************************************************************
+
+    #[derive(Debug, Clone)]
+    struct RUIIcon;
+
+    impl SDLComponent for RUIIcon {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "RUIIcon");
+            let v0 = sys::SDL_Vertex {
+                position: sys::SDL_FPoint {
+                    x: 400.,
+                    y: 150.,
+                },
+                color: sys::SDL_Color {
+                    r: 255,
+                    g: 0,
+                    b: 0,
+                    a: 255,
+                },
+                tex_coord: sys::SDL_FPoint {
+                    x: 0.,
+                    y: 0.,
+                },
+            };
+            let v1 = sys::SDL_Vertex {
+                position: sys::SDL_FPoint {
+                    x: 200.,
+                    y: 450.,
+                },
+                color: sys::SDL_Color {
+                    r: 0,
+                    g: 0,
+                    b: 255,
+                    a: 255,
+                },
+                tex_coord: sys::SDL_FPoint {
+                    x: 0.,
+                    y: 0.,
+                },
+            };
+            let v2 = sys::SDL_Vertex {
+                position: sys::SDL_FPoint {
+                    x: 600.,
+                    y: 450.,
+                },
+                color: sys::SDL_Color {
+                    r: 0,
+                    g: 255,
+                    b: 0,
+                    a: 255,
+                },
+                tex_coord: sys::SDL_FPoint {
+                    x: 0.,
+                    y: 0.,
+                },
+            };
+
+            let mut body = SDLBody::new("RUIIcon");
+            body.push(SDLTexturedPolygon {
+                poly: SDLPolygon {
+                    vers: vec![v0, v1, v2],
+                    inds: vec![],
+                },
+                tex: None,
+            });
+            body
+        }
+    }
+
+    impl Component for RUIIcon {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // SDLText *************************************************************************************
+
+    #[derive(Debug, Clone)]
+    struct SDLText {
+        text: String,
+        native_size: u32,
+        poly: SDLTexturedPolygon,
+    }
+
+    impl SDLComponent for SDLText {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "SDLText");
+            // TODO: Insert text code
+
+            SDLBody::new("SDLText")
+        }
+    }
+
+    impl Component for SDLText {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Button **************************************************************************************
+
+    impl SDLComponent for Button {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Button");
+            let size = text_size();
+            let width = measure_text(&self.title, size).0 + 4. * MENU_PADDING;
+            let height = size + 2. * MENU_PADDING;
+            let (background, color) = match self.state() {
+                ButtonState::Idle => (BUTTON_BACKGROUND, TEXT_COLOR),
+                ButtonState::Hover => (BUTTON_HOVER_BACKGROUND, TEXT_COLOR),
+                ButtonState::Pressed => (BUTTON_PRESSED_BACKGROUND, TEXT_COLOR),
+                ButtonState::Disabled => (BUTTON_BACKGROUND, DISABLED_TEXT_COLOR),
+            };
+            let mut body = SDLBody::new("Button");
+            body.push(rect_polygon(0., 0., width, height, background));
+            body.extend(build_text(&self.title, size, color, 2. * MENU_PADDING, MENU_PADDING));
+            push_forced_border(&mut body, 0., 0., width, height);
+            body.add_hit_area(0., 0., width, height, vec![]);
+            body
+        }
+    }
+
+    impl Component for Button {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, _path: &[usize], event: crate::elements::Event) -> bool {
+            self.press(event)
+        }
+    }
+
+    // TextField ***********************************************************************************
+
+    const TEXT_FIELD_BACKGROUND: sys::SDL_Color = sdl_color(30, 30, 35, 255);
+    const ERROR_COLOR: sys::SDL_Color = sdl_color(255, 110, 100, 255);
+    /// Size of the validation errors relative to the text size
+    const ERROR_TEXT_SCALE: f32 = 0.8;
+
+    impl SDLComponent for TextField {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "TextField");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let height = size + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("TextField");
+            body.push(rect_polygon(0., 0., width, height, TEXT_FIELD_BACKGROUND));
+            let border = if self.is_valid() { STATUS_SEPARATOR_COLOR } else { ERROR_COLOR };
+            body.push(rect_outline_polygon(0., 0., width, height, 1., border));
+            push_forced_border(&mut body, 0., 0., width, height);
+            body.extend(build_text(&self.text, size, TEXT_COLOR, MENU_PADDING, MENU_PADDING));
+            let error_size = size * ERROR_TEXT_SCALE;
+            for (i, error) in self.errors.iter().flatten().enumerate() {
+                let y = height + LABEL_LINE_SPACING + (error_size + LABEL_LINE_SPACING) * i as f32;
+                body.extend(build_text(error, error_size, ERROR_COLOR, MENU_PADDING, y));
+            }
+            // Clicking an editable one focuses it
+            if self.editable {
+                body.add_hit_area(0., 0., width, height, vec![]);
+            }
+            body
+        }
+    }
+
+    impl Component for TextField {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, _path: &[usize], _event: crate::elements::Event) -> bool {
+            self.editable
+        }
+    }
+
+    /// Whether component takes typing once focused, an editable TextField or TextArea, a Spinner
+    /// or a ChipInput
+    fn is_editable_text(component: &dyn Component) -> bool {
+        component.downcast_ref::<TextField>().is_some_and(|t| t.editable)
+            || component.downcast_ref::<TextArea>().is_some_and(|t| t.editable)
+            || component.is::<Spinner>() || component.is::<ChipInput>()
+    }
+
+    // ChipInput ***********************************************************************************
+
+    const CHIP_BACKGROUND: sys::SDL_Color = sdl_color(60, 65, 80, 255);
+    /// Space around the chips inside the field
+    const CHIP_GAP: f32 = 3.;
+
+    /// A chip is its text and then its cross
+    fn chip_width(chip: &str, size: f32) -> f32 {
+        measure_text(chip, size).0 + 2. * MENU_PADDING + size
+    }
+
+    impl SDLComponent for ChipInput {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ChipInput");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let places = self.layout(|chip| chip_width(chip, size), CHIP_GAP, width);
+            let rows = places.last().map_or(1, |(_, row)| row + 1);
+            let height = row_height * rows as f32;
+            let mut body = SDLBody::new("ChipInput");
+            body.push(rect_polygon(0., 0., width, height, TEXT_FIELD_BACKGROUND));
+            body.push(rect_outline_polygon(0., 0., width, height, 1., STATUS_SEPARATOR_COLOR));
+            push_forced_border(&mut body, 0., 0., width, height);
+            // Clicking the field focuses it, the crosses are on top of it
+            body.add_hit_area(0., 0., width, height, vec![]);
+            for (i, (chip, (x, row))) in self.chips.iter().zip(&places).enumerate() {
+                let (w, y) = (chip_width(chip, size), row_height * *row as f32);
+                body.push(rect_polygon(*x, y + CHIP_GAP, w, row_height - 2. * CHIP_GAP, CHIP_BACKGROUND));
+                body.extend(build_text(chip, size, TEXT_COLOR, x + MENU_PADDING, y + MENU_PADDING));
+                let cross_x = x + w - size;
+                let (left, right) = (cross_x + size * 0.25, cross_x + size * 0.75);
+                let (top, bottom) = (y + MENU_PADDING + size * 0.25, y + MENU_PADDING + size * 0.75);
+                body.push(line_polygon(left, top, right, bottom, 1.5, TEXT_COLOR));
+                body.push(line_polygon(left, bottom, right, top, 1.5, TEXT_COLOR));
+                body.add_hit_area(cross_x, y, size, row_height, vec![i]);
+            }
+            if let Some((x, row)) = places.last() {
+                body.extend(build_text(&self.text, size, TEXT_COLOR, *x, row_height * *row as f32 + MENU_PADDING));
+            }
+            body
+        }
+    }
+
+    impl Component for ChipInput {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// The crosses remove their chips, clicking the field only focuses it
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(index) => self.remove(*index, event.position),
+                None => false,
+            }
+        }
+    }
+
+    // Label ***************************************************************************************
+
+    const LABEL_LINE_SPACING: f32 = 2.;
+    const CARET_WIDTH: f32 = 2.;
+
+    impl SDLComponent for Label {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Label");
+            let (width, _) = parent_size(parent);
+            let size = label_text_size(self);
+            let line_height = size + LABEL_LINE_SPACING;
+            let lines = wrap_text(&self.text, size, width);
+            let caret = self.caret.filter(|_| accessibility::caret_browsing());
+            let color = self.color.or_else(|| context::get::<TextColor>().map(|c| c.0))
+                .map_or(TEXT_COLOR, |c| sdl_color(c.r, c.g, c.b, c.a));
+            let offset = |line: &str| self.align.offset(measure_text(line, size).0, width);
+            let mut body = SDLBody::new("Label");
+            for (i, line) in lines.iter().enumerate() {
+                let (x, y) = (offset(line), line_height * i as f32);
+                if let Some((from, to)) = caret.and_then(|c| c.selected_columns(i, &lines)) {
+                    let (start, end) = (prefix_width(line, from, size), prefix_width(line, to, size));
+                    body.push(rect_polygon(x + start, y, end - start, line_height, SELECTED_BACKGROUND));
+                }
+                body.extend(build_text(line, size, color, x, y));
+            }
+            if let Some(caret) = caret {
+                let TextPosition { line, column } = caret.clamped(&lines);
+                let x = lines.get(line).map_or(0., |l| offset(l) + prefix_width(l, column, size));
+                body.push(rect_polygon(x, line_height * line as f32, CARET_WIDTH, line_height, TEXT_COLOR));
+            }
+            body.add_hit_area(0., 0., width, line_height * lines.len() as f32, vec![]);
+            *self.lines.borrow_mut() = lines;
+            body
+        }
+    }
+
+    impl Component for Label {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Width of the first column characters of line
+    fn prefix_width(line: &str, column: usize, size: f32) -> f32 {
+        let end = line.char_indices().nth(column).map_or(line.len(), |(i, _)| i);
+        measure_text(&line[..end], size).0
+    }
+
+    /// The size of the text of a Label, scaled like text_size()
+    fn label_text_size(label: &Label) -> f32 {
+        label.size.map_or_else(text_size, |size| size * accessibility::font_scale())
+    }
+
+    /// Where the caret goes when clicking a Label width wide at (x, y), relative to its top left
+    /// corner
+    fn label_caret_at(label: &Label, width: f32, x: f32, y: f32) -> Caret {
+        let size = label_text_size(label);
+        let lines = label.lines.borrow();
+        let line = ((y / (size + LABEL_LINE_SPACING)).max(0.) as usize).min(lines.len().saturating_sub(1));
+        let text = lines.get(line).map_or("", |l| l.as_str());
+        let x = x - label.align.offset(measure_text(text, size).0, width);
+        let column = (0..=text.chars().count())
+            .min_by(|a, b| {
+                let distance = |column: &usize| (prefix_width(text, *column, size) - x).abs();
+                distance(a).total_cmp(&distance(b))
+            })
+            .unwrap_or(0);
+        Caret::at(line, column)
+    }
+
+    // Hyperlink ***********************************************************************************
+
+    const VISITED_LINK_COLOR: sys::SDL_Color = sdl_color(170, 130, 230, 255);
+
+    impl SDLComponent for Hyperlink {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Hyperlink");
+            let size = text_size();
+            let color = if self.visited { VISITED_LINK_COLOR } else { LINK_COLOR };
+            let (width, _) = measure_text(&self.text, size);
+            let line_height = size + LABEL_LINE_SPACING;
+            let mut body = SDLBody::new("Hyperlink");
+            body.extend(build_text(&self.text, size, color, 0., 0.));
+            body.push(rect_polygon(0., size, width, (size / 12.).max(1.), color));
+            body.add_hit_area(0., 0., width, line_height, vec![]);
+            body
+        }
+    }
+
+    impl Component for Hyperlink {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, _path: &[usize], event: crate::elements::Event) -> bool {
+            if let Some(url) = self.activate(event.position) {
+                if let Err(e) = sdl2::url::open_url(url) {
+                    println!("Failed to open {} {}", url, e);
+                }
+            }
+            // It's drawn as visited now
+            true
+        }
+    }
+
+    // MarkdownView ********************************************************************************
+
+    const CODE_BACKGROUND_COLOR: sys::SDL_Color = sdl_color(40, 40, 46, 255);
+    const QUOTE_BAR_WIDTH: f32 = 3.;
+
+    impl SDLComponent for MarkdownView {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "MarkdownView");
+            let width = resolve_dimension(&self.width, parent_size(parent).0);
+            let measure = |text: &str, size: f32, style: FontStyle| measure_styled_text(text, size, style).0;
+            let (shapes, height) = markdown::layout(&self.document(), width, text_size(), &measure);
+            let mut body = SDLBody::new("MarkdownView");
+            body.push(rect_polygon(0., 0., width, height, CANVAS_BACKGROUND_COLOR));
+            for shape in shapes {
+                match shape {
+                    MarkdownShape::Text { text, x, y, size, style, link: None } => {
+                        body.extend(build_styled_text(&text, size, TEXT_COLOR, x, y, style));
+                    }
+                    MarkdownShape::Text { text, x, y, size, style, link: Some(link) } => {
+                        let (w, _) = measure_styled_text(&text, size, style);
+                        body.extend(build_styled_text(&text, size, LINK_COLOR, x, y, style));
+                        body.push(rect_polygon(x, y + size, w, (size / 12.).max(1.), LINK_COLOR));
+                        body.add_hit_area(x, y, w, size + LABEL_LINE_SPACING, vec![link]);
+                    }
+                    MarkdownShape::CodeBackground { x, y, w, h } => {
+                        body.push(rect_polygon(x, y, w, h, CODE_BACKGROUND_COLOR));
+                    }
+                    MarkdownShape::QuoteBar { x, y, h } => {
+                        body.push(rect_polygon(x, y, QUOTE_BAR_WIDTH, h, CHECKBOX_BORDER_COLOR));
+                    }
+                    MarkdownShape::Rule { x, y, w } => body.push(rect_polygon(x, y, w, 1., STATUS_SEPARATOR_COLOR)),
+                }
+            }
+            body
+        }
+    }
+
+    impl Component for MarkdownView {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            if let Some(url) = path.first().and_then(|link| self.activate(*link, event.position)) {
+                if let Err(e) = sdl2::url::open_url(&url) {
+                    println!("Failed to open {} {}", url, e);
+                }
+            }
+            false
+        }
+    }
+
+    // TextArea ************************************************************************************
+
+    impl SDLComponent for TextArea {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "TextArea");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let size = text_size();
+            let line_height = size + LABEL_LINE_SPACING;
+            let text_height = |lines: &[String]| line_height * lines.len() as f32 + 2. * MENU_PADDING;
+            let mut lines = wrap_text(&self.text, size, width - 2. * MENU_PADDING);
+            let mut viewport = (width, height);
+            if text_height(&lines) > height {
+                // Again so the lines leave room for the scrollbar
+                viewport.0 = width - SCROLLBAR_SIZE;
+                lines = wrap_text(&self.text, size, viewport.0 - 2. * MENU_PADDING);
+            }
+            self.extent.set(ScrollExtent { content: (viewport.0, text_height(&lines)), viewport });
+            let scroll = self.clamped_scroll();
+            let mut body = SDLBody::new("TextArea");
+            body.push(rect_polygon(0., 0., width, height, TEXT_FIELD_BACKGROUND));
+            let mut text = SDLBody::new("TextArea text");
+            for (i, line) in lines.iter().enumerate() {
+                let y = MENU_PADDING + line_height * i as f32 - scroll;
+                // Only the lines that show
+                if y + line_height > 0. && y < height {
+                    text.extend(build_text(line, size, TEXT_COLOR, MENU_PADDING, y));
+                }
+            }
+            body.append_clipped_child(0, 0., 0., viewport.0, viewport.1, text);
+            if let Some((start, length)) = self.thumb() {
+                body.push(rect_polygon(viewport.0, 0., SCROLLBAR_SIZE, height, SCROLLBAR_TRACK_COLOR));
+                body.push(rect_polygon(viewport.0, start * height, SCROLLBAR_SIZE, length * height,
+                                       SCROLLBAR_THUMB_COLOR));
+            }
+            body.push(rect_outline_polygon(0., 0., width, height, 1., STATUS_SEPARATOR_COLOR));
+            push_forced_border(&mut body, 0., 0., width, height);
+            // Clicking an editable one focuses it and the wheel scrolls it anywhere over it
+            body.add_hit_area(0., 0., width, height, vec![]);
+            body
+        }
+    }
+
+    impl Component for TextArea {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, _path: &[usize], _event: crate::elements::Event) -> bool {
+            self.editable
+        }
+    }
+
+    // Checkbox ************************************************************************************
+
+    const CHECKBOX_BORDER_COLOR: sys::SDL_Color = sdl_color(160, 160, 170, 255);
+    const CHECKMARK_COLOR: sys::SDL_Color = sdl_color(255, 255, 255, 255);
+
+    impl SDLComponent for Checkbox {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Checkbox");
+            let size = text_size();
+            let mut body = SDLBody::new("Checkbox");
+            if self.checked {
+                body.push(rect_polygon(0., 0., size, size, SELECTED_BACKGROUND));
+                body.push(checkmark_polygon(size * 0.2, size / 2., size, CHECKMARK_COLOR));
+            }
+            body.push(rect_outline_polygon(0., 0., size, size, 1., CHECKBOX_BORDER_COLOR));
+            let label_x = size + 2. * MENU_PADDING;
+            body.extend(build_text(&self.label, size, TEXT_COLOR, label_x, 0.));
+            // The label toggles it too
+            body.add_hit_area(0., 0., label_x + measure_text(&self.label, size).0, size, vec![]);
+            body
+        }
+    }
+
+    impl Component for Checkbox {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, _path: &[usize], event: crate::elements::Event) -> bool {
+            // It changed whatever on_toggle says, so it has to be rebuilt
+            self.toggle(event.position);
+            true
+        }
+    }
+
+    // RadioGroup **********************************************************************************
+
+    impl SDLComponent for RadioButton {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "RadioButton");
+            let size = text_size();
+            let radius = size / 2.;
+            let mut body = SDLBody::new("RadioButton");
+            body.push(circle_polygon(radius, radius, radius, CHECKBOX_BORDER_COLOR));
+            body.push(circle_polygon(radius, radius, radius - 1., TEXT_FIELD_BACKGROUND));
+            if self.selected {
+                body.push(circle_polygon(radius, radius, radius / 2., SELECTED_BACKGROUND));
+            }
+            let label_x = size + 2. * MENU_PADDING;
+            body.extend(build_text(&self.label, size, TEXT_COLOR, label_x, 0.));
+            body.add_hit_area(0., 0., label_x + measure_text(&self.label, size).0, size, vec![]);
+            body
+        }
+    }
+
+    impl Component for RadioButton {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// Outside of a RadioGroup it can only be selected
+        fn handle_click(&mut self, _path: &[usize], _event: crate::elements::Event) -> bool {
+            !std::mem::replace(&mut self.selected, true)
+        }
+    }
+
+    impl SDLComponent for RadioGroup {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "RadioGroup");
+            let mut body = SDLBody::new("RadioGroup");
+            let mut y = 0.;
+            for (i, child) in self.children.iter().enumerate() {
+                let mut child_body = child.build(parent);
+                child_body.translate(0., y);
+                body.append_child(i, child_body);
+                y += text_size() + MENU_PADDING;
+            }
+            body
+        }
+    }
+
+    impl Component for RadioGroup {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(index) => self.select(*index, event.position),
+                None => false,
+            }
+        }
+    }
+
+    // Console *************************************************************************************
+
+    const CONSOLE_BACKGROUND: sys::SDL_Color = sdl_color(12, 12, 14, 255);
+    /// Of the text without a color of its own, ANSI colors are given as elements::Color
+    const CONSOLE_TEXT_COLOR: crate::elements::Color = crate::elements::Color::rgb(204, 204, 204);
+
+    impl SDLComponent for Console {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Console");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let line_height = size + LABEL_LINE_SPACING;
+            let height = line_height * self.visible_rows as f32 + 2. * MENU_PADDING;
+            let thumb = self.thumb();
+            let viewport = if thumb.is_some() { width - SCROLLBAR_SIZE } else { width };
+            let color = |c: crate::elements::Color| sdl_color(c.r, c.g, c.b, c.a);
+            let mut body = SDLBody::new("Console");
+            body.push(rect_polygon(0., 0., width, height, CONSOLE_BACKGROUND));
+            body.add_hit_area(0., 0., width, height, vec![]);
+            let mut text = SDLBody::new("Console text");
+            for (row, line) in self.visible_range().enumerate() {
+                let (mut x, y) = (MENU_PADDING, MENU_PADDING + line_height * row as f32);
+                for span in self.lines[line].iter() {
+                    let (w, _) = measure_styled_text(&span.text, size, FontStyle::Monospace);
+                    if let Some(background) = span.style.background {
+                        text.push(rect_polygon(x, y, w, line_height, color(background)));
+                    }
+                    let foreground = color(span.style.text_color(CONSOLE_TEXT_COLOR));
+                    text.extend(build_styled_text(&span.text, size, foreground, x, y, FontStyle::Monospace));
+                    x += w;
+                }
+            }
+            // Long lines are cut at the edge like in a terminal
+            body.append_clipped_child(0, 0., 0., viewport, height, text);
+            if let Some((start, length)) = thumb {
+                body.push(rect_polygon(viewport, 0., SCROLLBAR_SIZE, height, SCROLLBAR_TRACK_COLOR));
+                body.push(rect_polygon(viewport, start * height, SCROLLBAR_SIZE, length * height,
+                                       SCROLLBAR_THUMB_COLOR));
+            }
+            body.push(rect_outline_polygon(0., 0., width, height, 1., CHECKBOX_BORDER_COLOR));
+            body
+        }
+    }
+
+    impl Component for Console {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // ListView ************************************************************************************
+
+    const LIST_ROW_BACKGROUND: sys::SDL_Color = sdl_color(30, 30, 35, 255);
+    const LIST_ALTERNATE_ROW_BACKGROUND: sys::SDL_Color = sdl_color(40, 40, 46, 255);
+    const OVERSCROLL_GLOW_ALPHA: f32 = 140.;
+
+    impl SDLComponent for ListView {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ListView");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let row_height = list_row_height();
+            let height = row_height * self.visible_rows as f32;
+            let bounce = -self.overscroll.offset() * row_height;
+            let mut body = SDLBody::new("ListView");
+            body.push(rect_polygon(0., 0., width, height, LIST_ROW_BACKGROUND));
+            body.add_hit_area(0., 0., width, height, vec![]);
+            for (row, y) in self.row_positions(row_height) {
+                let y = y + bounce;
+                // Nothing is clipped, the row pushed out while bouncing is left out
+                if y < 0. || y + row_height > height + 0.5 {
+                    continue;
+                }
+                if row >= self.model.len() {
+                    let mut loading = build_loading_row(width, row_height);
+                    loading.translate(0., y);
+                    body.append(loading);
+                    continue;
+                }
+                let background = match (self.model.selection.is_selected(row), row % 2) {
+                    (true, _) => SELECTED_BACKGROUND,
+                    (false, 0) => LIST_ROW_BACKGROUND,
+                    (false, _) => LIST_ALTERNATE_ROW_BACKGROUND,
+                };
+                body.push(rect_polygon(0., y, width, row_height, background));
+                body.extend(build_text(&self.model.items()[row], size, TEXT_COLOR, MENU_PADDING, y + MENU_PADDING));
+                body.add_hit_area(0., y, width, row_height, vec![row]);
+            }
+            if let Some(layout) = self.section_layout(row_height) {
+                // Bouncing at the end leaves the sticky header where it is
+                let headers = build_section_headers(&layout, self.scroll as f32 * row_height, width, height);
+                for mut header in headers {
+                    header.translate(0., bounce);
+                    if header.bounds().is_some_and(|(_, y, _, h)| y >= 0. && y + h <= height + 0.5) {
+                        body.append_child(ListView::SECTION_HEADERS, header);
+                    }
+                }
+            }
+            if let Some((intensity, at_end)) = self.overscroll.glow() {
+                let glow_height = row_height / 2.;
+                let glow_y = if at_end { height - glow_height } else { 0. };
+                let color = sdl_color(70, 130, 200, (intensity * OVERSCROLL_GLOW_ALPHA) as u8);
+                body.push(rect_polygon(0., glow_y, width, glow_height, color));
+            }
+            body.extend(build_rubber_band(&self.model.selection));
+            body.push(rect_outline_polygon(0., 0., width, height, 1., CHECKBOX_BORDER_COLOR));
+            body
+        }
+    }
+
+    fn list_row_height() -> f32 {
+        text_size() + 2. * MENU_PADDING
+    }
+
+    impl Component for ListView {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], _event: crate::elements::Event) -> bool {
+            match path {
+                [row] => self.click(*row, SelectModifiers::default()),
+                // The headers of the sections
+                _ => false,
+            }
+        }
+    }
+
+    // TableView ***********************************************************************************
+
+    /// Width of the area around the right border of a header cell that resizes the column
+    const COLUMN_BORDER_GRIP: f32 = 8.;
+
+    /// The visible rows of table laid out below top, see TableView::row_layout(). Wrapped rows are
+    /// measured through its RowHeights, the rest are a line high
+    fn table_rows(table: &TableView, size: f32, top: f32, bottom: f32) -> Vec<(usize, f32, f32)> {
+        let mut heights = table.row_heights.borrow_mut();
+        if heights.line_height != size || heights.padding != MENU_PADDING {
+            *heights = RowHeights::new(size, MENU_PADDING);
+        }
+        let widths = table.columns.widths();
+        let measure = |text: &str| measure_text(text, size).0;
+        table.row_layout(top, bottom, &mut |row| match table.proxy.map_to_model(row) {
+            Some(model_row) if table.wrap_cells => {
+                heights.row_height(model_row, &table.model.rows[model_row], widths, &measure)
+            }
+            _ => heights.min_height,
+        })
+    }
+
+    impl SDLComponent for TableView {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "TableView", rows = self.visible_rows);
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let height = row_height * (self.visible_rows + 1) as f32;
+            self.viewport_width.set(width);
+            let mut body = SDLBody::new("TableView");
+            body.push(rect_polygon(0., 0., width, height, LIST_ROW_BACKGROUND));
+            body.push(rect_polygon(0., 0., width, row_height, SECTION_HEADER_BACKGROUND));
+            let layout = self.section_layout(row_height);
+            let rows = match &layout {
+                Some(layout) => layout.visible_rows(self.scroll as f32 * row_height, height - row_height).into_iter()
+                    .filter(|(row, _)| *row < self.row_count())
+                    .map(|(row, y)| (row, y + row_height, row_height))
+                    .collect(),
+                None => table_rows(self, size, row_height, height),
+            };
+            for &(row, y, h) in rows.iter() {
+                // The last row may not fit whole
+                let h = h.min(height - y);
+                let background = match (self.is_row_selected(row), row % 2) {
+                    (true, _) => SELECTED_BACKGROUND,
+                    (false, 0) => LIST_ROW_BACKGROUND,
+                    (false, _) => LIST_ALTERNATE_ROW_BACKGROUND,
+                };
+                body.push(rect_polygon(0., y, width, h, background));
+                body.add_hit_area(0., y, width, h, vec![TableView::ROWS, row]);
+            }
+            // Only the columns and rows that can be seen are built, each column is clipped so
+            // long cells don't spill over the next one. The glyphs come from the shared atlas
+            let visible = self.columns.visible_columns(self.clamped_scroll_x(), width);
+            let frozen_width = self.columns.frozen_width();
+            for (column, x) in visible.iter().copied() {
+                let right = (x + self.columns.widths()[column]).min(width);
+                // The scrolled columns go under the frozen ones
+                let left = if column < self.columns.frozen { x } else { x.max(frozen_width) };
+                let mut cells = SDLBody::new("TableColumn");
+                let mut title_width = right - x - MENU_PADDING;
+                if let Some(order) = self.sort_order(column) {
+                    title_width -= size;
+                    cells.push(build_sort_indicator(order, right - size, (row_height - size) / 2., size, TEXT_COLOR));
+                }
+                cells.append_clipped_child(0, x, 0., title_width.max(0.), row_height, {
+                    let mut title = SDLBody::new("TableHeader");
+                    title.extend(build_text(&self.model.column_title(column), size, TEXT_COLOR,
+                                            x + MENU_PADDING, MENU_PADDING));
+                    title
+                });
+                for &(row, y, _) in rows.iter() {
+                    let text = match self.proxy.cell(&self.model, row, column) {
+                        Some(text) => text,
+                        None => continue,
+                    };
+                    let lines = match self.wrap_cells {
+                        true => wrap_text(&text, size, self.columns.widths()[column] - 2. * MENU_PADDING),
+                        false => vec![text],
+                    };
+                    for (i, line) in lines.iter().enumerate() {
+                        cells.extend(build_text(line, size, TEXT_COLOR, x + MENU_PADDING,
+                                                y + MENU_PADDING + size * i as f32));
+                    }
+                }
+                body.add_hit_area(left, 0., right - left, row_height, vec![TableView::HEADER, column]);
+                body.append_clipped_child(column, left, 0., right - left, height, cells);
+                body.push(rect_polygon(right - 1., 0., 1., height, CHECKBOX_BORDER_COLOR));
+            }
+            // After the header cells, so the grips are hit first
+            for (column, x) in visible {
+                let right = x + self.columns.widths()[column];
+                body.add_hit_area(right - COLUMN_BORDER_GRIP / 2., 0., COLUMN_BORDER_GRIP, row_height,
+                                  vec![TableView::COLUMN_BORDER, column]);
+            }
+            // Over the cells, below the column titles
+            if let Some(layout) = &layout {
+                let scroll = self.scroll as f32 * row_height;
+                for mut header in build_section_headers(layout, scroll, width, height - row_height) {
+                    header.translate(0., row_height);
+                    body.append_child(TableView::SECTION_HEADERS, header);
+                }
+            }
+            // Over the column borders, it spans all of them
+            if let Some((_, y, _)) = rows.iter().find(|(row, _, _)| *row >= self.proxy.row_count()) {
+                let mut loading = build_loading_row(width, row_height);
+                loading.translate(0., *y);
+                body.append(loading);
+            }
+            body.push(rect_polygon(0., row_height - 1., width, 1., CHECKBOX_BORDER_COLOR));
+            body.push(rect_outline_polygon(0., 0., width, height, 1., CHECKBOX_BORDER_COLOR));
+            // Below the rows, when the columns don't fit
+            if let Some((start, length)) = self.horizontal_thumb() {
+                body.push(rect_polygon(0., height, width, SCROLLBAR_SIZE, SCROLLBAR_TRACK_COLOR));
+                body.push(rect_polygon(start * width, height, length * width, SCROLLBAR_SIZE, SCROLLBAR_THUMB_COLOR));
+                body.add_hit_area(0., height, width, SCROLLBAR_SIZE, vec![TableView::HORIZONTAL_SCROLLBAR]);
+            }
+            body
+        }
+    }
+
+    impl Component for TableView {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path {
+                [TableView::HEADER, column] => self.sort_by(*column, event.position),
+                [TableView::ROWS, row] => self.click(*row, SelectModifiers::default()),
+                _ => false,
+            }
+        }
+    }
+
+    /// Puts the selected rows of table in the clipboard as tab separated values, see
+    /// TableView::selected_text()
+    pub fn copy_selection(table: &TableView) -> Result<(), String> {
+        table.selected_text().map_or(Ok(()), |text| copy_to_clipboard(&text))
+    }
+
+    // TreeTable ***********************************************************************************
+
+    impl SDLComponent for TreeTable {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "TreeTable", rows = self.visible_rows);
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let height = row_height * (self.visible_rows + 1) as f32;
+            let mut body = SDLBody::new("TreeTable");
+            body.push(rect_polygon(0., 0., width, height, LIST_ROW_BACKGROUND));
+            body.push(rect_polygon(0., 0., width, row_height, SECTION_HEADER_BACKGROUND));
+            for (i, row) in self.visible_range().enumerate() {
+                let y = row_height * (i + 1) as f32;
+                let background = match (self.selection.is_selected(row), row % 2) {
+                    (true, _) => SELECTED_BACKGROUND,
+                    (false, 0) => LIST_ROW_BACKGROUND,
+                    (false, _) => LIST_ALTERNATE_ROW_BACKGROUND,
+                };
+                body.push(rect_polygon(0., y, width, row_height, background));
+                body.add_hit_area(0., y, width, row_height, vec![TreeTable::ROWS, row]);
+            }
+            // Like the columns of a TableView, the tree column is the frozen one
+            let visible = self.column_layout.visible_columns(0., width);
+            for (column, x) in visible.iter().copied() {
+                let column_width = self.column_layout.widths()[column].min(width - x);
+                let mut cells = SDLBody::new("TreeTableColumn");
+                cells.extend(build_text(&self.column_title(column), size, TEXT_COLOR, x + MENU_PADDING, MENU_PADDING));
+                for (i, row) in self.visible_range().enumerate() {
+                    let y = row_height * (i + 1) as f32;
+                    let mut text_x = x + MENU_PADDING;
+                    if column == 0 {
+                        let tree_row = &self.rows()[row];
+                        if tree_row.has_children {
+                            let (expander_x, expander_y) = (text_x + self.indent * tree_row.depth as f32,
+                                                            y + (row_height - self.indent) / 2.);
+                            cells.push(build_expander(tree_row.expanded, expander_x, expander_y, self.indent,
+                                                      TEXT_COLOR));
+                        }
+                        text_x += self.tree_cell_offset(row);
+                    }
+                    cells.extend(build_text(&self.cell(row, column), size, TEXT_COLOR, text_x, y + MENU_PADDING));
+                }
+                body.add_hit_area(x, 0., column_width, row_height, vec![TreeTable::HEADER, column]);
+                body.append_clipped_child(column, x, 0., column_width, height, cells);
+                body.push(rect_polygon(x + column_width - 1., 0., 1., height, CHECKBOX_BORDER_COLOR));
+            }
+            // After the rows and the header cells, so the expanders and the grips are hit first
+            for (i, row) in self.visible_range().enumerate() {
+                let tree_row = &self.rows()[row];
+                if tree_row.has_children {
+                    let x = MENU_PADDING + self.indent * tree_row.depth as f32;
+                    body.add_hit_area(x, row_height * (i + 1) as f32, self.indent, row_height,
+                                      vec![TreeTable::EXPANDERS, row]);
+                }
+            }
+            for (column, x) in visible {
+                let right = x + self.column_layout.widths()[column];
+                body.add_hit_area(right - COLUMN_BORDER_GRIP / 2., 0., COLUMN_BORDER_GRIP, row_height,
+                                  vec![TreeTable::COLUMN_BORDER, column]);
+            }
+            body.push(rect_polygon(0., row_height - 1., width, 1., CHECKBOX_BORDER_COLOR));
+            body.push(rect_outline_polygon(0., 0., width, height, 1., CHECKBOX_BORDER_COLOR));
+            body
+        }
+    }
+
+    impl Component for TreeTable {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], _event: crate::elements::Event) -> bool {
+            match path {
+                [TreeTable::EXPANDERS, row] => self.toggle(*row),
+                [TreeTable::ROWS, row] => self.click(*row, SelectModifiers::default()),
+                _ => false,
+            }
+        }
+    }
+
+    // ComboBox ************************************************************************************
+
+    impl SDLComponent for ComboBox {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ComboBox");
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let widest = self.items.iter().map(|i| measure_text(i, size).0).fold(0., f32::max);
+            let arrow_x = widest + 3. * MENU_PADDING;
+            let width = arrow_x + size / 2. + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("ComboBox");
+            body.push(rect_polygon(0., 0., width, row_height, TEXT_FIELD_BACKGROUND));
+            body.push(rect_outline_polygon(0., 0., width, row_height, 1., CHECKBOX_BORDER_COLOR));
+            if let Some(item) = self.selected_item() {
+                body.extend(build_text(item, size, TEXT_COLOR, 2. * MENU_PADDING, MENU_PADDING));
+            }
+            let mid = row_height / 2.;
+            body.push(SDLTexturedPolygon {
+                poly: SDLPolygon {
+                    vers: vec![vertex(arrow_x, mid - size / 8., TEXT_COLOR),
+                               vertex(arrow_x + size / 2., mid - size / 8., TEXT_COLOR),
+                               vertex(arrow_x + size / 4., mid + size / 8., TEXT_COLOR)],
+                    inds: vec![],
+                },
+                tex: None,
+            });
+            body.add_hit_area(0., 0., width, row_height, vec![]);
+            if self.open {
+                let mut popup = SDLBody::new("ComboBoxPopup");
+                let height = row_height * self.items.len() as f32;
+                popup.push(rect_polygon(0., row_height, width, height, MENU_BACKGROUND));
+                for (i, item) in self.items.iter().enumerate() {
+                    let y = row_height * (i + 1) as f32;
+                    if self.selected == Some(i) {
+                        popup.push(rect_polygon(0., y, width, row_height, SELECTED_BACKGROUND));
+                    }
+                    popup.extend(build_text(item, size, TEXT_COLOR, 2. * MENU_PADDING, y + MENU_PADDING));
+                    popup.add_hit_area(0., y, width, row_height, vec![i]);
+                }
+                popup.push(rect_outline_polygon(0., row_height, width, height, 1., CHECKBOX_BORDER_COLOR));
+                body.push_overlay(popup);
+            }
+            body
+        }
+    }
+
+    impl Component for ComboBox {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// The box opens and closes the popup, a row of the popup selects its item
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(index) => self.select(*index, event.position),
+                None => {
+                    self.open = !self.open;
+                    true
+                }
+            }
+        }
+    }
+
+    // DatePicker **********************************************************************************
+
+    impl SDLComponent for DatePicker {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "DatePicker");
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let text_width = measure_text("0000-00-00", size).0.max(measure_text(&self.placeholder, size).0);
+            let arrow_x = text_width + 3. * MENU_PADDING;
+            let width = arrow_x + size / 2. + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("DatePicker");
+            body.push(rect_polygon(0., 0., width, row_height, TEXT_FIELD_BACKGROUND));
+            body.push(rect_outline_polygon(0., 0., width, row_height, 1., CHECKBOX_BORDER_COLOR));
+            let text_color = if self.date.is_some() { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+            body.extend(build_text(&self.text(), size, text_color, 2. * MENU_PADDING, MENU_PADDING));
+            let mid = row_height / 2.;
+            body.push(triangle_polygon([(arrow_x, mid - size / 8.), (arrow_x + size / 2., mid - size / 8.),
+                                        (arrow_x + size / 4., mid + size / 8.)], TEXT_COLOR));
+            body.add_hit_area(0., 0., width, row_height, vec![]);
+            if self.open {
+                body.push_overlay(build_calendar(self, row_height, size));
+            }
+            body
+        }
+    }
+
+    /// The popup of a DatePicker under its field: the month with its arrows, the names of the
+    /// weekdays and the grid of days
+    fn build_calendar(date_picker: &DatePicker, top: f32, size: f32) -> SDLBody {
+        let row_height = size + 2. * MENU_PADDING;
+        let cell = (measure_text("00", size).0 + 2. * MENU_PADDING).max(row_height);
+        let width = cell * WEEKDAY_NAMES.len() as f32;
+        let height = row_height * (2 + GRID_DAYS / WEEKDAY_NAMES.len()) as f32;
+        let centered = |text: &str, x: f32, w: f32| x + (w - measure_text(text, size).0) / 2.;
+        let mut popup = SDLBody::new("DatePickerPopup");
+        popup.push(rect_polygon(0., top, width, height, MENU_BACKGROUND));
+        let title = date_picker.month.month_title();
+        popup.extend(build_text(&title, size, TEXT_COLOR, centered(&title, 0., width), top + MENU_PADDING));
+        let (mid, arrow) = (top + row_height / 2., size / 4.);
+        popup.push(triangle_polygon([(cell / 2. + arrow / 2., mid - arrow), (cell / 2. + arrow / 2., mid + arrow),
+                                     (cell / 2. - arrow / 2., mid)], TEXT_COLOR));
+        popup.push(triangle_polygon([(width - cell / 2. - arrow / 2., mid - arrow),
+                                     (width - cell / 2. - arrow / 2., mid + arrow),
+                                     (width - cell / 2. + arrow / 2., mid)], TEXT_COLOR));
+        popup.add_hit_area(0., top, cell, row_height, vec![DatePicker::PREVIOUS_MONTH]);
+        popup.add_hit_area(width - cell, top, cell, row_height, vec![DatePicker::NEXT_MONTH]);
+        for (i, name) in WEEKDAY_NAMES.iter().enumerate() {
+            let x = centered(name, cell * i as f32, cell);
+            popup.extend(build_text(name, size, DISABLED_TEXT_COLOR, x, top + row_height + MENU_PADDING));
+        }
+        let today = Date::today();
+        for (i, day) in date_picker.month.month_grid().into_iter().enumerate() {
+            let x = cell * (i % WEEKDAY_NAMES.len()) as f32;
+            let y = top + row_height * (2 + i / WEEKDAY_NAMES.len()) as f32;
+            if date_picker.date == Some(day) {
+                popup.push(rect_polygon(x, y, cell, row_height, SELECTED_BACKGROUND));
+            }
+            if day == today {
+                popup.push(rect_outline_polygon(x, y, cell, row_height, 1., HIGHLIGHT_TEXT_COLOR));
+            }
+            // The days of the weeks around the month are dimmed
+            let color = if day.month == date_picker.month.month { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+            let number = day.day.to_string();
+            popup.extend(build_text(&number, size, color, centered(&number, x, cell), y + MENU_PADDING));
+            popup.add_hit_area(x, y, cell, row_height, vec![DatePicker::FIRST_DAY + i]);
+        }
+        popup.push(rect_outline_polygon(0., top, width, height, 1., CHECKBOX_BORDER_COLOR));
+        popup
+    }
+
+    impl Component for DatePicker {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// The field opens and closes the popup, the arrows of the popup change the month shown
+        /// and a day selects it
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                None => self.toggle(),
+                Some(&DatePicker::PREVIOUS_MONTH) => self.show_month(-1),
+                Some(&DatePicker::NEXT_MONTH) => self.show_month(1),
+                Some(index) => match self.month.month_grid().get(index - DatePicker::FIRST_DAY) {
+                    Some(&date) => self.select(date, event.position),
+                    None => false,
+                },
+            }
+        }
+    }
+
+    // Rating **************************************************************************************
+
+    const RATING_STAR_COLOR: sys::SDL_Color = sdl_color(255, 200, 60, 255);
+    /// The stars previewed while hovering, until they're clicked
+    const RATING_PREVIEW_COLOR: sys::SDL_Color = sdl_color(190, 150, 50, 255);
+
+    impl SDLComponent for Rating {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Rating");
+            let size = text_size() + 2. * MENU_PADDING;
+            let radius = size / 2. - 1.;
+            let filled = if self.hovered.is_some() { RATING_PREVIEW_COLOR } else { RATING_STAR_COLOR };
+            let mut body = SDLBody::new("Rating");
+            let parts = self.parts_per_star();
+            let part_width = size / parts as f32;
+            for i in 0..self.max {
+                let (cx, cy) = (size * (i as f32 + 0.5), size / 2.);
+                let fill = self.star_fill(i);
+                if fill >= 1. {
+                    body.push(star_polygon(cx, cy, radius, filled));
+                } else {
+                    body.push(star_polygon(cx, cy, radius, CHECKBOX_BORDER_COLOR));
+                    body.push(star_polygon(cx, cy, radius - 3., TEXT_FIELD_BACKGROUND));
+                    if fill >= 0.5 {
+                        body.push(half_star_polygon(cx, cy, radius, filled));
+                    }
+                }
+                for part in 0..parts {
+                    body.add_hit_area(size * i as f32 + part_width * part as f32, 0., part_width, size,
+                                      vec![i * parts + part]);
+                }
+            }
+            push_forced_border(&mut body, 0., 0., size * self.max as f32, size);
+            body
+        }
+    }
+
+    impl Component for Rating {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(part) => self.click_part(*part, event.position),
+                None => false,
+            }
+        }
+    }
+
+    // SegmentedControl ****************************************************************************
+
+    impl SDLComponent for SegmentedControl {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "SegmentedControl");
+            let size = text_size();
+            let height = size + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("SegmentedControl");
+            let mut x = 0.;
+            for (i, segment) in self.segments.iter().enumerate() {
+                let width = measure_text(segment, size).0 + 4. * MENU_PADDING;
+                let background = if self.selected == Some(i) { SELECTED_BACKGROUND } else { BUTTON_BACKGROUND };
+                body.push(rect_polygon(x, 0., width, height, background));
+                if i > 0 {
+                    body.push(rect_polygon(x, 0., 1., height, CHECKBOX_BORDER_COLOR));
+                }
+                push_forced_border(&mut body, x, 0., width, height);
+                body.extend(build_text(segment, size, TEXT_COLOR, x + 2. * MENU_PADDING, MENU_PADDING));
+                body.add_hit_area(x, 0., width, height, vec![i]);
+                x += width;
+            }
+            body.push(rect_outline_polygon(0., 0., x, height, 1., CHECKBOX_BORDER_COLOR));
+            body
+        }
+    }
+
+    impl Component for SegmentedControl {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(index) => self.select(*index, event.position),
+                None => false,
+            }
+        }
+    }
+
+    // Breadcrumb ********************************************************************************
+
+    impl SDLComponent for Breadcrumb {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Breadcrumb");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let height = size + 2. * MENU_PADDING;
+            let widths: Vec<f32> = self.segments.iter().map(|s| measure_text(s, size).0 + 2. * MENU_PADDING).collect();
+            let gap = measure_text(Breadcrumb::SEPARATOR, size).0;
+            let ellipsis = measure_text(Breadcrumb::ELLIPSIS, size).0 + 2. * MENU_PADDING;
+            // The ellipsis goes to the last place it hides
+            let mut items = vec![];
+            for (n, i) in self.visible(&widths, gap, ellipsis, width).into_iter().enumerate() {
+                if n == 1 && i > 1 {
+                    items.push((Breadcrumb::ELLIPSIS, ellipsis, i - 1));
+                }
+                items.push((self.segments[i].as_str(), widths[i], i));
+            }
+            let current = self.segments.len().saturating_sub(1);
+            let mut body = SDLBody::new("Breadcrumb");
+            let mut x = 0.;
+            for (n, (text, w, index)) in items.into_iter().enumerate() {
+                if n > 0 {
+                    body.extend(build_text(Breadcrumb::SEPARATOR, size, DISABLED_TEXT_COLOR, x, MENU_PADDING));
+                    x += gap;
+                }
+                let color = if index == current { TEXT_COLOR } else { LINK_COLOR };
+                body.extend(build_text(text, size, color, x + MENU_PADDING, MENU_PADDING));
+                if index != current {
+                    body.add_hit_area(x, 0., w, height, vec![index]);
+                }
+                x += w;
+            }
+            body
+        }
+    }
+
+    impl Component for Breadcrumb {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(index) => self.click(*index, event.position),
+                None => false,
+            }
+        }
+    }
+
+    // TabPane *************************************************************************************
+
+    const TAB_ACTIVE_BACKGROUND: sys::SDL_Color = sdl_color(30, 30, 35, 255);
+    const TAB_INDICATOR_HEIGHT: f32 = 2.;
+
+    impl SDLComponent for TabPane {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "TabPane");
+            let (width, height) = parent_size(parent);
+            let size = text_size();
+            let strip_height = size + 2. * MENU_PADDING;
+            let mut body = SDLBody::new("TabPane");
+            body.push(rect_polygon(0., 0., width, strip_height, MENU_BACKGROUND));
+            let mut x = 0.;
+            for (i, tab) in self.tabs.iter().enumerate() {
+                let tab_width = measure_text(&tab.title, size).0 + 4. * MENU_PADDING;
+                if i == self.active {
+                    body.push(rect_polygon(x, 0., tab_width, strip_height, TAB_ACTIVE_BACKGROUND));
+                    body.push(rect_polygon(x, strip_height - TAB_INDICATOR_HEIGHT, tab_width,
+                                           TAB_INDICATOR_HEIGHT, SELECTED_BACKGROUND));
+                }
+                push_forced_border(&mut body, x, 0., tab_width, strip_height);
+                body.extend(build_text(&tab.title, size, TEXT_COLOR, x + 2. * MENU_PADDING, MENU_PADDING));
+                body.add_hit_area(x, 0., tab_width, strip_height, vec![i]);
+                x += tab_width;
+            }
+            // Only the active tab is built, switching rebuilds the content with the new one
+            if let Some(tab) = self.active_tab() {
+                let mut content = tab.content.build(&pseudo_parent(width, height - strip_height));
+                let content_height = content.bounds().map(|(_, by, _, bh)| by + bh).unwrap_or(0.) + CONTAINER_PADDING;
+                content.translate(0., strip_height);
+                body.append_child(self.active, content);
+                body.push(rect_outline_polygon(0., strip_height, width, content_height, 1., CHECKBOX_BORDER_COLOR));
+            }
+            body
+        }
+    }
+
+    impl Component for TabPane {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// A tab of the strip becomes the active one, the rest goes to the content of the tab
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.split_first() {
+                Some((tab, [])) => self.select(*tab, event.position),
+                Some((tab, rest)) => match self.tabs.get_mut(*tab) {
+                    Some(tab) => tab.content.handle_click(rest, event),
+                    None => false,
+                },
+                None => false,
+            }
+        }
+    }
+
+    // Carousel ************************************************************************************
+
+    const CAROUSEL_ARROW_WIDTH: f32 = 24.;
+    const CAROUSEL_DOTS_HEIGHT: f32 = 20.;
+    const CAROUSEL_DOT_RADIUS: f32 = 4.;
+    const CAROUSEL_DOT_SPACING: f32 = 16.;
+
+    impl SDLComponent for Carousel {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Carousel");
+            let (width, parent_height) = parent_size(parent);
+            let height = resolve_dimension(&self.height, parent_height);
+            let page_width = (width - 2. * CAROUSEL_ARROW_WIDTH).max(0.);
+            let mut body = SDLBody::new("Carousel");
+            // Only the pages in view are built, the one shown and the one it slides from or to
+            let position = self.position();
+            let mut pages = SDLBody::new("Pages");
+            for (i, page) in self.pages.iter().enumerate().filter(|(i, _)| (*i as f32 - position).abs() < 1.) {
+                let mut page_body = SDLBody::new("Page");
+                // Dragging the page anywhere but on its components slides the pages
+                page_body.add_hit_area(0., 0., page_width, height, vec![]);
+                page_body.append(page.build(&pseudo_parent(page_width, height)));
+                page_body.translate(CAROUSEL_ARROW_WIDTH + (i as f32 - position) * page_width, 0.);
+                pages.append_child(i, page_body);
+            }
+            body.append_clipped_child(Carousel::PAGES, CAROUSEL_ARROW_WIDTH, 0., page_width, height, pages);
+            body.push(rect_outline_polygon(CAROUSEL_ARROW_WIDTH, 0., page_width, height, 1., CHECKBOX_BORDER_COLOR));
+            let mid = height / 2.;
+            for (index, x, enabled) in [(Carousel::PREVIOUS, 0., self.active > 0),
+                                        (Carousel::NEXT, width - CAROUSEL_ARROW_WIDTH, self.active + 1 < self.pages.len())] {
+                body.push(rect_polygon(x, 0., CAROUSEL_ARROW_WIDTH, height, SPINNER_ARROW_BACKGROUND));
+                push_forced_border(&mut body, x, 0., CAROUSEL_ARROW_WIDTH, height);
+                let color = if enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+                let (tip, back) = match index {
+                    Carousel::PREVIOUS => (x + CAROUSEL_ARROW_WIDTH * 0.3, x + CAROUSEL_ARROW_WIDTH * 0.7),
+                    _ => (x + CAROUSEL_ARROW_WIDTH * 0.7, x + CAROUSEL_ARROW_WIDTH * 0.3),
+                };
+                let half = CAROUSEL_ARROW_WIDTH * 0.3;
+                body.push(triangle_polygon([(tip, mid), (back, mid - half), (back, mid + half)], color));
+                body.add_hit_area(x, 0., CAROUSEL_ARROW_WIDTH, height, vec![index]);
+            }
+            let dots_width = self.pages.len() as f32 * CAROUSEL_DOT_SPACING;
+            let dots_y = height + CAROUSEL_DOTS_HEIGHT / 2.;
+            for i in 0..self.pages.len() {
+                let x = (width - dots_width) / 2. + i as f32 * CAROUSEL_DOT_SPACING;
+                let color = if i == self.active { SELECTED_BACKGROUND } else { CHECKBOX_BORDER_COLOR };
+                body.push(circle_polygon(x + CAROUSEL_DOT_SPACING / 2., dots_y, CAROUSEL_DOT_RADIUS, color));
+                body.add_hit_area(x, height, CAROUSEL_DOT_SPACING, CAROUSEL_DOTS_HEIGHT, vec![Carousel::DOTS, i]);
+            }
+            body
+        }
+    }
+
+    impl Component for Carousel {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// The arrows and the dots change the page, the rest goes to the components of the page.
+        /// Its background is dragged by the window
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path {
+                [Carousel::PREVIOUS] => self.previous(event.position),
+                [Carousel::NEXT] => self.next(event.position),
+                [Carousel::DOTS, page] => self.go_to(*page, event.position),
+                [Carousel::PAGES, page, rest @ ..] if !rest.is_empty() => match self.pages.get_mut(*page) {
+                    Some(page) => page.handle_click(rest, event),
+                    None => false,
+                },
+                _ => false,
+            }
+        }
+    }
+
+    // ScrollPane **********************************************************************************
+
+    const SCROLLBAR_SIZE: f32 = 10.;
+    const SCROLLBAR_TRACK_COLOR: sys::SDL_Color = sdl_color(30, 30, 35, 255);
+    const SCROLLBAR_THUMB_COLOR: sys::SDL_Color = sdl_color(110, 110, 120, 255);
+    /// How many lines of text a notch of the mouse wheel scrolls a ScrollPane
+    const SCROLL_WHEEL_LINES: f32 = 3.;
+
+    /// Size of a built Container including the padding after its last child
+    fn content_size(content: &SDLBody) -> (f32, f32) {
+        content.bounds()
+            .map(|(x, y, w, h)| (x + w + CONTAINER_PADDING, y + h + CONTAINER_PADDING))
+            .unwrap_or((0., 0.))
+    }
+
+    impl SDLComponent for ScrollPane {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ScrollPane");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let mut content = self.content.build(&pseudo_parent(width, height));
+            let mut size = content_size(&content);
+            let viewport = ScrollPane::viewport_for(size, (width, height), SCROLLBAR_SIZE);
+            if viewport.0 < width {
+                // Again so the children filling the width leave room for the vertical scrollbar
+                content = self.content.build(&pseudo_parent(viewport.0, height));
+                size = content_size(&content);
+            }
+            self.extent.set(ScrollExtent { content: size, viewport });
+            let (scroll_x, scroll_y) = self.clamped_scroll();
+            content.translate(-scroll_x, -scroll_y);
+            let mut body = SDLBody::new("ScrollPane");
+            // The wheel scrolls the pane wherever the pointer is over it
+            body.add_hit_area(0., 0., viewport.0, viewport.1, vec![]);
+            body.append_clipped_child(ScrollPane::CONTENT, 0., 0., viewport.0, viewport.1, content);
+            if let Some((start, length)) = self.thumb(true) {
+                body.push(rect_polygon(viewport.0, 0., SCROLLBAR_SIZE, viewport.1, SCROLLBAR_TRACK_COLOR));
+                body.push(rect_polygon(viewport.0, start * viewport.1, SCROLLBAR_SIZE, length * viewport.1,
+                                       SCROLLBAR_THUMB_COLOR));
+                body.add_hit_area(viewport.0, 0., SCROLLBAR_SIZE, viewport.1,
+                                  vec![ScrollPane::VERTICAL_SCROLLBAR]);
+            }
+            if let Some((start, length)) = self.thumb(false) {
+                body.push(rect_polygon(0., viewport.1, width, SCROLLBAR_SIZE, SCROLLBAR_TRACK_COLOR));
+                body.push(rect_polygon(start * viewport.0, viewport.1, length * viewport.0, SCROLLBAR_SIZE,
+                                       SCROLLBAR_THUMB_COLOR));
+                body.add_hit_area(0., viewport.1, viewport.0, SCROLLBAR_SIZE,
+                                  vec![ScrollPane::HORIZONTAL_SCROLLBAR]);
+            }
+            body
+        }
+    }
+
+    impl Component for ScrollPane {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// Clicks in the content go to it, the scrollbars are dragged by the window
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.split_first() {
+                Some((&ScrollPane::CONTENT, rest)) => self.content.handle_click(rest, event),
+                _ => false,
+            }
+        }
+    }
+
+    /// Where the pointer puts the middle of a scrollbar thumb, as a fraction of how far the pane
+    /// can scroll
+    fn scrollbar_fraction(track: &SDLHitArea, thumb_length: f32, vertical: bool, (x, y): (i32, i32)) -> f32 {
+        let (start, length, pointer) = match vertical {
+            true => (track.y, track.h, y as f32),
+            false => (track.x, track.w, x as f32),
+        };
+        let thumb = thumb_length * length;
+        (pointer - start - thumb / 2.) / (length - thumb).max(1.)
+    }
+
+    // ProgressBar *********************************************************************************
+
+    impl SDLComponent for ProgressBar {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ProgressBar");
+            let (width, _) = parent_size(parent);
+            let height = text_size() / 2.;
+            let (start, end) = self.filled_range();
+            let mut body = SDLBody::new("ProgressBar");
+            body.push(rect_polygon(0., 0., width, height, PROGRESS_TRACK_COLOR));
+            body.push(rect_polygon(width * start, 0., width * (end - start), height, PROGRESS_FILL_COLOR));
+            push_forced_border(&mut body, 0., 0., width, height);
+            body
+        }
+    }
+
+    impl Component for ProgressBar {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Gauge ***************************************************************************************
+
+    const GAUGE_NEEDLE_COLOR: sys::SDL_Color = sdl_color(230, 230, 230, 255);
+
+    impl SDLComponent for Gauge {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Gauge");
+            let (parent_width, _) = parent_size(parent);
+            let diameter = resolve_dimension(&self.size, parent_width);
+            let (radius, size) = (diameter / 2., text_size());
+            let center = (radius, radius);
+            let arc_width = radius * Gauge::ARC_WIDTH;
+            let arc_radius = radius - arc_width / 2.;
+            let mut body = SDLBody::new("Gauge");
+            // Invisible, the dial takes its size even where it's open
+            body.push(rect_polygon(0., 0., diameter, diameter, CANVAS_BACKGROUND_COLOR));
+            let arc = StrokeStyle::solid(arc_width);
+            body.push(stroke_polygon(&self.arc(self.min, self.max, center, arc_radius), false, &arc,
+                                     PROGRESS_TRACK_COLOR));
+            for zone in &self.zones {
+                let color = sdl_color(zone.color.r, zone.color.g, zone.color.b, zone.color.a);
+                body.push(stroke_polygon(&self.arc(zone.from, zone.to, center, arc_radius), false, &arc, color));
+            }
+            let text = self.text();
+            let text_y = radius + radius / 4.;
+            body.extend(build_text(&text, size, TEXT_COLOR, radius - measure_text(&text, size).0 / 2., text_y));
+            if !self.unit.is_empty() {
+                body.extend(build_text(&self.unit, size, DISABLED_TEXT_COLOR,
+                                       radius - measure_text(&self.unit, size).0 / 2., text_y + size));
+            }
+            let hub = radius * Gauge::ARC_WIDTH / 2.;
+            body.push(triangle_polygon(self.needle_triangle(center, radius - arc_width, hub * 2.), GAUGE_NEEDLE_COLOR));
+            body.push(circle_polygon(radius, radius, hub, GAUGE_NEEDLE_COLOR));
+            body
+        }
+    }
+
+    impl Component for Gauge {
+        fn get_height(&self) -> &Dimension {
+            &self.size
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.size
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Separator ***********************************************************************************
+
+    const SEPARATOR_MARGIN_COLOR: sys::SDL_Color = sdl_color(0, 0, 0, 0);
+
+    impl SDLComponent for Separator {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Separator");
+            let (parent_width, parent_height) = parent_size(parent);
+            let length = match (self.orientation, &self.length) {
+                (Orientation::Horizontal, Some(length)) => resolve_dimension(length, parent_width),
+                (Orientation::Vertical, Some(length)) => resolve_dimension(length, parent_height),
+                (Orientation::Horizontal, None) => parent_width,
+                (Orientation::Vertical, None) => text_size() + LABEL_LINE_SPACING,
+            };
+            let (width, height) = self.size(length);
+            let color = self.color.map_or(STATUS_SEPARATOR_COLOR, |c| sdl_color(c.r, c.g, c.b, c.a));
+            let thickness = self.thickness.max(1.);
+            let mut body = SDLBody::new("Separator");
+            // Invisible, it makes the margins part of the bounds so the layout leaves them
+            body.push(rect_polygon(0., 0., width, height, SEPARATOR_MARGIN_COLOR));
+            match self.orientation {
+                Orientation::Horizontal => body.push(rect_polygon(0., Separator::MARGIN, width, thickness, color)),
+                Orientation::Vertical => body.push(rect_polygon(Separator::MARGIN, 0., thickness, height, color)),
+            }
+            body
+        }
+    }
+
+    impl Component for Separator {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Slider **************************************************************************************
+
+    const SLIDER_TRACK_HEIGHT: f32 = 4.;
+
+    impl SDLComponent for Slider {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Slider");
+            let (width, _) = parent_size(parent);
+            let size = text_size();
+            let radius = size / 2.;
+            let track_width = (width - 2. * radius).max(0.);
+            let track_y = radius - SLIDER_TRACK_HEIGHT / 2.;
+            let thumb_x = radius + track_width * self.fraction();
+            let mut body = SDLBody::new("Slider");
+            body.push(rect_polygon(radius, track_y, track_width, SLIDER_TRACK_HEIGHT, PROGRESS_TRACK_COLOR));
+            push_forced_border(&mut body, radius, track_y, track_width, SLIDER_TRACK_HEIGHT);
+            body.push(rect_polygon(radius, track_y, thumb_x - radius, SLIDER_TRACK_HEIGHT, PROGRESS_FILL_COLOR));
+            body.push(circle_polygon(thumb_x, radius, radius, CHECKBOX_BORDER_COLOR));
+            body.add_hit_area(0., 0., width, size, vec![]);
+            body
+        }
+    }
+
+    /// Where x falls along the track of a Slider built in area, from 0 to 1
+    fn slider_fraction(area: &SDLHitArea, x: i32) -> f32 {
+        let radius = text_size() / 2.;
+        ((x as f32 - area.x - radius) / (area.w - 2. * radius).max(1.)).clamp(0., 1.)
+    }
+
+    impl Component for Slider {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Spinner *************************************************************************************
+
+    const SPINNER_ARROW_BACKGROUND: sys::SDL_Color = sdl_color(60, 60, 70, 255);
+
+    impl SDLComponent for Spinner {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Spinner");
+            let size = text_size();
+            let row_height = size + 2. * MENU_PADDING;
+            let widest = [self.format(self.min), self.format(self.max), self.text.clone()].iter()
+                .map(|t| measure_text(t, size).0)
+                .fold(0., f32::max);
+            let unit_width = match self.unit.is_empty() {
+                true => 0.,
+                false => measure_text(&self.unit, size).0 + MENU_PADDING,
+            };
+            let field_width = widest + unit_width + 4. * MENU_PADDING;
+            let arrow_height = row_height / 2.;
+            let mut body = SDLBody::new("Spinner");
+            body.push(rect_polygon(0., 0., field_width, row_height, TEXT_FIELD_BACKGROUND));
+            let border = if self.is_valid() { CHECKBOX_BORDER_COLOR } else { ERROR_COLOR };
+            body.push(rect_outline_polygon(0., 0., field_width, row_height, 1., border));
+            body.extend(build_text(&self.text, size, TEXT_COLOR, 2. * MENU_PADDING, MENU_PADDING));
+            if !self.unit.is_empty() {
+                body.extend(build_text(&self.unit, size, DISABLED_TEXT_COLOR, widest + 3. * MENU_PADDING, MENU_PADDING));
+            }
+            body.add_hit_area(0., 0., field_width, row_height, vec![]);
+            // The increment arrow on top of the decrement one, both pointing where they move the value
+            for (index, y, up) in [(Spinner::INCREMENT, 0., true), (Spinner::DECREMENT, arrow_height, false)] {
+                body.push(rect_polygon(field_width, y, row_height, arrow_height, SPINNER_ARROW_BACKGROUND));
+                body.push(rect_outline_polygon(field_width, y, row_height, arrow_height, 1., CHECKBOX_BORDER_COLOR));
+                let (left, right, mid) = (field_width + row_height / 3., field_width + row_height * 2. / 3.,
+                                          field_width + row_height / 2.);
+                let (base, tip) = match up {
+                    true => (y + arrow_height * 0.7, y + arrow_height * 0.3),
+                    false => (y + arrow_height * 0.3, y + arrow_height * 0.7),
+                };
+                body.push(SDLTexturedPolygon {
+                    poly: SDLPolygon {
+                        vers: vec![vertex(left, base, TEXT_COLOR), vertex(right, base, TEXT_COLOR),
+                                   vertex(mid, tip, TEXT_COLOR)],
+                        inds: vec![],
+                    },
+                    tex: None,
+                });
+                body.add_hit_area(field_width, y, row_height, arrow_height, vec![index]);
+            }
+            body
+        }
+    }
+
+    impl Component for Spinner {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+
+        /// The arrows step the value and repeat while held, clicking the field only focuses it
+        fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+            match path.first() {
+                Some(&index) if index == Spinner::DECREMENT || index == Spinner::INCREMENT => {
+                    self.press(index, event.position)
+                }
+                _ => false,
+            }
+        }
+    }
+
+    // ColorPicker *********************************************************************************
+
+    /// How many cells the square is split into each way, the colors are interpolated inside them
+    const COLOR_SQUARE_CELLS: usize = 8;
+    const COLOR_MARKER_SIZE: f32 = 6.;
+
+    impl SDLComponent for ColorPicker {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "ColorPicker");
+            let strip_width = text_size();
+            let side = strip_width * 8.;
+            let strip_x = side + MENU_PADDING;
+            let color = |c: crate::elements::Color| sdl_color(c.r, c.g, c.b, c.a);
+            let hsv = |hue, saturation, value| color(crate::elements::Color::from_hsv(hue, saturation, value));
+            let mut body = SDLBody::new("ColorPicker");
+            // The saturation and value of a hue blend linearly enough for vertex colors in each cell
+            let cell = side / COLOR_SQUARE_CELLS as f32;
+            let at = |column: usize, row: usize| {
+                let (saturation, value) = (column as f32 / COLOR_SQUARE_CELLS as f32,
+                                           1. - row as f32 / COLOR_SQUARE_CELLS as f32);
+                hsv(self.hue, saturation, value)
+            };
+            for row in 0..COLOR_SQUARE_CELLS {
+                for column in 0..COLOR_SQUARE_CELLS {
+                    body.push(gradient_polygon(column as f32 * cell, row as f32 * cell, cell, cell,
+                                               [at(column, row), at(column + 1, row),
+                                                at(column + 1, row + 1), at(column, row + 1)]));
+                }
+            }
+            // Between the hues that are multiples of 60 only one channel changes, linearly
+            let segment = side / 6.;
+            for i in 0..6 {
+                let (top, bottom) = (hsv(i as f32 * 60., 1., 1.), hsv((i + 1) as f32 * 60., 1., 1.));
+                body.push(gradient_polygon(strip_x, i as f32 * segment, strip_width, segment,
+                                           [top, top, bottom, bottom]));
+            }
+            push_forced_border(&mut body, 0., 0., side, side);
+            push_forced_border(&mut body, strip_x, 0., strip_width, side);
+            let (marker_x, marker_y) = (self.saturation * side, (1. - self.value) * side);
+            let half = COLOR_MARKER_SIZE / 2.;
+            let marker_color = if self.value > 0.5 { sdl_color(0, 0, 0, 255) } else { CHECKMARK_COLOR };
+            body.push(rect_outline_polygon(marker_x - half, marker_y - half, COLOR_MARKER_SIZE,
+                                           COLOR_MARKER_SIZE, 1., marker_color));
+            let hue_y = self.hue / 360. * side;
+            body.push(rect_outline_polygon(strip_x - 1., hue_y - half / 2., strip_width + 2., half, 1.,
+                                           TEXT_COLOR));
+            // A swatch of the picked color
+            let swatch_x = strip_x + strip_width + MENU_PADDING;
+            body.push(rect_polygon(swatch_x, 0., strip_width * 2., strip_width * 2., color(self.color())));
+            body.push(rect_outline_polygon(swatch_x, 0., strip_width * 2., strip_width * 2., 1.,
+                                           CHECKBOX_BORDER_COLOR));
+            body.add_hit_area(0., 0., side, side, vec![ColorPicker::SQUARE]);
+            body.add_hit_area(strip_x, 0., strip_width, side, vec![ColorPicker::HUE]);
+            body
+        }
+    }
+
+    impl Component for ColorPicker {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Image ***************************************************************************************
+
+    impl SDLComponent for Image {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Image");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let mut body = SDLBody::new("Image");
+            match image_texture_id(&self.source) {
+                Some((id, (image_w, image_h))) => {
+                    for quad in self.scale_mode.layout((image_w as f32, image_h as f32), (width, height)) {
+                        let background = self.background.as_ref()
+                            .and_then(|pattern| pattern_polygon(pattern, quad.x, quad.y, quad.w, quad.h));
+                        if let Some(background) = background {
+                            body.push(background);
+                        }
+                        body.push(image_polygon(id, &quad));
+                    }
+                }
+                None => body.push(rect_outline_polygon(0., 0., width, height, 1., CHECKBOX_BORDER_COLOR)),
+            }
+            body
+        }
+    }
+
+    impl Component for Image {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Canvas **************************************************************************************
+
+    const CANVAS_BACKGROUND_COLOR: sys::SDL_Color = sdl_color(0, 0, 0, 0);
+
+    impl SDLComponent for Canvas {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Canvas");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let mut body = SDLBody::new("Canvas");
+            // Invisible, the canvas takes its size whatever is drawn in it
+            body.push(rect_polygon(0., 0., width, height, CANVAS_BACKGROUND_COLOR));
+            if let Some(background) = self.background.as_ref().and_then(|p| pattern_polygon(p, 0., 0., width, height)) {
+                body.push(background);
+            }
+            let mut drawing = SDLBody::new("CanvasDrawing");
+            drawing.extend((self.draw)(width, height).iter().map(canvas_polygon).collect());
+            body.append_clipped_child(0, 0., 0., width, height, drawing);
+            body
+        }
+    }
+
+    fn canvas_polygon(polygon: &CanvasPolygon) -> SDLTexturedPolygon {
+        let vers: Vec<sys::SDL_Vertex> = polygon.triangles().iter().flatten()
+            .map(|v| vertex(v.x, v.y, sdl_color(v.color.r, v.color.g, v.color.b, v.color.a)))
+            .collect();
+        let inds = (0..vers.len() as i32).collect();
+        SDLTexturedPolygon { poly: SDLPolygon { vers, inds }, tex: None }
+    }
+
+    impl Component for Canvas {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // SvgImage ************************************************************************************
+
+    /// The document is tessellated in the size it's shown at, so it stays crisp when scaled
+    #[cfg(feature = "lyon")]
+    impl SDLComponent for SvgImage {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "SvgImage");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let mut body = SDLBody::new("SvgImage");
+            // Invisible, the image takes its size whatever is drawn in it
+            body.push(rect_polygon(0., 0., width, height, CANVAS_BACKGROUND_COLOR));
+            let mut drawing = SDLBody::new("SvgDrawing");
+            drawing.extend(self.polygons(width, height).iter().map(canvas_polygon).collect());
+            // Tiles and the parts of a Fill that are left over go past it
+            body.append_clipped_child(0, 0., 0., width, height, drawing);
+            body
+        }
+    }
+
+    #[cfg(feature = "lyon")]
+    impl Component for SvgImage {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Charts **************************************************************************************
+
+    #[cfg(feature = "charts")]
+    const CHART_RULE_COLOR: sys::SDL_Color = sdl_color(120, 120, 125, 255);
+    #[cfg(feature = "charts")]
+    const CHART_GRID_COLOR: sys::SDL_Color = sdl_color(120, 120, 125, 90);
+    #[cfg(feature = "charts")]
+    const CHART_LINE_THICKNESS: f32 = 2.;
+
+    /// The body of a chart laid out in width by height as shapes, with labels of size
+    #[cfg(feature = "charts")]
+    fn build_chart(name: &str, shapes: Vec<ChartShape>, width: f32, height: f32, size: f32) -> SDLBody {
+        let color = |c: crate::elements::Color| sdl_color(c.r, c.g, c.b, c.a);
+        let mut body = SDLBody::new(name);
+        // Invisible, the chart takes its size whatever is drawn in it
+        body.push(rect_polygon(0., 0., width, height, CANVAS_BACKGROUND_COLOR));
+        for shape in shapes {
+            match shape {
+                ChartShape::Rule { x1, y1, x2, y2 } => body.push(line_polygon(x1, y1, x2, y2, 1., CHART_RULE_COLOR)),
+                ChartShape::Grid { x1, y1, x2, y2 } => {
+                    let style = StrokeStyle::dashed(1., &charts::GRID_DASHES);
+                    body.push(stroke_polygon(&[(x1, y1), (x2, y2)], false, &style, CHART_GRID_COLOR));
+                }
+                ChartShape::Rect { x, y, w, h, color: c } => body.push(rect_polygon(x, y, w, h, color(c))),
+                ChartShape::Polyline { points, color: c } => {
+                    let style = StrokeStyle::solid(CHART_LINE_THICKNESS).with_join(LineJoin::Round);
+                    body.push(stroke_polygon(&points, false, &style, color(c)));
+                }
+                ChartShape::Polygon { points, color: c } => {
+                    let vers = points.iter().map(|(x, y)| vertex(*x, *y, color(c))).collect();
+                    let inds = (2..points.len() as i32).flat_map(|i| [0, i - 1, i]).collect();
+                    body.push(SDLTexturedPolygon { poly: SDLPolygon { vers, inds }, tex: None });
+                }
+                ChartShape::Label { text, x, y } => body.extend(build_text(&text, size, TEXT_COLOR, x, y)),
+            }
+        }
+        body
+    }
+
+    #[cfg(feature = "charts")]
+    impl SDLComponent for LineChart {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "LineChart");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let size = text_size() * charts::LABEL_SCALE;
+            build_chart("LineChart", self.shapes(width, height, size, &measure_text), width, height, size)
+        }
+    }
+
+    #[cfg(feature = "charts")]
+    impl Component for LineChart {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[cfg(feature = "charts")]
+    impl SDLComponent for BarChart {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "BarChart");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let size = text_size() * charts::LABEL_SCALE;
+            build_chart("BarChart", self.shapes(width, height, size, &measure_text), width, height, size)
+        }
+    }
+
+    #[cfg(feature = "charts")]
+    impl Component for BarChart {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[cfg(feature = "charts")]
+    impl SDLComponent for PieChart {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "PieChart");
+            let (parent_width, parent_height) = parent_size(parent);
+            let width = resolve_dimension(&self.width, parent_width);
+            let height = resolve_dimension(&self.height, parent_height);
+            let size = text_size() * charts::LABEL_SCALE;
+            build_chart("PieChart", self.shapes(width, height, size, &measure_text), width, height, size)
+        }
+    }
+
+    #[cfg(feature = "charts")]
+    impl Component for PieChart {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            &self.width
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // FrameDiff ***********************************************************************************
+
+    /// Side of the cells of the heatmap, in pixels of the output
+    const FRAME_DIFF_CELL: u32 = 8;
+
+    /// The heatmap of diff over the window, redder where more pixels of a cell changed. The frames
+    /// are in pixels of the output, which are scale times the units of the bodies
+    fn build_frame_diff(diff: &FrameDiff, scale: f32) -> SDLBody {
+        let mut body = SDLBody::new("FrameDiff");
+        let to_units = |value: u32| value as f32 / scale;
+        for cell in diff.heatmap(FRAME_DIFF_CELL) {
+            let color = sdl_color(255, 40, 40, (60. + 160. * cell.heat) as u8);
+            body.push(rect_polygon(to_units(cell.x), to_units(cell.y), to_units(cell.w), to_units(cell.h), color));
+        }
+        if let Some((x, y, w, h)) = diff.bounds() {
+            body.push(rect_outline_polygon(to_units(x), to_units(y), to_units(w), to_units(h), 1.,
+                                           sdl_color(255, 220, 0, 255)));
+        }
+        body
+    }
+
+    // DropZone ************************************************************************************
+
+    const DROP_ZONE_HIGHLIGHT: sys::SDL_Color = sdl_color(70, 130, 200, 60);
+
+    impl SDLComponent for DropZone {
+        fn build(&self, parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "DropZone");
+            let (width, parent_height) = parent_size(parent);
+            let height = resolve_dimension(&self.height, parent_height);
+            let size = text_size();
+            let mut body = SDLBody::new("DropZone");
+            let border = match self.drag_over {
+                true => {
+                    body.push(rect_polygon(0., 0., width, height, DROP_ZONE_HIGHLIGHT));
+                    SELECTED_BACKGROUND
+                }
+                false => CHECKBOX_BORDER_COLOR,
+            };
+            body.push(rect_outline_polygon(0., 0., width, height, if self.drag_over { 2. } else { 1. }, border));
+            let hint = self.hint();
+            let lines = 1. + f32::from(u8::from(hint.is_some()));
+            let mut y = (height - lines * size) / 2.;
+            for (text, color) in [(Some(self.text.clone()), TEXT_COLOR), (hint, DISABLED_TEXT_COLOR)] {
+                if let Some(text) = text {
+                    let (text_w, _) = measure_text(&text, size);
+                    body.extend(build_text(&text, size, color, (width - text_w) / 2., y));
+                    y += size;
+                }
+            }
+            body
+        }
+    }
+
+    impl Component for DropZone {
+        fn get_height(&self) -> &Dimension {
+            &self.height
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Avatar **************************************************************************************
+
+    /// How big the initials are next to the size of the Avatar
+    const AVATAR_INITIALS_SCALE: f32 = 0.4;
+
+    impl SDLComponent for Avatar {
+        fn build(&self, _parent: &dyn Component) -> SDLBody {
+            let _span = trace_span!("build", component = "Avatar");
+            let size = self.size;
+            let outline = rounded_square_points(size, self.corner_radius());
+            let mut body = SDLBody::new("Avatar");
+            let crate::elements::Color { r, g, b, a } = self.color();
+            body.push(fan_polygon(&outline, sdl_color(r, g, b, a), None));
+            match self.source.as_ref().and_then(image_texture_id) {
+                Some((id, (image_w, image_h))) => {
+                    // The middle of the picture is cropped to a square
+                    let side = image_w.min(image_h) as f32;
+                    let (u0, v0) = ((image_w as f32 - side) / 2. / image_w as f32, (image_h as f32 - side) / 2. / image_h as f32);
+                    let (u_span, v_span) = (side / image_w as f32, side / image_h as f32);
+                    let uv = move |x: f32, y: f32| (u0 + x / size * u_span, v0 + y / size * v_span);
+                    body.push(fan_polygon(&outline, sdl_color(255, 255, 255, 255), Some((id, &uv))));
+                }
+                None => {
+                    let initials = self.initials();
+                    let text_size = size * AVATAR_INITIALS_SCALE;
+                    let (text_w, text_h) = measure_text(&initials, text_size);
+                    body.extend(build_text(&initials, text_size, TEXT_COLOR, (size - text_w) / 2., (size - text_h) / 2.));
+                }
+            }
+            push_forced_border(&mut body, 0., 0., size, size);
+            body
+        }
+    }
+
+    impl Component for Avatar {
+        fn get_height(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn get_width(&self) -> &Dimension {
+            todo!()
+        }
+
+        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+            Box::new(self.build(parent))
+        }
+
+        fn clone_dyn(&self) -> Box<dyn Component> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// The corners of a square of side size with its corners rounded by radius, clockwise from the
+    /// top of the left side
+    fn rounded_square_points(size: f32, radius: f32) -> Vec<(f32, f32)> {
+        let segments = ((radius / 2.) as usize).clamp(1, 16);
+        let centers = [(radius, radius), (size - radius, radius), (size - radius, size - radius), (radius, size - radius)];
+        let mut points = vec![];
+        for (corner, (cx, cy)) in centers.iter().enumerate() {
+            for i in 0..=segments {
+                let angle = std::f32::consts::PI * (1. + (corner as f32 + i as f32 / segments as f32) / 2.);
+                points.push((cx + radius * angle.cos(), cy + radius * angle.sin()));
+            }
+        }
+        points
+    }
+
+    /// Gives the texture coordinates of a point of a textured polygon
+    type UvMapping<'a> = &'a dyn Fn(f32, f32) -> (f32, f32);
+
+    /// A convex outline filled with color, or with the image id where uv gives its texture
+    /// coordinates at each point
+    fn fan_polygon(outline: &[(f32, f32)], color: sys::SDL_Color,
+                   texture: Option<(u32, UvMapping)>) -> SDLTexturedPolygon {
+        let count = outline.len() as i32;
+        let vers = outline.iter().map(|&(x, y)| {
+            let (u, v) = texture.map_or((0., 0.), |(_, uv)| uv(x, y));
+            sys::SDL_Vertex {
+                position: sys::SDL_FPoint { x, y },
+                color,
+                tex_coord: sys::SDL_FPoint { x: u, y: v },
+            }
+        }).collect();
+        let inds = (1..count - 1).flat_map(|i| [0, i, i + 1]).collect();
+        SDLTexturedPolygon {
+            poly: SDLPolygon { vers, inds },
+            tex: texture.map(|(id, _)| TextureId::Image(id)),
+        }
+    }
+
+    /// A quad of an image textured with the part of it given by u and v
+    fn image_polygon(id: u32, quad: &ImageQuad) -> SDLTexturedPolygon {
+        let white = sdl_color(255, 255, 255, 255);
+        let corner = |x: f32, y: f32, u: f32, v: f32| sys::SDL_Vertex {
+            position: sys::SDL_FPoint { x, y },
+            color: white,
+            tex_coord: sys::SDL_FPoint { x: u, y: v },
+        };
+        let (left, top, right, bottom) = (quad.x, quad.y, quad.x + quad.w, quad.y + quad.h);
+        SDLTexturedPolygon {
+            poly: SDLPolygon {
+                vers: vec![corner(left, top, quad.u.0, quad.v.0), corner(right, top, quad.u.1, quad.v.0),
+                           corner(right, bottom, quad.u.1, quad.v.1), corner(left, bottom, quad.u.0, quad.v.1)],
+                inds: vec![0, 1, 2, 2, 3, 0],
+            },
+            tex: Some(TextureId::Image(id)),
+        }
+    }
+
+    /// Where the pixels of a DecodedImage come from
+    #[derive(PartialEq)]
+    enum PixelSource {
+        Image(ImageSource),
+        Pattern(Pattern),
+    }
+
+    /// The rectangle filled with the pattern repeated from its top left corner, its tiles share a
+    /// polygon so they're drawn at once. Nothing when the pattern is broken
+    pub fn pattern_polygon(pattern: &Pattern, x: f32, y: f32, w: f32, h: f32) -> Option<SDLTexturedPolygon> {
+        let id = pattern_texture_id(pattern)?;
+        let mut tiles = pattern.tiles((w, h)).into_iter()
+            .map(|quad| image_polygon(id, &ImageQuad { x: x + quad.x, y: y + quad.y, ..quad }));
+        let mut polygon = tiles.next()?;
+        for tile in tiles {
+            polygon.poly.merge(&tile.poly);
+        }
+        Some(polygon)
+    }
+
+    /// An image decoded by the thread, windows create their textures from its pixels
+    struct DecodedImage {
+        id: u32,
+        source: PixelSource,
+        /// Its dimensions and RGBA32 pixels, or why it couldn't be decoded
+        pixels: Result<((u32, u32), Vec<u8>), String>,
+    }
+
+    thread_local! {
+        /// Images are decoded once and kept until forget_image(), failures too so they are only
+        /// reported once
+        static IMAGES: std::cell::RefCell<Vec<DecodedImage>> = const { std::cell::RefCell::new(vec![]) };
+        /// The id of the next decoded image, ids aren't reused since windows may still hold the
+        /// texture of a forgotten image
+        static NEXT_IMAGE_ID: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+    }
+
+    fn next_image_id() -> u32 {
+        NEXT_IMAGE_ID.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        })
+    }
+
+    /// The id and dimensions of the image of source, decoding it the first time. None when it
+    /// can't be decoded
+    fn image_texture_id(source: &ImageSource) -> Option<(u32, (u32, u32))> {
+        IMAGES.with(|images| {
+            let mut images = images.borrow_mut();
+            let index = match images.iter().position(|i| matches!(&i.source, PixelSource::Image(s) if s == source)) {
+                Some(index) => index,
+                None => {
+                    let _span = trace_span!("image_decoding");
+                    let pixels = decode_image(source);
+                    if let Err(e) = &pixels {
+                        println!("Failed to load the image {:?}: {}", source, e);
+                    }
+                    let id = next_image_id();
+                    images.push(DecodedImage { id, source: PixelSource::Image(source.clone()), pixels });
+                    images.len() - 1
+                }
             };
+            let image = &images[index];
+            image.pixels.as_ref().ok().map(|(dims, _)| (image.id, *dims))
+        })
+    }
 
-            SDLBody {
-                _name: "RUIIcon".to_string(),
-                polygons: vec![SDLTexturedPolygon {
-                    poly: SDLPolygon {
-                        vers: vec![v0, v1, v2],
-                        inds: vec![],
-                    },
-                    tex: None,
-                }],
-            }
-        }
+    /// The id of the texture of the pattern, its pixels are kept like those of a decoded image.
+    /// None when they aren't as many as its size needs
+    fn pattern_texture_id(pattern: &Pattern) -> Option<u32> {
+        IMAGES.with(|images| {
+            let mut images = images.borrow_mut();
+            let image = match images.iter().position(|i| matches!(&i.source, PixelSource::Pattern(p) if p == pattern)) {
+                Some(index) => &images[index],
+                None => {
+                    let dims = (pattern.width, pattern.height);
+                    let pixels = match pattern.pixels.len() == (dims.0 * dims.1 * 4) as usize && dims.0 * dims.1 > 0 {
+                        true => Ok((dims, pattern.pixels.to_vec())),
+                        false => Err(format!("{} bytes for {}x{} pixels", pattern.pixels.len(), dims.0, dims.1)),
+                    };
+                    if let Err(e) = &pixels {
+                        println!("Failed to load a pattern: {}", e);
+                    }
+                    let id = next_image_id();
+                    images.push(DecodedImage { id, source: PixelSource::Pattern(pattern.clone()), pixels });
+                    &images[images.len() - 1]
+                }
+            };
+            image.pixels.is_ok().then_some(image.id)
+        })
     }
 
-    impl Component for RUIIcon {
-        fn get_height(&self) -> &Dimension {
-            todo!()
+    fn with_image_pixels<R>(id: u32, f: impl FnOnce((u32, u32), &[u8]) -> R) -> Option<R> {
+        IMAGES.with(|images| {
+            let images = images.borrow();
+            let (dims, rgba) = images.iter().find(|i| i.id == id)?.pixels.as_ref().ok()?;
+            Some(f(*dims, rgba))
+        })
+    }
+
+    /// Drops the decoded pixels of source, the windows drawing it keep their textures until they
+    /// stop drawing it. It's decoded again when built after this
+    pub fn forget_image(source: &ImageSource) {
+        IMAGES.with(|images| {
+            images.borrow_mut().retain(|i| !matches!(&i.source, PixelSource::Image(s) if s == source))
+        });
+    }
+
+    /// Drops the pixels of pattern like forget_image(), patterns are kept until then
+    pub fn forget_pattern(pattern: &Pattern) {
+        IMAGES.with(|images| {
+            images.borrow_mut().retain(|i| !matches!(&i.source, PixelSource::Pattern(p) if p == pattern))
+        });
+    }
+
+    #[cfg(feature = "image")]
+    fn decode_image(source: &ImageSource) -> Result<((u32, u32), Vec<u8>), String> {
+        use sdl2::image::{ImageRWops, LoadSurface};
+        use sdl2::pixels::PixelFormatEnum;
+        use sdl2::rwops::RWops;
+        use sdl2::surface::Surface;
+
+        let surface = match source {
+            ImageSource::Path(path) => match assets::resolve(path) {
+                Some(assets::Asset::Embedded(bytes)) => RWops::from_bytes(bytes)?.load()?,
+                Some(assets::Asset::Path(path)) => Surface::from_file(path)?,
+                None => Surface::from_file(path)?,
+            },
+            ImageSource::Bytes(bytes) => RWops::from_bytes(bytes)?.load()?,
+        };
+        let surface = surface.convert_format(PixelFormatEnum::RGBA32)?;
+        let (width, height) = surface.size();
+        // Rows may be padded past the pixels
+        let pitch = surface.pitch() as usize;
+        let rgba = surface.with_lock(|pixels| {
+            pixels.chunks(pitch).take(height as usize)
+                .flat_map(|row| &row[..width as usize * 4])
+                .copied()
+                .collect()
+        });
+        Ok(((width, height), rgba))
+    }
+
+    #[cfg(not(feature = "image"))]
+    fn decode_image(_source: &ImageSource) -> Result<((u32, u32), Vec<u8>), String> {
+        Err("Built without the image feature".to_string())
+    }
+
+    // Accessibility *******************************************************************************
+
+    /// color as drawn in mode, the alpha is kept
+    fn mode_color(mode: ColorMode, color: sys::SDL_Color) -> sys::SDL_Color {
+        let (r, g, b) = mode.map(rgb(color));
+        sdl_color(r, g, b, color.a)
+    }
+
+    /// Outlines a widget in forced colors, where its background is the color of the window
+    fn push_forced_border(body: &mut SDLBody, x: f32, y: f32, w: f32, h: f32) {
+        if accessibility::forced_colors() {
+            body.push(rect_outline_polygon(x, y, w, h, 1., TEXT_COLOR));
         }
+    }
 
-        fn get_width(&self) -> &Dimension {
-            todo!()
+    fn rgb(color: sys::SDL_Color) -> contrast::Rgb {
+        (color.r, color.g, color.b)
+    }
+
+    /// The text colors the engine draws and what it draws them on
+    fn text_color_pairs() -> Vec<ColorPair> {
+        vec![ColorPair::new("Window text", rgb(TEXT_COLOR), (0, 0, 0)),
+             ColorPair::new("Menu text", rgb(TEXT_COLOR), rgb(MENU_BACKGROUND)),
+             ColorPair::new("Menu mnemonic", rgb(HIGHLIGHT_TEXT_COLOR), rgb(MENU_BACKGROUND)),
+             ColorPair::new("Link", rgb(LINK_COLOR), rgb(MENU_BACKGROUND)),
+             ColorPair::new("Button text", rgb(TEXT_COLOR), rgb(BUTTON_BACKGROUND)),
+             ColorPair::new("Hovered button text", rgb(TEXT_COLOR), rgb(BUTTON_HOVER_BACKGROUND)),
+             ColorPair::new("Pressed button text", rgb(TEXT_COLOR), rgb(BUTTON_PRESSED_BACKGROUND)),
+             ColorPair::new("Text field", rgb(TEXT_COLOR), rgb(TEXT_FIELD_BACKGROUND)),
+             ColorPair::new("Field error", rgb(ERROR_COLOR), (0, 0, 0)),
+             ColorPair::new("Section header", rgb(TEXT_COLOR), rgb(SECTION_HEADER_BACKGROUND)),
+             ColorPair::new("List row", rgb(TEXT_COLOR), rgb(LIST_ROW_BACKGROUND)),
+             ColorPair::new("Alternate list row", rgb(TEXT_COLOR), rgb(LIST_ALTERNATE_ROW_BACKGROUND)),
+             ColorPair::new("Selected row", rgb(TEXT_COLOR), rgb(SELECTED_BACKGROUND)),
+             ColorPair::new("Active tab", rgb(TEXT_COLOR), rgb(TAB_ACTIVE_BACKGROUND))]
+    }
+
+    /// Tells about the text colors below WCAG AA and the variants that would pass
+    fn warn_low_contrast() {
+        for issue in contrast::check(&text_color_pairs(), contrast::AA_NORMAL_TEXT) {
+            println!("Low contrast: {}", issue);
         }
+    }
 
-        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
-            Box::new(self.build(parent))
+    const CURSOR_BASE_SIZE: f32 = 16.;
+
+    /// A white arrow with a black outline, CURSOR_BASE_SIZE pixels high times scale
+    fn arrow_cursor(scale: f32) -> Result<Cursor, String> {
+        let size = (CURSOR_BASE_SIZE * scale).round().clamp(CURSOR_BASE_SIZE, 128.) as u32;
+        let mut surface = sdl2::surface::Surface::new(size, size, sdl2::pixels::PixelFormatEnum::RGBA32)?;
+        let pitch = surface.pitch() as usize;
+        let (height, outline) = (size as f32 * 0.8, scale.max(1.));
+        surface.with_lock_mut(|pixels| {
+            for y in 0..size as usize {
+                for x in 0..size as usize {
+                    let (fx, fy) = (x as f32, y as f32);
+                    let edge = fy * 0.6;
+                    if fy >= height || fx > edge {
+                        continue;
+                    }
+                    let border = fx < outline || fx > edge - outline || fy > height - outline;
+                    let value = if border { 0 } else { 255 };
+                    let offset = y * pitch + x * 4;
+                    pixels[offset..offset + 4].copy_from_slice(&[value, value, value, 255]);
+                }
+            }
+        });
+        Cursor::from_surface(surface, 0, 0)
+    }
+
+    // Geometry ************************************************************************************
+
+    pub const fn sdl_color(r: u8, g: u8, b: u8, a: u8) -> sys::SDL_Color {
+        sys::SDL_Color { r, g, b, a }
+    }
+
+    pub fn vertex(x: f32, y: f32, color: sys::SDL_Color) -> sys::SDL_Vertex {
+        sys::SDL_Vertex {
+            position: sys::SDL_FPoint { x, y },
+            color,
+            tex_coord: sys::SDL_FPoint { x: 0., y: 0. },
         }
+    }
 
-        fn clone_dyn(&self) -> Box<dyn Component> {
-            Box::new(self.clone())
+    pub fn rect_polygon(x: f32, y: f32, w: f32, h: f32, color: sys::SDL_Color) -> SDLTexturedPolygon {
+        SDLTexturedPolygon {
+            poly: SDLPolygon {
+                vers: vec![vertex(x, y, color), vertex(x, y + h, color),
+                           vertex(x + w, y + h, color), vertex(x + w, y, color)],
+                inds: vec![0, 1, 2, 2, 3, 0],
+            },
+            tex: None,
         }
     }
 
-    // SDLText *************************************************************************************
+    /// A rectangle outline made of four thin rectangles
+    pub fn rect_outline_polygon(x: f32, y: f32, w: f32, h: f32, thickness: f32,
+                                color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let mut outline = rect_polygon(x, y, w, thickness, color);
+        outline.poly.merge(&rect_polygon(x, y + h - thickness, w, thickness, color).poly);
+        outline.poly.merge(&rect_polygon(x, y, thickness, h, color).poly);
+        outline.poly.merge(&rect_polygon(x + w - thickness, y, thickness, h, color).poly);
+        outline
+    }
 
-    #[derive(Debug, Clone)]
-    struct SDLText {
-        text: String,
-        native_size: u32,
-        poly: SDLTexturedPolygon,
+    /// The stroke of the path lowered to triangles, see the stroke module
+    pub fn stroke_polygon(points: &[Point], closed: bool, style: &StrokeStyle,
+                          color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let vers: Vec<sys::SDL_Vertex> = stroke::triangles(points, closed, style).iter().flatten()
+            .map(|(x, y)| vertex(*x, *y, color))
+            .collect();
+        let inds = (0..vers.len() as i32).collect();
+        SDLTexturedPolygon { poly: SDLPolygon { vers, inds }, tex: None }
     }
 
-    impl SDLComponent for SDLText {
-        fn build(&self, parent: &dyn Component) -> SDLBody {
-            // TODO: Insert text code
+    /// A rectangle outline stroked inside of it, unlike rect_outline_polygon its corners are joined
+    /// and it can be dashed
+    pub fn rect_stroke_polygon(x: f32, y: f32, w: f32, h: f32, style: &StrokeStyle,
+                               color: sys::SDL_Color) -> SDLTexturedPolygon {
+        stroke_polygon(&stroke::rect(x, y, w, h, style.width), true, style, color)
+    }
 
-            SDLBody {
-                _name: "SDLText".to_string(),
-                polygons: vec![],
+    /// A filled circle as a triangle fan around its center
+    pub fn circle_polygon(cx: f32, cy: f32, radius: f32, color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let segments = ((radius * 2.) as i32).clamp(12, 64);
+        let mut vers = vec![vertex(cx, cy, color)];
+        let mut inds = vec![];
+        for i in 0..=segments {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            vers.push(vertex(cx + radius * angle.cos(), cy + radius * angle.sin(), color));
+            if i > 0 {
+                inds.extend_from_slice(&[0, i, i + 1]);
             }
         }
+        SDLTexturedPolygon {
+            poly: SDLPolygon { vers, inds },
+            tex: None,
+        }
     }
 
-    impl Component for SDLText {
-        fn get_height(&self) -> &Dimension {
-            todo!()
+    /// A five-pointed star pointing up, radius is the one of its points
+    pub fn star_polygon(cx: f32, cy: f32, radius: f32, color: sys::SDL_Color) -> SDLTexturedPolygon {
+        star_fan(cx, cy, radius, color, 0, 10)
+    }
+
+    /// The left half of star_polygon, a half filled star over an empty one
+    pub fn half_star_polygon(cx: f32, cy: f32, radius: f32, color: sys::SDL_Color) -> SDLTexturedPolygon {
+        star_fan(cx, cy, radius, color, 5, 10)
+    }
+
+    /// The triangles of a star between its vertices first and last, clockwise from the top point.
+    /// The bottom inner vertex (5) is below the center, so 5 to 10 is exactly the left half
+    fn star_fan(cx: f32, cy: f32, radius: f32, color: sys::SDL_Color, first: i32, last: i32) -> SDLTexturedPolygon {
+        let mut vers = vec![vertex(cx, cy, color)];
+        let mut inds = vec![];
+        for i in first..=last {
+            let angle = i as f32 / 10. * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2;
+            let distance = if i % 2 == 0 { radius } else { radius * 0.4 };
+            vers.push(vertex(cx + distance * angle.cos(), cy + distance * angle.sin(), color));
+            if i > first {
+                inds.extend_from_slice(&[0, i - first, i - first + 1]);
+            }
+        }
+        SDLTexturedPolygon {
+            poly: SDLPolygon { vers, inds },
+            tex: None,
         }
+    }
 
-        fn get_width(&self) -> &Dimension {
-            todo!()
+    /// A segment with the given thickness as a quad
+    pub fn line_polygon(x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32,
+                        color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let (nx, ny) = (-dy / length * thickness / 2., dx / length * thickness / 2.);
+        SDLTexturedPolygon {
+            poly: SDLPolygon {
+                vers: vec![vertex(x1 + nx, y1 + ny, color), vertex(x1 - nx, y1 - ny, color),
+                           vertex(x2 - nx, y2 - ny, color), vertex(x2 + nx, y2 + ny, color)],
+                inds: vec![0, 1, 2, 2, 3, 0],
+            },
+            tex: None,
         }
+    }
 
-        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
-            Box::new(self.build(parent))
+    /// A rectangle with a color at each corner blended across it, from the top left clockwise
+    pub fn gradient_polygon(x: f32, y: f32, w: f32, h: f32, colors: [sys::SDL_Color; 4]) -> SDLTexturedPolygon {
+        let [top_left, top_right, bottom_right, bottom_left] = colors;
+        SDLTexturedPolygon {
+            poly: SDLPolygon {
+                vers: vec![vertex(x, y, top_left), vertex(x, y + h, bottom_left),
+                           vertex(x + w, y + h, bottom_right), vertex(x + w, y, top_right)],
+                inds: vec![0, 1, 2, 2, 3, 0],
+            },
+            tex: None,
         }
+    }
 
-        fn clone_dyn(&self) -> Box<dyn Component> {
-            Box::new(self.clone())
+    pub fn triangle_polygon(points: [(f32, f32); 3], color: sys::SDL_Color) -> SDLTexturedPolygon {
+        SDLTexturedPolygon {
+            poly: SDLPolygon {
+                vers: points.iter().map(|&(x, y)| vertex(x, y, color)).collect(),
+                inds: vec![],
+            },
+            tex: None,
         }
     }
 
-    // Button **************************************************************************************
+    /// A checkmark starting at x and centered vertically on mid, size is the one of the text it
+    /// goes with
+    pub fn checkmark_polygon(x: f32, mid: f32, size: f32, color: sys::SDL_Color) -> SDLTexturedPolygon {
+        let thickness = size / 8.;
+        let mut checkmark = line_polygon(x, mid, x + size / 4., mid + size / 4., thickness, color);
+        checkmark.poly.merge(&line_polygon(x + size / 4., mid + size / 4., x + size * 0.6,
+                                           mid - size / 4., thickness, color).poly);
+        checkmark
+    }
 
-    impl SDLComponent for Button {
-        fn build(&self, parent: &dyn Component) -> SDLBody {
-            todo!()
+    /// Used when a parent doesn't have a size in pixels, it should only happen with pseudo parents
+    const FALLBACK_PARENT_SIZE: (f32, f32) = (800., 600.);
+
+    /// Converts a Dimension into pixels, Relative dimensions take all the available space
+    pub fn resolve_dimension(dimension: &Dimension, available: f32) -> f32 {
+        match dimension {
+            Dimension::Pixels(pixels) => *pixels as f32,
+            Dimension::Percentage(percentage) => available * *percentage as f32 / 100.,
+            Dimension::Relative(_) => available,
         }
     }
 
-    impl Component for Button {
-        fn get_height(&self) -> &Dimension {
-            todo!()
-        }
+    /// The size in pixels of a parent, parents pass a Container with Pixels dimensions to their
+    /// children so they know how much space they have
+    pub fn parent_size(parent: &dyn Component) -> (f32, f32) {
+        (resolve_dimension(parent.get_width(), FALLBACK_PARENT_SIZE.0),
+         resolve_dimension(parent.get_height(), FALLBACK_PARENT_SIZE.1))
+    }
 
-        fn get_width(&self) -> &Dimension {
-            todo!()
+    /// A Container with no children that only carries a size for children to resolve theirs
+    pub fn pseudo_parent(width: f32, height: f32) -> Container {
+        Container {
+            width: Dimension::Pixels(width as i32),
+            height: Dimension::Pixels(height as i32),
+            ..Default::default()
         }
+    }
 
-        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
-            Box::new(self.build(parent))
-        }
+    // Text ****************************************************************************************
 
-        fn clone_dyn(&self) -> Box<dyn Component> {
-            Box::new(self.clone())
+    /// The glyph atlas shared by every window of the UI thread, text is laid out by glyph_brush
+    /// when components are built and the resulting polygons point to TextureId::GlyphAtlas.
+    /// Whenever the atlas has to grow every glyph is re-rasterized, so the generation changes and
+    /// SDLWindow rebuilds everything that was built with the previous generation. Text is laid out
+    /// a string at a time, so when glyph_brush makes room for one by evicting the glyphs of text
+    /// built before the atlas grows too
+    #[cfg(feature = "glyph-brush-text")]
+    struct TextPipeline {
+        brush: glyph_brush::GlyphBrush<SDLPolygon>,
+        atlas: Vec<u8>,
+        atlas_dims: (u32, u32),
+        atlas_dirty: bool,
+        /// Regions of the atlas written since it was last resized, a write over one of them
+        /// replaced glyphs that text built before may still point to
+        uploaded: Vec<glyph_brush::Rectangle<u32>>,
+        generation: u32,
+        default_size: f32,
+        last_built: Vec<SDLPolygon>,
+        /// Render scale of the canvas the text is drawn on, glyphs are rasterized that much bigger
+        /// and laid out in its pixels so zoomed text stays sharp
+        pixel_scale: f32,
+        /// The fonts of the styles that have one registered in the assets, the others use the
+        /// regular font (FontId 0)
+        fonts: Vec<(FontStyle, glyph_brush::FontId)>,
+    }
+
+    #[cfg(feature = "glyph-brush-text")]
+    impl TextPipeline {
+        /// The font of the style and whether it has to be thickened, for bold without a bold font
+        fn font(&self, style: FontStyle) -> (glyph_brush::FontId, bool) {
+            match self.fonts.iter().find(|(s, _)| *s == style) {
+                Some((_, id)) => (*id, false),
+                None => (glyph_brush::FontId(0), style == FontStyle::Bold),
+            }
         }
     }
 
-    // TextField ***********************************************************************************
+    #[cfg(feature = "glyph-brush-text")]
+    thread_local! {
+        static TEXT: std::cell::RefCell<Option<TextPipeline>> = const { std::cell::RefCell::new(None) };
+    }
 
-    impl SDLComponent for TextField {
-        fn build(&self, parent: &dyn Component) -> SDLBody {
-            todo!()
+    /// Initializes the text pipeline of this thread, it does nothing if it was already done
+    #[cfg(feature = "glyph-brush-text")]
+    fn init_text(config: &EngineConfig) -> Result<(), String> {
+        use glyph_brush::ab_glyph::FontArc;
+
+        if TEXT.with(|text| text.borrow().is_some()) {
+            return Ok(());
+        }
+        let load = |asset: assets::Asset| {
+            match asset.bytes()? {
+                std::borrow::Cow::Borrowed(bytes) => FontArc::try_from_slice(bytes),
+                std::borrow::Cow::Owned(bytes) => FontArc::try_from_vec(bytes),
+            }.map_err(|e| e.to_string())
+        };
+        let font = load(assets::resolve(&config.font_name)
+            .unwrap_or_else(|| assets::Asset::Path(config.font_name.clone().into())))?;
+        let (scale_tolerance, position_tolerance) = config.text_quality.cache_tolerances();
+        let mut brush = glyph_brush::GlyphBrushBuilder::using_font(font)
+            .initial_cache_size(config.atlas_initial_size)
+            .draw_cache_scale_tolerance(scale_tolerance)
+            .draw_cache_position_tolerance(position_tolerance)
+            .build();
+        let mut fonts = vec![];
+        let styles = [(FontStyle::Bold, assets::BOLD_FONT), (FontStyle::Italic, assets::ITALIC_FONT),
+                      (FontStyle::Monospace, assets::MONOSPACE_FONT)];
+        for (style, name) in styles {
+            match assets::resolve(name).map(load) {
+                Some(Ok(font)) => fonts.push((style, brush.add_font(font))),
+                Some(Err(e)) => println!("Failed to load the font {} {}", name, e),
+                None => {}
+            }
         }
+        let atlas_dims = brush.texture_dimensions();
+        TEXT.with(|text| *text.borrow_mut() = Some(TextPipeline {
+            brush,
+            atlas: vec![0; (atlas_dims.0 * atlas_dims.1) as usize],
+            atlas_dims,
+            atlas_dirty: true,
+            uploaded: vec![],
+            generation: 0,
+            default_size: config.font_size as f32,
+            last_built: vec![],
+            pixel_scale: 1.,
+            fonts,
+        }));
+        Ok(())
     }
 
-    impl Component for TextField {
-        fn get_height(&self) -> &Dimension {
-            todo!()
-        }
+    #[cfg(not(feature = "glyph-brush-text"))]
+    fn init_text(_config: &EngineConfig) -> Result<(), String> {
+        Ok(())
+    }
 
-        fn get_width(&self) -> &Dimension {
-            todo!()
+    /// The size of the text when a component doesn't specify one, scaled by the accessibility
+    /// preferences
+    pub fn text_size() -> f32 {
+        #[cfg(feature = "glyph-brush-text")]
+        if let Some(size) = TEXT.with(|text| text.borrow().as_ref().map(|t| t.default_size)) {
+            return size * accessibility::font_scale();
         }
+        20. * accessibility::font_scale()
+    }
 
-        fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
-            Box::new(self.build(parent))
-        }
+    /// Width and height that the text would take once built
+    pub fn measure_text(text: &str, size: f32) -> (f32, f32) {
+        measure_styled_text(text, size, FontStyle::Regular)
+    }
 
-        fn clone_dyn(&self) -> Box<dyn Component> {
-            Box::new(self.clone())
+    /// Like measure_text() in the font of the style
+    #[cfg(feature = "glyph-brush-text")]
+    pub fn measure_styled_text(text: &str, size: f32, style: FontStyle) -> (f32, f32) {
+        use glyph_brush::{GlyphCruncher, Section, Text};
+
+        TEXT.with(|pipeline| {
+            let mut pipeline = pipeline.borrow_mut();
+            let bounds = pipeline.as_mut().and_then(|p| {
+                let (font, thickened) = p.font(style);
+                let text = Text::new(text).with_scale(size * p.pixel_scale).with_font_id(font);
+                let bounds = p.brush.glyph_bounds(Section::default().add_text(text));
+                let extra = if thickened { bold_offset(size) } else { 0. };
+                bounds.map(|b| (b.width() / p.pixel_scale + extra, b.height() / p.pixel_scale))
+            });
+            match bounds {
+                Some((width, height)) => (width, height.max(size)),
+                None => (0., size),
+            }
+        })
+    }
+
+    /// Without a text backend text takes the space it would take with a monospaced font
+    #[cfg(not(feature = "glyph-brush-text"))]
+    pub fn measure_styled_text(text: &str, size: f32, _style: FontStyle) -> (f32, f32) {
+        (text.chars().count() as f32 * size * 0.6, size)
+    }
+
+    /// How far bold text without a bold font is drawn again to the right to thicken it
+    #[cfg(feature = "glyph-brush-text")]
+    fn bold_offset(size: f32) -> f32 {
+        (size / 20.).max(1.)
+    }
+
+    /// Splits the text into lines that fit in width, see text_wrap::wrap_lines()
+    pub fn wrap_text(text: &str, size: f32, width: f32) -> Vec<String> {
+        wrap_lines(text, width, &|line| measure_text(line, size).0)
+    }
+
+    /// Lays out the text with its top left corner at (x, y), the result is a single polygon
+    /// textured with the glyph atlas
+    pub fn build_text(text: &str, size: f32, color: sys::SDL_Color, x: f32, y: f32) -> Vec<SDLTexturedPolygon> {
+        build_styled_text(text, size, color, x, y, FontStyle::Regular)
+    }
+
+    /// Like build_text() in the font of the style
+    #[cfg(feature = "glyph-brush-text")]
+    pub fn build_styled_text(text: &str, size: f32, color: sys::SDL_Color, x: f32, y: f32, style: FontStyle)
+                             -> Vec<SDLTexturedPolygon> {
+        use glyph_brush::{BrushAction, BrushError, Section, Text};
+
+        let _span = trace_span!("text_shaping", chars = text.len());
+        TEXT.with(|pipeline| {
+            let mut pipeline = pipeline.borrow_mut();
+            let pipeline = match pipeline.as_mut() {
+                Some(pipeline) => pipeline,
+                None => return vec![],
+            };
+            let rgba = [color.r as f32 / 255., color.g as f32 / 255., color.b as f32 / 255.,
+                        color.a as f32 / 255.];
+            let pixel_scale = pipeline.pixel_scale;
+            let (font, thickened) = pipeline.font(style);
+            let section = |x: f32| Section::default()
+                .with_screen_position((x * pixel_scale, y * pixel_scale))
+                .add_text(Text::new(text).with_scale(size * pixel_scale).with_color(rgba).with_font_id(font));
+            let polygons = loop {
+                pipeline.brush.queue(section(x));
+                if thickened {
+                    pipeline.brush.queue(section(x + bold_offset(size)));
+                }
+                let atlas = &mut pipeline.atlas;
+                let atlas_width = pipeline.atlas_dims.0;
+                let uploaded = &mut pipeline.uploaded;
+                let mut dirty = false;
+                let mut evicted = false;
+                let action = pipeline.brush.process_queued(
+                    |rect, tex_data| {
+                        evicted |= uploaded.iter().any(|r| overlap(r, &rect));
+                        uploaded.push(rect);
+                        update_atlas(atlas, atlas_width, rect, tex_data);
+                        dirty = true;
+                    },
+                    into_vertex,
+                );
+                pipeline.atlas_dirty |= dirty;
+                let (width, height) = pipeline.atlas_dims;
+                let size = match action {
+                    Err(BrushError::TextureTooSmall { suggested }) => suggested,
+                    _ if evicted => (width * 2, height * 2),
+                    Ok(BrushAction::Draw(polygons)) => {
+                        pipeline.last_built = polygons.clone();
+                        break polygons;
+                    }
+                    Ok(BrushAction::ReDraw) => break pipeline.last_built.clone(),
+                };
+                println!("Resizing glyph atlas -> {}x{} to fit glyphs", size.0, size.1);
+                pipeline.brush.resize_texture(size.0, size.1);
+                pipeline.atlas = vec![0; (size.0 * size.1) as usize];
+                pipeline.atlas_dims = size;
+                pipeline.atlas_dirty = true;
+                pipeline.uploaded.clear();
+                pipeline.generation += 1;
+            };
+            let mut merged = SDLPolygon { vers: vec![], inds: vec![] };
+            for polygon in polygons.iter() {
+                merged.merge(polygon);
+            }
+            if merged.vers.is_empty() {
+                return vec![];
+            }
+            if pixel_scale != 1. {
+                for vertex in merged.vers.iter_mut() {
+                    vertex.position.x /= pixel_scale;
+                    vertex.position.y /= pixel_scale;
+                }
+            }
+            vec![SDLTexturedPolygon {
+                poly: merged,
+                tex: Some(TextureId::GlyphAtlas),
+            }]
+        })
+    }
+
+    #[cfg(not(feature = "glyph-brush-text"))]
+    pub fn build_styled_text(_text: &str, _size: f32, _color: sys::SDL_Color, _x: f32, _y: f32, _style: FontStyle)
+                             -> Vec<SDLTexturedPolygon> {
+        vec![]
+    }
+
+    /// Increments every time the glyph atlas is recreated, polygons built with an older
+    /// generation point to glyphs that are not there anymore
+    fn text_generation() -> u32 {
+        #[cfg(feature = "glyph-brush-text")]
+        if let Some(generation) = TEXT.with(|text| text.borrow().as_ref().map(|t| t.generation)) {
+            return generation;
         }
+        0
     }
 
-    // Text ****************************************************************************************
+    /// Gives the atlas pixels (as white RGBA32 with the glyph coverage as alpha) when they changed
+    /// since the last call
+    #[cfg(feature = "glyph-brush-text")]
+    fn take_dirty_atlas() -> Option<((u32, u32), Vec<u8>)> {
+        TEXT.with(|text| {
+            let mut text = text.borrow_mut();
+            let pipeline = text.as_mut()?;
+            if !pipeline.atlas_dirty {
+                return None;
+            }
+            pipeline.atlas_dirty = false;
+            let mut rgba = Vec::with_capacity(pipeline.atlas.len() * 4);
+            for alpha in pipeline.atlas.iter() {
+                rgba.extend_from_slice(&[255, 255, 255, *alpha]);
+            }
+            Some((pipeline.atlas_dims, rgba))
+        })
+    }
 
-    fn update_texture(rect: glyph_brush::Rectangle<u32>, tex_data: &[u8], texture: &mut Texture, color: &Color) {
-        let format_enum = texture.query().format;
-        let bytes_per_pixel = format_enum.byte_size_per_pixel();
-        let pitch = bytes_per_pixel * rect.width() as usize;
-        let pixel_format = sdl2::pixels::PixelFormat::try_from(format_enum)
-            .expect("Failed to get a PixelFormat from PixelFormatEnum at update_texture()");
-        let mut sdl_color = sdl2::pixels::Color {
-            r: color.r,
-            g: color.g,
-            b: color.b,
-            a: 0,
-        };
-        let mut new_data: Vec<u8> = vec![];
-        for alpha in tex_data {
-            sdl_color.a = *alpha;
-            let native = sdl_color.to_u32(&pixel_format).to_ne_bytes();
-            new_data.extend_from_slice(&native);
+    #[cfg(not(feature = "glyph-brush-text"))]
+    fn take_dirty_atlas() -> Option<((u32, u32), Vec<u8>)> {
+        None
+    }
+
+    /// Makes take_dirty_atlas() give the whole atlas again, for when its texture was lost
+    /// Text is built for a canvas with this render scale from now on, what was built before has to
+    /// be built again so the generation changes
+    #[cfg(feature = "glyph-brush-text")]
+    fn set_text_pixel_scale(scale: f32) {
+        TEXT.with(|text| if let Some(pipeline) = text.borrow_mut().as_mut() {
+            if pipeline.pixel_scale != scale {
+                pipeline.pixel_scale = scale;
+                pipeline.generation += 1;
+            }
+        });
+    }
+
+    #[cfg(not(feature = "glyph-brush-text"))]
+    fn set_text_pixel_scale(_scale: f32) {}
+
+    #[cfg(feature = "glyph-brush-text")]
+    fn mark_atlas_dirty() {
+        TEXT.with(|text| if let Some(pipeline) = text.borrow_mut().as_mut() {
+            pipeline.atlas_dirty = true;
+        });
+    }
+
+    #[cfg(not(feature = "glyph-brush-text"))]
+    fn mark_atlas_dirty() {}
+
+    #[cfg(feature = "glyph-brush-text")]
+    fn overlap(a: &glyph_brush::Rectangle<u32>, b: &glyph_brush::Rectangle<u32>) -> bool {
+        a.min[0] < b.max[0] && b.min[0] < a.max[0] && a.min[1] < b.max[1] && b.min[1] < a.max[1]
+    }
+
+    #[cfg(feature = "glyph-brush-text")]
+    fn update_atlas(atlas: &mut [u8], atlas_width: u32, rect: glyph_brush::Rectangle<u32>, tex_data: &[u8]) {
+        let width = rect.width() as usize;
+        for (row, line) in tex_data.chunks(width).enumerate() {
+            let start = (rect.min[1] as usize + row) * atlas_width as usize + rect.min[0] as usize;
+            atlas[start..start + width].copy_from_slice(line);
         }
-        let r = sdl2::rect::Rect::new(rect.min[0].try_into().unwrap(), rect.min[1].try_into().unwrap(),
-                                      rect.width(), rect.height());
-        texture.update(r, &new_data, pitch).expect(
-            &format!("Failed to update_texture() {}", sdl2::get_error()));
     }
 
+    #[cfg(feature = "glyph-brush-text")]
     fn into_vertex(vd: glyph_brush::GlyphVertex) -> SDLPolygon {
-        let global_alpha = 128;
-        let v1 = sys::SDL_Vertex {
-            position: sys::SDL_FPoint {
-                x: vd.pixel_coords.min.x,
-                y: vd.pixel_coords.min.y,
-            },
-            color: sys::SDL_Color {
-                r: 255,
-                g: 255,
-                b: 255,
-                a: global_alpha,
-            },
-            tex_coord: sys::SDL_FPoint {
-                x: vd.tex_coords.min.x,
-                y: vd.tex_coords.min.y,
-            },
-        };
-        let v2 = sys::SDL_Vertex {
-            position: sys::SDL_FPoint {
-                x: vd.pixel_coords.min.x,
-                y: vd.pixel_coords.max.y,
-            },
-            color: sys::SDL_Color {
-                r: 255,
-                g: 255,
-                b: 255,
-                a: global_alpha,
-            },
-            tex_coord: sys::SDL_FPoint {
-                x: vd.tex_coords.min.x,
-                y: vd.tex_coords.max.y,
-            },
+        let [r, g, b, a] = vd.extra.color;
+        let color = sys::SDL_Color {
+            r: (r * 255.) as u8,
+            g: (g * 255.) as u8,
+            b: (b * 255.) as u8,
+            a: (a * 255.) as u8,
         };
-        let v3 = sys::SDL_Vertex {
-            position: sys::SDL_FPoint {
-                x: vd.pixel_coords.max.x,
-                y: vd.pixel_coords.max.y,
-            },
-            color: sys::SDL_Color {
-                r: 255,
-                g: 255,
-                b: 255,
-                a: global_alpha,
-            },
-            tex_coord: sys::SDL_FPoint {
-                x: vd.tex_coords.max.x,
-                y: vd.tex_coords.max.y,
-            },
-        };
-        let v4 = sys::SDL_Vertex {
-            position: sys::SDL_FPoint {
-                x: vd.pixel_coords.max.x,
-                y: vd.pixel_coords.min.y,
-            },
-            color: sys::SDL_Color {
-                r: 255,
-                g: 255,
-                b: 255,
-                a: global_alpha,
-            },
-            tex_coord: sys::SDL_FPoint {
-                x: vd.tex_coords.max.x,
-                y: vd.tex_coords.min.y,
-            },
+        let corner = |x: f32, y: f32, u: f32, v: f32| sys::SDL_Vertex {
+            position: sys::SDL_FPoint { x, y },
+            color,
+            tex_coord: sys::SDL_FPoint { x: u, y: v },
         };
+        let (p, t) = (vd.pixel_coords, vd.tex_coords);
         SDLPolygon {
-            vers: vec![v1, v2, v3, v4],
-            inds: vec![0, 1, 2, 2, 3, 0]
+            vers: vec![corner(p.min.x, p.min.y, t.min.x, t.min.y),
+                       corner(p.min.x, p.max.y, t.min.x, t.max.y),
+                       corner(p.max.x, p.max.y, t.max.x, t.max.y),
+                       corner(p.max.x, p.min.y, t.max.x, t.min.y)],
+            inds: vec![0, 1, 2, 2, 3, 0],
+        }
+    }
+
+    // SDLRawTexture *******************************************************************************
+
+    /// A texture owned by a SDLWindow. rust-sdl2's Texture borrows its TextureCreator, which can't
+    /// live in the same struct, so the engine manages these through SDL directly
+    struct SDLRawTexture {
+        raw: *mut sys::SDL_Texture,
+        dims: (u32, u32),
+    }
+
+    impl SDLRawTexture {
+        fn new(canvas: &WindowCanvas, dims: (u32, u32)) -> Result<Self, String> {
+            let raw = unsafe {
+                sys::SDL_CreateTexture(canvas.raw(),
+                                       sys::SDL_PixelFormatEnum::SDL_PIXELFORMAT_RGBA32 as u32,
+                                       sys::SDL_TextureAccess::SDL_TEXTUREACCESS_STATIC as i32,
+                                       dims.0 as i32, dims.1 as i32)
+            };
+            if raw.is_null() {
+                return Err(format!("Failed at SDL_CreateTexture {}", sdl2::get_error()));
+            }
+            unsafe {
+                sys::SDL_SetTextureBlendMode(raw, sys::SDL_BlendMode::SDL_BLENDMODE_BLEND);
+            }
+            Ok(SDLRawTexture { raw, dims })
+        }
+
+        fn bytes(&self) -> usize {
+            self.dims.0 as usize * self.dims.1 as usize * 4
+        }
+
+        /// Replaces all the pixels, rgba has to be RGBA32 with the same dimensions as the texture
+        fn update(&mut self, rgba: &[u8]) -> Result<(), String> {
+            let pitch = self.dims.0 as i32 * 4;
+            let ret = unsafe {
+                sys::SDL_UpdateTexture(self.raw, ptr::null(), rgba.as_ptr() as *const _, pitch)
+            };
+            if ret != 0 {
+                return Err(format!("Failed at SDL_UpdateTexture {}", sdl2::get_error()));
+            }
+            telemetry::report(|t| t.texture_uploaded(rgba.len()));
+            Ok(())
+        }
+    }
+
+    impl Drop for SDLRawTexture {
+        fn drop(&mut self) {
+            unsafe { sys::SDL_DestroyTexture(self.raw) };
+        }
+    }
+
+    /// The textures a SDLWindow draws polygons with, see TextureId
+    struct SDLTextures {
+        glyph_atlas: Option<SDLRawTexture>,
+        /// Created the first time an image is drawn and destroyed once a frame doesn't draw it
+        images: HashMap<u32, SDLRawTexture>,
+        drawn_images: HashSet<u32>,
+        /// EngineConfig::texture_budget
+        budget: usize,
+        /// Images that didn't fit in the budget, reported once
+        over_budget: HashSet<u32>,
+    }
+
+    impl SDLTextures {
+        fn new(budget: usize) -> Self {
+            SDLTextures {
+                glyph_atlas: None,
+                images: HashMap::new(),
+                drawn_images: HashSet::new(),
+                budget,
+                over_budget: HashSet::new(),
+            }
+        }
+
+        /// The texture of the decoded image id, uploading it when it's not there yet. None when
+        /// there is no such image or it doesn't fit in the budget
+        fn image(&mut self, canvas: &WindowCanvas, id: u32) -> Result<Option<*mut sys::SDL_Texture>, String> {
+            self.drawn_images.insert(id);
+            if let Some(texture) = self.images.get(&id) {
+                return Ok(Some(texture.raw));
+            }
+            let texture = with_image_pixels(id, |dims, rgba| {
+                if !self.make_room(dims) {
+                    if self.over_budget.insert(id) {
+                        println!("Image {} of {}x{} doesn't fit in the texture budget of {} bytes",
+                                 id, dims.0, dims.1, self.budget);
+                    }
+                    return Ok(None);
+                }
+                let mut texture = SDLRawTexture::new(canvas, dims)?;
+                texture.update(rgba)?;
+                Ok::<_, String>(Some(texture))
+            });
+            match texture.transpose()?.flatten() {
+                Some(texture) => Ok(Some(self.images.entry(id).or_insert(texture).raw)),
+                None => Ok(None),
+            }
+        }
+
+        /// Bytes kept in textures, the glyph atlas included
+        fn bytes(&self) -> usize {
+            self.glyph_atlas.iter().chain(self.images.values()).map(SDLRawTexture::bytes).sum()
+        }
+
+        /// Destroys the images this frame didn't draw yet until a texture of dims fits in the
+        /// budget. False when it doesn't fit anyway
+        fn make_room(&mut self, dims: (u32, u32)) -> bool {
+            let size = dims.0 as usize * dims.1 as usize * 4;
+            if self.bytes() + size > self.budget {
+                let drawn = &self.drawn_images;
+                self.images.retain(|id, _| drawn.contains(id));
+            }
+            self.bytes() + size <= self.budget
+        }
+
+        fn release_undrawn_images(&mut self) {
+            let drawn = &self.drawn_images;
+            self.images.retain(|id, _| drawn.contains(id));
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::list_model::ListModel;
+        use crate::sections::Section;
+        use crate::table_model::VecTableModel;
+
+        /// SDL without a display, for driving windows through their events
+        fn headless_context() -> SDLContextAndSubsystems {
+            std::env::set_var("SDL_VIDEODRIVER", "dummy");
+            init(EngineConfig::default()).unwrap()
+        }
+
+        fn mouse_event(window: &SDLWindow, kind: &str, x: i32, y: i32) -> Event {
+            let (window_id, which, mouse_btn, clicks) = (window.id(), 0, MouseButton::Left, 1);
+            match kind {
+                "down" => Event::MouseButtonDown { timestamp: 0, window_id, which, mouse_btn, clicks, x, y },
+                "up" => Event::MouseButtonUp { timestamp: 0, window_id, which, mouse_btn, clicks, x, y },
+                _ => Event::MouseMotion {
+                    timestamp: 0,
+                    window_id,
+                    which,
+                    mousestate: MouseState::from_sdl_state(1),
+                    x,
+                    y,
+                    xrel: 0,
+                    yrel: 0,
+                },
+            }
+        }
+
+        #[test]
+        fn dragging_in_a_list_view_selects_the_rows_under_the_rubber_band() {
+            let context = headless_context();
+            let items = (0..6).map(|i| i.to_string()).collect();
+            let list_view = ListView { model: ListModel::new(items), visible_rows: 4, ..Default::default() };
+            let window = Window {
+                container: Some(Container { children: vec![Box::new(list_view)], ..Default::default() }),
+                ..Default::default()
+            };
+            let mut sdl_window = SDLWindow::new(&window, &context).unwrap();
+            sdl_window.rebuild();
+            let list_box = sdl_window.layout_box(&[0]).unwrap();
+            let row_height = list_row_height();
+            let x = (list_box.x + list_box.w / 2.) as i32;
+            let row_middle = |row: f32| (list_box.y + row_height * (row + 0.5)) as i32;
+            let list = |sdl_window: &SDLWindow| sdl_window.old_window.container.as_ref()
+                .and_then(|c| c.children[0].downcast_ref::<ListView>())
+                .map(|l| (l.model.selection.selected().to_vec(), l.model.selection.rubber_band().is_some()))
+                .unwrap();
+
+            assert!(sdl_window.inject_event(mouse_event(&sdl_window, "down", x, row_middle(1.))));
+            assert_eq!(list(&sdl_window), (vec![1], true));
+            sdl_window.inject_event(mouse_event(&sdl_window, "motion", x + 20, row_middle(3.)));
+            assert_eq!(list(&sdl_window), (vec![1, 2, 3], true));
+            let container = sdl_window.components.iter().find(|b| b._name == "Container").unwrap();
+            let fill = |c: sys::SDL_Color| (c.r, c.g, c.b, c.a);
+            let mut vertices = container.polygons.iter().flat_map(|p| &p.poly.vers);
+            assert!(vertices.any(|v| fill(v.color) == fill(RUBBER_BAND_FILL)), "The band is drawn while dragging");
+            sdl_window.inject_event(mouse_event(&sdl_window, "up", x + 20, row_middle(3.)));
+            assert_eq!(list(&sdl_window), (vec![1, 2, 3], false));
+        }
+
+        #[test]
+        fn the_header_of_the_scrolled_section_sticks_over_its_rows() {
+            let context = headless_context();
+            let items = (0..6).map(|i| i.to_string()).collect();
+            let sections = vec![Section { title: "A".to_string(), rows: 3 },
+                                Section { title: "B".to_string(), rows: 3 }];
+            let mut list_view = ListView { model: ListModel::new(items), visible_rows: 4, sections,
+                                           ..Default::default() };
+            // Scrolled to row 1, under the header of A. Below it are row 2, the header of B and row 3
+            assert!(list_view.scroll_by(2));
+            let window = Window {
+                container: Some(Container { children: vec![Box::new(list_view)], ..Default::default() }),
+                ..Default::default()
+            };
+            let mut sdl_window = SDLWindow::new(&window, &context).unwrap();
+            sdl_window.rebuild();
+            let list_box = sdl_window.layout_box(&[0]).unwrap();
+            let row_height = list_row_height();
+            let (x, line_middle) = (list_box.x + 10., |line: f32| list_box.y + row_height * (line + 0.5));
+            let container = sdl_window.components.iter().find(|b| b._name == "Container").unwrap();
+            let headers = ListView::SECTION_HEADERS;
+            assert_eq!(container.hit_test(x, line_middle(0.)), Some(&[0, headers, 0][..]), "The sticky header of A");
+            assert_eq!(container.hit_test(x, line_middle(1.)), Some(&[0, 2][..]));
+            assert_eq!(container.hit_test(x, line_middle(2.)), Some(&[0, headers, 1][..]));
+            assert_eq!(container.hit_test(x, line_middle(3.)), Some(&[0, 3][..]));
+            let selected = |sdl_window: &SDLWindow| sdl_window.old_window.container.as_ref()
+                .and_then(|c| c.children[0].downcast_ref::<ListView>())
+                .map(|l| l.model.selection.selected().to_vec())
+                .unwrap();
+            sdl_window.inject_click(x as i32, line_middle(0.) as i32);
+            assert_eq!(selected(&sdl_window), Vec::<usize>::new(), "Row 1 is under the header");
+            sdl_window.inject_click(x as i32, line_middle(3.) as i32);
+            assert_eq!(selected(&sdl_window), vec![3]);
+        }
+
+        #[test]
+        fn frozen_columns_stay_put_while_the_others_scroll() {
+            let _context = headless_context();
+            let model = VecTableModel { columns: (0..20).map(|c| c.to_string()).collect(), rows: vec![] };
+            let mut table = TableView::new(model, vec![50.; 20]);
+            table.columns.frozen = 1;
+            let parent = pseudo_parent(120., 400.);
+            let header_at = |table: &TableView, x: f32| table.build(&parent).hit_test(x, 5.).map(|p| p.to_vec());
+            assert_eq!(header_at(&table, 10.), Some(vec![TableView::HEADER, 0]));
+            assert_eq!(header_at(&table, 70.), Some(vec![TableView::HEADER, 1]));
+            // The wheel scrolls by pixels, column 1 goes under the frozen one and column 2 shows after it
+            assert!(table.scroll_x_by(60.));
+            assert_eq!(header_at(&table, 10.), Some(vec![TableView::HEADER, 0]));
+            assert_eq!(header_at(&table, 70.), Some(vec![TableView::HEADER, 2]));
+            // Up to the last column, whose right side is at the right of the table
+            assert!(table.scroll_x_by(f32::MAX));
+            assert_eq!(table.clamped_scroll_x(), 50. * 20. - 120.);
+            assert_eq!(header_at(&table, 10.), Some(vec![TableView::HEADER, 0]));
+            assert_eq!(header_at(&table, 100.), Some(vec![TableView::HEADER, 19]));
+            let body = table.build(&parent);
+            let (_, _, _, height) = body.bounds().unwrap();
+            assert_eq!(body.hit_test(10., height - 1.), Some(&[TableView::HORIZONTAL_SCROLLBAR][..]));
+            assert!(table.set_scroll_x_fraction(0.));
+            assert_eq!(header_at(&table, 70.), Some(vec![TableView::HEADER, 1]));
+        }
+
+        #[test]
+        fn forgotten_images_leave_their_id_unused() {
+            let red = Pattern::from_fn(2, 2, |_, _| crate::elements::Color::rgb(255, 0, 0));
+            let red_id = pattern_texture_id(&red).unwrap();
+            forget_pattern(&red);
+            let blue = Pattern::from_fn(4, 4, |_, _| crate::elements::Color::rgb(0, 0, 255));
+            let blue_id = pattern_texture_id(&blue).unwrap();
+            assert_ne!(red_id, blue_id);
+            assert_eq!(with_image_pixels(blue_id, |dims, rgba| (dims, rgba[2])), Some(((4, 4), 255)));
+            assert_eq!(with_image_pixels(red_id, |dims, _| dims), None);
         }
     }
-} // END mod sdl
\ No newline at end of file
+} // END mod sdl
+
+#[cfg(target_arch = "wasm32")]
+pub mod web;
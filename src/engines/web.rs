@@ -0,0 +1,4504 @@
+//! The browser engine, used instead of engines::sdl when building for wasm32. Windows are drawn on
+//! a HTML canvas through its 2D context and the DOM events are translated into clicks and shortcuts
+//! of the same elements, so a rui_lopez app runs natively and in the browser:
+//!
+//! ```ignore
+//! #[wasm_bindgen(start)]
+//! pub fn start() -> Result<(), JsValue> {
+//!     rui_lopez::engines::web::main_loop(build_window(), "canvas").map_err(|e| JsValue::from_str(&e))
+//! }
+//! ```
+//!
+//! Build it with `cargo build --lib --target wasm32-unknown-unknown --no-default-features` and run
+//! wasm-bindgen on the result. Instant is not available in the browser, so transient status
+//! messages are timed with performance.now() instead of StatusBar.show_message()
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Clamped;
+use web_sys::{js_sys, CanvasPattern, CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlImageElement, ImageData,
+              KeyboardEvent, MouseEvent, WheelEvent};
+
+use crate::accessibility::{self, ColorMode};
+use crate::accessibility_tree::{self, AccessibleNode, Live};
+use crate::calendar::{Date, GRID_DAYS, WEEKDAY_NAMES};
+use crate::caret::TextPosition;
+#[cfg(feature = "charts")]
+use crate::charts::{self, BarChart, ChartShape, LineChart, PieChart};
+use crate::commands;
+use crate::context;
+use crate::contrast::{self, ColorPair};
+use crate::elements::*;
+use crate::incremental;
+use crate::layout_check::{self, LayoutBox, LayoutWarning};
+use crate::markdown::{self, MarkdownShape};
+use crate::row_heights::RowHeights;
+use crate::sections::{SectionEntry, SectionLayout};
+use crate::selection::SelectModifiers;
+use crate::stroke::{self, LineJoin, StrokeStyle};
+#[cfg(feature = "lyon")]
+use crate::svg::SvgImage;
+use crate::table_model::{SortOrder, TableModel};
+use crate::taskbar::{self, Attention};
+#[cfg(feature = "remote-debug")]
+use crate::remote_debug::{self, RemoteCommand};
+use crate::telemetry;
+use crate::text_wrap::wrap_lines;
+use crate::timers;
+use crate::tour::{self, Tour, TourStep};
+use crate::tree_table::TreeTable;
+use crate::ui_channel::{self, UiMessage};
+use crate::ui_macros::{self, Macro, MacroStep, WidgetId};
+
+//Structs and Traits *******************************************************************************
+
+/// A draw call of the 2D context, colors are CSS colors
+#[derive(Debug, Clone)]
+pub enum WebShape {
+    Rect { x: f32, y: f32, w: f32, h: f32, color: &'static str },
+    Outline { x: f32, y: f32, w: f32, h: f32, thickness: f32, color: &'static str },
+    Circle { cx: f32, cy: f32, radius: f32, color: &'static str },
+    /// A filled rectangle with its corners rounded by radius
+    RoundedRect { x: f32, y: f32, w: f32, h: f32, radius: f32, color: Cow<'static, str> },
+    /// Connected line segments, e.g. a checkmark
+    Lines { points: Vec<(f32, f32)>, thickness: f32, color: Cow<'static, str> },
+    /// The stroke of a path with its corners joined, dashed or solid, see the stroke module
+    Stroke { points: Vec<(f32, f32)>, closed: bool, style: StrokeStyle, color: Cow<'static, str> },
+    /// A filled polygon, e.g. a triangle drawn by a Canvas
+    Polygon { points: Vec<(f32, f32)>, color: String },
+    /// Colors blended from the top to the bottom (or left to right), stops are at fractions of it.
+    /// A single stop fills it with its color
+    Gradient { x: f32, y: f32, w: f32, h: f32, vertical: bool, stops: Vec<(f32, String)> },
+    /// The rectangle filled with the pattern repeated from its top left corner
+    Pattern { x: f32, y: f32, w: f32, h: f32, pattern: Pattern },
+    /// Drawn from its top left corner
+    Text { text: String, x: f32, y: f32, size: f32, color: Cow<'static, str> },
+    /// Text in the font of a style, drawn from its top left corner
+    Span { text: String, x: f32, y: f32, size: f32, style: FontStyle, color: Cow<'static, str> },
+    /// The image at src laid out in the rectangle once the browser loaded it, its corners are
+    /// rounded by radius. The background is drawn under it where it is
+    Image { src: String, x: f32, y: f32, w: f32, h: f32, scale_mode: ScaleMode, radius: f32,
+            background: Option<Pattern> },
+}
+
+/// The web counterpart of SDLBody, a group of shapes and the areas reacting to the pointer
+#[derive(Debug, Clone)]
+pub struct WebBody {
+    _name: String,
+    shapes: Vec<WebShape>,
+    hit_areas: Vec<WebHitArea>,
+    /// Popups drawn after every body and hit tested before them, see SDLBody
+    overlays: Vec<WebBody>,
+    /// Drawn after the shapes and only inside their rectangle, see SDLBody
+    clipped: Vec<WebClippedBody>,
+    /// Where the containers in it laid out their children, see SDLBody
+    layout: Vec<LayoutBox>,
+}
+
+/// A body drawn and hit tested only inside a rectangle, see SDLClippedBody
+#[derive(Debug, Clone)]
+pub struct WebClippedBody {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    pub body: WebBody,
+}
+
+/// A rectangle of a WebBody that reacts to the pointer, see SDLHitArea
+#[derive(Debug, Clone)]
+pub struct WebHitArea {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    pub path: Vec<usize>,
+}
+
+impl WebShape {
+    fn translate(&mut self, dx: f32, dy: f32) {
+        match self {
+            WebShape::Rect { x, y, .. }
+            | WebShape::Outline { x, y, .. }
+            | WebShape::Gradient { x, y, .. }
+            | WebShape::RoundedRect { x, y, .. }
+            | WebShape::Text { x, y, .. }
+            | WebShape::Span { x, y, .. }
+            | WebShape::Image { x, y, .. }
+            | WebShape::Pattern { x, y, .. }
+            | WebShape::Circle { cx: x, cy: y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            WebShape::Lines { points, .. } | WebShape::Stroke { points, .. } | WebShape::Polygon { points, .. } => {
+                for (x, y) in points.iter_mut() {
+                    *x += dx;
+                    *y += dy;
+                }
+            }
+        }
+    }
+
+    /// Its bounding rectangle as (min x, min y, max x, max y)
+    fn extent(&self) -> (f32, f32, f32, f32) {
+        match self {
+            WebShape::Rect { x, y, w, h, .. }
+            | WebShape::Outline { x, y, w, h, .. }
+            | WebShape::Gradient { x, y, w, h, .. }
+            | WebShape::RoundedRect { x, y, w, h, .. }
+            | WebShape::Image { x, y, w, h, .. }
+            | WebShape::Pattern { x, y, w, h, .. } => (*x, *y, x + w, y + h),
+            WebShape::Circle { cx, cy, radius, .. } => (cx - radius, cy - radius, cx + radius, cy + radius),
+            WebShape::Text { text, x, y, size, .. } => {
+                let (w, h) = measure_text(text, *size);
+                (*x, *y, x + w, y + h)
+            }
+            WebShape::Span { text, x, y, size, style, .. } => {
+                let (w, h) = measure_styled_text(text, *size, *style);
+                (*x, *y, x + w, y + h)
+            }
+            WebShape::Lines { points, .. } | WebShape::Stroke { points, .. } | WebShape::Polygon { points, .. } => {
+                points.iter().fold(
+                    (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+                    |(min_x, min_y, max_x, max_y), (x, y)| {
+                        (min_x.min(*x), min_y.min(*y), max_x.max(*x), max_y.max(*y))
+                    })
+            }
+        }
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        match self {
+            WebShape::Rect { x, y, w, h, color } => {
+                context.set_fill_style_str(&contrast_style(color));
+                context.fill_rect(*x as f64, *y as f64, *w as f64, *h as f64);
+            }
+            WebShape::Outline { x, y, w, h, thickness, color } => {
+                // Strokes are centered on the path, the outline is kept inside the rectangle
+                let half = *thickness as f64 / 2.;
+                context.set_stroke_style_str(&contrast_style(color));
+                context.set_line_width(*thickness as f64);
+                context.stroke_rect(*x as f64 + half, *y as f64 + half,
+                                    *w as f64 - 2. * half, *h as f64 - 2. * half);
+            }
+            WebShape::Gradient { x, y, w, h, vertical, stops } => {
+                let (x, y, w, h) = (*x as f64, *y as f64, *w as f64, *h as f64);
+                let gradient = match vertical {
+                    true => context.create_linear_gradient(x, y, x, y + h),
+                    false => context.create_linear_gradient(x, y, x + w, y),
+                };
+                for (offset, color) in stops {
+                    if let Err(e) = gradient.add_color_stop(*offset, color) {
+                        log(&format!("Failed to add the gradient stop {} {:?}", color, e));
+                    }
+                }
+                context.set_fill_style_canvas_gradient(&gradient);
+                context.fill_rect(x, y, w, h);
+            }
+            WebShape::Circle { cx, cy, radius, color } => {
+                context.set_fill_style_str(&contrast_style(color));
+                context.begin_path();
+                if let Err(e) = context.arc(*cx as f64, *cy as f64, *radius as f64, 0., std::f64::consts::TAU) {
+                    log(&format!("Failed to draw a circle {:?}", e));
+                }
+                context.fill();
+            }
+            WebShape::RoundedRect { x, y, w, h, radius, color } => {
+                context.set_fill_style_str(&contrast_style(color));
+                rounded_rect_path(context, *x, *y, *w, *h, *radius);
+                context.fill();
+            }
+            WebShape::Lines { points, thickness, color } => {
+                let mut points = points.iter();
+                let (x, y) = match points.next() {
+                    Some(first) => first,
+                    None => return,
+                };
+                context.set_stroke_style_str(&contrast_style(color));
+                context.set_line_width(*thickness as f64);
+                context.begin_path();
+                context.move_to(*x as f64, *y as f64);
+                for (x, y) in points {
+                    context.line_to(*x as f64, *y as f64);
+                }
+                context.stroke();
+            }
+            WebShape::Stroke { points, closed, style, color } => {
+                // The dashes and the join are put back for the other shapes
+                context.save();
+                let dashes: js_sys::Array = match style.is_dashed() {
+                    true => style.dashes.iter().map(|d| JsValue::from_f64(*d as f64)).collect(),
+                    false => js_sys::Array::new(),
+                };
+                if let Err(e) = context.set_line_dash(&dashes) {
+                    log(&format!("Failed to dash a stroke {:?}", e));
+                }
+                context.set_line_join(match style.join {
+                    LineJoin::Miter => "miter",
+                    LineJoin::Bevel => "bevel",
+                    LineJoin::Round => "round",
+                });
+                context.set_miter_limit(stroke::MITER_LIMIT as f64);
+                context.set_stroke_style_str(&contrast_style(color));
+                context.set_line_width(style.width as f64);
+                context.begin_path();
+                for (x, y) in points {
+                    context.line_to(*x as f64, *y as f64);
+                }
+                if *closed {
+                    context.close_path();
+                }
+                context.stroke();
+                context.restore();
+            }
+            WebShape::Polygon { points, color } => {
+                context.set_fill_style_str(&contrast_style(color));
+                context.begin_path();
+                for (x, y) in points {
+                    context.line_to(*x as f64, *y as f64);
+                }
+                context.fill();
+            }
+            WebShape::Pattern { x, y, w, h, pattern } => fill_pattern(context, pattern, *x, *y, *w, *h),
+            WebShape::Text { text, x, y, size, color } => {
+                context.set_fill_style_str(&contrast_style(color));
+                context.set_font(&font(*size));
+                context.set_text_baseline("top");
+                if let Err(e) = context.fill_text(text, *x as f64, *y as f64) {
+                    log(&format!("Failed to draw {} {:?}", text, e));
+                }
+            }
+            WebShape::Span { text, x, y, size, style, color } => {
+                context.set_fill_style_str(&contrast_style(color));
+                context.set_font(&styled_font(*size, *style));
+                context.set_text_baseline("top");
+                if let Err(e) = context.fill_text(text, *x as f64, *y as f64) {
+                    log(&format!("Failed to draw {} {:?}", text, e));
+                }
+            }
+            WebShape::Image { src, x, y, w, h, scale_mode, radius, background } => {
+                let image = match loaded_image(src) {
+                    Some(image) => image,
+                    None => return,
+                };
+                context.save();
+                if *radius > 0. {
+                    rounded_rect_path(context, *x, *y, *w, *h, *radius);
+                    context.clip();
+                }
+                let (image_w, image_h) = (image.natural_width() as f32, image.natural_height() as f32);
+                for quad in scale_mode.layout((image_w, image_h), (*w, *h)) {
+                    if let Some(background) = background {
+                        fill_pattern(context, background, x + quad.x, y + quad.y, quad.w, quad.h);
+                    }
+                    let result = context.draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                        &image, (quad.u.0 * image_w) as f64, (quad.v.0 * image_h) as f64,
+                        ((quad.u.1 - quad.u.0) * image_w) as f64, ((quad.v.1 - quad.v.0) * image_h) as f64,
+                        (x + quad.x) as f64, (y + quad.y) as f64, quad.w as f64, quad.h as f64);
+                    if let Err(e) = result {
+                        log(&format!("Failed to draw the image {} {:?}", src, e));
+                        break;
+                    }
+                }
+                context.restore();
+            }
+        }
+    }
+}
+
+/// Starts a path around the rectangle with its corners rounded by radius
+fn rounded_rect_path(context: &CanvasRenderingContext2d, x: f32, y: f32, w: f32, h: f32, radius: f32) {
+    let (x, y, w, h) = (x as f64, y as f64, w as f64, h as f64);
+    let radius = (radius as f64).min(w / 2.).min(h / 2.);
+    context.begin_path();
+    context.move_to(x + radius, y);
+    let corners = [(x + w, y, x + w, y + h), (x + w, y + h, x, y + h), (x, y + h, x, y), (x, y, x + w, y)];
+    for (corner_x, corner_y, next_x, next_y) in corners {
+        if let Err(e) = context.arc_to(corner_x, corner_y, next_x, next_y, radius) {
+            log(&format!("Failed to round a corner {:?}", e));
+        }
+    }
+    context.close_path();
+}
+
+impl WebBody {
+    pub fn new(name: &str) -> Self {
+        WebBody {
+            _name: name.to_string(),
+            shapes: vec![],
+            hit_areas: vec![],
+            overlays: vec![],
+            clipped: vec![],
+            layout: vec![],
+        }
+    }
+
+    pub fn push(&mut self, shape: WebShape) {
+        self.shapes.push(shape);
+    }
+
+    /// Takes the shapes of other, which is expected to be already translated
+    pub fn append(&mut self, other: WebBody) {
+        self.shapes.extend(other.shapes);
+        self.hit_areas.extend(other.hit_areas);
+        self.overlays.extend(other.overlays);
+        self.clipped.extend(other.clipped);
+        self.layout.extend(other.layout);
+    }
+
+    /// Like append() but the paths of the hit areas of the child get its index in front, so a
+    /// click can be routed down to it
+    pub fn append_child(&mut self, index: usize, mut child: WebBody) {
+        child.prefix_paths(index);
+        self.append(child);
+    }
+
+    /// Like append_child() but the child is only drawn and hit inside the rectangle
+    pub fn append_clipped_child(&mut self, index: usize, x: f32, y: f32, w: f32, h: f32, mut child: WebBody) {
+        child.prefix_paths(index);
+        self.overlays.append(&mut child.overlays);
+        self.clipped.push(WebClippedBody { x, y, w, h, body: child });
+    }
+
+    fn prefix_paths(&mut self, index: usize) {
+        for area in self.hit_areas.iter_mut() {
+            area.path.insert(0, index);
+        }
+        for layout_box in self.layout.iter_mut() {
+            layout_box.path.insert(0, index);
+        }
+        for body in self.overlays.iter_mut().chain(self.clipped.iter_mut().map(|c| &mut c.body)) {
+            body.prefix_paths(index);
+        }
+    }
+
+    pub fn add_hit_area(&mut self, x: f32, y: f32, w: f32, h: f32, path: Vec<usize>) {
+        self.hit_areas.push(WebHitArea { x, y, w, h, path });
+    }
+
+    pub fn push_overlay(&mut self, overlay: WebBody) {
+        self.overlays.push(overlay);
+    }
+
+    pub fn overlay_hit_area(&self, x: f32, y: f32) -> Option<&WebHitArea> {
+        self.overlays.iter().rev().find_map(|o| o.hit_area(x, y))
+    }
+
+    /// The boxes of the components laid out in it, overlays and clipped bodies included
+    pub fn layout_boxes(&self) -> Vec<LayoutBox> {
+        let clipped = self.clipped.iter().flat_map(|c| c.body.layout_boxes())
+            .map(|layout_box| LayoutBox { clipped: true, ..layout_box });
+        self.layout.iter().cloned()
+            .chain(self.overlays.iter().flat_map(|o| o.layout_boxes()))
+            .chain(clipped)
+            .collect()
+    }
+
+    pub fn hit_area(&self, x: f32, y: f32) -> Option<&WebHitArea> {
+        self.overlay_hit_area(x, y)
+            .or_else(|| self.clipped.iter().rev()
+                .filter(|c| x >= c.x && x < c.x + c.w && y >= c.y && y < c.y + c.h)
+                .find_map(|c| c.body.hit_area(x, y)))
+            .or_else(|| self.hit_areas.iter().rev()
+                .find(|a| x >= a.x && x < a.x + a.w && y >= a.y && y < a.y + a.h))
+    }
+
+    /// The path of the topmost (last added) hit area containing the point
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<&[usize]> {
+        self.hit_area(x, y).map(|a| a.path.as_slice())
+    }
+
+    /// Components build themselves at the origin, their parents move them into place
+    pub fn translate(&mut self, dx: f32, dy: f32) {
+        for shape in self.shapes.iter_mut() {
+            shape.translate(dx, dy);
+        }
+        for area in self.hit_areas.iter_mut() {
+            area.x += dx;
+            area.y += dy;
+        }
+        for layout_box in self.layout.iter_mut() {
+            layout_box.x += dx;
+            layout_box.y += dy;
+        }
+        for overlay in self.overlays.iter_mut() {
+            overlay.translate(dx, dy);
+        }
+        for clipped in self.clipped.iter_mut() {
+            clipped.x += dx;
+            clipped.y += dy;
+            clipped.body.translate(dx, dy);
+        }
+    }
+
+    /// The smallest rectangle containing every shape as (x, y, width, height), the overlays are
+    /// left out and the clipped bodies count as their rectangle
+    pub fn bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        let clip_extents = self.clipped.iter().map(|c| (c.x, c.y, c.x + c.w, c.y + c.h));
+        let mut extents = self.shapes.iter().map(|s| s.extent()).chain(clip_extents);
+        let first = extents.next()?;
+        let (min_x, min_y, max_x, max_y) = extents.fold(first, |a, b| {
+            (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+        });
+        Some((min_x, min_y, max_x - min_x, max_y - min_y))
+    }
+
+    /// Draws the shapes and then the clipped bodies, the clip of the context narrows down with
+    /// each one and is restored after it
+    pub fn draw(&self, context: &CanvasRenderingContext2d) {
+        for shape in self.shapes.iter() {
+            shape.draw(context);
+        }
+        for clipped in self.clipped.iter() {
+            context.save();
+            context.begin_path();
+            context.rect(clipped.x as f64, clipped.y as f64, clipped.w as f64, clipped.h as f64);
+            context.clip();
+            clipped.body.draw(context);
+            context.restore();
+        }
+    }
+}
+
+/// This web engine will only use WebBody as NativeDrawable, the same way the SDL one only uses
+/// SDLBody
+impl NativeDrawable for WebBody {}
+
+/// The web version of SDLComponent
+pub trait WebComponent: std::fmt::Debug + Component {
+    fn build(&self, parent: &dyn Component) -> WebBody;
+}
+
+/// Component.build_dyn() returns the dynamic NativeDrawable, it's casted back to WebBody
+///
+/// # Panics
+/// When the NativeDrawable is not a WebBody
+fn dyn_to_web_body(drawable: Box<dyn NativeDrawable>) -> WebBody {
+    *drawable.downcast::<WebBody>().expect("Only WebBody should be NativeDrawable!")
+}
+
+// Globals *****************************************************************************************
+
+thread_local! {
+    /// The context of the canvas, used to measure text while building
+    static CONTEXT: RefCell<Option<CanvasRenderingContext2d>> = const { RefCell::new(None) };
+    /// The images by src, the browser loads them in the background and they are drawn once loaded
+    static IMAGES: RefCell<HashMap<String, HtmlImageElement>> = RefCell::new(HashMap::new());
+    /// The patterns and the canvases of their tile, kept once drawn
+    static PATTERNS: RefCell<Vec<(Pattern, HtmlCanvasElement)>> = const { RefCell::new(vec![]) };
+}
+
+// Functions ***************************************************************************************
+
+/// Shows the window on the canvas with id canvas_id, it returns once the first frame is requested
+/// since the browser runs the loop from then on
+pub fn main_loop(window: Window, canvas_id: &str) -> Result<(), String> {
+    let web_window = Rc::new(RefCell::new(WebWindow::new(&window, canvas_id)?));
+    web_window.borrow_mut().rebuild();
+    listen_to_pointer(&web_window)?;
+    listen_to_wheel(&web_window)?;
+    listen_to_keyboard(&web_window)?;
+
+    let frame = Rc::new(RefCell::new(None::<Closure<dyn FnMut()>>));
+    let next_frame = frame.clone();
+    let mut count = 0u64;
+    *frame.borrow_mut() = Some(Closure::new(move || {
+        {
+            let frame_start = now();
+            let _span = trace_span!("frame", frame = count);
+            telemetry::report(|t| t.frame_start(count));
+            let mut web_window = web_window.borrow_mut();
+            web_window.update(frame_start);
+            web_window.render();
+            let elapsed = Duration::from_secs_f64((now() - frame_start).max(0.) / 1000.);
+            telemetry::report(|t| t.frame_end(count, elapsed));
+            count += 1;
+        }
+        if let Some(callback) = next_frame.borrow().as_ref() {
+            if let Err(e) = request_animation_frame(callback) {
+                log(&e);
+            }
+        }
+    }));
+    let first = frame.borrow();
+    request_animation_frame(first.as_ref().expect("The frame callback was just set"))
+}
+
+fn request_animation_frame(callback: &Closure<dyn FnMut()>) -> Result<(), String> {
+    browser_window()?.request_animation_frame(callback.as_ref().unchecked_ref())
+        .map(|_| ())
+        .map_err(|e| format!("Failed to request an animation frame {:?}", e))
+}
+
+fn listen_to_pointer(web_window: &Rc<RefCell<WebWindow>>) -> Result<(), String> {
+    let target = web_window.clone();
+    let on_mouse_down = Closure::<dyn FnMut(MouseEvent)>::new(move |event: MouseEvent| {
+        let _alloc = alloc_scope!("events");
+        let (x, y) = unzoom(event.offset_x(), event.offset_y());
+        if event.button() == 0 && target.borrow_mut().handle_click(x, y) {
+            event.prevent_default();
+        }
+    });
+    web_window.borrow().canvas
+        .add_event_listener_with_callback("mousedown", on_mouse_down.as_ref().unchecked_ref())
+        .map_err(|e| format!("Failed to listen to the pointer {:?}", e))?;
+    // The listener lives as long as the page
+    on_mouse_down.forget();
+    Ok(())
+}
+
+fn listen_to_wheel(web_window: &Rc<RefCell<WebWindow>>) -> Result<(), String> {
+    let target = web_window.clone();
+    let on_wheel = Closure::<dyn FnMut(WheelEvent)>::new(move |event: WheelEvent| {
+        let _alloc = alloc_scope!("events");
+        let (x, y) = unzoom(event.offset_x(), event.offset_y());
+        // Shift turns the wheel sideways
+        let (dx, dy) = match event.shift_key() {
+            true => ((event.delta_x() + event.delta_y()) as f32, 0.),
+            false => (event.delta_x() as f32, event.delta_y() as f32),
+        };
+        // Scrolled views keep the page from scrolling
+        if target.borrow_mut().handle_wheel(x, y, dx, dy) {
+            event.prevent_default();
+        }
+    });
+    web_window.borrow().canvas
+        .add_event_listener_with_callback("wheel", on_wheel.as_ref().unchecked_ref())
+        .map_err(|e| format!("Failed to listen to the wheel {:?}", e))?;
+    on_wheel.forget();
+    Ok(())
+}
+
+fn listen_to_keyboard(web_window: &Rc<RefCell<WebWindow>>) -> Result<(), String> {
+    let target = web_window.clone();
+    let on_key_down = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+        let _alloc = alloc_scope!("events");
+        // Consumed shortcuts don't reach the browser, e.g. Ctrl+S doesn't save the page
+        if target.borrow_mut().handle_key(&event) {
+            event.prevent_default();
+        }
+    });
+    browser_window()?
+        .add_event_listener_with_callback("keydown", on_key_down.as_ref().unchecked_ref())
+        .map_err(|e| format!("Failed to listen to the keyboard {:?}", e))?;
+    on_key_down.forget();
+    Ok(())
+}
+
+/// A point of the canvas in the coordinates the components are laid out in
+fn unzoom(x: i32, y: i32) -> (i32, i32) {
+    let zoom = accessibility::zoom();
+    ((x as f32 / zoom) as i32, (y as f32 / zoom) as i32)
+}
+
+fn browser_window() -> Result<web_sys::Window, String> {
+    web_sys::window().ok_or("Not running in a browser window".to_string())
+}
+
+/// Milliseconds update() gives the incremental jobs every frame
+const WORK_BUDGET: f64 = 8.;
+
+/// Milliseconds since the page was loaded
+fn now() -> f64 {
+    web_sys::window().and_then(|w| w.performance()).map(|p| p.now()).unwrap_or(0.)
+}
+
+/// println!() goes nowhere in the browser, this goes to the console
+fn log(text: &str) {
+    web_sys::console::log_1(&JsValue::from_str(text));
+}
+
+/// The name of a key combination the way shortcuts are written, e.g. "Ctrl+Shift+S", letters are
+/// uppercase like SDL names them
+fn shortcut_name(event: &KeyboardEvent) -> String {
+    let mut name = String::new();
+    if event.ctrl_key() {
+        name.push_str("Ctrl+");
+    }
+    if event.alt_key() {
+        name.push_str("Alt+");
+    }
+    if event.shift_key() {
+        name.push_str("Shift+");
+    }
+    name.push_str(&event.key().to_uppercase());
+    name
+}
+
+// WebWindow ***************************************************************************************
+
+/// A Window shown on a HTML canvas, the web counterpart of SDLWindow
+pub struct WebWindow {
+    old_window: Window,
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    components: Vec<WebBody>,
+    /// Transient status bar text and when it expires, in now() milliseconds
+    status_message: Option<(String, f64)>,
+    /// When update() last ran, animations advance by the time since
+    last_update: Option<f64>,
+    /// accessibility::generation() when the preferences were last applied
+    accessibility_generation: u64,
+    /// The accessibility tree of the container when it was last built, None before that
+    accessible_nodes: Option<Vec<AccessibleNode>>,
+    /// What layout_check found when the window was last built, logged in debug builds
+    layout_warnings: Vec<LayoutWarning>,
+    /// The guided tour shown over the window, see tour::start()
+    tour: Option<Tour>,
+}
+
+impl WebWindow {
+    pub fn new(window: &Window, canvas_id: &str) -> Result<Self, String> {
+        let document = browser_window()?.document().ok_or("The page has no document".to_string())?;
+        let canvas = document.get_element_by_id(canvas_id)
+            .ok_or(format!("There is no element with the id {}", canvas_id))?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| format!("The element {} is not a canvas", canvas_id))?;
+        let context = canvas.get_context("2d")
+            .map_err(|e| format!("Failed to get the 2D context {:?}", e))?
+            .ok_or("The canvas has no 2D context".to_string())?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|_| "The 2D context is not a CanvasRenderingContext2d".to_string())?;
+        CONTEXT.with(|c| *c.borrow_mut() = Some(context.clone()));
+        warn_low_contrast();
+        document.set_title(&window.title);
+        Ok(WebWindow {
+            old_window: window.clone(),
+            canvas,
+            context,
+            components: vec![],
+            status_message: None,
+            last_update: None,
+            accessibility_generation: 0,
+            accessible_nodes: None,
+            layout_warnings: vec![],
+            tour: None,
+        })
+    }
+
+    /// The canvas as a parent for the top level components, smaller when zoomed in
+    fn window_pseudo_parent(&self) -> Container {
+        let zoom = accessibility::zoom();
+        pseudo_parent(self.canvas.width() as f32 / zoom, self.canvas.height() as f32 / zoom)
+    }
+
+    /// Builds the whole window again, the web engine doesn't rebuild parts of it
+    pub fn rebuild(&mut self) {
+        if let Some(container) = self.old_window.container.as_mut() {
+            container.expand();
+        }
+        let pseudo = self.window_pseudo_parent();
+        let mut components = vec![];
+        if let Some(body) = build_window_background(&self.old_window.background, &pseudo) {
+            components.push(body);
+        }
+        if let Some(body) = build_docked_container(&self.old_window, &pseudo) {
+            components.push(body);
+        }
+        if let Some(status_bar) = &self.old_window.status_bar {
+            let mut status_bar = status_bar.clone();
+            if let Some((message, _)) = &self.status_message {
+                status_bar.text = message.clone();
+            }
+            components.push(build_docked_status_bar(&status_bar, &pseudo));
+        }
+        if let Some(tool_bar) = &self.old_window.tool_bar {
+            components.push(build_docked_tool_bar(&self.old_window, tool_bar, &pseudo));
+        }
+        // Last so its dropdown is drawn over the rest
+        if let Some(menu) = &self.old_window.menu {
+            components.push(menu.build(&pseudo));
+        }
+        telemetry::report(|t| t.components_built(&self.old_window.title, components.len()));
+        self.components = components;
+        self.announce_live_changes();
+        self.check_layout();
+        // Over everything else, after the container it highlights a widget of
+        if let Some(body) = self.build_tour(&pseudo) {
+            self.components.push(body);
+        }
+    }
+
+    /// Runs layout_check on the container, the warnings that are new are logged in debug builds
+    fn check_layout(&mut self) {
+        let (width, height) = parent_size(&self.window_pseudo_parent());
+        let boxes = self.components.iter().find(|b| b._name == "Container")
+            .map(|b| b.layout_boxes())
+            .unwrap_or_default();
+        let warnings = layout_check::check(&boxes, width, height);
+        if cfg!(debug_assertions) {
+            for warning in warnings.iter().filter(|w| !self.layout_warnings.contains(w)) {
+                log(&format!("Layout warning in {}: {}", self.old_window.title, warning));
+            }
+        }
+        self.layout_warnings = warnings;
+    }
+
+    /// Announces what the live components say since the container was last built, through the
+    /// live regions of the page
+    fn announce_live_changes(&mut self) {
+        let nodes = self.old_window.container.as_ref().map(accessibility_tree::tree).unwrap_or_default();
+        if let Some(previous) = &self.accessible_nodes {
+            for announcement in accessibility_tree::changes(previous, &nodes) {
+                let region = live_region(announcement.live);
+                if let Err(e) = region.map(|r| r.set_text_content(Some(&announcement.text))) {
+                    log(&format!("Failed to announce {} {}", announcement.text, e));
+                }
+                accessibility_tree::announce(announcement);
+            }
+        }
+        self.accessible_nodes = Some(nodes);
+    }
+
+    /// Shows the background behind the components from now on, e.g. when the theme changes
+    pub fn set_background(&mut self, background: WindowBackground) {
+        self.old_window.background = background;
+        self.rebuild();
+    }
+
+    /// Shows the guided tour over the window from its current step, replacing the one shown if
+    /// any. Escape or the Skip button end it
+    pub fn start_tour(&mut self, tour: Tour) {
+        if tour.step().is_none() {
+            (tour.on_end)(true);
+            return;
+        }
+        if let Some(shown) = self.tour.take() {
+            shown.skip();
+        }
+        self.tour = Some(tour);
+        self.rebuild();
+        self.announce_tour_step();
+    }
+
+    fn next_tour_step(&mut self) {
+        if self.tour.as_mut().is_some_and(|t| !t.advance()) {
+            self.tour = None;
+        }
+        self.rebuild();
+        self.announce_tour_step();
+    }
+
+    fn announce_tour_step(&self) {
+        if let Some(announcement) = self.tour.as_ref().and_then(Tour::announcement) {
+            if let Err(e) = live_region(announcement.live).map(|r| r.set_text_content(Some(&announcement.text))) {
+                log(&format!("Failed to announce {} {}", announcement.text, e));
+            }
+            accessibility_tree::announce(announcement);
+        }
+    }
+
+    fn skip_tour(&mut self) {
+        if let Some(tour) = self.tour.take() {
+            tour.skip();
+        }
+        self.rebuild();
+    }
+
+    /// Whether path is in the widget the tour highlights
+    fn in_tour_target(&self, path: &[usize]) -> bool {
+        let nodes = self.accessible_nodes.as_deref().unwrap_or_default();
+        self.tour.as_ref().and_then(|t| t.target_path(nodes)).is_some_and(|target| path.starts_with(&target))
+    }
+
+    fn build_tour(&self, pseudo: &Container) -> Option<WebBody> {
+        let tour = self.tour.as_ref()?;
+        let highlight = tour.target_path(self.accessible_nodes.as_deref().unwrap_or_default())
+            .and_then(|path| self.layout_box(&path))
+            .map(|b| (b.x - TOUR_HIGHLIGHT_PADDING, b.y - TOUR_HIGHLIGHT_PADDING,
+                      b.w + 2. * TOUR_HIGHLIGHT_PADDING, b.h + 2. * TOUR_HIGHLIGHT_PADDING));
+        Some(build_tour_step(tour, tour.step()?, highlight, parent_size(pseudo)))
+    }
+
+    pub fn render(&self) {
+        let background = css_color(self.old_window.background.clear_color());
+        self.context.set_fill_style_str(&contrast_style(&background));
+        self.context.fill_rect(0., 0., self.canvas.width() as f64, self.canvas.height() as f64);
+        let zoom = accessibility::zoom() as f64;
+        if let Err(e) = self.context.set_transform(zoom, 0., 0., zoom, 0., 0.) {
+            log(&format!("Failed to zoom the UI {:?}", e));
+        }
+        let overlays = self.components.iter().flat_map(|b| b.overlays.iter());
+        for body in self.components.iter().chain(overlays) {
+            body.draw(&self.context);
+        }
+        if let Err(e) = self.context.reset_transform() {
+            log(&format!("Failed to reset the transform {:?}", e));
+        }
+    }
+
+    /// Applies a message posted through ui_channel::post()
+    pub fn handle_message(&mut self, message: UiMessage) -> Result<(), String> {
+        match message {
+            UiMessage::MenuUpdate(batch) => {
+                self.old_window.menu.as_mut()
+                    .ok_or("Window has no menu".to_string())?
+                    .apply(&batch)?;
+            }
+            UiMessage::StatusText(text) => self.status_bar_mut()?.text = text,
+            UiMessage::StatusMessage(text, duration) => {
+                self.status_bar_mut()?;
+                self.status_message = Some((text, now() + duration.as_secs_f64() * 1000.));
+            }
+            UiMessage::StatusProgress(progress) => self.status_bar_mut()?.progress = progress,
+            UiMessage::StatusSectionText(index, text) => self.status_bar_mut()?.set_section_text(index, &text)?,
+            UiMessage::PageLoaded(delivery) => {
+                if !self.old_window.container.as_mut().is_some_and(|c| c.deliver_page(&delivery)) {
+                    return Err(format!("No view requested the page of {} at {}", delivery.source, delivery.offset));
+                }
+            }
+            UiMessage::Loading { source, loading } => {
+                if !self.old_window.container.as_mut().is_some_and(|c| c.set_loading(&source, loading)) {
+                    return Err(format!("No Loadable shows {}", source));
+                }
+            }
+            UiMessage::ConsoleOutput { source, text } => {
+                if !self.old_window.container.as_mut().is_some_and(|c| c.write_console(&source, &text)) {
+                    return Err(format!("No Console shows {}", source));
+                }
+            }
+            UiMessage::TaskbarProgress(progress) => {
+                self.old_window.taskbar_progress = progress;
+                // Tabs show the title of the page, which is the closest thing to a taskbar
+                if !taskbar::show_progress(&self.old_window.title, progress.as_ref()) {
+                    let title = match progress {
+                        Some(progress) => format!("({}) {}", progress.label(), self.old_window.title),
+                        None => self.old_window.title.clone(),
+                    };
+                    browser_window()?.document().ok_or("The page has no document".to_string())?.set_title(&title);
+                }
+                return Ok(());
+            }
+            UiMessage::RequestAttention(Attention::Cancel) => return Ok(()),
+            UiMessage::RequestAttention(_) => return Err("Pages can't flash their tab".to_string()),
+            UiMessage::ShowAbout(info) => {
+                return Err(format!("The web engine has no About dialog for {}", info.name));
+            }
+            UiMessage::ShowSettings(_) => return Err("The web engine has no settings dialog".to_string()),
+            UiMessage::ShowFileChooser(file_chooser) => {
+                return Err(format!("The web engine has no FileChooser for {}", file_chooser.title));
+            }
+            UiMessage::ArgumentsForwarded(forwarded) => {
+                (forwarded.on_arguments)(&forwarded.arguments);
+                return Ok(());
+            }
+            UiMessage::PlayMacro(recorded) => return self.play_macro(&recorded),
+            UiMessage::StartTour(tour) => {
+                self.start_tour(tour);
+                return Ok(());
+            }
+            #[cfg(feature = "remote-debug")]
+            UiMessage::RemoteDebug(request) => {
+                let answer = self.answer_remote(&request.command);
+                request.reply(answer);
+                return Ok(());
+            }
+        }
+        self.rebuild();
+        Ok(())
+    }
+
+    #[cfg(feature = "remote-debug")]
+    fn answer_remote(&mut self, command: &RemoteCommand) -> String {
+        match command {
+            RemoteCommand::Tree => format!("{:#?}", self.old_window),
+            RemoteCommand::Layout => self.components.iter()
+                .map(|b| format!("{} bounds: {:?} hit areas: {:?}", b._name, b.bounds(), b.hit_areas))
+                .collect::<Vec<String>>().join("\n"),
+            RemoteCommand::Events => remote_debug::events().join("\n"),
+            RemoteCommand::Diff => "The web engine doesn't capture frames".to_string(),
+            RemoteCommand::Warnings => self.layout_warnings.iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<String>>().join("\n"),
+            RemoteCommand::Set { path, value } => {
+                match remote_debug::set_property(&mut self.old_window, path, value) {
+                    Ok(()) => {
+                        self.rebuild();
+                        "OK".to_string()
+                    }
+                    Err(e) => e,
+                }
+            }
+        }
+    }
+
+    fn status_bar_mut(&mut self) -> Result<&mut StatusBar, String> {
+        self.old_window.status_bar.as_mut().ok_or("Window has no status bar".to_string())
+    }
+
+    /// Applies the messages posted to the ui_channel, follows the size of the canvas and expires
+    /// the status message, then runs the incremental jobs. main_loop() calls it every frame with now()
+    pub fn update(&mut self, now: f64) {
+        let start = self::now();
+        for message in ui_channel::drain() {
+            if let Err(e) = self.handle_message(message) {
+                log(&format!("Failed to apply a UI message {}", e));
+            }
+        }
+        let mut changed = false;
+        // The canvas is laid out by the page, its drawing buffer follows it
+        let (width, height) = (self.canvas.client_width().max(1) as u32,
+                               self.canvas.client_height().max(1) as u32);
+        if (width, height) != (self.canvas.width(), self.canvas.height()) {
+            self.canvas.set_width(width);
+            self.canvas.set_height(height);
+            changed = true;
+        }
+        if self.status_message.as_ref().is_some_and(|(_, until)| *until <= now) {
+            self.status_message = None;
+            changed = true;
+        }
+        let generation = accessibility::generation();
+        if generation != self.accessibility_generation {
+            self.accessibility_generation = generation;
+            changed = true;
+        }
+        let elapsed = self.last_update.map_or(0., |last| (now - last).max(0.));
+        self.last_update = Some(now);
+        let elapsed = Duration::from_secs_f64(elapsed / 1000.);
+        timers::advance(elapsed);
+        // The conditions of If and the keys of ForEach may depend on anything the app changed
+        if self.old_window.container.as_mut().is_some_and(|c| c.expand() | c.animate(elapsed)) {
+            changed = true;
+        }
+        // A Deferred per frame, after the first one so the page shows up with its placeholders
+        if !elapsed.is_zero() && self.old_window.container.as_mut().is_some_and(|c| c.build_next_deferred()) {
+            changed = true;
+        }
+        if changed {
+            self.rebuild();
+        }
+        incremental::run(|| self::now() - start < WORK_BUDGET);
+    }
+
+    /// Gives the click to the topmost body with a hit area under the pointer
+    pub fn handle_click(&mut self, x: i32, y: i32) -> bool {
+        let (x_f, y_f) = (x as f32, y as f32);
+        let hit = self.components.iter().rev()
+            .find_map(|b| b.overlay_hit_area(x_f, y_f).map(|a| (b._name.clone(), a.path.clone())))
+            .or_else(|| self.components.iter().rev()
+                .find_map(|b| b.hit_test(x_f, y_f).map(|path| (b._name.clone(), path.to_vec()))));
+        // Popups close when clicking anywhere but in them or the component they belong to
+        let keep_popup = match &hit {
+            Some((name, path)) if name == "Container" => path.clone(),
+            _ => vec![],
+        };
+        let in_tool_bar = matches!(&hit, Some((name, _)) if name == "ToolBar");
+        let event = Event { source: "Container".to_string(), position: Some((x, y)) };
+        let consumed = match hit {
+            Some((name, path)) => match (name.as_str(), path.as_slice()) {
+                ("StatusBar", [section]) => match &self.old_window.status_bar {
+                    Some(status_bar) => status_bar.click_section(*section, (x, y)),
+                    None => false,
+                },
+                ("MainMenu", _) => match self.old_window.menu.as_mut() {
+                    Some(menu) => menu.handle_click(&path, event),
+                    None => false,
+                },
+                ("ToolBar", _) => match self.old_window.tool_bar.as_mut() {
+                    // Without a mouseup the button is let go right away
+                    Some(tool_bar) => match path.as_slice() {
+                        [ToolBar::ITEMS, index] => tool_bar.press(*index, Some((x, y))) && tool_bar.release(),
+                        [ToolBar::OVERFLOW, index] => tool_bar.choose_overflow(*index, Some((x, y))),
+                        [ToolBar::OVERFLOW] => {
+                            tool_bar.toggle_overflow();
+                            true
+                        }
+                        _ => false,
+                    },
+                    None => false,
+                },
+                ("Tour", [TOUR_NEXT]) => {
+                    self.next_tour_step();
+                    true
+                }
+                ("Tour", [TOUR_SKIP]) => {
+                    self.skip_tour();
+                    true
+                }
+                // Only the highlighted widget can be used meanwhile
+                ("Tour", _) => true,
+                ("Container", _) => {
+                    self.record_click(&path, (x, y));
+                    let in_tour_target = self.in_tour_target(&path);
+                    let consumed = self.click_container(&path, event);
+                    if in_tour_target {
+                        self.next_tour_step();
+                    }
+                    consumed
+                }
+                _ => false,
+            },
+            // Clicking anywhere else closes the menu
+            None => match self.old_window.menu.as_mut() {
+                Some(menu) if menu.menu.open => {
+                    menu.menu.close_all();
+                    true
+                }
+                _ => false,
+            },
+        };
+        let mut closed = self.old_window.container.as_mut().is_some_and(|c| c.close_popups(&keep_popup));
+        if !in_tool_bar {
+            closed |= self.old_window.tool_bar.as_mut().is_some_and(|t| std::mem::replace(&mut t.overflow_open, false));
+        }
+        if consumed || closed {
+            self.rebuild();
+        }
+        consumed
+    }
+
+    /// Scrolls the ListView, the TableView or the innermost ScrollPane or TextArea under the
+    /// pointer by the wheel deltas, which are in pixels. A Spinner under it steps instead. Only
+    /// TableViews and ScrollPanes scroll sideways
+    pub fn handle_wheel(&mut self, x: i32, y: i32, dx: f32, dy: f32) -> bool {
+        let path = self.components.iter().find(|b| b._name == "Container")
+            .and_then(|b| b.hit_test(x as f32, y as f32))
+            .map(|path| path.to_vec());
+        let (path, container) = match (path, self.old_window.container.as_mut()) {
+            (Some(path), Some(container)) => (path, container),
+            _ => return false,
+        };
+        let spinner_path = [path.len(), path.len().saturating_sub(1)].into_iter()
+            .map(|len| path[..len].to_vec())
+            .find(|p| !p.is_empty() && container.child_at_mut(p).is_some_and(|c| c.is::<Spinner>()));
+        if let Some(spinner_path) = spinner_path {
+            let stepped = dy != 0. && container.child_at_mut(&spinner_path)
+                .and_then(|c| c.downcast_mut::<Spinner>())
+                .is_some_and(|s| s.step_by(-dy.signum() as i32, None));
+            if stepped {
+                self.rebuild();
+            }
+            return stepped;
+        }
+        let list_path = [path.len(), path.len().saturating_sub(1)].into_iter()
+            .map(|len| path[..len].to_vec())
+            .find(|p| !p.is_empty() && container.child_at_mut(p).is_some_and(|c| c.is::<ListView>()));
+        let table_path = (1..=path.len()).rev()
+            .map(|len| path[..len].to_vec())
+            .find(|p| container.child_at_mut(p)
+                .is_some_and(|c| c.is::<TableView>() || c.is::<TreeTable>() || c.is::<Console>()));
+        let scrolled = match (list_path, table_path) {
+            (Some(list_path), _) => container.child_at_mut(&list_path)
+                .and_then(|c| c.downcast_mut::<ListView>())
+                .is_some_and(|l| l.scroll_by(dy.signum() as i32)),
+            (None, Some(table_path)) => match container.child_at_mut(&table_path) {
+                Some(console) if console.is::<Console>() => {
+                    console.downcast_mut::<Console>().is_some_and(|c| c.scroll_by(dy.signum() as i32))
+                }
+                Some(tree) if tree.is::<TreeTable>() => {
+                    tree.downcast_mut::<TreeTable>().is_some_and(|t| t.scroll_by(dy.signum() as i32))
+                }
+                table => table.and_then(|c| c.downcast_mut::<TableView>())
+                    .is_some_and(|t| t.scroll_by(dy.signum() as i32) | t.scroll_x_by(dx)),
+            },
+            (None, None) => {
+                let pane_path = (1..=path.len()).rev()
+                    .map(|len| path[..len].to_vec())
+                    .find(|p| container.child_at_mut(p)
+                        .is_some_and(|c| c.is::<ScrollPane>() || c.is::<TextArea>()));
+                match pane_path.and_then(|p| container.child_at_mut(&p)) {
+                    Some(pane) if pane.is::<TextArea>() => {
+                        pane.downcast_mut::<TextArea>().is_some_and(|t| t.scroll_by(dy))
+                    }
+                    Some(pane) => pane.downcast_mut::<ScrollPane>().is_some_and(|p| p.scroll_by(dx, dy)),
+                    None => false,
+                }
+            }
+        };
+        if scrolled {
+            self.rebuild();
+        }
+        scrolled
+    }
+
+    /// Where the container laid out the component at path, in page coordinates
+    fn layout_box(&self, path: &[usize]) -> Option<LayoutBox> {
+        self.components.iter().find(|b| b._name == "Container")?
+            .layout_boxes().into_iter().find(|b| b.path == path)
+    }
+
+    /// Adds the click on the component at path to the macro being recorded, if any
+    fn record_click(&self, path: &[usize], position: (i32, i32)) {
+        if !ui_macros::is_recording() {
+            return;
+        }
+        if let Some((widget, part)) = WidgetId::of(self.accessible_nodes.as_deref().unwrap_or_default(), path) {
+            let origin = self.layout_box(&widget.path).map_or((0, 0), |b| (b.x as i32, b.y as i32));
+            let offset = (position.0 - origin.0, position.1 - origin.1);
+            ui_macros::record(MacroStep::Click { widget, part, offset });
+        }
+    }
+
+    /// Replays the clicks of the macro, see ui_macros::play(). The page has no keyboard focus to
+    /// type in yet
+    fn play_macro(&mut self, recorded: &Macro) -> Result<(), String> {
+        for step in &recorded.steps {
+            let (part, offset) = match step {
+                MacroStep::Click { part, offset, .. } => (part, offset),
+                _ => return Err(format!("The macro {} types, pages have no keyboard focus", recorded.name)),
+            };
+            let path = step.widget().resolve(self.accessible_nodes.as_deref().unwrap_or_default())
+                .ok_or(format!("The macro {} found no {:?}", recorded.name, step.widget()))?;
+            let origin = self.layout_box(&path).map_or((0, 0), |b| (b.x as i32, b.y as i32));
+            let event = Event {
+                source: "Container".to_string(),
+                position: Some((origin.0 + offset.0, origin.1 + offset.1)),
+            };
+            if self.click_container(&[path, part.clone()].concat(), event) {
+                self.rebuild();
+            }
+        }
+        Ok(())
+    }
+
+    /// Clicking a Slider or the scrollbar of a ScrollPane or TableView moves its thumb there and
+    /// clicking a ColorPicker picks the color there, there is no dragging on the web yet
+    fn click_container(&mut self, path: &[usize], event: Event) -> bool {
+        let position = event.position.unwrap_or_default();
+        let area = self.components.iter().find(|b| b._name == "Container")
+            .and_then(|b| b.hit_area(position.0 as f32, position.1 as f32))
+            .cloned();
+        let container = match self.old_window.container.as_mut() {
+            Some(container) => container,
+            None => return false,
+        };
+        let table = match path.split_last() {
+            Some((&TableView::HORIZONTAL_SCROLLBAR, table_path)) => container.child_at_mut(table_path)
+                .and_then(|c| c.downcast_mut::<TableView>()),
+            _ => None,
+        };
+        if let (Some(table), Some(area)) = (table, &area) {
+            let thumb = table.horizontal_thumb().map_or(0., |(_, l)| l) * area.w;
+            return table.set_scroll_x_fraction((position.0 as f32 - area.x - thumb / 2.) / (area.w - thumb).max(1.));
+        }
+        let scrollbar = match path.split_last() {
+            Some((&ScrollPane::VERTICAL_SCROLLBAR, pane_path)) => Some((true, pane_path)),
+            Some((&ScrollPane::HORIZONTAL_SCROLLBAR, pane_path)) => Some((false, pane_path)),
+            _ => None,
+        };
+        let pane = scrollbar.and_then(|(vertical, pane_path)| {
+            container.child_at_mut(pane_path)?.downcast_mut::<ScrollPane>().map(|p| (vertical, p))
+        });
+        if let (Some((vertical, pane)), Some(area)) = (pane, &area) {
+            let (start, length, pointer) = match vertical {
+                true => (area.y, area.h, position.1 as f32),
+                false => (area.x, area.w, position.0 as f32),
+            };
+            let thumb = pane.thumb(vertical).map_or(0., |(_, l)| l) * length;
+            return pane.set_scroll_fraction(vertical, (pointer - start - thumb / 2.) / (length - thumb).max(1.));
+        }
+        let picker = path.split_last().and_then(|(part, picker_path)| {
+            container.child_at_mut(picker_path)?.downcast_mut::<ColorPicker>().map(|p| (*part, p))
+        });
+        if let (Some((part, picker)), Some(area)) = (picker, &area) {
+            let x = ((position.0 as f32 - area.x) / area.w.max(1.)).clamp(0., 1.);
+            let y = ((position.1 as f32 - area.y) / area.h.max(1.)).clamp(0., 1.);
+            return match part {
+                ColorPicker::SQUARE => picker.set_hsv(picker.hue, x, 1. - y, event.position),
+                _ => picker.set_hsv(y * 360., picker.saturation, picker.value, event.position),
+            };
+        }
+        let slider = container.child_at_mut(path).and_then(|c| c.downcast_mut::<Slider>());
+        match (slider, area) {
+            (Some(slider), Some(area)) => {
+                let radius = text_size() / 2.;
+                let fraction = (position.0 as f32 - area.x - radius) / (area.w - 2. * radius).max(1.);
+                slider.set_fraction(fraction.clamp(0., 1.), event.position)
+            }
+            _ => container.handle_click(path, event),
+        }
+    }
+
+    /// Ctrl combinations run the registered command with that shortcut, then Ctrl and Alt
+    /// combinations and function keys activate the menu item with that accelerator. Escape closes
+    /// the menu. Otherwise Ctrl+= and Ctrl+- zoom the UI instead of the page and Ctrl+0 resets the
+    /// zoom
+    pub fn handle_key(&mut self, event: &KeyboardEvent) -> bool {
+        if event.key() == "Escape" && self.tour.is_some() {
+            self.skip_tour();
+            return true;
+        }
+        if event.key() == "Escape" {
+            return match self.old_window.menu.as_mut() {
+                Some(menu) if menu.menu.open => {
+                    menu.menu.close_all();
+                    self.rebuild();
+                    true
+                }
+                _ => false,
+            };
+        }
+        let key = event.key();
+        let function_key = key.len() > 1 && key.starts_with('F') && key[1..].chars().all(|c| c.is_ascii_digit());
+        if !event.ctrl_key() && !event.alt_key() && !function_key {
+            return false;
+        }
+        let name = shortcut_name(event);
+        if let Some(command) = commands::find_by_shortcut(&name).filter(|_| event.ctrl_key()) {
+            if let Err(e) = commands::execute(&command.id) {
+                log(&format!("Failed to execute the command {}", e));
+            }
+            return true;
+        }
+        if self.old_window.menu.as_mut().is_some_and(|m| m.activate_by_accelerator(&name)) {
+            self.rebuild();
+            return true;
+        }
+        if !event.ctrl_key() {
+            return false;
+        }
+        match key.as_str() {
+            "=" | "+" => accessibility::zoom_in(),
+            "-" => accessibility::zoom_out(),
+            "0" => accessibility::reset_zoom(),
+            _ => false,
+        }
+    }
+}
+
+// MainMenu ****************************************************************************************
+
+const WINDOW_BACKGROUND: &str = "rgb(0, 0, 0)";
+const TEXT_COLOR: &str = "rgb(230, 230, 230)";
+const DISABLED_TEXT_COLOR: &str = "rgb(120, 120, 120)";
+const MENU_BACKGROUND: &str = "rgb(50, 50, 55)";
+const MENU_PADDING: f32 = 4.;
+const MENU_GUTTER: f32 = 24.;
+const MENU_ACCELERATOR_GAP: f32 = 32.;
+
+impl WebComponent for MainMenu {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "MainMenu");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let bar_height = size + 2. * MENU_PADDING;
+        let (title, _) = parse_mnemonic(&self.menu.title);
+        let mut body = WebBody::new("MainMenu");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: bar_height, color: MENU_BACKGROUND });
+        body.push(WebShape::Text { text: title.clone(), x: MENU_PADDING, y: MENU_PADDING, size,
+                                   color: TEXT_COLOR.into() });
+        body.add_hit_area(0., 0., measure_text(&title, size).0 + 2. * MENU_PADDING, bar_height,
+                          vec![]);
+        if self.menu.open {
+            let mut dropdown = build_dropdown(&self.menu, size);
+            dropdown.translate(0., bar_height);
+            body.append(dropdown);
+        }
+        body
+    }
+}
+
+/// The entries of an open menu, only its items react to clicks since nested menus aren't opened
+/// by the web engine yet
+fn build_dropdown(menu: &Menu, size: f32) -> WebBody {
+    let row_height = size + 2. * MENU_PADDING;
+    let titles: Vec<String> = menu.children.iter().map(|c| parse_mnemonic(c.title()).0).collect();
+    let accelerator = |child: &Submenu| match child {
+        Submenu::MenuItem(item) => item.accelerator.clone(),
+        Submenu::Menu(_) => None,
+    };
+    let title_width = titles.iter().map(|t| measure_text(t, size).0).fold(0., f32::max);
+    let accelerator_width = menu.children.iter()
+        .filter_map(|c| accelerator(c).map(|a| measure_text(&a, size).0 + MENU_ACCELERATOR_GAP))
+        .fold(0., f32::max);
+    let width = MENU_GUTTER + title_width + accelerator_width + 2. * MENU_PADDING;
+    let mut body = WebBody::new("Dropdown");
+    body.push(WebShape::Rect { x: 0., y: 0., w: width, h: row_height * titles.len() as f32,
+                               color: MENU_BACKGROUND });
+    for (i, (child, title)) in menu.children.iter().zip(titles).enumerate() {
+        let y = i as f32 * row_height;
+        let color = match child {
+            Submenu::MenuItem(item) if item.enabled => TEXT_COLOR,
+            _ => DISABLED_TEXT_COLOR,
+        };
+        if let Submenu::MenuItem(MenuItem { checked: Some(true), .. }) = child {
+            body.push(checkmark_shape(MENU_PADDING, y + row_height / 2., size, color));
+        }
+        body.push(WebShape::Text { text: title, x: MENU_GUTTER, y: y + MENU_PADDING, size,
+                                   color: color.into() });
+        if let Some(accelerator) = accelerator(child) {
+            let x = width - MENU_PADDING - measure_text(&accelerator, size).0;
+            body.push(WebShape::Text { text: accelerator, x, y: y + MENU_PADDING, size,
+                                       color: color.into() });
+        }
+        body.add_hit_area(0., y, width, row_height, vec![i]);
+    }
+    body
+}
+
+impl Component for MainMenu {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    /// The title toggles the menu, an enabled item is activated (see MainMenu::click())
+    fn handle_click(&mut self, path: &[usize], _event: Event) -> bool {
+        // Nested menus aren't drawn, so they aren't opened either
+        if let [index] = path {
+            if matches!(self.menu.children.get(*index), Some(Submenu::Menu(_))) {
+                return true;
+            }
+        }
+        self.click(path);
+        // Clicks on disabled items or nested menus are eaten so the menu stays open
+        true
+    }
+}
+
+// StatusBar ***************************************************************************************
+
+const STATUS_SEPARATOR_COLOR: &str = "rgb(90, 90, 95)";
+const PROGRESS_TRACK_COLOR: &str = "rgb(30, 30, 30)";
+const PROGRESS_FILL_COLOR: &str = "rgb(70, 130, 200)";
+const STATUS_PROGRESS_WIDTH: f32 = 100.;
+
+impl WebComponent for StatusBar {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "StatusBar");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let height = size + 2. * MENU_PADDING;
+        let mut body = WebBody::new("StatusBar");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: MENU_BACKGROUND });
+        body.push(WebShape::Text { text: self.current_text().to_string(), x: MENU_PADDING,
+                                   y: MENU_PADDING, size, color: TEXT_COLOR.into() });
+        // Sections are laid out from the right edge, the first one is the leftmost
+        let mut x = width;
+        for (i, section) in self.sections.iter().enumerate().rev() {
+            let section_width = measure_text(&section.text, size).0 + 2. * MENU_PADDING;
+            x -= section_width;
+            body.push(WebShape::Rect { x, y: MENU_PADDING, w: 1., h: size, color: STATUS_SEPARATOR_COLOR });
+            body.push(WebShape::Text { text: section.text.clone(), x: x + MENU_PADDING,
+                                       y: MENU_PADDING, size, color: TEXT_COLOR.into() });
+            body.add_hit_area(x, 0., section_width, height, vec![i]);
+        }
+        if let Some(progress) = self.progress {
+            x -= STATUS_PROGRESS_WIDTH + 2. * MENU_PADDING;
+            let (track_y, track_height) = (height / 4., height / 2.);
+            body.push(WebShape::Rect { x: x + MENU_PADDING, y: track_y, w: STATUS_PROGRESS_WIDTH,
+                                       h: track_height, color: PROGRESS_TRACK_COLOR });
+            body.push(WebShape::Rect { x: x + MENU_PADDING, y: track_y,
+                                       w: STATUS_PROGRESS_WIDTH * progress.clamp(0., 1.),
+                                       h: track_height, color: PROGRESS_FILL_COLOR });
+        }
+        body
+    }
+}
+
+/// Builds the status bar docked at the bottom of the parent
+fn build_docked_status_bar(status_bar: &StatusBar, parent: &dyn Component) -> WebBody {
+    let (_, parent_height) = parent_size(parent);
+    let mut body = status_bar.build(parent);
+    body.translate(0., parent_height - (text_size() + 2. * MENU_PADDING));
+    body
+}
+
+impl Component for StatusBar {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// ToolBar *****************************************************************************************
+
+/// Space around the buttons of the ToolBar, see engines::sdl
+const TOOL_BAR_PADDING: f32 = 2.;
+const TOOL_SEPARATOR_WIDTH: f32 = 2. * MENU_PADDING + 1.;
+
+fn tool_bar_height() -> f32 {
+    text_size() + 2. * MENU_PADDING + 2. * TOOL_BAR_PADDING
+}
+
+fn tool_item_width(item: &ToolItem, size: f32) -> f32 {
+    match item {
+        ToolItem::Button(button) => measure_text(&button.label(), size).0 + 2. * MENU_PADDING,
+        ToolItem::Separator => TOOL_SEPARATOR_WIDTH,
+    }
+}
+
+impl WebComponent for ToolBar {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ToolBar");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let height = tool_bar_height();
+        let button_height = height - 2. * TOOL_BAR_PADDING;
+        let mut body = WebBody::new("ToolBar");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: MENU_BACKGROUND });
+        body.push(WebShape::Rect { x: 0., y: height - 1., w: width, h: 1., color: STATUS_SEPARATOR_COLOR });
+        let widths: Vec<f32> = self.items.iter().map(|i| tool_item_width(i, size)).collect();
+        let overflow_width = button_height;
+        let shown = self.shown_items(ToolBar::fitting_items(&widths, width - TOOL_BAR_PADDING, overflow_width));
+        let mut x = TOOL_BAR_PADDING;
+        for (i, item) in self.items.iter().enumerate().take(shown) {
+            match item {
+                ToolItem::Button(button) => {
+                    // Without a mouseup only the checked toggle buttons look pressed
+                    if button.checked == Some(true) {
+                        body.push(WebShape::Rect { x, y: TOOL_BAR_PADDING, w: widths[i], h: button_height,
+                                                   color: BUTTON_PRESSED_BACKGROUND });
+                        push_forced_border(&mut body, x, TOOL_BAR_PADDING, widths[i], button_height);
+                    }
+                    let color = if button.enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+                    body.push(WebShape::Text { text: button.label(), x: x + MENU_PADDING,
+                                               y: TOOL_BAR_PADDING + MENU_PADDING, size, color: color.into() });
+                    body.add_hit_area(x, TOOL_BAR_PADDING, widths[i], button_height, vec![ToolBar::ITEMS, i]);
+                }
+                ToolItem::Separator => {
+                    body.push(WebShape::Rect { x: x + MENU_PADDING, y: TOOL_BAR_PADDING + MENU_PADDING, w: 1.,
+                                               h: button_height - 2. * MENU_PADDING, color: STATUS_SEPARATOR_COLOR });
+                }
+            }
+            x += widths[i];
+        }
+        if shown < self.items.len() {
+            let overflow_x = width - TOOL_BAR_PADDING - overflow_width;
+            if self.overflow_open {
+                body.push(WebShape::Rect { x: overflow_x, y: TOOL_BAR_PADDING, w: overflow_width, h: button_height,
+                                           color: BUTTON_PRESSED_BACKGROUND });
+            }
+            let (mid, half) = (overflow_x + overflow_width / 2., overflow_width / 5.);
+            let arrow_y = TOOL_BAR_PADDING + button_height / 2.;
+            body.push(WebShape::Lines {
+                points: vec![(mid - half, arrow_y - half / 2.), (mid, arrow_y + half / 2.),
+                             (mid + half, arrow_y - half / 2.)],
+                thickness: 2.,
+                color: TEXT_COLOR.into(),
+            });
+            body.add_hit_area(overflow_x, TOOL_BAR_PADDING, overflow_width, button_height, vec![ToolBar::OVERFLOW]);
+            if self.overflow_open {
+                body.push_overlay(build_tool_bar_overflow(self, shown, size, width, height));
+            }
+        }
+        body
+    }
+}
+
+/// The items from shown on in a list under the right end of the ToolBar
+fn build_tool_bar_overflow(tool_bar: &ToolBar, shown: usize, size: f32, width: f32, top: f32) -> WebBody {
+    let row_height = size + 2. * MENU_PADDING;
+    let hidden = &tool_bar.items[shown..];
+    let menu_width = hidden.iter()
+        .map(|item| match item {
+            ToolItem::Button(button) => measure_text(&button.title, size).0,
+            ToolItem::Separator => 0.,
+        })
+        .fold(0., f32::max) + MENU_GUTTER + MENU_PADDING;
+    let x = width - menu_width;
+    let mut body = WebBody::new("ToolBarOverflow");
+    body.push(WebShape::Rect { x, y: top, w: menu_width, h: row_height * hidden.len() as f32,
+                               color: MENU_BACKGROUND });
+    for (i, item) in hidden.iter().enumerate() {
+        let y = top + i as f32 * row_height;
+        match item {
+            ToolItem::Button(button) => {
+                let color = if button.enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+                match (button.checked, &button.icon) {
+                    (Some(true), _) => {
+                        body.push(checkmark_shape(x + MENU_GUTTER / 4., y + row_height / 2., size, color));
+                    }
+                    (_, Some(icon)) => body.push(WebShape::Text { text: icon.clone(), x: x + MENU_PADDING,
+                                                                  y: y + MENU_PADDING, size, color: color.into() }),
+                    _ => {}
+                }
+                body.push(WebShape::Text { text: button.title.clone(), x: x + MENU_GUTTER, y: y + MENU_PADDING, size,
+                                           color: color.into() });
+                body.add_hit_area(x, y, menu_width, row_height, vec![ToolBar::OVERFLOW, shown + i]);
+            }
+            ToolItem::Separator => {
+                body.push(WebShape::Rect { x: x + MENU_PADDING, y: y + row_height / 2.,
+                                           w: menu_width - 2. * MENU_PADDING, h: 1., color: STATUS_SEPARATOR_COLOR });
+            }
+        }
+    }
+    push_forced_border(&mut body, x, top, menu_width, row_height * hidden.len() as f32);
+    body
+}
+
+/// Builds the tool bar docked under the menu bar of the window
+fn build_docked_tool_bar(window: &Window, tool_bar: &ToolBar, parent: &dyn Component) -> WebBody {
+    let mut body = tool_bar.build(parent);
+    if window.menu.is_some() {
+        body.translate(0., text_size() + 2. * MENU_PADDING);
+    }
+    body
+}
+
+impl Component for ToolBar {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Tour ********************************************************************************************
+
+const TOUR_DIM_COLOR: &str = "rgba(0, 0, 0, 0.6)";
+const TOUR_HIGHLIGHT_COLOR: &str = "rgb(255, 200, 80)";
+const TOUR_BUBBLE_WIDTH: f32 = 320.;
+/// Space between the highlighted widget and its outline
+const TOUR_HIGHLIGHT_PADDING: f32 = 4.;
+const TOUR_HIGHLIGHT_WIDTH: f32 = 2.;
+const TOUR_NEXT: usize = 0;
+const TOUR_SKIP: usize = 1;
+const TOUR_DIMMED: usize = 2;
+const TOUR_BUBBLE: usize = 3;
+
+/// The step of the tour over a page of size, like the SDL engine shows it: the page dimmed but for
+/// the highlighted area and the bubble explaining it with its buttons at [TOUR_NEXT] and [TOUR_SKIP]
+fn build_tour_step(tour: &Tour, step: &TourStep, highlight: Option<(f32, f32, f32, f32)>,
+                   (width, height): (f32, f32)) -> WebBody {
+    let _span = trace_span!("build", component = "Tour");
+    let mut body = WebBody::new("Tour");
+    for (x, y, w, h) in tour::dimmed_areas(highlight, (width, height)) {
+        body.push(WebShape::Rect { x, y, w, h, color: TOUR_DIM_COLOR });
+        body.add_hit_area(x, y, w, h, vec![TOUR_DIMMED]);
+    }
+    if let Some((x, y, w, h)) = highlight {
+        body.push(WebShape::Outline { x, y, w, h, thickness: TOUR_HIGHLIGHT_WIDTH, color: TOUR_HIGHLIGHT_COLOR });
+    }
+    let size = text_size();
+    let line_height = size + 2. * MENU_PADDING;
+    let padding = 4. * MENU_PADDING;
+    let bubble_width = TOUR_BUBBLE_WIDTH.min(width - 2. * MENU_PADDING).max(0.);
+    let lines = wrap_lines(&step.text, bubble_width - 2. * padding, &|line| measure_text(line, size).0);
+    let bubble_height = (lines.len() + 2) as f32 * line_height + 3. * padding;
+    let (bubble_x, bubble_y) = tour::bubble_position(highlight, (bubble_width, bubble_height), (width, height));
+    body.push(WebShape::Rect { x: bubble_x, y: bubble_y, w: bubble_width, h: bubble_height, color: MENU_BACKGROUND });
+    body.push(WebShape::Outline { x: bubble_x, y: bubble_y, w: bubble_width, h: bubble_height, thickness: 1.,
+                                  color: TEXT_COLOR });
+    body.add_hit_area(bubble_x, bubble_y, bubble_width, bubble_height, vec![TOUR_BUBBLE]);
+    let x = bubble_x + padding;
+    let mut y = bubble_y + padding;
+    body.push(WebShape::Span { text: step.title.clone(), x, y: y + MENU_PADDING, size, style: FontStyle::Bold,
+                               color: TEXT_COLOR.into() });
+    for line in lines {
+        y += line_height;
+        body.push(WebShape::Text { text: line, x, y: y + MENU_PADDING, size, color: TEXT_COLOR.into() });
+    }
+    y += line_height + padding;
+    body.push(WebShape::Text { text: tour.progress(), x, y: y + MENU_PADDING, size,
+                               color: DISABLED_TEXT_COLOR.into() });
+    let mut button_x = bubble_x + bubble_width - padding;
+    let next = if tour.is_last() { "Done" } else { "Next" };
+    let buttons = [Some((next, TOUR_NEXT)), Some(("Skip", TOUR_SKIP)).filter(|_| !tour.is_last())];
+    for (title, index) in buttons.into_iter().flatten() {
+        let button_width = measure_text(title, size).0 + 2. * MENU_PADDING;
+        button_x -= button_width;
+        body.push(WebShape::Rect { x: button_x, y, w: button_width, h: line_height, color: BUTTON_BACKGROUND });
+        body.push(WebShape::Text { text: title.to_string(), x: button_x + MENU_PADDING, y: y + MENU_PADDING, size,
+                                   color: TEXT_COLOR.into() });
+        body.add_hit_area(button_x, y, button_width, line_height, vec![index]);
+        button_x -= MENU_PADDING;
+    }
+    body
+}
+
+// Container ***************************************************************************************
+
+const CONTAINER_PADDING: f32 = 8.;
+const CONTAINER_SPACING: f32 = 6.;
+
+impl WebComponent for Container {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Container");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let mut body = WebBody::new("Container");
+        for (i, child_body) in stacked_children(self, width, height).into_iter().enumerate() {
+            body.append_child(i, child_body);
+        }
+        body
+    }
+}
+
+/// The bodies of the children of container where it shows them, stacked vertically with each one
+/// taking the height of what it built
+fn stacked_children(container: &Container, width: f32, height: f32) -> Vec<WebBody> {
+    let inner = pseudo_parent(width - 2. * CONTAINER_PADDING, height - 2. * CONTAINER_PADDING);
+    let mut y = CONTAINER_PADDING;
+    container.children.iter().map(|child| {
+        let mut child_body = dyn_to_web_body(child.build_dyn(&inner));
+        let bounds = child_body.bounds();
+        let child_height = bounds.map(|(_, by, _, bh)| by + bh).unwrap_or(0.);
+        let (x, by, w, h) = bounds.unwrap_or_default();
+        // The path is empty, the container puts the index of the child in front
+        child_body.layout.insert(0, LayoutBox { path: vec![], name: child_body._name.clone(), x, y: by, w, h,
+                                                clipped: false });
+        child_body.translate(CONTAINER_PADDING, y);
+        y += child_height + CONTAINER_SPACING;
+        child_body
+    }).collect()
+}
+
+// Loadable ****************************************************************************************
+
+const SKELETON_COLOR: (u8, u8, u8) = (60, 60, 65);
+const SKELETON_SHINE_COLOR: (u8, u8, u8) = (95, 95, 100);
+
+/// Draws the blocks of skeleton with its shimmer, which sweeps across width
+fn push_skeleton_blocks(body: &mut WebBody, skeleton: &Skeleton, blocks: &[SkeletonBlock], width: f32) {
+    let color = |x: f32| {
+        let amount = skeleton.shine(x, width);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+        let ((r0, g0, b0), (r1, g1, b1)) = (SKELETON_COLOR, SKELETON_SHINE_COLOR);
+        format!("rgb({}, {}, {})", mix(r0, r1), mix(g0, g1), mix(b0, b1))
+    };
+    let (start, middle, end) = skeleton.shimmer(width);
+    for block in blocks.iter().filter(|b| b.w > 0.) {
+        // Stops where the shimmer changes direction, at fractions of the block
+        let mut stops = vec![(0., color(block.x)), (1., color(block.x + block.w))];
+        stops.extend([start, middle, end].into_iter()
+            .filter(|x| *x > block.x && *x < block.x + block.w)
+            .map(|x| ((x - block.x) / block.w, color(x))));
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        body.push(WebShape::Gradient { x: block.x, y: block.y, w: block.w, h: block.h, vertical: false, stops });
+    }
+}
+
+impl WebComponent for Skeleton {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Skeleton");
+        let (width, _) = parent_size(parent);
+        let mut body = WebBody::new("Skeleton");
+        push_skeleton_blocks(&mut body, self, &self.blocks, width);
+        body
+    }
+}
+
+impl Component for Skeleton {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+impl WebComponent for Loadable {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Loadable");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.content.width, parent_width);
+        let height = resolve_dimension(&self.content.height, parent_height);
+        let mut body = WebBody::new("Loadable");
+        if !self.loading {
+            body.append(self.content.build(parent));
+            return body;
+        }
+        // Without blocks the skeleton takes the shape of the content, which is built but not shown
+        let blocks: Vec<SkeletonBlock> = match self.skeleton.blocks.is_empty() {
+            true => stacked_children(&self.content, width, height).iter()
+                .filter_map(|child| child.bounds())
+                .map(|(x, y, w, h)| SkeletonBlock { x, y, w, h })
+                .collect(),
+            false => self.skeleton.blocks.clone(),
+        };
+        push_skeleton_blocks(&mut body, &self.skeleton, &blocks, width);
+        body
+    }
+}
+
+impl Component for Loadable {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Deferred ****************************************************************************************
+
+impl WebComponent for Deferred {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Deferred", ready = self.ready);
+        let mut body = WebBody::new("Deferred");
+        if self.ready {
+            body.append(self.content.build(parent));
+            return body;
+        }
+        // Building the content to take its shape would be the expensive build it defers
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.content.width, parent_width);
+        let blocks = match self.placeholder.blocks.is_empty() {
+            true => {
+                let height = resolve_dimension(&self.content.height, parent_height);
+                vec![SkeletonBlock { x: 0., y: 0., w: width, h: height }]
+            }
+            false => self.placeholder.blocks.clone(),
+        };
+        push_skeleton_blocks(&mut body, &self.placeholder, &blocks, width);
+        body
+    }
+}
+
+impl Component for Deferred {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Provider ****************************************************************************************
+
+impl WebComponent for Provider {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Provider");
+        let mut body = WebBody::new("Provider");
+        body.append(context::provide(&self.values, || self.content.build(parent)));
+        body
+    }
+}
+
+impl Component for Provider {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// If **********************************************************************************************
+
+impl WebComponent for If {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "If");
+        let mut body = WebBody::new("If");
+        body.append(self.active().build(parent));
+        body
+    }
+}
+
+impl Component for If {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// ForEach *****************************************************************************************
+
+impl WebComponent for ForEach {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ForEach");
+        let mut body = WebBody::new("ForEach");
+        body.append(self.content.build(parent));
+        body
+    }
+}
+
+impl Component for ForEach {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+/// What is drawn of the background over the color the canvas is filled with, None for a color
+fn build_window_background(background: &WindowBackground, parent: &dyn Component) -> Option<WebBody> {
+    let (w, h) = parent_size(parent);
+    let mut body = WebBody::new("WindowBackground");
+    match background {
+        WindowBackground::Color(_) => return None,
+        WindowBackground::Gradient { from, to, vertical } => {
+            let stops = vec![(0., css_color(*from)), (1., css_color(*to))];
+            body.push(WebShape::Gradient { x: 0., y: 0., w, h, vertical: *vertical, stops });
+        }
+        WindowBackground::Image { source: ImageSource::Path(src), scale_mode } => {
+            body.push(WebShape::Image { src: src.clone(), x: 0., y: 0., w, h, scale_mode: *scale_mode, radius: 0.,
+                                        background: None });
+        }
+        // Encoded bytes would need a blob URL, only paths are loaded on the web
+        WindowBackground::Image { source: ImageSource::Bytes(_), .. } => return None,
+        WindowBackground::Draw(draw) => push_canvas_polygons(&mut body, &draw(w, h)),
+    }
+    Some(body)
+}
+
+/// Builds the container of the window in the space between the menu and the status bar
+fn build_docked_container(window: &Window, parent: &dyn Component) -> Option<WebBody> {
+    let container = window.container.as_ref()?;
+    let (width, height) = parent_size(parent);
+    let bar_height = text_size() + 2. * MENU_PADDING;
+    let mut top = if window.menu.is_some() { bar_height } else { 0. };
+    if window.tool_bar.is_some() {
+        top += tool_bar_height();
+    }
+    let bottom = if window.status_bar.is_some() { bar_height } else { 0. };
+    let mut body = container.build(&pseudo_parent(width, height - top - bottom));
+    body.translate(0., top);
+    Some(body)
+}
+
+impl Component for Container {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.split_first() {
+            Some((index, rest)) => match self.children.get_mut(*index) {
+                Some(child) => child.handle_click(rest, event),
+                None => false,
+            },
+            None => false,
+        }
+    }
+}
+
+// Form ********************************************************************************************
+
+impl WebComponent for Form {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Form");
+        let content = self.content.build(parent);
+        let bottom = content.bounds().map(|(_, y, _, h)| y + h).unwrap_or(0.);
+        let mut submit = Button { title: self.submit_title.clone(), ..Default::default() }.build(parent);
+        submit.translate(CONTAINER_PADDING, bottom + CONTAINER_SPACING);
+        let mut body = WebBody::new("Form");
+        body.append_child(Form::CONTENT, content);
+        body.append_child(Form::SUBMIT, submit);
+        body
+    }
+}
+
+impl Component for Form {
+    fn get_height(&self) -> &Dimension {
+        &self.content.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.content.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    /// Clicks in the content go to it, the submit button submits
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.split_first() {
+            Some((&Form::CONTENT, rest)) => self.content.handle_click(rest, event),
+            Some((&Form::SUBMIT, _)) => {
+                self.submit(event.position);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// Button ******************************************************************************************
+
+const BUTTON_BACKGROUND: &str = "rgb(80, 80, 90)";
+const BUTTON_PRESSED_BACKGROUND: &str = "rgb(60, 60, 70)";
+
+impl WebComponent for Button {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Button");
+        let size = text_size();
+        let width = measure_text(&self.title, size).0 + 4. * MENU_PADDING;
+        let height = size + 2. * MENU_PADDING;
+        // There is no hovering on the web yet
+        let (background, color) = match self.state() {
+            ButtonState::Pressed => (BUTTON_PRESSED_BACKGROUND, TEXT_COLOR),
+            ButtonState::Disabled => (BUTTON_BACKGROUND, DISABLED_TEXT_COLOR),
+            _ => (BUTTON_BACKGROUND, TEXT_COLOR),
+        };
+        let mut body = WebBody::new("Button");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: background });
+        body.push(WebShape::Text { text: self.title.clone(), x: 2. * MENU_PADDING, y: MENU_PADDING,
+                                   size, color: color.into() });
+        push_forced_border(&mut body, 0., 0., width, height);
+        body.add_hit_area(0., 0., width, height, vec![]);
+        body
+    }
+}
+
+impl Component for Button {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, _path: &[usize], event: Event) -> bool {
+        self.press(event)
+    }
+}
+
+// TextField ***************************************************************************************
+
+const TEXT_FIELD_BACKGROUND: &str = "rgb(30, 30, 35)";
+const ERROR_COLOR: &str = "rgb(255, 110, 100)";
+const ERROR_TEXT_SCALE: f32 = 0.8;
+
+impl WebComponent for TextField {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "TextField");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let height = size + 2. * MENU_PADDING;
+        let mut body = WebBody::new("TextField");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: TEXT_FIELD_BACKGROUND });
+        let border = if self.is_valid() { STATUS_SEPARATOR_COLOR } else { ERROR_COLOR };
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1., color: border });
+        push_forced_border(&mut body, 0., 0., width, height);
+        body.push(WebShape::Text { text: self.text.clone(), x: MENU_PADDING, y: MENU_PADDING, size,
+                                   color: TEXT_COLOR.into() });
+        let error_size = size * ERROR_TEXT_SCALE;
+        for (i, error) in self.errors.iter().flatten().enumerate() {
+            let y = height + LABEL_LINE_SPACING + (error_size + LABEL_LINE_SPACING) * i as f32;
+            body.push(WebShape::Text { text: error.clone(), x: MENU_PADDING, y, size: error_size,
+                                       color: ERROR_COLOR.into() });
+        }
+        body
+    }
+}
+
+impl Component for TextField {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// ChipInput ***************************************************************************************
+
+const CHIP_BACKGROUND: &str = "rgb(60, 65, 80)";
+const CHIP_GAP: f32 = 3.;
+
+fn chip_width(chip: &str, size: f32) -> f32 {
+    measure_text(chip, size).0 + 2. * MENU_PADDING + size
+}
+
+/// The web engine has no keyboard focus yet, so the chips can only be removed
+impl WebComponent for ChipInput {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ChipInput");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let places = self.layout(|chip| chip_width(chip, size), CHIP_GAP, width);
+        let rows = places.last().map_or(1, |(_, row)| row + 1);
+        let height = row_height * rows as f32;
+        let mut body = WebBody::new("ChipInput");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: TEXT_FIELD_BACKGROUND });
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                      color: STATUS_SEPARATOR_COLOR });
+        push_forced_border(&mut body, 0., 0., width, height);
+        for (i, (chip, (x, row))) in self.chips.iter().zip(&places).enumerate() {
+            let (w, y) = (chip_width(chip, size), row_height * *row as f32);
+            body.push(WebShape::Rect { x: *x, y: y + CHIP_GAP, w, h: row_height - 2. * CHIP_GAP,
+                                       color: CHIP_BACKGROUND });
+            body.push(WebShape::Text { text: chip.clone(), x: x + MENU_PADDING, y: y + MENU_PADDING, size,
+                                       color: TEXT_COLOR.into() });
+            let cross_x = x + w - size;
+            let (left, right) = (cross_x + size * 0.25, cross_x + size * 0.75);
+            let (top, bottom) = (y + MENU_PADDING + size * 0.25, y + MENU_PADDING + size * 0.75);
+            for points in [vec![(left, top), (right, bottom)], vec![(left, bottom), (right, top)]] {
+                body.push(WebShape::Lines { points, thickness: 1.5, color: TEXT_COLOR.into() });
+            }
+            body.add_hit_area(cross_x, y, size, row_height, vec![i]);
+        }
+        if let Some((x, row)) = places.last() {
+            body.push(WebShape::Text { text: self.text.clone(), x: *x, y: row_height * *row as f32 + MENU_PADDING,
+                                       size, color: TEXT_COLOR.into() });
+        }
+        body
+    }
+}
+
+impl Component for ChipInput {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(index) => self.remove(*index, event.position),
+            None => false,
+        }
+    }
+}
+
+// Label *******************************************************************************************
+
+const LABEL_LINE_SPACING: f32 = 2.;
+const CARET_WIDTH: f32 = 2.;
+
+/// The web engine has no keyboard focus yet, so caret browsing only shows a caret set by the app
+impl WebComponent for Label {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Label");
+        let (width, _) = parent_size(parent);
+        let size = self.size.map_or_else(text_size, |size| size * accessibility::font_scale());
+        let line_height = size + LABEL_LINE_SPACING;
+        let lines = wrap_lines(&self.text, width, &|line| measure_text(line, size).0);
+        let caret = self.caret.filter(|_| accessibility::caret_browsing());
+        let color: Cow<'static, str> = self.color.or_else(|| context::get::<TextColor>().map(|c| c.0))
+            .map_or(TEXT_COLOR.into(), |c| css_color(c).into());
+        let prefix_width = |line: &str, column: usize| {
+            let end = line.char_indices().nth(column).map_or(line.len(), |(i, _)| i);
+            measure_text(&line[..end], size).0
+        };
+        let offset = |line: &str| self.align.offset(measure_text(line, size).0, width);
+        let mut body = WebBody::new("Label");
+        for (i, line) in lines.iter().enumerate() {
+            let (x, y) = (offset(line), line_height * i as f32);
+            if let Some((from, to)) = caret.and_then(|c| c.selected_columns(i, &lines)) {
+                let (start, end) = (prefix_width(line, from), prefix_width(line, to));
+                body.push(WebShape::Rect { x: x + start, y, w: end - start, h: line_height,
+                                           color: SELECTED_BACKGROUND });
+            }
+            body.push(WebShape::Text { text: line.clone(), x, y, size, color: color.clone() });
+        }
+        if let Some(caret) = caret {
+            let TextPosition { line, column } = caret.clamped(&lines);
+            let x = lines.get(line).map_or(0., |l| offset(l) + prefix_width(l, column));
+            body.push(WebShape::Rect { x, y: line_height * line as f32, w: CARET_WIDTH, h: line_height,
+                                       color: TEXT_COLOR });
+        }
+        body.add_hit_area(0., 0., width, line_height * lines.len() as f32, vec![]);
+        *self.lines.borrow_mut() = lines;
+        body
+    }
+}
+
+impl Component for Label {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Hyperlink ***************************************************************************************
+
+const LINK_COLOR: &str = "rgb(100, 160, 255)";
+const VISITED_LINK_COLOR: &str = "rgb(170, 130, 230)";
+
+/// The page doesn't track the pointer yet, so it doesn't turn into a hand over it
+impl WebComponent for Hyperlink {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Hyperlink");
+        let size = text_size();
+        let color = if self.visited { VISITED_LINK_COLOR } else { LINK_COLOR };
+        let (width, _) = measure_text(&self.text, size);
+        let mut body = WebBody::new("Hyperlink");
+        body.push(WebShape::Text { text: self.text.clone(), x: 0., y: 0., size, color: color.into() });
+        body.push(WebShape::Rect { x: 0., y: size, w: width, h: (size / 12.).max(1.), color });
+        body.add_hit_area(0., 0., width, size + LABEL_LINE_SPACING, vec![]);
+        body
+    }
+}
+
+impl Component for Hyperlink {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, _path: &[usize], event: Event) -> bool {
+        if let Some(url) = self.activate(event.position) {
+            // In a new tab, the app stays where it is
+            let opened = browser_window().and_then(|w| {
+                w.open_with_url_and_target(url, "_blank").map_err(|e| format!("{:?}", e))
+            });
+            if let Err(e) = opened {
+                log(&format!("Failed to open {} {}", url, e));
+            }
+        }
+        true
+    }
+}
+
+// MarkdownView ************************************************************************************
+
+const CODE_BACKGROUND_COLOR: &str = "rgb(40, 40, 46)";
+const QUOTE_BAR_WIDTH: f32 = 3.;
+
+impl WebComponent for MarkdownView {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "MarkdownView");
+        let width = resolve_dimension(&self.width, parent_size(parent).0);
+        let measure = |text: &str, size: f32, style: FontStyle| measure_styled_text(text, size, style).0;
+        let (shapes, height) = markdown::layout(&self.document(), width, text_size(), &measure);
+        let mut body = WebBody::new("MarkdownView");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: CANVAS_BACKGROUND_COLOR });
+        for shape in shapes {
+            match shape {
+                MarkdownShape::Text { text, x, y, size, style, link: None } => {
+                    body.push(WebShape::Span { text, x, y, size, style, color: TEXT_COLOR.into() });
+                }
+                MarkdownShape::Text { text, x, y, size, style, link: Some(link) } => {
+                    let (w, _) = measure_styled_text(&text, size, style);
+                    body.push(WebShape::Span { text, x, y, size, style, color: LINK_COLOR.into() });
+                    body.push(WebShape::Rect { x, y: y + size, w, h: (size / 12.).max(1.), color: LINK_COLOR });
+                    body.add_hit_area(x, y, w, size + LABEL_LINE_SPACING, vec![link]);
+                }
+                MarkdownShape::CodeBackground { x, y, w, h } => {
+                    body.push(WebShape::Rect { x, y, w, h, color: CODE_BACKGROUND_COLOR });
+                }
+                MarkdownShape::QuoteBar { x, y, h } => {
+                    body.push(WebShape::Rect { x, y, w: QUOTE_BAR_WIDTH, h, color: CHECKBOX_BORDER_COLOR });
+                }
+                MarkdownShape::Rule { x, y, w } => {
+                    body.push(WebShape::Rect { x, y, w, h: 1., color: STATUS_SEPARATOR_COLOR });
+                }
+            }
+        }
+        body
+    }
+}
+
+impl Component for MarkdownView {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        if let Some(url) = path.first().and_then(|link| self.activate(*link, event.position)) {
+            // In a new tab, the app stays where it is
+            let opened = browser_window().and_then(|w| {
+                w.open_with_url_and_target(&url, "_blank").map_err(|e| format!("{:?}", e))
+            });
+            if let Err(e) = opened {
+                log(&format!("Failed to open {} {}", url, e));
+            }
+        }
+        false
+    }
+}
+
+// TextArea ****************************************************************************************
+
+/// The web engine has no keyboard focus yet, so it's only scrolled with the wheel
+impl WebComponent for TextArea {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "TextArea");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let size = text_size();
+        let line_height = size + LABEL_LINE_SPACING;
+        let text_height = |lines: &[String]| line_height * lines.len() as f32 + 2. * MENU_PADDING;
+        let wrap = |width: f32| {
+            wrap_lines(&self.text, width - 2. * MENU_PADDING, &|line| measure_text(line, size).0)
+        };
+        let mut lines = wrap(width);
+        let mut viewport = (width, height);
+        if text_height(&lines) > height {
+            viewport.0 = width - SCROLLBAR_SIZE;
+            lines = wrap(viewport.0);
+        }
+        self.extent.set(ScrollExtent { content: (viewport.0, text_height(&lines)), viewport });
+        let scroll = self.clamped_scroll();
+        let mut body = WebBody::new("TextArea");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: TEXT_FIELD_BACKGROUND });
+        let mut text = WebBody::new("TextArea text");
+        for (i, line) in lines.into_iter().enumerate() {
+            let y = MENU_PADDING + line_height * i as f32 - scroll;
+            if y + line_height > 0. && y < height {
+                text.push(WebShape::Text { text: line, x: MENU_PADDING, y, size, color: TEXT_COLOR.into() });
+            }
+        }
+        body.append_clipped_child(0, 0., 0., viewport.0, viewport.1, text);
+        if let Some((start, length)) = self.thumb() {
+            body.push(WebShape::Rect { x: viewport.0, y: 0., w: SCROLLBAR_SIZE, h: height,
+                                       color: SCROLLBAR_TRACK_COLOR });
+            body.push(WebShape::Rect { x: viewport.0, y: start * height, w: SCROLLBAR_SIZE, h: length * height,
+                                       color: SCROLLBAR_THUMB_COLOR });
+        }
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                      color: STATUS_SEPARATOR_COLOR });
+        push_forced_border(&mut body, 0., 0., width, height);
+        body.add_hit_area(0., 0., width, height, vec![]);
+        body
+    }
+}
+
+impl Component for TextArea {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Checkbox ****************************************************************************************
+
+const CHECKBOX_BORDER_COLOR: &str = "rgb(160, 160, 170)";
+const CHECKMARK_COLOR: &str = "rgb(255, 255, 255)";
+const SELECTED_BACKGROUND: &str = "rgb(70, 130, 200)";
+
+impl WebComponent for Checkbox {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Checkbox");
+        let size = text_size();
+        let mut body = WebBody::new("Checkbox");
+        if self.checked {
+            body.push(WebShape::Rect { x: 0., y: 0., w: size, h: size, color: SELECTED_BACKGROUND });
+            body.push(checkmark_shape(size * 0.2, size / 2., size, CHECKMARK_COLOR));
+        }
+        body.push(WebShape::Outline { x: 0., y: 0., w: size, h: size, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        let label_x = size + 2. * MENU_PADDING;
+        body.push(WebShape::Text { text: self.label.clone(), x: label_x, y: 0., size,
+                                   color: TEXT_COLOR.into() });
+        // The label toggles it too
+        body.add_hit_area(0., 0., label_x + measure_text(&self.label, size).0, size, vec![]);
+        body
+    }
+}
+
+impl Component for Checkbox {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, _path: &[usize], event: Event) -> bool {
+        // It changed whatever on_toggle says, so it has to be rebuilt
+        self.toggle(event.position);
+        true
+    }
+}
+
+// RadioGroup **************************************************************************************
+
+impl WebComponent for RadioButton {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "RadioButton");
+        let size = text_size();
+        let radius = size / 2.;
+        let mut body = WebBody::new("RadioButton");
+        body.push(WebShape::Circle { cx: radius, cy: radius, radius, color: CHECKBOX_BORDER_COLOR });
+        body.push(WebShape::Circle { cx: radius, cy: radius, radius: radius - 1., color: TEXT_FIELD_BACKGROUND });
+        if self.selected {
+            body.push(WebShape::Circle { cx: radius, cy: radius, radius: radius / 2., color: SELECTED_BACKGROUND });
+        }
+        let label_x = size + 2. * MENU_PADDING;
+        body.push(WebShape::Text { text: self.label.clone(), x: label_x, y: 0., size,
+                                   color: TEXT_COLOR.into() });
+        body.add_hit_area(0., 0., label_x + measure_text(&self.label, size).0, size, vec![]);
+        body
+    }
+}
+
+impl Component for RadioButton {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    /// Outside of a RadioGroup it can only be selected
+    fn handle_click(&mut self, _path: &[usize], _event: Event) -> bool {
+        !std::mem::replace(&mut self.selected, true)
+    }
+}
+
+impl WebComponent for RadioGroup {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "RadioGroup");
+        let mut body = WebBody::new("RadioGroup");
+        let mut y = 0.;
+        for (i, child) in self.children.iter().enumerate() {
+            let mut child_body = child.build(parent);
+            child_body.translate(0., y);
+            body.append_child(i, child_body);
+            y += text_size() + MENU_PADDING;
+        }
+        body
+    }
+}
+
+impl Component for RadioGroup {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(index) => self.select(*index, event.position),
+            None => false,
+        }
+    }
+}
+
+// Console *****************************************************************************************
+
+const CONSOLE_BACKGROUND: &str = "rgb(12, 12, 14)";
+/// Of the text without a color of its own, ANSI colors are given as elements::Color
+const CONSOLE_TEXT_COLOR: Color = Color::rgb(204, 204, 204);
+
+impl WebComponent for Console {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Console");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let line_height = size + LABEL_LINE_SPACING;
+        let height = line_height * self.visible_rows as f32 + 2. * MENU_PADDING;
+        let thumb = self.thumb();
+        let viewport = if thumb.is_some() { width - SCROLLBAR_SIZE } else { width };
+        let mut body = WebBody::new("Console");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: CONSOLE_BACKGROUND });
+        body.add_hit_area(0., 0., width, height, vec![]);
+        let mut text = WebBody::new("Console text");
+        for (row, line) in self.visible_range().enumerate() {
+            let (mut x, y) = (MENU_PADDING, MENU_PADDING + line_height * row as f32);
+            for span in self.lines[line].iter() {
+                let (w, _) = measure_styled_text(&span.text, size, FontStyle::Monospace);
+                if let Some(background) = span.style.background {
+                    let points = vec![(x, y), (x + w, y), (x + w, y + line_height), (x, y + line_height)];
+                    text.push(WebShape::Polygon { points, color: css_color(background) });
+                }
+                let color = css_color(span.style.text_color(CONSOLE_TEXT_COLOR)).into();
+                text.push(WebShape::Span { text: span.text.clone(), x, y, size, style: FontStyle::Monospace, color });
+                x += w;
+            }
+        }
+        // Long lines are cut at the edge like in a terminal
+        body.append_clipped_child(0, 0., 0., viewport, height, text);
+        if let Some((start, length)) = thumb {
+            body.push(WebShape::Rect { x: viewport, y: 0., w: SCROLLBAR_SIZE, h: height,
+                                       color: SCROLLBAR_TRACK_COLOR });
+            body.push(WebShape::Rect { x: viewport, y: start * height, w: SCROLLBAR_SIZE, h: length * height,
+                                       color: SCROLLBAR_THUMB_COLOR });
+        }
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1., color: CHECKBOX_BORDER_COLOR });
+        body
+    }
+}
+
+impl Component for Console {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// ListView ****************************************************************************************
+
+const LIST_ROW_BACKGROUND: &str = "rgb(30, 30, 35)";
+const LIST_ALTERNATE_ROW_BACKGROUND: &str = "rgb(40, 40, 46)";
+const OVERSCROLL_GLOW: &str = "rgba(70, 130, 200, 0.4)";
+
+impl WebComponent for ListView {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ListView");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let height = row_height * self.visible_rows as f32;
+        let bounce = -self.overscroll.offset() * row_height;
+        let mut body = WebBody::new("ListView");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: LIST_ROW_BACKGROUND });
+        body.add_hit_area(0., 0., width, height, vec![]);
+        for (row, y) in self.row_positions(row_height) {
+            let y = y + bounce;
+            if y < 0. || y + row_height > height + 0.5 {
+                continue;
+            }
+            if row >= self.model.len() {
+                let mut loading = loading_row(width, row_height);
+                loading.translate(0., y);
+                body.append(loading);
+                continue;
+            }
+            let color = match (self.model.selection.is_selected(row), row % 2) {
+                (true, _) => SELECTED_BACKGROUND,
+                (false, 0) => LIST_ROW_BACKGROUND,
+                (false, _) => LIST_ALTERNATE_ROW_BACKGROUND,
+            };
+            body.push(WebShape::Rect { x: 0., y, w: width, h: row_height, color });
+            body.push(WebShape::Text { text: self.model.items()[row].clone(), x: MENU_PADDING, y: y + MENU_PADDING,
+                                       size, color: TEXT_COLOR.into() });
+            body.add_hit_area(0., y, width, row_height, vec![row]);
+        }
+        if let Some(layout) = self.section_layout(row_height) {
+            for mut header in section_headers(&layout, self.scroll as f32 * row_height, width, height) {
+                header.translate(0., bounce);
+                if header.bounds().is_some_and(|(_, y, _, h)| y >= 0. && y + h <= height + 0.5) {
+                    body.append_child(ListView::SECTION_HEADERS, header);
+                }
+            }
+        }
+        // The colors are static, so the glow doesn't fade here
+        if let Some((_, at_end)) = self.overscroll.glow() {
+            let glow_height = row_height / 2.;
+            let glow_y = if at_end { height - glow_height } else { 0. };
+            body.push(WebShape::Rect { x: 0., y: glow_y, w: width, h: glow_height, color: OVERSCROLL_GLOW });
+        }
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        body
+    }
+}
+
+impl Component for ListView {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], _event: Event) -> bool {
+        match path {
+            [row] => self.click(*row, SelectModifiers::default()),
+            // The headers of the sections
+            _ => false,
+        }
+    }
+}
+
+// TableView ***************************************************************************************
+
+const SECTION_HEADER_BACKGROUND: &str = "rgb(70, 70, 78)";
+
+/// The headers of the sections in the viewport of a ListView or TableView, the sticky one last so
+/// it's drawn and hit tested on top. Each is hit tested as [section], see sdl::build_section_header()
+fn section_headers(layout: &SectionLayout, scroll: f32, width: f32, height: f32) -> Vec<WebBody> {
+    let size = text_size();
+    let header = |section: usize, y: f32| {
+        let mut body = WebBody::new("SectionHeader");
+        body.push(WebShape::Rect { x: 0., y, w: width, h: layout.header_height, color: SECTION_HEADER_BACKGROUND });
+        if let Some(section) = layout.sections.get(section) {
+            body.push(WebShape::Text { text: section.title.clone(), x: MENU_PADDING,
+                                       y: y + (layout.header_height - size) / 2., size, color: TEXT_COLOR.into() });
+        }
+        body.add_hit_area(0., y, width, layout.header_height, vec![section]);
+        body
+    };
+    let mut headers: Vec<WebBody> = layout.visible_entries(scroll, height).into_iter()
+        .filter_map(|(entry, y)| match entry {
+            SectionEntry::Header(section) => Some(header(section, y)),
+            SectionEntry::Row { .. } => None,
+        })
+        .collect();
+    headers.extend(layout.sticky_header(scroll).map(|(section, y)| header(section, y)));
+    headers
+}
+
+/// The arrow drawn in a sorted column header, pointing up when ascending, see
+/// sdl::build_sort_indicator()
+fn sort_indicator(order: SortOrder, x: f32, y: f32, size: f32) -> WebShape {
+    let (left, right, mid) = (x + size / 4., x + size * 3. / 4., x + size / 2.);
+    let (top, bottom) = (y + size / 3., y + size * 2. / 3.);
+    let points = match order {
+        SortOrder::Ascending => vec![(left, bottom), (right, bottom), (mid, top), (left, bottom)],
+        SortOrder::Descending => vec![(left, top), (right, top), (mid, bottom), (left, top)],
+    };
+    WebShape::Lines { points, thickness: 2., color: TEXT_COLOR.into() }
+}
+
+/// The visible rows of table laid out below top, see sdl::table_rows()
+fn table_rows(table: &TableView, size: f32, top: f32, bottom: f32) -> Vec<(usize, f32, f32)> {
+    let mut heights = table.row_heights.borrow_mut();
+    if heights.line_height != size || heights.padding != MENU_PADDING {
+        *heights = RowHeights::new(size, MENU_PADDING);
+    }
+    let widths = table.columns.widths();
+    let measure = |text: &str| measure_text(text, size).0;
+    table.row_layout(top, bottom, &mut |row| match table.proxy.map_to_model(row) {
+        Some(model_row) if table.wrap_cells => {
+            heights.row_height(model_row, &table.model.rows[model_row], widths, &measure)
+        }
+        _ => heights.min_height,
+    })
+}
+
+impl WebComponent for TableView {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "TableView", rows = self.visible_rows);
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let height = row_height * (self.visible_rows + 1) as f32;
+        self.viewport_width.set(width);
+        let mut body = WebBody::new("TableView");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: LIST_ROW_BACKGROUND });
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: row_height, color: SECTION_HEADER_BACKGROUND });
+        let layout = self.section_layout(row_height);
+        let rows = match &layout {
+            Some(layout) => layout.visible_rows(self.scroll as f32 * row_height, height - row_height).into_iter()
+                .filter(|(row, _)| *row < self.row_count())
+                .map(|(row, y)| (row, y + row_height, row_height))
+                .collect(),
+            None => table_rows(self, size, row_height, height),
+        };
+        for &(row, y, h) in rows.iter() {
+            let h = h.min(height - y);
+            let color = match (self.is_row_selected(row), row % 2) {
+                (true, _) => SELECTED_BACKGROUND,
+                (false, 0) => LIST_ROW_BACKGROUND,
+                (false, _) => LIST_ALTERNATE_ROW_BACKGROUND,
+            };
+            body.push(WebShape::Rect { x: 0., y, w: width, h, color });
+            body.add_hit_area(0., y, width, h, vec![TableView::ROWS, row]);
+        }
+        // Only the cells that can be seen, each column clipped so long cells don't spill over
+        let frozen_width = self.columns.frozen_width();
+        for (column, x) in self.columns.visible_columns(self.clamped_scroll_x(), width) {
+            let right = (x + self.columns.widths()[column]).min(width);
+            // The scrolled columns go under the frozen ones
+            let left = if column < self.columns.frozen { x } else { x.max(frozen_width) };
+            let mut cells = WebBody::new("TableColumn");
+            let mut title_width = right - x - MENU_PADDING;
+            if let Some(order) = self.sort_order(column) {
+                title_width -= size;
+                cells.push(sort_indicator(order, right - size, (row_height - size) / 2., size));
+            }
+            let mut title = WebBody::new("TableHeader");
+            title.push(WebShape::Text { text: self.model.column_title(column), x: x + MENU_PADDING, y: MENU_PADDING,
+                                        size, color: TEXT_COLOR.into() });
+            cells.append_clipped_child(0, x, 0., title_width.max(0.), row_height, title);
+            for &(row, y, _) in rows.iter() {
+                let text = match self.proxy.cell(&self.model, row, column) {
+                    Some(text) => text,
+                    None => continue,
+                };
+                let lines = match self.wrap_cells {
+                    true => wrap_lines(&text, self.columns.widths()[column] - 2. * MENU_PADDING,
+                                       &|line| measure_text(line, size).0),
+                    false => vec![text],
+                };
+                for (i, text) in lines.into_iter().enumerate() {
+                    let y = y + MENU_PADDING + size * i as f32;
+                    cells.push(WebShape::Text { text, x: x + MENU_PADDING, y, size, color: TEXT_COLOR.into() });
+                }
+            }
+            // There is no dragging on the web yet, so the borders can't be grabbed to resize
+            body.add_hit_area(left, 0., right - left, row_height, vec![TableView::HEADER, column]);
+            body.append_clipped_child(column, left, 0., right - left, height, cells);
+            body.push(WebShape::Rect { x: right - 1., y: 0., w: 1., h: height, color: CHECKBOX_BORDER_COLOR });
+        }
+        // Over the cells, below the column titles
+        if let Some(layout) = &layout {
+            let scroll = self.scroll as f32 * row_height;
+            for mut header in section_headers(layout, scroll, width, height - row_height) {
+                header.translate(0., row_height);
+                body.append_child(TableView::SECTION_HEADERS, header);
+            }
+        }
+        if let Some((_, y, _)) = rows.iter().find(|(row, _, _)| *row >= self.proxy.row_count()) {
+            let mut loading = loading_row(width, row_height);
+            loading.translate(0., *y);
+            body.append(loading);
+        }
+        body.push(WebShape::Rect { x: 0., y: row_height - 1., w: width, h: 1., color: CHECKBOX_BORDER_COLOR });
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        // Below the rows, when the columns don't fit
+        if let Some((start, length)) = self.horizontal_thumb() {
+            body.push(WebShape::Rect { x: 0., y: height, w: width, h: SCROLLBAR_SIZE, color: SCROLLBAR_TRACK_COLOR });
+            body.push(WebShape::Rect { x: start * width, y: height, w: length * width, h: SCROLLBAR_SIZE,
+                                       color: SCROLLBAR_THUMB_COLOR });
+            body.add_hit_area(0., height, width, SCROLLBAR_SIZE, vec![TableView::HORIZONTAL_SCROLLBAR]);
+        }
+        body
+    }
+}
+
+impl Component for TableView {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path {
+            [TableView::HEADER, column] => self.sort_by(*column, event.position),
+            [TableView::ROWS, row] => self.click(*row, SelectModifiers::default()),
+            _ => false,
+        }
+    }
+}
+
+// TreeTable ***************************************************************************************
+
+/// The arrow in front of a tree node with children, see sdl::build_expander()
+fn expander(expanded: bool, x: f32, y: f32, size: f32) -> WebShape {
+    let (near, far, mid) = (size / 3., size * 2. / 3., size / 2.);
+    let points = match expanded {
+        true => vec![(x + size / 4., y + near), (x + size * 3. / 4., y + near), (x + mid, y + far)],
+        false => vec![(x + near, y + size / 4.), (x + near, y + size * 3. / 4.), (x + far, y + mid)],
+    };
+    WebShape::Polygon { points, color: TEXT_COLOR.to_string() }
+}
+
+impl WebComponent for TreeTable {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "TreeTable", rows = self.visible_rows);
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let height = row_height * (self.visible_rows + 1) as f32;
+        let mut body = WebBody::new("TreeTable");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: LIST_ROW_BACKGROUND });
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: row_height, color: SECTION_HEADER_BACKGROUND });
+        for (i, row) in self.visible_range().enumerate() {
+            let y = row_height * (i + 1) as f32;
+            let color = match (self.selection.is_selected(row), row % 2) {
+                (true, _) => SELECTED_BACKGROUND,
+                (false, 0) => LIST_ROW_BACKGROUND,
+                (false, _) => LIST_ALTERNATE_ROW_BACKGROUND,
+            };
+            body.push(WebShape::Rect { x: 0., y, w: width, h: row_height, color });
+            body.add_hit_area(0., y, width, row_height, vec![TreeTable::ROWS, row]);
+        }
+        for (column, x) in self.column_layout.visible_columns(0., width) {
+            let column_width = self.column_layout.widths()[column].min(width - x);
+            let mut cells = WebBody::new("TreeTableColumn");
+            cells.push(WebShape::Text { text: self.column_title(column), x: x + MENU_PADDING, y: MENU_PADDING,
+                                        size, color: TEXT_COLOR.into() });
+            for (i, row) in self.visible_range().enumerate() {
+                let y = row_height * (i + 1) as f32;
+                let mut text_x = x + MENU_PADDING;
+                if column == 0 {
+                    let tree_row = &self.rows()[row];
+                    if tree_row.has_children {
+                        let expander_x = text_x + self.indent * tree_row.depth as f32;
+                        cells.push(expander(tree_row.expanded, expander_x, y + (row_height - self.indent) / 2.,
+                                            self.indent));
+                    }
+                    text_x += self.tree_cell_offset(row);
+                }
+                cells.push(WebShape::Text { text: self.cell(row, column), x: text_x, y: y + MENU_PADDING, size,
+                                            color: TEXT_COLOR.into() });
+            }
+            body.add_hit_area(x, 0., column_width, row_height, vec![TreeTable::HEADER, column]);
+            body.append_clipped_child(column, x, 0., column_width, height, cells);
+            body.push(WebShape::Rect { x: x + column_width - 1., y: 0., w: 1., h: height,
+                                       color: CHECKBOX_BORDER_COLOR });
+        }
+        // After the rows, so the expanders are hit first
+        for (i, row) in self.visible_range().enumerate() {
+            let tree_row = &self.rows()[row];
+            if tree_row.has_children {
+                let x = MENU_PADDING + self.indent * tree_row.depth as f32;
+                body.add_hit_area(x, row_height * (i + 1) as f32, self.indent, row_height,
+                                  vec![TreeTable::EXPANDERS, row]);
+            }
+        }
+        body.push(WebShape::Rect { x: 0., y: row_height - 1., w: width, h: 1., color: CHECKBOX_BORDER_COLOR });
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        body
+    }
+}
+
+impl Component for TreeTable {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], _event: Event) -> bool {
+        match path {
+            [TreeTable::EXPANDERS, row] => self.toggle(*row),
+            [TreeTable::ROWS, row] => self.click(*row, SelectModifiers::default()),
+            _ => false,
+        }
+    }
+}
+
+// Paging ******************************************************************************************
+
+/// The row shown at the end of a paged view while the next page is loading, see
+/// sdl::build_loading_row()
+fn loading_row(width: f32, row_height: f32) -> WebBody {
+    let size = text_size();
+    let mut body = WebBody::new("LoadingRow");
+    body.push(WebShape::Rect { x: 0., y: 0., w: width, h: row_height, color: MENU_BACKGROUND });
+    let text = "Loading...";
+    let x = (width - measure_text(text, size).0) / 2.;
+    body.push(WebShape::Text { text: text.to_string(), x, y: (row_height - size) / 2., size,
+                               color: DISABLED_TEXT_COLOR.into() });
+    body
+}
+
+// ComboBox ****************************************************************************************
+
+impl WebComponent for ComboBox {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ComboBox");
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let widest = self.items.iter().map(|i| measure_text(i, size).0).fold(0., f32::max);
+        let arrow_x = widest + 3. * MENU_PADDING;
+        let width = arrow_x + size / 2. + 2. * MENU_PADDING;
+        let mid = row_height / 2.;
+        let mut body = WebBody::new("ComboBox");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: row_height, color: TEXT_FIELD_BACKGROUND });
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: row_height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        if let Some(item) = self.selected_item() {
+            body.push(WebShape::Text { text: item.to_string(), x: 2. * MENU_PADDING, y: MENU_PADDING, size,
+                                       color: TEXT_COLOR.into() });
+        }
+        body.push(WebShape::Lines {
+            points: vec![(arrow_x, mid - size / 8.), (arrow_x + size / 4., mid + size / 8.),
+                         (arrow_x + size / 2., mid - size / 8.)],
+            thickness: size / 8.,
+            color: TEXT_COLOR.into(),
+        });
+        body.add_hit_area(0., 0., width, row_height, vec![]);
+        if self.open {
+            let mut popup = WebBody::new("ComboBoxPopup");
+            let height = row_height * self.items.len() as f32;
+            popup.push(WebShape::Rect { x: 0., y: row_height, w: width, h: height, color: MENU_BACKGROUND });
+            for (i, item) in self.items.iter().enumerate() {
+                let y = row_height * (i + 1) as f32;
+                if self.selected == Some(i) {
+                    popup.push(WebShape::Rect { x: 0., y, w: width, h: row_height, color: SELECTED_BACKGROUND });
+                }
+                popup.push(WebShape::Text { text: item.clone(), x: 2. * MENU_PADDING, y: y + MENU_PADDING, size,
+                                            color: TEXT_COLOR.into() });
+                popup.add_hit_area(0., y, width, row_height, vec![i]);
+            }
+            popup.push(WebShape::Outline { x: 0., y: row_height, w: width, h: height, thickness: 1.,
+                                           color: CHECKBOX_BORDER_COLOR });
+            body.push_overlay(popup);
+        }
+        body
+    }
+}
+
+impl Component for ComboBox {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(index) => self.select(*index, event.position),
+            None => {
+                self.open = !self.open;
+                true
+            }
+        }
+    }
+}
+
+// DatePicker **************************************************************************************
+
+const TODAY_OUTLINE: &str = "rgb(255, 200, 80)";
+
+impl WebComponent for DatePicker {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "DatePicker");
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let text_width = measure_text("0000-00-00", size).0.max(measure_text(&self.placeholder, size).0);
+        let arrow_x = text_width + 3. * MENU_PADDING;
+        let width = arrow_x + size / 2. + 2. * MENU_PADDING;
+        let mid = row_height / 2.;
+        let mut body = WebBody::new("DatePicker");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: row_height, color: TEXT_FIELD_BACKGROUND });
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: row_height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        let text_color = if self.date.is_some() { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+        body.push(WebShape::Text { text: self.text(), x: 2. * MENU_PADDING, y: MENU_PADDING, size,
+                                   color: text_color.into() });
+        body.push(WebShape::Lines {
+            points: vec![(arrow_x, mid - size / 8.), (arrow_x + size / 4., mid + size / 8.),
+                         (arrow_x + size / 2., mid - size / 8.)],
+            thickness: size / 8.,
+            color: TEXT_COLOR.into(),
+        });
+        body.add_hit_area(0., 0., width, row_height, vec![]);
+        if self.open {
+            body.push_overlay(build_calendar(self, row_height, size));
+        }
+        body
+    }
+}
+
+/// The popup of a DatePicker under its field, see the SDL engine
+fn build_calendar(date_picker: &DatePicker, top: f32, size: f32) -> WebBody {
+    let row_height = size + 2. * MENU_PADDING;
+    let cell = (measure_text("00", size).0 + 2. * MENU_PADDING).max(row_height);
+    let width = cell * WEEKDAY_NAMES.len() as f32;
+    let height = row_height * (2 + GRID_DAYS / WEEKDAY_NAMES.len()) as f32;
+    let centered = |text: &str, x: f32, w: f32| x + (w - measure_text(text, size).0) / 2.;
+    let text = |text: String, x: f32, y: f32, color: &'static str| WebShape::Text {
+        x: centered(&text, x, cell), text, y: y + MENU_PADDING, size, color: color.into(),
+    };
+    let mut popup = WebBody::new("DatePickerPopup");
+    popup.push(WebShape::Rect { x: 0., y: top, w: width, h: height, color: MENU_BACKGROUND });
+    let title = date_picker.month.month_title();
+    popup.push(WebShape::Text { x: centered(&title, 0., width), text: title, y: top + MENU_PADDING, size,
+                                color: TEXT_COLOR.into() });
+    let (mid, arrow) = (top + row_height / 2., size / 4.);
+    for (x, direction) in [(cell / 2., 1.), (width - cell / 2., -1.)] {
+        popup.push(WebShape::Lines {
+            points: vec![(x + direction * arrow / 2., mid - arrow), (x - direction * arrow / 2., mid),
+                         (x + direction * arrow / 2., mid + arrow)],
+            thickness: size / 8.,
+            color: TEXT_COLOR.into(),
+        });
+    }
+    popup.add_hit_area(0., top, cell, row_height, vec![DatePicker::PREVIOUS_MONTH]);
+    popup.add_hit_area(width - cell, top, cell, row_height, vec![DatePicker::NEXT_MONTH]);
+    for (i, name) in WEEKDAY_NAMES.iter().enumerate() {
+        popup.push(text(name.to_string(), cell * i as f32, top + row_height, DISABLED_TEXT_COLOR));
+    }
+    let today = Date::today();
+    for (i, day) in date_picker.month.month_grid().into_iter().enumerate() {
+        let x = cell * (i % WEEKDAY_NAMES.len()) as f32;
+        let y = top + row_height * (2 + i / WEEKDAY_NAMES.len()) as f32;
+        if date_picker.date == Some(day) {
+            popup.push(WebShape::Rect { x, y, w: cell, h: row_height, color: SELECTED_BACKGROUND });
+        }
+        if day == today {
+            popup.push(WebShape::Outline { x, y, w: cell, h: row_height, thickness: 1., color: TODAY_OUTLINE });
+        }
+        let color = if day.month == date_picker.month.month { TEXT_COLOR } else { DISABLED_TEXT_COLOR };
+        popup.push(text(day.day.to_string(), x, y, color));
+        popup.add_hit_area(x, y, cell, row_height, vec![DatePicker::FIRST_DAY + i]);
+    }
+    popup.push(WebShape::Outline { x: 0., y: top, w: width, h: height, thickness: 1., color: CHECKBOX_BORDER_COLOR });
+    popup
+}
+
+impl Component for DatePicker {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            None => self.toggle(),
+            Some(&DatePicker::PREVIOUS_MONTH) => self.show_month(-1),
+            Some(&DatePicker::NEXT_MONTH) => self.show_month(1),
+            Some(index) => match self.month.month_grid().get(index - DatePicker::FIRST_DAY) {
+                Some(&date) => self.select(date, event.position),
+                None => false,
+            },
+        }
+    }
+}
+
+// Rating ******************************************************************************************
+
+const RATING_STAR_COLOR: &str = "rgb(255, 200, 60)";
+
+/// The stars are text, the canvas fonts have them. A half star is the star with its left half black
+impl WebComponent for Rating {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Rating");
+        let size = text_size();
+        let cell = size + 2. * MENU_PADDING;
+        let parts = self.parts_per_star();
+        let part_width = cell / parts as f32;
+        let mut body = WebBody::new("Rating");
+        for i in 0..self.max {
+            let fill = self.star_fill(i);
+            let (star, color) = match (fill >= 1., fill >= 0.5) {
+                (true, _) => ("\u{2605}", RATING_STAR_COLOR),
+                (false, true) => ("\u{2BEA}", RATING_STAR_COLOR),
+                (false, false) => ("\u{2606}", CHECKBOX_BORDER_COLOR),
+            };
+            body.push(WebShape::Text { text: star.to_string(), x: cell * i as f32 + MENU_PADDING, y: MENU_PADDING,
+                                       size, color: color.into() });
+            for part in 0..parts {
+                body.add_hit_area(cell * i as f32 + part_width * part as f32, 0., part_width, cell,
+                                  vec![i * parts + part]);
+            }
+        }
+        push_forced_border(&mut body, 0., 0., cell * self.max as f32, cell);
+        body
+    }
+}
+
+impl Component for Rating {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    /// There is no hovering on the web yet, so no preview either
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(part) => self.click_part(*part, event.position),
+            None => false,
+        }
+    }
+}
+
+// SegmentedControl ********************************************************************************
+
+impl WebComponent for SegmentedControl {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "SegmentedControl");
+        let size = text_size();
+        let height = size + 2. * MENU_PADDING;
+        let mut body = WebBody::new("SegmentedControl");
+        let mut x = 0.;
+        for (i, segment) in self.segments.iter().enumerate() {
+            let width = measure_text(segment, size).0 + 4. * MENU_PADDING;
+            let background = if self.selected == Some(i) { SELECTED_BACKGROUND } else { BUTTON_BACKGROUND };
+            body.push(WebShape::Rect { x, y: 0., w: width, h: height, color: background });
+            if i > 0 {
+                body.push(WebShape::Rect { x, y: 0., w: 1., h: height, color: CHECKBOX_BORDER_COLOR });
+            }
+            push_forced_border(&mut body, x, 0., width, height);
+            body.push(WebShape::Text { text: segment.clone(), x: x + 2. * MENU_PADDING, y: MENU_PADDING, size,
+                                       color: TEXT_COLOR.into() });
+            body.add_hit_area(x, 0., width, height, vec![i]);
+            x += width;
+        }
+        body.push(WebShape::Outline { x: 0., y: 0., w: x, h: height, thickness: 1., color: CHECKBOX_BORDER_COLOR });
+        body
+    }
+}
+
+impl Component for SegmentedControl {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(index) => self.select(*index, event.position),
+            None => false,
+        }
+    }
+}
+
+// Breadcrumb **************************************************************************************
+
+impl WebComponent for Breadcrumb {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Breadcrumb");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let height = size + 2. * MENU_PADDING;
+        let widths: Vec<f32> = self.segments.iter().map(|s| measure_text(s, size).0 + 2. * MENU_PADDING).collect();
+        let gap = measure_text(Breadcrumb::SEPARATOR, size).0;
+        let ellipsis = measure_text(Breadcrumb::ELLIPSIS, size).0 + 2. * MENU_PADDING;
+        let mut items = vec![];
+        for (n, i) in self.visible(&widths, gap, ellipsis, width).into_iter().enumerate() {
+            if n == 1 && i > 1 {
+                items.push((Breadcrumb::ELLIPSIS, ellipsis, i - 1));
+            }
+            items.push((self.segments[i].as_str(), widths[i], i));
+        }
+        let current = self.segments.len().saturating_sub(1);
+        let mut body = WebBody::new("Breadcrumb");
+        let mut x = 0.;
+        for (n, (text, w, index)) in items.into_iter().enumerate() {
+            if n > 0 {
+                body.push(WebShape::Text { text: Breadcrumb::SEPARATOR.to_string(), x, y: MENU_PADDING, size,
+                                           color: DISABLED_TEXT_COLOR.into() });
+                x += gap;
+            }
+            let color = if index == current { TEXT_COLOR } else { LINK_COLOR };
+            body.push(WebShape::Text { text: text.to_string(), x: x + MENU_PADDING, y: MENU_PADDING, size,
+                                       color: color.into() });
+            if index != current {
+                body.add_hit_area(x, 0., w, height, vec![index]);
+            }
+            x += w;
+        }
+        body
+    }
+}
+
+impl Component for Breadcrumb {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(index) => self.click(*index, event.position),
+            None => false,
+        }
+    }
+}
+
+// TabPane *****************************************************************************************
+
+const TAB_ACTIVE_BACKGROUND: &str = "rgb(30, 30, 35)";
+const TAB_INDICATOR_HEIGHT: f32 = 2.;
+
+impl WebComponent for TabPane {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "TabPane");
+        let (width, height) = parent_size(parent);
+        let size = text_size();
+        let strip_height = size + 2. * MENU_PADDING;
+        let mut body = WebBody::new("TabPane");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: strip_height, color: MENU_BACKGROUND });
+        let mut x = 0.;
+        for (i, tab) in self.tabs.iter().enumerate() {
+            let tab_width = measure_text(&tab.title, size).0 + 4. * MENU_PADDING;
+            if i == self.active {
+                body.push(WebShape::Rect { x, y: 0., w: tab_width, h: strip_height, color: TAB_ACTIVE_BACKGROUND });
+                body.push(WebShape::Rect { x, y: strip_height - TAB_INDICATOR_HEIGHT, w: tab_width,
+                                           h: TAB_INDICATOR_HEIGHT, color: SELECTED_BACKGROUND });
+            }
+            push_forced_border(&mut body, x, 0., tab_width, strip_height);
+            body.push(WebShape::Text { text: tab.title.clone(), x: x + 2. * MENU_PADDING, y: MENU_PADDING, size,
+                                       color: TEXT_COLOR.into() });
+            body.add_hit_area(x, 0., tab_width, strip_height, vec![i]);
+            x += tab_width;
+        }
+        if let Some(tab) = self.active_tab() {
+            let mut content = tab.content.build(&pseudo_parent(width, height - strip_height));
+            let content_height = content.bounds().map(|(_, by, _, bh)| by + bh).unwrap_or(0.) + CONTAINER_PADDING;
+            content.translate(0., strip_height);
+            body.append_child(self.active, content);
+            body.push(WebShape::Outline { x: 0., y: strip_height, w: width, h: content_height, thickness: 1.,
+                                          color: CHECKBOX_BORDER_COLOR });
+        }
+        body
+    }
+}
+
+impl Component for TabPane {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.split_first() {
+            Some((tab, [])) => self.select(*tab, event.position),
+            Some((tab, rest)) => match self.tabs.get_mut(*tab) {
+                Some(tab) => tab.content.handle_click(rest, event),
+                None => false,
+            },
+            None => false,
+        }
+    }
+}
+
+// Carousel ****************************************************************************************
+
+const CAROUSEL_ARROW_WIDTH: f32 = 24.;
+const CAROUSEL_DOTS_HEIGHT: f32 = 20.;
+const CAROUSEL_DOT_RADIUS: f32 = 4.;
+const CAROUSEL_DOT_SPACING: f32 = 16.;
+
+impl WebComponent for Carousel {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Carousel");
+        let (width, parent_height) = parent_size(parent);
+        let height = resolve_dimension(&self.height, parent_height);
+        let page_width = (width - 2. * CAROUSEL_ARROW_WIDTH).max(0.);
+        let mut body = WebBody::new("Carousel");
+        // Only the pages in view are built, pages aren't dragged without a mouseup to end it
+        let position = self.position();
+        let mut pages = WebBody::new("Pages");
+        for (i, page) in self.pages.iter().enumerate().filter(|(i, _)| (*i as f32 - position).abs() < 1.) {
+            let mut page_body = page.build(&pseudo_parent(page_width, height));
+            page_body.translate(CAROUSEL_ARROW_WIDTH + (i as f32 - position) * page_width, 0.);
+            pages.append_child(i, page_body);
+        }
+        body.append_clipped_child(Carousel::PAGES, CAROUSEL_ARROW_WIDTH, 0., page_width, height, pages);
+        body.push(WebShape::Outline { x: CAROUSEL_ARROW_WIDTH, y: 0., w: page_width, h: height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        let mid = height / 2.;
+        for (index, x, enabled) in [(Carousel::PREVIOUS, 0., self.active > 0),
+                                    (Carousel::NEXT, width - CAROUSEL_ARROW_WIDTH, self.active + 1 < self.pages.len())] {
+            body.push(WebShape::Rect { x, y: 0., w: CAROUSEL_ARROW_WIDTH, h: height, color: SPINNER_ARROW_BACKGROUND });
+            push_forced_border(&mut body, x, 0., CAROUSEL_ARROW_WIDTH, height);
+            let (tip, back) = match index {
+                Carousel::PREVIOUS => (x + CAROUSEL_ARROW_WIDTH / 3., x + CAROUSEL_ARROW_WIDTH * 2. / 3.),
+                _ => (x + CAROUSEL_ARROW_WIDTH * 2. / 3., x + CAROUSEL_ARROW_WIDTH / 3.),
+            };
+            let half = CAROUSEL_ARROW_WIDTH / 4.;
+            body.push(WebShape::Lines {
+                points: vec![(back, mid - half), (tip, mid), (back, mid + half)],
+                thickness: 2.,
+                color: if enabled { TEXT_COLOR } else { DISABLED_TEXT_COLOR }.into(),
+            });
+            body.add_hit_area(x, 0., CAROUSEL_ARROW_WIDTH, height, vec![index]);
+        }
+        let dots_width = self.pages.len() as f32 * CAROUSEL_DOT_SPACING;
+        let dots_y = height + CAROUSEL_DOTS_HEIGHT / 2.;
+        for i in 0..self.pages.len() {
+            let x = (width - dots_width) / 2. + i as f32 * CAROUSEL_DOT_SPACING;
+            let color = if i == self.active { SELECTED_BACKGROUND } else { CHECKBOX_BORDER_COLOR };
+            body.push(WebShape::Circle { cx: x + CAROUSEL_DOT_SPACING / 2., cy: dots_y, radius: CAROUSEL_DOT_RADIUS,
+                                         color });
+            body.add_hit_area(x, height, CAROUSEL_DOT_SPACING, CAROUSEL_DOTS_HEIGHT, vec![Carousel::DOTS, i]);
+        }
+        body
+    }
+}
+
+impl Component for Carousel {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    /// The arrows and the dots change the page, the rest goes to the components of the page
+    fn handle_click(&mut self, path: &[usize], event: crate::elements::Event) -> bool {
+        match path {
+            [Carousel::PREVIOUS] => self.previous(event.position),
+            [Carousel::NEXT] => self.next(event.position),
+            [Carousel::DOTS, page] => self.go_to(*page, event.position),
+            [Carousel::PAGES, page, rest @ ..] => match self.pages.get_mut(*page) {
+                Some(page) => page.handle_click(rest, event),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+// ScrollPane **************************************************************************************
+
+const SCROLLBAR_SIZE: f32 = 10.;
+const SCROLLBAR_TRACK_COLOR: &str = "rgb(30, 30, 35)";
+const SCROLLBAR_THUMB_COLOR: &str = "rgb(110, 110, 120)";
+
+/// Size of a built Container including the padding after its last child
+fn content_size(content: &WebBody) -> (f32, f32) {
+    content.bounds()
+        .map(|(x, y, w, h)| (x + w + CONTAINER_PADDING, y + h + CONTAINER_PADDING))
+        .unwrap_or((0., 0.))
+}
+
+impl WebComponent for ScrollPane {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ScrollPane");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let mut content = self.content.build(&pseudo_parent(width, height));
+        let mut size = content_size(&content);
+        let viewport = ScrollPane::viewport_for(size, (width, height), SCROLLBAR_SIZE);
+        if viewport.0 < width {
+            content = self.content.build(&pseudo_parent(viewport.0, height));
+            size = content_size(&content);
+        }
+        self.extent.set(ScrollExtent { content: size, viewport });
+        let (scroll_x, scroll_y) = self.clamped_scroll();
+        content.translate(-scroll_x, -scroll_y);
+        let mut body = WebBody::new("ScrollPane");
+        body.add_hit_area(0., 0., viewport.0, viewport.1, vec![]);
+        body.append_clipped_child(ScrollPane::CONTENT, 0., 0., viewport.0, viewport.1, content);
+        if let Some((start, length)) = self.thumb(true) {
+            body.push(WebShape::Rect { x: viewport.0, y: 0., w: SCROLLBAR_SIZE, h: viewport.1,
+                                       color: SCROLLBAR_TRACK_COLOR });
+            body.push(WebShape::Rect { x: viewport.0, y: start * viewport.1, w: SCROLLBAR_SIZE,
+                                       h: length * viewport.1, color: SCROLLBAR_THUMB_COLOR });
+            body.add_hit_area(viewport.0, 0., SCROLLBAR_SIZE, viewport.1, vec![ScrollPane::VERTICAL_SCROLLBAR]);
+        }
+        if let Some((start, length)) = self.thumb(false) {
+            body.push(WebShape::Rect { x: 0., y: viewport.1, w: width, h: SCROLLBAR_SIZE,
+                                       color: SCROLLBAR_TRACK_COLOR });
+            body.push(WebShape::Rect { x: start * viewport.0, y: viewport.1, w: length * viewport.0,
+                                       h: SCROLLBAR_SIZE, color: SCROLLBAR_THUMB_COLOR });
+            body.add_hit_area(0., viewport.1, viewport.0, SCROLLBAR_SIZE, vec![ScrollPane::HORIZONTAL_SCROLLBAR]);
+        }
+        body
+    }
+}
+
+impl Component for ScrollPane {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.split_first() {
+            Some((&ScrollPane::CONTENT, rest)) => self.content.handle_click(rest, event),
+            _ => false,
+        }
+    }
+}
+
+// ProgressBar *************************************************************************************
+
+impl WebComponent for ProgressBar {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ProgressBar");
+        let (width, _) = parent_size(parent);
+        let height = text_size() / 2.;
+        let (start, end) = self.filled_range();
+        let mut body = WebBody::new("ProgressBar");
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: PROGRESS_TRACK_COLOR });
+        body.push(WebShape::Rect { x: width * start, y: 0., w: width * (end - start), h: height,
+                                   color: PROGRESS_FILL_COLOR });
+        push_forced_border(&mut body, 0., 0., width, height);
+        body
+    }
+}
+
+impl Component for ProgressBar {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Gauge *******************************************************************************************
+
+const GAUGE_NEEDLE_COLOR: &str = "rgb(230, 230, 230)";
+
+impl WebComponent for Gauge {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Gauge");
+        let (parent_width, _) = parent_size(parent);
+        let diameter = resolve_dimension(&self.size, parent_width);
+        let (radius, size) = (diameter / 2., text_size());
+        let center = (radius, radius);
+        let arc_width = radius * Gauge::ARC_WIDTH;
+        let arc_radius = radius - arc_width / 2.;
+        let mut body = WebBody::new("Gauge");
+        // Invisible, the dial takes its size even where it's open
+        body.push(WebShape::Rect { x: 0., y: 0., w: diameter, h: diameter, color: CANVAS_BACKGROUND_COLOR });
+        let arc = |from: f32, to: f32, color: Cow<'static, str>| WebShape::Stroke {
+            points: self.arc(from, to, center, arc_radius),
+            closed: false,
+            style: StrokeStyle::solid(arc_width),
+            color,
+        };
+        body.push(arc(self.min, self.max, PROGRESS_TRACK_COLOR.into()));
+        for zone in &self.zones {
+            body.push(arc(zone.from, zone.to, css_color(zone.color).into()));
+        }
+        let text = self.text();
+        let text_y = radius + radius / 4.;
+        body.push(WebShape::Text { x: radius - measure_text(&text, size).0 / 2., y: text_y, text, size,
+                                   color: TEXT_COLOR.into() });
+        if !self.unit.is_empty() {
+            body.push(WebShape::Text { text: self.unit.clone(), x: radius - measure_text(&self.unit, size).0 / 2.,
+                                       y: text_y + size, size, color: DISABLED_TEXT_COLOR.into() });
+        }
+        let hub = radius * Gauge::ARC_WIDTH / 2.;
+        let needle = self.needle_triangle(center, radius - arc_width, hub * 2.);
+        body.push(WebShape::Polygon { points: needle.to_vec(), color: GAUGE_NEEDLE_COLOR.to_string() });
+        body.push(WebShape::Circle { cx: radius, cy: radius, radius: hub, color: GAUGE_NEEDLE_COLOR });
+        body
+    }
+}
+
+impl Component for Gauge {
+    fn get_height(&self) -> &Dimension {
+        &self.size
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.size
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Separator ***************************************************************************************
+
+const SEPARATOR_MARGIN_COLOR: &str = "rgba(0, 0, 0, 0)";
+
+impl WebComponent for Separator {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Separator");
+        let (parent_width, parent_height) = parent_size(parent);
+        let length = match (self.orientation, &self.length) {
+            (Orientation::Horizontal, Some(length)) => resolve_dimension(length, parent_width),
+            (Orientation::Vertical, Some(length)) => resolve_dimension(length, parent_height),
+            (Orientation::Horizontal, None) => parent_width,
+            (Orientation::Vertical, None) => text_size() + LABEL_LINE_SPACING,
+        };
+        let (width, height) = self.size(length);
+        let color: Cow<'static, str> = self.color.map_or(STATUS_SEPARATOR_COLOR.into(), |c| css_color(c).into());
+        let thickness = self.thickness.max(1.);
+        let mut body = WebBody::new("Separator");
+        // Invisible, it makes the margins part of the bounds so the layout leaves them
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: SEPARATOR_MARGIN_COLOR });
+        let (x, y, w, h) = match self.orientation {
+            Orientation::Horizontal => (0., Separator::MARGIN, width, thickness),
+            Orientation::Vertical => (Separator::MARGIN, 0., thickness, height),
+        };
+        body.push(WebShape::RoundedRect { x, y, w, h, radius: 0., color });
+        body
+    }
+}
+
+impl Component for Separator {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Slider ******************************************************************************************
+
+const SLIDER_TRACK_HEIGHT: f32 = 4.;
+
+impl WebComponent for Slider {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Slider");
+        let (width, _) = parent_size(parent);
+        let size = text_size();
+        let radius = size / 2.;
+        let track_width = (width - 2. * radius).max(0.);
+        let track_y = radius - SLIDER_TRACK_HEIGHT / 2.;
+        let thumb_x = radius + track_width * self.fraction();
+        let mut body = WebBody::new("Slider");
+        body.push(WebShape::Rect { x: radius, y: track_y, w: track_width, h: SLIDER_TRACK_HEIGHT,
+                                   color: PROGRESS_TRACK_COLOR });
+        push_forced_border(&mut body, radius, track_y, track_width, SLIDER_TRACK_HEIGHT);
+        body.push(WebShape::Rect { x: radius, y: track_y, w: thumb_x - radius, h: SLIDER_TRACK_HEIGHT,
+                                   color: PROGRESS_FILL_COLOR });
+        body.push(WebShape::Circle { cx: thumb_x, cy: radius, radius, color: CHECKBOX_BORDER_COLOR });
+        body.add_hit_area(0., 0., width, size, vec![]);
+        body
+    }
+}
+
+impl Component for Slider {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Spinner *****************************************************************************************
+
+const SPINNER_ARROW_BACKGROUND: &str = "rgb(60, 60, 70)";
+
+impl WebComponent for Spinner {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Spinner");
+        let size = text_size();
+        let row_height = size + 2. * MENU_PADDING;
+        let widest = [self.format(self.min), self.format(self.max), self.text.clone()].iter()
+            .map(|t| measure_text(t, size).0)
+            .fold(0., f32::max);
+        let unit_width = match self.unit.is_empty() {
+            true => 0.,
+            false => measure_text(&self.unit, size).0 + MENU_PADDING,
+        };
+        let field_width = widest + unit_width + 4. * MENU_PADDING;
+        let arrow_height = row_height / 2.;
+        let mut body = WebBody::new("Spinner");
+        body.push(WebShape::Rect { x: 0., y: 0., w: field_width, h: row_height, color: TEXT_FIELD_BACKGROUND });
+        let border = if self.is_valid() { CHECKBOX_BORDER_COLOR } else { ERROR_COLOR };
+        body.push(WebShape::Outline { x: 0., y: 0., w: field_width, h: row_height, thickness: 1., color: border });
+        body.push(WebShape::Text { text: self.text.clone(), x: 2. * MENU_PADDING, y: MENU_PADDING, size,
+                                   color: TEXT_COLOR.into() });
+        if !self.unit.is_empty() {
+            body.push(WebShape::Text { text: self.unit.clone(), x: widest + 3. * MENU_PADDING,
+                                       y: MENU_PADDING, size, color: DISABLED_TEXT_COLOR.into() });
+        }
+        body.add_hit_area(0., 0., field_width, row_height, vec![]);
+        for (index, y, up) in [(Spinner::INCREMENT, 0., true), (Spinner::DECREMENT, arrow_height, false)] {
+            body.push(WebShape::Rect { x: field_width, y, w: row_height, h: arrow_height,
+                                       color: SPINNER_ARROW_BACKGROUND });
+            body.push(WebShape::Outline { x: field_width, y, w: row_height, h: arrow_height, thickness: 1.,
+                                          color: CHECKBOX_BORDER_COLOR });
+            let (base, tip) = match up {
+                true => (y + arrow_height * 0.7, y + arrow_height * 0.3),
+                false => (y + arrow_height * 0.3, y + arrow_height * 0.7),
+            };
+            body.push(WebShape::Lines {
+                points: vec![(field_width + row_height / 3., base), (field_width + row_height / 2., tip),
+                             (field_width + row_height * 2. / 3., base)],
+                thickness: 2.,
+                color: TEXT_COLOR.into(),
+            });
+            body.add_hit_area(field_width, y, row_height, arrow_height, vec![index]);
+        }
+        body
+    }
+}
+
+impl Component for Spinner {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+
+    /// The arrows step the value once, there is no typing in the field nor holding the arrows (no
+    /// mouseup is listened to) on the web yet
+    fn handle_click(&mut self, path: &[usize], event: Event) -> bool {
+        match path.first() {
+            Some(&Spinner::DECREMENT) => self.step_by(-1, event.position),
+            Some(&Spinner::INCREMENT) => self.step_by(1, event.position),
+            _ => false,
+        }
+    }
+}
+
+// ColorPicker *************************************************************************************
+
+const COLOR_MARKER_SIZE: f32 = 6.;
+
+impl WebComponent for ColorPicker {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "ColorPicker");
+        let strip_width = text_size();
+        let side = strip_width * 8.;
+        let strip_x = side + MENU_PADDING;
+        let mut body = WebBody::new("ColorPicker");
+        // The hue, whitened to the left and darkened to the bottom
+        body.push(WebShape::Gradient { x: 0., y: 0., w: side, h: side, vertical: false,
+                                       stops: vec![(0., "rgb(255, 255, 255)".to_string()),
+                                                   (1., css_color(Color::from_hsv(self.hue, 1., 1.)))] });
+        body.push(WebShape::Gradient { x: 0., y: 0., w: side, h: side, vertical: true,
+                                       stops: vec![(0., "rgba(0, 0, 0, 0)".to_string()),
+                                                   (1., "rgb(0, 0, 0)".to_string())] });
+        let hues = (0..=6).map(|i| (i as f32 / 6., css_color(Color::from_hsv(i as f32 * 60., 1., 1.)))).collect();
+        body.push(WebShape::Gradient { x: strip_x, y: 0., w: strip_width, h: side, vertical: true, stops: hues });
+        push_forced_border(&mut body, 0., 0., side, side);
+        push_forced_border(&mut body, strip_x, 0., strip_width, side);
+        let half = COLOR_MARKER_SIZE / 2.;
+        let marker_color = if self.value > 0.5 { "rgb(0, 0, 0)" } else { CHECKMARK_COLOR };
+        body.push(WebShape::Outline { x: self.saturation * side - half, y: (1. - self.value) * side - half,
+                                      w: COLOR_MARKER_SIZE, h: COLOR_MARKER_SIZE, thickness: 1., color: marker_color });
+        body.push(WebShape::Outline { x: strip_x - 1., y: self.hue / 360. * side - half / 2., w: strip_width + 2.,
+                                      h: half, thickness: 1., color: TEXT_COLOR });
+        let swatch_x = strip_x + strip_width + MENU_PADDING;
+        let color = css_color(self.color());
+        body.push(WebShape::Gradient { x: swatch_x, y: 0., w: strip_width * 2., h: strip_width * 2., vertical: true,
+                                       stops: vec![(0., color)] });
+        body.push(WebShape::Outline { x: swatch_x, y: 0., w: strip_width * 2., h: strip_width * 2., thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        body.add_hit_area(0., 0., side, side, vec![ColorPicker::SQUARE]);
+        body.add_hit_area(strip_x, 0., strip_width, side, vec![ColorPicker::HUE]);
+        body
+    }
+}
+
+impl Component for ColorPicker {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Image *******************************************************************************************
+
+impl WebComponent for Image {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Image");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let mut body = WebBody::new("Image");
+        match &self.source {
+            ImageSource::Path(path) => body.push(WebShape::Image { src: path.clone(), x: 0., y: 0., w: width, h: height,
+                                                                   scale_mode: self.scale_mode, radius: 0.,
+                                                                   background: self.background.clone() }),
+            // Encoded bytes would need a blob URL, only paths are loaded on the web
+            ImageSource::Bytes(_) => body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                                                   color: CHECKBOX_BORDER_COLOR }),
+        }
+        body
+    }
+}
+
+impl Component for Image {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Canvas ******************************************************************************************
+
+const CANVAS_BACKGROUND_COLOR: &str = "rgba(0, 0, 0, 0)";
+
+impl WebComponent for Canvas {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Canvas");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let mut body = WebBody::new("Canvas");
+        // Invisible, the canvas takes its size whatever is drawn in it
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: CANVAS_BACKGROUND_COLOR });
+        if let Some(pattern) = &self.background {
+            body.push(WebShape::Pattern { x: 0., y: 0., w: width, h: height, pattern: pattern.clone() });
+        }
+        let mut drawing = WebBody::new("CanvasDrawing");
+        push_canvas_polygons(&mut drawing, &(self.draw)(width, height));
+        body.append_clipped_child(0, 0., 0., width, height, drawing);
+        body
+    }
+}
+
+fn push_canvas_polygons(body: &mut WebBody, polygons: &[CanvasPolygon]) {
+    // The 2D context can't blend the colors of the corners, a triangle gets their average
+    for triangle in polygons.iter().flat_map(CanvasPolygon::triangles) {
+        let average = |channel: fn(&Color) -> u8| {
+            (triangle.iter().map(|v| channel(&v.color) as u32).sum::<u32>() / 3) as u8
+        };
+        let color = Color { r: average(|c| c.r), g: average(|c| c.g), b: average(|c| c.b), a: average(|c| c.a) };
+        let points = triangle.iter().map(|v| (v.x, v.y)).collect();
+        body.push(WebShape::Polygon { points, color: css_color(color) });
+    }
+}
+
+impl Component for Canvas {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// SvgImage ****************************************************************************************
+
+/// The document is tessellated in the size it's shown at, so it stays crisp when scaled
+#[cfg(feature = "lyon")]
+impl WebComponent for SvgImage {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "SvgImage");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let mut body = WebBody::new("SvgImage");
+        // Invisible, the image takes its size whatever is drawn in it
+        body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: CANVAS_BACKGROUND_COLOR });
+        let mut drawing = WebBody::new("SvgDrawing");
+        push_canvas_polygons(&mut drawing, &self.polygons(width, height));
+        // Tiles and the parts of a Fill that are left over go past it
+        body.append_clipped_child(0, 0., 0., width, height, drawing);
+        body
+    }
+}
+
+#[cfg(feature = "lyon")]
+impl Component for SvgImage {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Charts ******************************************************************************************
+
+#[cfg(feature = "charts")]
+const CHART_RULE_COLOR: &str = "rgb(120, 120, 125)";
+#[cfg(feature = "charts")]
+const CHART_GRID_COLOR: &str = "rgba(120, 120, 125, 0.35)";
+#[cfg(feature = "charts")]
+const CHART_LINE_THICKNESS: f32 = 2.;
+
+/// The body of a chart laid out in width by height as shapes, with labels of size
+#[cfg(feature = "charts")]
+fn build_chart(name: &str, shapes: Vec<ChartShape>, width: f32, height: f32, size: f32) -> WebBody {
+    let mut body = WebBody::new(name);
+    // Invisible, the chart takes its size whatever is drawn in it
+    body.push(WebShape::Rect { x: 0., y: 0., w: width, h: height, color: CANVAS_BACKGROUND_COLOR });
+    for shape in shapes {
+        body.push(match shape {
+            ChartShape::Rule { x1, y1, x2, y2 } => {
+                WebShape::Lines { points: vec![(x1, y1), (x2, y2)], thickness: 1., color: CHART_RULE_COLOR.into() }
+            }
+            ChartShape::Rect { x, y, w, h, color } => WebShape::RoundedRect { x, y, w, h, radius: 0.,
+                                                                              color: css_color(color).into() },
+            ChartShape::Grid { x1, y1, x2, y2 } => WebShape::Stroke {
+                points: vec![(x1, y1), (x2, y2)],
+                closed: false,
+                style: StrokeStyle::dashed(1., &charts::GRID_DASHES),
+                color: CHART_GRID_COLOR.into(),
+            },
+            ChartShape::Polyline { points, color } => WebShape::Stroke {
+                points,
+                closed: false,
+                style: StrokeStyle::solid(CHART_LINE_THICKNESS).with_join(LineJoin::Round),
+                color: css_color(color).into(),
+            },
+            ChartShape::Polygon { points, color } => WebShape::Polygon { points, color: css_color(color) },
+            ChartShape::Label { text, x, y } => WebShape::Text { text, x, y, size, color: TEXT_COLOR.into() },
+        });
+    }
+    body
+}
+
+#[cfg(feature = "charts")]
+impl WebComponent for LineChart {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "LineChart");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let size = text_size() * charts::LABEL_SCALE;
+        build_chart("LineChart", self.shapes(width, height, size, &measure_text), width, height, size)
+    }
+}
+
+#[cfg(feature = "charts")]
+impl Component for LineChart {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "charts")]
+impl WebComponent for BarChart {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "BarChart");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let size = text_size() * charts::LABEL_SCALE;
+        build_chart("BarChart", self.shapes(width, height, size, &measure_text), width, height, size)
+    }
+}
+
+#[cfg(feature = "charts")]
+impl Component for BarChart {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "charts")]
+impl WebComponent for PieChart {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "PieChart");
+        let (parent_width, parent_height) = parent_size(parent);
+        let width = resolve_dimension(&self.width, parent_width);
+        let height = resolve_dimension(&self.height, parent_height);
+        let size = text_size() * charts::LABEL_SCALE;
+        build_chart("PieChart", self.shapes(width, height, size, &measure_text), width, height, size)
+    }
+}
+
+#[cfg(feature = "charts")]
+impl Component for PieChart {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        &self.width
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// DropZone ****************************************************************************************
+
+impl WebComponent for DropZone {
+    fn build(&self, parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "DropZone");
+        let (width, parent_height) = parent_size(parent);
+        let height = resolve_dimension(&self.height, parent_height);
+        let size = text_size();
+        let mut body = WebBody::new("DropZone");
+        // Browsers don't give the paths of dropped files, so it never lights up
+        body.push(WebShape::Outline { x: 0., y: 0., w: width, h: height, thickness: 1.,
+                                      color: CHECKBOX_BORDER_COLOR });
+        let hint = self.hint();
+        let lines = 1. + f32::from(u8::from(hint.is_some()));
+        let mut y = (height - lines * size) / 2.;
+        for (text, color) in [(Some(self.text.clone()), TEXT_COLOR), (hint, DISABLED_TEXT_COLOR)] {
+            if let Some(text) = text {
+                let (text_w, _) = measure_text(&text, size);
+                body.push(WebShape::Text { text, x: (width - text_w) / 2., y, size, color: color.into() });
+                y += size;
+            }
+        }
+        body
+    }
+}
+
+impl Component for DropZone {
+    fn get_height(&self) -> &Dimension {
+        &self.height
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+// Avatar ******************************************************************************************
+
+/// How big the initials are next to the size of the Avatar, see engines::sdl
+const AVATAR_INITIALS_SCALE: f32 = 0.4;
+
+impl WebComponent for Avatar {
+    fn build(&self, _parent: &dyn Component) -> WebBody {
+        let _span = trace_span!("build", component = "Avatar");
+        let (size, radius) = (self.size, self.corner_radius());
+        let mut body = WebBody::new("Avatar");
+        body.push(WebShape::RoundedRect { x: 0., y: 0., w: size, h: size, radius,
+                                          color: css_color(self.color()).into() });
+        // The initials stay under the image, they're seen until the browser loaded it
+        let initials = self.initials();
+        let text_size = size * AVATAR_INITIALS_SCALE;
+        let (text_w, text_h) = measure_text(&initials, text_size);
+        body.push(WebShape::Text { text: initials, x: (size - text_w) / 2., y: (size - text_h) / 2., size: text_size,
+                                   color: TEXT_COLOR.into() });
+        // Encoded bytes would need a blob URL like for an Image, they keep the initials
+        if let Some(ImageSource::Path(path)) = &self.source {
+            body.push(WebShape::Image { src: path.clone(), x: 0., y: 0., w: size, h: size, scale_mode: ScaleMode::Fill,
+                                        radius, background: None });
+        }
+        body
+    }
+}
+
+impl Component for Avatar {
+    fn get_height(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn get_width(&self) -> &Dimension {
+        todo!()
+    }
+
+    fn build_dyn(&self, parent: &dyn Component) -> Box<dyn NativeDrawable> {
+        Box::new(self.build(parent))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Component> {
+        Box::new(self.clone())
+    }
+}
+
+/// The image at src once the browser loaded it, the first call starts loading it
+fn loaded_image(src: &str) -> Option<HtmlImageElement> {
+    IMAGES.with(|images| {
+        let mut images = images.borrow_mut();
+        if !images.contains_key(src) {
+            let image = match HtmlImageElement::new() {
+                Ok(image) => image,
+                Err(e) => {
+                    log(&format!("Failed to create an image {:?}", e));
+                    return None;
+                }
+            };
+            image.set_src(src);
+            images.insert(src.to_string(), image);
+        }
+        images.get(src).filter(|i| i.complete() && i.natural_width() > 0).cloned()
+    })
+}
+
+/// Fills the rectangle with the pattern repeated from its top left corner
+fn fill_pattern(context: &CanvasRenderingContext2d, pattern: &Pattern, x: f32, y: f32, w: f32, h: f32) {
+    let fill = match pattern_fill(context, pattern) {
+        Some(fill) => fill,
+        None => return,
+    };
+    // Patterns repeat from the origin of the context
+    context.save();
+    context.set_fill_style_canvas_pattern(&fill);
+    if let Err(e) = context.translate(x as f64, y as f64) {
+        log(&format!("Failed to move a pattern {:?}", e));
+    }
+    context.fill_rect(0., 0., w as f64, h as f64);
+    context.restore();
+}
+
+/// The fill style repeating the tile of pattern, its canvas is made the first time. None when it
+/// can't be made
+fn pattern_fill(context: &CanvasRenderingContext2d, pattern: &Pattern) -> Option<CanvasPattern> {
+    let tile = PATTERNS.with(|patterns| {
+        if let Some((_, tile)) = patterns.borrow().iter().find(|(p, _)| p == pattern) {
+            return Ok(tile.clone());
+        }
+        let tile = pattern_tile(pattern)?;
+        patterns.borrow_mut().push((pattern.clone(), tile.clone()));
+        Ok::<_, String>(tile)
+    });
+    let fill = tile.and_then(|tile| {
+        context.create_pattern_with_html_canvas_element(&tile, "repeat")
+            .map_err(|e| format!("{:?}", e))?
+            .ok_or("The tile is empty".to_string())
+    });
+    fill.map_err(|e| log(&format!("Failed to make a pattern {}", e))).ok()
+}
+
+/// A canvas as big as the tile of pattern with its pixels
+fn pattern_tile(pattern: &Pattern) -> Result<HtmlCanvasElement, String> {
+    let document = browser_window()?.document().ok_or("The page has no document".to_string())?;
+    let tile = document.create_element("canvas")
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| "The element is not a canvas".to_string())?;
+    tile.set_width(pattern.width);
+    tile.set_height(pattern.height);
+    let context = tile.get_context("2d")
+        .map_err(|e| format!("{:?}", e))?
+        .ok_or("The tile has no 2D context".to_string())?
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(|_| "The 2D context is not a CanvasRenderingContext2d".to_string())?;
+    let pixels = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pattern.pixels), pattern.width, pattern.height)
+        .map_err(|e| format!("{:?}", e))?;
+    context.put_image_data(&pixels, 0., 0.).map_err(|e| format!("{:?}", e))?;
+    Ok(tile)
+}
+
+// Geometry ****************************************************************************************
+
+/// The same checkmark as engines::sdl::checkmark_polygon()
+fn checkmark_shape(x: f32, mid: f32, size: f32, color: &'static str) -> WebShape {
+    WebShape::Lines {
+        points: vec![(x, mid), (x + size / 4., mid + size / 4.), (x + size * 0.6, mid - size / 4.)],
+        thickness: size / 8.,
+        color: color.into(),
+    }
+}
+
+/// Used when a parent doesn't have a size in pixels, it should only happen with pseudo parents
+const FALLBACK_PARENT_SIZE: (f32, f32) = (800., 600.);
+
+/// Converts a Dimension into pixels, Relative dimensions take all the available space
+fn resolve_dimension(dimension: &Dimension, available: f32) -> f32 {
+    match dimension {
+        Dimension::Pixels(pixels) => *pixels as f32,
+        Dimension::Percentage(percentage) => available * *percentage as f32 / 100.,
+        Dimension::Relative(_) => available,
+    }
+}
+
+fn parent_size(parent: &dyn Component) -> (f32, f32) {
+    (resolve_dimension(parent.get_width(), FALLBACK_PARENT_SIZE.0),
+     resolve_dimension(parent.get_height(), FALLBACK_PARENT_SIZE.1))
+}
+
+fn pseudo_parent(width: f32, height: f32) -> Container {
+    Container {
+        width: Dimension::Pixels(width as i32),
+        height: Dimension::Pixels(height as i32),
+        ..Default::default()
+    }
+}
+
+// Text ********************************************************************************************
+
+/// The size of the text when a component doesn't specify one
+pub fn text_size() -> f32 {
+    20. * accessibility::font_scale()
+}
+
+/// The channels of a CSS rgb() or rgba() color and its alpha, None for any other kind of color
+fn css_rgb(color: &str) -> Option<(contrast::Rgb, Option<&str>)> {
+    let inner = color.strip_suffix(')')?;
+    let inner = inner.strip_prefix("rgba(").or_else(|| inner.strip_prefix("rgb("))?;
+    let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    let channel = |i: usize| parts.get(i).and_then(|p| p.parse::<u8>().ok());
+    Some(((channel(0)?, channel(1)?, channel(2)?), parts.get(3).copied()))
+}
+
+/// The CSS rgb() (or rgba() when it's translucent) of color
+fn css_color(color: Color) -> String {
+    match color {
+        Color { r, g, b, a: 255 } => format!("rgb({}, {}, {})", r, g, b),
+        Color { r, g, b, a } => format!("rgba({}, {}, {}, {})", r, g, b, a as f32 / 255.),
+    }
+}
+
+/// The CSS color to draw instead of color, mapped by the color mode of the accessibility
+/// preferences. Only rgb() and rgba() colors are mapped
+fn contrast_style(color: &str) -> String {
+    let mode = accessibility::color_mode();
+    if mode == ColorMode::Standard {
+        return color.to_string();
+    }
+    match css_rgb(color) {
+        Some((rgb, alpha)) => {
+            let (r, g, b) = mode.map(rgb);
+            match alpha {
+                Some(alpha) => format!("rgba({}, {}, {}, {})", r, g, b, alpha),
+                None => format!("rgb({}, {}, {})", r, g, b),
+            }
+        }
+        None => color.to_string(),
+    }
+}
+
+/// The visually hidden element of the page that screen readers read when its text changes, one
+/// for each politeness. It's created the first time
+fn live_region(live: Live) -> Result<Element, String> {
+    let politeness = match live {
+        Live::Assertive => "assertive",
+        _ => "polite",
+    };
+    let id = format!("rui-live-{}", politeness);
+    let document = browser_window()?.document().ok_or("The page has no document".to_string())?;
+    if let Some(region) = document.get_element_by_id(&id) {
+        return Ok(region);
+    }
+    let region = document.create_element("div")
+        .map_err(|e| format!("Failed to create a live region {:?}", e))?;
+    region.set_id(&id);
+    let attributes = [("aria-live", politeness), ("aria-atomic", "true"),
+                      ("style", "position: absolute; width: 1px; height: 1px; overflow: hidden; clip: rect(0 0 0 0)")];
+    for (name, value) in attributes {
+        region.set_attribute(name, value).map_err(|e| format!("Failed to set {} {:?}", name, e))?;
+    }
+    document.body().ok_or("The page has no body".to_string())?
+        .append_child(&region)
+        .map_err(|e| format!("Failed to add a live region {:?}", e))?;
+    Ok(region)
+}
+
+/// Outlines a widget in forced colors, where its background is the color of the window
+fn push_forced_border(body: &mut WebBody, x: f32, y: f32, w: f32, h: f32) {
+    if accessibility::forced_colors() {
+        body.push(WebShape::Outline { x, y, w, h, thickness: 1., color: TEXT_COLOR });
+    }
+}
+
+/// The text colors the engine draws and what it draws them on
+fn text_color_pairs() -> Vec<ColorPair> {
+    [("Window text", TEXT_COLOR, WINDOW_BACKGROUND),
+     ("Menu text", TEXT_COLOR, MENU_BACKGROUND),
+     ("Button text", TEXT_COLOR, BUTTON_BACKGROUND),
+     ("Pressed button text", TEXT_COLOR, BUTTON_PRESSED_BACKGROUND),
+     ("Text field", TEXT_COLOR, TEXT_FIELD_BACKGROUND),
+     ("Field error", ERROR_COLOR, WINDOW_BACKGROUND),
+     ("List row", TEXT_COLOR, LIST_ROW_BACKGROUND),
+     ("Alternate list row", TEXT_COLOR, LIST_ALTERNATE_ROW_BACKGROUND),
+     ("Selected row", TEXT_COLOR, SELECTED_BACKGROUND),
+     ("Table header", TEXT_COLOR, SECTION_HEADER_BACKGROUND),
+     ("Active tab", TEXT_COLOR, TAB_ACTIVE_BACKGROUND)]
+        .iter()
+        .filter_map(|(name, foreground, background)| {
+            Some(ColorPair::new(name, css_rgb(foreground)?.0, css_rgb(background)?.0))
+        })
+        .collect()
+}
+
+/// Tells about the text colors below WCAG AA and the variants that would pass
+fn warn_low_contrast() {
+    for issue in contrast::check(&text_color_pairs(), contrast::AA_NORMAL_TEXT) {
+        log(&format!("Low contrast: {}", issue));
+    }
+}
+
+fn font(size: f32) -> String {
+    styled_font(size, FontStyle::Regular)
+}
+
+/// The CSS font of the style, the browser picks the fonts
+fn styled_font(size: f32, style: FontStyle) -> String {
+    match style {
+        FontStyle::Regular => format!("{}px sans-serif", size),
+        FontStyle::Bold => format!("bold {}px sans-serif", size),
+        FontStyle::Italic => format!("italic {}px sans-serif", size),
+        FontStyle::Monospace => format!("{}px monospace", size),
+    }
+}
+
+/// Width and height that the text takes, measured by the canvas once there is one
+pub fn measure_text(text: &str, size: f32) -> (f32, f32) {
+    measure_styled_text(text, size, FontStyle::Regular)
+}
+
+/// Like measure_text() in the font of the style
+pub fn measure_styled_text(text: &str, size: f32, style: FontStyle) -> (f32, f32) {
+    let width = CONTEXT.with(|context| {
+        let context = context.borrow();
+        let context = context.as_ref()?;
+        context.set_font(&styled_font(size, style));
+        context.measure_text(text).ok().map(|metrics| metrics.width() as f32)
+    });
+    // Before that it takes the space it would take with a monospaced font
+    (width.unwrap_or(text.chars().count() as f32 * size * 0.6), size)
+}
+
@@ -0,0 +1,367 @@
+//! The dialog asking which file to open or where to save one. It's made of the widgets: a path
+//! bar with a button per folder from the root, the list of the folder, a field for the name of the
+//! file and a ComboBox of filters. The engine shows it over the window like a modal sub-window
+//! once it's posted:
+//!
+//! ```ignore
+//! let chooser = FileChooser::open("Open a picture")
+//!     .with_filter(FileFilter::new("Images", &["png", "jpg"]))
+//!     .on_choose(|_event, path| load(&path));
+//! ui_channel::post(UiMessage::ShowFileChooser(Box::new(chooser)));
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::elements::{ComboBox, Event, ListView, TextField};
+use crate::list_model::ListModel;
+use crate::selection::SelectModifiers;
+
+/// How many entries of the folder are shown at a time
+const VISIBLE_ENTRIES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileChooserMode {
+    /// Chooses a file that exists
+    Open,
+    /// Chooses a name for a file, which may exist already
+    Save,
+}
+
+/// Which files of a folder are listed, folders always are
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileFilter {
+    pub name: String,
+    /// Without the dot, none matches every file
+    pub extensions: Vec<String>,
+}
+
+impl FileFilter {
+    pub fn new(name: &str, extensions: &[&str]) -> Self {
+        FileFilter {
+            name: name.to_string(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    pub fn all_files() -> Self {
+        FileFilter::new("All files", &[])
+    }
+
+    /// Whether the extension of path is one of the filter, whatever its case
+    pub fn matches(&self, path: &Path) -> bool {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        self.extensions.is_empty() || self.extensions.iter().any(|e| e.eq_ignore_ascii_case(extension))
+    }
+
+    /// E.g. "Images (*.png, *.jpg)"
+    pub fn title(&self) -> String {
+        match self.extensions.is_empty() {
+            true => self.name.clone(),
+            false => {
+                let patterns: Vec<String> = self.extensions.iter().map(|e| format!("*.{}", e)).collect();
+                format!("{} ({})", self.name, patterns.join(", "))
+            }
+        }
+    }
+}
+
+/// A file or folder listed by the dialog
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// The folders of directory and its files that filter matches, each sorted by name. Hidden ones
+/// (starting with a dot) are left out
+pub fn read_directory(directory: &Path, filter: &FileFilter) -> Result<Vec<FileEntry>, String> {
+    let read = std::fs::read_dir(directory)
+        .map_err(|e| format!("Failed to read the folder {} {}", directory.display(), e))?;
+    let mut entries: Vec<FileEntry> = read
+        .filter_map(|entry| entry.ok())
+        .map(|entry| FileEntry {
+            name: entry.file_name().to_string_lossy().to_string(),
+            is_dir: entry.path().is_dir(),
+        })
+        .filter(|entry| !entry.name.starts_with('.'))
+        .filter(|entry| entry.is_dir || filter.matches(Path::new(&entry.name)))
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(entries)
+}
+
+/// The state of the dialog, its parts are hit tested as [PATH_BAR, folder], [ENTRIES, row],
+/// [FILE_NAME], [FILTERS, index], [CANCEL] and [CHOOSE]. Clicking a folder enters it and clicking
+/// a file puts its name in the field
+#[derive(Debug, Clone)]
+pub struct FileChooser {
+    pub mode: FileChooserMode,
+    pub title: String,
+    /// The folder that is listed
+    pub directory: PathBuf,
+    pub filters: Vec<FileFilter>,
+    /// The entries of directory, folders end with a slash
+    pub entries: ListView,
+    pub file_name: TextField,
+    /// Lists the filters, the selected one is applied
+    pub filter_box: ComboBox,
+    /// Why the last folder couldn't be read or the name couldn't be chosen, shown under the field
+    pub error: Option<String>,
+    /// Gets the chosen path, the dialog closes after it
+    pub on_choose: fn(Event, PathBuf) -> bool,
+    pub on_cancel: fn(Event) -> bool,
+    listed: Vec<FileEntry>,
+}
+
+impl FileChooser {
+    pub const PATH_BAR: usize = 0;
+    pub const ENTRIES: usize = 1;
+    pub const FILE_NAME: usize = 2;
+    pub const FILTERS: usize = 3;
+    pub const CANCEL: usize = 4;
+    pub const CHOOSE: usize = 5;
+
+    /// Chooses a file to open, starting in the current folder
+    pub fn open(title: &str) -> Self {
+        FileChooser::new(FileChooserMode::Open, title, "")
+    }
+
+    /// Chooses where to save a file, file_name is proposed
+    pub fn save(title: &str, file_name: &str) -> Self {
+        FileChooser::new(FileChooserMode::Save, title, file_name)
+    }
+
+    fn new(mode: FileChooserMode, title: &str, file_name: &str) -> Self {
+        let mut chooser = FileChooser {
+            mode,
+            title: title.to_string(),
+            directory: PathBuf::new(),
+            filters: vec![FileFilter::all_files()],
+            entries: ListView { visible_rows: VISIBLE_ENTRIES, ..Default::default() },
+            file_name: TextField { text: file_name.to_string(), editable: true, ..Default::default() },
+            filter_box: ComboBox { selected: Some(0), ..Default::default() },
+            error: None,
+            on_choose: |_event, _path| true,
+            on_cancel: |_event| true,
+            listed: vec![],
+        };
+        chooser.update_filters();
+        let start = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+        chooser.set_directory(&start);
+        chooser
+    }
+
+    pub fn with_directory(mut self, directory: &Path) -> Self {
+        self.set_directory(directory);
+        self
+    }
+
+    /// Adds a filter, the first one added replaces the default one listing all the files and is
+    /// selected
+    pub fn with_filter(mut self, filter: FileFilter) -> Self {
+        if self.filters == [FileFilter::all_files()] {
+            self.filters.clear();
+        }
+        self.filters.push(filter);
+        self.update_filters();
+        self.refresh();
+        self
+    }
+
+    pub fn on_choose(mut self, on_choose: fn(Event, PathBuf) -> bool) -> Self {
+        self.on_choose = on_choose;
+        self
+    }
+
+    pub fn on_cancel(mut self, on_cancel: fn(Event) -> bool) -> Self {
+        self.on_cancel = on_cancel;
+        self
+    }
+
+    fn update_filters(&mut self) {
+        self.filter_box.items = self.filters.iter().map(FileFilter::title).collect();
+        self.filter_box.selected = Some(self.filter_box.selected.unwrap_or(0).min(self.filters.len().saturating_sub(1)));
+    }
+
+    pub fn filter(&self) -> FileFilter {
+        self.filter_box.selected.and_then(|i| self.filters.get(i)).cloned().unwrap_or_else(FileFilter::all_files)
+    }
+
+    /// The listed files and folders
+    pub fn listed(&self) -> &[FileEntry] {
+        &self.listed
+    }
+
+    /// Lists directory, when it can't be read the error is shown and the folder stays. Returns
+    /// whether it was listed
+    pub fn set_directory(&mut self, directory: &Path) -> bool {
+        let directory = directory.canonicalize().unwrap_or_else(|_| directory.to_path_buf());
+        match read_directory(&directory, &self.filter()) {
+            Ok(listed) => {
+                self.entries.model = ListModel::new(listed.iter().map(|entry| match entry.is_dir {
+                    true => format!("{}/", entry.name),
+                    false => entry.name.clone(),
+                }).collect());
+                self.entries.scroll = 0;
+                self.listed = listed;
+                self.directory = directory;
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+
+    /// Lists the folder again, e.g. after the filter changed
+    pub fn refresh(&mut self) -> bool {
+        let directory = self.directory.clone();
+        self.set_directory(&directory)
+    }
+
+    /// The folders of the path bar from the root to the listed one
+    pub fn path_bar(&self) -> Vec<PathBuf> {
+        let mut folders: Vec<PathBuf> = self.directory.ancestors().map(Path::to_path_buf).collect();
+        folders.reverse();
+        folders
+    }
+
+    /// What the button of a folder of the path bar shows
+    pub fn folder_title(folder: &Path) -> String {
+        match folder.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => folder.display().to_string(),
+        }
+    }
+
+    /// Enters the folder at row or puts the name of the file at row in the field. Returns whether
+    /// anything changed
+    pub fn click_entry(&mut self, row: usize) -> bool {
+        let entry = match self.listed.get(row) {
+            Some(entry) => entry.clone(),
+            None => return false,
+        };
+        match entry.is_dir {
+            true => self.set_directory(&self.directory.join(&entry.name)),
+            false => {
+                self.entries.model.click(row, SelectModifiers::default());
+                self.file_name.text = entry.name;
+                true
+            }
+        }
+    }
+
+    /// Moves the selection of the list by delta rows, a file that gets selected is put in the field
+    pub fn move_selection(&mut self, delta: i32) -> bool {
+        let row = match self.entries.model.move_selection(delta) {
+            Some(row) => row,
+            None => return false,
+        };
+        self.entries.scroll_to(row);
+        if let Some(entry) = self.listed.get(row).filter(|e| !e.is_dir) {
+            self.file_name.text = entry.name.clone();
+        }
+        true
+    }
+
+    /// The path the field names, in the listed folder unless it's absolute. When saving without an
+    /// extension the first one of the filter is added
+    pub fn chosen_path(&self) -> Result<PathBuf, String> {
+        let name = self.file_name.text.trim();
+        if name.is_empty() {
+            return Err("Enter the name of a file".to_string());
+        }
+        let mut path = self.directory.join(name);
+        match self.mode {
+            FileChooserMode::Open if !path.is_file() => Err(format!("There is no file {}", name)),
+            FileChooserMode::Open => Ok(path),
+            FileChooserMode::Save => {
+                if let (None, Some(extension)) = (path.extension(), self.filter().extensions.first()) {
+                    path.set_extension(extension);
+                }
+                Ok(path)
+            }
+        }
+    }
+
+    /// Enters the folder the field names, otherwise calls on_choose with the chosen path or shows
+    /// why there is none. Returns whether a path was chosen, the dialog closes then
+    pub fn choose(&mut self, position: Option<(i32, i32)>) -> bool {
+        let named = self.directory.join(self.file_name.text.trim());
+        if !self.file_name.text.trim().is_empty() && named.is_dir() {
+            self.file_name.text.clear();
+            self.set_directory(&named);
+            return false;
+        }
+        match self.chosen_path() {
+            Ok(path) => {
+                (self.on_choose)(Event { source: "FileChooser".to_string(), position }, path);
+                true
+            }
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+
+    pub fn cancel(&mut self, position: Option<(i32, i32)>) -> bool {
+        (self.on_cancel)(Event { source: "FileChooser".to_string(), position })
+    }
+
+    /// Clicks on the path bar, the list, the field and the filters, see CANCEL and CHOOSE for the
+    /// buttons. The popup of the filters closes on a click anywhere else. Returns whether anything
+    /// changed
+    pub fn click(&mut self, path: &[usize], position: Option<(i32, i32)>) -> bool {
+        let closed = path.first() != Some(&Self::FILTERS) && std::mem::replace(&mut self.filter_box.open, false);
+        closed | match path {
+            [Self::PATH_BAR, folder] => match self.path_bar().get(*folder) {
+                Some(folder) => self.set_directory(folder),
+                None => false,
+            },
+            [Self::ENTRIES, row] => self.click_entry(*row),
+            [Self::FILTERS] => {
+                self.filter_box.open = !self.filter_box.open;
+                true
+            }
+            [Self::FILTERS, index] => {
+                self.filter_box.select(*index, position);
+                self.refresh()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folders_are_listed_first_and_files_are_filtered() {
+        let dir = std::env::temp_dir().join(format!("rui-lopez-chooser-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("photos")).unwrap();
+        for file in ["notes.txt", "b.PNG", "a.png", ".hidden.png"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        let mut chooser = FileChooser::save("Export", "picture")
+            .with_filter(FileFilter::new("Images", &["png"]))
+            .with_directory(&dir);
+        assert_eq!(chooser.entries.model.items(), &["photos/", "a.png", "b.PNG"]);
+        assert!(chooser.click_entry(2));
+        assert_eq!(chooser.file_name.text, "b.PNG");
+        chooser.file_name.text = "picture".to_string();
+        assert_eq!(chooser.chosen_path(), Ok(dir.canonicalize().unwrap().join("picture.png")));
+        assert!(chooser.click_entry(0));
+        assert!(chooser.listed().is_empty(), "In photos");
+        assert!(chooser.click(&[FileChooser::PATH_BAR, chooser.path_bar().len() - 2], None));
+        let mut open = FileChooser::open("Open").with_directory(&dir);
+        open.file_name.text = "missing.txt".to_string();
+        assert!(!open.choose(None));
+        assert_eq!(open.error.as_deref(), Some("There is no file missing.txt"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
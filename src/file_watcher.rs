@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Notices when watched files change by polling their modification time, cheap enough to be
+/// polled every frame since it only looks at the files once per interval
+#[derive(Debug, Clone)]
+pub struct FileWatcher {
+    /// Last seen modification time, None while the file doesn't exist
+    files: HashMap<PathBuf, Option<SystemTime>>,
+    pub interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl FileWatcher {
+    pub fn new(interval: Duration) -> Self {
+        FileWatcher {
+            files: HashMap::new(),
+            interval,
+            last_poll: None,
+        }
+    }
+
+    pub fn watch<P: Into<PathBuf>>(&mut self, path: P) {
+        let path = path.into();
+        let time = modified(&path);
+        self.files.insert(path, time);
+    }
+
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) {
+        self.files.remove(path.as_ref());
+    }
+
+    /// The files that were modified, created or removed since the last poll, nothing until the
+    /// interval passed
+    pub fn poll(&mut self, now: Instant) -> Vec<PathBuf> {
+        if self.last_poll.is_some_and(|l| now.saturating_duration_since(l) < self.interval) {
+            return vec![];
+        }
+        self.last_poll = Some(now);
+        let mut changed = vec![];
+        for (path, time) in self.files.iter_mut() {
+            let current = modified(path);
+            if current != *time {
+                *time = current;
+                changed.push(path.clone());
+            }
+        }
+        changed.sort();
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn created_and_removed_files_are_changes() {
+        let file = std::env::temp_dir().join(format!("rui-lopez-watched-{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let mut watcher = FileWatcher::new(Duration::from_secs(1));
+        watcher.watch(&file);
+        let start = Instant::now();
+        assert!(watcher.poll(start).is_empty());
+        std::fs::write(&file, "a").unwrap();
+        assert!(watcher.poll(start).is_empty(), "Polled again before the interval");
+        assert_eq!(watcher.poll(start + Duration::from_secs(1)), vec![file.clone()]);
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(watcher.poll(start + Duration::from_secs(2)), vec![file]);
+    }
+}
@@ -0,0 +1,79 @@
+/// A part of the window Tab cycles in while it's the innermost scope, e.g. a modal dialog or a
+/// named region of the container
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusScope {
+    pub name: String,
+    /// Path in the container of the region holding the targets, None when nothing of the
+    /// container can take the focus (e.g. a dialog drawn by the engine)
+    pub region: Option<Vec<usize>>,
+    /// What had the focus when the scope was opened, it gets it back when the scope closes
+    restore: Option<Vec<usize>>,
+}
+
+/// The stack of open focus scopes, without any Tab cycles through the whole container
+#[derive(Debug, Clone, Default)]
+pub struct FocusScopes {
+    scopes: Vec<FocusScope>,
+}
+
+impl FocusScopes {
+    /// Confines Tab to region until pop() is called with the same name, focused is restored then
+    pub fn push(&mut self, name: &str, region: Option<Vec<usize>>, focused: Option<Vec<usize>>) {
+        self.scopes.push(FocusScope { name: name.to_string(), region, restore: focused });
+    }
+
+    /// Closes the innermost scope named name and the ones opened after it, returns what has to get
+    /// the focus back
+    pub fn pop(&mut self, name: &str) -> Option<Vec<usize>> {
+        let index = self.scopes.iter().rposition(|s| s.name == name)?;
+        self.scopes.split_off(index).into_iter().next()?.restore
+    }
+
+    pub fn innermost(&self) -> Option<&FocusScope> {
+        self.scopes.last()
+    }
+
+    /// The target after focused (before it when going backwards) in the innermost scope, wrapping
+    /// around. targets are the paths that can take the focus in visual order, see
+    /// Container::focusable_paths()
+    pub fn cycle(&self, targets: &[Vec<usize>], focused: Option<&[usize]>,
+                 backwards: bool) -> Option<Vec<usize>> {
+        let region: &[usize] = match self.innermost() {
+            Some(scope) => scope.region.as_deref()?,
+            None => &[],
+        };
+        let targets: Vec<&Vec<usize>> = targets.iter().filter(|t| t.starts_with(region)).collect();
+        let len = targets.len();
+        if len == 0 {
+            return None;
+        }
+        let current = focused.and_then(|f| targets.iter().position(|t| t.as_slice() == f));
+        let index = match (current, backwards) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        Some(targets[index].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_stays_in_the_innermost_scope() {
+        let targets = vec![vec![0], vec![1, 0], vec![1, 1], vec![2]];
+        let mut scopes = FocusScopes::default();
+        assert_eq!(scopes.cycle(&targets, Some(&[2]), false), Some(vec![0]));
+        scopes.push("Region", Some(vec![1]), Some(vec![2]));
+        assert_eq!(scopes.cycle(&targets, Some(&[2]), false), Some(vec![1, 0]));
+        assert_eq!(scopes.cycle(&targets, Some(&[1, 0]), true), Some(vec![1, 1]));
+        scopes.push("Dialog", None, Some(vec![1, 1]));
+        assert_eq!(scopes.cycle(&targets, Some(&[1, 1]), false), None);
+        assert_eq!(scopes.pop("Region"), Some(vec![2]), "Closing a scope closes the ones inside it");
+        assert_eq!(scopes.innermost(), None);
+        assert_eq!(scopes.pop("Region"), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! Pixel diffs of two frames, to check that a state change redraws what it should and nothing
+//! else. With EngineConfig.debug_overlays the SDL engine captures a frame on Ctrl+Shift+D, another
+//! one on the next Ctrl+Shift+D and shows where they differ as a heatmap over the window until the
+//! third. The remote-debug inspector does the same with `diff`
+
+use std::fmt::{Display, Formatter};
+
+/// A frame as it was presented
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA, row after row
+    pub pixels: Vec<u8>,
+}
+
+/// A cell of the heatmap, in pixels of the frames
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatCell {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+    /// The fraction of the pixels of the cell that changed
+    pub heat: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameDiff {
+    pub width: u32,
+    pub height: u32,
+    /// How much each pixel changed from 0 to 1, its largest change of a channel
+    pub changes: Vec<f32>,
+}
+
+impl FrameDiff {
+    /// Fails when the frames don't have the same size, e.g. the window was resized in between
+    pub fn new(before: &Frame, after: &Frame) -> Result<FrameDiff, String> {
+        if (before.width, before.height) != (after.width, after.height) {
+            return Err(format!("The frames have different sizes, {}x{} and {}x{}", before.width, before.height,
+                               after.width, after.height));
+        }
+        let changes = before.pixels.chunks_exact(4).zip(after.pixels.chunks_exact(4))
+            .map(|(a, b)| a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0) as f32 / 255.)
+            .collect();
+        Ok(FrameDiff { width: before.width, height: before.height, changes })
+    }
+
+    pub fn changed_pixels(&self) -> usize {
+        self.changes.iter().filter(|c| **c > 0.).count()
+    }
+
+    /// The smallest rectangle containing every changed pixel as (x, y, width, height)
+    pub fn bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        let width = self.width.max(1) as usize;
+        let changed = self.changes.iter().enumerate().filter(|(_, c)| **c > 0.)
+            .map(|(i, _)| ((i % width) as u32, (i / width) as u32));
+        changed.fold(None, |bounds, (x, y)| match bounds {
+            None => Some((x, y, x, y)),
+            Some((min_x, min_y, max_x, max_y)) => Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))),
+        }).map(|(min_x, min_y, max_x, max_y)| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+    }
+
+    /// The cells of cell pixels (smaller at the edges) where something changed
+    pub fn heatmap(&self, cell: u32) -> Vec<HeatCell> {
+        let cell = cell.max(1);
+        let mut cells = vec![];
+        for y in (0..self.height).step_by(cell as usize) {
+            for x in (0..self.width).step_by(cell as usize) {
+                let (w, h) = (cell.min(self.width - x), cell.min(self.height - y));
+                let changed = (y..y + h)
+                    .flat_map(|row| (x..x + w).map(move |column| (row * self.width + column) as usize))
+                    .filter(|i| self.changes[*i] > 0.)
+                    .count();
+                if changed > 0 {
+                    cells.push(HeatCell { x, y, w, h, heat: changed as f32 / (w * h) as f32 });
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// E.g. "1200 pixels changed in (10, 20, 100, 30)"
+impl Display for FrameDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.bounds() {
+            Some(bounds) => write!(f, "{} pixels changed in {:?}", self.changed_pixels(), bounds),
+            None => write!(f, "Nothing changed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_locate_the_changed_pixels() {
+        let frame = |changed: &[usize]| Frame {
+            width: 4,
+            height: 3,
+            pixels: (0..12).flat_map(|i| match changed.contains(&i) {
+                true => [255, 0, 0, 255],
+                false => [0, 0, 0, 255],
+            }).collect(),
+        };
+        let diff = FrameDiff::new(&frame(&[]), &frame(&[5, 6, 11])).unwrap();
+        assert_eq!(diff.changed_pixels(), 3);
+        assert_eq!(diff.bounds(), Some((1, 1, 3, 2)));
+        assert_eq!(diff.heatmap(2), vec![HeatCell { x: 0, y: 0, w: 2, h: 2, heat: 0.25 },
+                                         HeatCell { x: 2, y: 0, w: 2, h: 2, heat: 0.25 },
+                                         HeatCell { x: 2, y: 2, w: 2, h: 1, heat: 0.5 }]);
+        assert_eq!(FrameDiff::new(&frame(&[]), &frame(&[])).unwrap().to_string(), "Nothing changed");
+        assert!(FrameDiff::new(&frame(&[]), &Frame { width: 3, height: 4, pixels: vec![] }).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::ops::Range;
+
+/// How well a query matched a text, ranges are the matched characters (char indexes, not bytes)
+/// merged when consecutive so widgets can highlight them
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    pub ranges: Vec<Range<usize>>,
+}
+
+const MATCH_SCORE: i32 = 1;
+const CONSECUTIVE_BONUS: i32 = 5;
+const WORD_START_BONUS: i32 = 8;
+const FIRST_CHAR_BONUS: i32 = 10;
+const GAP_PENALTY: i32 = 1;
+const MAX_GAP_PENALTY: i32 = 5;
+
+/// Matches the characters of query in order inside text ignoring case and whitespace of the
+/// query, None when some character is missing. Matches at the start of words and consecutive
+/// matches score higher, gaps lower
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let text: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let mut score = 0;
+    let mut ranges: Vec<Range<usize>> = vec![];
+    let mut next = 0;
+    let query: Vec<char> = query.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    for (k, &q) in query.iter().enumerate() {
+        // The rest of the query still has to fit after the character chosen for q
+        let fits = |i: usize| is_subsequence(&query[k + 1..], &lower[i + 1..]);
+        // Prefers continuing the current run, then the start of a word, then the earliest
+        // occurrence, which fits whenever any does
+        let continues = ranges.last().is_some_and(|r| r.end == next) && lower.get(next) == Some(&q)
+            && fits(next);
+        let found = if continues {
+            next
+        } else {
+            (next..lower.len()).find(|i| lower[*i] == q && is_word_start(&text, *i) && fits(*i))
+                .or_else(|| (next..lower.len()).find(|i| lower[*i] == q))?
+        };
+        score += MATCH_SCORE;
+        if found == 0 {
+            score += FIRST_CHAR_BONUS;
+        } else if is_word_start(&text, found) {
+            score += WORD_START_BONUS;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end == found => {
+                range.end += 1;
+                score += CONSECUTIVE_BONUS;
+            }
+            _ => {
+                score -= ((found - next) as i32 * GAP_PENALTY).min(MAX_GAP_PENALTY);
+                ranges.push(found..found + 1);
+            }
+        }
+        next = found + 1;
+    }
+    Some(FuzzyMatch { score, ranges })
+}
+
+fn is_subsequence(query: &[char], text: &[char]) -> bool {
+    let mut text = text.iter();
+    query.iter().all(|q| text.any(|c| c == q))
+}
+
+fn is_word_start(text: &[char], i: usize) -> bool {
+    match i {
+        0 => true,
+        _ => {
+            let (previous, current) = (text[i - 1], text[i]);
+            !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase())
+        }
+    }
+}
+
+/// The indexes of the texts matching query with their match, best first. Equal scores keep the
+/// order of the texts
+pub fn rank<'a, I: IntoIterator<Item=&'a str>>(query: &str, texts: I) -> Vec<(usize, FuzzyMatch)> {
+    let mut ranked: Vec<(usize, FuzzyMatch)> = texts.into_iter().enumerate()
+        .filter_map(|(i, text)| fuzzy_match(query, text).map(|m| (i, m)))
+        .collect();
+    ranked.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_starts_are_preferred() {
+        let m = fuzzy_match("sf", "Save File").unwrap();
+        assert_eq!(m.ranges, vec![0..1, 5..6]);
+        assert_eq!(fuzzy_match("xyz", "Save File"), None);
+        let ranked = rank("of", ["Toggle Overflow", "Open File"]);
+        assert_eq!(ranked.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![1, 0]);
+    }
+
+    #[test]
+    fn consecutive_characters_are_merged() {
+        let m = fuzzy_match("ope", "Open File").unwrap();
+        assert_eq!((m.ranges.len(), m.ranges.first()), (1, Some(&(0..3))));
+    }
+
+    #[test]
+    fn word_starts_that_leave_no_room_are_skipped() {
+        let m = fuzzy_match("port", "Export Pdf").unwrap();
+        assert_eq!((m.ranges.len(), m.ranges.first()), (1, Some(&(2..6))));
+        assert_eq!(fuzzy_match("port", "Export").unwrap().ranges, m.ranges);
+    }
+}
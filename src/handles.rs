@@ -0,0 +1,167 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt::{Debug, Formatter};
+use std::rc::{Rc, Weak};
+
+use crate::elements::Component;
+
+type SharedComponent = Rc<RefCell<Box<dyn Component>>>;
+
+/// A shared, owning handle to a Component kept by application state, e.g. a widget model the app
+/// edits and copies into the Windows it builds. The handle owns the component, it doesn't reach
+/// the copy inside a built Window. What may outlive the window it's for (timers, threads, other
+/// windows) should keep a WeakComponentHandle instead, so it doesn't keep the component alive
+#[derive(Clone)]
+pub struct ComponentHandle {
+    inner: SharedComponent,
+}
+
+/// The non-owning counterpart of ComponentHandle, it has to be upgraded before being used and the
+/// upgrade fails once every ComponentHandle to the same Component has been dropped
+#[derive(Clone)]
+pub struct WeakComponentHandle {
+    inner: Weak<RefCell<Box<dyn Component>>>,
+}
+
+impl ComponentHandle {
+    /// Wraps the component into a handle, window is the id of the window it's for (e.g.
+    /// SDLWindow.id()), it's only used by the leak detector to tell which window the component
+    /// belonged to
+    pub fn new<T: Component>(component: T, window: u32) -> Self {
+        let inner: SharedComponent = Rc::new(RefCell::new(Box::new(component)));
+        leak_detector::track(window, std::any::type_name::<T>(), &inner);
+        ComponentHandle { inner }
+    }
+
+    pub fn downgrade(&self) -> WeakComponentHandle {
+        WeakComponentHandle {
+            inner: Rc::downgrade(&self.inner),
+        }
+    }
+
+    /// # Panics
+    /// When the component is currently mutably borrowed, same as RefCell.borrow()
+    pub fn borrow(&self) -> Ref<'_, Box<dyn Component>> {
+        self.inner.borrow()
+    }
+
+    /// # Panics
+    /// When the component is currently borrowed, same as RefCell.borrow_mut()
+    pub fn borrow_mut(&self) -> RefMut<'_, Box<dyn Component>> {
+        self.inner.borrow_mut()
+    }
+
+    pub fn ptr_eq(&self, other: &ComponentHandle) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl WeakComponentHandle {
+    /// Returns None when the component is already gone, callbacks are expected to just do nothing
+    /// in that case
+    pub fn upgrade(&self) -> Option<ComponentHandle> {
+        self.inner.upgrade().map(|inner| ComponentHandle { inner })
+    }
+}
+
+impl Debug for ComponentHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.inner.try_borrow() {
+            Ok(component) => write!(f, "ComponentHandle {{ {:?} }}", component),
+            Err(_) => write!(f, "ComponentHandle {{ <borrowed> }}"),
+        }
+    }
+}
+
+impl Debug for WeakComponentHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WeakComponentHandle {{ alive: {} }}", self.inner.strong_count() > 0)
+    }
+}
+
+/// Keeps a list of every handle created on this thread so the engine can tell, when a window is
+/// closed, which of its components are still alive, meaning something is holding a strong
+/// ComponentHandle. It's only active on debug builds and in tests
+pub mod leak_detector {
+    use std::cell::RefCell;
+    use std::rc::Weak;
+
+    use super::SharedComponent;
+    use crate::elements::Component;
+
+    struct TrackedComponent {
+        window: u32,
+        type_name: &'static str,
+        component: Weak<RefCell<Box<dyn Component>>>,
+    }
+
+    thread_local! {
+        static TRACKED: RefCell<Vec<TrackedComponent>> = const { RefCell::new(vec![]) };
+    }
+
+    pub(crate) fn track(window: u32, type_name: &'static str, component: &SharedComponent) {
+        if cfg!(any(debug_assertions, test)) {
+            TRACKED.with(|tracked| {
+                let mut tracked = tracked.borrow_mut();
+                tracked.retain(|t| t.component.strong_count() > 0);
+                tracked.push(TrackedComponent {
+                    window,
+                    type_name,
+                    component: std::rc::Rc::downgrade(component),
+                });
+            });
+        }
+    }
+
+    /// Type names of the components created for the window with the id that are still alive
+    pub fn leaked_components(window: u32) -> Vec<&'static str> {
+        TRACKED.with(|tracked| {
+            tracked.borrow().iter()
+                .filter(|t| t.window == window && t.component.strong_count() > 0)
+                .map(|t| t.type_name)
+                .collect()
+        })
+    }
+
+    /// Warns about the components that outlived their window, called by the engine when a window
+    /// is dropped. title only names the window in the warning
+    pub fn report_leaks(window: u32, title: &str) {
+        if cfg!(debug_assertions) {
+            let leaked = leaked_components(window);
+            if !leaked.is_empty() {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(window, title, ?leaked, "Window was closed but these components are still alive");
+                #[cfg(not(feature = "tracing"))]
+                let _ = title;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::elements::TextField;
+
+    use super::*;
+
+    #[test]
+    fn weak_handle_does_not_keep_component_alive() {
+        let handle = ComponentHandle::new(TextField::default(), 1);
+        let weak = handle.downgrade();
+        assert!(weak.upgrade().is_some());
+        drop(handle);
+        assert!(weak.upgrade().is_none());
+        assert!(leak_detector::leaked_components(1).is_empty());
+    }
+
+    #[test]
+    fn strong_handle_is_reported_as_leak() {
+        let handle = ComponentHandle::new(TextField::default(), 2);
+        // Windows are told apart by id, whatever their titles
+        let other = ComponentHandle::new(TextField::default(), 3);
+        assert_eq!(leak_detector::leaked_components(2).len(), 1);
+        drop(handle);
+        assert!(leak_detector::leaked_components(2).is_empty());
+        assert_eq!(leak_detector::leaked_components(3).len(), 1);
+        drop(other);
+    }
+}
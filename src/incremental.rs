@@ -0,0 +1,107 @@
+//! Jobs too long for a frame split into chunks run in the time frames have left, so a big job
+//! (building the rows of a huge model, shaping a long text, repacking a cache...) never makes the
+//! window miss a frame. A job is called once per chunk until it returns that it has nothing left:
+//!
+//! ```ignore
+//! let mut rows = model.rows().into_iter();
+//! incremental::spawn("index", move || {
+//!     rows.by_ref().take(100).for_each(|row| index.add(row));
+//!     rows.len() > 0
+//! });
+//! ```
+//!
+//! The jobs take turns a chunk at a time. The SDL main_loop() runs chunks once the frame is
+//! presented until EngineConfig.work_budget or the time before the next frame runs out, the web
+//! engine at the end of update(), embedding apps call run() themselves. Chunks run on the UI
+//! thread so they can use the text pipeline, a chunk always runs to its end so keep them short
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+type Job = Box<dyn FnMut() -> bool>;
+
+thread_local! {
+    /// The jobs in the order they get their next chunk
+    static JOBS: RefCell<VecDeque<(String, Job)>> = RefCell::new(VecDeque::new());
+}
+
+/// Adds a job, replacing the one with the same name. job runs a chunk and returns whether there
+/// is more to do
+pub fn spawn(name: &str, job: impl FnMut() -> bool + 'static) {
+    cancel(name);
+    JOBS.with(|jobs| jobs.borrow_mut().push_back((name.to_string(), Box::new(job))));
+}
+
+/// Returns whether the job was still pending. A job can't cancel itself from its chunk, it
+/// returns false instead
+pub fn cancel(name: &str) -> bool {
+    JOBS.with(|jobs| {
+        let mut jobs = jobs.borrow_mut();
+        let before = jobs.len();
+        jobs.retain(|(pending, _)| pending != name);
+        jobs.len() != before
+    })
+}
+
+pub fn is_pending(name: &str) -> bool {
+    JOBS.with(|jobs| jobs.borrow().iter().any(|(pending, _)| pending == name))
+}
+
+pub fn pending() -> usize {
+    JOBS.with(|jobs| jobs.borrow().len())
+}
+
+/// Runs chunks of the jobs in turn while has_time() says there's time left in the frame. A chunk
+/// runs even when there's none so the jobs move on in slow frames too. Returns how many ran
+pub fn run(mut has_time: impl FnMut() -> bool) -> usize {
+    let mut count = 0;
+    while count == 0 || has_time() {
+        // The job is taken out while it runs so it can spawn others
+        let (name, mut job) = match JOBS.with(|jobs| jobs.borrow_mut().pop_front()) {
+            Some(next) => next,
+            None => break,
+        };
+        let more = job();
+        count += 1;
+        if more && !is_pending(&name) {
+            JOBS.with(|jobs| jobs.borrow_mut().push_back((name, job)));
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn jobs_take_turns_until_the_frame_is_over() {
+        let log = Rc::new(RefCell::new(vec![]));
+        let job = |name: &'static str, chunks: usize| {
+            let (log, mut left) = (log.clone(), chunks);
+            move || {
+                log.borrow_mut().push(name);
+                left -= 1;
+                left > 0
+            }
+        };
+        spawn("a", job("a", 3));
+        spawn("b", job("b", 1));
+        assert_eq!(run(|| false), 1, "A chunk even without time");
+        let mut budget = 2;
+        assert_eq!(run(|| {
+            budget -= 1;
+            budget >= 0
+        }), 3);
+        assert_eq!(*log.borrow(), ["a", "b", "a", "a"]);
+        assert_eq!(pending(), 0);
+        spawn("c", job("c", 5));
+        spawn("c", job("c2", 5));
+        run(|| false);
+        assert!(cancel("c") && !is_pending("c"));
+        assert_eq!(log.borrow().last(), Some(&"c2"));
+        assert_eq!(run(|| true), 0);
+    }
+}
@@ -0,0 +1,106 @@
+//! Warnings about the layout the engines find after building a window: components that take no
+//! space, that are laid out outside of the window or that overlap their siblings. They're usually
+//! layout bugs, e.g. a Dimension resolving to 0 or a component drawing above its origin. The
+//! engines log them when EngineConfig.layout_warnings is set and the remote-debug inspector lists
+//! them with `warnings`
+
+use std::fmt::{Display, Formatter};
+
+/// Overlaps thinner than this are rounding, not a bug
+const OVERLAP_TOLERANCE: f32 = 1.;
+
+/// Where a container laid out one of its children, in window coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutBox {
+    /// Where the component is in the container of the window
+    pub path: Vec<usize>,
+    /// The name of the body it built, e.g. "Label"
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    /// Inside a clipped body like the content of a ScrollPane, where being out of sight is expected
+    pub clipped: bool,
+}
+
+impl LayoutBox {
+    fn overlap(&self, other: &LayoutBox) -> (f32, f32) {
+        let w = (self.x + self.w).min(other.x + other.w) - self.x.max(other.x);
+        let h = (self.y + self.h).min(other.y + other.h) - self.y.max(other.y);
+        (w, h)
+    }
+
+    fn is_sibling_of(&self, other: &LayoutBox) -> bool {
+        self.path.len() == other.path.len() && self.path.split_last().map(|(_, parent)| parent)
+            == other.path.split_last().map(|(_, parent)| parent)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutWarning {
+    ZeroArea { path: Vec<usize>, name: String },
+    Offscreen { path: Vec<usize>, name: String },
+    Overlap { first: Vec<usize>, second: Vec<usize> },
+}
+
+impl Display for LayoutWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutWarning::ZeroArea { path, name } => write!(f, "{} at {:?} takes no space", name, path),
+            LayoutWarning::Offscreen { path, name } => write!(f, "{} at {:?} is outside of the window", name, path),
+            LayoutWarning::Overlap { first, second } => write!(f, "{:?} and {:?} overlap", first, second),
+        }
+    }
+}
+
+/// The warnings about boxes laid out in a window of width by height, in the order of the boxes
+pub fn check(boxes: &[LayoutBox], width: f32, height: f32) -> Vec<LayoutWarning> {
+    let mut warnings = vec![];
+    for (i, layout_box) in boxes.iter().enumerate() {
+        let (path, name) = (layout_box.path.clone(), layout_box.name.clone());
+        if layout_box.w <= 0. || layout_box.h <= 0. {
+            warnings.push(LayoutWarning::ZeroArea { path, name });
+            continue;
+        }
+        let outside = layout_box.x >= width || layout_box.y >= height
+            || layout_box.x + layout_box.w <= 0. || layout_box.y + layout_box.h <= 0.;
+        if outside && !layout_box.clipped {
+            warnings.push(LayoutWarning::Offscreen { path, name });
+        }
+        for other in boxes[i + 1..].iter().filter(|o| o.is_sibling_of(layout_box) && o.w > 0. && o.h > 0.) {
+            let (w, h) = layout_box.overlap(other);
+            if w > OVERLAP_TOLERANCE && h > OVERLAP_TOLERANCE {
+                warnings.push(LayoutWarning::Overlap { first: layout_box.path.clone(), second: other.path.clone() });
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_offscreen_and_overlapping_boxes_are_warned_about() {
+        let layout_box = |path: Vec<usize>, y: f32, h: f32, clipped: bool| LayoutBox {
+            path,
+            name: "Label".to_string(),
+            x: 10.,
+            y,
+            w: 100.,
+            h,
+            clipped,
+        };
+        let boxes = [layout_box(vec![0], 10., 20., false), layout_box(vec![1], 25., 20., false),
+                     layout_box(vec![2], 50., 0., false), layout_box(vec![3, 0], 700., 20., false),
+                     layout_box(vec![3, 1], 730., 20., true), layout_box(vec![4], 48., 20., false)];
+        assert_eq!(check(&boxes, 800., 600.), vec![
+            LayoutWarning::Overlap { first: vec![0], second: vec![1] },
+            LayoutWarning::ZeroArea { path: vec![2], name: "Label".to_string() },
+            LayoutWarning::Offscreen { path: vec![3, 0], name: "Label".to_string() },
+        ]);
+        assert_eq!(check(&boxes, 800., 600.)[0].to_string(), "[0] and [1] overlap");
+    }
+}
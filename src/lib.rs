@@ -1,8 +1,93 @@
 #[macro_use]
 extern crate mopa;
 
+/// Opens a tracing span that lasts until the returned guard is dropped, when the tracing feature
+/// is disabled it expands to a guard that does nothing
+macro_rules! trace_span {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!($($arg)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let span = crate::NoSpan;
+        span
+    }};
+}
+
+/// Attributes the allocations done until the returned guard is dropped to the given subsystem,
+/// unless a nested scope takes them. It does nothing unless the alloc-counter feature is enabled
+macro_rules! alloc_scope {
+    ($name:expr) => {{
+        #[cfg(feature = "alloc-counter")]
+        let scope = crate::alloc_counter::AllocScope::new($name);
+        #[cfg(not(feature = "alloc-counter"))]
+        let scope = crate::NoSpan;
+        scope
+    }};
+}
+
+#[cfg(any(not(feature = "tracing"), not(feature = "alloc-counter")))]
+pub(crate) struct NoSpan;
+
+pub mod accessibility;
+pub mod accessibility_tree;
+#[cfg(feature = "alloc-counter")]
+pub mod alloc_counter;
+pub mod ansi;
+pub mod app_info;
+pub mod assets;
+pub mod calendar;
+pub mod caret;
+#[cfg(feature = "charts")]
+pub mod charts;
+pub mod columns;
+pub mod commands;
+pub mod context;
+pub mod contrast;
+pub mod deep_links;
+pub mod documents;
 pub mod elements;
 pub mod engines;
+pub mod file_chooser;
+pub mod file_watcher;
+pub mod focus;
+pub mod frame_diff;
+pub mod fuzzy;
+pub mod handles;
+pub mod incremental;
+pub mod layout_check;
+pub mod list_model;
+#[cfg(feature = "lua")]
+pub mod lua;
+pub mod markdown;
+pub mod overscroll;
+pub mod paging;
+pub mod recent_files;
+pub mod recovery;
+#[cfg(feature = "remote-debug")]
+pub mod remote_debug;
+pub mod row_heights;
+pub mod sections;
+pub mod selection;
+pub mod settings;
+pub mod single_instance;
+pub mod statechart;
+pub mod stroke;
+#[cfg(feature = "lyon")]
+pub mod svg;
+pub mod table_export;
+pub mod table_model;
+pub mod taskbar;
+pub mod telemetry;
+#[cfg(feature = "lyon")]
+pub mod tessellation;
+pub mod text_wrap;
+pub mod timers;
+pub mod tour;
+pub mod tree_table;
+pub mod ui_channel;
+pub mod ui_macros;
+pub mod validation;
+pub mod view_state;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,82 @@
+use crate::selection::{SelectModifiers, Selection, SelectionItem};
+
+/// The data shown by a ListView: its rows and which of them are selected, the selection tells
+/// about changes through its on_selection_changed
+#[derive(Debug, Clone, Default)]
+pub struct ListModel {
+    items: Vec<String>,
+    pub selection: Selection,
+}
+
+impl ListModel {
+    pub fn new(items: Vec<String>) -> Self {
+        ListModel {
+            items,
+            selection: Selection::default(),
+        }
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Replaces the rows, the selected ones that no longer exist are unselected
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        let selected = self.selection.selected().iter().copied().filter(|row| *row < self.items.len()).collect();
+        self.selection.set_selected(selected);
+    }
+
+    /// The rows as selection items, one unit high each so ranges follow their order
+    fn selection_items(&self) -> Vec<SelectionItem> {
+        (0..self.items.len())
+            .map(|row| SelectionItem { id: row, bounds: (0., row as f32, 1., 1.) })
+            .collect()
+    }
+
+    /// Handles a click on row, returns whether the selection changed
+    pub fn click(&mut self, row: usize, modifiers: SelectModifiers) -> bool {
+        if row >= self.items.len() {
+            return false;
+        }
+        let items = self.selection_items();
+        self.selection.click(row, modifiers, &items)
+    }
+
+    /// Selects the row delta rows away from the last selected one (the first row when there is
+    /// none), clamped to the rows. Returns the selected row
+    pub fn move_selection(&mut self, delta: i32) -> Option<usize> {
+        let last = self.items.len().checked_sub(1)?;
+        let row = match self.selection.selected().last() {
+            Some(current) => (*current as i64 + delta as i64).clamp(0, last as i64) as usize,
+            None => 0,
+        };
+        self.click(row, SelectModifiers::default());
+        Some(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_follows_the_rows() {
+        let mut model = ListModel::new(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        assert_eq!(model.move_selection(1), Some(0), "Nothing was selected");
+        assert!(model.click(2, SelectModifiers { shift: true, ..Default::default() }));
+        assert_eq!(model.selection.selected(), &[0, 1, 2]);
+        assert_eq!(model.move_selection(5), Some(2));
+        model.set_items(vec!["a".to_string()]);
+        assert!(model.selection.selected().is_empty());
+        assert!(!model.click(1, SelectModifiers::default()));
+    }
+}
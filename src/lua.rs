@@ -0,0 +1,271 @@
+//! UI logic in Lua scripts, enabled by the lua feature. A script returns the description of a
+//! window and names the global functions that handle its events:
+//!
+//! ```lua
+//! function run(source)
+//!     ui.status("Running " .. source)
+//! end
+//!
+//! return {
+//!     title = "Mod Tool",
+//!     status = "Ready",
+//!     menu = { { title = "&Run", accelerator = "Ctrl+R", on_action = "run" } },
+//!     children = {
+//!         { type = "button", title = "Run", on_action = "run" },
+//!         { type = "text_field", text = "Hello", editable = true },
+//!     },
+//! }
+//! ```
+//!
+//! Scripts are sandboxed: they only get the table, string, math and utf8 libraries plus the ui
+//! table (status, message, execute, log), no io, os or package. The loaders of the base library
+//! (dofile, loadfile and load) are taken away too, and so are the functions that reach past
+//! metatables or the collector (collectgarbage, rawget, rawset, getmetatable, setmetatable), with
+//! them a script could change the string metatable every script shares
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use mlua::{Function, Lua, LuaOptions, StdLib, Table};
+
+use crate::commands;
+use crate::elements::*;
+use crate::file_watcher::FileWatcher;
+use crate::ui_channel::{self, UiMessage};
+
+const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+struct Script {
+    lua: Lua,
+    path: PathBuf,
+    /// Source of the event -> name of the global function handling it
+    actions: HashMap<String, String>,
+    watcher: FileWatcher,
+}
+
+thread_local! {
+    /// Lua states can't leave the UI thread, neither can the script
+    static SCRIPT: RefCell<Option<Script>> = const { RefCell::new(None) };
+}
+
+fn error(path: &Path, e: mlua::Error) -> String {
+    format!("Script {} failed: {}", path.display(), e)
+}
+
+fn sandbox() -> mlua::Result<Lua> {
+    let lua = Lua::new_with(StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8,
+                            LuaOptions::new())?;
+    // The base library is always loaded, these would run other files or unchecked chunks, or
+    // tamper with the shared metatables and the collector
+    for unsafe_global in ["dofile", "loadfile", "load", "collectgarbage", "rawget", "rawset",
+                          "getmetatable", "setmetatable"] {
+        lua.globals().set(unsafe_global, mlua::Value::Nil)?;
+    }
+    let ui = lua.create_table()?;
+    ui.set("status", lua.create_function(|_, text: String| {
+        ui_channel::post(UiMessage::StatusText(text));
+        Ok(())
+    })?)?;
+    ui.set("message", lua.create_function(|_, (text, seconds): (String, Option<f64>)| {
+        ui_channel::post(UiMessage::StatusMessage(text, Duration::from_secs_f64(seconds.unwrap_or(3.))));
+        Ok(())
+    })?)?;
+    ui.set("execute", lua.create_function(|_, id: String| {
+        commands::execute(&id).map_err(mlua::Error::RuntimeError)
+    })?)?;
+    ui.set("log", lua.create_function(|_, _text: String| {
+        #[cfg(feature = "tracing")]
+        tracing::info!(target: "lua", "{}", _text);
+        Ok(())
+    })?)?;
+    lua.globals().set("ui", ui)?;
+    Ok(lua)
+}
+
+/// Runs the script and builds the window it returns, it's watched from now on so tick() reloads
+/// it when it changes. The previous script is replaced
+pub fn load<P: Into<PathBuf>>(path: P) -> Result<Window, String> {
+    let path = path.into();
+    let mut watcher = FileWatcher::new(RELOAD_INTERVAL);
+    watcher.watch(&path);
+    let (lua, window, actions) = run(&path)?;
+    SCRIPT.with(|script| *script.borrow_mut() = Some(Script { lua, path, actions, watcher }));
+    Ok(window)
+}
+
+fn run(path: &Path) -> Result<(Lua, Window, HashMap<String, String>), String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read script {}: {}", path.display(), e))?;
+    let lua = sandbox().map_err(|e| error(path, e))?;
+    let (window, actions) = build(&lua, &source, &path.display().to_string()).map_err(|e| error(path, e))?;
+    Ok((lua, window, actions))
+}
+
+/// Runs the source named name in lua and builds the window it returns. The table it returns
+/// borrows lua, so it's dropped here before lua moves
+fn build(lua: &Lua, source: &str, name: &str) -> mlua::Result<(Window, HashMap<String, String>)> {
+    let table: Table = lua.load(source).set_name(name).eval()?;
+    let mut actions = HashMap::new();
+    let window = window_from_table(&table, &mut actions)?;
+    Ok((window, actions))
+}
+
+/// The window of the script when it changed since it was loaded, the engine calls it every
+/// frame. A script that fails to reload keeps the previous one running
+pub fn tick(now: Instant) -> Option<Window> {
+    let path = SCRIPT.with(|script| {
+        let mut script = script.borrow_mut();
+        let script = script.as_mut()?;
+        match script.watcher.poll(now).is_empty() {
+            true => None,
+            false => Some(script.path.clone()),
+        }
+    })?;
+    match run(&path) {
+        Ok((lua, window, actions)) => {
+            SCRIPT.with(|script| if let Some(script) = script.borrow_mut().as_mut() {
+                script.lua = lua;
+                script.actions = actions;
+            });
+            println!("Reloaded {}", path.display());
+            Some(window)
+        }
+        Err(e) => {
+            println!("{}", e);
+            None
+        }
+    }
+}
+
+/// The on_action of the components built from scripts, calls the global function the script
+/// named for the source of the event. A function returning nothing consumes the event
+fn dispatch(event: Event) -> bool {
+    SCRIPT.with(|script| {
+        let script = script.borrow();
+        let script = match script.as_ref() {
+            Some(script) => script,
+            None => return false,
+        };
+        let name = match script.actions.get(&event.source) {
+            Some(name) => name,
+            None => return false,
+        };
+        let result = script.lua.globals().get::<_, Function>(name.as_str())
+            .and_then(|f| f.call::<_, Option<bool>>(event.source.as_str()));
+        match result {
+            Ok(consumed) => consumed.unwrap_or(true),
+            Err(e) => {
+                println!("{}", error(&script.path, e));
+                false
+            }
+        }
+    })
+}
+
+fn action(table: &Table, title: &str, actions: &mut HashMap<String, String>) -> mlua::Result<()> {
+    if let Some(name) = table.get::<_, Option<String>>("on_action")? {
+        actions.insert(title.to_string(), name);
+    }
+    Ok(())
+}
+
+fn window_from_table(table: &Table, actions: &mut HashMap<String, String>) -> mlua::Result<Window> {
+    let mut window = Window {
+        title: table.get::<_, Option<String>>("title")?.unwrap_or(Window::default().title),
+        ..Default::default()
+    };
+    if let Some(status) = table.get::<_, Option<String>>("status")? {
+        window.status_bar = Some(StatusBar { text: status, ..Default::default() });
+    }
+    if let Some(items) = table.get::<_, Option<Table>>("menu")? {
+        let mut children = vec![];
+        for item in items.sequence_values::<Table>() {
+            let item = item?;
+            let title: String = item.get("title")?;
+            // Menus activate their items with the title without the & of its mnemonic
+            action(&item, &parse_mnemonic(&title).0, actions)?;
+            children.push(Submenu::MenuItem(MenuItem {
+                title,
+                accelerator: item.get("accelerator")?,
+                on_action: dispatch,
+                ..Default::default()
+            }));
+        }
+        window.menu = Some(MainMenu {
+            menu: Menu { title: "&File".to_string(), children, ..Default::default() },
+            ..Default::default()
+        });
+    }
+    if let Some(elements) = table.get::<_, Option<Table>>("children")? {
+        let mut children: Vec<Box<dyn Component>> = vec![];
+        for element in elements.sequence_values::<Table>() {
+            let element = element?;
+            let kind: String = element.get("type")?;
+            match kind.as_str() {
+                "button" => {
+                    let title: String = element.get("title")?;
+                    action(&element, &title, actions)?;
+                    children.push(Box::new(Button { title, on_action: dispatch, ..Default::default() }));
+                }
+                "text_field" => children.push(Box::new(TextField {
+                    text: element.get::<_, Option<String>>("text")?.unwrap_or_default(),
+                    editable: element.get::<_, Option<bool>>("editable")?.unwrap_or(false),
+                    ..Default::default()
+                })),
+                other => return Err(mlua::Error::RuntimeError(format!("Unknown element type {}", other))),
+            }
+        }
+        window.container = Some(Container { children, ..Default::default() });
+    }
+    Ok(window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_describe_windows_in_a_sandbox() {
+        let lua = sandbox().unwrap();
+        let script = r#"
+            function run(source)
+                ran = source
+            end
+
+            return {
+                title = "Mod Tool",
+                status = "Ready",
+                menu = { { title = "&Run", on_action = "run" } },
+                children = {
+                    { type = "button", title = "Build", on_action = "build" },
+                    { type = "text_field", text = "Hello", editable = true },
+                },
+            }
+        "#;
+        let (window, actions) = build(&lua, script, "tool.lua").unwrap();
+        assert_eq!(window.title, "Mod Tool");
+        assert_eq!(window.status_bar.as_ref().map(|s| s.text.as_str()), Some("Ready"));
+        assert_eq!(window.container.as_ref().map(|c| c.children.len()), Some(2));
+        assert_eq!(actions.get("Build"), Some(&"build".to_string()));
+        for escape in ["return io", "return os", "return load", "return dofile", "return loadfile",
+                       "return collectgarbage", "return rawget", "return rawset", "return getmetatable",
+                       "return setmetatable"] {
+            assert!(lua.load(escape).eval::<mlua::Value>().unwrap().is_nil(), "{} is there", escape);
+        }
+
+        SCRIPT.with(|script| *script.borrow_mut() = Some(Script {
+            lua,
+            path: PathBuf::from("tool.lua"),
+            actions,
+            watcher: FileWatcher::new(RELOAD_INTERVAL),
+        }));
+        let mut menu = window.menu.unwrap();
+        assert!(menu.open_by_mnemonic('f'));
+        assert!(menu.activate_by_mnemonic('r'));
+        let ran = SCRIPT.with(|script| script.borrow().as_ref().unwrap().lua.globals()
+            .get::<_, Option<String>>("ran").unwrap());
+        assert_eq!(ran, Some("Run".to_string()));
+    }
+}
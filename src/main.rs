@@ -1,26 +1,310 @@
 extern crate sdl2;
 
 use std::default::Default;
+use std::path::Path;
+use std::time::Duration;
 
+use rui_lopez::accessibility::{self, ColorMode};
+use rui_lopez::accessibility_tree::Accessible;
+use rui_lopez::commands::{self, Command};
+use rui_lopez::deep_links::{self, DeepLink};
 use rui_lopez::elements::*;
+use rui_lopez::engines::sdl::EngineConfig;
+use rui_lopez::file_chooser::{FileChooser, FileFilter};
+use rui_lopez::list_model::ListModel;
+use rui_lopez::settings::Settings;
+use rui_lopez::single_instance::{self, Instance};
+use rui_lopez::table_model::VecTableModel;
+use rui_lopez::taskbar::{self, Attention, TaskbarProgress};
+use rui_lopez::timers::RateLimit;
+use rui_lopez::tour::{self, Tour, TourStep};
+use rui_lopez::ui_channel::{self, UiMessage};
+use rui_lopez::ui_macros::{self, Macro};
+use rui_lopez::validation::{Rule, Validator};
+
+#[cfg(feature = "alloc-counter")]
+#[global_allocator]
+static GLOBAL: rui_lopez::alloc_counter::CountingAllocator = rui_lopez::alloc_counter::CountingAllocator;
 
 pub fn main() -> Result<(), String> {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let _instance = match single_instance::acquire("rui_lopez", &arguments,
+                                                   deep_links::dispatch_arguments)? {
+        Instance::Primary(guard) => guard,
+        Instance::Secondary => return Ok(()),
+    };
+    commands::register(Command::new("app.hello", "Say Hello", |event| {
+        println!("Hello from {:?}", &event);
+        true
+    }).with_shortcut("Ctrl+H"));
+    commands::register(Command::new("app.about", "About", |_event| {
+        ui_channel::post(UiMessage::ShowAbout(rui_lopez::app_info!()));
+        true
+    }));
+    commands::register(Command::new("app.settings", "Settings", |_event| {
+        let mut settings = Settings::new()
+            .declare("editor.word_wrap", "Word wrap", "Editor", "Wrap long lines", true)
+            .declare("editor.indent", "Indent", "Editor", "Spaces inserted by Tab", 4i64)
+            .declare("ui.title", "Title", "Interface", "Title of the window", "RUI Lopez".to_string());
+        if let Err(e) = settings.load(std::env::temp_dir().join("rui_lopez_settings.txt")) {
+            println!("{}", e);
+        }
+        ui_channel::post(UiMessage::ShowSettings(settings));
+        true
+    }));
+    commands::register(Command::new("app.export", "Export", |_event| {
+        // A long operation in the background, the taskbar shows how far it got
+        std::thread::spawn(|| {
+            for step in 0..=10 {
+                taskbar::set_progress(Some(TaskbarProgress { value: Some(step as f32 / 10.), ..Default::default() }));
+                std::thread::sleep(Duration::from_millis(300));
+            }
+            taskbar::set_progress(None);
+            taskbar::request_attention(Attention::Briefly);
+        });
+        true
+    }));
+    commands::register(Command::new("macro.record", "Record Macro", |_event| {
+        // The second time stops recording and saves what was done meanwhile
+        match ui_macros::stop_recording() {
+            Some(recorded) => {
+                if let Err(e) = recorded.save(Path::new("macro.txt")) {
+                    println!("{}", e);
+                }
+            }
+            None => ui_macros::start_recording("Demo"),
+        }
+        true
+    }));
+    commands::register(Command::new("macro.play", "Play Macro", |_event| {
+        match Macro::load(Path::new("macro.txt")) {
+            Ok(recorded) => ui_macros::play(recorded),
+            Err(e) => println!("{}", e),
+        }
+        true
+    }));
+    commands::register(Command::new("app.tour", "Take the Tour", |_event| {
+        tour::start(Tour::new(vec![
+            TourStep::new("Word wrap", "Word wrap", "Long lines go on in the next one instead of off the edge"),
+            TourStep::new("Line endings", "Line endings", "How lines end when the file is saved"),
+            TourStep::new("Indent", "Indent", "How many spaces Tab inserts"),
+        ]).on_end(|completed| println!("Tour completed {}", completed)));
+        true
+    }));
+    commands::register(Command::new("view.color_mode", "Cycle Color Mode", |_event| {
+        let mut preferences = accessibility::preferences();
+        preferences.color_mode = match preferences.color_mode {
+            ColorMode::Standard => ColorMode::HighContrast,
+            ColorMode::HighContrast => ColorMode::ForcedColors(Default::default()),
+            ColorMode::ForcedColors(_) => ColorMode::Standard,
+        };
+        accessibility::set_preferences(preferences);
+        true
+    }));
+    commands::register(Command::new("app.open_link", "Open Link", |event| {
+        println!("Opened {:?}", DeepLink::parse(&event.source));
+        true
+    }));
+    // Runs once typing in the search field paused
+    let search: fn(Event, String) -> bool = |_event, text| {
+        println!("Search {}", text);
+        true
+    };
+    accessibility::set_preferences(accessibility::detect());
+    deep_links::set_scheme("ruilopez");
+    deep_links::route("open", "app.open_link");
+    deep_links::dispatch_arguments(&arguments);
+    #[cfg(feature = "remote-debug")]
+    rui_lopez::remote_debug::start("127.0.0.1:7878")?;
     let window = Window {
         title: "Hello World".to_string(),
         menu: Some(MainMenu {
             menu: Menu {
-                title: "File".to_string(),
+                title: "&File".to_string(),
                 children: vec![Submenu::MenuItem(MenuItem {
-                    title: "Open".to_string()
+                    title: "&Open".to_string(),
+                    accelerator: Some("Ctrl+O".to_string()),
+                    on_action: |_event| {
+                        let chooser = FileChooser::open("Open a text file")
+                            .with_filter(FileFilter::new("Text", &["txt", "md"]))
+                            .with_filter(FileFilter::all_files())
+                            .on_choose(|_event, path| {
+                                println!("Open {}", path.display());
+                                true
+                            });
+                        ui_channel::post(UiMessage::ShowFileChooser(Box::new(chooser)));
+                        true
+                    },
+                    ..Default::default()
                 }), Submenu::MenuItem(MenuItem {
-                    title: "Exit".to_string()
+                    title: "E&xit".to_string(),
+                    ..Default::default()
                 })],
-            }
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        tool_bar: Some(ToolBar {
+            items: vec![ToolItem::Button(ToolButton {
+                title: "New".to_string(),
+                show_title: true,
+                on_action: |event| {
+                    println!("New {:?}", event);
+                    true
+                },
+                ..Default::default()
+            }), ToolItem::Button(ToolButton {
+                title: "Save".to_string(),
+                enabled: false,
+                ..Default::default()
+            }), ToolItem::Separator, ToolItem::Button(ToolButton {
+                title: "Bold".to_string(),
+                icon: Some("B".to_string()),
+                checked: Some(false),
+                ..Default::default()
+            })],
+            ..Default::default()
         }),
         container: Some(Container {
             children: vec![Box::new(TextField {
+                text: String::new(),
+                editable: true,
+                on_change: search.debounced(Duration::from_millis(300)),
+                ..Default::default()
+            }), Box::new(TextArea {
+                text: "Notes\nThis text wraps to the width of the window and scrolls with the mouse wheel once \
+                       it doesn't fit, click it to type more".to_string(),
+                editable: true,
+                height: Dimension::Pixels(80),
+                ..Default::default()
+            }), Box::new(Checkbox {
+                label: "Word wrap".to_string(),
+                on_toggle: |_event, checked| {
+                    println!("Word wrap {}", checked);
+                    true
+                },
+                ..Default::default()
+            }), Box::new(RadioGroup {
+                children: vec![RadioButton {
+                    label: "LF".to_string(),
+                    selected: true,
+                }, RadioButton {
+                    label: "CRLF".to_string(),
+                    ..Default::default()
+                }],
+                on_change: |event, index| {
+                    println!("Line endings {} ({})", event.source, index);
+                    true
+                },
+                accessible: Accessible { name: Some("Line endings".to_string()), ..Default::default() },
+            }), Box::new(Slider {
+                value: 50.,
+                step: 5.,
+                on_change: |_event, value| {
+                    println!("Volume {}", value);
+                    true
+                },
+                ..Default::default()
+            }), Box::new(Spinner {
+                min: 1.,
+                max: 16.,
+                value: 4.,
+                text: "4".to_string(),
+                on_change: |_event, value| {
+                    println!("Indent {}", value);
+                    true
+                },
+                accessible: Accessible { name: Some("Indent".to_string()), ..Default::default() },
+                ..Default::default()
+            }), Box::new(ComboBox {
+                items: vec!["UTF-8".to_string(), "UTF-16".to_string(), "Latin-1".to_string()],
+                selected: Some(0),
+                on_change: |event, index| {
+                    println!("Encoding {} ({})", event.source, index);
+                    true
+                },
+                ..Default::default()
+            }), Box::new(ListView {
+                model: ListModel::new(["Alpha", "Beta", "Gamma", "Delta", "Epsilon", "Zeta", "Eta", "Theta"]
+                    .iter().map(|i| i.to_string()).collect()),
+                visible_rows: 4,
+                ..Default::default()
+            }), Box::new(TableView {
+                visible_rows: 4,
+                on_sort: |event, key| {
+                    println!("Sorted by {} {:?}", event.source, key.order);
+                    true
+                },
+                ..TableView::new(VecTableModel {
+                    columns: vec!["Name".to_string(), "Size".to_string(), "Kind".to_string()],
+                    rows: [("main.rs", "6", "Rust"), ("Cargo.toml", "2", "TOML"), ("README.md", "4", "Markdown"),
+                           ("rui.lua", "1", "Lua"), ("icon.png", "12", "Image")]
+                        .iter().map(|(name, size, kind)| vec![name.to_string(), size.to_string(), kind.to_string()])
+                        .collect(),
+                }, vec![140., 60., 100.])
+            }), Box::new(TabPane {
+                tabs: vec![Tab {
+                    title: "General".to_string(),
+                    content: Container {
+                        children: vec![Box::new(Checkbox {
+                            label: "Show line numbers".to_string(),
+                            ..Default::default()
+                        })],
+                        ..Default::default()
+                    },
+                }, Tab {
+                    title: "Advanced".to_string(),
+                    content: Container {
+                        children: vec![Box::new(Form {
+                            content: Container {
+                                children: vec![Box::new(TextField {
+                                    text: String::new(),
+                                    editable: true,
+                                    validator: Validator::new()
+                                        .with_rule(Rule::Required, "Enter a tab width")
+                                        .with_rule(Rule::Range(1., 16.), "Between 1 and 16"),
+                                    ..Default::default()
+                                })],
+                                ..Default::default()
+                            },
+                            submit_title: "Apply".to_string(),
+                            on_submit: |event| {
+                                println!("Applied {:?}", event);
+                                true
+                            },
+                        })],
+                        ..Default::default()
+                    },
+                }],
+                on_change: |event, index| {
+                    println!("Tab {} ({})", event.source, index);
+                    true
+                },
+                ..Default::default()
+            }), Box::new(ScrollPane {
+                height: Dimension::Pixels(90),
+                content: Container {
+                    children: (1..=8).map(|i| Box::new(Checkbox {
+                        label: format!("Option {}", i),
+                        ..Default::default()
+                    }) as Box<dyn Component>).collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }), Box::new(Label {
+                text: "Caret browsing".to_string(),
+                color: Some(Color::rgb(255, 200, 80)),
+                size: Some(24.),
+                align: TextAlign::Center,
+                ..Default::default()
+            }), Box::new(Label {
+                text: "Press F7 to browse this text with a caret, Shift and the arrows select it".to_string(),
                 ..Default::default()
-            }), Box::new(Button {
+            }), Box::new(Image {
+                source: ImageSource::Path("logo.png".to_string()),
+                height: Dimension::Pixels(64),
+                ..Default::default()
+            }), Box::new(ProgressBar::default()), Box::new(Button {
                 on_action: |event| {
                     println!("Clicked! {:?}", &event);
                     true
@@ -29,8 +313,19 @@ pub fn main() -> Result<(), String> {
             })],
             ..Default::default()
         }),
+        status_bar: Some(StatusBar {
+            text: "Ready".to_string(),
+            sections: vec![StatusSection {
+                text: "UTF-8".to_string(),
+                on_click: |event| {
+                    println!("Clicked! {:?}", &event);
+                    true
+                },
+            }],
+            ..Default::default()
+        }),
         ..Default::default()
     };
     println!("{:?}", window);
-    rui_lopez::engines::sdl::main_loop(vec![window])
+    rui_lopez::engines::sdl::main_loop(vec![window], EngineConfig::default())
 }
\ No newline at end of file
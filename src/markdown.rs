@@ -0,0 +1,453 @@
+//! The Markdown a MarkdownView understands: ATX headings (`# Title`), paragraphs, bullet and
+//! numbered lists nested by indenting them, fenced code blocks, block quotes and rules, with
+//! **bold**, *italic*, `code` and [links](url) inside them. It's parsed into Blocks and laid out
+//! here as MarkdownShapes, the engines only draw them so both show the same document:
+//!
+//! ```ignore
+//! let document = markdown::parse("# Notes\nSee [the docs](https://example.com) for **more**");
+//! let (shapes, height) = markdown::layout(&document, 400., 16., &measure);
+//! ```
+//!
+//! HTML, tables, images and reference links are shown as they're written
+
+use crate::elements::FontStyle;
+
+/// How much bigger than the text the headings of each level are
+const HEADING_SCALES: [f32; 6] = [1.8, 1.5, 1.25, 1.1, 1., 0.9];
+/// Space between the lines of a block, as a fraction of the size of its text
+const LINE_SPACING: f32 = 0.3;
+/// Space between blocks, as a fraction of the size of the text
+const BLOCK_GAP: f32 = 0.6;
+/// How far a level of a list or a quote is indented, as a fraction of the size of the text
+const INDENT: f32 = 1.5;
+/// Space around the lines of a code block, as a fraction of the size of the text
+const CODE_PADDING: f32 = 0.5;
+
+/// Text in a style, link is the index of its url in Document::links
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: FontStyle,
+    pub link: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// level goes from 1 for `#` to 6
+    Heading { level: usize, spans: Vec<Span> },
+    Paragraph(Vec<Span>),
+    /// An entry of a list at depth (0 when it isn't nested) after its marker, a bullet or its number
+    Item { marker: String, depth: usize, spans: Vec<Span> },
+    Quote(Vec<Span>),
+    /// The lines of a fenced code block as they are
+    Code(Vec<String>),
+    Rule,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub blocks: Vec<Block>,
+    /// The urls of the links in the order they appear
+    pub links: Vec<String>,
+}
+
+impl Document {
+    /// The text without the Markdown, a line per block, e.g. for screen readers
+    pub fn plain_text(&self) -> String {
+        let text = |spans: &[Span]| spans.iter().map(|s| s.text.as_str()).collect::<String>();
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Heading { spans, .. } | Block::Paragraph(spans) | Block::Quote(spans) => Some(text(spans)),
+            Block::Item { marker, spans, .. } => Some(format!("{} {}", marker, text(spans))),
+            Block::Code(lines) => Some(lines.join("\n")),
+            Block::Rule => None,
+        }).collect::<Vec<String>>().join("\n")
+    }
+}
+
+/// What the lines being gathered into a block will become
+enum Pending {
+    Paragraph,
+    Quote,
+    Item { marker: String, depth: usize },
+}
+
+pub fn parse(source: &str) -> Document {
+    let mut document = Document::default();
+    let mut pending: Option<(Pending, String)> = None;
+    let mut code: Option<Vec<String>> = None;
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if let Some(lines) = code.as_mut() {
+            match trimmed.starts_with("```") {
+                true => document.blocks.push(Block::Code(code.take().unwrap_or_default())),
+                false => lines.push(line.to_string()),
+            }
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        let (kind, text) = if trimmed.is_empty() {
+            flush(&mut document, pending.take());
+            continue;
+        } else if trimmed.starts_with("```") {
+            flush(&mut document, pending.take());
+            code = Some(vec![]);
+            continue;
+        } else if let Some((level, text)) = heading(trimmed) {
+            flush(&mut document, pending.take());
+            let spans = spans(text, &mut document.links);
+            document.blocks.push(Block::Heading { level, spans });
+            continue;
+        } else if is_rule(trimmed) {
+            flush(&mut document, pending.take());
+            document.blocks.push(Block::Rule);
+            continue;
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            (Pending::Quote, text.trim_start())
+        } else if let Some((marker, text)) = list_marker(trimmed) {
+            (Pending::Item { marker, depth: indent / 2 }, text)
+        } else {
+            // Lines go on the block before them, only an empty line ends a paragraph
+            if let Some((_, gathered)) = pending.as_mut() {
+                gathered.push(' ');
+                gathered.push_str(trimmed);
+                continue;
+            }
+            (Pending::Paragraph, trimmed)
+        };
+        match (&kind, pending.as_mut()) {
+            (Pending::Quote, Some((Pending::Quote, gathered))) => {
+                gathered.push(' ');
+                gathered.push_str(text);
+            }
+            _ => {
+                flush(&mut document, pending.take());
+                pending = Some((kind, text.to_string()));
+            }
+        }
+    }
+    flush(&mut document, pending);
+    // A code block that isn't closed goes on to the end
+    if let Some(lines) = code {
+        document.blocks.push(Block::Code(lines));
+    }
+    document
+}
+
+fn flush(document: &mut Document, pending: Option<(Pending, String)>) {
+    let (kind, text) = match pending {
+        Some(pending) => pending,
+        None => return,
+    };
+    let spans = spans(text.trim_end(), &mut document.links);
+    document.blocks.push(match kind {
+        Pending::Paragraph => Block::Paragraph(spans),
+        Pending::Quote => Block::Quote(spans),
+        Pending::Item { marker, depth } => Block::Item { marker, depth, spans },
+    });
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = &line[level..];
+    match (1..=6).contains(&level) && (text.is_empty() || text.starts_with(' ')) {
+        true => Some((level, text.trim().trim_end_matches('#').trim_end())),
+        false => None,
+    }
+}
+
+/// Three or more of `-`, `*` or `_` and nothing else but spaces
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && ['-', '*', '_'].contains(&marks[0]) && marks.iter().all(|c| *c == marks[0])
+}
+
+/// The marker of the entry of a list the line starts and the text after it
+fn list_marker(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), text.trim_start()));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = &line[digits..];
+    match digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        true => Some((format!("{}.", &line[..digits]), rest[2..].trim_start())),
+        false => None,
+    }
+}
+
+/// The spans of the inline Markdown of text, adding the urls of its links to links. Emphasis is
+/// only taken as such when it's closed further on
+fn spans(text: &str, links: &mut Vec<String>) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let rest = |i: usize| chars[i..].iter().collect::<String>();
+    let mut spans: Vec<Span> = vec![];
+    let (mut bold, mut italic) = (false, false);
+    let mut current = String::new();
+    let push = |spans: &mut Vec<Span>, text: &mut String, style: FontStyle, link: Option<usize>| {
+        if !text.is_empty() {
+            spans.push(Span { text: std::mem::take(text), style, link });
+        }
+    };
+    let style = |bold: bool, italic: bool| match (bold, italic) {
+        (true, _) => FontStyle::Bold,
+        (false, true) => FontStyle::Italic,
+        (false, false) => FontStyle::Regular,
+    };
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let before = i.checked_sub(1).map(|b| chars[b]);
+        match c {
+            '\\' if chars.get(i + 1).is_some_and(|n| n.is_ascii_punctuation()) => {
+                current.push(chars[i + 1]);
+                i += 2;
+            }
+            '`' => match chars[i + 1..].iter().position(|n| *n == '`') {
+                Some(length) => {
+                    push(&mut spans, &mut current, style(bold, italic), None);
+                    let mut code = chars[i + 1..i + 1 + length].iter().collect();
+                    push(&mut spans, &mut code, FontStyle::Monospace, None);
+                    i += length + 2;
+                }
+                None => {
+                    current.push(c);
+                    i += 1;
+                }
+            },
+            '*' | '_' => {
+                let double = chars.get(i + 1) == Some(&c);
+                let marker: String = if double { [c, c].iter().collect() } else { c.to_string() };
+                let open = if double { bold } else { italic };
+                let after = chars.get(i + marker.len());
+                // Underscores inside words, e.g. snake_case, are kept
+                let in_word = c == '_' && match open {
+                    true => after.is_some_and(|a| a.is_alphanumeric()),
+                    false => before.is_some_and(|b| b.is_alphanumeric()),
+                };
+                let closes_later = open || rest(i + marker.len()).contains(&marker);
+                if in_word || !closes_later || (!open && after.is_none_or(|a| a.is_whitespace())) {
+                    current.push_str(&marker);
+                } else {
+                    push(&mut spans, &mut current, style(bold, italic), None);
+                    match double {
+                        true => bold = !bold,
+                        false => italic = !italic,
+                    }
+                }
+                i += marker.len();
+            }
+            '[' => match link(&chars[i..]) {
+                Some((label, url, length)) => {
+                    push(&mut spans, &mut current, style(bold, italic), None);
+                    links.push(url);
+                    let mut label = label;
+                    push(&mut spans, &mut label, style(bold, italic), Some(links.len() - 1));
+                    i += length;
+                }
+                None => {
+                    current.push(c);
+                    i += 1;
+                }
+            },
+            _ => {
+                current.push(c);
+                i += 1;
+            }
+        }
+    }
+    push(&mut spans, &mut current, style(bold, italic), None);
+    spans
+}
+
+/// The label and the url of the link `[label](url)` chars start with and how many chars it takes
+fn link(chars: &[char]) -> Option<(String, String, usize)> {
+    let close = chars.iter().position(|c| *c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 2 + chars[close + 2..].iter().position(|c| *c == ')')?;
+    let label: String = chars[1..close].iter().collect();
+    let url: String = chars[close + 2..end].iter().collect();
+    Some((label, url.trim().to_string(), end + 1))
+}
+
+/// What a document is made of once laid out, in pixels from its top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkdownShape {
+    /// Drawn from its top left corner in the font of style, the text of a link is underlined and
+    /// hit at [link]
+    Text { text: String, x: f32, y: f32, size: f32, style: FontStyle, link: Option<usize> },
+    /// Behind the lines of a code block
+    CodeBackground { x: f32, y: f32, w: f32, h: f32 },
+    /// Along the left of a quote
+    QuoteBar { x: f32, y: f32, h: f32 },
+    Rule { x: f32, y: f32, w: f32 },
+}
+
+/// Lays the document out in width for text of size, measure gives the width of a text in a size
+/// and a style. Returns the shapes and the height they take
+pub fn layout(document: &Document, width: f32, size: f32, measure: &dyn Fn(&str, f32, FontStyle) -> f32)
+              -> (Vec<MarkdownShape>, f32) {
+    let mut shapes = vec![];
+    let mut y = 0.;
+    let indent = size * INDENT;
+    for (i, block) in document.blocks.iter().enumerate() {
+        if i > 0 {
+            y += size * BLOCK_GAP;
+        }
+        y = match block {
+            Block::Heading { level, spans } => {
+                let bold: Vec<Span> = spans.iter().map(|span| Span {
+                    style: if span.style == FontStyle::Monospace { span.style } else { FontStyle::Bold },
+                    ..span.clone()
+                }).collect();
+                let scale = HEADING_SCALES[level.clamp(&1, &6) - 1];
+                flow(&mut shapes, &bold, (0., width), y, size * scale, measure)
+            }
+            Block::Paragraph(spans) => flow(&mut shapes, spans, (0., width), y, size, measure),
+            Block::Item { marker, depth, spans } => {
+                let x = indent * *depth as f32;
+                let marker_x = x + indent - size / 2. - measure(marker, size, FontStyle::Regular);
+                shapes.push(MarkdownShape::Text { text: marker.clone(), x: marker_x.max(x), y, size,
+                                                  style: FontStyle::Regular, link: None });
+                flow(&mut shapes, spans, (x + indent, (width - x - indent).max(0.)), y, size, measure)
+            }
+            Block::Quote(spans) => {
+                let bottom = flow(&mut shapes, spans, (indent, (width - indent).max(0.)), y, size, measure);
+                shapes.push(MarkdownShape::QuoteBar { x: indent / 4., y, h: bottom - y });
+                bottom
+            }
+            Block::Code(lines) => {
+                let padding = size * CODE_PADDING;
+                let line_height = size * (1. + LINE_SPACING);
+                let inside = (width - 2. * padding).max(0.);
+                let lines: Vec<String> = lines.iter()
+                    .flat_map(|line| break_line(line, inside, &|text| measure(text, size, FontStyle::Monospace)))
+                    .collect();
+                let h = line_height * lines.len() as f32 + 2. * padding;
+                shapes.push(MarkdownShape::CodeBackground { x: 0., y, w: width, h });
+                for (i, line) in lines.into_iter().enumerate() {
+                    shapes.push(MarkdownShape::Text { text: line, x: padding, y: y + padding + line_height * i as f32,
+                                                      size, style: FontStyle::Monospace, link: None });
+                }
+                y + h
+            }
+            Block::Rule => {
+                shapes.push(MarkdownShape::Rule { x: 0., y: y + size / 2., w: width });
+                y + size
+            }
+        };
+    }
+    (shapes, y)
+}
+
+/// Lays the spans out word by word in the lines from x (the first of the pair) that are the
+/// second of the pair wide, starting at y. A word wider than the line gets a line of its own.
+/// Returns where the lines end
+fn flow(shapes: &mut Vec<MarkdownShape>, spans: &[Span], (left, width): (f32, f32), y: f32, size: f32,
+        measure: &dyn Fn(&str, f32, FontStyle) -> f32) -> f32 {
+    let line_height = size * (1. + LINE_SPACING);
+    let space = measure(" ", size, FontStyle::Regular);
+    let (mut x, mut y) = (left, y);
+    // Whether the line has words yet and whether a space goes before the next one
+    let (mut started, mut spaced) = (false, false);
+    for span in spans {
+        // The words of the span on the line so far and where they start
+        let mut run: Option<(String, f32)> = None;
+        let flush = |shapes: &mut Vec<MarkdownShape>, run: &mut Option<(String, f32)>, y: f32| {
+            if let Some((text, x)) = run.take() {
+                shapes.push(MarkdownShape::Text { text, x, y, size, style: span.style, link: span.link });
+            }
+        };
+        for (i, word) in span.text.split(' ').enumerate() {
+            spaced |= i > 0;
+            if word.is_empty() {
+                continue;
+            }
+            let word_width = measure(word, size, span.style);
+            let mut gap = if spaced && started { space } else { 0. };
+            if started && x + gap + word_width > left + width {
+                flush(shapes, &mut run, y);
+                (x, y, gap) = (left, y + line_height, 0.);
+            }
+            match run.as_mut() {
+                Some((text, _)) => {
+                    if gap > 0. {
+                        text.push(' ');
+                    }
+                    text.push_str(word);
+                }
+                None => run = Some((word.to_string(), x + gap)),
+            }
+            x += gap + word_width;
+            (started, spaced) = (true, false);
+        }
+        flush(shapes, &mut run, y);
+    }
+    match started {
+        true => y + line_height,
+        false => y,
+    }
+}
+
+/// The line cut into lines that fit in width, at any character since code is shown as it's written
+fn break_line(line: &str, width: f32, measure: &dyn Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for c in line.chars() {
+        let last = lines.last_mut().expect("There is always a line");
+        last.push(c);
+        if measure(last) > width && last.chars().count() > 1 {
+            last.pop();
+            lines.push(c.to_string());
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_is_parsed_and_flowed_into_lines() {
+        let document = parse("# A *title*\n\nSome **bold** and \\*not\\* snake_case `x = 1`,\n\
+                              see [docs](https://a.b).\n\n- one\n  - two\n3. three\n\n\
+                              > quoted\n> on\n\n```\n  let x;\n```\n***");
+        fn plain(spans: &[Span]) -> Vec<(&str, FontStyle)> {
+            spans.iter().map(|s| (s.text.as_str(), s.style)).collect()
+        }
+        assert_eq!(document.blocks.len(), 8);
+        assert_eq!(document.blocks[0], Block::Heading { level: 1, spans: vec![
+            Span { text: "A ".to_string(), style: FontStyle::Regular, link: None },
+            Span { text: "title".to_string(), style: FontStyle::Italic, link: None },
+        ] });
+        match &document.blocks[1] {
+            Block::Paragraph(spans) => assert_eq!(plain(spans), [
+                ("Some ", FontStyle::Regular), ("bold", FontStyle::Bold),
+                (" and *not* snake_case ", FontStyle::Regular), ("x = 1", FontStyle::Monospace),
+                (", see ", FontStyle::Regular), ("docs", FontStyle::Regular), (".", FontStyle::Regular),
+            ]),
+            other => panic!("Not a paragraph {:?}", other),
+        }
+        assert_eq!(document.links, ["https://a.b"]);
+        let items: Vec<(&str, usize)> = document.blocks[2..5].iter().filter_map(|b| match b {
+            Block::Item { marker, depth, .. } => Some((marker.as_str(), *depth)),
+            _ => None,
+        }).collect();
+        assert_eq!(items, [("•", 0), ("•", 1), ("3.", 0)]);
+        assert!(matches!(&document.blocks[5], Block::Quote(spans) if spans[0].text == "quoted on"));
+        assert_eq!(document.blocks[6..], [Block::Code(vec!["  let x;".to_string()]), Block::Rule]);
+
+        let measure = |text: &str, size: f32, _style| text.chars().count() as f32 * size / 2.;
+        let paragraph = parse("aaaa bbbb [cc](u) dd");
+        let (shapes, height) = layout(&paragraph, 50., 10., &measure);
+        let texts: Vec<(&str, f32, f32, Option<usize>)> = shapes.iter().filter_map(|s| match s {
+            MarkdownShape::Text { text, x, y, link, .. } => Some((text.as_str(), *x, *y, *link)),
+            _ => None,
+        }).collect();
+        assert_eq!(texts, [("aaaa bbbb", 0., 0., None), ("cc", 0., 13., Some(0)), ("dd", 15., 13., None)]);
+        assert_eq!(height, 26.);
+        assert_eq!(paragraph.plain_text(), "aaaa bbbb cc dd");
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use crate::accessibility;
+
+/// How far past the content an overscroll can go, in the units the view scrolls by
+const MAX_OVERSCROLL: f32 = 1.;
+/// Scrolling past the content moves it this much of the distance, so it feels like stretching
+const RESISTANCE: f32 = 0.4;
+/// How long the overscroll takes to go halfway back
+const HALF_LIFE: Duration = Duration::from_millis(80);
+/// Below this it's snapped back to rest
+const REST_THRESHOLD: f32 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OverscrollMode {
+    /// Scrolling stops at the edges
+    None,
+    /// The content follows the pointer past the edge and springs back
+    #[default]
+    Bounce,
+    /// The content stops but the edge glows, brighter the harder it's pushed
+    Glow,
+}
+
+/// What a scrolling view shows when scrolled past its content, it's pushed by the scrolling
+/// that didn't fit and goes back to rest as it's advanced every frame
+#[derive(Debug, Clone, Default)]
+pub struct Overscroll {
+    pub mode: OverscrollMode,
+    /// Negative past the start of the content, positive past its end
+    amount: f32,
+}
+
+impl Overscroll {
+    pub fn new(mode: OverscrollMode) -> Self {
+        Overscroll { mode, amount: 0. }
+    }
+
+    /// Takes the scrolling past the content (negative before the start), returns whether the
+    /// view has to be drawn again. There is none when reducing motion
+    pub fn push(&mut self, excess: f32) -> bool {
+        let amount = match self.mode {
+            _ if accessibility::reduce_motion() => return false,
+            OverscrollMode::None => return false,
+            OverscrollMode::Bounce => self.amount + excess * RESISTANCE,
+            OverscrollMode::Glow => self.amount + excess,
+        };
+        let amount = amount.clamp(-MAX_OVERSCROLL, MAX_OVERSCROLL);
+        let changed = amount != self.amount;
+        self.amount = amount;
+        changed
+    }
+
+    /// Goes back toward rest, returns whether it moved
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        if self.amount == 0. {
+            return false;
+        }
+        self.amount *= 0.5f32.powf(elapsed.as_secs_f32() / HALF_LIFE.as_secs_f32());
+        if self.amount.abs() < REST_THRESHOLD {
+            self.amount = 0.;
+        }
+        true
+    }
+
+    pub fn is_at_rest(&self) -> bool {
+        self.amount == 0.
+    }
+
+    /// How far the content is moved past the edge when bouncing, with the sign of push()
+    pub fn offset(&self) -> f32 {
+        match self.mode {
+            OverscrollMode::Bounce => self.amount,
+            _ => 0.,
+        }
+    }
+
+    /// How bright the glow is (0.0 - 1.0) and whether it's at the end of the content instead of
+    /// the start
+    pub fn glow(&self) -> Option<(f32, bool)> {
+        match self.mode {
+            OverscrollMode::Glow if self.amount != 0. => Some((self.amount.abs() / MAX_OVERSCROLL, self.amount > 0.)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overscroll_springs_back() {
+        let mut bounce = Overscroll::default();
+        assert!(bounce.push(-1.));
+        assert_eq!(bounce.offset(), -RESISTANCE);
+        assert!(bounce.advance(HALF_LIFE));
+        assert_eq!(bounce.offset(), -RESISTANCE / 2.);
+        assert!(bounce.advance(HALF_LIFE * 10));
+        assert!(bounce.is_at_rest());
+        assert!(!bounce.advance(HALF_LIFE));
+        let mut glow = Overscroll::new(OverscrollMode::Glow);
+        glow.push(5.);
+        assert_eq!(glow.glow(), Some((1., true)));
+        assert_eq!(glow.offset(), 0.);
+        assert!(!Overscroll::new(OverscrollMode::None).push(1.));
+    }
+}
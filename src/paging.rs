@@ -0,0 +1,171 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+use crate::ui_channel::{self, UiMessage};
+
+/// A row as the cells' text, views showing a single column (ListView) use the first cell
+pub type Row = Vec<String>;
+
+/// Which rows a view wants, offset is the index of the first one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageRequest {
+    pub offset: usize,
+    pub count: usize,
+}
+
+/// Rows loaded by a DataProvider, see deliver_page()
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageDelivery {
+    /// The source of the PagedRows that requested the page
+    pub source: String,
+    pub offset: usize,
+    pub rows: Vec<Row>,
+    /// False when there are no rows after these ones
+    pub more: bool,
+}
+
+/// Loads rows for infinite-scroll views. Requests are made on the UI thread, so they should only
+/// start the work (e.g. spawn a thread or send a network request), the rows are given back later
+/// with deliver_page()
+pub trait DataProvider: Send {
+    fn request_page(&mut self, source: &str, request: PageRequest);
+}
+
+/// Hands loaded rows to the view that requested them, it can be called from any thread. They are
+/// appended at the start of the next frame through the UI channel
+pub fn deliver_page(source: &str, offset: usize, rows: Vec<Row>, more: bool) {
+    ui_channel::post(UiMessage::PageLoaded(PageDelivery {
+        source: source.to_string(),
+        offset,
+        rows,
+        more,
+    }));
+}
+
+/// Shows the skeleton of the Loadable with source while its data is loading, or its content once
+/// it's loaded. It can be called from any thread, e.g. before and after a request
+pub fn set_loading(source: &str, loading: bool) {
+    ui_channel::post(UiMessage::Loading { source: source.to_string(), loading });
+}
+
+/// The rows of a ListView or Table that are loaded page by page while the user scrolls near the
+/// end. Deliveries are routed by source, so it should be unique in the window
+#[derive(Clone)]
+pub struct PagedRows {
+    pub source: String,
+    pub rows: Vec<Row>,
+    pub page_size: usize,
+    /// How close (in rows) the last visible row has to get to the end to request the next page
+    pub threshold: usize,
+    loading: bool,
+    exhausted: bool,
+    provider: Arc<Mutex<Box<dyn DataProvider>>>,
+}
+
+impl Debug for PagedRows {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PagedRows")
+            .field("source", &self.source)
+            .field("rows", &self.rows.len())
+            .field("loading", &self.loading)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
+
+impl PagedRows {
+    pub fn new(source: &str, provider: Box<dyn DataProvider>) -> Self {
+        PagedRows {
+            source: source.to_string(),
+            rows: vec![],
+            page_size: 50,
+            threshold: 10,
+            loading: false,
+            exhausted: false,
+            provider: Arc::new(Mutex::new(provider)),
+        }
+    }
+
+    /// Whether a page was requested and not delivered yet, views show a loading row meanwhile
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    /// Whether the provider said there are no more rows
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// The amount of rows to show, including the loading row
+    pub fn display_len(&self) -> usize {
+        self.rows.len() + self.loading as usize
+    }
+
+    /// Requests the next page unless one is already loading or there are no more rows. Returns
+    /// whether it was requested
+    pub fn request_next_page(&mut self) -> bool {
+        if self.loading || self.exhausted {
+            return false;
+        }
+        self.loading = true;
+        let request = PageRequest {
+            offset: self.rows.len(),
+            count: self.page_size,
+        };
+        self.provider.lock().expect("Data provider is poisoned")
+            .request_page(&self.source, request);
+        true
+    }
+
+    /// Called by the view every time it scrolls, the next page is requested when the last visible
+    /// row is close to the end. Returns whether it was requested
+    pub fn scrolled_to(&mut self, last_visible: usize) -> bool {
+        if last_visible + self.threshold >= self.rows.len() {
+            self.request_next_page()
+        } else {
+            false
+        }
+    }
+
+    /// Appends the delivered rows, deliveries for other sources or that don't continue the loaded
+    /// rows are ignored. Returns whether the rows changed
+    pub fn deliver(&mut self, delivery: &PageDelivery) -> bool {
+        if delivery.source != self.source || delivery.offset != self.rows.len() {
+            return false;
+        }
+        self.loading = false;
+        self.exhausted = !delivery.more;
+        self.rows.extend(delivery.rows.iter().cloned());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Requests(Arc<Mutex<Vec<PageRequest>>>);
+
+    impl DataProvider for Requests {
+        fn request_page(&mut self, _source: &str, request: PageRequest) {
+            self.0.lock().unwrap().push(request);
+        }
+    }
+
+    #[test]
+    fn next_page_is_requested_near_the_end() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let mut rows = PagedRows::new("contacts", Box::new(Requests(requests.clone())));
+        rows.page_size = 20;
+        assert!(rows.scrolled_to(0));
+        assert!(!rows.scrolled_to(0));
+        assert_eq!(rows.display_len(), 1);
+        let page = vec![vec!["Ana".to_string()]; 20];
+        assert!(rows.deliver(&PageDelivery { source: "contacts".to_string(), offset: 0, rows: page,
+                                             more: true }));
+        assert!(!rows.scrolled_to(5));
+        assert!(rows.scrolled_to(12));
+        assert_eq!(*requests.lock().unwrap(),
+                   vec![PageRequest { offset: 0, count: 20 }, PageRequest { offset: 20, count: 20 }]);
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+
+use crate::elements::{Event, MenuItem, Submenu};
+use crate::table_model::TableModel;
+
+/// The most recently used files, newest first, persisted in a text file with one path per line.
+/// The file usually lives in the directory given by engines::sdl::preferences_dir()
+#[derive(Debug, Clone)]
+pub struct RecentFiles {
+    pub paths: Vec<PathBuf>,
+    pub capacity: usize,
+    file: PathBuf,
+}
+
+impl RecentFiles {
+    /// Loads the list from file, a missing file is an empty list
+    pub fn load<P: Into<PathBuf>>(file: P, capacity: usize) -> Result<Self, String> {
+        let file = file.into();
+        let paths = match std::fs::read_to_string(&file) {
+            Ok(content) => content.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(PathBuf::from)
+                .take(capacity)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(format!("Failed to read recent files {}: {}", file.display(), e)),
+        };
+        Ok(RecentFiles { paths, capacity, file })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let content: Vec<String> = self.paths.iter().map(|p| p.display().to_string()).collect();
+        std::fs::write(&self.file, content.join("\n"))
+            .map_err(|e| format!("Failed to save recent files {}: {}", self.file.display(), e))
+    }
+
+    /// Puts path first (moving it if it was already there), drops the oldest ones over capacity
+    /// and saves the list
+    pub fn add<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        let path = path.as_ref().to_path_buf();
+        self.paths.retain(|p| *p != path);
+        self.paths.insert(0, path);
+        self.paths.truncate(self.capacity);
+        self.save()
+    }
+
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        self.paths.retain(|p| p != path.as_ref());
+        self.save()
+    }
+
+    /// Removes the files that don't exist anymore, returns how many were removed
+    pub fn prune(&mut self) -> Result<usize, String> {
+        let before = self.paths.len();
+        self.paths.retain(|p| p.exists());
+        let removed = before - self.paths.len();
+        if removed > 0 {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// Menu items for the files, numbered 1 to 9 as mnemonics. on_action gets the event of the
+    /// item, path_of_event() gives the path back
+    pub fn menu_section(&self, on_action: fn(Event) -> bool) -> Vec<Submenu> {
+        self.paths.iter().enumerate().map(|(i, path)| {
+            let number = match i + 1 {
+                n if n < 10 => format!("&{}", n),
+                n => n.to_string(),
+            };
+            Submenu::MenuItem(MenuItem {
+                // A & of the path is literal, the menu strips the mnemonic markers off the source
+                title: format!("{} {}", number, path.display().to_string().replace('&', "&&")),
+                on_action,
+                ..Default::default()
+            })
+        }).collect()
+    }
+}
+
+/// The path of a menu item built by RecentFiles::menu_section()
+pub fn path_of_event(event: &Event) -> Option<PathBuf> {
+    let (number, path) = event.source.split_once(' ')?;
+    number.parse::<usize>().ok()?;
+    Some(PathBuf::from(path))
+}
+
+/// A single "Path" column, so the files can be shown in a ListView or Table
+impl TableModel for RecentFiles {
+    fn row_count(&self) -> usize {
+        self.paths.len()
+    }
+
+    fn column_count(&self) -> usize {
+        1
+    }
+
+    fn column_title(&self, _column: usize) -> String {
+        "Path".to_string()
+    }
+
+    fn cell(&self, row: usize, _column: usize) -> String {
+        self.paths.get(row).map(|p| p.display().to_string()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_files_are_capped_and_persisted() {
+        let file = std::env::temp_dir().join(format!("rui-lopez-recent-{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let mut recent = RecentFiles::load(&file, 2).unwrap();
+        recent.add("a.txt").unwrap();
+        recent.add("b.txt").unwrap();
+        recent.add("a.txt").unwrap();
+        recent.add("c.txt").unwrap();
+        let loaded = RecentFiles::load(&file, 2).unwrap();
+        assert_eq!(loaded.paths, vec![PathBuf::from("c.txt"), PathBuf::from("a.txt")]);
+        let event = Event { source: "1 c.txt".to_string(), ..Default::default() };
+        assert_eq!(path_of_event(&event), Some(PathBuf::from("c.txt")));
+        recent.prune().unwrap();
+        assert!(recent.paths.is_empty());
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn menu_items_give_back_paths_with_ampersands() {
+        let recent = RecentFiles { paths: vec![PathBuf::from("a&b.txt")], capacity: 9, file: PathBuf::new() };
+        let title = match &recent.menu_section(|_event| true)[0] {
+            Submenu::MenuItem(item) => item.title.clone(),
+            Submenu::Menu(menu) => menu.title.clone(),
+        };
+        assert_eq!(title, "&1 a&&b.txt");
+        let event = Event { source: crate::elements::parse_mnemonic(&title).0, ..Default::default() };
+        assert_eq!(path_of_event(&event), Some(PathBuf::from("a&b.txt")));
+    }
+}
@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::documents::{self, Snapshot};
+
+/// Exists while the app runs, finding it on start means the last session didn't end cleanly
+const LOCK_FILE: &str = "session.lock";
+const AUTOSAVE_EXTENSION: &str = "autosave";
+/// What a session that didn't end cleanly autosaved, kept until it's restored or discarded
+const RECOVERED_EXTENSION: &str = "recovered";
+
+struct Recovery {
+    dir: PathBuf,
+    interval: Duration,
+    last_autosave: Option<Instant>,
+}
+
+static RECOVERY: Lazy<Mutex<Option<Recovery>>> = Lazy::new(|| Mutex::new(None));
+
+/// Starts autosaving the unsaved documents to dir (usually inside engines::sdl::preferences_dir())
+/// every interval. Returns what was autosaved by the last session when it crashed or was killed,
+/// the app should offer to recover it (engines::sdl::recovery_prompt()) and then restore() or
+/// discard() it. Until then it's offered again on every start
+pub fn start<P: Into<PathBuf>>(dir: P, interval: Duration) -> Result<Vec<Snapshot>, String> {
+    let dir = dir.into();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let lock = dir.join(LOCK_FILE);
+    if lock.exists() {
+        // Moved aside so the autosaves of this session don't replace them
+        let mut next = files(&dir, RECOVERED_EXTENSION)?.len();
+        for file in files(&dir, AUTOSAVE_EXTENSION)? {
+            let recovered = loop {
+                let recovered = dir.join(format!("{}.{}", next, RECOVERED_EXTENSION));
+                next += 1;
+                if !recovered.exists() {
+                    break recovered;
+                }
+            };
+            std::fs::rename(&file, &recovered)
+                .map_err(|e| format!("Failed to move {}: {}", file.display(), e))?;
+        }
+    }
+    remove_files(&dir, AUTOSAVE_EXTENSION)?;
+    std::fs::write(&lock, std::process::id().to_string())
+        .map_err(|e| format!("Failed to write {}: {}", lock.display(), e))?;
+    let recovered = read_snapshots(&dir)?;
+    *RECOVERY.lock().expect("Recovery is poisoned") = Some(Recovery {
+        dir,
+        interval,
+        last_autosave: None,
+    });
+    Ok(recovered)
+}
+
+/// Opens the snapshots returned by start() again with documents::restore() and autosaves them as
+/// documents of this session, then removes what the last session autosaved
+pub fn restore(snapshots: &[Snapshot]) -> Result<(), String> {
+    for snapshot in snapshots {
+        documents::restore(snapshot)?;
+    }
+    autosave_now()?;
+    discard()
+}
+
+/// Removes what the last session autosaved, when the user doesn't want to recover it
+pub fn discard() -> Result<(), String> {
+    match RECOVERY.lock().expect("Recovery is poisoned").as_ref() {
+        Some(recovery) => remove_files(&recovery.dir, RECOVERED_EXTENSION),
+        None => Ok(()),
+    }
+}
+
+/// Autosaves when the interval passed, it's called by main_loop() once per frame
+pub fn tick(now: Instant) {
+    let due = match RECOVERY.lock().expect("Recovery is poisoned").as_mut() {
+        Some(recovery) if recovery.last_autosave.is_none_or(|l| now - l >= recovery.interval) => {
+            recovery.last_autosave = Some(now);
+            true
+        }
+        _ => false,
+    };
+    if due {
+        if let Err(e) = autosave_now() {
+            println!("Failed to autosave {}", e);
+        }
+    }
+}
+
+/// Writes the snapshots of the unsaved documents replacing the previous ones. It never waits for
+/// a lock, so the crash reporter calls it from the panic hook for a last autosave
+pub fn autosave_now() -> Result<(), String> {
+    let recovery = RECOVERY.try_lock().map_err(|_| "Recovery is busy".to_string())?;
+    match recovery.as_ref() {
+        Some(recovery) => write_autosaves(&recovery.dir, &documents::dirty_snapshots()?),
+        None => Ok(()),
+    }
+}
+
+/// Marks a clean shutdown removing the autosaves, main_loop() calls it when it ends after closing
+/// the documents. Documents that still have unsaved changes are autosaved one last time and kept
+/// with the lock, so the next start() offers to recover them
+pub fn finish() -> Result<(), String> {
+    let recovery = RECOVERY.lock().expect("Recovery is poisoned").take();
+    if let Some(recovery) = recovery {
+        let snapshots = documents::dirty_snapshots()
+            .map_err(|e| format!("Keeping the autosaves, the unsaved documents are unknown: {}", e))?;
+        if !snapshots.is_empty() {
+            return write_autosaves(&recovery.dir, &snapshots);
+        }
+        remove_files(&recovery.dir, AUTOSAVE_EXTENSION)?;
+        let lock = recovery.dir.join(LOCK_FILE);
+        std::fs::remove_file(&lock).map_err(|e| format!("Failed to remove {}: {}", lock.display(), e))?;
+    }
+    Ok(())
+}
+
+fn write_autosaves(dir: &Path, snapshots: &[Snapshot]) -> Result<(), String> {
+    remove_files(dir, AUTOSAVE_EXTENSION)?;
+    for (i, snapshot) in snapshots.iter().enumerate() {
+        let file = dir.join(format!("{}.{}", i, AUTOSAVE_EXTENSION));
+        std::fs::write(&file, encode(snapshot))
+            .map_err(|e| format!("Failed to autosave {}: {}", file.display(), e))?;
+    }
+    Ok(())
+}
+
+fn files(dir: &Path, extension: &str) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut files: Vec<PathBuf> = entries.filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == extension))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn read_snapshots(dir: &Path) -> Result<Vec<Snapshot>, String> {
+    let mut snapshots = vec![];
+    for file in files(dir, RECOVERED_EXTENSION)? {
+        let bytes = std::fs::read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        match decode(&bytes) {
+            Some(snapshot) => snapshots.push(snapshot),
+            None => println!("Ignoring the damaged autosave {}", file.display()),
+        }
+    }
+    Ok(snapshots)
+}
+
+fn remove_files(dir: &Path, extension: &str) -> Result<(), String> {
+    for file in files(dir, extension)? {
+        std::fs::remove_file(&file).map_err(|e| format!("Failed to remove {}: {}", file.display(), e))?;
+    }
+    Ok(())
+}
+
+/// A line with the window, a line with the path (empty when it was never saved) and the content
+fn encode(snapshot: &Snapshot) -> Vec<u8> {
+    let path = snapshot.path.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+    let mut bytes = format!("{}\n{}\n", snapshot.window, path).into_bytes();
+    bytes.extend_from_slice(&snapshot.contents);
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> Option<Snapshot> {
+    let mut parts = bytes.splitn(3, |b| *b == b'\n');
+    let window = String::from_utf8(parts.next()?.to_vec()).ok()?;
+    let path = String::from_utf8(parts.next()?.to_vec()).ok()?;
+    let contents = parts.next()?.to_vec();
+    Some(Snapshot {
+        window,
+        path: if path.is_empty() { None } else { Some(PathBuf::from(path)) },
+        contents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autosaves_are_recovered_after_an_unclean_shutdown() {
+        let dir = std::env::temp_dir().join(format!("rui-lopez-recovery-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(start(&dir, Duration::from_secs(60)), Ok(vec![]));
+        let snapshot = Snapshot {
+            window: "Editor".to_string(),
+            path: Some(PathBuf::from("notes.txt")),
+            contents: b"first\nsecond".to_vec(),
+        };
+        std::fs::write(dir.join("0.autosave"), encode(&snapshot)).unwrap();
+        // Starting again without finish() is what the next launch after a crash does
+        assert_eq!(start(&dir, Duration::from_secs(60)), Ok(vec![snapshot.clone()]));
+        finish().unwrap();
+        // Offered again until it's restored or discarded
+        assert_eq!(start(&dir, Duration::from_secs(60)), Ok(vec![snapshot]));
+        discard().unwrap();
+        finish().unwrap();
+        assert_eq!(start(&dir, Duration::from_secs(60)), Ok(vec![]));
+        finish().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
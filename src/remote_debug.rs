@@ -0,0 +1,426 @@
+//! Development server of the remote-debug feature, it lets an inspector on another machine look
+//! at the live UI of an embedded or kiosk deployment and tweak it. It speaks a line protocol over
+//! TCP, so `nc <host> <port>` is enough of a client:
+//!
+//! - `tree` prints the Window model with all its components
+//! - `layout` prints the bodies the engine built, with their bounds and hit areas
+//! - `events` prints the last events the engine dispatched
+//! - `warnings` prints what layout_check found wrong with the layout
+//! - `diff` captures the frame before a change, then the one after and shows where they differ,
+//!   then hides it again (see frame_diff)
+//! - `set <path> <value>` changes a property, e.g. `set title Demo`, `set status_bar.text Busy`
+//!   or `set container.0.text Hello` (see set_property())
+//!
+//! Every reply ends with a line holding a single dot. There is no authentication, don't enable
+//! the feature in release builds
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::elements::*;
+use crate::table_model::{SortKey, SortOrder};
+use crate::ui_channel::{self, UiMessage};
+
+/// How long a connection waits for the UI thread, it only answers between frames
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+const EVENT_LOG_SIZE: usize = 100;
+
+static EVENT_LOG: Lazy<Mutex<VecDeque<String>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    Tree,
+    Layout,
+    Events,
+    /// What layout_check found, see layout_check::check()
+    Warnings,
+    /// Moves the frame diff to its next step, see frame_diff
+    Diff,
+    Set { path: String, value: String },
+}
+
+impl RemoteCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (name, argument) = line.split_once(' ').unwrap_or((line, ""));
+        match name {
+            "tree" => Ok(RemoteCommand::Tree),
+            "layout" => Ok(RemoteCommand::Layout),
+            "events" => Ok(RemoteCommand::Events),
+            "warnings" => Ok(RemoteCommand::Warnings),
+            "diff" => Ok(RemoteCommand::Diff),
+            "set" => match argument.trim_start().split_once(' ') {
+                Some((path, value)) => Ok(RemoteCommand::Set { path: path.to_string(), value: value.to_string() }),
+                None => Err("Usage: set <path> <value>".to_string()),
+            },
+            _ => Err(format!("Unknown command {}, try tree, layout, events, warnings, diff or set", name)),
+        }
+    }
+}
+
+/// A command for the engine, which answers it on the UI thread with reply()
+#[derive(Debug)]
+pub struct RemoteRequest {
+    pub command: RemoteCommand,
+    reply: Sender<String>,
+}
+
+impl RemoteRequest {
+    pub fn reply(self, text: String) {
+        // The connection may be gone already
+        let _ = self.reply.send(text);
+    }
+}
+
+/// Starts serving on address in a background thread, returns where it listens (the port can be
+/// 0 to let the OS choose one)
+pub fn start<A: ToSocketAddrs>(address: A) -> Result<SocketAddr, String> {
+    let listener = TcpListener::bind(address)
+        .map_err(|e| format!("Failed to start the remote debug server: {}", e))?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().filter_map(|s| s.ok()) {
+            std::thread::spawn(move || {
+                if let Err(e) = serve(stream) {
+                    println!("Remote debug connection closed {}", e);
+                }
+            });
+        }
+    });
+    println!("Remote debug server listening on {}", address);
+    Ok(address)
+}
+
+fn serve(stream: TcpStream) -> Result<(), String> {
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+    for line in BufReader::new(stream).lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let answer = match RemoteCommand::parse(&line) {
+            Ok(command) => {
+                let (reply, answer) = mpsc::channel();
+                ui_channel::post(UiMessage::RemoteDebug(RemoteRequest { command, reply }));
+                answer.recv_timeout(REPLY_TIMEOUT)
+                    .unwrap_or("The UI thread didn't answer".to_string())
+            }
+            Err(e) => e,
+        };
+        writer.write_all(format!("{}\n.\n", answer).as_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Records an event the engine dispatched, only the last ones are kept
+pub fn log_event(event: &str, consumed: bool, latency: Duration) {
+    let mut log = EVENT_LOG.lock().expect("Event log is poisoned");
+    if log.len() == EVENT_LOG_SIZE {
+        log.pop_front();
+    }
+    log.push_back(format!("{} consumed: {} in {:?}", event, consumed, latency));
+}
+
+/// The logged events, oldest first
+pub fn events() -> Vec<String> {
+    EVENT_LOG.lock().expect("Event log is poisoned").iter().cloned().collect()
+}
+
+fn parse_value<T: std::str::FromStr>(path: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} is not a valid value for {}", value, path))
+}
+
+/// Changes the property of the window at path: title, status_bar.text, status_bar.progress (a
+/// fraction or none), status_bar.sections.<index> for the text of a section or
+/// container.<index>[.<index>...].<property> for the text and editable of a TextField or a TextArea
+/// (its scroll too), the text and chips (separated by commas) of a ChipInput, the title of a
+/// Button, the text, url and visited of a Hyperlink, the label and checked of a Checkbox, the
+/// selected index of a RadioGroup, a SegmentedControl, a ComboBox or a ListView, the date of a
+/// DatePicker (YYYY-MM-DD or none), the active tab of a TabPane, the active page of a Carousel, the
+/// loading of a Loadable (true or false), the scroll_x and scroll_y of a ScrollPane, the sort
+/// column of a TableView (ascending, or none), the path and scale_mode (fit, fill, stretch or tile)
+/// of an Image, the text, size (or none) and align (left, center or right) of a Label, the color of
+/// a ColorPicker (#rrggbb) and the value of a Slider, a Spinner, a Rating or a ProgressBar (a
+/// fraction or none)
+pub fn set_property(window: &mut Window, path: &str, value: &str) -> Result<(), String> {
+    let segments: Vec<&str> = path.split('.').collect();
+    match segments.as_slice() {
+        ["title"] => window.title = value.to_string(),
+        ["status_bar", property] => {
+            let status_bar = window.status_bar.as_mut().ok_or("Window has no status bar".to_string())?;
+            match *property {
+                "text" => status_bar.text = value.to_string(),
+                "progress" if value == "none" => status_bar.progress = None,
+                "progress" => status_bar.progress = Some(parse_value(path, value)?),
+                _ => return Err(format!("Unknown property {}", path)),
+            }
+        }
+        ["status_bar", "sections", index] => {
+            let status_bar = window.status_bar.as_mut().ok_or("Window has no status bar".to_string())?;
+            status_bar.set_section_text(parse_value(path, index)?, value)?;
+        }
+        ["container", indexes @ .., property] if !indexes.is_empty() => {
+            let indexes = indexes.iter().map(|i| parse_value(path, i)).collect::<Result<Vec<usize>, _>>()?;
+            let component = window.container.as_mut()
+                .and_then(|c| c.child_at_mut(&indexes))
+                .ok_or(format!("There is no component at {}", path))?;
+            set_component_property(component, path, property, value)?;
+        }
+        _ => return Err(format!("Unknown property {}", path)),
+    }
+    Ok(())
+}
+
+fn set_component_property(component: &mut Box<dyn Component>, path: &str, property: &str,
+                          value: &str) -> Result<(), String> {
+    if let Some(text_field) = component.downcast_mut::<TextField>() {
+        match property {
+            "text" => text_field.text = value.to_string(),
+            "editable" => text_field.editable = parse_value(path, value)?,
+            _ => return Err(format!("Unknown property {} of TextField", property)),
+        }
+    } else if let Some(text_area) = component.downcast_mut::<TextArea>() {
+        match property {
+            "text" => text_area.text = value.to_string(),
+            "editable" => text_area.editable = parse_value(path, value)?,
+            "scroll" => text_area.scroll = parse_value(path, value)?,
+            _ => return Err(format!("Unknown property {} of TextArea", property)),
+        }
+    } else if let Some(chip_input) = component.downcast_mut::<ChipInput>() {
+        match property {
+            "text" => chip_input.text = value.to_string(),
+            "chips" => {
+                chip_input.chips = value.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+            }
+            _ => return Err(format!("Unknown property {} of ChipInput", property)),
+        }
+    } else if let Some(button) = component.downcast_mut::<Button>() {
+        match property {
+            "title" => button.title = value.to_string(),
+            _ => return Err(format!("Unknown property {} of Button", property)),
+        }
+    } else if let Some(hyperlink) = component.downcast_mut::<Hyperlink>() {
+        match property {
+            "text" => hyperlink.text = value.to_string(),
+            "url" => hyperlink.url = value.to_string(),
+            "visited" => hyperlink.visited = parse_value(path, value)?,
+            _ => return Err(format!("Unknown property {} of Hyperlink", property)),
+        }
+    } else if let Some(checkbox) = component.downcast_mut::<Checkbox>() {
+        match property {
+            "label" => checkbox.label = value.to_string(),
+            "checked" => checkbox.checked = parse_value(path, value)?,
+            _ => return Err(format!("Unknown property {} of Checkbox", property)),
+        }
+    } else if let Some(group) = component.downcast_mut::<RadioGroup>() {
+        match property {
+            "selected" => {
+                let index: usize = parse_value(path, value)?;
+                if index >= group.children.len() {
+                    return Err(format!("{} has no option {}", path, index));
+                }
+                for (i, child) in group.children.iter_mut().enumerate() {
+                    child.selected = i == index;
+                }
+            }
+            _ => return Err(format!("Unknown property {} of RadioGroup", property)),
+        }
+    } else if let Some(segmented_control) = component.downcast_mut::<SegmentedControl>() {
+        match property {
+            "selected" => {
+                let index: usize = parse_value(path, value)?;
+                if index >= segmented_control.segments.len() {
+                    return Err(format!("{} has no segment {}", path, index));
+                }
+                segmented_control.select(index, None);
+            }
+            _ => return Err(format!("Unknown property {} of SegmentedControl", property)),
+        }
+    } else if let Some(rating) = component.downcast_mut::<Rating>() {
+        match property {
+            "value" => {
+                rating.set_value(parse_value(path, value)?, None);
+            }
+            _ => return Err(format!("Unknown property {} of Rating", property)),
+        }
+    } else if let Some(color_picker) = component.downcast_mut::<ColorPicker>() {
+        match property {
+            "color" => {
+                let hex = value.strip_prefix('#').filter(|h| h.len() == 6)
+                    .and_then(|h| u32::from_str_radix(h, 16).ok())
+                    .ok_or(format!("{} is not a valid value for {}", value, path))?;
+                let color = Color::rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8);
+                color_picker.set_color(color, None);
+            }
+            _ => return Err(format!("Unknown property {} of ColorPicker", property)),
+        }
+    } else if let Some(date_picker) = component.downcast_mut::<DatePicker>() {
+        match property {
+            "date" if value == "none" => date_picker.date = None,
+            "date" => {
+                date_picker.select(value.parse()?, None);
+            }
+            _ => return Err(format!("Unknown property {} of DatePicker", property)),
+        }
+    } else if let Some(combo_box) = component.downcast_mut::<ComboBox>() {
+        match property {
+            "selected" => {
+                let index: usize = parse_value(path, value)?;
+                if index >= combo_box.items.len() {
+                    return Err(format!("{} has no item {}", path, index));
+                }
+                combo_box.selected = Some(index);
+            }
+            _ => return Err(format!("Unknown property {} of ComboBox", property)),
+        }
+    } else if let Some(tab_pane) = component.downcast_mut::<TabPane>() {
+        match property {
+            "active" => {
+                let index: usize = parse_value(path, value)?;
+                if index >= tab_pane.tabs.len() {
+                    return Err(format!("{} has no tab {}", path, index));
+                }
+                tab_pane.active = index;
+            }
+            _ => return Err(format!("Unknown property {} of TabPane", property)),
+        }
+    } else if let Some(carousel) = component.downcast_mut::<Carousel>() {
+        match property {
+            "active" => {
+                let index: usize = parse_value(path, value)?;
+                if index >= carousel.pages.len() {
+                    return Err(format!("{} has no page {}", path, index));
+                }
+                carousel.go_to(index, None);
+            }
+            _ => return Err(format!("Unknown property {} of Carousel", property)),
+        }
+    } else if let Some(loadable) = component.downcast_mut::<Loadable>() {
+        match property {
+            "loading" => loadable.loading = parse_value(path, value)?,
+            _ => return Err(format!("Unknown property {} of Loadable", property)),
+        }
+    } else if let Some(scroll_pane) = component.downcast_mut::<ScrollPane>() {
+        match property {
+            "scroll_x" => scroll_pane.scroll.0 = parse_value(path, value)?,
+            "scroll_y" => scroll_pane.scroll.1 = parse_value(path, value)?,
+            _ => return Err(format!("Unknown property {} of ScrollPane", property)),
+        }
+    } else if let Some(list_view) = component.downcast_mut::<ListView>() {
+        match property {
+            "selected" => {
+                let row: usize = parse_value(path, value)?;
+                if row >= list_view.model.len() {
+                    return Err(format!("{} has no row {}", path, row));
+                }
+                list_view.model.selection.set_selected(vec![row]);
+                list_view.scroll_to(row);
+            }
+            _ => return Err(format!("Unknown property {} of ListView", property)),
+        }
+    } else if let Some(table_view) = component.downcast_mut::<TableView>() {
+        match property {
+            "sort" if value == "none" => table_view.proxy.set_sort_keys(vec![], &table_view.model),
+            "sort" => {
+                let column: usize = parse_value(path, value)?;
+                if column >= table_view.model.columns.len() {
+                    return Err(format!("{} has no column {}", path, column));
+                }
+                let key = SortKey { column, order: SortOrder::Ascending };
+                table_view.proxy.set_sort_keys(vec![key], &table_view.model);
+            }
+            _ => return Err(format!("Unknown property {} of TableView", property)),
+        }
+    } else if let Some(image) = component.downcast_mut::<Image>() {
+        match property {
+            "path" => image.source = ImageSource::Path(value.to_string()),
+            "scale_mode" => image.scale_mode = match value {
+                "fit" => ScaleMode::Fit,
+                "fill" => ScaleMode::Fill,
+                "stretch" => ScaleMode::Stretch,
+                "tile" => ScaleMode::Tile,
+                _ => return Err(format!("{} is not a valid value for {}", value, path)),
+            },
+            _ => return Err(format!("Unknown property {} of Image", property)),
+        }
+    } else if let Some(label) = component.downcast_mut::<Label>() {
+        match property {
+            "text" => label.text = value.to_string(),
+            "size" if value == "none" => label.size = None,
+            "size" => label.size = Some(parse_value(path, value)?),
+            "align" => label.align = match value {
+                "left" => TextAlign::Left,
+                "center" => TextAlign::Center,
+                "right" => TextAlign::Right,
+                _ => return Err(format!("{} is not a valid value for {}", value, path)),
+            },
+            _ => return Err(format!("Unknown property {} of Label", property)),
+        }
+    } else if let Some(slider) = component.downcast_mut::<Slider>() {
+        match property {
+            "value" => {
+                slider.set_value(parse_value(path, value)?, None);
+            }
+            _ => return Err(format!("Unknown property {} of Slider", property)),
+        }
+    } else if let Some(spinner) = component.downcast_mut::<Spinner>() {
+        match property {
+            "value" => {
+                spinner.set_value(parse_value(path, value)?, None);
+            }
+            _ => return Err(format!("Unknown property {} of Spinner", property)),
+        }
+    } else if let Some(progress_bar) = component.downcast_mut::<ProgressBar>() {
+        match property {
+            "value" if value == "none" => progress_bar.value = None,
+            "value" => progress_bar.value = Some(parse_value(path, value)?),
+            _ => return Err(format!("Unknown property {} of ProgressBar", property)),
+        }
+    } else {
+        return Err(format!("The properties of {:?} can't be set remotely", component));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_are_set_by_path() {
+        let mut window = Window {
+            container: Some(Container {
+                children: vec![Box::new(TextField::default()), Box::new(Checkbox::default())],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(RemoteCommand::parse("set container.0.text Hello world"), Ok(RemoteCommand::Set {
+            path: "container.0.text".to_string(),
+            value: "Hello world".to_string(),
+        }));
+        set_property(&mut window, "title", "Demo").unwrap();
+        set_property(&mut window, "container.0.text", "Hello world").unwrap();
+        set_property(&mut window, "container.1.checked", "true").unwrap();
+        assert_eq!(window.title, "Demo");
+        let container = window.container.as_mut().unwrap();
+        assert_eq!(container.child_at_mut(&[0]).unwrap().downcast_ref::<TextField>().unwrap().text,
+                   "Hello world");
+        assert!(container.child_at_mut(&[1]).unwrap().downcast_ref::<Checkbox>().unwrap().checked);
+        assert!(set_property(&mut window, "container.1.checked", "maybe").is_err());
+        assert!(set_property(&mut window, "status_bar.text", "Busy").is_err(), "No status bar");
+        window.status_bar = Some(StatusBar {
+            sections: vec![StatusSection { text: "Ln 1".to_string(), ..Default::default() }],
+            ..Default::default()
+        });
+        set_property(&mut window, "status_bar.sections.0", "Ln 2").unwrap();
+        assert_eq!(window.status_bar.as_ref().unwrap().sections[0].text, "Ln 2");
+        assert!(set_property(&mut window, "status_bar.sections.1", "Col 1").is_err(), "No such section");
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use crate::text_wrap::wrap_lines;
+
+/// Heights of the rows of a Table whose cells wrap their text. The height of every cell is cached
+/// with the width of its column, so resizing a column only measures again the cells of that column
+#[derive(Debug, Clone)]
+pub struct RowHeights {
+    pub line_height: f32,
+    /// Added above and below the text of the cells
+    pub padding: f32,
+    pub min_height: f32,
+    cache: HashMap<(usize, usize), (f32, f32)>,
+}
+
+impl RowHeights {
+    pub fn new(line_height: f32, padding: f32) -> Self {
+        RowHeights {
+            line_height,
+            padding,
+            min_height: line_height + 2. * padding,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The height of the row, the one of its tallest cell. widths are the column widths and
+    /// measure gives the width of a string, e.g. the text pipeline's measure_text()
+    pub fn row_height(&mut self, row: usize, cells: &[String], widths: &[f32],
+                      measure: &dyn Fn(&str) -> f32) -> f32 {
+        let mut height = self.min_height;
+        for (column, (cell, width)) in cells.iter().zip(widths).enumerate() {
+            let cell_height = match self.cache.get(&(row, column)) {
+                Some((cached_width, cell_height)) if cached_width == width => *cell_height,
+                _ => {
+                    let lines = wrap_lines(cell, (width - 2. * self.padding).max(0.), measure);
+                    let cell_height = lines.len() as f32 * self.line_height + 2. * self.padding;
+                    self.cache.insert((row, column), (*width, cell_height));
+                    cell_height
+                }
+            };
+            height = height.max(cell_height);
+        }
+        height
+    }
+
+    /// Forgets the cells of the row, it has to be called when its content changes
+    pub fn invalidate_row(&mut self, row: usize) {
+        self.cache.retain(|(r, _), _| *r != row);
+    }
+
+    /// Forgets the cells of the column, e.g. when it's resized
+    pub fn invalidate_column(&mut self, column: usize) {
+        self.cache.retain(|(_, c), _| *c != column);
+    }
+
+    /// Forgets everything, e.g. when the rows change or the font does
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn only_resized_columns_are_measured_again() {
+        let measured = Cell::new(0);
+        let measure = |text: &str| {
+            measured.set(measured.get() + 1);
+            text.chars().count() as f32 * 10.
+        };
+        let mut heights = RowHeights::new(20., 2.);
+        let cells = vec!["one two three".to_string(), "short".to_string()];
+        assert_eq!(heights.row_height(0, &cells, &[54., 100.], &measure), 64.);
+        let after_first = measured.get();
+        assert_eq!(heights.row_height(0, &cells, &[54., 100.], &measure), 64.);
+        assert_eq!(measured.get(), after_first);
+        assert_eq!(heights.row_height(0, &cells, &[200., 100.], &measure), 24.);
+        assert!(measured.get() > after_first);
+        let after_resize = measured.get();
+        heights.invalidate_column(1);
+        assert_eq!(heights.row_height(0, &cells, &[200., 100.], &measure), 24.);
+        assert_eq!(measured.get(), after_resize + 1, "Only the short cell is measured again");
+    }
+}
@@ -0,0 +1,197 @@
+/// A group of rows under a header, like "A" in a contact list or "Display" in settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: String,
+    pub rows: usize,
+}
+
+/// What is at a position of a sectioned ListView or Table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SectionEntry {
+    Header(usize),
+    Row { section: usize, row: usize },
+}
+
+/// Vertical layout of grouped rows whose headers stick to the top of the viewport while their
+/// section is scrolled, until the next header pushes them out. Positions are in content
+/// coordinates (0 is the top of the first header) unless they say viewport
+#[derive(Debug, Clone)]
+pub struct SectionLayout {
+    pub sections: Vec<Section>,
+    pub header_height: f32,
+    pub row_height: f32,
+}
+
+impl SectionLayout {
+    pub fn new(sections: Vec<Section>, header_height: f32, row_height: f32) -> Self {
+        SectionLayout {
+            sections,
+            header_height,
+            row_height,
+        }
+    }
+
+    fn section_height(&self, section: &Section) -> f32 {
+        self.header_height + section.rows as f32 * self.row_height
+    }
+
+    /// The index of the first row of the section, counting the rows of the sections before it
+    pub fn first_row(&self, section: usize) -> usize {
+        self.sections.iter().take(section).map(|s| s.rows).sum()
+    }
+
+    /// Where the header of the section is
+    pub fn section_top(&self, index: usize) -> f32 {
+        self.sections.iter().take(index).map(|s| self.section_height(s)).sum()
+    }
+
+    pub fn content_height(&self) -> f32 {
+        self.sections.iter().map(|s| self.section_height(s)).sum()
+    }
+
+    /// The entry at y, without taking the sticky header into account
+    pub fn entry_at(&self, y: f32) -> Option<SectionEntry> {
+        if y < 0. {
+            return None;
+        }
+        let mut top = 0.;
+        for (index, section) in self.sections.iter().enumerate() {
+            let bottom = top + self.section_height(section);
+            if y < bottom {
+                return Some(match y - top - self.header_height {
+                    offset if offset < 0. => SectionEntry::Header(index),
+                    offset => SectionEntry::Row {
+                        section: index,
+                        row: (offset / self.row_height) as usize,
+                    },
+                });
+            }
+            top = bottom;
+        }
+        None
+    }
+
+    /// The header pinned to the top of the viewport for the scroll offset and its viewport y,
+    /// which is negative while the next header is pushing it out
+    pub fn sticky_header(&self, scroll: f32) -> Option<(usize, f32)> {
+        let mut top = 0.;
+        let mut pinned = None;
+        for (index, section) in self.sections.iter().enumerate() {
+            if top > scroll {
+                let overlap = top - scroll - self.header_height;
+                return pinned.map(|p| (p, overlap.min(0.)));
+            }
+            pinned = Some(index);
+            top += self.section_height(section);
+        }
+        pinned.map(|p| (p, 0.))
+    }
+
+    /// What is under viewport_y, the sticky header covers the entries scrolled under it
+    pub fn hit_test(&self, viewport_y: f32, scroll: f32) -> Option<SectionEntry> {
+        match self.sticky_header(scroll) {
+            Some((section, y)) if viewport_y >= y && viewport_y < y + self.header_height => {
+                Some(SectionEntry::Header(section))
+            }
+            _ => self.entry_at(viewport_y + scroll),
+        }
+    }
+
+    /// The entries intersecting the viewport with their viewport y, the sticky header is not
+    /// included so views draw it last, on top
+    pub fn visible_entries(&self, scroll: f32, viewport_height: f32) -> Vec<(SectionEntry, f32)> {
+        let mut entries = vec![];
+        let mut top = 0.;
+        for (index, section) in self.sections.iter().enumerate() {
+            if top - scroll >= viewport_height {
+                break;
+            }
+            if top + self.header_height > scroll {
+                entries.push((SectionEntry::Header(index), top - scroll));
+            }
+            for row in 0..section.rows {
+                let y = top + self.header_height + row as f32 * self.row_height;
+                if y + self.row_height > scroll && y - scroll < viewport_height {
+                    entries.push((SectionEntry::Row { section: index, row }, y - scroll));
+                }
+            }
+            top += self.section_height(section);
+        }
+        entries
+    }
+
+    /// The rows intersecting the viewport, numbered across sections, with their viewport y
+    pub fn visible_rows(&self, scroll: f32, viewport_height: f32) -> Vec<(usize, f32)> {
+        self.visible_entries(scroll, viewport_height).into_iter()
+            .filter_map(|(entry, y)| match entry {
+                SectionEntry::Row { section, row } => Some((self.first_row(section) + row, y)),
+                SectionEntry::Header(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// The line of row in a view that scrolls by lines (ListView, TableView), where the header of each
+/// section takes the line before its rows. The rows past the sections follow them
+pub fn line_of_row(sections: &[Section], row: usize) -> usize {
+    let mut first = 0;
+    for (index, section) in sections.iter().enumerate() {
+        if row < first + section.rows {
+            return row + index + 1;
+        }
+        first += section.rows;
+    }
+    row + sections.len()
+}
+
+/// The first row at line or after it, see line_of_row()
+pub fn row_at_line(sections: &[Section], line: usize) -> usize {
+    let mut first = 0;
+    for (index, section) in sections.iter().enumerate() {
+        let header = first + index;
+        if line <= header + section.rows {
+            return first + line.saturating_sub(header + 1);
+        }
+        first += section.rows;
+    }
+    line - sections.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contacts() -> SectionLayout {
+        SectionLayout::new(vec![Section { title: "A".to_string(), rows: 3 },
+                                Section { title: "B".to_string(), rows: 2 }], 20., 10.)
+    }
+
+    #[test]
+    fn header_sticks_until_pushed_by_the_next_one() {
+        let layout = contacts();
+        assert_eq!(layout.sticky_header(0.), Some((0, 0.)));
+        assert_eq!(layout.sticky_header(25.), Some((0, 0.)));
+        // B's header is at 50, it starts pushing A's one out at 30
+        assert_eq!(layout.sticky_header(40.), Some((0, -10.)));
+        assert_eq!(layout.sticky_header(55.), Some((1, 0.)));
+    }
+
+    #[test]
+    fn sticky_header_covers_the_rows_under_it() {
+        let layout = contacts();
+        assert_eq!(layout.hit_test(5., 25.), Some(SectionEntry::Header(0)));
+        assert_eq!(layout.hit_test(15., 10.), Some(SectionEntry::Header(0)));
+        assert_eq!(layout.hit_test(25., 10.), Some(SectionEntry::Row { section: 0, row: 1 }));
+        assert_eq!(layout.entry_at(75.), Some(SectionEntry::Row { section: 1, row: 0 }));
+    }
+
+    #[test]
+    fn headers_take_a_line_before_their_rows() {
+        let sections = contacts().sections;
+        let lines: Vec<usize> = (0..6).map(|row| line_of_row(&sections, row)).collect();
+        assert_eq!(lines, vec![1, 2, 3, 5, 6, 7]);
+        let rows: Vec<usize> = (0..8).map(|line| row_at_line(&sections, line)).collect();
+        assert_eq!(rows, vec![0, 0, 1, 2, 3, 3, 4, 5]);
+        assert_eq!(contacts().visible_rows(30., 50.), vec![(1, 0.), (2, 10.), (3, 40.)]);
+    }
+}
@@ -0,0 +1,188 @@
+/// A selectable item of a view (ListView, Canvas, IconView...) as given to the Selection, items
+/// are expected in their visual order so Shift-click can select ranges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectionItem {
+    pub id: usize,
+    /// Where the item is as (x, y, width, height), in the same coordinates as the pointer
+    pub bounds: (f32, f32, f32, f32),
+}
+
+/// Modifier keys held while clicking or starting a rubber band
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SelectModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+/// What is given to on_selection_changed, the ids are sorted
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SelectionChanged {
+    pub selected: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct RubberBand {
+    start: (f32, f32),
+    end: (f32, f32),
+    /// Selection when the drag started, it's kept when extending with Ctrl or Shift
+    base: Vec<usize>,
+}
+
+/// Selection controller shared by the views with selectable items. It handles plain clicks,
+/// Ctrl-click (toggle), Shift-click (range from the last clicked item) and rubber-band dragging,
+/// calling on_selection_changed every time the selected ids change
+#[derive(Debug, Clone)]
+pub struct Selection {
+    selected: Vec<usize>,
+    anchor: Option<usize>,
+    rubber_band: Option<RubberBand>,
+    pub on_selection_changed: fn(SelectionChanged) -> bool,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection {
+            selected: vec![],
+            anchor: None,
+            rubber_band: None,
+            on_selection_changed: |_event| true,
+        }
+    }
+}
+
+impl Selection {
+    /// The selected ids, sorted
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    pub fn is_selected(&self, id: usize) -> bool {
+        self.selected.binary_search(&id).is_ok()
+    }
+
+    /// Handles a click on the item with the given id, returns whether the selection changed
+    pub fn click(&mut self, id: usize, modifiers: SelectModifiers, items: &[SelectionItem]) -> bool {
+        let anchor_index = self.anchor.and_then(|a| items.iter().position(|i| i.id == a));
+        let index = items.iter().position(|i| i.id == id);
+        let selected = match (modifiers.shift, anchor_index, index) {
+            (true, Some(anchor_index), Some(index)) => {
+                let range = anchor_index.min(index)..=anchor_index.max(index);
+                let mut selected: Vec<usize> = items[range].iter().map(|i| i.id).collect();
+                if modifiers.ctrl {
+                    selected.extend_from_slice(&self.selected);
+                }
+                selected
+            }
+            _ if modifiers.ctrl => {
+                self.anchor = Some(id);
+                let mut selected = self.selected.clone();
+                match selected.iter().position(|s| *s == id) {
+                    Some(position) => {
+                        selected.remove(position);
+                    }
+                    None => selected.push(id),
+                }
+                selected
+            }
+            _ => {
+                self.anchor = Some(id);
+                vec![id]
+            }
+        };
+        self.set_selected(selected)
+    }
+
+    /// Starts dragging a rubber band at the given point, usually when pressing on empty space.
+    /// Without modifiers the current selection is cleared
+    pub fn begin_rubber_band(&mut self, x: f32, y: f32, modifiers: SelectModifiers) -> bool {
+        let base = if modifiers.ctrl || modifiers.shift { self.selected.clone() } else { vec![] };
+        self.rubber_band = Some(RubberBand {
+            start: (x, y),
+            end: (x, y),
+            base: base.clone(),
+        });
+        self.set_selected(base)
+    }
+
+    /// Moves the end of the rubber band, the items intersecting it are selected. Returns whether
+    /// the selection changed
+    pub fn drag_rubber_band(&mut self, x: f32, y: f32, items: &[SelectionItem]) -> bool {
+        let band = match self.rubber_band.as_mut() {
+            Some(band) => band,
+            None => return false,
+        };
+        band.end = (x, y);
+        let (bx, by, bw, bh) = band_bounds(band);
+        let mut selected = band.base.clone();
+        selected.extend(items.iter()
+            .filter(|i| {
+                let (ix, iy, iw, ih) = i.bounds;
+                ix < bx + bw && bx < ix + iw && iy < by + bh && by < iy + ih
+            })
+            .map(|i| i.id));
+        self.set_selected(selected)
+    }
+
+    pub fn end_rubber_band(&mut self) {
+        self.rubber_band = None;
+    }
+
+    /// The rectangle being dragged as (x, y, width, height), so views can draw it
+    pub fn rubber_band(&self) -> Option<(f32, f32, f32, f32)> {
+        self.rubber_band.as_ref().map(band_bounds)
+    }
+
+    /// Replaces the selection, on_selection_changed is called when it's different
+    pub fn set_selected(&mut self, mut selected: Vec<usize>) -> bool {
+        selected.sort_unstable();
+        selected.dedup();
+        if selected == self.selected {
+            return false;
+        }
+        self.selected = selected;
+        (self.on_selection_changed)(SelectionChanged { selected: self.selected.clone() });
+        true
+    }
+}
+
+fn band_bounds(band: &RubberBand) -> (f32, f32, f32, f32) {
+    let (x, y) = (band.start.0.min(band.end.0), band.start.1.min(band.end.1));
+    (x, y, (band.start.0 - band.end.0).abs(), (band.start.1 - band.end.1).abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(ids: &[usize]) -> Vec<SelectionItem> {
+        ids.iter().map(|id| SelectionItem { id: *id, bounds: (*id as f32 * 10., 0., 10., 10.) })
+            .collect()
+    }
+
+    #[test]
+    fn clicks_select_toggle_and_extend() {
+        let items = row(&[0, 1, 2, 3, 4]);
+        let mut selection = Selection::default();
+        let ctrl = SelectModifiers { ctrl: true, shift: false };
+        let shift = SelectModifiers { ctrl: false, shift: true };
+        assert!(selection.click(1, SelectModifiers::default(), &items));
+        assert!(selection.click(3, shift, &items));
+        assert_eq!(selection.selected(), &[1, 2, 3]);
+        assert!(selection.click(2, ctrl, &items));
+        assert_eq!(selection.selected(), &[1, 3]);
+        assert!(selection.click(4, SelectModifiers::default(), &items));
+        assert_eq!(selection.selected(), &[4]);
+    }
+
+    #[test]
+    fn rubber_band_selects_intersecting_items() {
+        let items = row(&[0, 1, 2, 3, 4]);
+        let mut selection = Selection::default();
+        selection.begin_rubber_band(25., 5., SelectModifiers::default());
+        assert!(selection.drag_rubber_band(12., 8., &items));
+        assert_eq!(selection.selected(), &[1, 2]);
+        assert_eq!(selection.rubber_band(), Some((12., 5., 13., 3.)));
+        selection.end_rubber_band();
+        assert_eq!(selection.rubber_band(), None);
+    }
+}
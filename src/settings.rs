@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use crate::sections::Section;
+
+/// The value of a setting, the type of a setting is the one of its default value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl SettingValue {
+    fn same_type(&self, other: &SettingValue) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Parses text as a value of the same type as self
+    fn parse_like(&self, text: &str) -> Option<SettingValue> {
+        match self {
+            SettingValue::Bool(_) => text.parse().ok().map(SettingValue::Bool),
+            SettingValue::Int(_) => text.parse().ok().map(SettingValue::Int),
+            SettingValue::Float(_) => text.parse().ok().map(SettingValue::Float),
+            SettingValue::Text(_) => Some(SettingValue::Text(unescape(text))),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            SettingValue::Bool(value) => value.to_string(),
+            SettingValue::Int(value) => value.to_string(),
+            SettingValue::Float(value) => value.to_string(),
+            SettingValue::Text(value) => escape(value),
+        }
+    }
+}
+
+impl Display for SettingValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingValue::Bool(true) => write!(f, "On"),
+            SettingValue::Bool(false) => write!(f, "Off"),
+            SettingValue::Int(value) => write!(f, "{}", value),
+            SettingValue::Float(value) => write!(f, "{}", value),
+            SettingValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Rust types that can be the type of a setting
+pub trait SettingType: Sized {
+    fn into_value(self) -> SettingValue;
+    fn from_value(value: &SettingValue) -> Option<Self>;
+}
+
+impl SettingType for bool {
+    fn into_value(self) -> SettingValue {
+        SettingValue::Bool(self)
+    }
+
+    fn from_value(value: &SettingValue) -> Option<Self> {
+        match value {
+            SettingValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl SettingType for i64 {
+    fn into_value(self) -> SettingValue {
+        SettingValue::Int(self)
+    }
+
+    fn from_value(value: &SettingValue) -> Option<Self> {
+        match value {
+            SettingValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl SettingType for f64 {
+    fn into_value(self) -> SettingValue {
+        SettingValue::Float(self)
+    }
+
+    fn from_value(value: &SettingValue) -> Option<Self> {
+        match value {
+            SettingValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl SettingType for String {
+    fn into_value(self) -> SettingValue {
+        SettingValue::Text(self)
+    }
+
+    fn from_value(value: &SettingValue) -> Option<Self> {
+        match value {
+            SettingValue::Text(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The declaration of a setting, key is what identifies it in the code and in the saved file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingSpec {
+    pub key: String,
+    pub title: String,
+    /// Settings are grouped by category in the settings dialog
+    pub category: String,
+    pub description: String,
+    pub default: SettingValue,
+}
+
+/// What subscribers get when a setting changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingChanged {
+    pub key: String,
+    pub value: SettingValue,
+}
+
+/// Settings declared once with their type, default, category and description. Changes are saved
+/// right away to the file given to load() (key=value lines) and notified to the subscribers,
+/// e.g. the app or the theme
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    specs: Vec<SettingSpec>,
+    values: HashMap<String, SettingValue>,
+    file: Option<PathBuf>,
+    subscribers: Vec<fn(&SettingChanged)>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Settings::default()
+    }
+
+    /// Declares a setting, its value is the default until it's set or loaded
+    pub fn declare<T: SettingType>(mut self, key: &str, title: &str, category: &str,
+                                   description: &str, default: T) -> Self {
+        self.specs.retain(|s| s.key != key);
+        self.specs.push(SettingSpec {
+            key: key.to_string(),
+            title: title.to_string(),
+            category: category.to_string(),
+            description: description.to_string(),
+            default: default.into_value(),
+        });
+        self
+    }
+
+    /// Reads the values saved in file, it's also where changes are saved from now on. Unknown
+    /// keys and values of the wrong type are ignored
+    pub fn load<P: Into<PathBuf>>(&mut self, file: P) -> Result<(), String> {
+        let file = file.into();
+        match std::fs::read_to_string(&file) {
+            Ok(content) => {
+                for (key, text) in content.lines().filter_map(|l| l.split_once('=')) {
+                    let value = self.spec(key.trim()).and_then(|s| s.default.parse_like(text));
+                    if let Some(value) = value {
+                        self.values.insert(key.trim().to_string(), value);
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to read settings {}: {}", file.display(), e)),
+        }
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Writes the values that are not the default ones, nothing is done until load() was called
+    pub fn save(&self) -> Result<(), String> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        let lines: Vec<String> = self.specs.iter()
+            .filter_map(|s| self.values.get(&s.key).map(|v| format!("{}={}", s.key, v.to_text())))
+            .collect();
+        std::fs::write(file, lines.join("\n"))
+            .map_err(|e| format!("Failed to save settings {}: {}", file.display(), e))
+    }
+
+    pub fn specs(&self) -> &[SettingSpec] {
+        &self.specs
+    }
+
+    pub fn spec(&self, key: &str) -> Option<&SettingSpec> {
+        self.specs.iter().find(|s| s.key == key)
+    }
+
+    pub fn value(&self, key: &str) -> Option<&SettingValue> {
+        self.values.get(key).or_else(|| self.spec(key).map(|s| &s.default))
+    }
+
+    /// The value of the setting, None when it's not declared or has another type
+    pub fn get<T: SettingType>(&self, key: &str) -> Option<T> {
+        self.value(key).and_then(T::from_value)
+    }
+
+    /// Changes a setting, saves and notifies the subscribers when the value is different
+    pub fn set<T: SettingType>(&mut self, key: &str, value: T) -> Result<(), String> {
+        self.set_value(key, value.into_value())
+    }
+
+    pub fn set_value(&mut self, key: &str, value: SettingValue) -> Result<(), String> {
+        let spec = self.spec(key).ok_or(format!("No setting declared as {}", key))?;
+        if !spec.default.same_type(&value) {
+            return Err(format!("Setting {} is {:?}, not {:?}", key, spec.default, value));
+        }
+        if self.value(key) == Some(&value) {
+            return Ok(());
+        }
+        self.values.insert(key.to_string(), value.clone());
+        self.save()?;
+        let changed = SettingChanged { key: key.to_string(), value };
+        for subscriber in self.subscribers.iter() {
+            subscriber(&changed);
+        }
+        Ok(())
+    }
+
+    /// Sets the setting to the typed text, parsed as its type. Text settings take it as it is
+    pub fn set_text(&mut self, key: &str, text: &str) -> Result<(), String> {
+        let spec = self.spec(key).ok_or(format!("No setting declared as {}", key))?;
+        let value = match &spec.default {
+            SettingValue::Text(_) => Some(SettingValue::Text(text.to_string())),
+            default => default.parse_like(text.trim()),
+        };
+        let value = value.ok_or(format!("{} is not a valid {}", text, spec.title))?;
+        self.set_value(key, value)
+    }
+
+    /// Goes back to the default value
+    pub fn reset(&mut self, key: &str) -> Result<(), String> {
+        let default = self.spec(key).ok_or(format!("No setting declared as {}", key))?.default.clone();
+        self.set_value(key, default)?;
+        self.values.remove(key);
+        self.save()
+    }
+
+    pub fn subscribe(&mut self, subscriber: fn(&SettingChanged)) {
+        self.subscribers.push(subscriber);
+    }
+
+    /// The specs grouped by category in declaration order, with the Sections the settings dialog
+    /// lays out with sticky category headers
+    pub fn by_category(&self) -> (Vec<Section>, Vec<&SettingSpec>) {
+        let mut categories: Vec<&str> = vec![];
+        for spec in self.specs.iter() {
+            if !categories.contains(&spec.category.as_str()) {
+                categories.push(&spec.category);
+            }
+        }
+        let mut sections = vec![];
+        let mut specs = vec![];
+        for category in categories {
+            let in_category: Vec<&SettingSpec> = self.specs.iter()
+                .filter(|s| s.category == category).collect();
+            sections.push(Section { title: category.to_string(), rows: in_category.len() });
+            specs.extend(in_category);
+        }
+        (sections, specs)
+    }
+}
+
+/// State of the settings dialog (see UiMessage::ShowSettings): the Settings it edits and how far
+/// it's scrolled. Clicking a row toggles a Bool setting, the others have their value typed and
+/// set with Return. Changes are saved and notified like with Settings::set()
+#[derive(Debug, Clone)]
+pub struct SettingsDialog {
+    pub settings: Settings,
+    pub scroll: f32,
+    /// The key of the setting being typed and the text typed so far
+    pub editing: Option<(String, String)>,
+}
+
+impl SettingsDialog {
+    pub fn open(settings: Settings) -> Self {
+        SettingsDialog { settings, scroll: 0., editing: None }
+    }
+
+    /// The key of the row in the section, as they are laid out by Settings::by_category()
+    pub fn key_at(&self, section: usize, row: usize) -> Option<String> {
+        let (sections, specs) = self.settings.by_category();
+        let index = sections.iter().take(section).map(|s| s.rows).sum::<usize>() + row;
+        sections.get(section).filter(|s| row < s.rows).and(specs.get(index)).map(|s| s.key.clone())
+    }
+
+    /// Toggles the Bool setting of the row, or starts typing the value of the others. Typing
+    /// another one sets it first. Returns whether anything changed
+    pub fn click(&mut self, section: usize, row: usize) -> Result<bool, String> {
+        let key = match self.key_at(section, row) {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+        if self.editing.as_ref().is_some_and(|(editing, _)| *editing == key) {
+            return Ok(false);
+        }
+        self.commit()?;
+        match self.settings.value(&key).cloned() {
+            Some(SettingValue::Bool(value)) => self.settings.set(&key, !value)?,
+            Some(value) => self.editing = Some((key, value.to_string())),
+            None => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        if let Some((_, typed)) = self.editing.as_mut() {
+            typed.push_str(text);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some((_, typed)) = self.editing.as_mut() {
+            typed.pop();
+        }
+    }
+
+    /// Sets the setting being typed to the text, the typing ends even when it's not valid
+    pub fn commit(&mut self) -> Result<(), String> {
+        match self.editing.take() {
+            Some((key, typed)) => self.settings.set_text(&key, &typed),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declared() -> Settings {
+        Settings::new()
+            .declare("editor.font_size", "Font size", "Editor", "Size of the text", 14i64)
+            .declare("editor.title", "Title", "Editor", "Window title", "Hi\nthere".to_string())
+            .declare("ui.animations", "Animations", "Interface", "Animate transitions", true)
+    }
+
+    #[test]
+    fn settings_are_typed_and_persisted() {
+        let file = std::env::temp_dir().join(format!("rui-lopez-settings-{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let mut settings = declared();
+        settings.load(&file).unwrap();
+        assert_eq!(settings.get::<i64>("editor.font_size"), Some(14));
+        assert!(settings.set("editor.font_size", "big".to_string()).is_err());
+        settings.set("editor.font_size", 18i64).unwrap();
+        settings.set("ui.animations", false).unwrap();
+        let mut loaded = declared();
+        loaded.load(&file).unwrap();
+        assert_eq!(loaded.get::<i64>("editor.font_size"), Some(18));
+        assert_eq!(loaded.get::<bool>("ui.animations"), Some(false));
+        assert_eq!(loaded.get::<String>("editor.title"), Some("Hi\nthere".to_string()));
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn settings_are_grouped_by_category() {
+        let settings = declared();
+        let (sections, specs) = settings.by_category();
+        assert_eq!(sections.iter().map(|s| s.rows).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(specs[2].key, "ui.animations");
+    }
+
+    #[test]
+    fn the_dialog_toggles_and_types_settings() {
+        let file = std::env::temp_dir().join(format!("rui-lopez-settings-dialog-{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let mut settings = declared();
+        settings.load(&file).unwrap();
+        let mut dialog = SettingsDialog::open(settings);
+        assert_eq!(dialog.click(1, 0), Ok(true));
+        assert_eq!(dialog.click(0, 0), Ok(true));
+        assert_eq!(dialog.editing, Some(("editor.font_size".to_string(), "14".to_string())));
+        dialog.pop_char();
+        dialog.push_str("6");
+        dialog.commit().unwrap();
+        assert_eq!(dialog.click(0, 0), Ok(true));
+        dialog.push_str("x");
+        assert!(dialog.commit().is_err());
+        assert_eq!(dialog.click(2, 0), Ok(false), "There is no third category");
+        let mut loaded = declared();
+        loaded.load(&file).unwrap();
+        assert_eq!(loaded.get::<i64>("editor.font_size"), Some(16));
+        assert_eq!(loaded.get::<bool>("ui.animations"), Some(false));
+        std::fs::remove_file(&file).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::ui_channel::{self, UiMessage};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Arguments another launch of the app forwarded, the engine raises the window and calls
+/// on_arguments with them on the UI thread
+#[derive(Debug, Clone)]
+pub struct ForwardedArguments {
+    pub arguments: Vec<String>,
+    pub on_arguments: fn(&[String]),
+}
+
+/// Result of acquire()
+#[derive(Debug)]
+pub enum Instance {
+    /// This is the first instance, keep the guard alive while the app runs
+    Primary(InstanceGuard),
+    /// Another instance got the arguments, this one should exit
+    Secondary,
+}
+
+/// Removes the instance file when dropped, so the next launch becomes the primary one
+#[derive(Debug)]
+pub struct InstanceGuard {
+    file: PathBuf,
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.file);
+    }
+}
+
+/// Makes sure only one instance of the app called name runs. The first one listens on a loopback
+/// socket whose port and secrets are in a file only the user can access, later launches send it
+/// their arguments (e.g. a file to open) and get Instance::Secondary
+pub fn acquire(name: &str, arguments: &[String], on_arguments: fn(&[String])) -> Result<Instance, String> {
+    let file = instance_dir()?.join(format!("{}.instance", name));
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .map_err(|e| format!("Failed to listen for other instances: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let secrets = Secrets { token: secret()?, ack: secret()? };
+    let deadline = Instant::now() + CONNECT_TIMEOUT;
+    // Created exclusively, so of two launches starting together only one becomes the primary
+    loop {
+        match create_private(&file) {
+            Ok(mut created) => {
+                created.write_all(format!("{}\n{}\n{}\n", port, secrets.token, secrets.ack).as_bytes())
+                    .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+                break;
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to create {}: {}", file.display(), e)),
+        }
+        let content = std::fs::read_to_string(&file).unwrap_or_default();
+        match parse_instance_file(&content) {
+            Some((port, primary)) if forward(port, &primary, arguments).is_ok() => return Ok(Instance::Secondary),
+            // The primary is still writing it
+            None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            // Left by an instance that didn't exit cleanly, its port may belong to another program
+            _ => {
+                let _ = std::fs::remove_file(&file);
+            }
+        }
+    }
+    std::thread::spawn(move || {
+        for stream in listener.incoming().filter_map(|s| s.ok()) {
+            match receive(stream, &secrets) {
+                Ok(arguments) => ui_channel::post(UiMessage::ArgumentsForwarded(ForwardedArguments {
+                    arguments,
+                    on_arguments,
+                })),
+                Err(e) => println!("Ignoring a connection from another instance {}", e),
+            }
+        }
+    });
+    Ok(Instance::Primary(InstanceGuard { file }))
+}
+
+/// The token proves a launch read the instance file, the ack proves the primary wrote it. The ack
+/// is never sent by a launch, so whatever else listens on a stale port can't answer with it
+struct Secrets {
+    token: String,
+    ack: String,
+}
+
+const SECRET_BYTES: usize = 16;
+
+/// Hex of random bytes from the OS
+fn secret() -> Result<String, String> {
+    let mut bytes = [0u8; SECRET_BYTES];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to get random bytes: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The port and secrets of a completely written instance file
+fn parse_instance_file(content: &str) -> Option<(u16, Secrets)> {
+    if !content.ends_with('\n') {
+        return None;
+    }
+    let mut lines = content.lines();
+    let port = lines.next()?.parse::<u16>().ok()?;
+    let token = lines.next()?.to_string();
+    let ack = lines.next()?.to_string();
+    match token.len() == 2 * SECRET_BYTES && ack.len() == 2 * SECRET_BYTES {
+        true => Some((port, Secrets { token, ack })),
+        false => None,
+    }
+}
+
+/// Where the instance files are, a directory other users can't access so they can neither read
+/// the secrets nor plant a file pointing to their own port. The temp dir is per user on Windows
+/// and macOS, not on the other unixes
+fn instance_dir() -> Result<PathBuf, String> {
+    #[cfg(any(not(unix), target_os = "macos"))]
+    let base = std::env::temp_dir();
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let base = match (std::env::var_os("XDG_RUNTIME_DIR"), std::env::var_os("HOME")) {
+        (Some(runtime), _) => PathBuf::from(runtime),
+        (None, Some(home)) => PathBuf::from(home).join(".cache"),
+        (None, None) => return Err("There is no per-user directory for the instance file".to_string()),
+    };
+    let dir = base.join("rui_lopez");
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Creates the file readable and writable only by the user, failing when it already exists
+fn create_private(file: &Path) -> std::io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(file)
+}
+
+/// Sends the token line and waits for the ack line of the primary before sending the arguments
+/// separated by \0, so they only reach the primary
+fn forward(port: u16, secrets: &Secrets, arguments: &[String]) -> Result<(), String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream);
+    reader.get_mut().write_all(format!("{}\n", secrets.token).as_bytes()).map_err(|e| e.to_string())?;
+    let mut ack = String::new();
+    reader.read_line(&mut ack).map_err(|e| e.to_string())?;
+    if ack.trim_end_matches('\n') != secrets.ack {
+        return Err("Something that isn't the primary instance listens on its port".to_string());
+    }
+    reader.get_mut().write_all(arguments.join("\0").as_bytes()).map_err(|e| e.to_string())
+}
+
+fn receive(stream: TcpStream, secrets: &Secrets) -> Result<Vec<String>, String> {
+    stream.set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream);
+    let mut token = String::new();
+    reader.read_line(&mut token).map_err(|e| e.to_string())?;
+    if token.trim_end_matches('\n') != secrets.token {
+        return Err("with a wrong token".to_string());
+    }
+    reader.get_mut().write_all(format!("{}\n", secrets.ack).as_bytes()).map_err(|e| e.to_string())?;
+    let mut arguments = String::new();
+    reader.read_to_string(&mut arguments).map_err(|e| e.to_string())?;
+    Ok(match arguments.is_empty() {
+        true => vec![],
+        false => arguments.split('\0').map(|a| a.to_string()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_instance_forwards_its_arguments() {
+        let name = format!("rui-lopez-test-{}", std::process::id());
+        let guard = acquire(&name, &[], |_arguments| {}).unwrap();
+        #[cfg(unix)]
+        if let Instance::Primary(guard) = &guard {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&guard.file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(matches!(guard, Instance::Primary(_)));
+        let arguments = vec!["notes.txt".to_string(), "--line=3".to_string()];
+        assert!(matches!(acquire(&name, &arguments, |_arguments| {}), Ok(Instance::Secondary)));
+        let mut forwarded = None;
+        for _ in 0..100 {
+            forwarded = ui_channel::drain().into_iter().find_map(|m| match m {
+                UiMessage::ArgumentsForwarded(f) => Some(f.arguments),
+                _ => None,
+            });
+            if forwarded.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(forwarded, Some(arguments));
+    }
+
+    #[test]
+    fn stale_file_of_a_reused_port_is_replaced() {
+        let name = format!("rui-lopez-stale-test-{}", std::process::id());
+        // Another program now listens on the port of an instance that didn't exit cleanly
+        let other = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = other.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in other.incoming().filter_map(|s| s.ok()) {
+                let _ = stream.write_all(b"hello\n");
+            }
+        });
+        let file = instance_dir().unwrap().join(format!("{}.instance", name));
+        let stale = format!("{}\n{}\n{}\n", port, secret().unwrap(), secret().unwrap());
+        create_private(&file).unwrap().write_all(stale.as_bytes()).unwrap();
+        let guard = acquire(&name, &["notes.txt".to_string()], |_arguments| {}).unwrap();
+        assert!(matches!(guard, Instance::Primary(_)));
+        assert_ne!(std::fs::read_to_string(&file).unwrap(), stale);
+    }
+}
@@ -0,0 +1,125 @@
+/// What fires when a StateMachine enters or leaves a state, it gets the state and the event
+/// causing the transition
+pub type StateAction<S, E> = fn(S, E);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transition<S, E> {
+    /// None for a transition from any state
+    from: Option<S>,
+    event: E,
+    to: S,
+}
+
+/// A flat statechart: the current state changes when it gets an event with a transition from it,
+/// running the exit actions of the old state and then the entry actions of the new one. Widgets
+/// use it for their interaction states (see ButtonState) instead of separate booleans
+#[derive(Debug, Clone)]
+pub struct StateMachine<S, E> {
+    state: S,
+    transitions: Vec<Transition<S, E>>,
+    entry_actions: Vec<(S, StateAction<S, E>)>,
+    exit_actions: Vec<(S, StateAction<S, E>)>,
+}
+
+impl<S: Copy + PartialEq, E: Copy + PartialEq> StateMachine<S, E> {
+    pub fn new(initial: S) -> Self {
+        StateMachine {
+            state: initial,
+            transitions: vec![],
+            entry_actions: vec![],
+            exit_actions: vec![],
+        }
+    }
+
+    /// The first transition added for a state and event wins
+    pub fn with_transition(mut self, from: S, event: E, to: S) -> Self {
+        self.transitions.push(Transition { from: Some(from), event, to });
+        self
+    }
+
+    /// Only used when the current state has no transition of its own for the event
+    pub fn with_transition_from_any(mut self, event: E, to: S) -> Self {
+        self.transitions.push(Transition { from: None, event, to });
+        self
+    }
+
+    pub fn with_entry(mut self, state: S, action: StateAction<S, E>) -> Self {
+        self.entry_actions.push((state, action));
+        self
+    }
+
+    pub fn with_exit(mut self, state: S, action: StateAction<S, E>) -> Self {
+        self.exit_actions.push((state, action));
+        self
+    }
+
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    pub fn is_in(&self, state: S) -> bool {
+        self.state == state
+    }
+
+    /// Follows the transition of the current state for event, returns whether the state changed
+    pub fn fire(&mut self, event: E) -> bool {
+        let to = self.transitions.iter()
+            .find(|t| t.from == Some(self.state) && t.event == event)
+            .or_else(|| self.transitions.iter().find(|t| t.from.is_none() && t.event == event))
+            .map(|t| t.to);
+        let to = match to {
+            Some(to) if to != self.state => to,
+            _ => return false,
+        };
+        for (_, action) in self.exit_actions.iter().filter(|(s, _)| *s == self.state) {
+            action(self.state, event);
+        }
+        self.state = to;
+        for (_, action) in self.entry_actions.iter().filter(|(s, _)| *s == to) {
+            action(to, event);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Door {
+        Closed,
+        Open,
+        Locked,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Action {
+        Push,
+        Lock,
+        Unlock,
+    }
+
+    static OPENED: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn transitions_run_their_actions() {
+        let mut door = StateMachine::new(Door::Closed)
+            .with_transition(Door::Closed, Action::Push, Door::Open)
+            .with_transition(Door::Open, Action::Push, Door::Closed)
+            .with_transition(Door::Locked, Action::Unlock, Door::Closed)
+            .with_transition_from_any(Action::Lock, Door::Locked)
+            .with_entry(Door::Open, |_, _| {
+                OPENED.fetch_add(1, Ordering::SeqCst);
+            });
+        assert!(door.fire(Action::Push));
+        assert!(door.fire(Action::Lock));
+        assert!(!door.fire(Action::Push), "Locked doors don't open");
+        assert!(!door.fire(Action::Lock));
+        assert!(door.fire(Action::Unlock));
+        assert!(door.is_in(Door::Closed));
+        assert_eq!(OPENED.load(Ordering::SeqCst), 1);
+    }
+}
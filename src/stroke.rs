@@ -0,0 +1,237 @@
+//! Lines of a width along a path of straight segments, solid or dashed, with their corners joined,
+//! lowered to triangles so the SDL engine draws them like any other polygon: focus rings, the
+//! rubber band of a selection, the grid of a chart... The web engine hands the same StrokeStyle to
+//! the 2D context instead. Unlike the tessellation module it needs no feature, but it doesn't do
+//! curves or caps:
+//!
+//! ```ignore
+//! let style = StrokeStyle::dashed(1., &[4., 3.]);
+//! let triangles = stroke::triangles(&[(0., 0.), (40., 0.), (40., 20.)], false, &style);
+//! ```
+
+use std::f32::consts::PI;
+
+/// A point in pixels
+pub type Point = (f32, f32);
+
+/// How far a miter may reach, in widths of the stroke, before it's cut to a bevel like in SVG
+pub const MITER_LIMIT: f32 = 4.;
+
+/// The largest angle a triangle of a round join covers
+const ROUND_STEP: f32 = PI / 8.;
+
+/// How the segments of a stroke meet at a corner
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LineJoin {
+    /// Sharp, up to MITER_LIMIT
+    #[default]
+    Miter,
+    /// Cut flat across the corner
+    Bevel,
+    Round,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    /// Lengths drawn and skipped in turn along the path, a list of an odd length is gone through
+    /// twice. Empty for a solid stroke
+    pub dashes: Vec<f32>,
+    pub join: LineJoin,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        StrokeStyle { width: 1., dashes: vec![], join: LineJoin::default() }
+    }
+}
+
+impl StrokeStyle {
+    pub fn solid(width: f32) -> Self {
+        StrokeStyle { width, ..Default::default() }
+    }
+
+    pub fn dashed(width: f32, dashes: &[f32]) -> Self {
+        StrokeStyle { width, dashes: dashes.to_vec(), ..Default::default() }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Whether the dashes leave gaps, negative lengths or lengths adding up to nothing are drawn solid
+    pub fn is_dashed(&self) -> bool {
+        self.dashes.iter().all(|d| *d >= 0.) && self.dashes.iter().sum::<f32>() > 0.
+    }
+}
+
+/// The dashes along the path as open paths of their own, a dash going around a corner keeps it.
+/// A closed path goes back to its first point, the dashes don't start over there
+pub fn dash(points: &[Point], closed: bool, dashes: &[f32]) -> Vec<Vec<Point>> {
+    let mut path = points.to_vec();
+    if closed && points.len() > 2 {
+        path.push(points[0]);
+    }
+    if !StrokeStyle::dashed(1., dashes).is_dashed() {
+        return vec![path];
+    }
+    let mut pieces = vec![];
+    let mut piece: Vec<Point> = vec![];
+    // The dash, its length left and whether it's drawn or skipped. Lists of an odd length are gone
+    // through twice, the second time skipping what was drawn
+    let (mut index, mut left, mut on) = (0, dashes[0], true);
+    for pair in path.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let length = distance(from, to);
+        let mut done = 0.;
+        while done < length {
+            let step = left.min(length - done);
+            let at = |d: f32| (from.0 + (to.0 - from.0) * d / length, from.1 + (to.1 - from.1) * d / length);
+            if on {
+                if piece.is_empty() {
+                    piece.push(at(done));
+                }
+                piece.push(at(done + step));
+            }
+            done += step;
+            left -= step;
+            if left <= 0. {
+                if !piece.is_empty() {
+                    pieces.push(std::mem::take(&mut piece));
+                }
+                index = (index + 1) % dashes.len();
+                (left, on) = (dashes[index], !on);
+            }
+        }
+    }
+    if piece.len() > 1 {
+        pieces.push(piece);
+    }
+    pieces
+}
+
+/// The triangles covering the stroke of the path. A closed solid path is joined at its first point
+/// too, the dashes of a dashed one end flat
+pub fn triangles(points: &[Point], closed: bool, style: &StrokeStyle) -> Vec<[Point; 3]> {
+    let mut triangles = vec![];
+    if style.width <= 0. {
+        return triangles;
+    }
+    let ring = closed && !style.is_dashed();
+    for piece in dash(points, closed, &style.dashes) {
+        let mut piece = piece;
+        piece.dedup_by(|a, b| distance(*a, *b) <= f32::EPSILON);
+        if ring && piece.len() > 2 && piece.first() == piece.last() {
+            piece.pop();
+        }
+        push_piece(&mut triangles, &piece, ring && piece.len() > 2, style);
+    }
+    triangles
+}
+
+/// The corners of a rectangle stroked inside of it, e.g. an outline that doesn't cover what is
+/// next to it
+pub fn rect(x: f32, y: f32, w: f32, h: f32, width: f32) -> Vec<Point> {
+    let half = width / 2.;
+    let (left, top, right, bottom) = (x + half, y + half, x + w - half, y + h - half);
+    vec![(left, top), (right, top), (right, bottom), (left, bottom)]
+}
+
+fn push_piece(triangles: &mut Vec<[Point; 3]>, points: &[Point], closed: bool, style: &StrokeStyle) {
+    let half = style.width / 2.;
+    let count = points.len();
+    let segments = if closed { count } else { count.saturating_sub(1) };
+    let normal = |i: usize| {
+        let (from, to) = (points[i], points[(i + 1) % count]);
+        let length = distance(from, to);
+        (-(to.1 - from.1) / length * half, (to.0 - from.0) / length * half)
+    };
+    for i in 0..segments {
+        let (from, to, n) = (points[i], points[(i + 1) % count], normal(i));
+        let corners = [add(from, n, 1.), add(from, n, -1.), add(to, n, -1.), add(to, n, 1.)];
+        triangles.push([corners[0], corners[1], corners[2]]);
+        triangles.push([corners[2], corners[3], corners[0]]);
+    }
+    let joints = if closed { 0..count } else { 1..count.saturating_sub(1) };
+    for i in joints {
+        let previous = (i + count - 1) % count;
+        push_join(triangles, points[i], normal(previous), normal(i), half, style.join);
+    }
+}
+
+/// Fills the gap on the outer side of the corner at point between the segments of normals n0
+/// and n1, half the width long
+fn push_join(triangles: &mut Vec<[Point; 3]>, point: Point, n0: Point, n1: Point, half: f32, join: LineJoin) {
+    let cross = n0.0 * n1.1 - n0.1 * n1.0;
+    let dot = n0.0 * n1.0 + n0.1 * n1.1;
+    if cross.abs() <= f32::EPSILON * half * half && dot > 0. {
+        return;
+    }
+    // The gap is on the side the path turns away from
+    let side = if cross > 0. { -1. } else { 1. };
+    let (a, b) = (add(point, n0, side), add(point, n1, side));
+    match join {
+        LineJoin::Miter if half * half + dot > f32::EPSILON => {
+            let scale = half * half / (half * half + dot);
+            let tip = add(point, (n0.0 + n1.0, n0.1 + n1.1), side * scale);
+            if distance(point, tip) * 2. <= MITER_LIMIT * half * 2. {
+                triangles.push([point, a, tip]);
+                triangles.push([point, tip, b]);
+            } else {
+                triangles.push([point, a, b]);
+            }
+        }
+        LineJoin::Miter | LineJoin::Bevel => triangles.push([point, a, b]),
+        LineJoin::Round => {
+            let angle = (dot / (half * half)).clamp(-1., 1.).acos();
+            let steps = (angle / ROUND_STEP).ceil().max(1.) as usize;
+            let turn = if cross > 0. { 1. } else { -1. };
+            let (vx, vy) = (a.0 - point.0, a.1 - point.1);
+            let mut last = a;
+            for step in 1..=steps {
+                let (sin, cos) = (turn * angle * step as f32 / steps as f32).sin_cos();
+                let next = match step == steps {
+                    true => b,
+                    false => (point.0 + vx * cos - vy * sin, point.1 + vx * sin + vy * cos),
+                };
+                triangles.push([point, last, next]);
+                last = next;
+            }
+        }
+    }
+}
+
+fn add(point: Point, vector: Point, scale: f32) -> Point {
+    (point.0 + vector.0 * scale, point.1 + vector.1 * scale)
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strokes_are_dashed_and_joined_into_triangles() {
+        let line = [(0., 0.), (20., 0.)];
+        let pieces = dash(&line, false, &[4., 4.]);
+        assert_eq!(pieces, vec![vec![(0., 0.), (4., 0.)], vec![(8., 0.), (12., 0.)], vec![(16., 0.), (20., 0.)]]);
+        assert_eq!(dash(&line, false, &[6.]).len(), 2, "Odd lists skip what they drew the first time");
+        assert_eq!(dash(&line, false, &[0., 0.]), vec![line.to_vec()]);
+        let square = rect(0., 0., 10., 10., 2.);
+        assert_eq!(square[0], (1., 1.));
+        assert_eq!(triangles(&square, true, &StrokeStyle::solid(2.)).len(), 16, "Four sides and four mitered corners");
+        let bevel = StrokeStyle::solid(2.).with_join(LineJoin::Bevel);
+        assert_eq!(triangles(&square, true, &bevel).len(), 12);
+        let miter = triangles(&square, true, &StrokeStyle::solid(2.));
+        let extent = miter.iter().flatten().fold((f32::MAX, f32::MIN), |(min, max), p| (min.min(p.0), max.max(p.0)));
+        assert_eq!(extent, (0., 10.), "Stroked inside the rectangle");
+        let round = StrokeStyle::solid(2.).with_join(LineJoin::Round);
+        assert_eq!(triangles(&square, true, &round).len(), 8 + 4 * 4);
+        let spike = [(0., 0.), (10., 0.), (0., 1.)];
+        assert_eq!(triangles(&spike, false, &StrokeStyle::solid(2.)).len(), 5, "Past the miter limit");
+    }
+}
@@ -0,0 +1,494 @@
+//! SVG documents drawn as vector shapes: their paths are tessellated by lyon (see tessellation) in
+//! the size they're shown at, so icons stay crisp at any size and density instead of being scaled
+//! pixels. An SvgImage shows one like an Image shows a picture:
+//!
+//! ```ignore
+//! let icon = SvgImage::from_svg(include_str!("icons/save.svg"))?;
+//! ```
+//!
+//! The subset icons are drawn with is understood: `path`, `rect`, `circle`, `ellipse`, `line`,
+//! `polyline` and `polygon` filled and stroked as their attributes, their `style` or the groups
+//! they're in say, with their transforms. What is painted in `currentColor` takes the color of the
+//! SvgImage, and so do gradients and patterns, which aren't drawn. Text, images, masks and
+//! clipping paths are left out
+
+use std::rc::Rc;
+
+use lyon::math::{point, vector, Angle, Box2D, Point, Transform};
+use lyon::path::{Polygon, Winding};
+
+use crate::accessibility_tree::Accessible;
+use crate::elements::Dimension::Relative;
+use crate::elements::{CanvasPolygon, Color, Dimension, ScaleMode};
+use crate::tessellation::{self, Cap, Join, Path, Stroke};
+
+/// Elements whose content isn't drawn where it is
+const HIDDEN: [&str; 12] = ["defs", "clipPath", "mask", "symbol", "marker", "pattern", "linearGradient",
+                            "radialGradient", "filter", "style", "title", "text"];
+
+/// What a shape is filled or stroked with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Paint {
+    Color(Color),
+    /// The color of the SvgImage
+    Current,
+}
+
+impl Paint {
+    fn color(self, current: Color, opacity: f32) -> Color {
+        let color = match self {
+            Paint::Color(color) => color,
+            Paint::Current => current,
+        };
+        Color { a: (color.a as f32 * opacity.clamp(0., 1.)).round() as u8, ..color }
+    }
+}
+
+/// A shape of the document, its path and stroke in the units of the viewBox
+#[derive(Debug, Clone)]
+pub struct SvgShape {
+    pub path: Path,
+    pub fill: Option<Paint>,
+    pub stroke: Option<(Paint, Stroke)>,
+    pub fill_opacity: f32,
+    pub stroke_opacity: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SvgDocument {
+    /// The area of the shapes shown: x, y, width and height
+    pub view_box: (f32, f32, f32, f32),
+    /// Its width and height, the size of the viewBox when it doesn't say
+    pub size: (f32, f32),
+    /// In the order they're drawn
+    pub shapes: Vec<SvgShape>,
+}
+
+impl SvgDocument {
+    pub fn parse(svg: &str) -> Result<SvgDocument, String> {
+        let tags = tags(svg)?;
+        let root = match tags.first() {
+            Some(Tag::Open { name, attributes, .. }) if name == "svg" => attributes,
+            _ => return Err("The document doesn't start with an svg element".to_string()),
+        };
+        let length = |name: &str| attribute(root, name).and_then(number);
+        let view_box = attribute(root, "viewBox")
+            .map(numbers)
+            .and_then(|n| match n.as_slice() {
+                [x, y, w, h] if *w > 0. && *h > 0. => Some((*x, *y, *w, *h)),
+                _ => None,
+            });
+        let (view_box, size) = match (view_box, length("width"), length("height")) {
+            (Some(view_box), Some(w), Some(h)) => (view_box, (w, h)),
+            (Some(view_box), _, _) => (view_box, (view_box.2, view_box.3)),
+            (None, Some(w), Some(h)) if w > 0. && h > 0. => ((0., 0., w, h), (w, h)),
+            _ => return Err("The svg element has neither a viewBox nor a size".to_string()),
+        };
+        let mut shapes = vec![];
+        let mut styles = vec![Style::default()];
+        // How deep in an element that isn't drawn the tags are
+        let mut hidden = 0;
+        for tag in tags {
+            match tag {
+                Tag::Open { name, attributes, closed } => {
+                    if hidden > 0 || HIDDEN.contains(&name.as_str()) {
+                        hidden += !closed as usize;
+                        continue;
+                    }
+                    let style = styles.last().cloned().unwrap_or_default().apply(&attributes);
+                    if let Some(path) = shape_path(&name, &attributes)? {
+                        shapes.push(style.shape(path.transformed(&style.transform)));
+                    }
+                    if !closed {
+                        styles.push(style);
+                    }
+                }
+                Tag::Close if hidden > 0 => hidden -= 1,
+                Tag::Close => {
+                    styles.pop();
+                }
+            }
+        }
+        Ok(SvgDocument { view_box, size, shapes })
+    }
+
+    /// The shapes scaled to area as scale_mode says, with what is painted in currentColor in
+    /// current. Tiles and the parts of a Fill that are left over go past area
+    pub fn polygons(&self, (width, height): (f32, f32), scale_mode: ScaleMode, current: Color)
+                    -> Vec<CanvasPolygon> {
+        let (view_x, view_y, view_w, view_h) = self.view_box;
+        let mut polygons = vec![];
+        for quad in scale_mode.layout((view_w, view_h), (width, height)) {
+            // The whole viewBox scaled so the part of it in the quad lands on the quad
+            let scale_x = quad.w / ((quad.u.1 - quad.u.0) * view_w);
+            let scale_y = quad.h / ((quad.v.1 - quad.v.0) * view_h);
+            let transform = Transform::scale(scale_x, scale_y).then_translate(vector(
+                quad.x - (view_x + quad.u.0 * view_w) * scale_x,
+                quad.y - (view_y + quad.v.0 * view_h) * scale_y,
+            ));
+            let stroke_scale = (scale_x * scale_y).sqrt();
+            for shape in self.shapes.iter() {
+                let path = shape.path.clone().transformed(&transform);
+                if let Some(fill) = shape.fill {
+                    polygons.extend(tessellation::fill(&path, fill.color(current, shape.fill_opacity))
+                        .map_err(|e| println!("{}", e)).ok());
+                }
+                if let Some((paint, stroke)) = &shape.stroke {
+                    let stroke = Stroke {
+                        width: stroke.width * stroke_scale,
+                        dashes: stroke.dashes.iter().map(|d| d * stroke_scale).collect(),
+                        ..stroke.clone()
+                    };
+                    polygons.extend(tessellation::stroke(&path, &stroke, paint.color(current, shape.stroke_opacity))
+                        .map_err(|e| println!("{}", e)).ok());
+                }
+            }
+        }
+        polygons
+    }
+}
+
+/// An SVG document drawn in its size, scaled as scale_mode says
+#[derive(Debug, Clone)]
+pub struct SvgImage {
+    pub document: Rc<SvgDocument>,
+    pub width: Dimension,
+    pub height: Dimension,
+    pub scale_mode: ScaleMode,
+    /// What the document paints in currentColor
+    pub color: Color,
+    pub accessible: Accessible,
+}
+
+impl Default for SvgImage {
+    fn default() -> Self {
+        SvgImage {
+            document: Rc::new(SvgDocument { view_box: (0., 0., 1., 1.), size: (1., 1.), shapes: vec![] }),
+            width: Relative(-1),
+            height: Dimension::Pixels(100),
+            scale_mode: ScaleMode::Fit,
+            color: Color::rgb(230, 230, 230),
+            accessible: Accessible::default(),
+        }
+    }
+}
+
+impl SvgImage {
+    /// The document shown in the size it says
+    pub fn from_svg(svg: &str) -> Result<SvgImage, String> {
+        let document = SvgDocument::parse(svg)?;
+        Ok(SvgImage {
+            width: Dimension::Pixels(document.size.0.round() as i32),
+            height: Dimension::Pixels(document.size.1.round() as i32),
+            document: Rc::new(document),
+            ..Default::default()
+        })
+    }
+
+    /// The document drawn in width by height
+    pub fn polygons(&self, width: f32, height: f32) -> Vec<CanvasPolygon> {
+        self.document.polygons((width, height), self.scale_mode, self.color)
+    }
+}
+
+/// The presentation attributes a shape gets from itself and the groups it's in
+#[derive(Debug, Clone)]
+struct Style {
+    fill: Option<Paint>,
+    stroke: Option<Paint>,
+    stroke_width: f32,
+    join: Join,
+    cap: Cap,
+    dashes: Vec<f32>,
+    opacity: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    /// From the units of the element to the ones of the viewBox
+    transform: Transform,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some(Paint::Color(Color::rgb(0, 0, 0))),
+            stroke: None,
+            stroke_width: 1.,
+            join: Join::Miter,
+            cap: Cap::Butt,
+            dashes: vec![],
+            opacity: 1.,
+            fill_opacity: 1.,
+            stroke_opacity: 1.,
+            transform: Transform::identity(),
+        }
+    }
+}
+
+impl Style {
+    /// The style of an element with the attributes inside an element of this one. What its style
+    /// attribute says wins over the other attributes
+    fn apply(&self, attributes: &[(String, String)]) -> Style {
+        let mut style = self.clone();
+        style.opacity = 1.;
+        let declarations = attribute(attributes, "style").unwrap_or_default().split(';')
+            .filter_map(|d| d.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect::<Vec<(String, String)>>();
+        for (name, value) in attributes.iter().chain(declarations.iter()) {
+            let value = value.as_str();
+            match name.as_str() {
+                "fill" => style.fill = paint(value).unwrap_or(style.fill),
+                "stroke" => style.stroke = paint(value).unwrap_or(style.stroke),
+                "stroke-width" => style.stroke_width = number(value).unwrap_or(style.stroke_width),
+                "stroke-linejoin" => style.join = match value {
+                    "round" => Join::Round,
+                    "bevel" => Join::Bevel,
+                    _ => Join::Miter,
+                },
+                "stroke-linecap" => style.cap = match value {
+                    "round" => Cap::Round,
+                    "square" => Cap::Square,
+                    _ => Cap::Butt,
+                },
+                "stroke-dasharray" => style.dashes = numbers(value),
+                "opacity" => style.opacity = number(value).unwrap_or(1.),
+                "fill-opacity" => style.fill_opacity = number(value).unwrap_or(1.),
+                "stroke-opacity" => style.stroke_opacity = number(value).unwrap_or(1.),
+                "transform" => style.transform = transform(value).then(&self.transform),
+                _ => {}
+            }
+        }
+        style.opacity *= self.opacity;
+        style
+    }
+
+    /// The shape of the path in the viewBox, its stroke as wide as the transform makes it
+    fn shape(&self, path: Path) -> SvgShape {
+        let scale = self.transform.determinant().abs().sqrt();
+        let stroke = Stroke {
+            width: self.stroke_width * scale,
+            join: self.join,
+            cap: self.cap,
+            dashes: self.dashes.iter().map(|d| d * scale).collect(),
+        };
+        SvgShape {
+            path,
+            fill: self.fill,
+            stroke: self.stroke.filter(|_| stroke.width > 0.).map(|paint| (paint, stroke)),
+            fill_opacity: self.opacity * self.fill_opacity,
+            stroke_opacity: self.opacity * self.stroke_opacity,
+        }
+    }
+}
+
+enum Tag {
+    Open { name: String, attributes: Vec<(String, String)>, closed: bool },
+    Close,
+}
+
+/// The tags of the document in order, without its comments, declarations and text. The names
+/// lose their namespace prefix
+fn tags(svg: &str) -> Result<Vec<Tag>, String> {
+    let mut tags = vec![];
+    let mut rest = svg;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = match rest.strip_prefix("!--") {
+            Some(comment) => comment.find("-->").map(|end| end + 5),
+            // Attributes may have a > in their quotes
+            None => {
+                let mut quote = None;
+                rest.char_indices().find(|(_, c)| {
+                    match (quote, *c) {
+                        (None, '"' | '\'') => quote = Some(*c),
+                        (Some(q), c) if q == c => quote = None,
+                        _ => {}
+                    }
+                    quote.is_none() && *c == '>'
+                }).map(|(end, _)| end)
+            }
+        }.ok_or("A tag of the SVG isn't closed".to_string())?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        if tag.starts_with('/') {
+            tags.push(Tag::Close);
+            continue;
+        }
+        let (tag, closed) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let (name, mut attributes_text) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let mut attributes = vec![];
+        while let Some((name, value)) = attributes_text.split_once('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|q| *q == '"' || *q == '\'')
+                .ok_or(format!("The attribute {} has no quotes", name.trim()))?;
+            let end = value[1..].find(quote).ok_or(format!("The attribute {} isn't closed", name.trim()))?;
+            attributes.push((local_name(name.trim()).to_string(), value[1..1 + end].to_string()));
+            attributes_text = &value[end + 2..];
+        }
+        tags.push(Tag::Open { name: local_name(name).to_string(), attributes, closed });
+    }
+    Ok(tags)
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+}
+
+/// A number, lengths in pixels may say so
+fn number(value: &str) -> Option<f32> {
+    value.trim().trim_end_matches("px").parse().ok()
+}
+
+/// The numbers separated by spaces or commas
+fn numbers(value: &str) -> Vec<f32> {
+    value.split([' ', ',', '\t', '\n', '\r']).filter(|n| !n.is_empty()).filter_map(number).collect()
+}
+
+/// The paint of a fill or stroke, Some(None) for none and None when it can't tell
+fn paint(value: &str) -> Option<Option<Paint>> {
+    let value = value.trim();
+    let channels = |text: &str| numbers(text).iter().map(|c| c.clamp(0., 255.) as u8).collect::<Vec<u8>>();
+    let hex = |text: &str| u8::from_str_radix(text, 16).ok();
+    let color = match value {
+        "none" | "transparent" => return Some(None),
+        "currentColor" => return Some(Some(Paint::Current)),
+        "black" => Color::rgb(0, 0, 0),
+        "white" => Color::rgb(255, 255, 255),
+        "red" => Color::rgb(255, 0, 0),
+        "green" => Color::rgb(0, 128, 0),
+        "blue" => Color::rgb(0, 0, 255),
+        "gray" | "grey" => Color::rgb(128, 128, 128),
+        // Gradients and patterns
+        _ if value.starts_with("url(") => return Some(Some(Paint::Current)),
+        _ if value.starts_with('#') => {
+            let digits = &value[1..];
+            let long = |i: usize| digits.get(2 * i..2 * i + 2).and_then(hex);
+            let short = |i: usize| digits.get(i..i + 1).and_then(hex).map(|d| d * 17);
+            match digits.len() {
+                3 => Color::rgb(short(0)?, short(1)?, short(2)?),
+                6 => Color::rgb(long(0)?, long(1)?, long(2)?),
+                8 => Color { a: long(3)?, ..Color::rgb(long(0)?, long(1)?, long(2)?) },
+                _ => return None,
+            }
+        }
+        _ if value.starts_with("rgb") => {
+            let inside = value.split_once('(')?.1.trim_end_matches(')');
+            match channels(inside).as_slice() {
+                [r, g, b] => Color::rgb(*r, *g, *b),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(Some(Paint::Color(color)))
+}
+
+/// The transform of a transform attribute, its functions applied from the last one
+fn transform(value: &str) -> Transform {
+    value.split(')').filter_map(|function| {
+        let (name, arguments) = function.split_once('(')?;
+        let name = name.trim().trim_start_matches(',').trim();
+        Some(match (name, numbers(arguments).as_slice()) {
+            ("matrix", [a, b, c, d, e, f]) => Transform::new(*a, *b, *c, *d, *e, *f),
+            ("translate", [x]) => Transform::translation(*x, 0.),
+            ("translate", [x, y]) => Transform::translation(*x, *y),
+            ("scale", [s]) => Transform::scale(*s, *s),
+            ("scale", [x, y]) => Transform::scale(*x, *y),
+            ("rotate", [angle]) => Transform::rotation(Angle::degrees(*angle)),
+            ("rotate", [angle, x, y]) => Transform::translation(-x, -y)
+                .then_rotate(Angle::degrees(*angle))
+                .then_translate(vector(*x, *y)),
+            ("skewX", [angle]) => Transform::new(1., 0., angle.to_radians().tan(), 1., 0., 0.),
+            ("skewY", [angle]) => Transform::new(1., angle.to_radians().tan(), 0., 1., 0., 0.),
+            _ => Transform::identity(),
+        })
+    }).collect::<Vec<Transform>>().iter().rev().fold(Transform::identity(), |all, t| all.then(t))
+}
+
+/// The path of the element if it's a shape, in its own units
+fn shape_path(name: &str, attributes: &[(String, String)]) -> Result<Option<Path>, String> {
+    let length = |name: &str| attribute(attributes, name).and_then(number).unwrap_or(0.);
+    let mut builder = Path::builder();
+    match name {
+        "path" => return attribute(attributes, "d").map(tessellation::parse_svg).transpose(),
+        "rect" => {
+            let (x, y, w, h) = (length("x"), length("y"), length("width"), length("height"));
+            let radius = attribute(attributes, "rx").or(attribute(attributes, "ry")).and_then(number);
+            match radius {
+                Some(radius) => return Ok(Some(tessellation::rounded_rect(x, y, w, h, radius.min(w / 2.).min(h / 2.)))),
+                None => builder.add_rectangle(&Box2D::new(point(x, y), point(x + w, y + h)), Winding::Positive),
+            }
+        }
+        "circle" => return Ok(Some(tessellation::circle(length("cx"), length("cy"), length("r")))),
+        "ellipse" => {
+            let radii = vector(length("rx"), length("ry"));
+            builder.add_ellipse(point(length("cx"), length("cy")), radii, Angle::zero(), Winding::Positive);
+        }
+        "line" => {
+            builder.begin(point(length("x1"), length("y1")));
+            builder.line_to(point(length("x2"), length("y2")));
+            builder.end(false);
+        }
+        "polyline" | "polygon" => {
+            let points: Vec<Point> = numbers(attribute(attributes, "points").unwrap_or_default())
+                .chunks_exact(2)
+                .map(|p| point(p[0], p[1]))
+                .collect();
+            if points.is_empty() {
+                return Ok(None);
+            }
+            builder.add_polygon(Polygon { points: &points, closed: name == "polygon" });
+        }
+        _ => return Ok(None),
+    }
+    Ok(Some(builder.build()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_shapes_are_scaled_to_the_size_they_are_shown_at() {
+        let document = SvgDocument::parse(r##"<?xml version="1.0"?>
+            <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" width="48px" height="48">
+              <!-- An icon > a picture -->
+              <g fill="none" stroke="currentColor" stroke-width="2">
+                <path d="M 2 12 L 22 12"/>
+                <circle cx="11" cy="11" r="4" style="fill: #f00; stroke: none" transform="translate(1 1)"/>
+              </g>
+              <defs><rect width="24" height="24"/></defs>
+            </svg>"##).unwrap();
+        assert_eq!((document.view_box, document.size), ((0., 0., 24., 24.), (48., 48.)));
+        assert_eq!(document.shapes.len(), 2, "The rect is only defined");
+        assert_eq!(document.shapes[0].fill, None);
+        assert_eq!(document.shapes[1].fill, Some(Paint::Color(Color::rgb(255, 0, 0))));
+        assert!(document.shapes[1].stroke.is_none());
+
+        let white = Color::rgb(255, 255, 255);
+        let polygons = document.polygons((96., 48.), ScaleMode::Fit, white);
+        let bounds = |polygon: &CanvasPolygon| polygon.vertices.iter()
+            .fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |(x1, y1, x2, y2), v| {
+                (x1.min(v.x), y1.min(v.y), x2.max(v.x), y2.max(v.y))
+            });
+        let (x1, y1, x2, y2) = bounds(&polygons[0]);
+        assert_eq!((x1, x2), (28., 68.), "Centered and twice as big");
+        assert_eq!((y1, y2), (22., 26.), "The stroke is twice as wide too");
+        assert_eq!(polygons[0].vertices[0].color, white, "In the color of the image");
+        let (x1, y1, x2, y2) = bounds(&polygons[1]);
+        assert!((x1 - 40.).abs() < 0.5 && (x2 - 56.).abs() < 0.5 && (y1 - 16.).abs() < 0.5 && (y2 - 32.).abs() < 0.5);
+        assert_eq!(transform("translate(10) scale(2)").transform_point(point(1., 1.)), point(12., 2.));
+        assert_eq!(paint("rgb(1, 2, 3)"), Some(Some(Paint::Color(Color::rgb(1, 2, 3)))));
+        assert!(SvgDocument::parse("<svg><path d='M 0 0'/></svg>").is_err(), "No size");
+    }
+}
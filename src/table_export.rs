@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use crate::table_model::{SortFilterProxy, TableModel};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quoting {
+    /// Only cells containing the delimiter, the quote or line breaks are quoted
+    Necessary,
+    Always,
+    /// Cells are written as they are, even when that breaks the format
+    Never,
+    /// Nothing is quoted, the delimiter and line breaks inside cells become spaces so each cell
+    /// stays one field of one line
+    Replace,
+}
+
+/// How rows are serialized by to_delimited() and export_csv()
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DelimitedOptions {
+    pub delimiter: char,
+    pub quote: char,
+    pub quoting: Quoting,
+    /// Writes the column titles as the first line
+    pub header: bool,
+    pub line_ending: &'static str,
+}
+
+impl DelimitedOptions {
+    pub fn csv() -> Self {
+        DelimitedOptions {
+            delimiter: ',',
+            quote: '"',
+            quoting: Quoting::Necessary,
+            header: true,
+            line_ending: "\r\n",
+        }
+    }
+
+    /// Tab separated values, it's what spreadsheets expect in the clipboard. Tabs and line breaks
+    /// in cells become spaces, spreadsheets don't read quoted ones back
+    pub fn tsv() -> Self {
+        DelimitedOptions {
+            delimiter: '\t',
+            quoting: Quoting::Replace,
+            header: false,
+            line_ending: "\n",
+            ..DelimitedOptions::csv()
+        }
+    }
+}
+
+impl Default for DelimitedOptions {
+    fn default() -> Self {
+        DelimitedOptions::csv()
+    }
+}
+
+/// Which rows to serialize, the view ones follow the sorting and filtering of the proxy
+#[derive(Debug, Clone, Copy)]
+pub enum ExportRows<'a> {
+    /// Every row of the model, in the model order
+    All,
+    /// The rows shown through the proxy
+    Visible(&'a SortFilterProxy),
+    /// Some view rows (e.g. the selection), in the given order
+    Selected(&'a SortFilterProxy, &'a [usize]),
+}
+
+fn write_cell(out: &mut String, cell: &str, options: &DelimitedOptions) {
+    let needs_quotes = match options.quoting {
+        Quoting::Always => true,
+        Quoting::Never => false,
+        Quoting::Replace => {
+            // A Windows line break is a single space too
+            out.extend(cell.replace("\r\n", " ").chars().map(|c| match c {
+                '\r' | '\n' => ' ',
+                c if c == options.delimiter => ' ',
+                c => c,
+            }));
+            return;
+        }
+        Quoting::Necessary => cell.contains([options.delimiter, options.quote, '\n', '\r']),
+    };
+    if needs_quotes {
+        out.push(options.quote);
+        for c in cell.chars() {
+            if c == options.quote {
+                out.push(c);
+            }
+            out.push(c);
+        }
+        out.push(options.quote);
+    } else {
+        out.push_str(cell);
+    }
+}
+
+fn write_line<I: Iterator<Item=String>>(out: &mut String, cells: I, options: &DelimitedOptions) {
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            out.push(options.delimiter);
+        }
+        write_cell(out, &cell, options);
+    }
+    out.push_str(options.line_ending);
+}
+
+/// Serializes the rows of the model, e.g. to put them in the clipboard
+pub fn to_delimited(model: &dyn TableModel, rows: ExportRows, options: &DelimitedOptions) -> String {
+    let columns = model.column_count();
+    let mut out = String::new();
+    if options.header {
+        write_line(&mut out, (0..columns).map(|c| model.column_title(c)), options);
+    }
+    let model_rows: Vec<usize> = match rows {
+        ExportRows::All => (0..model.row_count()).collect(),
+        ExportRows::Visible(proxy) => (0..proxy.row_count())
+            .filter_map(|r| proxy.map_to_model(r)).collect(),
+        ExportRows::Selected(proxy, view_rows) => proxy.map_selection(view_rows),
+    };
+    for row in model_rows {
+        write_line(&mut out, (0..columns).map(|c| model.cell(row, c)), options);
+    }
+    out
+}
+
+/// Writes the rows of the model to a file
+pub fn export_csv<P: AsRef<Path>>(path: P, model: &dyn TableModel, rows: ExportRows,
+                                  options: &DelimitedOptions) -> Result<(), String> {
+    let path = path.as_ref();
+    std::fs::write(path, to_delimited(model, rows, options))
+        .map_err(|e| format!("Failed to export to {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_model::VecTableModel;
+
+    #[test]
+    fn cells_are_quoted_when_necessary() {
+        let model = VecTableModel {
+            columns: vec!["Name".to_string(), "Quote".to_string()],
+            rows: vec![vec!["Rui, Lopez".to_string(), "Say \"hi\"".to_string()],
+                       vec!["Ana".to_string(), "plain".to_string()]],
+        };
+        let proxy = SortFilterProxy::new(&model);
+        assert_eq!(to_delimited(&model, ExportRows::All, &DelimitedOptions::csv()),
+                   "Name,Quote\r\n\"Rui, Lopez\",\"Say \"\"hi\"\"\"\r\nAna,plain\r\n");
+        assert_eq!(to_delimited(&model, ExportRows::Selected(&proxy, &[1]), &DelimitedOptions::tsv()),
+                   "Ana\tplain\n");
+    }
+
+    #[test]
+    fn tsv_cells_stay_on_their_field_and_line() {
+        let model = VecTableModel {
+            columns: vec!["Name".to_string(), "Notes".to_string()],
+            rows: vec![vec!["Rui\tLopez".to_string(), "first\r\nsecond\nthird".to_string()]],
+        };
+        assert_eq!(to_delimited(&model, ExportRows::All, &DelimitedOptions::tsv()),
+                   "Rui Lopez\tfirst second third\n");
+    }
+}
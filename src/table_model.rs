@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+/// The data shown by a Table, cells are text
+pub trait TableModel {
+    fn row_count(&self) -> usize;
+    fn column_count(&self) -> usize;
+    fn column_title(&self, column: usize) -> String;
+    fn cell(&self, row: usize, column: usize) -> String;
+}
+
+/// A TableModel for data that is already in memory
+#[derive(Debug, Clone, Default)]
+pub struct VecTableModel {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableModel for VecTableModel {
+    fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_title(&self, column: usize) -> String {
+        self.columns.get(column).cloned().unwrap_or_default()
+    }
+
+    fn cell(&self, row: usize, column: usize) -> String {
+        self.rows.get(row).and_then(|r| r.get(column)).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SortKey {
+    pub column: usize,
+    pub order: SortOrder,
+}
+
+/// Decides if a row of the model (given by its model index) is shown
+pub type RowFilter = Rc<dyn Fn(&dyn TableModel, usize) -> bool>;
+
+/// Sits between a TableModel and the Table, showing the model rows filtered and sorted without
+/// touching the model. Sorting is stable and by several columns, the first key is the primary one.
+/// Views work with view indexes, map_to_model() gives the rows of the model back (e.g. for
+/// selection callbacks)
+#[derive(Clone, Default)]
+pub struct SortFilterProxy {
+    sort_keys: Vec<SortKey>,
+    filters: Vec<(String, RowFilter)>,
+    mapping: Vec<usize>,
+}
+
+impl Debug for SortFilterProxy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SortFilterProxy")
+            .field("sort_keys", &self.sort_keys)
+            .field("filters", &self.filters.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .field("rows", &self.mapping.len())
+            .finish()
+    }
+}
+
+impl SortFilterProxy {
+    /// Creates a proxy showing every row of the model in its order
+    pub fn new(model: &dyn TableModel) -> Self {
+        let mut proxy = SortFilterProxy::default();
+        proxy.invalidate(model);
+        proxy
+    }
+
+    pub fn sort_keys(&self) -> &[SortKey] {
+        &self.sort_keys
+    }
+
+    pub fn set_sort_keys(&mut self, keys: Vec<SortKey>, model: &dyn TableModel) {
+        self.sort_keys = keys;
+        self.invalidate(model);
+    }
+
+    /// What a click on the header of column does: the first click sorts ascending by it, clicking
+    /// it again flips the order. With extend (e.g. Shift held) the column is added as a secondary
+    /// key instead of replacing the current ones
+    pub fn header_clicked(&mut self, column: usize, extend: bool, model: &dyn TableModel) {
+        match self.sort_keys.iter().position(|k| k.column == column) {
+            Some(index) if extend || self.sort_keys.len() == 1 => {
+                let key = &mut self.sort_keys[index];
+                key.order = match key.order {
+                    SortOrder::Ascending => SortOrder::Descending,
+                    SortOrder::Descending => SortOrder::Ascending,
+                };
+            }
+            _ => {
+                if !extend {
+                    self.sort_keys.clear();
+                }
+                self.sort_keys.push(SortKey { column, order: SortOrder::Ascending });
+            }
+        }
+        self.invalidate(model);
+    }
+
+    /// Adds (or replaces) a named filter, rows are shown only when every filter accepts them
+    pub fn set_filter(&mut self, name: &str, filter: RowFilter, model: &dyn TableModel) {
+        self.filters.retain(|(n, _)| n != name);
+        self.filters.push((name.to_string(), filter));
+        self.invalidate(model);
+    }
+
+    pub fn remove_filter(&mut self, name: &str, model: &dyn TableModel) {
+        self.filters.retain(|(n, _)| n != name);
+        self.invalidate(model);
+    }
+
+    /// Recomputes which rows are shown and in which order, it has to be called when the model
+    /// changes
+    pub fn invalidate(&mut self, model: &dyn TableModel) {
+        let mut mapping: Vec<usize> = (0..model.row_count())
+            .filter(|row| self.filters.iter().all(|(_, filter)| filter(model, *row)))
+            .collect();
+        if !self.sort_keys.is_empty() {
+            mapping.sort_by(|a, b| {
+                self.sort_keys.iter()
+                    .map(|key| {
+                        let ordering = compare_cells(&model.cell(*a, key.column),
+                                                     &model.cell(*b, key.column));
+                        match key.order {
+                            SortOrder::Ascending => ordering,
+                            SortOrder::Descending => ordering.reverse(),
+                        }
+                    })
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+        self.mapping = mapping;
+    }
+
+    /// The amount of rows shown
+    pub fn row_count(&self) -> usize {
+        self.mapping.len()
+    }
+
+    pub fn map_to_model(&self, view_row: usize) -> Option<usize> {
+        self.mapping.get(view_row).copied()
+    }
+
+    /// None when the row is filtered out
+    pub fn map_from_model(&self, model_row: usize) -> Option<usize> {
+        self.mapping.iter().position(|row| *row == model_row)
+    }
+
+    /// The model indexes of the given view indexes, the ones out of range are skipped
+    pub fn map_selection(&self, view_rows: &[usize]) -> Vec<usize> {
+        view_rows.iter().filter_map(|row| self.map_to_model(*row)).collect()
+    }
+
+    pub fn cell(&self, model: &dyn TableModel, view_row: usize, column: usize) -> Option<String> {
+        self.map_to_model(view_row).map(|row| model.cell(row, column))
+    }
+}
+
+/// Cells that are both numbers are compared as numbers, otherwise as text
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> VecTableModel {
+        let rows = [["Rui", "30"], ["Ana", "9"], ["Rui", "12"], ["Bea", "30"]];
+        VecTableModel {
+            columns: vec!["Name".to_string(), "Age".to_string()],
+            rows: rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect(),
+        }
+    }
+
+    #[test]
+    fn header_clicks_sort_by_several_columns() {
+        let model = people();
+        let mut proxy = SortFilterProxy::new(&model);
+        proxy.header_clicked(1, false, &model);
+        assert_eq!(proxy.map_selection(&[0, 1, 2, 3]), vec![1, 2, 0, 3]);
+        proxy.header_clicked(0, true, &model);
+        proxy.header_clicked(1, true, &model);
+        assert_eq!(proxy.map_selection(&[0, 1, 2, 3]), vec![3, 0, 2, 1]);
+    }
+
+    #[test]
+    fn filtered_rows_map_back_to_the_model() {
+        let model = people();
+        let mut proxy = SortFilterProxy::new(&model);
+        proxy.set_filter("rui", Rc::new(|model, row| model.cell(row, 0) == "Rui"), &model);
+        assert_eq!(proxy.row_count(), 2);
+        assert_eq!(proxy.map_to_model(1), Some(2));
+        assert_eq!(proxy.map_from_model(1), None);
+        proxy.remove_filter("rui", &model);
+        assert_eq!(proxy.row_count(), 4);
+    }
+}
@@ -0,0 +1,118 @@
+//! Progress of long operations on the taskbar or the dock, e.g. a download while the window is
+//! minimized. SDL can only flash the window, so showing the progress is left to a
+//! TaskbarIntegration the app installs (ITaskbarList3 on Windows, NSDockTile on macOS, the launcher
+//! entry of Unity on Linux). The web engine puts it in the title of the page instead:
+//!
+//! ```ignore
+//! taskbar::set_progress(Some(TaskbarProgress::from(&progress_bar)));
+//! taskbar::request_attention(Attention::UntilFocused);
+//! ```
+
+use once_cell::sync::OnceCell;
+
+use crate::elements::ProgressBar;
+use crate::ui_channel::{self, UiMessage};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TaskbarState {
+    #[default]
+    Normal,
+    /// Usually yellow on Windows
+    Paused,
+    /// Usually red on Windows
+    Error,
+}
+
+/// The progress shown on the taskbar, the same one a ProgressBar shows
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TaskbarProgress {
+    /// Fraction (0.0 - 1.0) that is done, None is indeterminate
+    pub value: Option<f32>,
+    pub state: TaskbarState,
+}
+
+impl From<&ProgressBar> for TaskbarProgress {
+    fn from(progress_bar: &ProgressBar) -> Self {
+        TaskbarProgress { value: progress_bar.value, state: TaskbarState::Normal }
+    }
+}
+
+impl TaskbarProgress {
+    /// A short text for where only text fits, e.g. "45%" or "Paused at 45%"
+    pub fn label(&self) -> String {
+        let value = match self.value {
+            Some(value) => format!("{}%", (value.clamp(0., 1.) * 100.).round()),
+            None => "Busy".to_string(),
+        };
+        match (self.state, self.value) {
+            (TaskbarState::Normal, _) => value,
+            (TaskbarState::Paused, Some(_)) => format!("Paused at {}", value),
+            (TaskbarState::Paused, None) => "Paused".to_string(),
+            (TaskbarState::Error, Some(_)) => format!("Failed at {}", value),
+            (TaskbarState::Error, None) => "Failed".to_string(),
+        }
+    }
+}
+
+/// How the window asks for the attention of the user
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attention {
+    Briefly,
+    /// Until the window gets the focus
+    UntilFocused,
+    /// Stops asking
+    Cancel,
+}
+
+/// What the platform does with the progress, the engines call it on the UI thread
+pub trait TaskbarIntegration: Send + Sync {
+    /// None removes the progress from the taskbar
+    fn show_progress(&self, window: &str, progress: Option<&TaskbarProgress>);
+}
+
+static INTEGRATION: OnceCell<Box<dyn TaskbarIntegration>> = OnceCell::new();
+
+/// Installs the TaskbarIntegration for the whole process, the SDL engine doesn't show the progress
+/// until this is called. It can only be set once
+pub fn set_integration(integration: Box<dyn TaskbarIntegration>) -> Result<(), String> {
+    INTEGRATION.set(integration)
+        .map_err(|_| "The taskbar integration was already set".to_string())
+}
+
+/// Hands the progress of window to the TaskbarIntegration, returns whether there is one
+pub(crate) fn show_progress(window: &str, progress: Option<&TaskbarProgress>) -> bool {
+    match INTEGRATION.get() {
+        Some(integration) => {
+            integration.show_progress(window, progress);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Shows the progress on the taskbar, None removes it. It can be called from any thread
+pub fn set_progress(progress: Option<TaskbarProgress>) {
+    ui_channel::post(UiMessage::TaskbarProgress(progress));
+}
+
+/// Flashes the window on the taskbar, e.g. when a long operation finished in the background. It
+/// can be called from any thread
+pub fn request_attention(attention: Attention) {
+    ui_channel::post(UiMessage::RequestAttention(attention));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taskbar_progress_follows_the_progress_bar() {
+        let progress_bar = ProgressBar { value: Some(0.454), ..Default::default() };
+        let mut progress = TaskbarProgress::from(&progress_bar);
+        assert_eq!(progress.label(), "45%");
+        progress.state = TaskbarState::Paused;
+        assert_eq!(progress.label(), "Paused at 45%");
+        let busy = TaskbarProgress::from(&ProgressBar::default());
+        assert_eq!(TaskbarProgress { state: TaskbarState::Error, ..busy }.label(), "Failed");
+    }
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use once_cell::sync::OnceCell;
+
+/// Hooks the engine calls at key points so applications can forward metrics to whatever they use
+/// for monitoring (Prometheus, tracing, logs...). Every method does nothing by default so
+/// implementors only need to override the ones they care about
+pub trait Telemetry: Send + Sync {
+    /// Called right before the engine starts processing a new frame
+    fn frame_start(&self, _frame: u64) {}
+
+    /// Called once the frame was presented, elapsed doesn't include the frame pacing sleep
+    fn frame_end(&self, _frame: u64, _elapsed: Duration) {}
+
+    /// Called after a Window (or a part of it) was converted into drawables
+    fn components_built(&self, _window: &str, _count: usize) {}
+
+    /// Called after an event was handled, latency is the time the engine took to dispatch it
+    fn event_dispatched(&self, _event: &str, _latency: Duration) {}
+
+    /// Called whenever pixel data is uploaded to a GPU texture
+    fn texture_uploaded(&self, _bytes: usize) {}
+}
+
+static TELEMETRY: OnceCell<Box<dyn Telemetry>> = OnceCell::new();
+
+/// Installs the Telemetry implementation for the whole process, telemetry is opt-in so nothing is
+/// reported until this is called. It can only be set once
+pub fn set_telemetry(telemetry: Box<dyn Telemetry>) -> Result<(), String> {
+    TELEMETRY.set(telemetry)
+        .map_err(|_| "Telemetry was already set".to_string())
+}
+
+/// Runs f only when a Telemetry was installed
+pub(crate) fn report<F: FnOnce(&dyn Telemetry)>(f: F) {
+    if let Some(telemetry) = TELEMETRY.get() {
+        f(telemetry.as_ref());
+    }
+}
@@ -0,0 +1,205 @@
+//! Bezier paths turned into the triangles of CanvasPolygons by lyon, for what hand-written triangles
+//! can't draw well: vector icons, smooth rounded shapes, strokes with joins, caps and dashes. A
+//! Canvas draws them like any other CanvasPolygon:
+//!
+//! ```ignore
+//! let icon = tessellation::parse_svg("M 2 12 L 9 19 L 22 5")?;
+//! let check = Stroke { width: 3., join: Join::Round, cap: Cap::Round, ..Default::default() };
+//! Canvas {
+//!     draw: Rc::new(move |_, height| {
+//!         let icon = tessellation::scaled(&icon, height / 24., 0., 0.);
+//!         tessellation::stroke(&icon, &check, Color::rgb(90, 180, 100)).into_iter().collect()
+//!     }),
+//!     ..Default::default()
+//! }
+//! ```
+
+use lyon::extra::parser::{ParserOptions, PathParser, Source};
+use lyon::geom::Box2D;
+use lyon::math::{point, Point, Transform};
+use lyon::path::builder::BorderRadii;
+use lyon::path::iterator::PathIterator;
+use lyon::path::{PathEvent, Winding};
+use lyon::tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, LineCap, LineJoin, StrokeOptions,
+                         StrokeTessellator, StrokeVertex, VertexBuffers};
+
+use crate::elements::{CanvasPolygon, CanvasVertex, Color};
+
+pub use lyon::path::Path;
+
+/// How far the triangles may stray from the curves, in pixels
+pub const TOLERANCE: f32 = 0.1;
+
+/// How the segments of a stroke meet
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Join {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How a stroke (or a dash of it) ends
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Cap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub join: Join,
+    pub cap: Cap,
+    /// Lengths drawn and skipped in turn along the path, it starts over at every sub-path. Empty
+    /// for a solid stroke
+    pub dashes: Vec<f32>,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Stroke { width: 1., join: Join::default(), cap: Cap::default(), dashes: vec![] }
+    }
+}
+
+/// The path of SVG path data, e.g. the `d` attribute of the path of an icon
+pub fn parse_svg(data: &str) -> Result<Path, String> {
+    let mut builder = Path::builder_with_attributes(0);
+    PathParser::new().parse(&ParserOptions::DEFAULT, &mut Source::new(data.chars()), &mut builder)
+        .map_err(|e| format!("Failed to parse the path {:?} {}", data, e))?;
+    Ok(builder.build())
+}
+
+pub fn rounded_rect(x: f32, y: f32, w: f32, h: f32, radius: f32) -> Path {
+    let mut builder = Path::builder();
+    builder.add_rounded_rectangle(&Box2D::new(point(x, y), point(x + w, y + h)), &BorderRadii::new(radius),
+                                  Winding::Positive);
+    builder.build()
+}
+
+pub fn circle(cx: f32, cy: f32, radius: f32) -> Path {
+    let mut builder = Path::builder();
+    builder.add_circle(point(cx, cy), radius, Winding::Positive);
+    builder.build()
+}
+
+/// The path scaled and then moved by (x, y), e.g. an icon drawn in a box of 24 units fit to a
+/// Canvas
+pub fn scaled(path: &Path, scale: f32, x: f32, y: f32) -> Path {
+    path.clone().transformed(&Transform::scale(scale, scale).then_translate((x, y).into()))
+}
+
+/// The inside of the path by the non-zero rule, in a single color
+pub fn fill(path: &Path, color: Color) -> Result<CanvasPolygon, String> {
+    let mut geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let mut output = BuffersBuilder::new(&mut geometry, |vertex: FillVertex| vertex.position());
+    FillTessellator::new().tessellate_path(path, &FillOptions::tolerance(TOLERANCE), &mut output)
+        .map_err(|e| format!("Failed to fill the path {:?}", e))?;
+    Ok(polygon(geometry, color))
+}
+
+pub fn stroke(path: &Path, stroke: &Stroke, color: Color) -> Result<CanvasPolygon, String> {
+    let options = StrokeOptions::tolerance(TOLERANCE)
+        .with_line_width(stroke.width)
+        .with_line_join(match stroke.join {
+            Join::Miter => LineJoin::Miter,
+            Join::Round => LineJoin::Round,
+            Join::Bevel => LineJoin::Bevel,
+        })
+        .with_line_cap(match stroke.cap {
+            Cap::Butt => LineCap::Butt,
+            Cap::Round => LineCap::Round,
+            Cap::Square => LineCap::Square,
+        });
+    let dashed = dashed(path, &stroke.dashes);
+    let mut geometry: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let mut output = BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| vertex.position());
+    StrokeTessellator::new().tessellate_path(dashed.as_ref().unwrap_or(path), &options, &mut output)
+        .map_err(|e| format!("Failed to stroke the path {:?}", e))?;
+    Ok(polygon(geometry, color))
+}
+
+fn polygon(geometry: VertexBuffers<Point, u32>, color: Color) -> CanvasPolygon {
+    CanvasPolygon {
+        vertices: geometry.vertices.iter().map(|p| CanvasVertex { x: p.x, y: p.y, color }).collect(),
+        indexes: geometry.indices.iter().map(|i| *i as usize).collect(),
+    }
+}
+
+/// The dashes of the path as sub-paths of their own, None when it isn't dashed. Curves are
+/// flattened first
+fn dashed(path: &Path, dashes: &[f32]) -> Option<Path> {
+    if dashes.iter().any(|d| *d < 0.) || dashes.iter().sum::<f32>() <= 0. {
+        return None;
+    }
+    let mut builder = Path::builder();
+    // The dash, its length left, whether it's drawn or skipped and whether a sub-path is open for it.
+    // Lists of an odd length are gone through twice, the second time skipping what was drawn
+    let (mut index, mut left, mut on, mut drawing) = (0, dashes[0], true, false);
+    for event in path.iter().flattened(TOLERANCE) {
+        let (from, to, ends) = match event {
+            PathEvent::Begin { .. } => {
+                (index, left, on) = (0, dashes[0], true);
+                continue;
+            }
+            PathEvent::Line { from, to } => (from, to, false),
+            PathEvent::End { last, first, close } => (last, if close { first } else { last }, true),
+            // There are no curves left once flattened
+            _ => continue,
+        };
+        let mut start = from;
+        let mut remaining = (to - from).length();
+        while remaining > 0. {
+            let step = left.min(remaining);
+            let end = start + (to - from) / (to - from).length() * step;
+            if on {
+                if !drawing {
+                    builder.begin(start);
+                    drawing = true;
+                }
+                builder.line_to(end);
+            }
+            (start, remaining, left) = (end, remaining - step, left - step);
+            if left <= 0. {
+                if drawing {
+                    builder.end(false);
+                    drawing = false;
+                }
+                index = (index + 1) % dashes.len();
+                (left, on) = (dashes[index], !on);
+            }
+        }
+        if ends && drawing {
+            builder.end(false);
+            drawing = false;
+        }
+    }
+    if drawing {
+        builder.end(false);
+    }
+    Some(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_filled_and_stroked_into_triangles() {
+        let white = Color::rgb(255, 255, 255);
+        let square = parse_svg("M 0 0 L 10 0 L 10 10 L 0 10 Z").unwrap();
+        assert_eq!(fill(&square, white).unwrap().triangles().len(), 2);
+        assert!(parse_svg("M 0 0 X 1").is_err());
+        let line = parse_svg("M 0 0 L 20 0").unwrap();
+        let dashes = Stroke { width: 2., dashes: vec![4., 4.], ..Default::default() };
+        let dashed = stroke(&line, &dashes, white).unwrap();
+        assert_eq!(dashed.triangles().len(), 6, "Three dashes of two triangles");
+        let xs = dashed.vertices.iter().map(|v| v.x);
+        assert_eq!(xs.fold((f32::MAX, f32::MIN), |(min, max), x| (min.min(x), max.max(x))), (0., 20.));
+        let round = fill(&scaled(&circle(0., 0., 1.), 10., 10., 10.), white).unwrap();
+        assert!(round.vertices.len() > 16 && round.vertices.iter().all(|v| v.x >= 0. && v.y <= 20.));
+        assert!(!fill(&rounded_rect(0., 0., 20., 10., 4.), white).unwrap().indexes.is_empty());
+    }
+}
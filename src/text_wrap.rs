@@ -0,0 +1,45 @@
+/// Splits text into lines that fit in max_width according to measure (the width of a string),
+/// breaking at spaces when possible and inside words that don't fit on a line of their own.
+/// Explicit line breaks are kept
+pub fn wrap_lines(text: &str, max_width: f32, measure: &dyn Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if measure(&candidate) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if measure(&line) > max_width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monospace(text: &str) -> f32 {
+        text.chars().count() as f32 * 10.
+    }
+
+    #[test]
+    fn lines_break_at_spaces_and_inside_long_words() {
+        assert_eq!(wrap_lines("the quick brown fox", 100., &monospace),
+                   vec!["the quick", "brown fox"]);
+        assert_eq!(wrap_lines("abcdefghijkl\nab", 50., &monospace),
+                   vec!["abcde", "fghij", "kl", "ab"]);
+    }
+}
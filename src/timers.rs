@@ -0,0 +1,221 @@
+//! Actions run once a delay passed, on the UI thread when the engine advances the time every frame.
+//! Debounced and throttled callbacks are built on them, e.g. a search run once typing paused:
+//!
+//! ```ignore
+//! let search: fn(Event, String) -> bool = |_event, text| find(&text);
+//! TextField { on_change: search.debounced(Duration::from_millis(300)), ..Default::default() }
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::elements::Event;
+
+type Action = Box<dyn FnOnce() + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerId(u64);
+
+#[derive(Default)]
+struct Timers {
+    /// The time advanced since the start, timers are due at a point of it
+    clock: Duration,
+    next_id: u64,
+    pending: Vec<(TimerId, Duration, Action)>,
+}
+
+static TIMERS: Lazy<Mutex<Timers>> = Lazy::new(|| Mutex::new(Timers::default()));
+
+/// Runs action once delay passed, it can be called from any thread
+pub fn schedule(delay: Duration, action: impl FnOnce() + Send + 'static) -> TimerId {
+    let mut timers = TIMERS.lock().expect("Timers are poisoned");
+    let id = TimerId(timers.next_id);
+    timers.next_id += 1;
+    let due = timers.clock + delay;
+    timers.pending.push((id, due, Box::new(action)));
+    id
+}
+
+/// Returns whether the timer was still pending
+pub fn cancel(id: TimerId) -> bool {
+    let mut timers = TIMERS.lock().expect("Timers are poisoned");
+    let before = timers.pending.len();
+    timers.pending.retain(|(pending, _, _)| *pending != id);
+    timers.pending.len() != before
+}
+
+/// Advances the time and runs the actions that became due, the earliest first. The engines call
+/// it every frame, embedding apps calling update() get it too. Returns how many ran
+pub fn advance(elapsed: Duration) -> usize {
+    let mut due = {
+        let mut timers = TIMERS.lock().expect("Timers are poisoned");
+        timers.clock += elapsed;
+        let clock = timers.clock;
+        let (due, pending) = std::mem::take(&mut timers.pending).into_iter().partition(|(_, at, _)| *at <= clock);
+        timers.pending = pending;
+        due
+    };
+    due.sort_by_key(|(id, at, _)| (*at, id.0));
+    // The lock is released first so the actions can schedule again
+    let count = due.len();
+    for (_, _, action) in due {
+        action();
+    }
+    count
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Limit {
+    Immediate,
+    /// Called once the calls paused that long, with the last arguments
+    Debounce(Duration),
+    /// Called at most once that often, the first call right away and the last one at the end
+    Throttle(Duration),
+}
+
+struct LimitState<A> {
+    timer: Option<TimerId>,
+    /// The last call that is waiting for its timer
+    pending: Option<(Event, A)>,
+}
+
+/// A callback called through the timers, see debounced() and throttled(). Clones share the
+/// pending calls
+pub struct Limited<A> {
+    callback: fn(Event, A) -> bool,
+    limit: Limit,
+    state: Arc<Mutex<LimitState<A>>>,
+}
+
+impl<A> Clone for Limited<A> {
+    fn clone(&self) -> Self {
+        Limited { callback: self.callback, limit: self.limit, state: self.state.clone() }
+    }
+}
+
+impl<A> Debug for Limited<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Limited").field("limit", &self.limit).finish()
+    }
+}
+
+impl<A: Send + 'static> Limited<A> {
+    /// Calls callback right away
+    pub fn immediate(callback: fn(Event, A) -> bool) -> Self {
+        Self::new(callback, Limit::Immediate)
+    }
+
+    fn new(callback: fn(Event, A) -> bool, limit: Limit) -> Self {
+        Limited { callback, limit, state: Arc::new(Mutex::new(LimitState { timer: None, pending: None })) }
+    }
+
+    /// Calls the callback now or later depending on the limit. Returns what it returned when it
+    /// was called right away, false when it was left for later
+    pub fn call(&self, event: Event, argument: A) -> bool {
+        let mut state = self.state.lock().expect("Limited callback is poisoned");
+        match self.limit {
+            Limit::Immediate => {
+                drop(state);
+                (self.callback)(event, argument)
+            }
+            Limit::Debounce(delay) => {
+                if let Some(timer) = state.timer.take() {
+                    cancel(timer);
+                }
+                state.pending = Some((event, argument));
+                state.timer = Some(schedule(delay, self.flush(None)));
+                false
+            }
+            Limit::Throttle(_) if state.timer.is_some() => {
+                state.pending = Some((event, argument));
+                false
+            }
+            Limit::Throttle(interval) => {
+                state.timer = Some(schedule(interval, self.flush(Some(interval))));
+                drop(state);
+                (self.callback)(event, argument)
+            }
+        }
+    }
+
+    /// The action of a timer calling the pending call, if any. A throttle starts another interval
+    /// after it so calls keep being spaced
+    fn flush(&self, interval: Option<Duration>) -> impl FnOnce() + Send + 'static {
+        let limited = self.clone();
+        move || {
+            let pending = {
+                let mut state = limited.state.lock().expect("Limited callback is poisoned");
+                state.timer = None;
+                let pending = state.pending.take();
+                if let (Some(interval), true) = (interval, pending.is_some()) {
+                    state.timer = Some(schedule(interval, limited.flush(Some(interval))));
+                }
+                pending
+            };
+            if let Some((event, argument)) = pending {
+                (limited.callback)(event, argument);
+            }
+        }
+    }
+}
+
+/// Rate limits for callbacks of the usual fn(Event, A) -> bool form
+pub trait RateLimit<A> {
+    /// Called once the calls paused for delay, with the arguments of the last one
+    fn debounced(self, delay: Duration) -> Limited<A>;
+    /// Called at most once per interval: the first call right away and the last one of the
+    /// interval when it ends
+    fn throttled(self, interval: Duration) -> Limited<A>;
+}
+
+impl<A: Send + 'static> RateLimit<A> for fn(Event, A) -> bool {
+    fn debounced(self, delay: Duration) -> Limited<A> {
+        Limited::new(self, Limit::Debounce(delay))
+    }
+
+    fn throttled(self, interval: Duration) -> Limited<A> {
+        Limited::new(self, Limit::Throttle(interval))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    static DEBOUNCED: AtomicUsize = AtomicUsize::new(0);
+    static THROTTLED: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn debounced_and_throttled_calls_are_spaced() {
+        let event = || Event { source: "Test".to_string(), position: None };
+        let debounce: fn(Event, usize) -> bool = |_event, value| {
+            DEBOUNCED.store(value, Ordering::SeqCst);
+            true
+        };
+        let throttle: fn(Event, usize) -> bool = |_event, value| {
+            THROTTLED.fetch_add(value, Ordering::SeqCst);
+            true
+        };
+        let (debounced, throttled) = (debounce.debounced(Duration::from_millis(300)),
+                                      throttle.throttled(Duration::from_millis(100)));
+        for value in 1..=3 {
+            assert!(!debounced.call(event(), value));
+            advance(Duration::from_millis(200));
+        }
+        assert_eq!(DEBOUNCED.load(Ordering::SeqCst), 0, "Still typing");
+        advance(Duration::from_millis(100));
+        assert_eq!(DEBOUNCED.load(Ordering::SeqCst), 3, "Only the last call");
+        assert!(throttled.call(event(), 1));
+        assert!(!throttled.call(event(), 10));
+        assert!(!throttled.call(event(), 100));
+        assert_eq!(THROTTLED.load(Ordering::SeqCst), 1);
+        advance(Duration::from_millis(100));
+        assert_eq!(THROTTLED.load(Ordering::SeqCst), 101, "The last call of the interval");
+        assert!(!throttled.call(event(), 1000), "Another interval started");
+    }
+}
@@ -0,0 +1,173 @@
+//! Guided tours that walk users through the widgets of a window, e.g. the first time they open the
+//! app. Each step highlights a widget, dims the rest of the window and explains the widget in a
+//! bubble with Skip and Next buttons. Clicking the highlighted widget goes on to the next step too.
+//! Steps find their widget by its accessible name like the steps of macros (see
+//! ui_macros::WidgetId), so give an Accessible name to what the tour shows:
+//!
+//! ```ignore
+//! tour::start(Tour::new(vec![
+//!     TourStep::new("Search", "Find anything", "Type here to search all your documents"),
+//!     TourStep::new("Save", "Keep your work", "Changes are kept once you save them"),
+//! ]).on_end(|completed| println!("Onboarded {}", completed)));
+//! ```
+//!
+//! The bubble of a step whose widget isn't shown is centered in the window
+
+use crate::accessibility_tree::{AccessibleNode, Announcement, Live};
+use crate::ui_channel::{self, UiMessage};
+use crate::ui_macros::WidgetId;
+
+/// Space left between the highlighted widget and the bubble
+pub const BUBBLE_GAP: f32 = 12.;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TourStep {
+    /// The accessible name of the widget highlighted
+    pub target: String,
+    pub title: String,
+    pub text: String,
+}
+
+impl TourStep {
+    pub fn new(target: &str, title: &str, text: &str) -> Self {
+        TourStep {
+            target: target.to_string(),
+            title: title.to_string(),
+            text: text.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tour {
+    pub steps: Vec<TourStep>,
+    /// Index of the step shown
+    pub current: usize,
+    /// Called once the tour ends, with whether it went through all its steps (false when skipped)
+    pub on_end: fn(bool),
+}
+
+impl Default for Tour {
+    fn default() -> Self {
+        Tour {
+            steps: vec![],
+            current: 0,
+            on_end: |_completed| {},
+        }
+    }
+}
+
+impl Tour {
+    pub fn new(steps: Vec<TourStep>) -> Self {
+        Tour {
+            steps,
+            ..Default::default()
+        }
+    }
+
+    pub fn on_end(mut self, on_end: fn(bool)) -> Self {
+        self.on_end = on_end;
+        self
+    }
+
+    pub fn step(&self) -> Option<&TourStep> {
+        self.steps.get(self.current)
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.current + 1 >= self.steps.len()
+    }
+
+    /// Where the tour is, e.g. "2 of 5"
+    pub fn progress(&self) -> String {
+        format!("{} of {}", self.current + 1, self.steps.len())
+    }
+
+    /// What screen readers say when the step shown comes up
+    pub fn announcement(&self) -> Option<Announcement> {
+        let step = self.step()?;
+        Some(Announcement { text: format!("{}, {}. {}", step.title, self.progress(), step.text), live: Live::Polite })
+    }
+
+    /// Goes on to the next step. Returns false when there was none and the tour ended
+    pub fn advance(&mut self) -> bool {
+        self.current += 1;
+        if self.current >= self.steps.len() {
+            (self.on_end)(true);
+            return false;
+        }
+        true
+    }
+
+    /// Ends the tour before its last step
+    pub fn skip(&self) {
+        (self.on_end)(false);
+    }
+
+    /// Where the widget of the step shown is in the container, None when it isn't shown
+    pub fn target_path(&self, nodes: &[AccessibleNode]) -> Option<Vec<usize>> {
+        let step = self.step().filter(|s| !s.target.is_empty())?;
+        WidgetId { path: vec![], name: step.target.clone() }.resolve(nodes)
+    }
+}
+
+/// Shows the tour over the window, see Tour
+pub fn start(tour: Tour) {
+    ui_channel::post(UiMessage::StartTour(tour));
+}
+
+/// The parts of a window of size that are dimmed around the highlighted area (x, y, w, h): above,
+/// below, to the left and to the right of it. All of it without one
+pub fn dimmed_areas(highlight: Option<(f32, f32, f32, f32)>, (width, height): (f32, f32))
+                    -> Vec<(f32, f32, f32, f32)> {
+    let (x, y, w, h) = match highlight {
+        Some(highlight) => highlight,
+        None => return vec![(0., 0., width, height)],
+    };
+    let (top, bottom) = (y.clamp(0., height), (y + h).clamp(0., height));
+    let (left, right) = (x.clamp(0., width), (x + w).clamp(0., width));
+    [(0., 0., width, top), (0., bottom, width, height - bottom),
+     (0., top, left, bottom - top), (right, top, width - right, bottom - top)]
+        .into_iter()
+        .filter(|(_, _, w, h)| *w > 0. && *h > 0.)
+        .collect()
+}
+
+/// Where the bubble of size goes in a window of size: below the highlighted area, above it when
+/// there is no room below, or centered in the window without one. It's kept inside the window
+pub fn bubble_position(highlight: Option<(f32, f32, f32, f32)>, (bubble_width, bubble_height): (f32, f32),
+                       (width, height): (f32, f32)) -> (f32, f32) {
+    let (x, y) = match highlight {
+        Some((x, y, w, h)) => {
+            let below = y + h + BUBBLE_GAP;
+            let y = match below + bubble_height > height && y - BUBBLE_GAP - bubble_height >= 0. {
+                true => y - BUBBLE_GAP - bubble_height,
+                false => below,
+            };
+            (x + (w - bubble_width) / 2., y)
+        }
+        None => ((width - bubble_width) / 2., (height - bubble_height) / 2.),
+    };
+    (x.min(width - bubble_width).max(0.), y.min(height - bubble_height).max(0.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tours_go_through_their_steps_around_the_highlight() {
+        let mut tour = Tour::new(vec![TourStep::new("Save", "Save", "Keeps your work"), TourStep::default()]);
+        assert_eq!((tour.progress(), tour.is_last()), ("1 of 2".to_string(), false));
+        assert!(tour.advance());
+        assert_eq!(tour.target_path(&[]), None, "The second step has no target");
+        assert!(!tour.advance(), "Ended after the last step");
+
+        let areas = dimmed_areas(Some((10., 20., 30., 40.)), (100., 100.));
+        assert_eq!(areas, [(0., 0., 100., 20.), (0., 60., 100., 40.), (0., 20., 10., 40.), (40., 20., 60., 40.)]);
+        assert_eq!(dimmed_areas(None, (100., 100.)), [(0., 0., 100., 100.)]);
+        assert_eq!(bubble_position(Some((10., 20., 30., 40.)), (50., 20.), (100., 100.)), (0., 72.));
+        assert_eq!(bubble_position(Some((10., 70., 30., 20.)), (50., 20.), (100., 100.)), (0., 38.), "Above");
+        assert_eq!(bubble_position(None, (50., 20.), (100., 100.)), (25., 40.));
+    }
+}
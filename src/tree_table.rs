@@ -0,0 +1,280 @@
+use crate::accessibility_tree::Accessible;
+use crate::columns::ColumnLayout;
+use crate::elements::TableView;
+use crate::selection::{SelectModifiers, Selection, SelectionItem};
+use crate::table_model::TableModel;
+
+/// A row of a TreeTable and its children, the first cell is the one shown in the tree column
+#[derive(Debug, Clone, Default)]
+pub struct TreeNode {
+    pub cells: Vec<String>,
+    pub children: Vec<TreeNode>,
+    pub expanded: bool,
+}
+
+impl TreeNode {
+    pub fn new(cells: Vec<String>, children: Vec<TreeNode>) -> Self {
+        TreeNode {
+            cells,
+            children,
+            expanded: false,
+        }
+    }
+}
+
+/// A visible row of a TreeTable, path are the child indexes from the roots to the node
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    pub path: Vec<usize>,
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+/// Outline view: an expandable hierarchy in the first column with additional columns like a
+/// Table (e.g. a profiler's call tree with timings). The rows of the expanded nodes are flattened
+/// so the Table's machinery (ColumnLayout, Selection, SortFilterProxy, export) works on them,
+/// selection ids are indexes of the visible rows. It shows visible_rows rows at a time starting
+/// at scroll. It's hit tested like a TableView, plus the expanders in front of the nodes with
+/// children as [EXPANDERS, row]
+#[derive(Debug, Clone)]
+pub struct TreeTable {
+    pub columns: Vec<String>,
+    pub roots: Vec<TreeNode>,
+    pub column_layout: ColumnLayout,
+    pub selection: Selection,
+    /// Horizontal space per depth level in the tree column, the expanders are this wide
+    pub indent: f32,
+    pub visible_rows: usize,
+    /// The first visible row
+    pub scroll: usize,
+    pub accessible: Accessible,
+    rows: Vec<TreeRow>,
+}
+
+impl Default for TreeTable {
+    fn default() -> Self {
+        TreeTable {
+            columns: vec![],
+            roots: vec![],
+            column_layout: ColumnLayout::default(),
+            selection: Selection::default(),
+            indent: 16.,
+            visible_rows: 6,
+            scroll: 0,
+            accessible: Accessible::default(),
+            rows: vec![],
+        }
+    }
+}
+
+impl TreeTable {
+    pub const HEADER: usize = TableView::HEADER;
+    pub const COLUMN_BORDER: usize = TableView::COLUMN_BORDER;
+    pub const ROWS: usize = TableView::ROWS;
+    pub const EXPANDERS: usize = 3;
+
+    pub fn new(columns: Vec<String>, roots: Vec<TreeNode>) -> Self {
+        let column_layout = ColumnLayout::new(vec![150.; columns.len()], 1);
+        let mut table = TreeTable {
+            columns,
+            roots,
+            column_layout,
+            ..Default::default()
+        };
+        table.refresh();
+        table
+    }
+
+    /// The rows that are shown
+    pub fn visible_range(&self) -> std::ops::Range<usize> {
+        let end = (self.scroll + self.visible_rows).min(self.rows.len());
+        self.scroll.min(end)..end
+    }
+
+    /// Scrolls by rows (negative toward the first row), returns whether it scrolled
+    pub fn scroll_by(&mut self, rows: i32) -> bool {
+        let last = self.rows.len().saturating_sub(self.visible_rows) as i64;
+        let scroll = (self.scroll as i64 + rows as i64).clamp(0, last) as usize;
+        std::mem::replace(&mut self.scroll, scroll) != scroll
+    }
+
+    /// Handles a click on the visible row, returns whether the selection changed
+    pub fn click(&mut self, row: usize, modifiers: SelectModifiers) -> bool {
+        if row >= self.rows.len() {
+            return false;
+        }
+        // One unit high each so ranges follow the order of the rows
+        let items: Vec<SelectionItem> = (0..self.rows.len())
+            .map(|row| SelectionItem { id: row, bounds: (0., row as f32, 1., 1.) })
+            .collect();
+        self.selection.click(row, modifiers, &items)
+    }
+
+    /// Changes the width of column like TableView::resize_column(). Returns whether it changed
+    pub fn resize_column(&mut self, column: usize, width: f32) -> bool {
+        let width = width.max(TableView::MIN_COLUMN_WIDTH);
+        match self.column_layout.widths().get(column) {
+            Some(current) if *current != width => {
+                self.column_layout.resize(column, width);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The rows of the expanded nodes in display order
+    pub fn rows(&self) -> &[TreeRow] {
+        &self.rows
+    }
+
+    pub fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.roots.get(*first)?, |node, i| node.children.get(*i))
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.roots.get_mut(*first)?, |node, i| node.children.get_mut(*i))
+    }
+
+    /// Expands or collapses the node of the visible row, the selection is cleared because the
+    /// row indexes change. Returns whether the rows changed
+    pub fn toggle(&mut self, row: usize) -> bool {
+        let path = match self.rows.get(row) {
+            Some(row) if row.has_children => row.path.clone(),
+            _ => return false,
+        };
+        if let Some(node) = self.node_mut(&path) {
+            node.expanded = !node.expanded;
+        }
+        self.refresh();
+        self.selection.set_selected(vec![]);
+        // Collapsing may leave fewer rows than the scroll
+        self.scroll_by(0);
+        true
+    }
+
+    /// The paths of the expanded nodes, the ones under collapsed nodes too. A TreeTable built again
+    /// gets them back with set_expanded_paths(), see view_state
+    pub fn expanded_paths(&self) -> Vec<Vec<usize>> {
+        fn collect(nodes: &[TreeNode], path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                if node.expanded {
+                    paths.push(path.clone());
+                }
+                collect(&node.children, path, paths);
+                path.pop();
+            }
+        }
+        let mut paths = vec![];
+        collect(&self.roots, &mut vec![], &mut paths);
+        paths
+    }
+
+    /// Expands the nodes at paths and collapses the rest, the selection is cleared like in
+    /// toggle(). Returns whether the rows changed
+    pub fn set_expanded_paths(&mut self, paths: &[Vec<usize>]) -> bool {
+        if self.expanded_paths() == paths {
+            return false;
+        }
+        for path in self.expanded_paths() {
+            if let Some(node) = self.node_mut(&path) {
+                node.expanded = false;
+            }
+        }
+        for path in paths {
+            if let Some(node) = self.node_mut(path) {
+                node.expanded = true;
+            }
+        }
+        self.refresh();
+        self.selection.set_selected(vec![]);
+        true
+    }
+
+    /// Recomputes the visible rows, it has to be called after changing roots directly
+    pub fn refresh(&mut self) {
+        fn flatten(nodes: &[TreeNode], path: &mut Vec<usize>, rows: &mut Vec<TreeRow>) {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                rows.push(TreeRow {
+                    path: path.clone(),
+                    depth: path.len() - 1,
+                    has_children: !node.children.is_empty(),
+                    expanded: node.expanded,
+                });
+                if node.expanded {
+                    flatten(&node.children, path, rows);
+                }
+                path.pop();
+            }
+        }
+        let mut rows = vec![];
+        flatten(&self.roots, &mut vec![], &mut rows);
+        self.rows = rows;
+    }
+
+    /// Where the text of the tree column starts for the row, after the indentation and expander
+    pub fn tree_cell_offset(&self, row: usize) -> f32 {
+        self.rows.get(row).map(|r| (r.depth + 1) as f32 * self.indent).unwrap_or_default()
+    }
+}
+
+impl TableModel for TreeTable {
+    fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_title(&self, column: usize) -> String {
+        self.columns.get(column).cloned().unwrap_or_default()
+    }
+
+    fn cell(&self, row: usize, column: usize) -> String {
+        self.rows.get(row)
+            .and_then(|r| self.node(&r.path))
+            .and_then(|n| n.cells.get(column))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn expanded_nodes_show_their_children() {
+        let mut table = TreeTable::new(cells(&["Function", "Time"]), vec![
+            TreeNode::new(cells(&["main", "10ms"]), vec![
+                TreeNode::new(cells(&["render", "7ms"]), vec![]),
+                TreeNode::new(cells(&["layout", "3ms"]), vec![]),
+            ]),
+        ]);
+        assert_eq!(table.row_count(), 1);
+        assert!(table.toggle(0));
+        assert_eq!(table.row_count(), 3);
+        assert_eq!(table.cell(2, 0), "layout");
+        assert_eq!(table.rows()[2].depth, 1);
+        assert!(!table.toggle(1));
+        assert!(table.toggle(0));
+        assert_eq!(table.row_count(), 1);
+        assert!(table.set_expanded_paths(&[vec![0]]) && table.row_count() == 3);
+        assert_eq!(table.expanded_paths(), vec![vec![0]]);
+        assert!(!table.set_expanded_paths(&[vec![0]]));
+        table.visible_rows = 2;
+        assert!(table.scroll_by(1) && table.click(2, SelectModifiers::default()));
+        assert_eq!((table.visible_range(), table.selection.selected()), (1..3, &[2][..]));
+        assert!(table.toggle(0));
+        assert_eq!((table.visible_range(), table.selection.selected()), (0..1, &[][..]));
+    }
+}
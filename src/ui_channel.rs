@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::app_info::AppInfo;
+use crate::elements::MenuBatch;
+use crate::file_chooser::FileChooser;
+use crate::paging::PageDelivery;
+#[cfg(feature = "remote-debug")]
+use crate::remote_debug::RemoteRequest;
+use crate::settings::Settings;
+use crate::single_instance::ForwardedArguments;
+use crate::taskbar::{Attention, TaskbarProgress};
+use crate::tour::Tour;
+use crate::ui_macros::Macro;
+
+/// Updates to the UI that can be posted from anywhere (callbacks, other threads) and are applied
+/// by the engine on the UI thread at the start of the next frame
+#[derive(Debug)]
+pub enum UiMessage {
+    MenuUpdate(MenuBatch),
+    /// Replaces the permanent text of the status bar
+    StatusText(String),
+    /// A transient status bar message, see StatusBar.show_message()
+    StatusMessage(String, Duration),
+    StatusProgress(Option<f32>),
+    /// Replaces the text of the section at the index, see StatusBar.set_section_text()
+    StatusSectionText(usize, String),
+    /// Rows loaded by a DataProvider, see paging::deliver_page()
+    PageLoaded(PageDelivery),
+    /// Whether the Loadable with the source shows its skeleton, see paging::set_loading()
+    Loading { source: String, loading: bool },
+    /// Output for the Consoles with the source, see ansi::write_console()
+    ConsoleOutput { source: String, text: String },
+    /// Shows the progress on the taskbar, None removes it. See taskbar::set_progress()
+    TaskbarProgress(Option<TaskbarProgress>),
+    RequestAttention(Attention),
+    ShowAbout(AppInfo),
+    /// Opens the settings dialog generated from the declared settings. It edits this copy, which
+    /// saves to the file the settings were loaded from and notifies the same subscribers
+    ShowSettings(Settings),
+    /// Asks for a file over the window, its on_choose gets the answer
+    ShowFileChooser(Box<FileChooser>),
+    /// Another launch of the app handed over its arguments, see single_instance::acquire()
+    ArgumentsForwarded(ForwardedArguments),
+    /// Replays a recorded macro in the window, see ui_macros::play()
+    PlayMacro(Macro),
+    /// Shows a guided tour over the window, see tour::start()
+    StartTour(Tour),
+    /// A command of a remote inspector, see remote_debug::start()
+    #[cfg(feature = "remote-debug")]
+    RemoteDebug(RemoteRequest),
+}
+
+static QUEUE: Lazy<Mutex<VecDeque<UiMessage>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+pub fn post(message: UiMessage) {
+    QUEUE.lock().expect("UI channel is poisoned").push_back(message);
+}
+
+/// Takes all the pending messages, in the order they were posted
+pub(crate) fn drain() -> Vec<UiMessage> {
+    QUEUE.lock().expect("UI channel is poisoned").drain(..).collect()
+}
@@ -0,0 +1,246 @@
+//! Macros users record to automate repetitive tasks in the app: the clicks, the typed text and
+//! the keys pressed in the widgets of a window, replayed later with play(). Steps find their
+//! widget by its accessible name (see accessibility_tree) and the widgets without one by their
+//! path in the container, so give an Accessible name to what macros should still find once the
+//! layout changes. A macro is saved as a script with a line per step, its fields separated by
+//! tabs:
+//!
+//! ```text
+//! macro   Rename
+//! click   0.1     Name            12,8
+//! type    0.1     Name    Draft 2
+//! key     0.1     Name    Return
+//! ```
+//!
+//! The SDL engine records and replays every step. The web engine has no keyboard focus yet, it
+//! only records and replays the clicks
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::accessibility_tree::AccessibleNode;
+use crate::ui_channel::{self, UiMessage};
+
+static RECORDING: Lazy<Mutex<Option<Macro>>> = Lazy::new(|| Mutex::new(None));
+
+/// The widget a step was recorded on
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WidgetId {
+    /// Where it was in the container
+    pub path: Vec<usize>,
+    /// Its accessible name, empty when it has none
+    pub name: String,
+}
+
+impl WidgetId {
+    /// The widget at path or the one containing it, with the rest of the path inside of it (e.g.
+    /// the row of a ListView)
+    pub fn of(nodes: &[AccessibleNode], path: &[usize]) -> Option<(WidgetId, Vec<usize>)> {
+        let node = nodes.iter()
+            .filter(|n| path.starts_with(&n.path))
+            .max_by_key(|n| n.path.len())?;
+        let id = WidgetId { path: node.path.clone(), name: node.name.clone() };
+        Some((id, path[node.path.len()..].to_vec()))
+    }
+
+    /// Where the widget is in the container now: the one with the same name, the nearest to the
+    /// path when there are several, or else the one at the path
+    pub fn resolve(&self, nodes: &[AccessibleNode]) -> Option<Vec<usize>> {
+        let distance = |n: &&AccessibleNode| n.path.iter().zip(&self.path).take_while(|(a, b)| a == b).count();
+        let named = nodes.iter()
+            .filter(|n| !self.name.is_empty() && n.name == self.name)
+            .max_by_key(distance);
+        named.or_else(|| nodes.iter().find(|n| n.path == self.path)).map(|n| n.path.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroStep {
+    /// A click on the part of the widget (e.g. [2] for its third row), offset from its top left
+    /// corner
+    Click { widget: WidgetId, part: Vec<usize>, offset: (i32, i32) },
+    /// Text typed while the widget had the focus
+    Type { widget: WidgetId, text: String },
+    /// A key pressed while the widget had the focus, by its SDL name (e.g. "Return")
+    Key { widget: WidgetId, key: String },
+}
+
+impl MacroStep {
+    pub fn widget(&self) -> &WidgetId {
+        match self {
+            MacroStep::Click { widget, .. } | MacroStep::Type { widget, .. } | MacroStep::Key { widget, .. } => widget,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Macro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+}
+
+impl Macro {
+    pub fn from_script(script: &str) -> Result<Macro, String> {
+        let mut lines = script.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        let name = match lines.next().map(|(_, l)| l.split('\t').collect::<Vec<&str>>()) {
+            Some(fields) if fields.len() == 2 && fields[0] == "macro" => unescape(fields[1]),
+            _ => return Err("A macro starts with a macro line".to_string()),
+        };
+        let steps = lines.map(|(i, line)| parse_step(line).map_err(|e| format!("Line {}: {}", i + 1, e)))
+            .collect::<Result<Vec<MacroStep>, String>>()?;
+        Ok(Macro { name, steps })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_string()).map_err(|e| format!("Failed to save {:?} {}", path, e))
+    }
+
+    pub fn load(path: &Path) -> Result<Macro, String> {
+        let script = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {:?} {}", path, e))?;
+        Macro::from_script(&script)
+    }
+}
+
+/// The script of the macro
+impl Display for Macro {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "macro\t{}", escape(&self.name))?;
+        for step in &self.steps {
+            let kind = match step {
+                MacroStep::Click { .. } => "click",
+                MacroStep::Type { .. } => "type",
+                MacroStep::Key { .. } => "key",
+            };
+            let widget = step.widget();
+            write!(f, "{}\t{}\t{}\t", kind, format_path(&widget.path), escape(&widget.name))?;
+            match step {
+                MacroStep::Click { part, offset, .. } => {
+                    writeln!(f, "{}\t{},{}", format_path(part), offset.0, offset.1)
+                }
+                MacroStep::Type { text, .. } => writeln!(f, "{}", escape(text)),
+                MacroStep::Key { key, .. } => writeln!(f, "{}", escape(key)),
+            }?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_step(line: &str) -> Result<MacroStep, String> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    match fields.as_slice() {
+        ["click", path, name, part, offset] => {
+            let (x, y) = offset.split_once(',').ok_or(format!("{} is not an offset", offset))?;
+            let parse = |v: &str| v.parse().map_err(|_| format!("{} is not an offset", offset));
+            let widget = WidgetId { path: parse_path(path)?, name: unescape(name) };
+            Ok(MacroStep::Click { widget, part: parse_path(part)?, offset: (parse(x)?, parse(y)?) })
+        }
+        ["type", path, name, text] => {
+            let widget = WidgetId { path: parse_path(path)?, name: unescape(name) };
+            Ok(MacroStep::Type { widget, text: unescape(text) })
+        }
+        ["key", path, name, key] => {
+            let widget = WidgetId { path: parse_path(path)?, name: unescape(name) };
+            Ok(MacroStep::Key { widget, key: unescape(key) })
+        }
+        _ => Err(format!("Unknown step {}", line)),
+    }
+}
+
+/// Like the paths of remote_debug, e.g. "0.1"
+fn format_path(path: &[usize]) -> String {
+    path.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(".")
+}
+
+fn parse_path(path: &str) -> Result<Vec<usize>, String> {
+    match path.is_empty() {
+        true => Ok(vec![]),
+        false => path.split('.').map(|i| i.parse().map_err(|_| format!("{} is not a path", path))).collect(),
+    }
+}
+
+/// Tabs and line breaks would end the field
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(match chars.next() {
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some(other) => other,
+            None => '\\',
+        });
+    }
+    unescaped
+}
+
+/// Starts recording what the user does in the window into a macro with the name, replacing the
+/// one being recorded
+pub fn start_recording(name: &str) {
+    *RECORDING.lock().expect("Macro recording is poisoned") = Some(Macro { name: name.to_string(), steps: vec![] });
+}
+
+/// The recorded macro, None when nothing was being recorded
+pub fn stop_recording() -> Option<Macro> {
+    RECORDING.lock().expect("Macro recording is poisoned").take()
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.lock().expect("Macro recording is poisoned").is_some()
+}
+
+/// Adds the step to the macro being recorded, the engines call it for what the user does
+pub(crate) fn record(step: MacroStep) {
+    if let Some(recording) = RECORDING.lock().expect("Macro recording is poisoned").as_mut() {
+        recording.steps.push(step);
+    }
+}
+
+/// Replays the steps of the macro in the window, at the start of the next frame. It stops at the
+/// first step whose widget isn't found. It can be called from any thread
+pub fn play(recorded: Macro) {
+    ui_channel::post(UiMessage::PlayMacro(recorded));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility_tree::{Live, Role};
+
+    #[test]
+    fn macros_find_their_widgets_by_name_and_survive_their_script() {
+        let node = |path: Vec<usize>, name: &str| AccessibleNode {
+            path,
+            role: Role::Button,
+            name: name.to_string(),
+            description: None,
+            value: None,
+            live: Live::Off,
+        };
+        let before = [node(vec![0], "Save"), node(vec![1], "")];
+        let (widget, part) = WidgetId::of(&before, &[1, 3]).unwrap();
+        assert_eq!((widget.path.as_slice(), part.as_slice()), (&[1][..], &[3][..]));
+        let save = WidgetId::of(&before, &[0]).unwrap().0;
+        let after = [node(vec![0], "Open"), node(vec![1], ""), node(vec![2], "Save")];
+        assert_eq!(save.resolve(&after), Some(vec![2]));
+        assert_eq!(widget.resolve(&after), Some(vec![1]));
+        let recorded = Macro {
+            name: "Rename".to_string(),
+            steps: vec![MacroStep::Click { widget: save, part: vec![], offset: (12, 8) },
+                        MacroStep::Type { widget: widget.clone(), text: "Tab\there".to_string() },
+                        MacroStep::Key { widget, key: "Return".to_string() }],
+        };
+        assert_eq!(Macro::from_script(&recorded.to_string()), Ok(recorded));
+        assert!(Macro::from_script("click\t0\tSave\t\t1,1").is_err());
+    }
+}
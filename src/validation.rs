@@ -0,0 +1,108 @@
+//! Rules checking the text of a form field, each with the message shown when it fails. A
+//! Validator composes them for a TextField, which checks them when its text changes, when it
+//! loses the focus or when its Form is submitted
+
+/// When the rules of a field are checked, each one includes the later ones
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Trigger {
+    /// Every time the text changes
+    Change,
+    /// When the field loses the focus
+    Blur,
+    /// When the Form is submitted
+    Submit,
+}
+
+#[derive(Debug, Clone)]
+pub enum Rule {
+    /// Not only whitespace
+    Required,
+    /// At least that many characters
+    MinLength(usize),
+    /// At most that many characters
+    MaxLength(usize),
+    /// Matches the regular expression, anywhere in the text unless it's anchored with ^ and $
+    #[cfg(feature = "regex")]
+    Pattern(regex::Regex),
+    /// A number between the bounds, both included
+    Range(f64, f64),
+    /// Returns whether the text is valid
+    Custom(fn(&str) -> bool),
+}
+
+impl Rule {
+    pub fn check(&self, text: &str) -> bool {
+        match self {
+            Rule::Required => !text.trim().is_empty(),
+            Rule::MinLength(min) => text.chars().count() >= *min,
+            Rule::MaxLength(max) => text.chars().count() <= *max,
+            #[cfg(feature = "regex")]
+            Rule::Pattern(regex) => regex.is_match(text),
+            Rule::Range(min, max) => text.trim().parse::<f64>().is_ok_and(|n| n >= *min && n <= *max),
+            Rule::Custom(check) => check(text),
+        }
+    }
+}
+
+/// Rules checked in order, each with its error message. An empty text only fails Required, so
+/// fields without it can be left empty
+#[derive(Debug, Clone)]
+pub struct Validator {
+    rules: Vec<(Rule, String)>,
+    /// When the rules are checked the first time, once checked they're checked on every trigger
+    /// so the errors go away as they're fixed
+    pub trigger: Trigger,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Validator {
+            rules: vec![],
+            trigger: Trigger::Blur,
+        }
+    }
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule(mut self, rule: Rule, message: &str) -> Self {
+        self.rules.push((rule, message.to_string()));
+        self
+    }
+
+    pub fn with_trigger(mut self, trigger: Trigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// The messages of the rules text fails
+    pub fn errors(&self, text: &str) -> Vec<String> {
+        self.rules.iter()
+            .filter(|(rule, _)| !text.is_empty() || matches!(rule, Rule::Required))
+            .filter(|(rule, _)| !rule.check(text))
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failing_rules_give_their_messages() {
+        let validator = Validator::new()
+            .with_rule(Rule::Required, "Enter your age")
+            .with_rule(Rule::Range(18., 130.), "Between 18 and 130")
+            .with_rule(Rule::Custom(|text| !text.contains('.')), "Whole years");
+        assert_eq!(validator.errors(""), vec!["Enter your age"]);
+        assert_eq!(validator.errors("12.5"), vec!["Between 18 and 130", "Whole years"]);
+        assert!(validator.errors(" 42 ").is_empty());
+        let optional = Validator::new().with_rule(Rule::MinLength(3), "Too short");
+        assert!(optional.errors("").is_empty(), "Only Required rejects an empty text");
+        assert_eq!(optional.errors("ab"), vec!["Too short"]);
+    }
+}
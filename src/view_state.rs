@@ -0,0 +1,140 @@
+//! What the user did to the components that the app doesn't describe: how far they're scrolled,
+//! the tab or the page shown, the nodes of a tree that are expanded. It's kept here by the key of
+//! the component rather than in the component, so replacing the Window (set_window, a Lua reload)
+//! doesn't reset it. The key of a component of the container is its keyed path (see
+//! Container::keyed_path), e.g. `2."b".0` for the first child of the ForEach child with the key
+//! "b". The SDL engine saves the state of the container it had and restores it into the new one,
+//! apps keep the state of what they rebuild themselves under their own keys:
+//!
+//! ```ignore
+//! view_state::set("outline", ViewState::Expanded(tree_table.expanded_paths()));
+//! if let Some(ViewState::Expanded(paths)) = view_state::get("outline") {
+//!     tree_table.set_expanded_paths(&paths);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::elements::*;
+
+static STORE: Lazy<Mutex<HashMap<String, ViewState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViewState {
+    /// The scroll of a ScrollPane, or the vertical one of a TextArea
+    Offset(f32, f32),
+    /// The first row shown by a ListView or a TableView
+    FirstRow(usize),
+    /// The tab of a TabPane or the page of a Carousel that is shown
+    Active(usize),
+    /// The paths of the expanded nodes of a tree, see TreeTable::expanded_paths()
+    Expanded(Vec<Vec<usize>>),
+}
+
+impl ViewState {
+    /// The state of the component, None for the components that have none
+    pub fn of(component: &dyn Component) -> Option<ViewState> {
+        if let Some(scroll_pane) = component.downcast_ref::<ScrollPane>() {
+            Some(ViewState::Offset(scroll_pane.scroll.0, scroll_pane.scroll.1))
+        } else if let Some(text_area) = component.downcast_ref::<TextArea>() {
+            Some(ViewState::Offset(0., text_area.scroll))
+        } else if let Some(list_view) = component.downcast_ref::<ListView>() {
+            Some(ViewState::FirstRow(list_view.scroll))
+        } else if let Some(table_view) = component.downcast_ref::<TableView>() {
+            Some(ViewState::FirstRow(table_view.scroll))
+        } else if let Some(tab_pane) = component.downcast_ref::<TabPane>() {
+            Some(ViewState::Active(tab_pane.active))
+        } else {
+            component.downcast_ref::<Carousel>().map(|carousel| ViewState::Active(carousel.active))
+        }
+    }
+
+    /// Puts the state back into the component, a state of another kind or a tab or page it
+    /// doesn't have is ignored. Returns whether the component changed
+    pub fn apply(&self, component: &mut dyn Component) -> bool {
+        match self {
+            ViewState::Offset(x, y) => {
+                if let Some(scroll_pane) = component.downcast_mut::<ScrollPane>() {
+                    return std::mem::replace(&mut scroll_pane.scroll, (*x, *y)) != (*x, *y);
+                }
+                component.downcast_mut::<TextArea>()
+                    .is_some_and(|text_area| std::mem::replace(&mut text_area.scroll, *y) != *y)
+            }
+            ViewState::FirstRow(row) => {
+                if let Some(list_view) = component.downcast_mut::<ListView>() {
+                    return std::mem::replace(&mut list_view.scroll, *row) != *row;
+                }
+                component.downcast_mut::<TableView>()
+                    .is_some_and(|table_view| std::mem::replace(&mut table_view.scroll, *row) != *row)
+            }
+            ViewState::Active(index) => {
+                if let Some(tab_pane) = component.downcast_mut::<TabPane>().filter(|t| *index < t.tabs.len()) {
+                    return std::mem::replace(&mut tab_pane.active, *index) != *index;
+                }
+                component.downcast_mut::<Carousel>().filter(|c| *index < c.pages.len())
+                    .is_some_and(|carousel| std::mem::replace(&mut carousel.active, *index) != *index)
+            }
+            ViewState::Expanded(_) => false,
+        }
+    }
+}
+
+/// The key of the component at the keyed path, the indexes are separated by dots like in the
+/// paths of remote_debug and the keys are quoted
+pub fn key_of(keyed: &[PathStep]) -> String {
+    keyed.iter().map(|step| match step {
+        PathStep::Index(index) => index.to_string(),
+        PathStep::Key(key) => format!("{:?}", key),
+    }).collect::<Vec<String>>().join(".")
+}
+
+/// Keeps the state under the key, replacing what was there
+pub fn set(key: &str, state: ViewState) {
+    STORE.lock().expect("View state is poisoned").insert(key.to_string(), state);
+}
+
+pub fn get(key: &str) -> Option<ViewState> {
+    STORE.lock().expect("View state is poisoned").get(key).cloned()
+}
+
+/// Forgets the state under the key, e.g. for a component that is gone for good
+pub fn forget(key: &str) {
+    STORE.lock().expect("View state is poisoned").remove(key);
+}
+
+/// Forgets every state, the components keep what they have
+pub fn clear() {
+    STORE.lock().expect("View state is poisoned").clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_state_follows_the_keys_into_a_new_container() {
+        let container = |keys: &[&str]| {
+            let mut for_each = ForEach { template: |_key| Box::new(ScrollPane::default()), ..Default::default() };
+            for_each.reconcile(keys.iter().map(|k| k.to_string()).collect());
+            let tab = |title: &str| Tab { title: title.to_string(), content: Container::default() };
+            let tab_pane = TabPane { tabs: vec![tab("General"), tab("Advanced")], ..Default::default() };
+            Container { children: vec![Box::new(tab_pane), Box::new(for_each)], ..Default::default() }
+        };
+        let scroll = |container: &mut Container, path: &[usize]| container.child_at_mut(path)
+            .and_then(|c| c.downcast_mut::<ScrollPane>())
+            .map(|s| s.scroll);
+        let mut before = container(&["a", "b"]);
+        before.children[0].downcast_mut::<TabPane>().unwrap().active = 1;
+        before.child_at_mut(&[1, 1]).and_then(|c| c.downcast_mut::<ScrollPane>()).unwrap().scroll = (0., 40.);
+        before.save_view_state();
+        assert_eq!(get("1.\"b\""), Some(ViewState::Offset(0., 40.)));
+        let mut after = container(&["b", "c"]);
+        assert!(after.restore_view_state());
+        assert_eq!(after.children[0].downcast_ref::<TabPane>().unwrap().active, 1);
+        assert_eq!((scroll(&mut after, &[1, 0]), scroll(&mut after, &[1, 1])), (Some((0., 40.)), Some((0., 0.))));
+        assert!(!after.restore_view_state(), "Nothing left to restore");
+    }
+}